@@ -162,6 +162,7 @@ kotlin {
             implementation(compose.preview)
             implementation(libs.androidx.activity.compose)
             implementation(libs.ktor.client.okhttp)
+            implementation(libs.ktor.client.encoding)
             implementation(libs.sqldelight.android)
             implementation(libs.ktor.serialization.kotlinx.xml)
             implementation(libs.coil.network.okhttp)
@@ -202,6 +203,7 @@ kotlin {
             implementation(compose.desktop.currentOs)
             implementation(libs.kotlinx.coroutinesSwing)
             implementation(libs.ktor.client.cio)
+            implementation(libs.ktor.client.encoding)
             implementation(libs.sqldelight.jvm)
             implementation(libs.ktor.serialization.kotlinx.xml)
             implementation(libs.coil.network.okhttp)
@@ -1,5 +1,7 @@
 package com.opoojkk.podium.audio
 
+import android.content.Context
+import android.net.Uri
 import android.util.Log
 
 /**
@@ -11,6 +13,9 @@ class RustAudioPlayer {
     companion object {
         private const val TAG = "RustAudioPlayer"
 
+        // Native return code for AudioError::InvalidState (e.g. nothing loaded yet)
+        private const val ERROR_INVALID_STATE = -5
+
         // Load native library
         init {
             try {
@@ -48,6 +53,9 @@ class RustAudioPlayer {
         @JvmStatic
         private external fun nativeLoadBuffer(playerId: Long, buffer: ByteArray): Int
 
+        @JvmStatic
+        private external fun nativeLoadFd(playerId: Long, fd: Int, startOffset: Long, length: Long): Int
+
         @JvmStatic
         private external fun nativePlay(playerId: Long): Int
 
@@ -60,6 +68,9 @@ class RustAudioPlayer {
         @JvmStatic
         private external fun nativeSeek(playerId: Long, positionMs: Long): Int
 
+        @JvmStatic
+        private external fun nativeSetStartPosition(playerId: Long, positionMs: Long): Int
+
         @JvmStatic
         private external fun nativeSetVolume(playerId: Long, volume: Float): Int
 
@@ -157,6 +168,40 @@ class RustAudioPlayer {
         }
     }
 
+    /**
+     * Load audio from an open file descriptor
+     * The native side duplicates the descriptor, so the caller may close it after this returns.
+     * @param fd File descriptor, e.g. from ParcelFileDescriptor.getFd()
+     * @param startOffset Byte offset of the audio within the file
+     * @param length Length of the audio in bytes, or -1 for the rest of the file
+     * @throws AudioPlayerException if loading fails
+     */
+    fun loadFd(fd: Int, startOffset: Long = 0L, length: Long = -1L) {
+        checkNotReleased()
+        Log.d(TAG, "Loading file descriptor: $fd (offset $startOffset, length $length)")
+
+        val result = nativeLoadFd(playerId, fd, startOffset, length)
+        if (result != 0) {
+            throw AudioPlayerException("Failed to load file descriptor: $fd")
+        }
+    }
+
+    /**
+     * Load audio from a content:// URI (Storage Access Framework documents, downloads, etc.)
+     * @param context Context used to resolve the URI
+     * @param uri Content URI to play
+     * @throws AudioPlayerException if the URI cannot be opened or loading fails
+     */
+    fun loadUri(context: Context, uri: Uri) {
+        checkNotReleased()
+        val descriptor = context.contentResolver.openAssetFileDescriptor(uri, "r")
+            ?: throw AudioPlayerException("Failed to open URI: $uri")
+        // AssetFileDescriptor.UNKNOWN_LENGTH is -1, which the native side reads as "to the end"
+        descriptor.use {
+            loadFd(it.parcelFileDescriptor.fd, it.startOffset, it.declaredLength)
+        }
+    }
+
     /**
      * Start or resume playback
      * @throws AudioPlayerException if play fails
@@ -214,11 +259,34 @@ class RustAudioPlayer {
         }
     }
 
+    /**
+     * Start the next load at a position instead of the beginning
+     * Unlike load + seek, nothing is decoded or reported from 0 first. Applies to one load only.
+     * @param positionMs Start position in milliseconds (clamped to the duration)
+     * @throws AudioPlayerException if setting the position fails
+     * @throws IllegalArgumentException if the position is negative
+     */
+    fun setStartPosition(positionMs: Long) {
+        checkNotReleased()
+
+        if (positionMs < 0) {
+            throw IllegalArgumentException("Start position must not be negative, got $positionMs")
+        }
+
+        Log.d(TAG, "Set start position to $positionMs ms")
+
+        val result = nativeSetStartPosition(playerId, positionMs)
+        if (result != 0) {
+            throw AudioPlayerException("Failed to set start position to $positionMs ms")
+        }
+    }
+
     /**
      * Set playback volume
      * @param volume Volume level (0.0 - 1.0)
      * @throws AudioPlayerException if setting volume fails
      * @throws IllegalArgumentException if volume is out of range
+     * @throws IllegalStateException if no audio is loaded
      */
     fun setVolume(volume: Float) {
         checkNotReleased()
@@ -230,6 +298,9 @@ class RustAudioPlayer {
         Log.d(TAG, "Set volume to $volume")
 
         val result = nativeSetVolume(playerId, volume)
+        if (result == ERROR_INVALID_STATE) {
+            throw IllegalStateException("Cannot set volume before audio is loaded")
+        }
         if (result != 0) {
             throw AudioPlayerException("Failed to set volume to $volume")
         }
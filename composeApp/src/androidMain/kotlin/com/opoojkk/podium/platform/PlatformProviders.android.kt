@@ -8,6 +8,7 @@ import com.opoojkk.podium.player.PodcastPlayer
 import com.opoojkk.podium.player.android.RustPodcastPlayer
 import io.ktor.client.HttpClient
 import io.ktor.client.engine.okhttp.OkHttp
+import io.ktor.client.plugins.compression.ContentEncoding
 import io.ktor.client.plugins.contentnegotiation.ContentNegotiation
 import io.ktor.client.plugins.logging.Logging
 import io.ktor.serialization.kotlinx.json.json
@@ -18,6 +19,11 @@ actual fun createPlatformHttpClient(): HttpClient = HttpClient(OkHttp) {
     install(ContentNegotiation) {
         json(Json { ignoreUnknownKeys = true })
     }
+    // Many feed hosts gzip large feeds; servers that ignore Accept-Encoding still answer in plain text
+    install(ContentEncoding) {
+        gzip()
+        deflate()
+    }
     install(Logging)
 }
 
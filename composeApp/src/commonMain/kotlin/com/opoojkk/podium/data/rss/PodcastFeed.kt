@@ -11,6 +11,8 @@ data class PodcastFeed(
     val feedUrl: String,
     val lastUpdated: Instant,
     val episodes: List<RssEpisode>,
+    /** Feed items left out for having no audio (video-only or without an enclosure) */
+    val skippedItems: Int = 0,
 )
 
 data class RssEpisode(
@@ -22,4 +24,21 @@ data class RssEpisode(
     val duration: Long?,
     val imageUrl: String?,
     val chapters: List<Chapter> = emptyList(),
+    /** Season number from `<podcast:season>` or `<itunes:season>` */
+    val season: Int? = null,
+    val seasonName: String? = null,
+    /** Episode number; fractional for bonus episodes (e.g. 1.5) */
+    val episodeNumber: Double? = null,
+    /** Kept although it has no audio: `audioUrl` is its video or other file, or empty */
+    val nonAudio: Boolean = false,
+    /** Every audio file the item offers, in feed order; `audioUrl` is one of them */
+    val alternates: List<AudioRendition> = emptyList(),
+)
+
+data class AudioRendition(
+    val url: String,
+    val mime: String?,
+    /** kbit/s, declared or estimated from the file size */
+    val bitrate: Int?,
+    val bytes: Long?,
 )
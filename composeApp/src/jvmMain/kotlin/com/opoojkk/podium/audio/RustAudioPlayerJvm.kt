@@ -10,6 +10,10 @@ class RustAudioPlayerJvm {
 
     companion object {
         private const val TAG = "RustAudioPlayerJvm"
+
+        // Native return code for AudioError::InvalidState (e.g. nothing loaded yet)
+        private const val ERROR_INVALID_STATE = -5
+
         private var libraryLoaded = false
 
         // Load native library
@@ -256,12 +260,16 @@ class RustAudioPlayerJvm {
 
     /**
      * Set volume (0.0 - 1.0)
+     * Throws IllegalStateException if no audio is loaded.
      */
     fun setVolume(volume: Float) {
         checkNotReleased()
         val clampedVolume = volume.coerceIn(0f, 1f)
 
         val result = nativeSetVolume(playerId, clampedVolume)
+        if (result == ERROR_INVALID_STATE) {
+            throw IllegalStateException("Cannot set volume before audio is loaded")
+        }
         if (result != 0) {
             throw AudioPlayerException("Failed to set volume")
         }
@@ -14,6 +14,9 @@ import java.nio.file.Files
  */
 object RustRssParser {
 
+    // Fields added by newer native parsers must not fail the whole feed
+    private val json = Json { ignoreUnknownKeys = true }
+
     private var libraryLoaded = false
 
     init {
@@ -105,44 +108,7 @@ object RustRssParser {
         }
 
         return try {
-            val jsonResult = parseRss(feedUrl, xmlContent)
-            val result = Json.decodeFromString<RustPodcastFeed>(jsonResult)
-
-            // Check for error (feed-rs encountered an incompatible feed format)
-            if (result.error != null) {
-                // This is expected for some feed formats - will fallback to SimpleRssParser
-                return null
-            }
-
-            // Convert Rust result to Kotlin PodcastFeed
-            PodcastFeed(
-                id = result.id ?: return null,
-                title = result.title ?: return null,
-                description = result.description ?: "",
-                artworkUrl = result.artworkUrl,
-                feedUrl = result.feedUrl ?: feedUrl,
-                lastUpdated = result.lastUpdated?.let { Instant.fromEpochMilliseconds(it) }
-                    ?: Instant.fromEpochMilliseconds(System.currentTimeMillis()),
-                episodes = result.episodes?.map { episode ->
-                    RssEpisode(
-                        id = episode.id,
-                        title = episode.title,
-                        description = episode.description,
-                        audioUrl = episode.audioUrl,
-                        publishDate = Instant.fromEpochMilliseconds(episode.publishDate),
-                        duration = episode.duration,
-                        imageUrl = episode.imageUrl,
-                        chapters = episode.chapters.map { chapter ->
-                            Chapter(
-                                startTimeMs = chapter.startTimeMs,
-                                title = chapter.title,
-                                imageUrl = chapter.imageUrl,
-                                url = chapter.url
-                            )
-                        }
-                    )
-                } ?: emptyList()
-            )
+            decode(feedUrl, parseRss(feedUrl, xmlContent))
         } catch (e: Exception) {
             println("Failed to parse RSS with Rust parser: ${e.message}")
             e.printStackTrace()
@@ -150,6 +116,65 @@ object RustRssParser {
         }
     }
 
+    /**
+     * Convert the JSON returned by [parseRss] into a [PodcastFeed].
+     *
+     * @return Parsed PodcastFeed or null if the native parser reported an error
+     */
+    internal fun decode(feedUrl: String, jsonResult: String): PodcastFeed? {
+        val result = json.decodeFromString<RustPodcastFeed>(jsonResult)
+
+        // Check for error (feed-rs encountered an incompatible feed format)
+        if (result.error != null) {
+            // This is expected for some feed formats - will fallback to SimpleRssParser
+            return null
+        }
+
+        // Convert Rust result to Kotlin PodcastFeed
+        return PodcastFeed(
+            id = result.id ?: return null,
+            title = result.title ?: return null,
+            description = result.description ?: "",
+            artworkUrl = result.artworkUrl,
+            feedUrl = result.feedUrl ?: feedUrl,
+            lastUpdated = result.lastUpdated?.let { Instant.fromEpochMilliseconds(it) }
+                ?: Instant.fromEpochMilliseconds(System.currentTimeMillis()),
+            episodes = result.episodes?.map { episode ->
+                RssEpisode(
+                    id = episode.id,
+                    title = episode.title,
+                    description = episode.description,
+                    audioUrl = episode.audioUrl,
+                    // Unknown dates sort as oldest instead of "just published"
+                    publishDate = Instant.fromEpochMilliseconds(episode.publishDate ?: 0L),
+                    duration = episode.duration,
+                    imageUrl = episode.imageUrl,
+                    chapters = episode.chapters.map { chapter ->
+                        Chapter(
+                            startTimeMs = chapter.startTimeMs,
+                            title = chapter.title,
+                            imageUrl = chapter.imageUrl,
+                            url = chapter.url
+                        )
+                    },
+                    season = episode.season,
+                    seasonName = episode.seasonName,
+                    episodeNumber = episode.episodeNumber,
+                    nonAudio = episode.nonAudio,
+                    alternates = episode.alternates.map { rendition ->
+                        AudioRendition(
+                            url = rendition.url,
+                            mime = rendition.mime,
+                            bitrate = rendition.bitrate,
+                            bytes = rendition.bytes
+                        )
+                    }
+                )
+            } ?: emptyList(),
+            skippedItems = result.skipped.nonAudioEnclosure + result.skipped.noEnclosure
+        )
+    }
+
     /**
      * Native method implemented in Rust.
      * Returns JSON string containing the parsed feed or error.
@@ -169,19 +194,39 @@ private data class RustPodcastFeed(
     val feedUrl: String? = null,
     val lastUpdated: Long? = null,
     val episodes: List<RustRssEpisode>? = null,
+    val skipped: RustSkippedItems = RustSkippedItems(),
     val error: String? = null
 )
 
+@Serializable
+private data class RustSkippedItems(
+    val nonAudioEnclosure: Int = 0,
+    val noEnclosure: Int = 0
+)
+
 @Serializable
 private data class RustRssEpisode(
     val id: String,
     val title: String,
     val description: String,
     val audioUrl: String,
-    val publishDate: Long,
+    val publishDate: Long? = null,
     val duration: Long? = null,
     val imageUrl: String? = null,
-    val chapters: List<RustChapter> = emptyList()
+    val chapters: List<RustChapter> = emptyList(),
+    val season: Int? = null,
+    val seasonName: String? = null,
+    val episodeNumber: Double? = null,
+    val nonAudio: Boolean = false,
+    val alternates: List<RustAudioRendition> = emptyList()
+)
+
+@Serializable
+private data class RustAudioRendition(
+    val url: String,
+    val mime: String? = null,
+    val bitrate: Int? = null,
+    val bytes: Long? = null
 )
 
 @Serializable
@@ -0,0 +1,73 @@
+package com.opoojkk.podium.data.rss
+
+import com.opoojkk.podium.platform.createPlatformHttpClient
+import com.sun.net.httpserver.HttpServer
+import kotlinx.coroutines.runBlocking
+import java.io.ByteArrayOutputStream
+import java.net.InetSocketAddress
+import java.util.zip.GZIPOutputStream
+import kotlin.test.Test
+import kotlin.test.assertEquals
+
+class PodcastFeedServiceTest {
+
+    private val feedXml = """
+        <?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0">
+          <channel>
+            <title>Encoded Feed</title>
+            <description>Served compressed</description>
+            <item>
+              <guid>ep-1</guid>
+              <title>Episode 1</title>
+              <enclosure url="https://example.com/ep1.mp3" type="audio/mpeg" length="1"/>
+            </item>
+          </channel>
+        </rss>
+    """.trimIndent()
+
+    /** Serves the feed chunked, gzip-encoded when [gzip] is set, otherwise as plain identity */
+    private fun withServer(gzip: Boolean, block: (String) -> Unit) {
+        val server = HttpServer.create(InetSocketAddress("127.0.0.1", 0), 0)
+        server.createContext("/feed.xml") { exchange ->
+            val body = if (gzip) {
+                exchange.responseHeaders.add("Content-Encoding", "gzip")
+                ByteArrayOutputStream().also { out ->
+                    GZIPOutputStream(out).use { it.write(feedXml.toByteArray()) }
+                }.toByteArray()
+            } else {
+                feedXml.toByteArray()
+            }
+            exchange.responseHeaders.add("Content-Type", "application/rss+xml")
+            // Length 0 makes the server use chunked transfer encoding
+            exchange.sendResponseHeaders(200, 0)
+            exchange.responseBody.use { it.write(body) }
+        }
+        server.start()
+        try {
+            block("http://127.0.0.1:${server.address.port}/feed.xml")
+        } finally {
+            server.stop(0)
+        }
+    }
+
+    private fun fetch(url: String): PodcastFeed = runBlocking {
+        createPlatformHttpClient().use { client ->
+            PodcastFeedService(client, SimpleRssParser()).fetch(url)
+        }
+    }
+
+    @Test
+    fun gzipChunkedFeedIsDecoded() = withServer(gzip = true) { url ->
+        val feed = fetch(url)
+        assertEquals("Encoded Feed", feed.title)
+        assertEquals(1, feed.episodes.size)
+    }
+
+    @Test
+    fun serverIgnoringAcceptEncodingStillParses() = withServer(gzip = false) { url ->
+        val feed = fetch(url)
+        assertEquals("Encoded Feed", feed.title)
+        assertEquals(1, feed.episodes.size)
+    }
+}
@@ -0,0 +1,53 @@
+package com.opoojkk.podium.data.rss
+
+import kotlinx.datetime.Instant
+import java.io.File
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFalse
+import kotlin.test.assertNotNull
+import kotlin.test.assertNull
+
+class RustRssParserTest {
+
+    /**
+     * `parseRss` output for `rust-rss-parser/fixtures/feed.xml`; the Rust crate's tests keep it
+     * in step with what the native parser returns.
+     */
+    private val parsedJson = File("../rust-rss-parser/fixtures/feed.json").readText()
+
+    @Test
+    fun decodesNativeParserOutput() {
+        val feed = assertNotNull(RustRssParser.decode("https://example.com/feed.xml", parsedJson))
+
+        assertEquals("Fixture Podcast", feed.title)
+        assertEquals(listOf("Pilot", "Bonus"), feed.episodes.map { it.title })
+        // The video-only item
+        assertEquals(1, feed.skippedItems)
+
+        val pilot = feed.episodes[0]
+        assertEquals("https://example.com/pilot.mp3", pilot.audioUrl)
+        assertEquals(Instant.fromEpochMilliseconds(1_704_067_200_000), pilot.publishDate)
+        assertEquals(1, pilot.season)
+        assertEquals("Beginnings", pilot.seasonName)
+        assertEquals(1.0, pilot.episodeNumber)
+        assertFalse(pilot.nonAudio)
+        assertEquals(
+            listOf(
+                AudioRendition("https://example.com/pilot.mp3", "audio/mpeg", 128, 9_600_000),
+                AudioRendition("https://example.com/pilot.m4a", "audio/aac", 64, 4_800_000),
+            ),
+            pilot.alternates
+        )
+
+        val bonus = feed.episodes[1]
+        assertEquals(1, bonus.season)
+        assertNull(bonus.seasonName)
+        assertEquals(1.5, bonus.episodeNumber)
+    }
+
+    @Test
+    fun reportsNativeErrorsAsNull() {
+        assertNull(RustRssParser.decode("https://example.com/feed.xml", """{"error":"Failed to parse RSS feed"}"""))
+    }
+}
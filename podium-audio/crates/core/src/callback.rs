@@ -25,7 +25,11 @@ pub enum CallbackEvent {
     PlaybackCompleted,
 
     /// Playback error occurred
-    Error { message: String },
+    Error {
+        message: String,
+        /// Approximate stream position of a decoding failure, when known
+        position_ms: Option<u64>,
+    },
 
     /// Buffering state changed
     BufferingChanged { buffering: bool },
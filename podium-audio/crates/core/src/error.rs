@@ -36,6 +36,13 @@ pub enum AudioError {
     /// Decoding error
     DecodingError(String),
 
+    /// Decoding error at a known point in the stream
+    DecodingErrorAt {
+        message: String,
+        /// Timestamp of the failing packet (or the last one read) in milliseconds
+        position_ms: u64,
+    },
+
     /// Network error (download/streaming)
     NetworkError(String),
 
@@ -57,6 +64,14 @@ impl fmt::Display for AudioError {
             AudioError::JniError(msg) => write!(f, "JNI error: {}", msg),
             AudioError::IoError(msg) => write!(f, "IO error: {}", msg),
             AudioError::DecodingError(msg) => write!(f, "Decoding error: {}", msg),
+            AudioError::DecodingErrorAt { message, position_ms } => write!(
+                f,
+                "Decoding error at {}:{:02}.{:03}: {}",
+                position_ms / 60_000,
+                position_ms / 1000 % 60,
+                position_ms % 1000,
+                message
+            ),
             AudioError::NetworkError(msg) => write!(f, "Network error: {}", msg),
             AudioError::Other(msg) => write!(f, "Error: {}", msg),
         }
@@ -65,6 +80,47 @@ impl fmt::Display for AudioError {
 
 impl std::error::Error for AudioError {}
 
+impl AudioError {
+    /// Return code for the C ABI and JNI, so hosts can tell failures apart
+    /// -1 other (and the generic failure), -2 initialization, -3 load, -4 playback, -5 invalid state,
+    /// -6 unsupported format, -7 device, -8 thread, -9 JNI, -10 IO, -11 decoding, -12 network
+    pub fn code(&self) -> i32 {
+        match self {
+            AudioError::Other(_) => -1,
+            AudioError::InitializationError(_) => -2,
+            AudioError::LoadError(_) => -3,
+            AudioError::PlaybackError(_) => -4,
+            AudioError::InvalidState(_) => -5,
+            AudioError::UnsupportedFormat(_) => -6,
+            AudioError::DeviceError(_) => -7,
+            AudioError::ThreadError(_) => -8,
+            #[cfg(target_os = "android")]
+            AudioError::JniError(_) => -9,
+            AudioError::IoError(_) => -10,
+            AudioError::DecodingError(_) | AudioError::DecodingErrorAt { .. } => -11,
+            AudioError::NetworkError(_) => -12,
+        }
+    }
+
+    /// Attach the stream position to a decoding error; other errors are returned unchanged
+    pub fn at_position(self, position_ms: u64) -> Self {
+        match self {
+            AudioError::DecodingError(message) | AudioError::DecodingErrorAt { message, .. } => {
+                AudioError::DecodingErrorAt { message, position_ms }
+            }
+            other => other,
+        }
+    }
+
+    /// Stream position the error occurred at, when known
+    pub fn position_ms(&self) -> Option<u64> {
+        match self {
+            AudioError::DecodingErrorAt { position_ms, .. } => Some(*position_ms),
+            _ => None,
+        }
+    }
+}
+
 /// Result type alias for audio operations
 pub type Result<T> = std::result::Result<T, AudioError>;
 
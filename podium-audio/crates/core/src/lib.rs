@@ -1,12 +1,19 @@
 // Core types and traits for Podium audio player
 
+// Worker isolation (`threads::catch_panic`) needs panics to unwind
+#[cfg(panic = "abort")]
+compile_error!("build with panic = \"unwind\": panic = \"abort\" defeats threads::catch_panic");
+
 pub mod callback;
 pub mod error;
 pub mod player;
 pub mod state;
+pub mod stats;
+pub mod threads;
 
 // Re-export commonly used types
 pub use callback::{CallbackEvent, CallbackManager, PlayerCallback};
 pub use error::{AudioError, Result};
-pub use player::{AudioPlayer, Session};
+pub use player::{clamp_playback_rate, AudioPlayer, Session, MAX_PLAYBACK_RATE, MIN_PLAYBACK_RATE};
 pub use state::{PlaybackStatus, PlayerState, PlayerStateContainer};
+pub use stats::{PlaybackStats, StatsCounters};
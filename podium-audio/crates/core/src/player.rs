@@ -1,10 +1,28 @@
 // Core audio player trait and session management
 
 use crate::callback::PlayerCallback;
-use crate::error::Result;
+use crate::error::{AudioError, Result};
 use crate::state::{PlayerState, PlaybackStatus};
+use crate::stats::PlaybackStats;
 use std::sync::Arc;
 
+/// Slowest supported playback rate
+pub const MIN_PLAYBACK_RATE: f32 = 0.25;
+
+/// Fastest supported playback rate
+pub const MAX_PLAYBACK_RATE: f32 = 4.0;
+
+/// Clamp a requested rate into the supported range; NaN and infinities are rejected
+pub fn clamp_playback_rate(rate: f32) -> Result<f32> {
+    if !rate.is_finite() {
+        return Err(AudioError::InvalidState(format!(
+            "Invalid playback rate: {}",
+            rate
+        )));
+    }
+    Ok(rate.clamp(MIN_PLAYBACK_RATE, MAX_PLAYBACK_RATE))
+}
+
 /// Core audio player trait
 /// All platform-specific implementations must implement this trait
 pub trait AudioPlayer: Send + Sync {
@@ -32,7 +50,8 @@ pub trait AudioPlayer: Send + Sync {
     /// Set volume (0.0 - 1.0)
     fn set_volume(&mut self, volume: f32) -> Result<()>;
 
-    /// Set playback rate/speed (1.0 = normal speed)
+    /// Set playback rate/speed (1.0 = normal speed), clamped to
+    /// `MIN_PLAYBACK_RATE..=MAX_PLAYBACK_RATE`
     fn set_playback_rate(&mut self, rate: f32) -> Result<()>;
 
     /// Get current player state
@@ -41,6 +60,19 @@ pub trait AudioPlayer: Send + Sync {
     /// Get current playback status
     fn get_status(&self) -> PlaybackStatus;
 
+    /// Sample rate the output stream was actually opened at (0 before a stream is initialized)
+    fn output_sample_rate(&self) -> u32;
+
+    /// Channel count the output stream was actually opened with (0 before a stream is initialized)
+    fn output_channels(&self) -> u16;
+
+    /// Whether `seek` can work on the loaded source (false before anything is loaded,
+    /// and for live streams or tracks without a time base)
+    fn is_seekable(&self) -> bool;
+
+    /// Runtime telemetry (underflows, decode rate, network throughput) for diagnosing glitches
+    fn get_stats(&self) -> PlaybackStats;
+
     /// Set a callback for player events
     fn set_callback(&mut self, callback: Option<Arc<dyn PlayerCallback>>);
 
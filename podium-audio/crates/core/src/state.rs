@@ -36,6 +36,9 @@ pub struct PlaybackStatus {
     pub playback_rate: f32,
     /// Whether the player is buffering
     pub buffering: bool,
+    /// `duration_ms` is reliable; false while the track has not been probed
+    /// or the container does not state its length
+    pub duration_known: bool,
 }
 
 impl Default for PlaybackStatus {
@@ -46,10 +49,26 @@ impl Default for PlaybackStatus {
             volume: 1.0,
             playback_rate: 1.0,
             buffering: false,
+            duration_known: false,
         }
     }
 }
 
+impl PlaybackStatus {
+    /// Serialize as a flat JSON object (for the C ABI / JNI)
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"position_ms\":{},\"duration_ms\":{},\"duration_known\":{},\"volume\":{:.3},\"playback_rate\":{:.3},\"buffering\":{}}}",
+            self.position_ms,
+            self.duration_ms,
+            self.duration_known,
+            self.volume,
+            self.playback_rate,
+            self.buffering
+        )
+    }
+}
+
 /// Thread-safe player state container
 #[derive(Clone)]
 pub struct PlayerStateContainer {
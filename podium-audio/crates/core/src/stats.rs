@@ -0,0 +1,116 @@
+// Runtime playback statistics
+// Lock-free counters updated by the engine threads, snapshotted on demand for diagnostics
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Snapshot of playback telemetry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaybackStats {
+    /// Output callbacks that could not be fully served from the ring buffer
+    pub underflow_count: u64,
+    /// Ring buffer fill level (0.0 - 1.0)
+    pub ring_fullness: f32,
+    /// Frames produced by the decoder
+    pub decoded_frames: u64,
+    /// Bytes fetched from the network
+    pub bytes_downloaded: u64,
+    /// Network throughput while transferring (bytes per second)
+    pub download_bps: u64,
+    /// Average time spent decoding one packet
+    pub avg_decode_ms: f64,
+}
+
+impl PlaybackStats {
+    /// Serialize as a flat JSON object (for the C ABI / JNI)
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"underflow_count\":{},\"ring_fullness\":{:.3},\"decoded_frames\":{},\"bytes_downloaded\":{},\"download_bps\":{},\"avg_decode_ms\":{:.3}}}",
+            self.underflow_count,
+            self.ring_fullness,
+            self.decoded_frames,
+            self.bytes_downloaded,
+            self.download_bps,
+            self.avg_decode_ms
+        )
+    }
+}
+
+/// Live counters shared between the decode, render and network threads
+#[derive(Debug, Default)]
+pub struct StatsCounters {
+    underflows: AtomicU64,
+    decoded_frames: AtomicU64,
+    decoded_packets: AtomicU64,
+    decode_time_us: AtomicU64,
+    bytes_downloaded: AtomicU64,
+    download_time_us: AtomicU64,
+}
+
+impl StatsCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an output underflow; returns the new total
+    pub fn record_underflow(&self) -> u64 {
+        self.underflows.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Record one decoded packet
+    pub fn record_decode(&self, frames: u64, elapsed: Duration) {
+        self.decoded_frames.fetch_add(frames, Ordering::Relaxed);
+        self.decoded_packets.fetch_add(1, Ordering::Relaxed);
+        self.decode_time_us
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Record a completed network transfer
+    pub fn record_download(&self, bytes: u64, elapsed: Duration) {
+        self.bytes_downloaded.fetch_add(bytes, Ordering::Relaxed);
+        self.download_time_us
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Build a snapshot; `ring_fullness` comes from the ring buffer owned by the caller
+    pub fn snapshot(&self, ring_fullness: f32) -> PlaybackStats {
+        let packets = self.decoded_packets.load(Ordering::Relaxed);
+        let decode_us = self.decode_time_us.load(Ordering::Relaxed);
+        let bytes = self.bytes_downloaded.load(Ordering::Relaxed);
+        let download_us = self.download_time_us.load(Ordering::Relaxed);
+
+        PlaybackStats {
+            underflow_count: self.underflows.load(Ordering::Relaxed),
+            ring_fullness,
+            decoded_frames: self.decoded_frames.load(Ordering::Relaxed),
+            bytes_downloaded: bytes,
+            download_bps: (bytes * 1_000_000).checked_div(download_us).unwrap_or(0),
+            avg_decode_ms: if packets > 0 {
+                decode_us as f64 / packets as f64 / 1000.0
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_aggregates_counters() {
+        let counters = StatsCounters::new();
+        counters.record_decode(1152, Duration::from_millis(2));
+        counters.record_decode(1152, Duration::from_millis(4));
+        counters.record_download(500_000, Duration::from_millis(500));
+
+        let stats = counters.snapshot(0.5);
+        assert_eq!(stats.decoded_frames, 2304);
+        assert!((stats.avg_decode_ms - 3.0).abs() < 1e-9);
+        assert_eq!(stats.bytes_downloaded, 500_000);
+        assert_eq!(stats.download_bps, 1_000_000);
+        assert_eq!(stats.ring_fullness, 0.5);
+        assert!(stats.to_json().contains("\"download_bps\":1000000"));
+    }
+}
@@ -0,0 +1,76 @@
+// Named, panic-isolated engine threads
+
+use crate::error::{AudioError, Result};
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+
+/// Progressive HTTP download
+pub const DOWNLOAD_THREAD: &str = "podium-download";
+
+/// Playback engine thread that owns the output stream
+pub const RENDER_THREAD: &str = "podium-render";
+
+/// Spawn a thread with the given name (kept under 16 bytes so Linux/Android don't truncate it)
+pub fn spawn_named<F, T>(name: &str, f: F) -> Result<thread::JoinHandle<T>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    thread::Builder::new()
+        .name(name.to_string())
+        .spawn(f)
+        .map_err(|e| AudioError::ThreadError(format!("Failed to spawn {} thread: {}", name, e)))
+}
+
+/// Run `f`, converting a panic into `AudioError::Other` instead of unwinding the thread
+pub fn catch_panic<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> T,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|msg| msg.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let thread_name = thread::current().name().unwrap_or("worker").to_string();
+        AudioError::Other(format!("{} thread panicked: {}", thread_name, message))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawned_thread_is_named() {
+        let handle = spawn_named(RENDER_THREAD, || {
+            thread::current().name().map(|name| name.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(handle.thread().name(), Some(RENDER_THREAD));
+        assert_eq!(handle.join().unwrap().as_deref(), Some(RENDER_THREAD));
+    }
+
+    #[test]
+    fn test_panic_becomes_error() {
+        let handle = spawn_named(DOWNLOAD_THREAD, || catch_panic(|| panic!("corrupt packet"))).unwrap();
+
+        let result: Result<()> = handle.join().unwrap();
+        match result {
+            Err(AudioError::Other(msg)) => {
+                assert!(msg.contains(DOWNLOAD_THREAD));
+                assert!(msg.contains("corrupt packet"));
+            }
+            other => panic!("expected panic error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_thread_names_fit_os_limit() {
+        for name in [DOWNLOAD_THREAD, RENDER_THREAD] {
+            assert!(name.len() <= 15, "{} would be truncated", name);
+        }
+    }
+}
@@ -1,11 +1,19 @@
 // Audio decoding using Symphonia
 
 use podium_core::{AudioError, Result};
-use podium_demux::Demuxer;
-use symphonia::core::audio::{AudioBufferRef, Signal};
+use podium_demux::{Demuxer, TrackInfo};
+use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Signal};
+use symphonia::core::sample::Sample;
 use symphonia::core::codecs::{Decoder, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
 use symphonia::core::formats::Packet;
 
+/// Packet size assumed when the container does not declare one
+const FALLBACK_MAX_FRAMES_PER_PACKET: u64 = 65536;
+
+/// Packets tried when the sample rate has to be discovered by decoding
+const MAX_FORMAT_PROBE_PACKETS: usize = 16;
+
 /// Audio decoder
 pub struct AudioDecoder {
     decoder: Box<dyn Decoder>,
@@ -17,6 +25,61 @@ impl AudioDecoder {
     /// Create decoder from demuxer
     pub fn from_demuxer(demuxer: &Demuxer) -> Result<Self> {
         let track_info = demuxer.get_track_info()?;
+        Ok(Self {
+            decoder: Self::make_codec_decoder(demuxer)?,
+            sample_rate: track_info.sample_rate,
+            channels: track_info.channels,
+        })
+    }
+
+    /// Create decoder from demuxer, resolving the track format.
+    /// When the container does not state the sample rate, packets are decoded until one
+    /// yields audio and its spec is used instead. That audio is returned (interleaved f32)
+    /// so the caller can play it rather than drop the start of the stream.
+    pub fn open(demuxer: &mut Demuxer) -> Result<(Self, TrackInfo, Vec<f32>)> {
+        let mut decoder = Self::make_codec_decoder(demuxer)?;
+        if let Ok(track_info) = demuxer.get_track_info() {
+            let decoder = Self {
+                decoder,
+                sample_rate: track_info.sample_rate,
+                channels: track_info.channels,
+            };
+            return Ok((decoder, track_info, Vec::new()));
+        }
+
+        log::info!("[decode] sample rate not in codec params, decoding to discover it");
+        for _ in 0..MAX_FORMAT_PROBE_PACKETS {
+            let packet = demuxer.next_packet()?;
+            let audio_buf = match decoder.decode(&packet) {
+                Ok(buf) => buf,
+                Err(SymphoniaError::DecodeError(e)) => {
+                    log::warn!("[decode] skipping undecodable packet: {}", e);
+                    continue;
+                }
+                Err(e) => return Err(AudioError::DecodingError(format!("Decoding failed: {}", e))),
+            };
+            if audio_buf.frames() == 0 {
+                continue;
+            }
+
+            let spec = *audio_buf.spec();
+            let pcm = convert_audio_buffer_to_f32(audio_buf);
+            let track_info =
+                demuxer.get_track_info_with_format(spec.rate, spec.channels.count() as u16)?;
+            let decoder = Self {
+                decoder,
+                sample_rate: track_info.sample_rate,
+                channels: track_info.channels,
+            };
+            return Ok((decoder, track_info, pcm));
+        }
+
+        Err(AudioError::UnsupportedFormat(
+            "Sample rate not specified and no decodable audio found".to_string(),
+        ))
+    }
+
+    fn make_codec_decoder(demuxer: &Demuxer) -> Result<Box<dyn Decoder>> {
         let track = demuxer
             .format_reader()
             .tracks()
@@ -24,17 +87,22 @@ impl AudioDecoder {
             .find(|t| t.id == demuxer.track_id())
             .ok_or_else(|| AudioError::LoadError("Track not found".to_string()))?;
 
-        let codec_params = &track.codec_params;
+        // Matroska PCM tracks carry no frames-per-packet hint, which the PCM decoder requires
+        let mut codec_params = track.codec_params.clone();
+        if codec_params.max_frames_per_packet.is_none() {
+            codec_params.with_max_frames_per_packet(FALLBACK_MAX_FRAMES_PER_PACKET);
+        }
 
-        let decoder = symphonia::default::get_codecs()
-            .make(codec_params, &DecoderOptions::default())
-            .map_err(|e| AudioError::DecodingError(format!("Failed to create decoder: {}", e)))?;
+        symphonia::default::get_codecs()
+            .make(&codec_params, &DecoderOptions::default())
+            .map_err(|e| AudioError::DecodingError(format!("Failed to create decoder: {}", e)))
+    }
 
-        Ok(Self {
-            decoder,
-            sample_rate: track_info.sample_rate,
-            channels: track_info.channels,
-        })
+    /// Switch the demuxer to another track and rebuild the codec decoder for it
+    pub fn select_track(&mut self, demuxer: &mut Demuxer, track_id: u32) -> Result<()> {
+        demuxer.select_track(track_id)?;
+        *self = Self::from_demuxer(demuxer)?;
+        Ok(())
     }
 
     /// Decode a packet into PCM samples
@@ -47,6 +115,11 @@ impl AudioDecoder {
         Ok(convert_audio_buffer_to_f32(audio_buf))
     }
 
+    /// Drop inter-frame state (overlap buffers, bit reservoirs); call after the demuxer seeks
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
     /// Get sample rate
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
@@ -60,102 +133,343 @@ impl AudioDecoder {
 
 /// Convert Symphonia AudioBufferRef to interleaved f32 samples
 fn convert_audio_buffer_to_f32(buffer: AudioBufferRef) -> Vec<f32> {
-    let num_channels = buffer.spec().channels.count();
-    let num_frames = buffer.frames();
-    let mut output = Vec::with_capacity(num_frames * num_channels);
-
     match buffer {
         AudioBufferRef::F32(buf) => {
-            // F32: Direct copy and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    output.push(buf.chan(ch_idx)[frame_idx]);
-                }
+            // F32: mono is already "interleaved", copy the plane as-is
+            if buf.spec().channels.count() == 1 {
+                buf.chan(0).to_vec()
+            } else {
+                interleave(&buf, |s| s)
             }
         }
-        AudioBufferRef::F64(buf) => {
-            // F64: Convert to f32 and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    output.push(buf.chan(ch_idx)[frame_idx] as f32);
-                }
-            }
+        AudioBufferRef::F64(buf) => interleave(&buf, |s| s as f32),
+        // Signed: scale to f32 [-1.0, 1.0]
+        AudioBufferRef::S8(buf) => interleave(&buf, |s| s as f32 / 128.0),
+        AudioBufferRef::S16(buf) => interleave(&buf, |s| s as f32 / 32768.0),
+        // S24 is a newtype around i32, use inner() to get the i32 value
+        AudioBufferRef::S24(buf) => interleave(&buf, |s| s.inner() as f32 / 8388608.0), // 2^23
+        AudioBufferRef::S32(buf) => interleave(&buf, |s| s as f32 / 2147483648.0), // 2^31
+        // Unsigned: remove the offset, then scale
+        AudioBufferRef::U8(buf) => interleave(&buf, |s| (s as f32 - 128.0) / 128.0),
+        AudioBufferRef::U16(buf) => interleave(&buf, |s| (s as f32 - 32768.0) / 32768.0),
+        // U24 is a newtype around u32, use inner() to get the u32 value
+        AudioBufferRef::U24(buf) => {
+            interleave(&buf, |s| (s.inner() as f32 - 8388608.0) / 8388608.0) // 2^23
         }
-        AudioBufferRef::S8(buf) => {
-            // S8: Convert to f32 [-1.0, 1.0] and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    let sample = buf.chan(ch_idx)[frame_idx];
-                    output.push(sample as f32 / 128.0);
-                }
-            }
+        AudioBufferRef::U32(buf) => {
+            interleave(&buf, |s| (s as f32 - 2147483648.0) / 2147483648.0) // 2^31
         }
-        AudioBufferRef::S16(buf) => {
-            // S16: Convert to f32 [-1.0, 1.0] and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    let sample = buf.chan(ch_idx)[frame_idx];
-                    output.push(sample as f32 / 32768.0);
-                }
-            }
+    }
+}
+
+/// Interleave planar channels into one f32 buffer, one channel plane at a time
+fn interleave<S: Sample>(buf: &AudioBuffer<S>, convert: impl Fn(S) -> f32) -> Vec<f32> {
+    let num_channels = buf.spec().channels.count();
+    if num_channels == 1 {
+        return buf.chan(0).iter().map(|&s| convert(s)).collect();
+    }
+
+    let mut output = vec![0.0; buf.frames() * num_channels];
+    for ch_idx in 0..num_channels {
+        for (frame, &sample) in output.chunks_exact_mut(num_channels).zip(buf.chan(ch_idx)) {
+            frame[ch_idx] = convert(sample);
         }
-        AudioBufferRef::S24(buf) => {
-            // S24: Stored as i32, convert to f32 [-1.0, 1.0] and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    let sample = buf.chan(ch_idx)[frame_idx];
-                    // S24 is a newtype around i32, use inner() to get the i32 value
-                    output.push(sample.inner() as f32 / 8388608.0); // 2^23
-                }
-            }
+    }
+    output
+}
+
+/// Sample layout of headerless (raw) little-endian PCM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PcmFormat {
+    I16,
+    I32,
+    F32,
+}
+
+impl PcmFormat {
+    /// Convert from the C ABI / JNI integer representation (0=I16, 1=I32, 2=F32)
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(PcmFormat::I16),
+            1 => Some(PcmFormat::I32),
+            2 => Some(PcmFormat::F32),
+            _ => None,
         }
-        AudioBufferRef::S32(buf) => {
-            // S32: Convert to f32 [-1.0, 1.0] and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    let sample = buf.chan(ch_idx)[frame_idx];
-                    output.push(sample as f32 / 2147483648.0); // 2^31
-                }
-            }
+    }
+
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            PcmFormat::I16 => 2,
+            PcmFormat::I32 | PcmFormat::F32 => 4,
         }
-        AudioBufferRef::U8(buf) => {
-            // U8: Convert to f32 [-1.0, 1.0] and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    let sample = buf.chan(ch_idx)[frame_idx];
-                    output.push((sample as f32 - 128.0) / 128.0);
-                }
-            }
+    }
+
+    /// Codec short name, matching what Symphonia reports for the same layout
+    pub fn codec_name(self) -> &'static str {
+        match self {
+            PcmFormat::I16 => "pcm_s16le",
+            PcmFormat::I32 => "pcm_s32le",
+            PcmFormat::F32 => "pcm_f32le",
         }
-        AudioBufferRef::U16(buf) => {
-            // U16: Convert to f32 [-1.0, 1.0] and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    let sample = buf.chan(ch_idx)[frame_idx];
-                    output.push((sample as f32 - 32768.0) / 32768.0);
-                }
-            }
+    }
+}
+
+/// Convert raw interleaved little-endian PCM bytes to f32 samples
+pub fn pcm_bytes_to_f32(data: &[u8], format: PcmFormat) -> Result<Vec<f32>> {
+    let width = format.bytes_per_sample();
+    if !data.len().is_multiple_of(width) {
+        return Err(AudioError::LoadError(format!(
+            "PCM data length {} is not a multiple of the {}-byte sample size",
+            data.len(),
+            width
+        )));
+    }
+
+    let chunks = data.chunks_exact(width);
+    Ok(match format {
+        PcmFormat::I16 => chunks
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+            .collect(),
+        PcmFormat::I32 => chunks
+            .map(|b| (i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64 / 2147483648.0) as f32)
+            .collect(),
+        PcmFormat::F32 => chunks
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use std::io::Cursor;
+    use symphonia::core::audio::{Channels, SignalSpec};
+    use symphonia::core::formats::{Cue, FormatOptions, FormatReader, SeekMode, SeekTo, SeekedTo, Track};
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::{Metadata, MetadataOptions};
+    use symphonia::core::probe::Hint;
+
+    /// Encode an EBML element with an 8-byte size field
+    fn element(id: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.push(0x01);
+        out.extend_from_slice(&(data.len() as u64).to_be_bytes()[1..]);
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn uint(id: &[u8], value: u64) -> Vec<u8> {
+        element(id, &value.to_be_bytes())
+    }
+
+    fn track_entry(number: u8, language: &str) -> Vec<u8> {
+        let audio = [
+            element(&[0xB5], &8000.0f64.to_be_bytes()),
+            uint(&[0x9F], 1),
+            uint(&[0x62, 0x64], 16),
+        ]
+        .concat();
+        element(
+            &[0xAE],
+            &[
+                uint(&[0xD7], number as u64),
+                uint(&[0x73, 0xC5], number as u64),
+                uint(&[0x83], 2),
+                element(&[0x86], b"A_PCM/INT/LIT"),
+                element(&[0x22, 0xB5, 0x9C], language.as_bytes()),
+                element(&[0xE1], &audio),
+            ]
+            .concat(),
+        )
+    }
+
+    fn simple_block(track: u8, sample: i16, frames: usize) -> Vec<u8> {
+        let mut data = vec![0x80 | track, 0, 0, 0x80];
+        for _ in 0..frames {
+            data.extend_from_slice(&sample.to_le_bytes());
         }
-        AudioBufferRef::U24(buf) => {
-            // U24: Stored as u32, convert to f32 [-1.0, 1.0] and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    let sample = buf.chan(ch_idx)[frame_idx];
-                    // U24 is a newtype around u32, use inner() to get the u32 value
-                    output.push((sample.inner() as f32 - 8388608.0) / 8388608.0); // 2^23
-                }
+        element(&[0xA3], &data)
+    }
+
+    /// Mono 16-bit PCM Matroska file with two audio tracks holding different constant levels
+    fn two_track_mkv() -> Vec<u8> {
+        let ebml = element(&[0x1A, 0x45, 0xDF, 0xA3], &element(&[0x42, 0x82], b"matroska"));
+        let info = element(&[0x15, 0x49, 0xA9, 0x66], &uint(&[0x2A, 0xD7, 0xB1], 1_000_000));
+        let tracks = element(
+            &[0x16, 0x54, 0xAE, 0x6B],
+            &[track_entry(1, "eng"), track_entry(2, "fra")].concat(),
+        );
+        let cluster = element(
+            &[0x1F, 0x43, 0xB6, 0x75],
+            &[
+                uint(&[0xE7], 0),
+                simple_block(1, 8192, 80),
+                simple_block(2, -16384, 80),
+            ]
+            .concat(),
+        );
+        let segment = element(&[0x18, 0x53, 0x80, 0x67], &[info, tracks, cluster].concat());
+        [ebml, segment].concat()
+    }
+
+    /// Silent MPEG-1 Layer III frame: 128 kbps, 44.1 kHz, mono
+    fn silent_mp3_frame() -> Vec<u8> {
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0xC0];
+        frame.resize(417, 0);
+        frame
+    }
+
+    /// Delegating reader that hides the sample rate and channels, like ADTS streams
+    /// whose format is only known after decoding the first frame
+    struct RatelessReader {
+        inner: Box<dyn FormatReader>,
+        tracks: Vec<Track>,
+    }
+
+    impl RatelessReader {
+        fn wrap(inner: Box<dyn FormatReader>) -> Self {
+            let tracks = inner
+                .tracks()
+                .iter()
+                .map(|t| {
+                    let mut track = t.clone();
+                    track.codec_params.sample_rate = None;
+                    track.codec_params.channels = None;
+                    track
+                })
+                .collect();
+            Self { inner, tracks }
+        }
+    }
+
+    impl FormatReader for RatelessReader {
+        fn try_new(_: MediaSourceStream, _: &FormatOptions) -> symphonia::core::errors::Result<Self> {
+            unimplemented!("constructed with RatelessReader::wrap")
+        }
+
+        fn cues(&self) -> &[Cue] {
+            self.inner.cues()
+        }
+
+        fn metadata(&mut self) -> Metadata<'_> {
+            self.inner.metadata()
+        }
+
+        fn seek(&mut self, mode: SeekMode, to: SeekTo) -> symphonia::core::errors::Result<SeekedTo> {
+            self.inner.seek(mode, to)
+        }
+
+        fn tracks(&self) -> &[Track] {
+            &self.tracks
+        }
+
+        fn next_packet(&mut self) -> symphonia::core::errors::Result<Packet> {
+            self.inner.next_packet()
+        }
+
+        fn into_inner(self: Box<Self>) -> MediaSourceStream {
+            self.inner.into_inner()
+        }
+    }
+
+    fn open() -> Demuxer {
+        let mut hint = Hint::new();
+        hint.with_extension("mkv");
+        Demuxer::from_media_source(Box::new(Cursor::new(two_track_mkv())), hint).unwrap()
+    }
+
+    #[test]
+    fn test_select_track_changes_decoded_content() {
+        let demuxer = open();
+        let tracks = demuxer.list_tracks();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[1].language.as_deref(), Some("fra"));
+        assert_eq!(tracks[1].sample_rate, Some(8000));
+
+        let mut demuxer = open();
+        let mut decoder = AudioDecoder::from_demuxer(&demuxer).unwrap();
+        let default = decoder.decode(&demuxer.next_packet().unwrap()).unwrap();
+        assert!(default.iter().all(|&s| (s - 0.25).abs() < 1e-6));
+
+        let mut demuxer = open();
+        let mut decoder = AudioDecoder::from_demuxer(&demuxer).unwrap();
+        decoder.select_track(&mut demuxer, tracks[1].id).unwrap();
+        let selected = decoder.decode(&demuxer.next_packet().unwrap()).unwrap();
+        assert_eq!(selected.len(), default.len());
+        assert!(selected.iter().all(|&s| (s + 0.5).abs() < 1e-6));
+
+        assert!(decoder.select_track(&mut demuxer, 99).is_err());
+    }
+
+    /// Reference per-sample interleave the chunked conversion must match
+    fn naive_interleave(buf: &AudioBuffer<f32>) -> Vec<f32> {
+        let num_channels = buf.spec().channels.count();
+        let mut output = Vec::with_capacity(buf.frames() * num_channels);
+        for frame_idx in 0..buf.frames() {
+            for ch_idx in 0..num_channels {
+                output.push(buf.chan(ch_idx)[frame_idx]);
             }
         }
-        AudioBufferRef::U32(buf) => {
-            // U32: Convert to f32 [-1.0, 1.0] and interleave
-            for frame_idx in 0..num_frames {
-                for ch_idx in 0..num_channels {
-                    let sample = buf.chan(ch_idx)[frame_idx];
-                    output.push((sample as f32 - 2147483648.0) / 2147483648.0); // 2^31
-                }
+        output
+    }
+
+    fn planar_f32(channels: Channels, frames: usize) -> AudioBuffer<f32> {
+        let mut buf = AudioBuffer::<f32>::new(frames as u64, SignalSpec::new(44100, channels));
+        buf.render_reserved(Some(frames));
+        for ch_idx in 0..channels.count() {
+            for (i, sample) in buf.chan_mut(ch_idx).iter_mut().enumerate() {
+                *sample = ((i * 7 + ch_idx * 13) % 101) as f32 / 50.0 - 1.0 + f32::EPSILON * ch_idx as f32;
             }
         }
+        buf
     }
 
-    output
+    #[test]
+    fn test_f32_interleave_matches_naive() {
+        let layouts = [
+            Channels::FRONT_LEFT,
+            Channels::FRONT_LEFT | Channels::FRONT_RIGHT,
+            Channels::FRONT_LEFT
+                | Channels::FRONT_RIGHT
+                | Channels::FRONT_CENTRE
+                | Channels::LFE1
+                | Channels::REAR_LEFT
+                | Channels::REAR_RIGHT,
+        ];
+        for channels in layouts {
+            let buf = planar_f32(channels, 1023);
+            let expected: Vec<u32> = naive_interleave(&buf).iter().map(|s| s.to_bits()).collect();
+            let actual: Vec<u32> = convert_audio_buffer_to_f32(AudioBufferRef::F32(Cow::Borrowed(&buf)))
+                .iter()
+                .map(|s| s.to_bits())
+                .collect();
+            assert_eq!(actual, expected, "{} channels", channels.count());
+        }
+    }
+
+    #[test]
+    fn test_open_discovers_missing_sample_rate() {
+        let mp3: Vec<u8> = (0..4).flat_map(|_| silent_mp3_frame()).collect();
+        let stream = MediaSourceStream::new(Box::new(Cursor::new(mp3)), Default::default());
+        let reader = symphonia::default::get_probe()
+            .format(
+                Hint::new().with_extension("mp3"),
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .unwrap()
+            .format;
+        let mut demuxer = Demuxer::from_format_reader(Box::new(RatelessReader::wrap(reader))).unwrap();
+        assert!(demuxer.get_track_info().is_err());
+
+        let (decoder, track_info, pcm) = AudioDecoder::open(&mut demuxer).unwrap();
+        assert_eq!(track_info.sample_rate, 44100);
+        assert_eq!(track_info.channels, 1);
+        assert_eq!(decoder.sample_rate(), 44100);
+        assert_eq!(decoder.channels(), 1);
+        // The probe packet's audio is handed back rather than lost
+        assert_eq!(pcm.len(), 1152);
+    }
 }
@@ -1,6 +1,9 @@
 // Demuxing audio formats using Symphonia
 
 use podium_core::{AudioError, Result};
+use std::io::ErrorKind;
+use symphonia::core::codecs::{CodecType, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
 use symphonia::core::formats::{FormatOptions, FormatReader};
 use symphonia::core::io::{MediaSource, MediaSourceStream};
 use symphonia::core::meta::MetadataOptions;
@@ -10,11 +13,15 @@ use symphonia::core::probe::Hint;
 pub struct Demuxer {
     format_reader: Box<dyn FormatReader>,
     track_id: u32,
+    source_seekable: bool,
+    /// Timestamp of the last packet read (or the last seek target) in milliseconds
+    position_ms: u64,
 }
 
 impl Demuxer {
     /// Create demuxer from a media source
     pub fn from_media_source(media_source: Box<dyn MediaSource>, hint: Hint) -> Result<Self> {
+        let source_seekable = media_source.is_seekable();
         let media_source_stream = MediaSourceStream::new(media_source, Default::default());
 
         // Probe the media source
@@ -27,8 +34,13 @@ impl Demuxer {
             )
             .map_err(|e| AudioError::LoadError(format!("Failed to probe media: {}", e)))?;
 
-        let format_reader = probe_result.format;
+        let mut demuxer = Self::from_format_reader(probe_result.format)?;
+        demuxer.source_seekable = source_seekable;
+        Ok(demuxer)
+    }
 
+    /// Create demuxer from an already opened format reader (its source is assumed seekable)
+    pub fn from_format_reader(format_reader: Box<dyn FormatReader>) -> Result<Self> {
         // Get the default track
         let track = format_reader
             .default_track()
@@ -39,6 +51,8 @@ impl Demuxer {
         Ok(Self {
             format_reader,
             track_id,
+            source_seekable: true,
+            position_ms: 0,
         })
     }
 
@@ -54,31 +68,87 @@ impl Demuxer {
         hint
     }
 
+    /// Create a hint for a network stream from its `Content-Type` and URL path extension.
+    /// With neither recognised the hint is empty and the probe relies on content sniffing.
+    pub fn create_hint_for_stream(url: &str, content_type: Option<&str>) -> Hint {
+        let mut hint = Hint::new();
+        if let Some(mime) = content_type.map(media_type).filter(|m| !m.is_empty()) {
+            hint.mime_type(&mime);
+        }
+        if let Some(extension) = stream_extension(url, content_type) {
+            hint.with_extension(extension);
+        }
+        hint
+    }
+
     /// Get the next packet from the format reader
     pub fn next_packet(&mut self) -> Result<symphonia::core::formats::Packet> {
+        match self.read_packet() {
+            PacketRead::Packet(packet) => Ok(packet),
+            PacketRead::EndOfStream => Err(AudioError::DecodingError(
+                "Failed to read packet: end of stream".to_string(),
+            )),
+            PacketRead::Recoverable(e) | PacketRead::Fatal(e) => Err(e),
+        }
+    }
+
+    /// Get the next packet, telling end of stream apart from errors worth retrying
+    pub fn read_packet(&mut self) -> PacketRead {
         loop {
-            let packet = self
-                .format_reader
-                .next_packet()
-                .map_err(|e| AudioError::DecodingError(format!("Failed to read packet: {}", e)))?;
+            let packet = match self.format_reader.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+                    return PacketRead::EndOfStream;
+                }
+                Err(e) => {
+                    let error = AudioError::DecodingError(format!("Failed to read packet: {}", e))
+                        .at_position(self.position_ms);
+                    return match e {
+                        // Malformed data, a network hiccup or a changed track list: the next read may succeed
+                        SymphoniaError::IoError(_)
+                        | SymphoniaError::DecodeError(_)
+                        | SymphoniaError::ResetRequired => PacketRead::Recoverable(error),
+                        _ => PacketRead::Fatal(error),
+                    };
+                }
+            };
 
             // Only return packets for our track
             if packet.track_id() == self.track_id {
-                return Ok(packet);
+                if let Some(tb) = self.time_base() {
+                    self.position_ms = packet.ts() * 1000 * tb.numer as u64 / tb.denom.max(1) as u64;
+                }
+                return PacketRead::Packet(packet);
             }
         }
     }
 
-    /// Seek to a specific time position
-    pub fn seek(&mut self, time_ms: u64) -> Result<()> {
-        let time_base = self
-            .format_reader
+    /// Whether `seek` can work: the source supports random access and the track has a time base
+    pub fn is_seekable(&self) -> bool {
+        self.source_seekable
+            && self
+                .format_reader
+                .tracks()
+                .iter()
+                .any(|t| t.id == self.track_id && t.codec_params.time_base.is_some())
+    }
+
+    /// Position of the last packet read (or the last seek target) in milliseconds
+    pub fn position_ms(&self) -> u64 {
+        self.position_ms
+    }
+
+    fn time_base(&self) -> Option<symphonia::core::units::TimeBase> {
+        self.format_reader
             .tracks()
             .iter()
             .find(|t| t.id == self.track_id)
-            .and_then(|t| t.codec_params.time_base);
+            .and_then(|t| t.codec_params.time_base)
+    }
 
-        if let Some(tb) = time_base {
+    /// Seek to a specific time position
+    pub fn seek(&mut self, time_ms: u64) -> Result<()> {
+        if let Some(tb) = self.time_base() {
             let timestamp = (time_ms * tb.denom as u64) / (tb.numer as u64 * 1000);
             self.format_reader
                 .seek(
@@ -86,13 +156,50 @@ impl Demuxer {
                     symphonia::core::formats::SeekTo::TimeStamp { ts: timestamp, track_id: self.track_id },
                 )
                 .map_err(|e| AudioError::PlaybackError(format!("Seek failed: {}", e)))?;
+            self.position_ms = time_ms;
         }
 
         Ok(())
     }
 
+    /// List the audio tracks in the container (tracks without a codec are skipped)
+    pub fn list_tracks(&self) -> Vec<TrackDescriptor> {
+        self.format_reader
+            .tracks()
+            .iter()
+            .filter(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .map(|t| TrackDescriptor {
+                id: t.id,
+                codec: codec_short_name(t.codec_params.codec),
+                language: t.language.clone(),
+                channels: t.codec_params.channels.map(|c| c.count() as u16),
+                sample_rate: t.codec_params.sample_rate,
+            })
+            .collect()
+    }
+
+    /// Switch to another track. Packets from the previous track are dropped from here on;
+    /// the caller must rebuild its codec decoder for the new track.
+    pub fn select_track(&mut self, track_id: u32) -> Result<()> {
+        if !self.list_tracks().iter().any(|t| t.id == track_id) {
+            return Err(AudioError::InvalidState(format!("No audio track with id {}", track_id)));
+        }
+        self.track_id = track_id;
+        Ok(())
+    }
+
     /// Get track information
     pub fn get_track_info(&self) -> Result<TrackInfo> {
+        self.track_info_with(None)
+    }
+
+    /// Get track information, filling a sample rate / channel count the container
+    /// does not state with values discovered from decoded audio (e.g. some ADTS streams)
+    pub fn get_track_info_with_format(&self, sample_rate: u32, channels: u16) -> Result<TrackInfo> {
+        self.track_info_with(Some((sample_rate, channels)))
+    }
+
+    fn track_info_with(&self, discovered: Option<(u32, u16)>) -> Result<TrackInfo> {
         let track = self
             .format_reader
             .tracks()
@@ -101,19 +208,34 @@ impl Demuxer {
             .ok_or_else(|| AudioError::LoadError("Track not found".to_string()))?;
 
         let codec_params = &track.codec_params;
+        let channels = codec_params
+            .channels
+            .map(|c| c.count() as u16)
+            .or(discovered.map(|(_, channels)| channels))
+            .unwrap_or(2);
+        let sample_rate = codec_params
+            .sample_rate
+            .or(discovered.map(|(rate, _)| rate))
+            .ok_or_else(|| AudioError::UnsupportedFormat("Sample rate not specified".to_string()))?;
+        let codec = codec_short_name(codec_params.codec);
+
+        // Symphonia 0.5 only exposes a bitrate for uncompressed PCM (constant by definition)
+        let bitrate_bps = codec_params
+            .bits_per_coded_sample
+            .or(codec_params.bits_per_sample)
+            .filter(|_| codec.starts_with("pcm_"))
+            .map(|bits| bits * sample_rate * channels as u32);
 
         Ok(TrackInfo {
-            sample_rate: codec_params
-                .sample_rate
-                .ok_or_else(|| AudioError::UnsupportedFormat("Sample rate not specified".to_string()))?,
-            channels: codec_params
-                .channels
-                .map(|c| c.count() as u16)
-                .unwrap_or(2),
+            sample_rate,
+            channels,
             duration_ms: codec_params
                 .time_base
                 .and_then(|tb| codec_params.n_frames.map(|n| (n * 1000 * tb.numer as u64) / tb.denom as u64))
                 .unwrap_or(0),
+            codec,
+            bitrate_bps,
+            is_vbr: false,
         })
     }
 
@@ -133,10 +255,119 @@ impl Demuxer {
     }
 }
 
+/// Result of `Demuxer::read_packet`
+pub enum PacketRead {
+    Packet(symphonia::core::formats::Packet),
+    /// The stream is exhausted
+    EndOfStream,
+    /// Reading failed but later reads may succeed
+    Recoverable(AudioError),
+    /// Reading cannot continue
+    Fatal(AudioError),
+}
+
+/// Description of one selectable audio track
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackDescriptor {
+    pub id: u32,
+    /// Short codec name (e.g. "aac", "flac")
+    pub codec: String,
+    pub language: Option<String>,
+    pub channels: Option<u16>,
+    pub sample_rate: Option<u32>,
+}
+
 /// Track information
 #[derive(Debug, Clone)]
 pub struct TrackInfo {
     pub sample_rate: u32,
     pub channels: u16,
     pub duration_ms: u64,
+    /// Short codec name (e.g. "aac", "flac")
+    pub codec: String,
+    /// Nominal bitrate, when the container/codec states one
+    pub bitrate_bps: Option<u32>,
+    pub is_vbr: bool,
+}
+
+impl TrackInfo {
+    /// Serialize as a flat JSON object (for the C ABI / JNI)
+    pub fn to_json(&self) -> String {
+        let bitrate = self
+            .bitrate_bps
+            .map(|b| b.to_string())
+            .unwrap_or_else(|| "null".to_string());
+        format!(
+            "{{\"sample_rate\":{},\"channels\":{},\"duration_ms\":{},\"codec\":\"{}\",\"bitrate_bps\":{},\"is_vbr\":{}}}",
+            self.sample_rate,
+            self.channels,
+            self.duration_ms,
+            self.codec.replace('\\', "\\\\").replace('"', "\\\""),
+            bitrate,
+            self.is_vbr
+        )
+    }
+}
+
+/// Container extension for a network stream. The `Content-Type` describes the bytes
+/// actually served, so it wins over the URL (which may be a tracking redirect); a generic
+/// type such as `application/octet-stream` defers to a known audio extension in the path.
+pub fn stream_extension(url: &str, content_type: Option<&str>) -> Option<&'static str> {
+    let from_type = match content_type.map(media_type).as_deref() {
+        Some("audio/mpeg" | "audio/mp3" | "audio/mpeg3" | "audio/x-mpeg-3") => Some("mp3"),
+        Some("audio/aac" | "audio/aacp" | "audio/x-aac" | "audio/adts") => Some("aac"),
+        Some("audio/mp4" | "audio/m4a" | "audio/x-m4a" | "audio/x-m4b" | "video/mp4") => Some("m4a"),
+        Some("audio/ogg" | "application/ogg" | "audio/opus" | "audio/vorbis") => Some("ogg"),
+        Some("audio/flac" | "audio/x-flac") => Some("flac"),
+        Some("audio/wav" | "audio/wave" | "audio/x-wav" | "audio/vnd.wave") => Some("wav"),
+        Some("audio/webm" | "video/webm") => Some("webm"),
+        _ => None,
+    };
+    from_type.or_else(|| {
+        const KNOWN: [&str; 12] = [
+            "mp3", "aac", "m4a", "m4b", "mp4", "ogg", "oga", "opus", "flac", "wav", "webm", "mka",
+        ];
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        // Only the path counts: a host such as `example.aac` is not an extension
+        let path = match path.split_once("://") {
+            Some((_, rest)) => rest.split_once('/').map_or("", |(_, path)| path),
+            None => path,
+        };
+        let extension = std::path::Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        KNOWN.into_iter().find(|known| *known == extension)
+    })
+}
+
+/// Media type without parameters, lowercased (`Audio/AAC; charset=x` -> `audio/aac`)
+fn media_type(content_type: &str) -> String {
+    content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase()
+}
+
+fn codec_short_name(codec: CodecType) -> String {
+    symphonia::default::get_codecs()
+        .get_codec(codec)
+        .map(|d| d.short_name.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_extension_prefers_content_type() {
+        assert_eq!(stream_extension("https://cdn.example.com/live", Some("audio/aac")), Some("aac"));
+        assert_eq!(
+            stream_extension("https://t.example.com/ep.mp3", Some("audio/mp4; codecs=mp4a")),
+            Some("m4a")
+        );
+        assert_eq!(stream_extension("https://x.example.com/ep.M4A?sig=1.mp3", None), Some("m4a"));
+        assert_eq!(
+            stream_extension("https://x.example.com/ep.ogg", Some("application/octet-stream")),
+            Some("ogg")
+        );
+        // Neither source recognised: leave it to content sniffing
+        assert_eq!(stream_extension("https://x.example.com/stream.php", Some("text/html")), None);
+        assert_eq!(stream_extension("https://example.aac", None), None);
+    }
 }
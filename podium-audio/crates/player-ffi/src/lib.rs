@@ -4,17 +4,21 @@
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use podium_core::threads::{catch_panic, spawn_named, RENDER_THREAD};
 use podium_core::{
-    AudioError, AudioPlayer, PlaybackStatus, PlayerCallback, PlayerState, PlayerStateContainer,
-    Result,
+    AudioError, AudioPlayer, CallbackEvent, PlaybackStats, PlaybackStatus, PlayerCallback, PlayerState,
+    PlayerStateContainer, Result, StatsCounters,
 };
-use podium_decode::AudioDecoder;
-use podium_demux::Demuxer;
+use podium_decode::{pcm_bytes_to_f32, AudioDecoder, PcmFormat};
+use podium_demux::{Demuxer, PacketRead, TrackInfo};
+use podium_playlist::{PlaylistEntry, SourceKind};
+use podium_resampler::{ChannelMode, Resampler};
 use podium_ringbuffer::SharedRingBuffer;
 use podium_source_buffer::NetworkSource;
-use std::collections::HashMap;
+use podium_transport_http::HttpConfig;
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, AtomicU16, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Once};
 use std::thread;
 
@@ -22,11 +26,42 @@ use std::thread;
 /// This currently manages state only; audio pipeline integration can be layered in later.
 struct PodiumPlayer {
     state: PlayerStateContainer,
-    callback: Option<Arc<dyn PlayerCallback>>,
+    /// App callback, shared with the engine so decode errors can be reported
+    callback: Arc<Mutex<Option<Arc<dyn PlayerCallback>>>>,
     loaded: bool,
     /// Playback engine (decoder + renderer)
     engine: Option<PlaybackEngine>,
     last_source: Option<SourceKind>,
+    /// The current source came from app memory (`load_buffer` or `load_pcm`)
+    buffer_source: bool,
+    /// Requested output channel layout (`ChannelMode` as i32), shared with the decode loop
+    channel_mode: Arc<AtomicI32>,
+    /// Default ring sizes per source kind
+    buffer_config: BufferConfig,
+    /// Ring length override in seconds (f32 bits, 0 = use `buffer_config`), shared with the decode loop
+    buffer_seconds: Arc<AtomicU32>,
+    /// Network timeouts used by subsequent `load_url` calls
+    http_config: HttpConfig,
+    /// Extra request headers for the current HTTP source, given per load (see `load_url_with_headers`)
+    source_headers: Vec<(String, String)>,
+    /// Entries waiting to be played after the current source
+    queue: VecDeque<PlaylistEntry>,
+}
+
+/// A validated URL load, waiting for its reachability probe (see `load_url_unlocked`)
+struct UrlLoad {
+    url: String,
+    headers: Vec<(String, String)>,
+    /// The player's network settings with this load's headers
+    http_config: HttpConfig,
+}
+
+impl UrlLoad {
+    /// HEAD the server so unreachable hosts and redirect loops fail the load before a decode
+    /// thread is spawned and Ready is reported. Blocks for up to the connect timeout.
+    fn probe(&self) -> Result<()> {
+        podium_transport_http::check_reachable(&self.url, &self.http_config)
+    }
 }
 
 impl PodiumPlayer {
@@ -34,11 +69,234 @@ impl PodiumPlayer {
         log::info!("PodiumPlayer::new");
         Self {
             state: PlayerStateContainer::new(),
-            callback: None,
+            callback: Arc::new(Mutex::new(None)),
             loaded: false,
             engine: None,
             last_source: None,
+            buffer_source: false,
+            channel_mode: Arc::new(AtomicI32::new(ChannelMode::Auto.as_i32())),
+            buffer_config: BufferConfig::default(),
+            buffer_seconds: Arc::new(AtomicU32::new(0)),
+            http_config: HttpConfig::default(),
+            source_headers: Vec::new(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Force mono/stereo output regardless of the source layout (applies live)
+    fn set_output_channel_mode(&mut self, mode: ChannelMode) {
+        log::info!("set_output_channel_mode -> {:?}", mode);
+        self.channel_mode.store(mode.as_i32(), Ordering::SeqCst);
+    }
+
+    /// Override the ring length in seconds (applies live, keeping the playback position)
+    fn set_buffer_seconds(&mut self, seconds: f32) -> Result<()> {
+        if !seconds.is_finite() || seconds <= 0.0 {
+            return Err(AudioError::InvalidState(format!(
+                "Invalid buffer length: {} s",
+                seconds
+            )));
+        }
+        log::info!("set_buffer_seconds -> {}", seconds);
+        self.buffer_seconds.store(seconds.to_bits(), Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Cap how far ahead of playback the engine decodes, whatever the ring length
+    /// (0 removes the cap; takes effect on the next load)
+    fn set_max_lookahead_ms(&mut self, max_lookahead_ms: u64) -> Result<()> {
+        if max_lookahead_ms != 0 && max_lookahead_ms < MIN_LOOKAHEAD_MS {
+            return Err(AudioError::InvalidState(format!(
+                "Invalid max lookahead: {} ms (minimum {} ms)",
+                max_lookahead_ms, MIN_LOOKAHEAD_MS
+            )));
+        }
+        log::info!("set_max_lookahead_ms -> {}", max_lookahead_ms);
+        self.buffer_config.max_lookahead_ms = max_lookahead_ms;
+        Ok(())
+    }
+
+    /// Set the connect/read timeouts for HTTP sources (takes effect on the next `load_url`)
+    fn set_network_timeouts(&mut self, connect_timeout_ms: u64, read_timeout_ms: u64) -> Result<()> {
+        if connect_timeout_ms == 0 || read_timeout_ms == 0 {
+            return Err(AudioError::InvalidState(format!(
+                "Invalid network timeouts: connect {} ms, read {} ms",
+                connect_timeout_ms, read_timeout_ms
+            )));
+        }
+        log::info!(
+            "set_network_timeouts -> connect {} ms, read {} ms",
+            connect_timeout_ms,
+            read_timeout_ms
+        );
+        self.http_config = HttpConfig {
+            connect_timeout: std::time::Duration::from_millis(connect_timeout_ms),
+            read_timeout: std::time::Duration::from_millis(read_timeout_ms),
+            ..HttpConfig::default()
+        };
+        Ok(())
+    }
+
+    /// Network settings for the current HTTP source: the player's timeouts plus this load's headers
+    fn source_http_config(&self) -> HttpConfig {
+        HttpConfig {
+            headers: self.source_headers.clone(),
+            ..self.http_config.clone()
+        }
+    }
+
+    /// Load a URL, sending `headers` (e.g. a per-episode Authorization) with the probing HEAD
+    /// and every ranged GET. The headers apply to this load only; other loads and players are unaffected.
+    fn load_url_with_headers(&mut self, url: &str, headers: &[(&str, &str)]) -> Result<()> {
+        // file:// URLs (as handed out by file pickers) and plain paths play from disk
+        if let Some(path) = podium_playlist::local_path(url) {
+            return self.load_file(&path);
+        }
+        let load = self.prepare_url_load(url, headers)?;
+        load.probe()?;
+        self.start_url_load(load)
+    }
+
+    /// Check a URL load against the player's settings; the current playback is left alone
+    fn prepare_url_load(&self, url: &str, headers: &[(&str, &str)]) -> Result<UrlLoad> {
+        log::info!("load_url called ({} extra headers)", headers.len());
+        podium_transport_http::validate_url(url)?;
+        let headers: Vec<(String, String)> = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Ok(UrlLoad {
+            url: url.to_string(),
+            http_config: HttpConfig {
+                headers: headers.clone(),
+                ..self.http_config.clone()
+            },
+            headers,
+        })
+    }
+
+    /// Replace the current source with a probed URL load
+    fn start_url_load(&mut self, load: UrlLoad) -> Result<()> {
+        if load.headers != self.source_headers {
+            // A running engine for the same URL was opened with other credentials
+            if let Some(mut eng) = self.engine.take() {
+                eng.stop();
+            }
+            self.source_headers = load.headers;
+        }
+        self.state.set_state(PlayerState::Loading);
+        self.state.update_status(|status| {
+            status.position_ms = 0;
+            status.duration_ms = 0;
+            status.duration_known = false;
+            status.buffering = true;
+        });
+        self.loaded = true;
+        self.buffer_source = false;
+        self.start_engine(SourceKind::Http(load.url), 0)?;
+        self.state.set_state(PlayerState::Ready);
+        Ok(())
+    }
+
+    /// Parse an .m3u/.m3u8/.pls file and queue its entries (see `load_next_entry`).
+    /// Returns the number of entries in the playlist.
+    fn queue_playlist(&mut self, path: &str) -> Result<usize> {
+        let entries = podium_playlist::load_playlist(path)?;
+        let count = entries.len();
+        self.queue = entries.into();
+        if count == 0 {
+            return Err(AudioError::LoadError(format!("Playlist is empty: {}", path)));
+        }
+        Ok(count)
+    }
+
+    /// Take the next queued entry, if any
+    fn pop_queue(&mut self) -> Option<PlaylistEntry> {
+        let entry = self.queue.pop_front()?;
+        log::info!("[queue] next {:?} ({} remaining)", entry.title, self.queue.len());
+        Some(entry)
+    }
+
+    /// Switch the current source to another audio track (see `Demuxer::list_tracks`)
+    fn set_audio_track(&mut self, track_id: u32) -> Result<()> {
+        self.ensure_loaded()?;
+        let engine = self
+            .engine
+            .as_mut()
+            .ok_or_else(|| AudioError::InvalidState("No playback engine".to_string()))?;
+        log::info!("set_audio_track -> {}", track_id);
+        engine.select_track(track_id);
+        Ok(())
+    }
+
+    /// Where the current source's bytes come from (None when nothing is loaded)
+    fn current_source_kind(&self) -> Option<SourceKindInfo> {
+        if !self.loaded {
+            return None;
+        }
+        let kind = match self.last_source.as_ref() {
+            _ if self.buffer_source => SourceOrigin::Buffer,
+            Some(SourceKind::File(_)) => SourceOrigin::File,
+            Some(SourceKind::Http(_)) => SourceOrigin::Http,
+            None => return None,
+        };
+        // There is no disk cache yet: HTTP bytes always come from the network
+        Some(SourceKindInfo { kind, cached: false })
+    }
+
+    /// Play headerless interleaved PCM in the given format, bypassing the demuxer and decoder
+    fn load_pcm(&mut self, data: &[u8], sample_rate: u32, channels: u16, format: PcmFormat) -> Result<()> {
+        log::info!(
+            "load_pcm called ({} bytes, {} Hz, {} ch, {:?})",
+            data.len(),
+            sample_rate,
+            channels,
+            format
+        );
+        if sample_rate == 0 || channels == 0 {
+            return Err(AudioError::LoadError(format!(
+                "Invalid PCM format: {} Hz, {} channels",
+                sample_rate, channels
+            )));
+        }
+        let frame_bytes = format.bytes_per_sample() * channels as usize;
+        if !data.len().is_multiple_of(frame_bytes) {
+            return Err(AudioError::LoadError(format!(
+                "PCM data length {} is not a whole number of {}-byte frames",
+                data.len(),
+                frame_bytes
+            )));
+        }
+        let samples = pcm_bytes_to_f32(data, format)?;
+        let info = raw_track_info(samples.len(), sample_rate, channels, format);
+
+        self.state.set_state(PlayerState::Loading);
+        self.state.update_status(|status| {
+            status.position_ms = 0;
+            status.duration_ms = info.duration_ms;
+            status.duration_known = true;
+            status.buffering = false;
+        });
+        self.loaded = true;
+        self.buffer_source = true;
+        // There is nothing to reopen, so a raw engine is never reused by `start_engine`
+        self.last_source = None;
+        if let Some(mut eng) = self.engine.take() {
+            eng.stop();
         }
+        log::info!("[engine] start raw pcm");
+        self.spawn_engine(EngineSource::Pcm { samples, info }, 0)?;
+        self.state.set_state(PlayerState::Ready);
+        Ok(())
+    }
+
+    /// Format of the track being decoded
+    fn track_info(&self) -> Result<TrackInfo> {
+        self.ensure_loaded()?;
+        self.engine
+            .as_ref()
+            .and_then(|engine| engine.track_info.lock().clone())
+            .ok_or_else(|| AudioError::InvalidState("Track info not available yet".to_string()))
     }
 
     fn ensure_loaded(&self) -> Result<()> {
@@ -74,8 +332,23 @@ impl PodiumPlayer {
         };
         log::info!("[engine] start {}", desc);
         self.last_source = Some(source.clone());
-        let mut engine = PlaybackEngine::new(source, self.state.clone())?;
-        engine.seek_to(start_position_ms)?;
+        self.spawn_engine(EngineSource::Media(source), start_position_ms)
+    }
+
+    fn spawn_engine(&mut self, source: EngineSource, start_position_ms: u64) -> Result<()> {
+        let mut engine = PlaybackEngine::new(
+            source,
+            self.state.clone(),
+            self.channel_mode.clone(),
+            self.buffer_config,
+            self.buffer_seconds.clone(),
+            self.source_http_config(),
+            self.callback.clone(),
+        )?;
+        // A new engine already starts at 0
+        if start_position_ms > 0 {
+            engine.seek_to(start_position_ms)?;
+        }
         self.engine = Some(engine);
         Ok(())
     }
@@ -88,26 +361,18 @@ impl AudioPlayer for PodiumPlayer {
         self.state.update_status(|status| {
             status.position_ms = 0;
             status.duration_ms = 0;
+            status.duration_known = false;
             status.buffering = false;
         });
         self.loaded = true;
+        self.buffer_source = false;
         self.start_engine(SourceKind::File(_path.to_string()), 0)?;
         self.state.set_state(PlayerState::Ready);
         Ok(())
     }
 
-    fn load_url(&mut self, _url: &str) -> Result<()> {
-        log::info!("load_url called");
-        self.state.set_state(PlayerState::Loading);
-        self.state.update_status(|status| {
-            status.position_ms = 0;
-            status.duration_ms = 0;
-            status.buffering = true;
-        });
-        self.loaded = true;
-        self.start_engine(SourceKind::Http(_url.to_string()), 0)?;
-        self.state.set_state(PlayerState::Ready);
-        Ok(())
+    fn load_url(&mut self, url: &str) -> Result<()> {
+        self.load_url_with_headers(url, &[])
     }
 
     fn load_buffer(&mut self, _buffer: &[u8]) -> Result<()> {
@@ -116,9 +381,11 @@ impl AudioPlayer for PodiumPlayer {
         self.state.update_status(|status| {
             status.position_ms = 0;
             status.duration_ms = 0;
+            status.duration_known = false;
             status.buffering = false;
         });
         self.loaded = true;
+        self.buffer_source = true;
         // For buffer mode, write to temp file and play as file
         let tmp_path = std::env::temp_dir().join("podium_audio_buffer.tmp");
         std::fs::write(&tmp_path, _buffer)
@@ -166,20 +433,25 @@ impl AudioPlayer for PodiumPlayer {
     fn seek(&mut self, position_ms: u64) -> Result<()> {
         log::info!("seek called -> {} ms", position_ms);
         self.ensure_loaded()?;
-        if let Some(src) = self.last_source.clone() {
-            self.start_engine(src, position_ms)?;
-            self.state.update_status(|status| {
-                status.position_ms = position_ms;
-            });
-            self.state.set_state(PlayerState::Ready);
-        } else {
-            log::warn!("seek requested but no source cached");
+        match (self.last_source.clone(), &mut self.engine) {
+            (Some(src), _) => self.start_engine(src, position_ms)?,
+            // Raw PCM has no source to reopen; seek the running engine instead
+            (None, Some(engine)) => engine.seek_to(position_ms)?,
+            (None, None) => {
+                log::warn!("seek requested but no source cached");
+                return Ok(());
+            }
         }
+        self.state.update_status(|status| {
+            status.position_ms = position_ms;
+        });
+        self.state.set_state(PlayerState::Ready);
         Ok(())
     }
 
     fn set_volume(&mut self, volume: f32) -> Result<()> {
         log::info!("set_volume called -> {}", volume);
+        self.ensure_loaded()?;
         if !(0.0..=1.0).contains(&volume) {
             return Err(podium_core::AudioError::InvalidState(format!(
                 "Volume out of range: {}",
@@ -192,11 +464,8 @@ impl AudioPlayer for PodiumPlayer {
 
     fn set_playback_rate(&mut self, rate: f32) -> Result<()> {
         log::info!("set_playback_rate called -> {}", rate);
-        if rate <= 0.0 {
-            return Err(podium_core::AudioError::InvalidState(
-                "Playback rate must be > 0".to_string(),
-            ));
-        }
+        self.ensure_loaded()?;
+        let rate = podium_core::clamp_playback_rate(rate)?;
         self.state.update_status(|status| status.playback_rate = rate);
         Ok(())
     }
@@ -212,13 +481,41 @@ impl AudioPlayer for PodiumPlayer {
             let dur = engine.duration_ms.load(Ordering::SeqCst);
             if dur > 0 {
                 status.duration_ms = dur;
+                status.duration_known = true;
             }
         }
         status
     }
 
+    fn output_sample_rate(&self) -> u32 {
+        self.engine
+            .as_ref()
+            .map(|engine| engine.output_format.sample_rate.load(Ordering::SeqCst))
+            .unwrap_or(0)
+    }
+
+    fn output_channels(&self) -> u16 {
+        self.engine
+            .as_ref()
+            .map(|engine| engine.output_format.channels.load(Ordering::SeqCst))
+            .unwrap_or(0)
+    }
+
+    fn is_seekable(&self) -> bool {
+        self.engine
+            .as_ref()
+            .is_some_and(|engine| engine.seekable.load(Ordering::SeqCst))
+    }
+
+    fn get_stats(&self) -> PlaybackStats {
+        self.engine
+            .as_ref()
+            .map(|engine| engine.stats.snapshot(engine.ring.fullness()))
+            .unwrap_or_default()
+    }
+
     fn set_callback(&mut self, callback: Option<Arc<dyn PlayerCallback>>) {
-        self.callback = callback;
+        *self.callback.lock() = callback;
     }
 
     fn release(&mut self) -> Result<()> {
@@ -288,16 +585,322 @@ fn to_code(result: Result<()>) -> i32 {
     }
 }
 
+/// Load a URL into a registered player without holding the registry lock during the
+/// reachability probe, which can take up to the connect timeout while every other call
+/// (on any player) waits for the lock. The URL is validated before anything is stopped.
+fn load_url_unlocked(id: i64, url: &str, headers: &[(&str, &str)]) -> Result<()> {
+    if let Some(path) = podium_playlist::local_path(url) {
+        return with_player_mut(id, |p| p.load_file(&path));
+    }
+    let load = with_player(id, |p| p.prepare_url_load(url, headers))?;
+    load.probe()?;
+    with_player_mut(id, |p| p.start_url_load(load))
+}
+
+/// Load the next queued entry of a registered player (URLs through `load_url_unlocked`).
+/// Returns false when the queue is empty.
+fn load_next_entry(id: i64) -> Result<bool> {
+    let Some(entry) = with_player_mut(id, |p| Ok(p.pop_queue()))? else {
+        return Ok(false);
+    };
+    match entry.source {
+        SourceKind::File(path) => with_player_mut(id, |p| p.load_file(&path))?,
+        SourceKind::Http(url) => load_url_unlocked(id, &url, &[])?,
+    }
+    Ok(true)
+}
+
+/// Like `to_code`, but a failure returns the error's own code (`AudioError::code`)
+fn to_error_code(result: Result<()>) -> i32 {
+    match result {
+        Ok(_) => 0,
+        Err(err) => {
+            log::error!("FFI error: {}", err);
+            err.code()
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 // Playback engine
 // -----------------------------------------------------------------------------
 
-#[derive(Clone, PartialEq)]
-enum SourceKind {
-    Http(String),
-    File(String),
+/// Origin of the playing source's bytes (C ABI codes: 0=File, 1=Http, 2=Buffer)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceOrigin {
+    File = 0,
+    Http = 1,
+    Buffer = 2,
+}
+
+/// Current source description for data-usage-aware UIs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SourceKindInfo {
+    kind: SourceOrigin,
+    /// Bytes are served from a local cache rather than the network
+    cached: bool,
+}
+
+/// Recoverable read/decode errors tolerated in a row before the track is abandoned
+const MAX_CONSECUTIVE_ERRORS: u32 = 8;
+
+/// Outcome of one decode loop iteration
+enum DecodeStep {
+    /// Interleaved PCM in the source layout
+    Audio(Vec<f32>),
+    /// A recoverable error was logged and skipped
+    Skipped,
+    EndOfStream,
+    Fatal(AudioError),
+}
+
+/// What the engine plays
+enum EngineSource {
+    /// A file or stream, demuxed and decoded
+    Media(SourceKind),
+    /// Headerless PCM supplied by the app, already converted to interleaved f32
+    Pcm { samples: Vec<f32>, info: TrackInfo },
+}
+
+/// Frames handed to the loop per step for raw PCM, about one decoded packet's worth
+const RAW_PCM_CHUNK_FRAMES: usize = 1024;
+
+/// Track info for raw PCM, described like Symphonia describes the same PCM in a WAV
+fn raw_track_info(samples: usize, sample_rate: u32, channels: u16, format: PcmFormat) -> TrackInfo {
+    let frames = (samples / channels.max(1) as usize) as u64;
+    TrackInfo {
+        sample_rate,
+        channels,
+        duration_ms: frames * 1000 / sample_rate.max(1) as u64,
+        codec: format.codec_name().to_string(),
+        bitrate_bps: Some(format.bytes_per_sample() as u32 * 8 * sample_rate * channels as u32),
+        is_vbr: false,
+    }
+}
+
+/// PCM producer behind the decode loop
+enum PcmProducer {
+    Decoded {
+        demuxer: Demuxer,
+        decoder: AudioDecoder,
+    },
+    Raw {
+        samples: Vec<f32>,
+        sample_rate: u32,
+        channels: u16,
+        /// Next sample to hand out
+        cursor: usize,
+    },
+}
+
+impl PcmProducer {
+    /// Raw PCM is always seekable; decoded media depends on its source and time base
+    fn is_seekable(&self) -> bool {
+        match self {
+            PcmProducer::Decoded { demuxer, .. } => demuxer.is_seekable(),
+            PcmProducer::Raw { .. } => true,
+        }
+    }
+
+    fn seek(&mut self, position_ms: u64) {
+        match self {
+            PcmProducer::Decoded { demuxer, decoder } => {
+                let _ = demuxer.seek(position_ms);
+                // The first packet after a jump must not overlap with audio from before it
+                decoder.reset();
+            }
+            PcmProducer::Raw {
+                samples,
+                sample_rate,
+                channels,
+                cursor,
+            } => {
+                let frame = position_ms * *sample_rate as u64 / 1000;
+                *cursor = (frame as usize * *channels as usize).min(samples.len());
+            }
+        }
+    }
+
+    /// Switch to another track, returning its format
+    fn select_track(&mut self, track_id: u32) -> Result<TrackInfo> {
+        match self {
+            PcmProducer::Decoded { demuxer, decoder } => {
+                decoder.select_track(demuxer, track_id)?;
+                demuxer.get_track_info()
+            }
+            PcmProducer::Raw { .. } => Err(AudioError::InvalidState(
+                "Raw PCM has a single track".to_string(),
+            )),
+        }
+    }
+
+    fn next(&mut self, stats: &StatsCounters, channels: u16, consecutive_errors: &mut u32) -> DecodeStep {
+        match self {
+            PcmProducer::Decoded { demuxer, decoder } => {
+                PlaybackEngine::next_decoded(demuxer, decoder, stats, channels, consecutive_errors)
+            }
+            PcmProducer::Raw {
+                samples,
+                channels,
+                cursor,
+                ..
+            } => {
+                if *cursor >= samples.len() {
+                    return DecodeStep::EndOfStream;
+                }
+                let end = (*cursor + RAW_PCM_CHUNK_FRAMES * *channels as usize).min(samples.len());
+                let chunk = samples[*cursor..end].to_vec();
+                *cursor = end;
+                DecodeStep::Audio(chunk)
+            }
+        }
+    }
+}
+
+/// Decoded audio on its way into the engine ring. What the ring has no room for waits here
+/// instead of being dropped, and what has been written is counted so the position being heard
+/// can be worked out.
+struct RingFeed {
+    pending: Vec<f32>,
+    channels: usize,
+    sample_rate: u32,
+    /// Where the producer was last positioned
+    anchor_ms: u64,
+    /// Samples written to the ring since then
+    written: usize,
+}
+
+impl RingFeed {
+    fn new(channels: usize, sample_rate: u32) -> Self {
+        Self {
+            pending: Vec::new(),
+            channels: channels.max(1),
+            sample_rate: sample_rate.max(1),
+            anchor_ms: 0,
+            written: 0,
+        }
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Queue a decoded packet, in the ring's layout
+    fn push(&mut self, pcm: Vec<f32>) {
+        self.pending = pcm;
+    }
+
+    /// Write as much pending audio as the ring takes; returns the whole frames written
+    fn write(&mut self, ring: &SharedRingBuffer) -> usize {
+        let before = self.written / self.channels;
+        let written = ring.write(&self.pending);
+        self.pending.drain(..written);
+        self.written += written;
+        self.written / self.channels - before
+    }
+
+    /// The producer was repositioned to `position_ms` and the ring cleared
+    fn restart(&mut self, position_ms: u64) {
+        self.pending.clear();
+        self.anchor_ms = position_ms;
+        self.written = 0;
+    }
+
+    /// Position being heard: what was written since the anchor, less what is still buffered
+    fn played_ms(&self, ring: &SharedRingBuffer) -> u64 {
+        let played = self.written.saturating_sub(ring.available_read()) / self.channels;
+        self.anchor_ms + played as u64 * 1000 / self.sample_rate as u64
+    }
+}
+
+/// Output stream format chosen by the decode loop (0 until the stream is built)
+#[derive(Default)]
+struct OutputFormat {
+    sample_rate: AtomicU32,
+    channels: AtomicU16,
+}
+
+/// Smallest lookahead cap accepted; below this a single decoded packet may not fit the ring
+const MIN_LOOKAHEAD_MS: u64 = 100;
+
+/// Seconds of decoded audio the engine ring holds by default, per source kind.
+/// Local files refill quickly, so a short ring keeps memory and seek-clear cost low;
+/// network streams get a longer ring to ride out stalls.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BufferConfig {
+    file_seconds: f32,
+    http_seconds: f32,
+    /// Hard cap on decoded-ahead audio in ms (0 = none). The ring is the only place decoded
+    /// audio waits, so capping its length bounds both memory and the paused prebuffer.
+    max_lookahead_ms: u64,
+}
+
+impl Default for BufferConfig {
+    fn default() -> Self {
+        Self {
+            file_seconds: 2.0,
+            http_seconds: 8.0,
+            max_lookahead_ms: 0,
+        }
+    }
+}
+
+impl BufferConfig {
+    fn seconds_for(&self, source: &EngineSource) -> f32 {
+        match source {
+            EngineSource::Media(SourceKind::File(_)) | EngineSource::Pcm { .. } => self.file_seconds,
+            EngineSource::Media(SourceKind::Http(_)) => self.http_seconds,
+        }
+    }
+
+    /// Ring length after applying `max_lookahead_ms`
+    fn capped_seconds(&self, seconds: f32) -> f32 {
+        if self.max_lookahead_ms == 0 {
+            seconds
+        } else {
+            seconds.min(self.max_lookahead_ms as f32 / 1000.0)
+        }
+    }
+
+    fn samples_for(seconds: f32, sample_rate: u32, channels: usize) -> usize {
+        let frames = (sample_rate.max(1) as f32 * seconds).ceil() as usize;
+        (frames * channels.max(1)).max(1)
+    }
+}
+
+/// Length the decode loop keeps its ring at: the app's live override when set, else the
+/// source kind's default, always within the lookahead cap
+struct RingLength {
+    config: BufferConfig,
+    default_seconds: f32,
+    /// `PodiumPlayer::buffer_seconds`
+    override_seconds: Arc<AtomicU32>,
+}
+
+impl RingLength {
+    fn new(config: BufferConfig, source: &EngineSource, override_seconds: Arc<AtomicU32>) -> Self {
+        Self {
+            config,
+            default_seconds: config.seconds_for(source),
+            override_seconds,
+        }
+    }
+
+    /// Ring capacity in samples at the given format
+    fn samples(&self, sample_rate: u32, channels: usize) -> usize {
+        let override_seconds = f32::from_bits(self.override_seconds.load(Ordering::SeqCst));
+        let seconds = if override_seconds > 0.0 {
+            override_seconds
+        } else {
+            self.default_seconds
+        };
+        BufferConfig::samples_for(self.config.capped_seconds(seconds), sample_rate, channels)
+    }
 }
 
+/// `PlaybackEngine::seek_request` value when no seek is pending
+const NO_SEEK_REQUEST: u64 = u64::MAX;
+
 struct PlaybackEngine {
     ring: SharedRingBuffer,
     position_ms: Arc<AtomicU64>,
@@ -305,18 +908,40 @@ struct PlaybackEngine {
     playing: Arc<AtomicBool>,
     stop_flag: Arc<AtomicBool>,
     seek_request: Arc<AtomicU64>,
+    /// Track id to switch to, or -1 when no switch is pending
+    track_request: Arc<AtomicI64>,
+    output_format: Arc<OutputFormat>,
+    /// Format of the current track, set once the decode loop has probed the source
+    track_info: Arc<Mutex<Option<TrackInfo>>>,
+    /// Whether the opened source can seek; false until the decode loop has probed it
+    seekable: Arc<AtomicBool>,
+    stats: Arc<StatsCounters>,
     _render_thread: Option<thread::JoinHandle<()>>,
 }
 
 impl PlaybackEngine {
-    fn new(source: SourceKind, state: PlayerStateContainer) -> Result<Self> {
-        // Start with ~5s buffer for stereo f32 at 48k
-        let ring = SharedRingBuffer::new(48000 * 2 * 5);
+    fn new(
+        source: EngineSource,
+        state: PlayerStateContainer,
+        channel_mode: Arc<AtomicI32>,
+        buffer_config: BufferConfig,
+        buffer_seconds: Arc<AtomicU32>,
+        http_config: HttpConfig,
+        callback: Arc<Mutex<Option<Arc<dyn PlayerCallback>>>>,
+    ) -> Result<Self> {
+        // Start sized for stereo f32 at 48k; the decode loop resizes once the formats are known
+        let ring_length = RingLength::new(buffer_config, &source, buffer_seconds);
+        let ring = SharedRingBuffer::new(ring_length.samples(48000, 2));
         let position_ms = Arc::new(AtomicU64::new(0));
         let duration_ms = Arc::new(AtomicU64::new(0));
         let playing = Arc::new(AtomicBool::new(false));
         let stop_flag = Arc::new(AtomicBool::new(false));
-        let seek_request = Arc::new(AtomicU64::new(0));
+        let seek_request = Arc::new(AtomicU64::new(NO_SEEK_REQUEST));
+        let track_request = Arc::new(AtomicI64::new(-1));
+        let output_format = Arc::new(OutputFormat::default());
+        let track_info = Arc::new(Mutex::new(None));
+        let seekable = Arc::new(AtomicBool::new(false));
+        let stats = Arc::new(StatsCounters::new());
 
         // Decoder thread
         let ring_clone = ring.clone();
@@ -325,21 +950,49 @@ impl PlaybackEngine {
         let play_flag = playing.clone();
         let stop = stop_flag.clone();
         let seek = seek_request.clone();
-
-        let handle = thread::spawn(move || {
-            if let Err(e) = Self::decode_loop(
-                source,
-                ring_clone,
-                pos_clone,
-                dur_clone,
-                play_flag,
-                stop,
-                seek,
-                state,
-            ) {
-                log::error!("decode loop error: {}", e);
+        let track = track_request.clone();
+        let format = output_format.clone();
+        let info = track_info.clone();
+        let can_seek = seekable.clone();
+        let engine_stats = stats.clone();
+
+        let panic_state = state.clone();
+        let handle = spawn_named(RENDER_THREAD, move || {
+            let result = catch_panic(move || {
+                Self::decode_loop(
+                    source,
+                    ring_clone,
+                    pos_clone,
+                    dur_clone,
+                    play_flag,
+                    stop,
+                    seek,
+                    track,
+                    format,
+                    info,
+                    can_seek,
+                    engine_stats,
+                    channel_mode,
+                    ring_length,
+                    http_config,
+                    state,
+                )
+            });
+            // A failed open (e.g. network timeout), a fatal read error or a panic:
+            // leave the player in a state the app can observe instead of a silent dead engine
+            let error = match result {
+                Ok(Ok(())) => return,
+                Ok(Err(e)) | Err(e) => e,
+            };
+            log::error!("decode loop error: {}", error);
+            panic_state.set_state(PlayerState::Error);
+            if let Some(callback) = callback.lock().as_ref() {
+                callback.on_event(CallbackEvent::Error {
+                    message: error.to_string(),
+                    position_ms: error.position_ms(),
+                });
             }
-        });
+        })?;
 
         Ok(Self {
             ring,
@@ -348,6 +1001,11 @@ impl PlaybackEngine {
             playing,
             stop_flag,
             seek_request,
+            track_request,
+            output_format,
+            track_info,
+            seekable,
+            stats,
             _render_thread: Some(handle),
         })
     }
@@ -369,62 +1027,195 @@ impl PlaybackEngine {
     }
 
     fn seek_to(&mut self, position_ms: u64) -> Result<()> {
-        self.ring.clear();
+        // Publish the target before clearing, so the decode loop finds it once it sees the reset
         self.position_ms.store(position_ms, Ordering::SeqCst);
         self.seek_request.store(position_ms, Ordering::SeqCst);
+        self.ring.clear_and_sync();
         Ok(())
     }
 
-    fn decode_loop(
-        source: SourceKind,
-        ring: SharedRingBuffer,
-        pos_ms: Arc<AtomicU64>,
-        dur_ms: Arc<AtomicU64>,
-        playing: Arc<AtomicBool>,
-        stop_flag: Arc<AtomicBool>,
-        seek_request: Arc<AtomicU64>,
-        state: PlayerStateContainer,
-    ) -> Result<()> {
-        // Build MediaSource
-        let hint_path = match &source {
-            SourceKind::File(p) => Some(p.clone()),
-            SourceKind::Http(_) => None,
+    fn select_track(&mut self, track_id: u32) {
+        self.track_request.store(track_id as i64, Ordering::SeqCst);
+    }
+
+    /// Apply a live ring length change without dropping buffered audio: growing happens at
+    /// once, shrinking waits until playback has drained the ring below the new length.
+    /// Returns false while a shrink is waiting.
+    fn fit_ring(ring: &SharedRingBuffer, desired: usize) -> bool {
+        if desired == ring.size() {
+            return true;
+        }
+        let resized = ring.resize_keeping(desired);
+        if resized {
+            log::info!("[engine] ring resized to {} samples", desired);
+        }
+        resized
+    }
+
+    /// Output callback body: copy from the ring, padding (and counting) underflows with silence
+    fn fill_output(
+        ring: &SharedRingBuffer,
+        playing: &AtomicBool,
+        stats: &StatsCounters,
+        data: &mut [f32],
+    ) {
+        if !playing.load(Ordering::SeqCst) {
+            data.fill(0.0);
+            return;
+        }
+        let read = ring.read(data);
+        if read < data.len() {
+            data[read..].fill(0.0);
+            let underflows = stats.record_underflow();
+            if underflows.is_multiple_of(10) {
+                log::warn!("[engine] audio underflow count={}", underflows);
+            }
+        }
+    }
+
+    /// Read and decode the next packet. Recoverable read/decode errors are skipped
+    /// until `MAX_CONSECUTIVE_ERRORS` happen in a row, then treated as fatal.
+    fn next_decoded(
+        demuxer: &mut Demuxer,
+        decoder: &mut AudioDecoder,
+        stats: &StatsCounters,
+        channels: u16,
+        consecutive_errors: &mut u32,
+    ) -> DecodeStep {
+        let packet = match demuxer.read_packet() {
+            PacketRead::Packet(packet) => packet,
+            PacketRead::EndOfStream => return DecodeStep::EndOfStream,
+            PacketRead::Fatal(e) => return DecodeStep::Fatal(e),
+            PacketRead::Recoverable(e) => return Self::skip_error(e, consecutive_errors),
+        };
+
+        let decode_started = std::time::Instant::now();
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                *consecutive_errors = 0;
+                stats.record_decode(
+                    (decoded.len() / channels.max(1) as usize) as u64,
+                    decode_started.elapsed(),
+                );
+                DecodeStep::Audio(decoded)
+            }
+            Err(e) => Self::skip_error(e.at_position(demuxer.position_ms()), consecutive_errors),
+        }
+    }
+
+    fn skip_error(error: AudioError, consecutive_errors: &mut u32) -> DecodeStep {
+        *consecutive_errors += 1;
+        if *consecutive_errors > MAX_CONSECUTIVE_ERRORS {
+            let give_up =
+                |reason| format!("giving up after {} consecutive errors: {}", MAX_CONSECUTIVE_ERRORS, reason);
+            return DecodeStep::Fatal(match error {
+                AudioError::DecodingErrorAt { message, position_ms } => AudioError::DecodingErrorAt {
+                    message: give_up(message),
+                    position_ms,
+                },
+                error => AudioError::DecodingError(give_up(error.to_string())),
+            });
+        }
+        log::warn!(
+            "[engine] skipping recoverable error ({}/{}): {}",
+            consecutive_errors,
+            MAX_CONSECUTIVE_ERRORS,
+            error
+        );
+        DecodeStep::Skipped
+    }
+
+    /// Mix decoded PCM to the requested channel mode, then to the device layout
+    fn apply_channel_mode(
+        pcm: Vec<f32>,
+        source_channels: u16,
+        device_channels: u16,
+        sample_rate: u32,
+        mode: ChannelMode,
+    ) -> Vec<f32> {
+        let mixed_channels = mode.target_channels(source_channels);
+        let mixed = if mixed_channels != source_channels {
+            Resampler::new(sample_rate, sample_rate, source_channels, mixed_channels)
+                .convert_channels(&pcm)
+        } else {
+            pcm
         };
 
-        let media_source: Box<dyn symphonia::core::io::MediaSource> = match source {
+        if mixed_channels != device_channels {
+            Resampler::new(sample_rate, sample_rate, mixed_channels, device_channels)
+                .convert_channels(&mixed)
+        } else {
+            mixed
+        }
+    }
+
+    /// Open a file or HTTP source and probe it far enough to know the track format
+    fn open_media(
+        source: SourceKind,
+        http_config: HttpConfig,
+        stats: &Arc<StatsCounters>,
+    ) -> Result<(PcmProducer, TrackInfo, Vec<f32>)> {
+        let (media_source, hint): (Box<dyn symphonia::core::io::MediaSource>, _) = match source {
             SourceKind::File(path) => {
                 let file = File::open(&path)
                     .map_err(|e| AudioError::IoError(format!("open file {}: {}", path, e)))?;
-                Box::new(file)
+                (Box::new(file), Demuxer::create_hint_from_path(&path))
             }
             SourceKind::Http(url) => {
                 log::info!("[engine] using HttpRangeSource url={}", url);
-                let ns = NetworkSource::from_http_range(url)?;
-                Box::new(ns)
+                let ns = NetworkSource::from_http_range_with_config(url.clone(), http_config, stats.clone())?;
+                let hint = Demuxer::create_hint_for_stream(&url, ns.content_type());
+                (Box::new(ns), hint)
             }
         };
 
-        let hint = if let Some(p) = hint_path {
-            podium_demux::Demuxer::create_hint_from_path(&p)
-        } else {
-            podium_demux::Demuxer::create_hint_from_path("stream.mp3")
-        };
-
         let mut demuxer = Demuxer::from_media_source(media_source, hint)?;
-        let track_info = demuxer.get_track_info()?;
+        // Some streams (e.g. ADTS) only reveal their sample rate once a frame is decoded
+        let (decoder, track_info, probe_pcm) = AudioDecoder::open(&mut demuxer)?;
+        Ok((PcmProducer::Decoded { demuxer, decoder }, track_info, probe_pcm))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn decode_loop(
+        source: EngineSource,
+        ring: SharedRingBuffer,
+        pos_ms: Arc<AtomicU64>,
+        dur_ms: Arc<AtomicU64>,
+        playing: Arc<AtomicBool>,
+        stop_flag: Arc<AtomicBool>,
+        seek_request: Arc<AtomicU64>,
+        track_request: Arc<AtomicI64>,
+        output_format: Arc<OutputFormat>,
+        shared_track_info: Arc<Mutex<Option<TrackInfo>>>,
+        seekable: Arc<AtomicBool>,
+        stats: Arc<StatsCounters>,
+        channel_mode: Arc<AtomicI32>,
+        ring_length: RingLength,
+        http_config: HttpConfig,
+        state: PlayerStateContainer,
+    ) -> Result<()> {
+
+        let (mut producer, mut track_info, probe_pcm) = match source {
+            EngineSource::Media(kind) => Self::open_media(kind, http_config, &stats)?,
+            EngineSource::Pcm { samples, info } => {
+                let producer = PcmProducer::Raw {
+                    samples,
+                    sample_rate: info.sample_rate,
+                    channels: info.channels,
+                    cursor: 0,
+                };
+                (producer, info, Vec::new())
+            }
+        };
         dur_ms.store(track_info.duration_ms, Ordering::SeqCst);
+        *shared_track_info.lock() = Some(track_info.clone());
+        seekable.store(producer.is_seekable(), Ordering::SeqCst);
         log::info!(
             "[engine] track sample_rate={} channels={} duration_ms={}",
             track_info.sample_rate,
             track_info.channels,
             track_info.duration_ms
         );
-        // Resize ring to ~5s of audio
-        let desired_channels = track_info.channels.max(1) as usize;
-        let desired_sr = track_info.sample_rate.max(1);
-        ring.resize((desired_sr as usize) * desired_channels * 5);
-
-        let mut decoder = AudioDecoder::from_demuxer(&demuxer)?;
 
         // Setup renderer (cpal)
         let host = cpal::default_host();
@@ -437,32 +1228,22 @@ impl PlaybackEngine {
 
         let sample_rate = config.sample_rate().0;
         let channels = config.channels() as usize;
-        let mut out_channels = track_info.channels as usize;
-        if out_channels == 1 && channels >= 2 {
-            out_channels = 2; // upmix mono to stereo
-        }
+        // The ring always holds audio in the device layout so the callback can copy it straight out
+        let out_channels = channels.max(1);
+
+        // Size the ring for the source kind at the device format
+        ring.resize(ring_length.samples(sample_rate, out_channels));
 
         let err_fn = |err| log::error!("[engine] output stream error: {}", err);
         let ring_for_cb = ring.clone();
         let play_flag_for_cb = playing.clone();
-        let mut last_underflow = 0;
+        let stats_for_cb = stats.clone();
         let stream = match config.sample_format() {
             cpal::SampleFormat::F32 => device
                 .build_output_stream(
                     &config.config(),
                     move |data: &mut [f32], _| {
-                        if !play_flag_for_cb.load(Ordering::SeqCst) {
-                            data.fill(0.0);
-                            return;
-                        }
-                        let read = ring_for_cb.read(data);
-                        if read < data.len() {
-                            data[read..].fill(0.0);
-                            last_underflow += 1;
-                            if last_underflow % 10 == 0 {
-                                log::warn!("[engine] audio underflow count={}", last_underflow);
-                            }
-                        }
+                        Self::fill_output(&ring_for_cb, &play_flag_for_cb, &stats_for_cb, data);
                     },
                     err_fn,
                     None,
@@ -477,68 +1258,129 @@ impl PlaybackEngine {
         stream
             .play()
             .map_err(|e| AudioError::PlaybackError(format!("stream play: {}", e)))?;
+        output_format.sample_rate.store(sample_rate, Ordering::SeqCst);
+        output_format.channels.store(channels as u16, Ordering::SeqCst);
 
         playing.store(false, Ordering::SeqCst); // start paused; play() will toggle
         state.set_state(PlayerState::Ready);
 
+        // Keep whatever was decoded while discovering the format
+        let mut feed = RingFeed::new(out_channels, sample_rate);
+        if !probe_pcm.is_empty() {
+            let mode = ChannelMode::from_i32(channel_mode.load(Ordering::SeqCst)).unwrap_or_default();
+            let pcm = Self::apply_channel_mode(
+                probe_pcm,
+                track_info.channels,
+                out_channels as u16,
+                sample_rate,
+                mode,
+            );
+            feed.push(pcm);
+            feed.write(&ring);
+        }
+
         // Decode loop
+        let mut consecutive_errors = 0;
+        let mut last_seek_ms = 0;
         loop {
             if stop_flag.load(Ordering::SeqCst) {
                 log::info!("[engine] stop requested");
                 break;
             }
 
+            let ring_waiting = !Self::fit_ring(&ring, ring_length.samples(sample_rate, out_channels));
+
             // If not playing, still allow prebuffering until ring is mostly full
             if !playing.load(Ordering::SeqCst) && ring.fullness() > 0.9 {
                 thread::sleep(std::time::Duration::from_millis(10));
                 continue;
             }
 
-            // Handle seek request
-            let target_ms = seek_request.swap(0, Ordering::SeqCst);
-            if target_ms > 0 {
-                let _ = demuxer.seek(target_ms);
-                pos_ms.store(target_ms, Ordering::SeqCst);
-                ring.clear();
-                log::info!("[engine] decoder seek to {} ms", target_ms);
-            }
-            match demuxer.next_packet() {
-                Ok(packet) => {
-                    let mut pcm = decoder.decode(&packet)?;
-                    // If needed, upmix mono to stereo
-                    if out_channels == 2 && track_info.channels == 1 {
-                        let mut stereo = Vec::with_capacity(pcm.len() * 2);
-                        for s in pcm.iter() {
-                            stereo.push(*s);
-                            stereo.push(*s);
-                        }
-                        pcm = stereo;
-                    }
-                    let written = ring.write(&pcm);
-                    if written < pcm.len() {
-                        log::debug!(
-                            "[engine] ring full, dropped {} samples",
-                            pcm.len() - written
+            // Handle track switch: rebuild the decoder and continue from the current position
+            let requested_track = track_request.swap(-1, Ordering::SeqCst);
+            if requested_track >= 0 {
+                match producer.select_track(requested_track as u32) {
+                    Ok(info) => {
+                        track_info = info;
+                        *shared_track_info.lock() = Some(track_info.clone());
+                        seekable.store(producer.is_seekable(), Ordering::SeqCst);
+                        // Pick up where the listener is, not where decoding had got to
+                        let resume_ms = feed.played_ms(&ring);
+                        producer.seek(resume_ms);
+                        ring.clear();
+                        feed.restart(resume_ms);
+                        pos_ms.store(resume_ms, Ordering::SeqCst);
+                        log::info!(
+                            "[engine] switched to track {} at {} ms",
+                            requested_track,
+                            resume_ms
                         );
                     }
-                    let frames = written / out_channels;
-                    if playing.load(Ordering::SeqCst) {
-                        let inc_ms = (frames as u64 * 1000) / sample_rate as u64;
-                        let new_pos = pos_ms.fetch_add(inc_ms, Ordering::SeqCst) + inc_ms;
-                        if frames > 0 && new_pos % 1000 == 0 {
-                            log::debug!(
-                                "[engine] progress pos_ms={} ring_fullness={:.2}",
-                                new_pos,
-                                ring.fullness()
-                            );
-                        }
+                    Err(e) => log::warn!("[engine] track switch failed: {}", e),
+                }
+            }
+
+            // Handle seek request. The ring refuses writes after seek_to's clear until we have
+            // repositioned; a reset without a new target means we took that target before the
+            // clear and may have written past it since.
+            let requested_ms = seek_request.swap(NO_SEEK_REQUEST, Ordering::SeqCst);
+            if requested_ms != NO_SEEK_REQUEST || ring.reset_pending() {
+                if requested_ms != NO_SEEK_REQUEST {
+                    last_seek_ms = requested_ms;
+                }
+                producer.seek(last_seek_ms);
+                pos_ms.store(last_seek_ms, Ordering::SeqCst);
+                ring.acknowledge_reset();
+                feed.restart(last_seek_ms);
+                log::info!("[engine] decoder seek to {} ms", last_seek_ms);
+            }
+
+            // A shrink waiting for playback to drain the ring: decoding more would only delay it
+            if ring_waiting {
+                thread::sleep(std::time::Duration::from_millis(10));
+                continue;
+            }
+            if !feed.has_pending() {
+                match producer.next(&stats, track_info.channels, &mut consecutive_errors) {
+                    DecodeStep::Audio(decoded) => {
+                        let mode = ChannelMode::from_i32(channel_mode.load(Ordering::SeqCst))
+                            .unwrap_or_default();
+                        feed.push(Self::apply_channel_mode(
+                            decoded,
+                            track_info.channels,
+                            out_channels as u16,
+                            sample_rate,
+                            mode,
+                        ));
+                    }
+                    DecodeStep::Skipped => continue,
+                    DecodeStep::EndOfStream => {
+                        log::info!("[engine] end of stream");
+                        break;
+                    }
+                    DecodeStep::Fatal(e) => {
+                        playing.store(false, Ordering::SeqCst);
+                        return Err(e);
                     }
                 }
-                Err(e) => {
-                    log::info!("[engine] demux end or error: {}", e);
-                    break;
+            }
+
+            let frames = feed.write(&ring);
+            if playing.load(Ordering::SeqCst) {
+                let inc_ms = (frames as u64 * 1000) / sample_rate as u64;
+                let new_pos = pos_ms.fetch_add(inc_ms, Ordering::SeqCst) + inc_ms;
+                if frames > 0 && new_pos % 1000 == 0 {
+                    log::debug!(
+                        "[engine] progress pos_ms={} ring_fullness={:.2}",
+                        new_pos,
+                        ring.fullness()
+                    );
                 }
             }
+            // Ring full: the rest of the packet waits for playback to make room
+            if feed.has_pending() {
+                thread::sleep(std::time::Duration::from_millis(5));
+            }
         }
 
         state.set_state(PlayerState::Stopped);
@@ -574,51 +1416,387 @@ pub extern "C" fn rust_audio_player_load_url(player_id: i64, url: *const std::os
     }
     let c_str = unsafe { std::ffi::CStr::from_ptr(url) };
     match c_str.to_str() {
-        Ok(url_str) => to_code(with_player_mut(player_id, |p| p.load_url(url_str))),
+        Ok(url_str) => to_code(load_url_unlocked(player_id, url_str, &[])),
+        Err(_) => -1,
+    }
+}
+
+/// Split newline-separated `Name: value` lines into header pairs (blank lines are skipped)
+fn parse_header_lines(text: &str) -> Result<Vec<(&str, &str)>> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.split_once(':') {
+            Some((name, value)) if !name.trim().is_empty() => Ok((name.trim(), value.trim())),
+            _ => Err(AudioError::LoadError(format!("Malformed header line: {}", line))),
+        })
+        .collect()
+}
+
+/// Load a URL sending extra headers, given as newline-separated `Name: value` lines,
+/// with every request for this load only (e.g. a per-episode Authorization)
+///
+/// # Safety
+/// `url` and `headers` must be valid NUL-terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn rust_audio_player_load_url_with_headers(
+    player_id: i64,
+    url: *const std::os::raw::c_char,
+    headers: *const std::os::raw::c_char,
+) -> i32 {
+    if url.is_null() || headers.is_null() {
+        return -1;
+    }
+    let (Ok(url), Ok(headers)) = (
+        std::ffi::CStr::from_ptr(url).to_str(),
+        std::ffi::CStr::from_ptr(headers).to_str(),
+    ) else {
+        return -1;
+    };
+    to_code(parse_header_lines(headers).and_then(|headers| load_url_unlocked(player_id, url, &headers)))
+}
+
+/// Load headerless interleaved little-endian PCM
+/// sample_format: 0=i16, 1=i32, 2=f32
+///
+/// # Safety
+/// `data` must point to `len` readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn rust_audio_player_load_pcm(
+    player_id: i64,
+    data: *const u8,
+    len: usize,
+    sample_rate: u32,
+    channels: u16,
+    sample_format: i32,
+) -> i32 {
+    if data.is_null() {
+        return -1;
+    }
+    let Some(format) = PcmFormat::from_i32(sample_format) else {
+        log::error!("Invalid PCM sample format: {}", sample_format);
+        return -1;
+    };
+    let bytes = std::slice::from_raw_parts(data, len);
+    to_code(with_player_mut(player_id, |p| {
+        p.load_pcm(bytes, sample_rate, channels, format)
+    }))
+}
+
+#[no_mangle]
+pub extern "C" fn rust_audio_player_play(player_id: i64) -> i32 {
+    to_code(with_player_mut(player_id, |p| p.play()))
+}
+
+#[no_mangle]
+pub extern "C" fn rust_audio_player_pause(player_id: i64) -> i32 {
+    to_code(with_player_mut(player_id, |p| p.pause()))
+}
+
+#[no_mangle]
+pub extern "C" fn rust_audio_player_stop(player_id: i64) -> i32 {
+    to_code(with_player_mut(player_id, |p| p.stop()))
+}
+
+#[no_mangle]
+pub extern "C" fn rust_audio_player_seek(player_id: i64, position_ms: i64) -> i32 {
+    to_code(with_player_mut(player_id, |p| p.seek(position_ms as u64)))
+}
+
+/// Set volume (0.0 - 1.0)
+/// Returns: 0 on success, otherwise the error's code (-5 invalid state when nothing is loaded,
+/// the player was released or the ID is unknown)
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_volume(player_id: i64, volume: f32) -> i32 {
+    to_error_code(with_player_mut(player_id, |p| p.set_volume(volume)))
+}
+
+/// Set playback rate (1.0 = normal speed)
+/// Returns: 0 on success, otherwise the error's code (-5 invalid state when nothing is loaded,
+/// the player was released or the ID is unknown)
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_playback_rate(player_id: i64, rate: f32) -> i32 {
+    to_error_code(with_player_mut(player_id, |p| p.set_playback_rate(rate)))
+}
+
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_position(player_id: i64) -> i64 {
+    match with_player(player_id, |p| Ok(p.get_status().position_ms)) {
+        Ok(pos) => pos as i64,
+        Err(err) => {
+            log::error!("Failed to get position: {}", err);
+            -1
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_duration(player_id: i64) -> i64 {
+    match with_player(player_id, |p| Ok(p.get_status().duration_ms)) {
+        Ok(dur) => dur as i64,
+        Err(err) => {
+            log::error!("Failed to get duration: {}", err);
+            -1
+        }
+    }
+}
+
+/// Load a local .m3u/.m3u8/.pls playlist: the first entry is loaded and the rest are queued
+/// Returns: number of entries, or -1 on error
+///
+/// # Safety
+/// `path` must be a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rust_audio_player_load_playlist(
+    player_id: i64,
+    path: *const std::os::raw::c_char,
+) -> i32 {
+    if path.is_null() {
+        return -1;
+    }
+    let c_str = std::ffi::CStr::from_ptr(path);
+    let Ok(path_str) = c_str.to_str() else {
+        return -1;
+    };
+    let loaded = with_player_mut(player_id, |p| p.queue_playlist(path_str))
+        .and_then(|count| load_next_entry(player_id).map(|_| count));
+    match loaded {
+        Ok(count) => count as i32,
+        Err(err) => {
+            log::error!("Failed to load playlist: {}", err);
+            -1
+        }
+    }
+}
+
+/// Load the next queued playlist entry
+/// Returns: 1 if an entry was loaded, 0 if the queue is empty, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_next(player_id: i64) -> i32 {
+    match load_next_entry(player_id) {
+        Ok(loaded) => loaded as i32,
+        Err(err) => {
+            log::error!("Failed to load next entry: {}", err);
+            -1
+        }
+    }
+}
+
+/// Switch to another audio track of the current source
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_audio_track(player_id: i64, track_id: i32) -> i32 {
+    if track_id < 0 {
+        log::error!("Invalid track id: {}", track_id);
+        return -1;
+    }
+    to_code(with_player_mut(player_id, |p| p.set_audio_track(track_id as u32)))
+}
+
+/// Set the HTTP connect/read timeouts in milliseconds, used by subsequent `load_url` calls
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_network_timeouts(
+    player_id: i64,
+    connect_timeout_ms: i64,
+    read_timeout_ms: i64,
+) -> i32 {
+    if connect_timeout_ms <= 0 || read_timeout_ms <= 0 {
+        log::error!(
+            "Invalid network timeouts: connect {} ms, read {} ms",
+            connect_timeout_ms,
+            read_timeout_ms
+        );
+        return -1;
+    }
+    to_code(with_player_mut(player_id, |p| {
+        p.set_network_timeouts(connect_timeout_ms as u64, read_timeout_ms as u64)
+    }))
+}
+
+/// Override how many seconds of audio the engine buffers (applies live)
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_buffer_seconds(player_id: i64, seconds: f32) -> i32 {
+    to_code(with_player_mut(player_id, |p| p.set_buffer_seconds(seconds)))
+}
+
+/// Cap decoded-ahead audio at `max_lookahead_ms` regardless of the ring length
+/// (0 removes the cap, otherwise at least 100 ms; takes effect on the next load)
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_max_lookahead_ms(player_id: i64, max_lookahead_ms: u64) -> i32 {
+    to_code(with_player_mut(player_id, |p| p.set_max_lookahead_ms(max_lookahead_ms)))
+}
+
+/// Set the User-Agent for every HTTP request made from now on; null or empty restores the default
+///
+/// # Safety
+/// `user_agent` must be null or a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rust_audio_player_set_user_agent(user_agent: *const std::os::raw::c_char) -> i32 {
+    if user_agent.is_null() {
+        podium_transport_http::set_user_agent(None);
+        return 0;
+    }
+    match std::ffi::CStr::from_ptr(user_agent).to_str() {
+        Ok(ua) => {
+            podium_transport_http::set_user_agent(Some(ua));
+            0
+        }
         Err(_) => -1,
     }
 }
 
+/// Set the output channel layout: 0=Auto, 1=ForceMono, 2=ForceStereo
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_output_channel_mode(player_id: i64, mode: i32) -> i32 {
+    let Some(mode) = ChannelMode::from_i32(mode) else {
+        log::error!("Invalid channel mode: {}", mode);
+        return -1;
+    };
+    to_code(with_player_mut(player_id, |p| {
+        p.set_output_channel_mode(mode);
+        Ok(())
+    }))
+}
+
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_output_sample_rate(player_id: i64) -> i32 {
+    match with_player(player_id, |p| Ok(p.output_sample_rate())) {
+        Ok(rate) => rate as i32,
+        Err(err) => {
+            log::error!("Failed to get output sample rate: {}", err);
+            -1
+        }
+    }
+}
+
+/// Kind of the current source: 0=File, 1=Http, 2=Buffer; -1 on error or when nothing is loaded
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_source_kind(player_id: i64) -> i32 {
+    match with_player(player_id, |p| Ok(p.current_source_kind())) {
+        Ok(Some(info)) => info.kind as i32,
+        Ok(None) => -1,
+        Err(err) => {
+            log::error!("Failed to get source kind: {}", err);
+            -1
+        }
+    }
+}
+
+/// Whether the current source is served from a local cache: 1=yes, 0=no, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_is_source_cached(player_id: i64) -> i32 {
+    match with_player(player_id, |p| Ok(p.current_source_kind())) {
+        Ok(Some(info)) => info.cached as i32,
+        Ok(None) => -1,
+        Err(err) => {
+            log::error!("Failed to get source cache state: {}", err);
+            -1
+        }
+    }
+}
+
+/// Whether the loaded source can seek: 1=yes, 0=no (or nothing loaded), -1 on error
 #[no_mangle]
-pub extern "C" fn rust_audio_player_play(player_id: i64) -> i32 {
-    to_code(with_player_mut(player_id, |p| p.play()))
+pub extern "C" fn rust_audio_player_is_seekable(player_id: i64) -> i32 {
+    match with_player(player_id, |p| Ok(p.is_seekable())) {
+        Ok(seekable) => seekable as i32,
+        Err(err) => {
+            log::error!("Failed to get seekability: {}", err);
+            -1
+        }
+    }
 }
 
 #[no_mangle]
-pub extern "C" fn rust_audio_player_pause(player_id: i64) -> i32 {
-    to_code(with_player_mut(player_id, |p| p.pause()))
+pub extern "C" fn rust_audio_player_get_output_channels(player_id: i64) -> i32 {
+    match with_player(player_id, |p| Ok(p.output_channels())) {
+        Ok(channels) => channels as i32,
+        Err(err) => {
+            log::error!("Failed to get output channels: {}", err);
+            -1
+        }
+    }
 }
 
+/// Playback statistics as a JSON object; free the result with `rust_audio_player_free_string`.
+/// Returns null for an invalid player ID.
 #[no_mangle]
-pub extern "C" fn rust_audio_player_stop(player_id: i64) -> i32 {
-    to_code(with_player_mut(player_id, |p| p.stop()))
+pub extern "C" fn rust_audio_player_get_stats_json(player_id: i64) -> *mut std::os::raw::c_char {
+    match with_player(player_id, |p| Ok(p.get_stats())) {
+        Ok(stats) => std::ffi::CString::new(stats.to_json())
+            .map(|json| json.into_raw())
+            .unwrap_or(std::ptr::null_mut()),
+        Err(err) => {
+            log::error!("Failed to get stats: {}", err);
+            std::ptr::null_mut()
+        }
+    }
 }
 
+/// Playback status as a JSON object:
+/// `{position_ms, duration_ms, duration_known, volume, playback_rate, buffering}`.
+/// Free the result with `rust_audio_player_free_string`. Returns null for an invalid player ID.
 #[no_mangle]
-pub extern "C" fn rust_audio_player_seek(player_id: i64, position_ms: i64) -> i32 {
-    to_code(with_player_mut(player_id, |p| p.seek(position_ms as u64)))
+pub extern "C" fn rust_audio_player_get_status_json(player_id: i64) -> *mut std::os::raw::c_char {
+    match with_player(player_id, |p| Ok(p.get_status())) {
+        Ok(status) => std::ffi::CString::new(status.to_json())
+            .map(|json| json.into_raw())
+            .unwrap_or(std::ptr::null_mut()),
+        Err(err) => {
+            log::error!("Failed to get status: {}", err);
+            std::ptr::null_mut()
+        }
+    }
 }
 
+/// Current track format as JSON:
+/// `{sample_rate, channels, duration_ms, codec, bitrate_bps, is_vbr}` (`bitrate_bps` may be null).
+/// Free the result with `rust_audio_player_free_track_info_json`.
+/// Returns null for an invalid player ID or before the track has been probed.
 #[no_mangle]
-pub extern "C" fn rust_audio_player_get_position(player_id: i64) -> i64 {
-    match with_player(player_id, |p| Ok(p.get_status().position_ms)) {
-        Ok(pos) => pos as i64,
+pub extern "C" fn rust_audio_player_get_track_info_json(player_id: i64) -> *mut std::os::raw::c_char {
+    match with_player(player_id, |p| p.track_info()) {
+        Ok(info) => std::ffi::CString::new(info.to_json())
+            .map(|json| json.into_raw())
+            .unwrap_or(std::ptr::null_mut()),
         Err(err) => {
-            log::error!("Failed to get position: {}", err);
-            -1
+            log::error!("Failed to get track info: {}", err);
+            std::ptr::null_mut()
         }
     }
 }
 
+/// Track info JSON for the JNI classes (Android and JVM desktop alike), taken from the
+/// decoder's probe of the current source; `{}` until the track has been probed
+#[cfg(any(feature = "android", feature = "desktop", test))]
+fn track_info_json(player_id: i64) -> String {
+    with_player(player_id, |p| p.track_info())
+        .map(|info| info.to_json())
+        .unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Free a string returned by `rust_audio_player_get_track_info_json`
+///
+/// # Safety
+/// Same contract as `rust_audio_player_free_string`.
 #[no_mangle]
-pub extern "C" fn rust_audio_player_get_duration(player_id: i64) -> i64 {
-    match with_player(player_id, |p| Ok(p.get_status().duration_ms)) {
-        Ok(dur) => dur as i64,
-        Err(err) => {
-            log::error!("Failed to get duration: {}", err);
-            -1
-        }
+pub unsafe extern "C" fn rust_audio_player_free_track_info_json(ptr: *mut std::os::raw::c_char) {
+    rust_audio_player_free_string(ptr);
+}
+
+/// Free a string returned by this library
+///
+/// # Safety
+/// `ptr` must be null or a pointer previously returned by this library, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rust_audio_player_free_string(ptr: *mut std::os::raw::c_char) {
+    if ptr.is_null() {
+        return;
     }
+    drop(std::ffi::CString::from_raw(ptr));
 }
 
 #[no_mangle]
@@ -657,7 +1835,7 @@ pub extern "C" fn rust_audio_player_release(player_id: i64) -> i32 {
 mod jni_bridge {
     use super::*;
     use jni::objects::{JByteArray, JClass, JString};
-    use jni::sys::{jfloat, jint, jlong, jstring};
+    use jni::sys::{jboolean, jfloat, jint, jlong, jstring, JNI_FALSE, JNI_TRUE};
     use jni::JNIEnv;
 
     fn jstring_to_string(env: &mut JNIEnv, jstr: &JString) -> Result<String> {
@@ -705,7 +1883,7 @@ mod jni_bridge {
         url: JString,
     ) -> jint {
         match jstring_to_string(&mut env, &url) {
-            Ok(u) => to_code(with_player_mut(player_id, |player| player.load_url(&u))) as jint,
+            Ok(u) => to_code(load_url_unlocked(player_id, &u, &[])) as jint,
             Err(err) => {
                 log::error!("Failed to read URL: {}", err);
                 -1
@@ -773,7 +1951,7 @@ mod jni_bridge {
         player_id: jlong,
         volume: jfloat,
     ) -> jint {
-        to_code(with_player_mut(player_id, |p| p.set_volume(volume))) as jint
+        to_error_code(with_player_mut(player_id, |p| p.set_volume(volume))) as jint
     }
 
     #[no_mangle]
@@ -798,6 +1976,18 @@ mod jni_bridge {
             .unwrap_or(-1)
     }
 
+    #[no_mangle]
+    pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeIsSeekable(
+        _env: JNIEnv,
+        _class: JClass,
+        player_id: jlong,
+    ) -> jboolean {
+        match with_player(player_id, |p| Ok(p.is_seekable())) {
+            Ok(true) => JNI_TRUE,
+            _ => JNI_FALSE,
+        }
+    }
+
     #[no_mangle]
     pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeGetState(
         _env: JNIEnv,
@@ -864,7 +2054,7 @@ mod jni_bridge {
         url: JString,
     ) -> jint {
         match jstring_to_string(&mut env, &url) {
-            Ok(u) => to_code(with_player_mut(player_id, |player| player.load_url(&u))) as jint,
+            Ok(u) => to_code(load_url_unlocked(player_id, &u, &[])) as jint,
             Err(err) => {
                 log::error!("Failed to read URL: {}", err);
                 -1
@@ -932,7 +2122,7 @@ mod jni_bridge {
         player_id: jlong,
         volume: jfloat,
     ) -> jint {
-        to_code(with_player_mut(player_id, |p| p.set_volume(volume))) as jint
+        to_error_code(with_player_mut(player_id, |p| p.set_volume(volume))) as jint
     }
 
     #[no_mangle]
@@ -957,6 +2147,18 @@ mod jni_bridge {
             .unwrap_or(-1)
     }
 
+    #[no_mangle]
+    pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayerJvm_nativeIsSeekable(
+        _env: JNIEnv,
+        _class: JClass,
+        player_id: jlong,
+    ) -> jboolean {
+        match with_player(player_id, |p| Ok(p.is_seekable())) {
+            Ok(true) => JNI_TRUE,
+            _ => JNI_FALSE,
+        }
+    }
+
     #[no_mangle]
     pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayerJvm_nativeGetState(
         _env: JNIEnv,
@@ -990,13 +2192,893 @@ mod jni_bridge {
         }
     }
 
-    // Optional metadata stub to keep interface compatibility; returns "{}".
+    /// Sample rate, channels, duration and codec of the current track as JSON
+    #[no_mangle]
+    pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeGetTrackInfo(
+        env: JNIEnv,
+        _class: JClass,
+        player_id: jlong,
+    ) -> jstring {
+        string_to_jstring(&env, &track_info_json(player_id)).unwrap_or(std::ptr::null_mut())
+    }
+
+    #[no_mangle]
+    pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayerJvm_nativeGetTrackInfo(
+        env: JNIEnv,
+        _class: JClass,
+        player_id: jlong,
+    ) -> jstring {
+        string_to_jstring(&env, &track_info_json(player_id)).unwrap_or(std::ptr::null_mut())
+    }
+
+    // Kept for callers of the older metadata entry point; tags are not extracted yet,
+    // so this carries the same format info as `nativeGetTrackInfo`
     #[no_mangle]
     pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeGetMetadataJson(
         env: JNIEnv,
         _class: JClass,
-        _player_id: jlong,
+        player_id: jlong,
+    ) -> jstring {
+        string_to_jstring(&env, &track_info_json(player_id)).unwrap_or(std::ptr::null_mut())
+    }
+
+    #[no_mangle]
+    pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayerJvm_nativeGetMetadataJson(
+        env: JNIEnv,
+        _class: JClass,
+        player_id: jlong,
+    ) -> jstring {
+        string_to_jstring(&env, &track_info_json(player_id)).unwrap_or(std::ptr::null_mut())
+    }
+
+    #[no_mangle]
+    pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeGetStatsJson(
+        env: JNIEnv,
+        _class: JClass,
+        player_id: jlong,
     ) -> jstring {
-        string_to_jstring(&env, "{}").unwrap_or(std::ptr::null_mut())
+        let json = with_player(player_id, |p| Ok(p.get_stats().to_json()))
+            .unwrap_or_else(|_| "{}".to_string());
+        string_to_jstring(&env, &json).unwrap_or(std::ptr::null_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_underflows_are_counted() {
+        let ring = SharedRingBuffer::new(64);
+        let playing = AtomicBool::new(true);
+        let stats = StatsCounters::new();
+        let mut data = vec![1.0f32; 16];
+
+        // Enough buffered audio: no underflow
+        ring.write(&[0.5; 16]);
+        PlaybackEngine::fill_output(&ring, &playing, &stats, &mut data);
+        assert_eq!(stats.snapshot(ring.fullness()).underflow_count, 0);
+
+        // Drained ring: every callback underflows and is padded with silence
+        for expected in 1..=3 {
+            PlaybackEngine::fill_output(&ring, &playing, &stats, &mut data);
+            assert_eq!(stats.snapshot(ring.fullness()).underflow_count, expected);
+            assert!(data.iter().all(|&s| s == 0.0));
+        }
+
+        // Paused output is silence, not an underflow
+        playing.store(false, Ordering::SeqCst);
+        PlaybackEngine::fill_output(&ring, &playing, &stats, &mut data);
+        assert_eq!(stats.snapshot(ring.fullness()).underflow_count, 3);
+    }
+
+    #[test]
+    fn test_file_ring_smaller_than_http_by_default() {
+        let config = BufferConfig::default();
+        let file = EngineSource::Media(SourceKind::File("/tmp/a.mp3".to_string()));
+        let http = EngineSource::Media(SourceKind::Http("https://example.com/a.mp3".to_string()));
+        let override_seconds = Arc::new(AtomicU32::new(0));
+        let file_ring = RingLength::new(config, &file, override_seconds.clone());
+        let http_ring = RingLength::new(config, &http, override_seconds.clone());
+        assert!(file_ring.samples(48000, 2) < http_ring.samples(48000, 2));
+
+        // An explicit length applies to both kinds
+        override_seconds.store(1.5f32.to_bits(), Ordering::SeqCst);
+        assert_eq!(file_ring.samples(48000, 2), 48000 * 3);
+        assert_eq!(http_ring.samples(48000, 2), 48000 * 3);
+    }
+
+    #[test]
+    fn test_lookahead_cap_bounds_decoded_audio() {
+        let mut player = PodiumPlayer::new();
+        assert!(player.set_max_lookahead_ms(50).is_err());
+        player.set_max_lookahead_ms(500).unwrap();
+        let http = EngineSource::Media(SourceKind::Http("https://example.com/a.mp3".to_string()));
+        let cap_samples = 48000 / 2 * 2;
+
+        // The length the decode loop sizes its ring from, sharing the player's live override
+        let length = RingLength::new(player.buffer_config, &http, player.buffer_seconds.clone());
+        assert_eq!(length.samples(48000, 2), cap_samples);
+        // Shorter lengths are left alone
+        player.set_buffer_seconds(0.25).unwrap();
+        assert_eq!(length.samples(48000, 2), cap_samples / 2);
+
+        // Decode loop passes with an output callback between them; partway through the app asks
+        // for a 30 s ring, which the cap must keep the engine ring (and the paused prebuffer) from
+        let ring = SharedRingBuffer::new(length.samples(48000, 2));
+        let mut feed = RingFeed::new(2, 48000);
+        let stats = StatsCounters::new();
+        let playing = AtomicBool::new(true);
+        let mut callback = vec![0.0; 256 * 2];
+        let mut max_size = 0;
+        for pass in 0..400 {
+            if pass == 100 {
+                player.set_buffer_seconds(30.0).unwrap();
+            }
+            if pass == 300 {
+                playing.store(false, Ordering::SeqCst);
+            }
+            if PlaybackEngine::fit_ring(&ring, length.samples(48000, 2)) {
+                if !feed.has_pending() {
+                    feed.push(vec![0.0f32; 1152 * 2]);
+                }
+                feed.write(&ring);
+            }
+            max_size = max_size.max(ring.size());
+            assert!(ring.size() <= cap_samples);
+            assert!(ring.available_read() <= cap_samples);
+            PlaybackEngine::fill_output(&ring, &playing, &stats, &mut callback);
+        }
+        // The override did apply, up to the cap
+        assert_eq!(max_size, cap_samples);
+        assert!(ring.fullness() > 0.9);
+
+        // Without the cap, the same override gives the full 30 s on the next load
+        player.set_max_lookahead_ms(0).unwrap();
+        let length = RingLength::new(player.buffer_config, &http, player.buffer_seconds.clone());
+        assert_eq!(length.samples(48000, 2), 48000 * 30 * 2);
+    }
+
+    #[test]
+    fn test_live_resize_skips_no_audio() {
+        const RATE: u32 = 8000;
+        const FRAMES: usize = RATE as usize * 4;
+        // Each stereo frame carries its index, so a skipped or repeated frame shows in the output
+        let samples: Vec<f32> = (0..FRAMES).flat_map(|frame| [frame as f32; 2]).collect();
+        let mut producer = PcmProducer::Raw {
+            samples,
+            sample_rate: RATE,
+            channels: 2,
+            cursor: 0,
+        };
+        let ring_for = |seconds| BufferConfig::samples_for(seconds, RATE, 2);
+        let ring = SharedRingBuffer::new(ring_for(1.0));
+        let mut feed = RingFeed::new(2, RATE);
+        let stats = StatsCounters::new();
+        let playing = AtomicBool::new(true);
+        let mut errors = 0;
+        let mut ended = false;
+
+        let mut heard = Vec::new();
+        let mut callback = vec![0.0; 256 * 2];
+        while heard.len() < FRAMES {
+            // The app shortens the buffer below what is queued, then lengthens it, mid-playback
+            let seconds = match heard.len() {
+                n if n < RATE as usize => 1.0,
+                n if n < 2 * RATE as usize => 0.25,
+                _ => 2.0,
+            };
+            // Decode loop passes until the ring is full, then one output callback
+            while !ended && PlaybackEngine::fit_ring(&ring, ring_for(seconds)) {
+                if !feed.has_pending() {
+                    match producer.next(&stats, 2, &mut errors) {
+                        DecodeStep::Audio(pcm) => feed.push(pcm),
+                        _ => ended = true,
+                    }
+                }
+                feed.write(&ring);
+                if feed.has_pending() {
+                    break;
+                }
+            }
+            PlaybackEngine::fill_output(&ring, &playing, &stats, &mut callback);
+            heard.extend(callback.chunks(2).map(|frame| frame[0]));
+        }
+
+        assert_eq!(ring.size(), ring_for(2.0));
+        assert_eq!(stats.snapshot(ring.fullness()).underflow_count, 0);
+        assert!(heard.iter().enumerate().all(|(i, &frame)| frame == i as f32));
+        assert_eq!(feed.played_ms(&ring), 4000);
+    }
+
+    #[test]
+    fn test_playback_rate_is_clamped() {
+        let mut player = PodiumPlayer::new();
+        player.load_buffer(&wav_bytes(8000, 800)).unwrap();
+        player.set_playback_rate(1.5).unwrap();
+        assert_eq!(player.get_status().playback_rate, 1.5);
+        player.set_playback_rate(10.0).unwrap();
+        assert_eq!(player.get_status().playback_rate, podium_core::MAX_PLAYBACK_RATE);
+        player.set_playback_rate(0.0).unwrap();
+        assert_eq!(player.get_status().playback_rate, podium_core::MIN_PLAYBACK_RATE);
+        assert!(player.set_playback_rate(f32::NAN).is_err());
+    }
+
+    #[test]
+    fn test_volume_and_rate_need_a_loaded_source() {
+        let invalid_state = AudioError::InvalidState(String::new()).code();
+        let id = rust_audio_player_create();
+        assert_eq!(rust_audio_player_set_volume(id, 0.5), invalid_state);
+        assert_eq!(rust_audio_player_set_playback_rate(id, 1.5), invalid_state);
+
+        with_player_mut(id, |p| p.load_buffer(&wav_bytes(8000, 800))).unwrap();
+        assert_eq!(rust_audio_player_set_volume(id, 0.5), 0);
+        assert_eq!(rust_audio_player_set_playback_rate(id, 1.5), 0);
+
+        with_player_mut(id, |p| p.release()).unwrap();
+        assert_eq!(rust_audio_player_set_volume(id, 0.5), invalid_state);
+        assert_eq!(rust_audio_player_set_playback_rate(id, 1.0), invalid_state);
+
+        // A stale ID is an invalid state too
+        assert_eq!(rust_audio_player_release(id), 0);
+        assert_eq!(rust_audio_player_set_volume(id, 0.5), invalid_state);
+    }
+
+    #[test]
+    fn test_raw_i16_pcm_renders_as_f32() {
+        let frames: [[i16; 2]; 4] = [[0, 0], [16384, -16384], [32767, -32768], [-8192, 8192]];
+        let bytes: Vec<u8> = frames.iter().flatten().flat_map(|s| s.to_le_bytes()).collect();
+
+        let samples = pcm_bytes_to_f32(&bytes, PcmFormat::I16).unwrap();
+        let info = raw_track_info(samples.len(), 8000, 2, PcmFormat::I16);
+        assert_eq!(info.codec, "pcm_s16le");
+        assert_eq!(info.bitrate_bps, Some(256000));
+        let mut producer = PcmProducer::Raw {
+            samples,
+            sample_rate: 8000,
+            channels: 2,
+            cursor: 0,
+        };
+
+        // Feed the ring the way the decode loop does, then render one callback's worth
+        let ring = SharedRingBuffer::new(64);
+        let stats = StatsCounters::new();
+        let mut errors = 0;
+        while let DecodeStep::Audio(pcm) = producer.next(&stats, 2, &mut errors) {
+            ring.write(&pcm);
+        }
+        let mut out = vec![1.0f32; 8];
+        PlaybackEngine::fill_output(&ring, &AtomicBool::new(true), &stats, &mut out);
+        assert_eq!(out, [0.0, 0.0, 0.5, -0.5, 32767.0 / 32768.0, -1.0, -0.25, 0.25]);
+        assert_eq!(stats.snapshot(ring.fullness()).underflow_count, 0);
+
+        // Seeking moves the cursor back
+        producer.seek(0);
+        assert!(matches!(producer.next(&stats, 2, &mut errors), DecodeStep::Audio(pcm) if pcm.len() == 8));
+
+        // Partial frames and a zero rate are rejected up front
+        let mut player = PodiumPlayer::new();
+        assert!(player.load_pcm(&bytes[..6], 8000, 2, PcmFormat::I16).is_err());
+        assert!(player.load_pcm(&bytes, 0, 2, PcmFormat::I16).is_err());
+        player.load_pcm(&bytes, 8000, 2, PcmFormat::I16).unwrap();
+        assert_eq!(player.current_source_kind().map(|info| info.kind), Some(SourceOrigin::Buffer));
+        player.release().unwrap();
+    }
+
+    /// 16-bit stereo PCM WAV header followed by `frames` frames of silence
+    fn wav_bytes(sample_rate: u32, frames: u32) -> Vec<u8> {
+        let data_len = frames * 4;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&2u16.to_le_bytes()); // channels
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 4).to_le_bytes()); // byte rate
+        wav.extend_from_slice(&4u16.to_le_bytes()); // block align
+        wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.resize(44 + data_len as usize, 0);
+        wav
+    }
+
+    #[test]
+    fn test_track_info_json_matches_fixture() {
+        let wav = wav_bytes(44100, 44100 / 2);
+        let demuxer = Demuxer::from_media_source(
+            Box::new(std::io::Cursor::new(wav)),
+            Demuxer::create_hint_from_path("fixture.wav"),
+        )
+        .unwrap();
+        let info = demuxer.get_track_info().unwrap();
+        assert_eq!(
+            info.to_json(),
+            r#"{"sample_rate":44100,"channels":2,"duration_ms":500,"codec":"pcm_s16le","bitrate_bps":1411200,"is_vbr":false}"#
+        );
+
+        // Unknown players get null rather than a dangling string
+        assert!(rust_audio_player_get_track_info_json(-1).is_null());
+        unsafe { rust_audio_player_free_track_info_json(std::ptr::null_mut()) };
+    }
+
+    /// In-memory source that refuses random access, like a live stream
+    struct NonSeekableSource(std::io::Cursor<Vec<u8>>);
+
+    impl std::io::Read for NonSeekableSource {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.0.read(buf)
+        }
+    }
+
+    impl std::io::Seek for NonSeekableSource {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    impl symphonia::core::io::MediaSource for NonSeekableSource {
+        fn is_seekable(&self) -> bool {
+            false
+        }
+
+        fn byte_len(&self) -> Option<u64> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_seekability_follows_source() {
+        let hint = || Demuxer::create_hint_from_path("fixture.wav");
+        let file = Demuxer::from_media_source(
+            Box::new(std::io::Cursor::new(wav_bytes(8000, 800))),
+            hint(),
+        )
+        .unwrap();
+        assert!(file.is_seekable());
+
+        let live = Demuxer::from_media_source(
+            Box::new(NonSeekableSource(std::io::Cursor::new(wav_bytes(8000, 800)))),
+            hint(),
+        )
+        .unwrap();
+        assert!(!live.is_seekable());
+
+        // Nothing loaded yet
+        assert!(!PodiumPlayer::new().is_seekable());
+        assert_eq!(rust_audio_player_is_seekable(-1), -1);
+    }
+
+    #[test]
+    fn test_track_info_json_comes_from_the_engine() {
+        let id = register_player(PodiumPlayer::new());
+        // Nothing loaded (and unknown players) give an empty object rather than null
+        assert_eq!(track_info_json(id), "{}");
+        assert_eq!(track_info_json(-1), "{}");
+
+        // 0.5 s of 8 kHz stereo i16: the engine publishes the track before opening a device
+        let pcm = vec![0u8; 8000 / 2 * 2 * 2];
+        with_player_mut(id, |p| p.load_pcm(&pcm, 8000, 2, PcmFormat::I16)).unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while track_info_json(id) == "{}" && std::time::Instant::now() < deadline {
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(
+            track_info_json(id),
+            r#"{"sample_rate":8000,"channels":2,"duration_ms":500,"codec":"pcm_s16le","bitrate_bps":256000,"is_vbr":false}"#
+        );
+
+        PLAYER_REGISTRY.lock().remove(&id).unwrap().release().unwrap();
+    }
+
+    #[test]
+    fn test_duration_known_once_probed() {
+        let id = register_player(PodiumPlayer::new());
+        let wait_for_probe = || {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+            while track_info_json(id) == "{}" && std::time::Instant::now() < deadline {
+                thread::sleep(std::time::Duration::from_millis(10));
+            }
+        };
+
+        // The empty body never probes, so the stream's length is never learned
+        let url = format!("http://{}/stream.mp3", serve_empty_ok());
+        with_player_mut(id, |p| p.load_url(&url)).unwrap();
+        thread::sleep(std::time::Duration::from_millis(100));
+        let status = with_player(id, |p| Ok(p.get_status())).unwrap();
+        assert_eq!((status.duration_ms, status.duration_known), (0, false));
+
+        with_player_mut(id, |p| p.load_buffer(&wav_bytes(8000, 4000))).unwrap();
+        wait_for_probe();
+        let status = with_player(id, |p| Ok(p.get_status())).unwrap();
+        assert_eq!((status.duration_ms, status.duration_known), (500, true));
+
+        let ptr = rust_audio_player_get_status_json(id);
+        let json = unsafe { std::ffi::CStr::from_ptr(ptr) }.to_string_lossy().into_owned();
+        unsafe { rust_audio_player_free_string(ptr) };
+        assert!(json.contains(r#""duration_ms":500,"duration_known":true"#), "{}", json);
+        assert!(rust_audio_player_get_status_json(-1).is_null());
+
+        PLAYER_REGISTRY.lock().remove(&id).unwrap().release().unwrap();
+    }
+
+    /// ADTS AAC-LC stream, 44.1 kHz mono. Each frame carries a single spectral line whose
+    /// level changes per frame, so leftover overlap from a previous frame is audible.
+    fn adts_aac(frames: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        for i in 0..frames {
+            let mut bits = String::new();
+            bits += "0000000"; // SCE, tag 0
+            bits += &format!("{:08b}", 190 + (i % 8) * 4); // global gain
+            bits += "00000000010"; // ics_info: long window, max_sfb 1, no prediction
+            bits += "000100001"; // section: codebook 1 for one band
+            bits += "0"; // scalefactor delta 0
+            bits += "000"; // no pulse, TNS or gain control
+            bits += "10000"; // quad (+1, 0, 0, 0)
+            bits += "111"; // END
+            while !bits.len().is_multiple_of(8) {
+                bits.push('0');
+            }
+            let payload: Vec<u8> = bits
+                .as_bytes()
+                .chunks(8)
+                .map(|byte| u8::from_str_radix(std::str::from_utf8(byte).unwrap(), 2).unwrap())
+                .collect();
+
+            let len = 7 + payload.len();
+            out.extend_from_slice(&[
+                0xFF,
+                0xF1, // MPEG-4, no CRC
+                0x50, // LC, 44.1 kHz
+                0x40 | (len >> 11) as u8, // mono
+                (len >> 3) as u8,
+                ((len & 7) << 5) as u8 | 0x1F,
+                0xFC,
+            ]);
+            out.extend_from_slice(&payload);
+        }
+        out
+    }
+
+    #[test]
+    fn test_stream_without_range_support_plays_unseekable() {
+        // Range headers are ignored: every GET is a 200 with the whole file
+        let body = wav_bytes(8000, 8000);
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            use std::io::{Read, Write};
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 2048];
+                let n = stream.read(&mut request).unwrap_or(0);
+                let mut response =
+                    format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len())
+                        .into_bytes();
+                if n > 0 && !request.starts_with(b"HEAD") {
+                    response.extend_from_slice(&body);
+                }
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        let id = register_player(PodiumPlayer::new());
+        with_player_mut(id, |p| p.load_url(&format!("http://{}/episode.wav", addr))).unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while track_info_json(id) == "{}" && std::time::Instant::now() < deadline {
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(track_info_json(id).contains(r#""duration_ms":1000"#), "{}", track_info_json(id));
+        assert_eq!(rust_audio_player_is_seekable(id), 0);
+
+        PLAYER_REGISTRY.lock().remove(&id).unwrap().release().unwrap();
+    }
+
+    #[test]
+    fn test_stream_hint_comes_from_content_type() {
+        // An extension-less stream URL: only the Content-Type says this is ADTS AAC
+        let (addr, _) = serve_ranges_as(adts_aac(100), Some("audio/aac"));
+        let url = format!("http://{}/live", addr);
+        let source = NetworkSource::from_http_range(url.clone()).unwrap();
+        assert_eq!(source.content_type(), Some("audio/aac"));
+        assert_eq!(podium_demux::stream_extension(&url, source.content_type()), Some("aac"));
+
+        let hint = Demuxer::create_hint_for_stream(&url, source.content_type());
+        let mut demuxer = Demuxer::from_media_source(Box::new(source), hint).unwrap();
+        let (_, info, _) = AudioDecoder::open(&mut demuxer).unwrap();
+        assert_eq!((info.codec.as_str(), info.sample_rate), ("aac", 44100));
+    }
+
+    #[test]
+    fn test_seek_resets_decoder_state() {
+        let open = || {
+            let mut demuxer = Demuxer::from_media_source(
+                Box::new(std::io::Cursor::new(adts_aac(100))),
+                Demuxer::create_hint_from_path("fixture.aac"),
+            )
+            .unwrap();
+            let (decoder, info, _) = AudioDecoder::open(&mut demuxer).unwrap();
+            (demuxer, decoder, info)
+        };
+        let target_ms = 1000;
+
+        // Reference: a fresh decoder starting at the seek target
+        let (mut demuxer, mut decoder, info) = open();
+        demuxer.seek(target_ms).unwrap();
+        let reference = decoder.decode(&demuxer.next_packet().unwrap()).unwrap();
+        assert!(reference.iter().any(|s| s.abs() > 1e-3));
+
+        // Engine path: play a while, then jump
+        let (demuxer, decoder, _) = open();
+        let mut producer = PcmProducer::Decoded { demuxer, decoder };
+        let stats = StatsCounters::new();
+        let mut errors = 0;
+        let mut next = |producer: &mut PcmProducer| match producer.next(&stats, info.channels, &mut errors) {
+            DecodeStep::Audio(pcm) => pcm,
+            _ => panic!("expected audio"),
+        };
+        for _ in 0..5 {
+            next(&mut producer);
+        }
+        producer.seek(target_ms);
+        assert_eq!(next(&mut producer), reference);
+    }
+
+    /// Local HTTP server answering every request with an empty 200
+    fn serve_empty_ok() -> std::net::SocketAddr {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+            }
+        });
+        addr
+    }
+
+    /// Local HTTP server serving `body` with Range support, reporting each request's head
+    fn serve_ranges(body: Vec<u8>) -> (std::net::SocketAddr, std::sync::mpsc::Receiver<String>) {
+        serve_ranges_as(body, None)
+    }
+
+    /// `serve_ranges`, labelling the body with `content_type`
+    fn serve_ranges_as(
+        body: Vec<u8>,
+        content_type: Option<&'static str>,
+    ) -> (std::net::SocketAddr, std::sync::mpsc::Receiver<String>) {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (requests, received) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 2048];
+                let n = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..n]).to_string();
+                let range = request
+                    .lines()
+                    .find_map(|line| line.to_ascii_lowercase().strip_prefix("range: bytes=").map(str::to_string))
+                    .and_then(|range| {
+                        let (start, end) = range.split_once('-')?;
+                        Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?))
+                    });
+                let (status, slice) = match range {
+                    Some((start, end)) => {
+                        let start = start.min(body.len());
+                        let end = (end + 1).min(body.len());
+                        ("206 Partial Content", &body[start..end])
+                    }
+                    None => ("200 OK", &body[..]),
+                };
+                let mut response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+                    status,
+                    slice.len(),
+                    content_type.map(|t| format!("Content-Type: {}\r\n", t)).unwrap_or_default()
+                )
+                .into_bytes();
+                if !request.starts_with("HEAD") {
+                    response.extend_from_slice(slice);
+                }
+                let _ = requests.send(request);
+                let _ = stream.write_all(&response);
+            }
+        });
+        (addr, received)
+    }
+
+    #[test]
+    fn test_load_headers_reach_range_requests_of_that_load_only() {
+        let (addr, received) = serve_ranges(wav_bytes(8000, 8000));
+        let mut with_token = PodiumPlayer::new();
+        let mut without_token = PodiumPlayer::new();
+        with_token
+            .load_url_with_headers(
+                &format!("http://{}/private.wav", addr),
+                &[("Authorization", "Bearer episode-token")],
+            )
+            .unwrap();
+        without_token.load_url(&format!("http://{}/public.wav", addr)).unwrap();
+
+        let mut private = Vec::new();
+        let mut public = Vec::new();
+        let ranged = |requests: &Vec<String>| requests.iter().any(|r| r.to_ascii_lowercase().contains("\r\nrange: "));
+        while !(ranged(&private) && ranged(&public)) {
+            let request = received.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+            if request.contains(" /private.wav ") {
+                private.push(request);
+            } else {
+                public.push(request);
+            }
+        }
+
+        assert!(private.iter().any(|r| r.starts_with("HEAD")));
+        for request in &private {
+            assert!(request.contains("\r\nAuthorization: Bearer episode-token\r\n"), "{}", request);
+        }
+        for request in &public {
+            assert!(!request.to_ascii_lowercase().contains("authorization"), "{}", request);
+        }
+        // The player's own settings never picked up the per-load header
+        assert!(without_token.http_config.headers.is_empty() && with_token.http_config.headers.is_empty());
+
+        with_token.release().unwrap();
+        without_token.release().unwrap();
+    }
+
+    #[test]
+    fn test_header_lines_parse() {
+        assert_eq!(
+            parse_header_lines("Authorization: Bearer a:b\r\n\nX-Feed:  42 ").unwrap(),
+            vec![("Authorization", "Bearer a:b"), ("X-Feed", "42")]
+        );
+        assert!(parse_header_lines("no colon").is_err());
+        assert!(parse_header_lines(": value").is_err());
+    }
+
+    #[test]
+    fn test_source_kind_follows_load() {
+        let mut player = PodiumPlayer::new();
+        assert_eq!(player.current_source_kind(), None);
+
+        // The decode thread fails to open the empty body; the reported kind only depends on the load call
+        player.load_url(&format!("http://{}/stream.mp3", serve_empty_ok())).unwrap();
+        assert_eq!(
+            player.current_source_kind(),
+            Some(SourceKindInfo { kind: SourceOrigin::Http, cached: false })
+        );
+
+        let path = std::env::temp_dir().join("podium_source_kind_test.wav");
+        std::fs::write(&path, wav_bytes(8000, 800)).unwrap();
+        player.load_file(&path.to_string_lossy()).unwrap();
+        assert_eq!(player.current_source_kind().map(|info| info.kind), Some(SourceOrigin::File));
+
+        player.load_buffer(&wav_bytes(8000, 800)).unwrap();
+        assert_eq!(player.current_source_kind().map(|info| info.kind), Some(SourceOrigin::Buffer));
+
+        player.release().unwrap();
+        assert_eq!(player.current_source_kind(), None);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_load_url_routes_file_urls_to_disk() {
+        let path = std::env::temp_dir().join("podium url test é.wav");
+        std::fs::write(&path, wav_bytes(8000, 800)).unwrap();
+        let path_str = path.to_string_lossy().to_string();
+        let mut player = PodiumPlayer::new();
+
+        player.load_url(&format!("file://{}", path_str)).unwrap();
+        assert_eq!(player.last_source, Some(SourceKind::File(path_str.clone())));
+        assert_eq!(player.current_source_kind().map(|info| info.kind), Some(SourceOrigin::File));
+
+        let encoded = path_str.replace(' ', "%20").replace('é', "%C3%A9");
+        player.load_url(&format!("file://localhost{}", encoded)).unwrap();
+        assert_eq!(player.last_source, Some(SourceKind::File(path_str.clone())));
+
+        // A plain path is taken as-is
+        player.load_url(&path_str).unwrap();
+        assert_eq!(player.last_source, Some(SourceKind::File(path_str)));
+
+        player.release().unwrap();
+        let _ = std::fs::remove_file(path);
+    }
+
+    /// Local HTTP server that reports each request and answers it (empty 200) after `delay`
+    fn serve_after(delay: std::time::Duration) -> (std::net::SocketAddr, std::sync::mpsc::Receiver<()>) {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request);
+                let _ = tx.send(());
+                thread::sleep(delay);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+            }
+        });
+        (addr, rx)
+    }
+
+    #[test]
+    fn test_url_probe_does_not_hold_the_registry() {
+        let (addr, requests) = serve_after(std::time::Duration::from_millis(800));
+        let id = rust_audio_player_create();
+        let url = std::ffi::CString::new(format!("http://{}/slow.mp3", addr)).unwrap();
+        let loading = thread::spawn(move || rust_audio_player_load_url(id, url.as_ptr()));
+
+        // While the HEAD is outstanding, other calls get the registry straight away
+        requests.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+        let started = std::time::Instant::now();
+        assert_eq!(rust_audio_player_get_state(id), 0);
+        assert!(started.elapsed() < std::time::Duration::from_millis(400));
+
+        assert_eq!(loading.join().unwrap(), 0);
+        assert_eq!(rust_audio_player_release(id), 0);
+    }
+
+    #[test]
+    fn test_bad_url_leaves_the_current_source_playing() {
+        let mut player = PodiumPlayer::new();
+        player.load_buffer(&wav_bytes(8000, 8000)).unwrap();
+        player.play().unwrap();
+
+        let result = player.load_url_with_headers("not a url", &[("Authorization", "Bearer other")]);
+        assert!(matches!(result, Err(AudioError::NetworkError(_))));
+        // Validated before anything was stopped
+        assert!(player.loaded);
+        assert!(player.engine.is_some());
+        assert!(player.source_headers.is_empty());
+        assert_eq!(player.get_state(), PlayerState::Playing);
+        player.release().unwrap();
+    }
+
+    #[test]
+    fn test_load_url_rejects_bad_urls_synchronously() {
+        let mut player = PodiumPlayer::new();
+        player.set_network_timeouts(500, 500).unwrap();
+        for url in ["not a url", "ftp://example.com/a.mp3", "http://127.0.0.1:9/refused.mp3"] {
+            let result = player.load_url(url);
+            assert!(matches!(result, Err(AudioError::NetworkError(_))), "{}: {:?}", url, result.err());
+            // Nothing was started and the state never reached Ready
+            assert!(player.engine.is_none());
+            assert_eq!(player.get_state(), PlayerState::Idle);
+        }
+    }
+
+    /// Delegating reader that fails once with a transient IO error after the first packet
+    struct FlakyReader {
+        inner: Box<dyn symphonia::core::formats::FormatReader>,
+        reads: usize,
+    }
+
+    impl symphonia::core::formats::FormatReader for FlakyReader {
+        fn try_new(
+            _: symphonia::core::io::MediaSourceStream,
+            _: &symphonia::core::formats::FormatOptions,
+        ) -> symphonia::core::errors::Result<Self> {
+            unimplemented!("wraps an already opened reader")
+        }
+
+        fn cues(&self) -> &[symphonia::core::formats::Cue] {
+            self.inner.cues()
+        }
+
+        fn metadata(&mut self) -> symphonia::core::meta::Metadata<'_> {
+            self.inner.metadata()
+        }
+
+        fn seek(
+            &mut self,
+            mode: symphonia::core::formats::SeekMode,
+            to: symphonia::core::formats::SeekTo,
+        ) -> symphonia::core::errors::Result<symphonia::core::formats::SeekedTo> {
+            self.inner.seek(mode, to)
+        }
+
+        fn tracks(&self) -> &[symphonia::core::formats::Track] {
+            self.inner.tracks()
+        }
+
+        fn next_packet(&mut self) -> symphonia::core::errors::Result<symphonia::core::formats::Packet> {
+            self.reads += 1;
+            if self.reads == 2 {
+                return Err(symphonia::core::errors::Error::IoError(std::io::Error::new(
+                    std::io::ErrorKind::Interrupted,
+                    "connection reset mid-stream",
+                )));
+            }
+            self.inner.next_packet()
+        }
+
+        fn into_inner(self: Box<Self>) -> symphonia::core::io::MediaSourceStream {
+            self.inner.into_inner()
+        }
+    }
+
+    #[test]
+    fn test_transient_read_error_does_not_end_playback() {
+        let frames = 8000;
+        let stream = symphonia::core::io::MediaSourceStream::new(
+            Box::new(std::io::Cursor::new(wav_bytes(8000, frames))),
+            Default::default(),
+        );
+        let inner = symphonia::default::get_probe()
+            .format(
+                &Demuxer::create_hint_from_path("fixture.wav"),
+                stream,
+                &Default::default(),
+                &Default::default(),
+            )
+            .unwrap()
+            .format;
+        let mut demuxer = Demuxer::from_format_reader(Box::new(FlakyReader { inner, reads: 0 })).unwrap();
+        let mut decoder = AudioDecoder::from_demuxer(&demuxer).unwrap();
+        let stats = StatsCounters::new();
+
+        let mut consecutive_errors = 0;
+        let mut skipped = 0;
+        let mut decoded_frames = 0;
+        loop {
+            match PlaybackEngine::next_decoded(&mut demuxer, &mut decoder, &stats, 2, &mut consecutive_errors) {
+                DecodeStep::Audio(pcm) => decoded_frames += pcm.len() / 2,
+                DecodeStep::Skipped => skipped += 1,
+                DecodeStep::EndOfStream => break,
+                DecodeStep::Fatal(e) => panic!("unexpected fatal error: {}", e),
+            }
+        }
+
+        // The hiccup is skipped and the whole file still plays out
+        assert_eq!(skipped, 1);
+        assert_eq!(decoded_frames, frames as usize);
+    }
+
+    #[test]
+    fn test_decode_errors_report_stream_position() {
+        // Frames from 50 on (1024 samples each at 44.1 kHz: 1161 ms) use a reserved codebook
+        let frames = 100;
+        let mut adts = adts_aac(frames);
+        let frame_len = adts.len() / frames;
+        for frame in adts.chunks_mut(frame_len).skip(50) {
+            frame[7 + 3] = (frame[7 + 3] & !0x3C) | (0b1100 << 2);
+        }
+
+        let mut demuxer = Demuxer::from_media_source(
+            Box::new(std::io::Cursor::new(adts)),
+            Demuxer::create_hint_from_path("fixture.aac"),
+        )
+        .unwrap();
+        let (mut decoder, _, _) = AudioDecoder::open(&mut demuxer).unwrap();
+        let stats = StatsCounters::new();
+        let mut consecutive_errors = 0;
+        let error = loop {
+            match PlaybackEngine::next_decoded(&mut demuxer, &mut decoder, &stats, 1, &mut consecutive_errors) {
+                DecodeStep::Fatal(e) => break e,
+                DecodeStep::EndOfStream => panic!("corrupt frames were not reported"),
+                _ => {}
+            }
+        };
+
+        // Fatal after the run of skipped frames, so within a few frames of the corruption
+        let position_ms = error.position_ms().expect("decoding error without a position");
+        assert!((1161..1161 + 250).contains(&position_ms), "{}: {}", position_ms, error);
+        assert!(error.to_string().starts_with("Decoding error at 0:01."), "{}", error);
+    }
+
+    #[test]
+    fn test_repeated_errors_become_fatal() {
+        let mut consecutive_errors = 0;
+        for _ in 0..MAX_CONSECUTIVE_ERRORS {
+            let step = PlaybackEngine::skip_error(AudioError::IoError("hiccup".into()), &mut consecutive_errors);
+            assert!(matches!(step, DecodeStep::Skipped));
+        }
+        let step = PlaybackEngine::skip_error(AudioError::IoError("hiccup".into()), &mut consecutive_errors);
+        assert!(matches!(step, DecodeStep::Fatal(_)));
     }
 }
@@ -0,0 +1,358 @@
+// Local playlist files (.m3u / .m3u8 / .pls)
+// Parses a playlist into an ordered list of playable sources, resolving relative
+// paths against the playlist's own directory
+
+use podium_core::{AudioError, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Where a playable entry comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceKind {
+    Http(String),
+    File(String),
+}
+
+/// One playlist entry with the optional hints carried by the playlist format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    pub source: SourceKind,
+    /// Display title (`#EXTINF` in M3U, `TitleN` in PLS)
+    pub title: Option<String>,
+    /// Duration hint in milliseconds (None when absent or given as -1)
+    pub duration_ms: Option<u64>,
+}
+
+/// Read and parse a playlist file, choosing the format from its extension
+pub fn load_playlist<P: AsRef<Path>>(path: P) -> Result<Vec<PlaylistEntry>> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let bytes = std::fs::read(path)
+        .map_err(|e| AudioError::IoError(format!("read playlist {}: {}", path.display(), e)))?;
+    // Older .m3u files are often Latin-1; keep whatever decodes instead of failing the whole list
+    let text = String::from_utf8_lossy(&bytes);
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let entries = match extension.as_str() {
+        "m3u" | "m3u8" => parse_m3u(&text, base_dir),
+        "pls" => parse_pls(&text, base_dir),
+        other => {
+            return Err(AudioError::UnsupportedFormat(format!(
+                "Unsupported playlist type: .{}",
+                other
+            )))
+        }
+    };
+
+    log::info!(
+        "[playlist] {} entries from {}",
+        entries.len(),
+        path.display()
+    );
+    Ok(entries)
+}
+
+/// Parse M3U / extended M3U text
+pub fn parse_m3u(text: &str, base_dir: &Path) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut pending_title = None;
+    let mut pending_duration = None;
+
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // #EXTINF:<seconds>[ attributes],<title>
+            let (head, title) = info.split_once(',').unwrap_or((info, ""));
+            let seconds = head.split_whitespace().next().unwrap_or("");
+            pending_duration = parse_duration_secs(seconds);
+            pending_title = Some(title.trim().to_string()).filter(|t| !t.is_empty());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(PlaylistEntry {
+            source: resolve_location(line, base_dir),
+            title: pending_title.take(),
+            duration_ms: pending_duration.take(),
+        });
+    }
+
+    entries
+}
+
+/// Parse PLS (`[playlist]` with FileN / TitleN / LengthN keys)
+pub fn parse_pls(text: &str, base_dir: &Path) -> Vec<PlaylistEntry> {
+    #[derive(Default)]
+    struct Fields {
+        file: Option<String>,
+        title: Option<String>,
+        length: Option<String>,
+    }
+
+    let mut by_index: BTreeMap<u32, Fields> = BTreeMap::new();
+    for line in text.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim().to_string();
+
+        let (field, index) = if let Some(index) = key.strip_prefix("file") {
+            ("file", index)
+        } else if let Some(index) = key.strip_prefix("title") {
+            ("title", index)
+        } else if let Some(index) = key.strip_prefix("length") {
+            ("length", index)
+        } else {
+            continue;
+        };
+        let Ok(index) = index.parse::<u32>() else {
+            continue;
+        };
+
+        let fields = by_index.entry(index).or_default();
+        match field {
+            "file" => fields.file = Some(value),
+            "title" => fields.title = Some(value).filter(|t| !t.is_empty()),
+            _ => fields.length = Some(value),
+        }
+    }
+
+    by_index
+        .into_values()
+        .filter_map(|fields| {
+            let file = fields.file?;
+            Some(PlaylistEntry {
+                source: resolve_location(&file, base_dir),
+                title: fields.title,
+                duration_ms: fields.length.as_deref().and_then(parse_duration_secs),
+            })
+        })
+        .collect()
+}
+
+/// Seconds as written in playlists; negative means unknown
+fn parse_duration_secs(value: &str) -> Option<u64> {
+    let seconds: f64 = value.trim().parse().ok()?;
+    if seconds < 0.0 {
+        None
+    } else {
+        Some((seconds * 1000.0) as u64)
+    }
+}
+
+/// Turn a playlist location into a source: URLs stay URLs, relative paths are joined to `base_dir`
+fn resolve_location(location: &str, base_dir: &Path) -> SourceKind {
+    let lower = location.to_ascii_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        return SourceKind::Http(location.to_string());
+    }
+
+    let location = local_path(location).unwrap_or_else(|| location.to_string());
+    let path = Path::new(&location);
+    if path.is_absolute() {
+        SourceKind::File(location)
+    } else {
+        SourceKind::File(base_dir.join(path).to_string_lossy().to_string())
+    }
+}
+
+/// The local path behind a `file:` URL or a plain absolute path, None for anything else.
+/// `file:` URLs are percent-decoded; `file:///C:/x` becomes `C:/x` and `file://host/x`
+/// becomes the UNC path `//host/x`. Plain paths are returned as given.
+pub fn local_path(location: &str) -> Option<String> {
+    let Some(rest) = strip_prefix_ignore_case(location, "file:") else {
+        return is_absolute_path(location).then(|| location.to_string());
+    };
+
+    let path = match rest.strip_prefix("//") {
+        Some(authority_and_path) => {
+            let (host, path) = match authority_and_path.find('/') {
+                Some(slash) => authority_and_path.split_at(slash),
+                None => (authority_and_path, ""),
+            };
+            if host.is_empty() || host.eq_ignore_ascii_case("localhost") {
+                path.to_string()
+            } else if is_drive(host) {
+                // Non-standard but common: file://C:/Music/a.mp3
+                format!("{}{}", host, path)
+            } else {
+                format!("//{}{}", host, path)
+            }
+        }
+        None => rest.to_string(),
+    };
+
+    let path = percent_decode(&path);
+    // /C:/Music -> C:/Music
+    match path.strip_prefix('/') {
+        Some(rest) if is_drive(rest.get(..2).unwrap_or("")) => Some(rest.to_string()),
+        _ => Some(path),
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = value.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &value[prefix.len()..])
+}
+
+/// Absolute on either Unix or Windows, whatever platform we run on
+fn is_absolute_path(location: &str) -> bool {
+    let bytes = location.as_bytes();
+    match bytes.first() {
+        Some(b'/') | Some(b'\\') => true,
+        _ => is_drive(location.get(..2).unwrap_or("")) && matches!(bytes.get(2), Some(b'/' | b'\\')),
+    }
+}
+
+/// `C:` style Windows drive
+fn is_drive(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// Decode `%XX` escapes; malformed escapes are kept as written
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &Path) -> SourceKind {
+        SourceKind::File(path.to_string_lossy().to_string())
+    }
+
+    #[test]
+    fn test_parse_m3u_fixture() {
+        let dir = std::env::temp_dir().join(format!("podium_playlist_m3u_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let playlist = dir.join("mix.m3u");
+        std::fs::write(
+            &playlist,
+            "#EXTM3U\n\
+             #EXTINF:123,Artist - First\n\
+             music/first.mp3\n\
+             \n\
+             #EXTINF:-1,Live Stream\n\
+             https://example.com/live.aac\n\
+             /srv/audio/absolute.flac\n",
+        )
+        .unwrap();
+
+        let entries = load_playlist(&playlist).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                PlaylistEntry {
+                    source: file(&dir.join("music/first.mp3")),
+                    title: Some("Artist - First".to_string()),
+                    duration_ms: Some(123_000),
+                },
+                PlaylistEntry {
+                    source: SourceKind::Http("https://example.com/live.aac".to_string()),
+                    title: Some("Live Stream".to_string()),
+                    duration_ms: None,
+                },
+                PlaylistEntry {
+                    source: SourceKind::File("/srv/audio/absolute.flac".to_string()),
+                    title: None,
+                    duration_ms: None,
+                },
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_pls_fixture() {
+        let dir = std::env::temp_dir().join(format!("podium_playlist_pls_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let playlist = dir.join("shows.pls");
+        std::fs::write(
+            &playlist,
+            "[playlist]\n\
+             File2=/var/podcasts/ep2.mp3\n\
+             Title2=Episode 2\n\
+             File1=ep1.mp3\n\
+             Title1=Episode 1\n\
+             Length1=1800\n\
+             Length2=-1\n\
+             NumberOfEntries=2\n\
+             Version=2\n",
+        )
+        .unwrap();
+
+        let entries = load_playlist(&playlist).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].source, file(&dir.join("ep1.mp3")));
+        assert_eq!(entries[0].title.as_deref(), Some("Episode 1"));
+        assert_eq!(entries[0].duration_ms, Some(1_800_000));
+        assert_eq!(entries[1].source, SourceKind::File("/var/podcasts/ep2.mp3".to_string()));
+        assert_eq!(entries[1].duration_ms, None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_local_path_from_file_urls() {
+        let path = local_path;
+        assert_eq!(path("file:///tmp/a.mp3").as_deref(), Some("/tmp/a.mp3"));
+        assert_eq!(path("FILE://localhost/tmp/a.mp3").as_deref(), Some("/tmp/a.mp3"));
+        assert_eq!(path("file:/tmp/a.mp3").as_deref(), Some("/tmp/a.mp3"));
+        assert_eq!(
+            path("file:///home/me/My%20Music/%C3%A9t%C3%A9.flac").as_deref(),
+            Some("/home/me/My Music/été.flac")
+        );
+        assert_eq!(path("file:///tmp/100%.mp3").as_deref(), Some("/tmp/100%.mp3"));
+
+        // Windows forms
+        assert_eq!(path("file:///C:/Music/a%20b.mp3").as_deref(), Some("C:/Music/a b.mp3"));
+        assert_eq!(path("file://C:/Music/a.mp3").as_deref(), Some("C:/Music/a.mp3"));
+        assert_eq!(path("file://server/share/a.mp3").as_deref(), Some("//server/share/a.mp3"));
+
+        // Plain paths pass through untouched; other schemes are not local
+        assert_eq!(path("/tmp/a%20b.mp3").as_deref(), Some("/tmp/a%20b.mp3"));
+        assert_eq!(path(r"C:\Music\a.mp3").as_deref(), Some(r"C:\Music\a.mp3"));
+        assert_eq!(path("https://example.com/a.mp3"), None);
+        assert_eq!(path("content://media/external/audio/1"), None);
+        assert_eq!(path("music/a.mp3"), None);
+    }
+
+    #[test]
+    fn test_unknown_extension_is_rejected() {
+        assert!(load_playlist("list.txt").is_err());
+    }
+}
@@ -0,0 +1,343 @@
+// cpal-based audio renderer for Windows/Linux/macOS
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, FromSample, SampleFormat, SampleRate, SizedSample, Stream, StreamConfig};
+use parking_lot::Mutex;
+use podium_core::{AudioError, Result};
+use podium_renderer::{AudioCallback, AudioRenderer, AudioSpec, RendererFactory};
+use podium_ringbuffer::SharedRingBuffer;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// State shared between the renderer and the output callback
+struct RenderShared {
+    ring_buffer: SharedRingBuffer,
+    is_playing: AtomicBool,
+    user_callback: Mutex<Option<AudioCallback>>,
+}
+
+impl RenderShared {
+    /// Output callback body: user callback if set, otherwise the ring buffer; silence when paused
+    fn fill(&self, data: &mut [f32]) {
+        if !self.is_playing.load(Ordering::Relaxed) {
+            data.fill(0.0);
+            return;
+        }
+
+        let samples_written = if let Some(ref mut callback) = *self.user_callback.lock() {
+            callback(data)
+        } else {
+            self.ring_buffer.read(data)
+        };
+
+        // Zero-fill any unwritten samples to prevent playing stale data
+        if samples_written < data.len() {
+            data[samples_written..].fill(0.0);
+        }
+    }
+}
+
+/// cpal audio renderer
+pub struct CpalRenderer {
+    stream: Option<Stream>,
+    shared: Arc<RenderShared>,
+    sample_rate: u32,
+    channels: u16,
+    buffer_size: usize,
+}
+
+impl CpalRenderer {
+    /// Open the default output device, using the closest supported config to `spec`
+    pub fn new(spec: AudioSpec) -> Result<Self> {
+        let host = cpal::default_host();
+        let device = host
+            .default_output_device()
+            .ok_or_else(|| AudioError::InitializationError("No output device available".to_string()))?;
+        Self::with_device(&device, spec)
+    }
+
+    /// Open a specific output device
+    pub fn with_device(device: &Device, spec: AudioSpec) -> Result<Self> {
+        let (config, sample_format) = Self::pick_stream_config(device, spec)?;
+        log::info!(
+            "[renderer] stream config {}Hz {} ch {:?}",
+            config.sample_rate.0,
+            config.channels,
+            sample_format
+        );
+
+        let mut renderer = Self::headless(AudioSpec {
+            sample_rate: config.sample_rate.0,
+            channels: config.channels,
+            buffer_size: spec.buffer_size,
+        });
+
+        let shared = renderer.shared.clone();
+        let stream = match sample_format {
+            SampleFormat::F32 => Self::build_stream::<f32>(device, &config, shared),
+            SampleFormat::I16 => Self::build_stream::<i16>(device, &config, shared),
+            SampleFormat::U16 => Self::build_stream::<u16>(device, &config, shared),
+            SampleFormat::I32 => Self::build_stream::<i32>(device, &config, shared),
+            SampleFormat::U8 => Self::build_stream::<u8>(device, &config, shared),
+            SampleFormat::F64 => Self::build_stream::<f64>(device, &config, shared),
+            other => Err(AudioError::UnsupportedFormat(format!(
+                "Unsupported output sample format: {:?}",
+                other
+            ))),
+        }?;
+        renderer.stream = Some(stream);
+        Ok(renderer)
+    }
+
+    /// Renderer without an output device (e.g. offline rendering or tests).
+    /// Audio is only produced when the owner calls `pull`.
+    pub fn headless(spec: AudioSpec) -> Self {
+        Self {
+            stream: None,
+            shared: Arc::new(RenderShared {
+                ring_buffer: SharedRingBuffer::new(
+                    spec.sample_rate as usize * spec.channels as usize * 4,
+                ),
+                is_playing: AtomicBool::new(false),
+                user_callback: Mutex::new(None),
+            }),
+            sample_rate: spec.sample_rate,
+            channels: spec.channels,
+            buffer_size: spec.buffer_size,
+        }
+    }
+
+    /// Run the output callback into `data`, as the device would
+    pub fn pull(&self, data: &mut [f32]) {
+        self.shared.fill(data);
+    }
+
+    /// Get shared ring buffer for writing PCM data
+    pub fn get_ring_buffer(&self) -> &SharedRingBuffer {
+        &self.shared.ring_buffer
+    }
+
+    /// Pick a stream config matching `spec` as closely as the device allows.
+    /// Prefers the requested channel count, then the requested rate (clamped to the device range),
+    /// then f32 samples; falls back to the device default config.
+    fn pick_stream_config(device: &Device, spec: AudioSpec) -> Result<(StreamConfig, SampleFormat)> {
+        let default = device
+            .default_output_config()
+            .map_err(|e| AudioError::InitializationError(format!("Output config failed: {}", e)))?;
+
+        let ranges: Vec<_> = match device.supported_output_configs() {
+            Ok(ranges) => ranges.filter(|r| r.channels() == spec.channels).collect(),
+            Err(e) => {
+                log::warn!("[renderer] failed to query output configs ({}); using device default", e);
+                Vec::new()
+            }
+        };
+
+        let best = ranges
+            .iter()
+            .map(|range| {
+                let rate = spec
+                    .sample_rate
+                    .clamp(range.min_sample_rate().0, range.max_sample_rate().0);
+                let exact_rate = rate == spec.sample_rate;
+                let is_f32 = range.sample_format() == SampleFormat::F32;
+                ((exact_rate, is_f32), range.with_sample_rate(SampleRate(rate)))
+            })
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, config)| config);
+
+        let supported = match best {
+            Some(config) => config,
+            None => {
+                log::warn!(
+                    "[renderer] no supported config for {} channels; using device default",
+                    spec.channels
+                );
+                default
+            }
+        };
+        if supported.sample_rate().0 != spec.sample_rate {
+            log::info!(
+                "[renderer] device does not support {}Hz; using {}Hz",
+                spec.sample_rate,
+                supported.sample_rate().0
+            );
+        }
+
+        let sample_format = supported.sample_format();
+        let mut config = supported.config();
+        config.buffer_size = cpal::BufferSize::Default;
+        Ok((config, sample_format))
+    }
+
+    /// Build an output stream for sample type `T`, rendering as f32 and converting per sample
+    fn build_stream<T>(device: &Device, config: &StreamConfig, shared: Arc<RenderShared>) -> Result<Stream>
+    where
+        T: SizedSample + FromSample<f32>,
+    {
+        let mut scratch: Vec<f32> = Vec::new();
+        device
+            .build_output_stream(
+                config,
+                move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                    scratch.resize(data.len(), 0.0);
+                    shared.fill(&mut scratch);
+                    for (out, &sample) in data.iter_mut().zip(scratch.iter()) {
+                        *out = T::from_sample(sample);
+                    }
+                },
+                |err| {
+                    log::error!("Audio stream error: {}", err);
+                },
+                None,
+            )
+            .map_err(|e| AudioError::InitializationError(format!("Failed to build output stream: {}", e)))
+    }
+}
+
+impl AudioRenderer for CpalRenderer {
+    fn start(&mut self) -> Result<()> {
+        if let Some(stream) = &self.stream {
+            stream
+                .play()
+                .map_err(|e| AudioError::PlaybackError(format!("Failed to start stream: {}", e)))?;
+        }
+        self.shared.is_playing.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.shared.is_playing.store(false, Ordering::Relaxed);
+        if let Some(stream) = &self.stream {
+            stream
+                .pause()
+                .map_err(|e| AudioError::PlaybackError(format!("Failed to stop stream: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn pause(&mut self) -> Result<()> {
+        self.shared.is_playing.store(false, Ordering::Relaxed);
+        if let Some(stream) = &self.stream {
+            stream
+                .pause()
+                .map_err(|e| AudioError::PlaybackError(format!("Failed to pause stream: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        if let Some(stream) = &self.stream {
+            stream
+                .play()
+                .map_err(|e| AudioError::PlaybackError(format!("Failed to resume stream: {}", e)))?;
+        }
+        self.shared.is_playing.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn set_audio_callback(&mut self, callback: AudioCallback) -> Result<()> {
+        *self.shared.user_callback.lock() = Some(callback);
+        Ok(())
+    }
+
+    fn get_sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn get_channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn get_buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    fn is_playing(&self) -> bool {
+        self.shared.is_playing.load(Ordering::Relaxed)
+    }
+
+    fn release(&mut self) -> Result<()> {
+        self.stop()?;
+        self.stream = None;
+        Ok(())
+    }
+}
+
+/// Creates `CpalRenderer`s on the default output device
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DesktopRendererFactory;
+
+impl RendererFactory for DesktopRendererFactory {
+    fn create_renderer(&self, spec: AudioSpec) -> Result<Box<dyn AudioRenderer>> {
+        Ok(Box::new(CpalRenderer::new(spec)?))
+    }
+
+    fn get_preferred_spec(&self) -> AudioSpec {
+        let default_config = cpal::default_host()
+            .default_output_device()
+            .and_then(|device| device.default_output_config().ok());
+        match default_config {
+            Some(config) => AudioSpec {
+                sample_rate: config.sample_rate().0,
+                channels: config.channels(),
+                ..AudioSpec::default()
+            },
+            None => AudioSpec::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headless_renderer_pulls_from_callback() {
+        let mut renderer = CpalRenderer::headless(AudioSpec::default());
+        renderer
+            .set_audio_callback(Box::new(|data: &mut [f32]| {
+                let half = data.len() / 2;
+                data[..half].fill(0.5);
+                half
+            }))
+            .unwrap();
+
+        // Paused: silence, callback not consulted
+        let mut out = vec![1.0; 8];
+        renderer.pull(&mut out);
+        assert!(out.iter().all(|&s| s == 0.0));
+
+        renderer.start().unwrap();
+        assert!(renderer.is_playing());
+        renderer.pull(&mut out);
+        assert_eq!(out, [0.5, 0.5, 0.5, 0.5, 0.0, 0.0, 0.0, 0.0]);
+
+        renderer.pause().unwrap();
+        renderer.pull(&mut out);
+        assert!(out.iter().all(|&s| s == 0.0));
+        renderer.release().unwrap();
+    }
+
+    #[test]
+    fn test_headless_renderer_falls_back_to_ring_buffer() {
+        let mut renderer = CpalRenderer::headless(AudioSpec::default());
+        renderer.get_ring_buffer().write(&[0.25; 4]);
+        renderer.start().unwrap();
+
+        let mut out = vec![1.0; 6];
+        renderer.pull(&mut out);
+        assert_eq!(out, [0.25, 0.25, 0.25, 0.25, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_default_device_renderer() {
+        // Skip on machines without an output device
+        let Ok(mut renderer) = DesktopRendererFactory.create_renderer(AudioSpec::default()) else {
+            return;
+        };
+        assert!(renderer.get_sample_rate() > 0);
+        renderer.start().unwrap();
+        renderer.release().unwrap();
+    }
+}
@@ -0,0 +1,19 @@
+// Desktop (Windows/Linux/macOS) audio renderer using cpal
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+mod cpal_renderer;
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub use cpal_renderer::{CpalRenderer, DesktopRendererFactory};
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub struct CpalRenderer;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+impl CpalRenderer {
+    pub fn new(_spec: podium_renderer::AudioSpec) -> podium_core::Result<Self> {
+        Err(podium_core::AudioError::InitializationError(
+            "Cpal renderer is only available on Windows/Linux/macOS".to_string(),
+        ))
+    }
+}
@@ -1,5 +1,45 @@
 // Audio resampling and channel conversion
 
+/// Output channel layout requested by the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelMode {
+    /// Keep the source layout (only adapt to the device)
+    #[default]
+    Auto,
+    /// Downmix everything to mono (played as identical L/R on stereo devices)
+    ForceMono,
+    /// Always produce stereo (mono sources are duplicated, surround keeps front L/R)
+    ForceStereo,
+}
+
+impl ChannelMode {
+    /// Channel count the source should be mixed to before matching the device layout
+    pub fn target_channels(self, source_channels: u16) -> u16 {
+        match self {
+            ChannelMode::Auto => source_channels,
+            ChannelMode::ForceMono => 1,
+            ChannelMode::ForceStereo => 2,
+        }
+    }
+
+    /// Convert from the C ABI / JNI integer representation (0=Auto, 1=ForceMono, 2=ForceStereo)
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(ChannelMode::Auto),
+            1 => Some(ChannelMode::ForceMono),
+            2 => Some(ChannelMode::ForceStereo),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(self) -> i32 {
+        match self {
+            ChannelMode::Auto => 0,
+            ChannelMode::ForceMono => 1,
+            ChannelMode::ForceStereo => 2,
+        }
+    }
+}
 
 /// Audio resampler
 pub struct Resampler {
@@ -37,8 +77,9 @@ impl Resampler {
     }
 
     /// Convert between different channel configurations
-    fn convert_channels(&self, input: &[f32]) -> Vec<f32> {
+    pub fn convert_channels(&self, input: &[f32]) -> Vec<f32> {
         match (self.input_channels, self.output_channels) {
+            (from, to) if from == to => input.to_vec(),
             (1, 2) => {
                 // Mono to stereo: duplicate each sample
                 let mut output = Vec::with_capacity(input.len() * 2);
@@ -56,8 +97,33 @@ impl Resampler {
                 }
                 output
             }
+            (from, 1) if from > 2 => {
+                // Multichannel to mono: average all channels
+                let from = from as usize;
+                input
+                    .chunks_exact(from)
+                    .map(|frame| frame.iter().sum::<f32>() / from as f32)
+                    .collect()
+            }
+            (1, to) if to > 2 => {
+                // Mono to multichannel: same signal on every channel
+                let mut output = Vec::with_capacity(input.len() * to as usize);
+                for &sample in input {
+                    output.extend(std::iter::repeat_n(sample, to as usize));
+                }
+                output
+            }
+            (from, 2) if from > 2 => {
+                // Multichannel to stereo: keep front left/right
+                let mut output = Vec::with_capacity(input.len() / from as usize * 2);
+                for frame in input.chunks_exact(from as usize) {
+                    output.push(frame[0]);
+                    output.push(frame[1]);
+                }
+                output
+            }
             _ => {
-                // Same channel count or unsupported conversion
+                // Unsupported conversion
                 input.to_vec()
             }
         }
@@ -92,3 +158,40 @@ impl Resampler {
 pub fn needs_resampling(input_rate: u32, output_rate: u32, input_channels: u16, output_channels: u16) -> bool {
     input_rate != output_rate || input_channels != output_channels
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stereo_forced_to_mono() {
+        let stereo = [0.2f32, 0.6, -0.4, 0.0];
+        let mono_channels = ChannelMode::ForceMono.target_channels(2);
+        let downmix = Resampler::new(48000, 48000, 2, mono_channels).convert_channels(&stereo);
+        assert_eq!(downmix, vec![0.4, -0.2]);
+
+        // Played on a stereo device both sides carry the same signal
+        let device = Resampler::new(48000, 48000, mono_channels, 2).convert_channels(&downmix);
+        assert_eq!(device.len(), stereo.len());
+        for frame in device.chunks_exact(2) {
+            assert_eq!(frame[0], frame[1]);
+        }
+    }
+
+    #[test]
+    fn test_mono_forced_to_stereo() {
+        let mono = [0.1f32, -0.3, 0.5];
+        let stereo_channels = ChannelMode::ForceStereo.target_channels(1);
+        let output = Resampler::new(44100, 44100, 1, stereo_channels).convert_channels(&mono);
+        assert_eq!(output, vec![0.1, 0.1, -0.3, -0.3, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_channel_mode_round_trip() {
+        for mode in [ChannelMode::Auto, ChannelMode::ForceMono, ChannelMode::ForceStereo] {
+            assert_eq!(ChannelMode::from_i32(mode.as_i32()), Some(mode));
+        }
+        assert_eq!(ChannelMode::from_i32(7), None);
+        assert_eq!(ChannelMode::Auto.target_channels(6), 6);
+    }
+}
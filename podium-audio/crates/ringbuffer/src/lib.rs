@@ -109,60 +109,221 @@ impl AudioRingBuffer {
         }
     }
 
+    /// Resize to `new_size`, keeping the buffered samples in order. Returns false (and leaves the
+    /// buffer alone) when they would not fit.
+    pub fn resize_keeping(&mut self, new_size: usize) -> bool {
+        let buffered = self.available_read();
+        if buffered >= new_size.max(1) {
+            return false;
+        }
+        let mut buffer = vec![0.0; new_size];
+        self.read(&mut buffer[..buffered]);
+        self.buffer = buffer;
+        self.size = new_size;
+        self.read_pos = 0;
+        self.write_pos = buffered;
+        true
+    }
+
     /// Get current buffer size
     pub fn size(&self) -> usize {
         self.size
     }
 
-    /// Get buffer fullness as a percentage (0.0 to 1.0)
+    /// Samples the buffer can hold: one slot stays free to tell full from empty
+    pub fn capacity(&self) -> usize {
+        self.size.saturating_sub(1)
+    }
+
+    /// Get buffer fullness as a percentage of usable capacity (0.0 to 1.0)
     pub fn fullness(&self) -> f32 {
         let used = self.available_read();
-        used as f32 / self.size as f32
+        used as f32 / self.capacity().max(1) as f32
     }
 }
 
+struct SharedState {
+    ring: AudioRingBuffer,
+    /// Set by `clear_and_sync`; writes are refused until the producer acknowledges it
+    reset_pending: bool,
+}
+
 /// Thread-safe wrapper for AudioRingBuffer
+///
+/// One producer thread writes, the consumer reads. Clearing has two forms:
+/// - `clear` is for the producer itself (nothing of its own can be in flight)
+/// - `clear_and_sync` is for any other thread, e.g. a seek. Writes are refused from the
+///   clear until the producer has repositioned and called `acknowledge_reset`, so a packet
+///   decoded before the clear can never land after it.
 #[derive(Clone)]
 pub struct SharedRingBuffer {
-    inner: Arc<Mutex<AudioRingBuffer>>,
+    inner: Arc<Mutex<SharedState>>,
 }
 
 impl SharedRingBuffer {
     pub fn new(size: usize) -> Self {
         Self {
-            inner: Arc::new(Mutex::new(AudioRingBuffer::new(size))),
+            inner: Arc::new(Mutex::new(SharedState {
+                ring: AudioRingBuffer::new(size),
+                reset_pending: false,
+            })),
         }
     }
 
+    /// Returns 0 while a `clear_and_sync` awaits acknowledgement
     pub fn write(&self, data: &[f32]) -> usize {
-        self.inner.lock().write(data)
+        let mut state = self.inner.lock();
+        if state.reset_pending {
+            return 0;
+        }
+        state.ring.write(data)
     }
 
     pub fn read(&self, output: &mut [f32]) -> usize {
-        self.inner.lock().read(output)
+        self.inner.lock().ring.read(output)
     }
 
     pub fn available_write(&self) -> usize {
-        self.inner.lock().available_write()
+        self.inner.lock().ring.available_write()
     }
 
     pub fn available_read(&self) -> usize {
-        self.inner.lock().available_read()
+        self.inner.lock().ring.available_read()
     }
 
+    /// Producer side only: drop everything buffered
     pub fn clear(&self) {
-        self.inner.lock().clear()
+        self.inner.lock().ring.clear()
+    }
+
+    /// Drop everything buffered and refuse writes until the producer calls `acknowledge_reset`
+    pub fn clear_and_sync(&self) {
+        let mut state = self.inner.lock();
+        state.ring.clear();
+        state.reset_pending = true;
+    }
+
+    /// Producer side: whether a `clear_and_sync` is waiting for the producer to reposition
+    pub fn reset_pending(&self) -> bool {
+        self.inner.lock().reset_pending
+    }
+
+    /// Producer side: accept writes again once repositioned after a `clear_and_sync`
+    pub fn acknowledge_reset(&self) {
+        let mut state = self.inner.lock();
+        state.ring.clear();
+        state.reset_pending = false;
     }
 
     pub fn resize(&self, new_size: usize) {
-        self.inner.lock().resize(new_size)
+        self.inner.lock().ring.resize(new_size)
+    }
+
+    /// See `AudioRingBuffer::resize_keeping`
+    pub fn resize_keeping(&self, new_size: usize) -> bool {
+        self.inner.lock().ring.resize_keeping(new_size)
     }
 
     pub fn size(&self) -> usize {
-        self.inner.lock().size()
+        self.inner.lock().ring.size()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().ring.capacity()
     }
 
     pub fn fullness(&self) -> f32 {
-        self.inner.lock().fullness()
+        self.inner.lock().ring.fullness()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::thread;
+
+    #[test]
+    fn test_full_buffer_reads_as_full() {
+        for size in [4, 10, 4096] {
+            let mut ring = AudioRingBuffer::new(size);
+            assert_eq!(ring.fullness(), 0.0);
+            let written = ring.write(&vec![0.5; size * 2]);
+            assert_eq!(written, ring.capacity());
+            assert!(ring.fullness() >= 0.99, "size {}: {}", size, ring.fullness());
+
+            // Still exact once the positions have wrapped
+            let mut out = vec![0.0; size / 2];
+            ring.read(&mut out);
+            ring.write(&vec![0.5; size]);
+            assert!(ring.fullness() >= 0.99, "size {}: {}", size, ring.fullness());
+        }
+    }
+
+    #[test]
+    fn test_resize_keeping_preserves_buffered_samples() {
+        let mut ring = AudioRingBuffer::new(8);
+        ring.write(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        let mut out = [0.0; 4];
+        ring.read(&mut out);
+        ring.write(&[6.0, 7.0, 8.0]);
+
+        // Wrapped contents come out in order after growing
+        assert!(ring.resize_keeping(16));
+        assert_eq!((ring.size(), ring.available_read()), (16, 5));
+        ring.write(&[9.0]);
+        let mut out = [0.0; 6];
+        assert_eq!(ring.read(&mut out), 6);
+        assert_eq!(out, [4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+
+        // Shrinking below what is buffered is refused
+        ring.write(&[1.0; 5]);
+        assert!(!ring.resize_keeping(5));
+        assert_eq!((ring.size(), ring.available_read()), (16, 5));
+        assert!(ring.resize_keeping(6));
+        assert_eq!(ring.available_read(), 5);
+    }
+
+    #[test]
+    fn test_clear_and_sync_drops_stale_writes() {
+        // Samples carry the seek epoch the producer last acknowledged; after the consumer
+        // clears for epoch `e`, nothing older than `e` may be read back
+        let ring = SharedRingBuffer::new(4096);
+        let epoch = Arc::new(AtomicU64::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let producer = {
+            let (ring, epoch, done) = (ring.clone(), epoch.clone(), done.clone());
+            thread::spawn(move || {
+                let mut current = 0;
+                while !done.load(Ordering::SeqCst) {
+                    if ring.reset_pending() {
+                        // "Seek" to the latest epoch before writing again
+                        current = epoch.load(Ordering::SeqCst);
+                        ring.acknowledge_reset();
+                    }
+                    ring.write(&[current as f32; 64]);
+                }
+            })
+        };
+
+        let mut out = vec![0.0; 64];
+        for _ in 0..100 {
+            let cleared_for = epoch.fetch_add(1, Ordering::SeqCst) + 1;
+            ring.clear_and_sync();
+            // Read until the producer has delivered something after the clear
+            let mut received = 0;
+            while received < 256 {
+                let read = ring.read(&mut out);
+                if let Some(stale) = out[..read].iter().find(|&&s| (s as u64) < cleared_for) {
+                    panic!("epoch {} sample read after clearing for {}", stale, cleared_for);
+                }
+                received += read;
+                thread::yield_now();
+            }
+        }
+
+        done.store(true, Ordering::SeqCst);
+        producer.join().unwrap();
     }
 }
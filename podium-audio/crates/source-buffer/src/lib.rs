@@ -1,8 +1,8 @@
 // Network source buffer that bridges HTTP transport to Symphonia MediaSource
 
 use parking_lot::Mutex;
-use podium_core::Result;
-use podium_transport_http::HttpRangeSource;
+use podium_core::{Result, StatsCounters};
+use podium_transport_http::{HttpConfig, HttpRangeSource};
 use std::io::{Read, Seek, SeekFrom};
 use std::sync::Arc;
 use symphonia::core::io::MediaSource;
@@ -10,6 +10,7 @@ use symphonia::core::io::MediaSource;
 /// Network source that provides a MediaSource interface for HTTP streaming
 pub struct NetworkSource {
     inner: Box<dyn MediaSource>,
+    content_type: Option<String>,
 }
 
 impl NetworkSource {
@@ -17,13 +18,40 @@ impl NetworkSource {
     pub fn from_http_range(url: String) -> Result<Self> {
         let source = HttpRangeSource::new(url)?;
         Ok(Self {
+            content_type: source.content_type(),
+            inner: Box::new(source),
+        })
+    }
+
+    /// Create from HTTP Range source, reporting download totals to `stats`
+    pub fn from_http_range_with_stats(url: String, stats: Arc<StatsCounters>) -> Result<Self> {
+        Self::from_http_range_with_config(url, HttpConfig::default(), stats)
+    }
+
+    /// Create from HTTP Range source with custom network timeouts and headers, reporting download totals to `stats`
+    pub fn from_http_range_with_config(
+        url: String,
+        config: HttpConfig,
+        stats: Arc<StatsCounters>,
+    ) -> Result<Self> {
+        let source = HttpRangeSource::with_config(url, config, Some(stats))?;
+        Ok(Self {
+            content_type: source.content_type(),
             inner: Box::new(source),
         })
     }
 
     /// Create from a generic MediaSource
     pub fn from_media_source(source: Box<dyn MediaSource>) -> Self {
-        Self { inner: source }
+        Self {
+            inner: source,
+            content_type: None,
+        }
+    }
+
+    /// `Content-Type` reported by the server (None for non-HTTP sources)
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
     }
 }
 
@@ -1,19 +1,116 @@
 // HTTP client configuration and utilities
 
+use parking_lot::RwLock;
 use podium_core::{AudioError, Result};
 use std::time::Duration;
 
+/// User-Agent sent when the app has not configured one
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (compatible; PodiumAudioPlayer/2.0)";
+
+/// App-configured User-Agent for every agent built afterwards (None = default)
+static USER_AGENT: RwLock<Option<String>> = RwLock::new(None);
+
+/// Set the User-Agent for all HTTP requests made from now on; None or empty restores the default
+pub fn set_user_agent(user_agent: Option<&str>) {
+    let user_agent = user_agent.map(str::trim).filter(|ua| !ua.is_empty());
+    log::info!("HTTP User-Agent -> {}", user_agent.unwrap_or(DEFAULT_USER_AGENT));
+    *USER_AGENT.write() = user_agent.map(str::to_string);
+}
+
+/// User-Agent currently in effect
+pub fn user_agent() -> String {
+    USER_AGENT
+        .read()
+        .clone()
+        .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string())
+}
+
+/// User-tunable network settings for HTTP sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpConfig {
+    /// Time allowed to establish a connection (DNS, TCP and TLS)
+    pub connect_timeout: Duration,
+    /// Time a single read may wait for data before the request fails
+    pub read_timeout: Duration,
+    /// Extra headers sent with every request for this source (e.g. a per-feed Authorization)
+    pub headers: Vec<(String, String)>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(30),
+            read_timeout: Duration::from_secs(60),
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Add this config's extra headers to `request`
+    pub fn apply_headers(&self, request: ureq::Request) -> ureq::Request {
+        self.headers
+            .iter()
+            .fold(request, |request, (name, value)| request.set(name, value))
+    }
+}
+
 /// Create a configured HTTP agent with proper timeouts and settings
 pub fn create_http_agent() -> ureq::Agent {
+    create_http_agent_with(&HttpConfig::default())
+}
+
+/// Create an HTTP agent using the timeouts from `config`
+pub fn create_http_agent_with(config: &HttpConfig) -> ureq::Agent {
     ureq::AgentBuilder::new()
-        .timeout_connect(Duration::from_secs(30))
-        .timeout_read(Duration::from_secs(60))
+        .timeout_connect(config.connect_timeout)
+        .timeout_read(config.read_timeout)
         .timeout_write(Duration::from_secs(30))
-        .user_agent("Mozilla/5.0 (compatible; PodiumAudioPlayer/2.0)")
+        .user_agent(&user_agent())
         .redirects(10)
         .build()
 }
 
+/// Check that `url` is a well-formed http(s) URL, without touching the network
+pub fn validate_url(url: &str) -> Result<()> {
+    let lower = url.to_ascii_lowercase();
+    let rest = lower
+        .strip_prefix("http://")
+        .or_else(|| lower.strip_prefix("https://"))
+        .ok_or_else(|| AudioError::NetworkError(format!("Unsupported URL scheme: {}", url)))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if host.is_empty() || host.starts_with(':') || url.chars().any(char::is_whitespace) {
+        return Err(AudioError::NetworkError(format!("Malformed URL: {}", url)));
+    }
+    Ok(())
+}
+
+/// Quick synchronous reachability check: a HEAD request bounded by the connect timeout.
+/// Connection failures and redirect loops are errors; an HTTP error status is not,
+/// since some servers reject HEAD but serve GET.
+pub fn check_reachable(url: &str, config: &HttpConfig) -> Result<()> {
+    let probe_config = HttpConfig {
+        connect_timeout: config.connect_timeout,
+        read_timeout: config.read_timeout.min(config.connect_timeout),
+        headers: Vec::new(),
+    };
+    let request = create_http_agent_with(&probe_config).head(url);
+    match config.apply_headers(request).call() {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, _)) => {
+            log::warn!("HEAD {} returned {}, continuing", url, code);
+            Ok(())
+        }
+        Err(ureq::Error::Transport(t)) if t.kind() == ureq::ErrorKind::TooManyRedirects => Err(
+            AudioError::NetworkError(format!("Redirect loop or too many redirects: {}", url)),
+        ),
+        Err(ureq::Error::Transport(t)) => Err(AudioError::NetworkError(format!(
+            "Cannot reach {}: {}",
+            url, t
+        ))),
+    }
+}
+
 /// HTTP client wrapper
 pub struct HttpClient {
     agent: ureq::Agent,
@@ -90,3 +187,79 @@ pub fn is_m4a_format(url: &str) -> bool {
     let url_lower = url.to_lowercase();
     url_lower.contains(".m4a") || url_lower.contains(".mp4") || url_lower.contains(".m4b")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://example.com/a.mp3").is_ok());
+        assert!(validate_url("HTTP://example.com:8080").is_ok());
+        for bad in ["", "example.com/a.mp3", "ftp://example.com/a.mp3", "http://", "http:///a.mp3", "http://a b/c"] {
+            assert!(matches!(validate_url(bad), Err(AudioError::NetworkError(_))), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_configured_user_agent_is_sent() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (requests, received) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 1024];
+                let n = stream.read(&mut request).unwrap_or(0);
+                let _ = requests.send(String::from_utf8_lossy(&request[..n]).to_string());
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+            }
+        });
+        let url = format!("http://{}/a.mp3", addr);
+
+        set_user_agent(Some("PodiumTest/1.0 (+https://example.com)"));
+        let client = HttpClient::new();
+        set_user_agent(None);
+        assert_eq!(user_agent(), DEFAULT_USER_AGENT);
+
+        client.head(&url).unwrap();
+        client.get(&url).unwrap();
+        for method in ["HEAD", "GET"] {
+            let request = received.recv().unwrap();
+            assert!(request.starts_with(method), "{}", request);
+            assert!(
+                request
+                    .to_ascii_lowercase()
+                    .contains("\r\nuser-agent: podiumtest/1.0 (+https://example.com)\r\n"),
+                "{}",
+                request
+            );
+        }
+    }
+
+    #[test]
+    fn test_redirect_loop_is_reported() {
+        // Every request is redirected back to itself
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request);
+                let response = format!(
+                    "HTTP/1.1 302 Found\r\nLocation: http://{}/loop\r\nContent-Length: 0\r\n\r\n",
+                    addr
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        let result = check_reachable(&format!("http://{}/loop", addr), &HttpConfig::default());
+        assert!(
+            matches!(result, Err(AudioError::NetworkError(ref msg)) if msg.contains("redirect")),
+            "{:?}",
+            result
+        );
+    }
+}
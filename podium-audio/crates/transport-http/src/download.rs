@@ -1,10 +1,10 @@
 // Progressive download with prebuffering
 
 use crate::client::{create_http_agent, is_m4a_format, retry_request};
+use podium_core::threads::{catch_panic, spawn_named, DOWNLOAD_THREAD};
 use podium_core::{AudioError, Result};
 use std::fs::File;
 use std::io::Write;
-use std::thread;
 
 /// Download audio from URL with progressive buffering
 /// For M4A/MP4 files, downloads the complete file since metadata may be at the end
@@ -102,9 +102,14 @@ pub fn download_with_prebuffer(url: &str, dest_path: &str) -> Result<()> {
             let dest_owned = dest_path.to_string();
             let already_downloaded = total_downloaded;
 
-            thread::spawn(move || {
-                continue_download_in_background(url_owned, dest_owned, already_downloaded)
-            });
+            spawn_named(DOWNLOAD_THREAD, move || {
+                let result = catch_panic(move || {
+                    continue_download_in_background(url_owned, dest_owned, already_downloaded)
+                });
+                if let Err(e) = result {
+                    log::error!("Background download failed: {}", e);
+                }
+            })?;
 
             return Ok(());
         }
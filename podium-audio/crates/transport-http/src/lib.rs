@@ -4,6 +4,9 @@ pub mod client;
 pub mod download;
 pub mod range_source;
 
-pub use client::HttpClient;
+pub use client::{
+    check_reachable, set_user_agent, user_agent, validate_url, HttpClient, HttpConfig,
+    DEFAULT_USER_AGENT,
+};
 pub use download::download_with_prebuffer;
 pub use range_source::HttpRangeSource;
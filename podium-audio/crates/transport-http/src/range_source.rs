@@ -1,8 +1,8 @@
 // HTTP Range-based media source for on-demand streaming
 
-use crate::client::create_http_agent;
+use crate::client::{create_http_agent_with, HttpConfig};
 use parking_lot::Mutex;
-use podium_core::{AudioError, Result};
+use podium_core::{AudioError, Result, StatsCounters};
 use std::io::{Read, Seek, SeekFrom};
 use std::sync::Arc;
 
@@ -23,19 +23,35 @@ struct CacheEntry {
 struct HttpRangeState {
     url: String,
     total_size: Option<u64>,
+    /// `Content-Type` reported by the server, if any
+    content_type: Option<String>,
+    /// Whether the server honours `Range`; None until a response has said so either way
+    ranges_supported: Option<bool>,
+    /// Without range support: the full-body response being read front to back
+    sequential: Option<Box<dyn Read + Send>>,
+    /// Offset of the next byte `sequential` yields
+    sequential_position: u64,
     current_position: u64,
     cache: Vec<CacheEntry>,
     agent: ureq::Agent,
+    config: HttpConfig,
+    stats: Option<Arc<StatsCounters>>,
 }
 
 impl HttpRangeState {
-    fn new(url: String) -> Self {
+    fn new(url: String, config: HttpConfig) -> Self {
         Self {
             url,
             total_size: None,
+            content_type: None,
+            ranges_supported: None,
+            sequential: None,
+            sequential_position: 0,
             current_position: 0,
             cache: Vec::new(),
-            agent: create_http_agent(),
+            agent: create_http_agent_with(&config),
+            config,
+            stats: None,
         }
     }
 
@@ -45,30 +61,49 @@ impl HttpRangeState {
         }
 
         // Try HEAD request to get content length
-        match self.agent.head(&self.url).call() {
+        match self.config.apply_headers(self.agent.head(&self.url)).call() {
             Ok(response) => {
                 self.total_size = response
                     .header("Content-Length")
                     .and_then(|s| s.parse::<u64>().ok());
+                self.content_type = response.header("Content-Type").map(str::to_string);
+                match response.header("Accept-Ranges").map(|v| v.trim().to_ascii_lowercase()) {
+                    Some(ranges) if ranges == "bytes" => self.ranges_supported = Some(true),
+                    Some(ranges) if ranges == "none" => self.ranges_supported = Some(false),
+                    _ => {}
+                }
                 log::info!(
-                    "[range] HEAD ok content-length={:?} url={}",
+                    "[range] HEAD ok content-length={:?} content-type={:?} url={}",
                     self.total_size,
+                    self.content_type,
                     self.url
                 );
             }
-            Err(_) => {
-                // Fallback: try a small range request
-                if let Ok(size) = self.try_get_size_with_range_request() {
-                    self.total_size = size;
-                    log::info!(
-                        "[range] fallback size via Range={:?} url={}",
-                        self.total_size,
-                        self.url
-                    );
-                }
+            // Unreachable host or timeout: a second request would only fail the same way
+            Err(ureq::Error::Transport(e)) => {
+                return Err(AudioError::NetworkError(format!(
+                    "Failed to reach {}: {}",
+                    self.url, e
+                )));
+            }
+            Err(ureq::Error::Status(..)) => {}
+        }
+
+        // Neither a length nor `Accept-Ranges: bytes`: a one-byte range request settles both
+        if self.total_size.is_none() || self.ranges_supported.is_none() {
+            if let Ok(size) = self.try_get_size_with_range_request() {
+                self.total_size = self.total_size.or(size);
+                log::info!(
+                    "[range] fallback size via Range={:?} url={}",
+                    self.total_size,
+                    self.url
+                );
             }
         }
 
+        if self.ranges_supported == Some(false) {
+            log::warn!("[range] server ignores Range; reading sequentially url={}", self.url);
+        }
         if let Some(size) = self.total_size {
             log::info!(
                 "[range] initialized size={} bytes ({:.2} MB) url={}",
@@ -83,23 +118,82 @@ impl HttpRangeState {
         Ok(())
     }
 
-    fn try_get_size_with_range_request(&self) -> Result<Option<u64>> {
+    fn try_get_size_with_range_request(&mut self) -> Result<Option<u64>> {
         let response = self
-            .agent
-            .get(&self.url)
+            .config
+            .apply_headers(self.agent.get(&self.url))
             .set("Range", "bytes=0-0")
             .call()
             .map_err(|e| AudioError::NetworkError(format!("Range request failed: {}", e)))?;
-
-        if let Some(range) = response.header("Content-Range") {
-            if let Some(total) = Self::parse_total_from_content_range(range) {
+        self.content_type = response.header("Content-Type").map(str::to_string);
+
+        if response.status() == 206 {
+            self.ranges_supported = Some(true);
+            if let Some(total) = response
+                .header("Content-Range")
+                .and_then(Self::parse_total_from_content_range)
+            {
                 return Ok(Some(total));
             }
         }
 
-        Ok(response
+        let length = response
             .header("Content-Length")
-            .and_then(|s| s.parse::<u64>().ok()))
+            .and_then(|s| s.parse::<u64>().ok());
+        if response.status() == 200 {
+            // The whole body is on its way: keep reading it rather than asking again
+            self.ranges_supported = Some(false);
+            self.sequential = Some(Box::new(response.into_reader()));
+            self.sequential_position = 0;
+        }
+        Ok(length)
+    }
+
+    /// Read from a server without range support: forward reads continue the open response,
+    /// anything earlier starts the body over and skips ahead
+    fn read_sequential(&mut self, offset: u64, size: usize) -> Result<Vec<u8>> {
+        if self.sequential.is_none() || offset < self.sequential_position {
+            log::info!("[range] sequential restart for offset={}", offset);
+            let response = self
+                .config
+                .apply_headers(self.agent.get(&self.url))
+                .call()
+                .map_err(|e| AudioError::NetworkError(format!("Request failed: {}", e)))?;
+            self.sequential = Some(Box::new(response.into_reader()));
+            self.sequential_position = 0;
+        }
+        let reader = self.sequential.as_mut().expect("sequential reader just opened");
+        let read_err = |e: std::io::Error| AudioError::NetworkError(format!("Failed to read response: {}", e));
+
+        let started = std::time::Instant::now();
+        let mut transferred = 0u64;
+        let mut scratch = vec![0u8; size.clamp(1, CHUNK_SIZE)];
+        while self.sequential_position < offset {
+            let skip = ((offset - self.sequential_position) as usize).min(scratch.len());
+            let n = reader.read(&mut scratch[..skip]).map_err(read_err)?;
+            if n == 0 {
+                return Ok(Vec::new());
+            }
+            self.sequential_position += n as u64;
+            transferred += n as u64;
+        }
+
+        let mut data = vec![0u8; size];
+        let mut filled = 0;
+        while filled < size {
+            let n = reader.read(&mut data[filled..]).map_err(read_err)?;
+            if n == 0 {
+                break;
+            }
+            filled += n;
+        }
+        data.truncate(filled);
+        self.sequential_position += filled as u64;
+        transferred += filled as u64;
+        if let Some(stats) = &self.stats {
+            stats.record_download(transferred, started.elapsed());
+        }
+        Ok(data)
     }
 
     fn parse_total_from_content_range(header: &str) -> Option<u64> {
@@ -126,6 +220,9 @@ impl HttpRangeState {
     }
 
     fn fetch_range(&mut self, offset: u64, size: usize) -> Result<Vec<u8>> {
+        if self.ranges_supported == Some(false) {
+            return self.read_sequential(offset, size);
+        }
         if let Some(data) = self.get_from_cache(offset, size) {
             return Ok(data);
         }
@@ -167,12 +264,22 @@ impl HttpRangeState {
             chunk_size
         );
 
+        let started = std::time::Instant::now();
         let response = self
-            .agent
-            .get(&self.url)
+            .config
+            .apply_headers(self.agent.get(&self.url))
             .set("Range", &format!("bytes={}-{}", offset, end))
             .call()
             .map_err(|e| AudioError::NetworkError(format!("Range request failed: {}", e)))?;
+        if response.status() == 200 {
+            // Range ignored after all: this is the body from byte 0, so read on sequentially
+            log::warn!("[range] server answered a range request with 200; reading sequentially");
+            self.ranges_supported = Some(false);
+            self.cache.clear();
+            self.sequential = Some(Box::new(response.into_reader()));
+            self.sequential_position = 0;
+            return self.read_sequential(offset, size);
+        }
 
         let mut data = Vec::new();
         response
@@ -181,6 +288,9 @@ impl HttpRangeState {
             .map_err(|e| AudioError::NetworkError(format!("Failed to read response: {}", e)))?;
 
         let data_size = data.len();
+        if let Some(stats) = &self.stats {
+            stats.record_download(data_size as u64, started.elapsed());
+        }
 
         // Add to cache with intelligent eviction
         self.cache.push(CacheEntry {
@@ -235,7 +345,18 @@ pub struct HttpRangeSource {
 
 impl HttpRangeSource {
     pub fn new(url: String) -> Result<Self> {
-        let mut state = HttpRangeState::new(url);
+        Self::with_stats(url, None)
+    }
+
+    /// Create a source that reports transferred bytes and throughput to `stats`
+    pub fn with_stats(url: String, stats: Option<Arc<StatsCounters>>) -> Result<Self> {
+        Self::with_config(url, HttpConfig::default(), stats)
+    }
+
+    /// Create a source with custom network timeouts and request headers
+    pub fn with_config(url: String, config: HttpConfig, stats: Option<Arc<StatsCounters>>) -> Result<Self> {
+        let mut state = HttpRangeState::new(url, config);
+        state.stats = stats;
         state.initialize()?;
 
         Ok(Self {
@@ -246,6 +367,11 @@ impl HttpRangeSource {
     pub fn byte_len(&self) -> Option<u64> {
         self.state.lock().total_size
     }
+
+    /// `Content-Type` the server reported for the resource, if any
+    pub fn content_type(&self) -> Option<String> {
+        self.state.lock().content_type.clone()
+    }
 }
 
 impl Read for HttpRangeSource {
@@ -318,10 +444,110 @@ impl Seek for HttpRangeSource {
 // Implement MediaSource for HttpRangeSource
 impl symphonia::core::io::MediaSource for HttpRangeSource {
     fn is_seekable(&self) -> bool {
-        true
+        // Live streams come without a length; ranges into them are not meaningful.
+        // Without range support a seek means downloading everything before the target again.
+        let state = self.state.lock();
+        state.total_size.is_some() && state.ranges_supported != Some(false)
     }
 
     fn byte_len(&self) -> Option<u64> {
         self.state.lock().total_size
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    fn short_timeouts() -> HttpConfig {
+        HttpConfig {
+            connect_timeout: Duration::from_millis(300),
+            read_timeout: Duration::from_millis(300),
+            ..HttpConfig::default()
+        }
+    }
+
+    fn assert_fails_fast(url: &str) {
+        let config = short_timeouts();
+        let started = Instant::now();
+        let result = HttpRangeSource::with_config(url.to_string(), config.clone(), None);
+        let elapsed = started.elapsed();
+
+        assert!(matches!(result, Err(AudioError::NetworkError(_))), "{:?}", result.err());
+        assert!(
+            elapsed < config.connect_timeout + config.read_timeout + Duration::from_millis(500),
+            "took {:?}",
+            elapsed
+        );
+    }
+
+    /// Server that ignores `Range` and always answers 200 with the whole body,
+    /// optionally claiming `Accept-Ranges: bytes` on HEAD anyway
+    fn serve_without_ranges(body: Vec<u8>, claims_ranges: bool) -> std::net::SocketAddr {
+        use std::io::Write;
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 2048];
+                let n = stream.read(&mut request).unwrap_or(0);
+                let head = request.starts_with(b"HEAD");
+                let mut response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+                    body.len(),
+                    if head && claims_ranges { "Accept-Ranges: bytes\r\n" } else { "" }
+                )
+                .into_bytes();
+                if n > 0 && !head {
+                    response.extend_from_slice(&body);
+                }
+                let _ = stream.write_all(&response);
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn test_server_without_ranges_is_read_sequentially() {
+        let body: Vec<u8> = (0..700_000u32).map(|i| (i % 251) as u8).collect();
+        for claims_ranges in [false, true] {
+            let url = format!("http://{}/audio.mp3", serve_without_ranges(body.clone(), claims_ranges));
+            let mut source = HttpRangeSource::with_config(url, short_timeouts(), None).unwrap();
+
+            let mut read = Vec::new();
+            source.read_to_end(&mut read).unwrap();
+            assert!(read == body, "claims_ranges={}", claims_ranges);
+            assert!(!symphonia::core::io::MediaSource::is_seekable(&source));
+
+            // Going back restarts the body; going forward skips ahead in it
+            let mut buf = [0u8; 16];
+            source.seek(SeekFrom::Start(1000)).unwrap();
+            source.read_exact(&mut buf).unwrap();
+            assert_eq!(&buf[..], &body[1000..1016]);
+            source.seek(SeekFrom::Start(600_000)).unwrap();
+            source.read_exact(&mut buf).unwrap();
+            assert_eq!(&buf[..], &body[600_000..600_016]);
+        }
+    }
+
+    #[test]
+    fn test_unresponsive_server_times_out() {
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let _held: Vec<_> = listener.incoming().take(4).collect();
+            std::thread::sleep(Duration::from_secs(5));
+        });
+
+        assert_fails_fast(&format!("http://{}/audio.mp3", addr));
+    }
+
+    #[test]
+    fn test_unroutable_host_times_out() {
+        // Non-routable address: the connection attempt hangs until the connect timeout
+        assert_fails_fast("http://10.255.255.1:81/audio.mp3");
+    }
+}
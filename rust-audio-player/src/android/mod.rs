@@ -2,14 +2,39 @@
 // Oboe provides low-latency audio on Android using OpenSL ES or AAudio
 
 use crate::error::{AudioError, Result};
-use crate::player::{AudioPlayer, PlayerState, PlayerStateContainer, PlaybackStatus};
-use crate::callback::{CallbackEvent, PlayerCallback, CallbackManager};
+use crate::http_transport::validate_url;
+use crate::player::{
+    buffer_source_id, AudioPlayer, PlaybackStats, PlaybackToken, PlayerState, PlayerStateContainer,
+    PlaybackStatus, SeekMode,
+};
+use crate::callback::{CallbackEvent, CallbackHandle, PlayerCallback, CallbackManager};
+use crate::diagnostics::{LoadPhase, PhaseTimer};
 use crate::decoder::{AudioDecoder, AudioRingBuffer};
+use crate::data_uri::parse_data_uri;
+use crate::file_url::local_path;
+use crate::fd_source::FdSource;
+use crate::cue_points::CuePoints;
+use crate::ducking::{clamp_duck_level, DuckGain};
+use crate::latency::{FixedLatency, LatencyMode};
+use crate::limiter::Limiter;
+use crate::output_rate::requested_stream_rate;
+use crate::pcm_tap::{OutputTap, OutputTaps, PcmTap, PcmTaps};
+use crate::power_mode::{buffered_ms, PowerMode};
+use crate::replay_gain::ReplayGainSettings;
+use crate::transcript::{TranscriptCue, TranscriptSlot};
+use crate::playback_rate::{clamp_playback_rate, RateRamp};
+use crate::scratch_buffer::{render_stereo, ScratchBuffer};
+use crate::stream_fallback::{open_with_fallback, StreamPerformance, StreamSharing};
+use crate::threads::{
+    join_within, run_isolated, set_current_thread_priority, spawn_named, ThreadPriority, DECODE_THREAD,
+    RELEASE_JOIN_TIMEOUT,
+};
 use std::sync::Arc;
 use parking_lot::Mutex;
 use std::thread;
+use symphonia::core::probe::Hint;
 use std::sync::atomic::{AtomicBool, Ordering};
-use oboe::{AudioStream, AudioStreamBase};
+use oboe::{AudioStream, AudioStreamBase, AudioStreamSafe};
 use oboe::{
     AudioStreamBuilder,
     AudioStreamAsync,
@@ -32,14 +57,19 @@ const MIN_BUFFER_DURATION_SECS: u64 = 2;
 /// Maximum buffer duration in seconds (to limit memory usage)
 const MAX_BUFFER_DURATION_SECS: u64 = 8;
 
-/// Position update interval (milliseconds)
-const POSITION_UPDATE_INTERVAL_MS: u64 = 100;
-
 /// Audio output callback for Oboe
 struct PlayerAudioCallback {
     ring_buffer: Arc<Mutex<AudioRingBuffer>>,
     is_playing: Arc<AtomicBool>,
     sample_count: Arc<Mutex<u64>>,
+    /// App taps fed with exactly what each callback hands the device
+    output_taps: Arc<OutputTaps>,
+    /// Final stage before the device
+    limiter: Arc<Mutex<Limiter>>,
+    /// Ducks the main output while the app plays a clip over it
+    duck: Arc<Mutex<DuckGain>>,
+    /// Reused interleaved buffer so the audio thread does not allocate per callback
+    scratch: ScratchBuffer,
 }
 
 impl AudioOutputCallback for PlayerAudioCallback {
@@ -47,7 +77,7 @@ impl AudioOutputCallback for PlayerAudioCallback {
 
     fn on_audio_ready(
         &mut self,
-        _stream: &mut dyn oboe::AudioOutputStreamSafe,
+        stream: &mut dyn oboe::AudioOutputStreamSafe,
         output: &mut [(f32, f32)],
     ) -> DataCallbackResult {
         if !self.is_playing.load(Ordering::Relaxed) {
@@ -58,28 +88,24 @@ impl AudioOutputCallback for PlayerAudioCallback {
             return DataCallbackResult::Continue;
         }
 
-        // Read interleaved data from ring buffer
         let frame_count = output.len();
-        let mut interleaved = vec![0.0f32; frame_count * 2]; // 2 channels
-
-        let mut buffer = self.ring_buffer.lock();
-        let samples_read = buffer.read(&mut interleaved);
-        drop(buffer);
-
-        // Convert interleaved to frame format
-        for (i, frame) in output.iter_mut().enumerate() {
-            let idx = i * 2;
-            if idx + 1 < samples_read {
-                frame.0 = interleaved[idx];     // Left channel
-                frame.1 = interleaved[idx + 1]; // Right channel
-            } else {
-                *frame = (0.0, 0.0); // Silence
-            }
-        }
+        let sample_rate = stream.get_sample_rate() as u32;
+        let interleaved = render_stereo(
+            &self.ring_buffer,
+            &mut self.scratch,
+            &self.duck,
+            &self.limiter,
+            sample_rate,
+            output,
+        );
 
         // Update sample count for position tracking
         let mut count = self.sample_count.lock();
+        let played_from = *count;
         *count += frame_count as u64;
+        drop(count);
+
+        self.output_taps.publish(played_from, sample_rate, 2, interleaved);
 
         DataCallbackResult::Continue
     }
@@ -89,6 +115,8 @@ impl AudioOutputCallback for PlayerAudioCallback {
 pub struct AndroidAudioPlayer {
     state_container: PlayerStateContainer,
     callback_manager: Arc<CallbackManager>,
+    /// Durations of the current load's phases and the last seek
+    load_timer: PhaseTimer,
     audio_stream: Option<AudioStreamAsync<Output, PlayerAudioCallback>>,
     ring_buffer: Arc<Mutex<AudioRingBuffer>>,
     is_playing: Arc<AtomicBool>,
@@ -97,8 +125,37 @@ pub struct AndroidAudioPlayer {
     decoder_thread: Option<thread::JoinHandle<()>>,
     stop_decoder: Arc<AtomicBool>,
     decoder: Arc<Mutex<Option<AudioDecoder>>>,
+    /// Identifier of the loaded source, used for resume tokens
+    current_source_id: Option<String>,
+    /// Where the next load starts playing from (see `set_start_position`)
+    start_position_ms: Option<u64>,
     volume: Arc<Mutex<f32>>,
-    playback_rate: Arc<Mutex<f32>>,
+    /// Requested rate and its ramp; status reports the rate currently in effect
+    playback_rate: Arc<Mutex<RateRamp>>,
+    /// App taps fed with every decoded chunk
+    pcm_taps: Arc<PcmTaps>,
+    /// App taps fed from the output callback, in step with playback
+    output_taps: Arc<OutputTaps>,
+    /// Output limiter, kept across streams so its ceiling survives reloads
+    limiter: Arc<Mutex<Limiter>>,
+    /// Duck gain, applied after volume in the output callback
+    duck: Arc<Mutex<DuckGain>>,
+    /// Applied by the decoder thread when it starts
+    thread_priority: ThreadPriority,
+    /// Checked by the decoder thread against the played position
+    cue_points: Arc<CuePoints>,
+    /// Fixed output stream rate; None follows each source
+    target_sample_rate: Option<u32>,
+    /// Read by the decoder thread; the ring buffer length follows on the next load
+    power_mode: Arc<Mutex<PowerMode>>,
+    /// Loudness normalization, handed to the decoder before every packet
+    replay_gain: Arc<Mutex<ReplayGainSettings>>,
+    /// Fetched when the app sets the episode's transcript URL
+    transcript: Arc<TranscriptSlot>,
+    /// Applied when the next stream is opened
+    latency_mode: LatencyMode,
+    /// Buffer sizes in effect for a Fixed latency mode; None in Auto
+    fixed_latency: Arc<Mutex<Option<FixedLatency>>>,
 }
 
 impl AndroidAudioPlayer {
@@ -108,6 +165,7 @@ impl AndroidAudioPlayer {
         Ok(Self {
             state_container: PlayerStateContainer::new(),
             callback_manager: Arc::new(CallbackManager::new()),
+            load_timer: PhaseTimer::default(),
             audio_stream: None,
             ring_buffer: Arc::new(Mutex::new(AudioRingBuffer::new(RING_BUFFER_SIZE))),
             is_playing: Arc::new(AtomicBool::new(false)),
@@ -116,8 +174,22 @@ impl AndroidAudioPlayer {
             decoder_thread: None,
             stop_decoder: Arc::new(AtomicBool::new(false)),
             decoder: Arc::new(Mutex::new(None)),
+            current_source_id: None,
+            start_position_ms: None,
             volume: Arc::new(Mutex::new(1.0)),
-            playback_rate: Arc::new(Mutex::new(1.0)),
+            playback_rate: Arc::new(Mutex::new(RateRamp::default())),
+            pcm_taps: Arc::new(PcmTaps::new()),
+            output_taps: Arc::new(OutputTaps::new()),
+            limiter: Arc::new(Mutex::new(Limiter::new())),
+            duck: Arc::new(Mutex::new(DuckGain::default())),
+            thread_priority: ThreadPriority::default(),
+            cue_points: Arc::new(CuePoints::new()),
+            target_sample_rate: None,
+            power_mode: Arc::new(Mutex::new(PowerMode::default())),
+            replay_gain: Arc::new(Mutex::new(ReplayGainSettings::default())),
+            transcript: Arc::new(TranscriptSlot::new()),
+            latency_mode: LatencyMode::default(),
+            fixed_latency: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -128,6 +200,7 @@ impl AndroidAudioPlayer {
         if let Some(stream) = self.audio_stream.take() {
             drop(stream);
         }
+        self.limiter.lock().reset();
 
         // We support mono and stereo input. Mono is converted to stereo by the decoder.
         // We don't support more than 2 channels
@@ -137,26 +210,42 @@ impl AndroidAudioPlayer {
             ));
         }
 
+        let sample_rate = requested_stream_rate(sample_rate, self.target_sample_rate);
+
         // Note: Output is always stereo. Mono input is converted to stereo in the decoder.
         log::info!("Creating stereo audio stream for playback");
 
-        // Create audio callback
-        let callback = PlayerAudioCallback {
-            ring_buffer: self.ring_buffer.clone(),
-            is_playing: self.is_playing.clone(),
-            sample_count: self.sample_count.clone(),
-        };
-
-        // Build audio stream using type parameters
-        let stream = AudioStreamBuilder::default()
-            .set_performance_mode(PerformanceMode::LowLatency)
-            .set_sharing_mode(SharingMode::Exclusive)
-            .set_format::<f32>()
-            .set_channel_count::<Stereo>()
-            .set_sample_rate(sample_rate as i32)
-            .set_callback(callback)
-            .open_stream()
-            .map_err(|e| AudioError::InitializationError(format!("Failed to open audio stream: {:?}", e)))?;
+        // Prefer exclusive low-latency output, falling back for devices that refuse it.
+        // Each attempt consumes its callback, so build a fresh one per try.
+        let (mut stream, attempt) = open_with_fallback(|attempt| {
+            let callback = PlayerAudioCallback {
+                ring_buffer: self.ring_buffer.clone(),
+                is_playing: self.is_playing.clone(),
+                sample_count: self.sample_count.clone(),
+                output_taps: self.output_taps.clone(),
+                limiter: self.limiter.clone(),
+                duck: self.duck.clone(),
+                scratch: ScratchBuffer::default(),
+            };
+            let performance_mode = match attempt.performance {
+                StreamPerformance::LowLatency => PerformanceMode::LowLatency,
+                StreamPerformance::None => PerformanceMode::None,
+            };
+            let sharing_mode = match attempt.sharing {
+                StreamSharing::Exclusive => SharingMode::Exclusive,
+                StreamSharing::Shared => SharingMode::Shared,
+            };
+
+            AudioStreamBuilder::default()
+                .set_performance_mode(performance_mode)
+                .set_sharing_mode(sharing_mode)
+                .set_format::<f32>()
+                .set_channel_count::<Stereo>()
+                .set_sample_rate(sample_rate as i32)
+                .set_callback(callback)
+                .open_stream()
+        })?;
+        log::info!("Audio stream opened with {:?}", attempt);
 
         let actual_sample_rate = stream.get_sample_rate();
         let resolved_rate = if actual_sample_rate > 0 { actual_sample_rate as u32 } else { sample_rate };
@@ -172,141 +261,397 @@ impl AndroidAudioPlayer {
             );
         }
 
+        // A fixed latency pins the device buffer; the ring buffer gets the rest
+        let fixed_latency = match self.latency_mode {
+            LatencyMode::Auto => None,
+            LatencyMode::Fixed(total_ms) => {
+                let mut plan = FixedLatency::plan(total_ms, resolved_rate, None);
+                match stream.set_buffer_size_in_frames(plan.device_frames as i32) {
+                    // The device may round the request; plan around what it granted
+                    Ok(frames) if frames > 0 => {
+                        let frames = frames as u32;
+                        plan = FixedLatency::plan(total_ms, resolved_rate, Some((frames, frames)));
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Failed to set device buffer size: {:?}", e),
+                }
+                log::info!(
+                    "Fixed latency {} ms: device buffer {} frames, ring buffer {} frames",
+                    plan.latency_ms(),
+                    plan.device_frames,
+                    plan.ring_frames
+                );
+                Some(plan)
+            }
+        };
+        *self.fixed_latency.lock() = fixed_latency;
+
         self.audio_stream = Some(stream);
 
         log::info!("Audio stream initialized successfully");
         Ok(())
     }
 
-    fn start_decoder_thread(&mut self) {
+    /// Reposition the decoder with `seek`, which returns the new sample count
+    fn seek_decoder(&mut self, seek: impl FnOnce(&mut AudioDecoder) -> Result<u64>) -> Result<()> {
+        let seek_started = self.callback_manager.now();
+        let was_playing = self.is_playing.load(Ordering::Relaxed);
+
+        // Pause playback
+        if was_playing {
+            self.is_playing.store(false, Ordering::Relaxed);
+            thread::sleep(std::time::Duration::from_millis(10)); // Wait for audio callback to finish
+        }
+
+        // Clear ring buffer
+        self.ring_buffer.lock().clear();
+
+        // Seek decoder
+        let mut decoder_lock = self.decoder.lock();
+        if let Some(ref mut dec) = *decoder_lock {
+            let new_sample_count = match seek(dec) {
+                Ok(count) => count,
+                Err(e) => {
+                    // A refused seek leaves the decoder where it was: carry on playing
+                    if was_playing {
+                        self.is_playing.store(true, Ordering::Relaxed);
+                    }
+                    return Err(e);
+                }
+            };
+            *self.sample_count.lock() = new_sample_count;
+            self.cue_points.rearm(new_sample_count * 1000 / dec.format.sample_rate.max(1) as u64);
+        } else {
+            return Err(AudioError::PlaybackError("No decoder available".to_string()));
+        }
+        drop(decoder_lock);
+
+        // Resume playback if it was playing
+        if was_playing {
+            self.is_playing.store(true, Ordering::Relaxed);
+        }
+
+        self.load_timer.finish(LoadPhase::Seek, seek_started, &self.callback_manager);
+        log::info!("Seek completed");
+        Ok(())
+    }
+
+    /// Fail on an idle player with no output engine (never loaded, or released), where a
+    /// setting would be silently dropped
+    fn require_engine(&self, operation: &str) -> Result<()> {
+        if self.state_container.get_state() == PlayerState::Idle && self.audio_stream.is_none() {
+            return Err(AudioError::InvalidState(format!(
+                "Cannot {} on an idle player; load a source first",
+                operation
+            )));
+        }
+        Ok(())
+    }
+
+    /// Seek a freshly loaded decoder to the requested start, before playback pulls any audio
+    fn seek_to_start(&mut self, start_position_ms: Option<u64>) -> Result<()> {
+        let Some(position_ms) = start_position_ms.filter(|&ms| ms > 0) else {
+            return Ok(());
+        };
+        log::info!("Starting at {} ms", position_ms);
+        self.seek_decoder(|dec| {
+            let duration_ms = dec.format.duration_ms;
+            let start_ms = if duration_ms > 0 { position_ms.min(duration_ms) } else { position_ms };
+            dec.seek(start_ms)?;
+            Ok((start_ms * dec.format.sample_rate as u64) / 1000)
+        })
+    }
+
+    fn start_decoder_thread(&mut self) -> Result<()> {
         // Stop any existing decoder thread
         self.stop_decoder_thread();
 
         let decoder = self.decoder.clone();
         let ring_buffer = self.ring_buffer.clone();
         let is_playing = self.is_playing.clone();
+        // A fresh flag per pass, so a thread abandoned by `release` is never revived
+        self.stop_decoder = Arc::new(AtomicBool::new(false));
         let stop_decoder = self.stop_decoder.clone();
         let sample_count = self.sample_count.clone();
         let output_sample_rate = self.output_sample_rate.clone();
         let callback_manager = self.callback_manager.clone();
         let volume = self.volume.clone();
         let state_container = self.state_container.clone();
+        let pcm_taps = self.pcm_taps.clone();
+        let thread_priority = self.thread_priority;
+        let cue_points = self.cue_points.clone();
+        let power_mode = self.power_mode.clone();
+        let replay_gain = self.replay_gain.clone();
+        let fixed_latency = self.fixed_latency.clone();
+
+        // A new pass starts from the current position, with this track's chapters
+        let start_ms = self.get_status().position_ms;
+        let chapters = self
+            .decoder
+            .lock()
+            .as_ref()
+            .map(|dec| dec.metadata.chapters.clone())
+            .unwrap_or_default();
+        cue_points.sync_chapters(&chapters, start_ms);
+        cue_points.rearm(start_ms);
+
+        let panic_playing = self.is_playing.clone();
+        let panic_callbacks = self.callback_manager.clone();
+        let panic_state = self.state_container.clone();
+
+        let handle = spawn_named(DECODE_THREAD, move || {
+            let _ = run_isolated(&panic_playing, &panic_callbacks, &panic_state, move || {
+                log::info!("Decoder thread started");
+                if let Err(e) = set_current_thread_priority(thread_priority) {
+                    log::warn!("{}", e);
+                }
 
-        stop_decoder.store(false, Ordering::Relaxed);
-
-        let handle = thread::spawn(move || {
-            log::info!("Decoder thread started");
-
-            let mut last_position_update = std::time::Instant::now();
+                let mut last_position_update = callback_manager.now();
 
-            loop {
-                if stop_decoder.load(Ordering::Relaxed) {
-                    log::info!("Decoder thread stopping");
-                    break;
-                }
+                loop {
+                    if stop_decoder.load(Ordering::Relaxed) {
+                        log::info!("Decoder thread stopping");
+                        break;
+                    }
 
-                if !is_playing.load(Ordering::Relaxed) {
-                    // Sleep when not playing
-                    thread::sleep(std::time::Duration::from_millis(10));
-                    continue;
-                }
+                    if !is_playing.load(Ordering::Relaxed) {
+                        // Sleep when not playing
+                        thread::sleep(power_mode.lock().idle_sleep());
+                        continue;
+                    }
 
-                // Decode audio packets and get format info
-                let decode_result = {
-                    let mut decoder_lock = decoder.lock();
-                    if let Some(ref mut dec) = *decoder_lock {
-                        let sample_rate = dec.format.sample_rate;
-                        let duration_ms = dec.format.duration_ms;
-                        match dec.decode_next() {
-                            Ok(Some(mut samples)) => {
-                                // Apply volume (skip if volume is 1.0 to avoid unnecessary multiplication)
-                                let vol = *volume.lock();
-                                if (vol - 1.0).abs() > 0.001 {
-                                    for sample in samples.iter_mut() {
-                                        *sample *= vol;
+                    // Decode audio packets and get format info
+                    let decode_result = {
+                        let mut decoder_lock = decoder.lock();
+                        if let Some(ref mut dec) = *decoder_lock {
+                            dec.set_replay_gain(*replay_gain.lock());
+                            let decoded = match dec.decode_next() {
+                                // One bad packet can leave the codec inconsistent: reset it and
+                                // carry on from the next packet; a second failure is reported
+                                Err(e @ AudioError::DecodingError(_)) => {
+                                    log::warn!("{}; resetting codec", e);
+                                    dec.reset_codec();
+                                    dec.decode_next()
+                                }
+                                decoded => decoded,
+                            };
+                            dec.dispatch_changes(&callback_manager);
+                            let sample_rate = dec.format.sample_rate;
+                            let status = dec.status();
+                            match decoded {
+                                Ok(Some(mut samples)) => {
+                                    pcm_taps.publish(&samples);
+
+                                    // Apply volume (skip if volume is 1.0 to avoid unnecessary multiplication)
+                                    let vol = *volume.lock();
+                                    if (vol - 1.0).abs() > 0.001 {
+                                        for sample in samples.iter_mut() {
+                                            *sample *= vol;
+                                        }
                                     }
+                                    Some((samples, sample_rate, status))
+                                }
+                                Ok(None) => None,
+                                Err(e) => {
+                                    log::error!("Decoding error: {}", e);
+                                    callback_manager.dispatch_event(CallbackEvent::Error {
+                                        message: e.to_string(),
+                                    });
+                                    is_playing.store(false, Ordering::Relaxed);
+                                    state_container.set_state(PlayerState::Error);
+                                    return;  // Exit decode_result block
                                 }
-                                Some((samples, sample_rate, duration_ms))
-                            }
-                            Ok(None) => None,
-                            Err(e) => {
-                                log::error!("Decoding error: {}", e);
-                                callback_manager.dispatch_event(CallbackEvent::Error {
-                                    message: e.to_string(),
-                                });
-                                is_playing.store(false, Ordering::Relaxed);
-                                state_container.set_state(PlayerState::Error);
-                                return;  // Exit decode_result block
                             }
-                        }
-                    } else {
-                        return;  // No decoder, exit decode_result block
-                    }
-                };  // decoder_lock is released here
-
-                match decode_result {
-                    Some((samples, sample_rate, duration_ms)) => {
-                        let target_sample_rate = *output_sample_rate.lock();
-                        let (processed_samples, rate_for_position) = if target_sample_rate > 0 && target_sample_rate != sample_rate {
-                            let resampled = Self::resample_stereo(&samples, sample_rate, target_sample_rate);
-                            (resampled, target_sample_rate)
                         } else {
-                            (samples, sample_rate)
-                        };
-
-                        // Write to ring buffer (decoder lock already released)
-                        let mut buffer = ring_buffer.lock();
-                        let mut written = 0;
-                        while written < processed_samples.len() {
-                            let w = buffer.write(&processed_samples[written..]);
-                            if w == 0 {
-                                // Buffer is full - sleep based on fullness
-                                let fullness = buffer.fullness();
-                                drop(buffer);
-
-                                // Smart sleep: longer sleep when buffer is fuller
-                                let sleep_ms = if fullness > 0.9 {
-                                    15  // Buffer >90% full: long sleep
-                                } else if fullness > 0.7 {
-                                    10  // Buffer >70% full: medium sleep
-                                } else {
-                                    5   // Buffer <70% full: short sleep
-                                };
-                                thread::sleep(std::time::Duration::from_millis(sleep_ms));
-                                buffer = ring_buffer.lock();
+                            return;  // No decoder, exit decode_result block
+                        }
+                    };  // decoder_lock is released here
+
+                    match decode_result {
+                        Some((samples, sample_rate, status)) => {
+                            let target_sample_rate = *output_sample_rate.lock();
+                            let (processed_samples, rate_for_position) = if target_sample_rate > 0 && target_sample_rate != sample_rate {
+                                let resampled = Self::resample_stereo(&samples, sample_rate, target_sample_rate);
+                                (resampled, target_sample_rate)
                             } else {
-                                written += w;
+                                (samples, sample_rate)
+                            };
+
+                            // Write to ring buffer (decoder lock already released)
+                            let mut buffer = ring_buffer.lock();
+                            let mut written = 0;
+                            while written < processed_samples.len() {
+                                let w = buffer.write(&processed_samples[written..]);
+                                if w == 0 {
+                                    // Nothing drains a full ring once playback stops: give up the packet
+                                    if stop_decoder.load(Ordering::Relaxed) {
+                                        break;
+                                    }
+                                    // Buffer is full - sleep based on fullness and power mode
+                                    let fullness = buffer.fullness();
+                                    let buffered = buffered_ms(buffer.available_read(), 2, rate_for_position);
+                                    drop(buffer);
+
+                                    let sleep = match *fixed_latency.lock() {
+                                        Some(fixed) => fixed.refill_sleep(),
+                                        None => power_mode.lock().full_buffer_sleep(fullness, buffered),
+                                    };
+                                    thread::sleep(sleep);
+                                    buffer = ring_buffer.lock();
+                                } else {
+                                    written += w;
+                                }
                             }
-                        }
-                        drop(buffer);
+                            drop(buffer);
 
-                        // Update position periodically
-                        if last_position_update.elapsed().as_millis() >= POSITION_UPDATE_INTERVAL_MS as u128 {
                             let count = *sample_count.lock();
                             let effective_rate = if rate_for_position > 0 { rate_for_position } else { sample_rate };
                             let position_ms = (count * 1000) / effective_rate as u64;
-                            callback_manager.dispatch_event(CallbackEvent::PositionChanged {
-                                position_ms,
-                                duration_ms,
-                            });
-                            last_position_update = std::time::Instant::now();
+                            let position_ms = match *fixed_latency.lock() {
+                                Some(fixed) => fixed.audible_position_ms(position_ms),
+                                None => position_ms,
+                            };
+                            let position_ms = status.source_position_ms(position_ms);
+                            cue_points.dispatch_reached(position_ms, &callback_manager);
+
+                            // Update position periodically
+                            let interval_ms = power_mode
+                                .lock()
+                                .position_interval_ms(callback_manager.position_update_interval_ms());
+                            let now = callback_manager.now();
+                            let since_update = now.saturating_duration_since(last_position_update);
+                            if since_update.as_millis() >= interval_ms as u128 {
+                                callback_manager.dispatch_event(CallbackEvent::PositionChanged {
+                                    position_ms,
+                                    duration_ms: status.duration_ms,
+                                });
+                                last_position_update = now;
+                            }
+                        }
+                        None => {
+                            // End of stream
+                            log::info!("Playback completed");
+                            is_playing.store(false, Ordering::Relaxed);
+                            callback_manager.dispatch_event(CallbackEvent::PlaybackCompleted);
+                            state_container.set_state(PlayerState::Stopped);
+                            break;
                         }
-                    }
-                    None => {
-                        // End of stream
-                        log::info!("Playback completed");
-                        is_playing.store(false, Ordering::Relaxed);
-                        callback_manager.dispatch_event(CallbackEvent::PlaybackCompleted);
-                        state_container.set_state(PlayerState::Stopped);
-                        break;
                     }
                 }
-            }
 
-            log::info!("Decoder thread exited");
-        });
+                log::info!("Decoder thread exited");
+            });
+        })?;
 
         self.decoder_thread = Some(handle);
+        Ok(())
+    }
+
+    /// Load audio from an open file descriptor, e.g. a `content://` document or an APK asset
+    /// Plays `length` bytes from `start_offset` (everything after it when None). The descriptor is
+    /// duplicated, so the caller may close its own copy once this returns.
+    pub fn load_fd(&mut self, fd: i32, start_offset: u64, length: Option<u64>) -> Result<()> {
+        log::info!("Loading audio from file descriptor {} (offset {}, length {:?})", fd, start_offset, length);
+        // A descriptor cannot be reopened later, so there is nothing to resume from
+        self.current_source_id = None;
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
+
+        self.state_container.set_state(PlayerState::Loading);
+        self.callback_manager.dispatch_event(CallbackEvent::StateChanged {
+            old_state: PlayerState::Idle,
+            new_state: PlayerState::Loading,
+        });
+
+        // Stop any ongoing playback
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread();
+
+        // Clear ring buffer
+        self.ring_buffer.lock().clear();
+        *self.sample_count.lock() = 0;
+
+        let source = FdSource::new(fd, start_offset, length)?;
+        let decoder = AudioDecoder::from_streaming_source(Box::new(source), Hint::new())?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
+        let sample_rate = decoder.format.sample_rate;
+        let channels = decoder.format.channels;
+
+        // Initialize audio stream with the correct format
+        let stream_started = self.callback_manager.now();
+        self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
+
+        // Store decoder
+        *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
+
+        // Optimize buffer size based on audio duration
+        self.optimize_buffer_size();
+
+        self.state_container.set_state(PlayerState::Ready);
+        self.callback_manager.dispatch_event(CallbackEvent::StateChanged {
+            old_state: PlayerState::Loading,
+            new_state: PlayerState::Ready,
+        });
+
+        log::info!("Audio loaded from file descriptor");
+        Ok(())
+    }
+
+    /// Load encoded audio held in memory (`load_buffer`, data: URIs)
+    fn load_memory(&mut self, buffer: &[u8], hint: Hint) -> Result<()> {
+        log::info!("Loading audio from buffer: {} bytes", buffer.len());
+        self.current_source_id = Some(buffer_source_id(buffer));
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
+
+        self.state_container.set_state(PlayerState::Loading);
+        self.callback_manager.dispatch_event(CallbackEvent::StateChanged {
+            old_state: PlayerState::Idle,
+            new_state: PlayerState::Loading,
+        });
+
+        // Stop any ongoing playback
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread();
+
+        // Clear ring buffer
+        self.ring_buffer.lock().clear();
+        *self.sample_count.lock() = 0;
+
+        // Load the audio buffer
+        let decoder = AudioDecoder::from_buffer_with_hint(buffer.to_vec(), hint)?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
+        let sample_rate = decoder.format.sample_rate;
+        let channels = decoder.format.channels;
+
+        // Initialize audio stream
+        let stream_started = self.callback_manager.now();
+        self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
+
+        // Store decoder
+        *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
+
+        // Optimize buffer size based on audio duration
+        self.optimize_buffer_size();
+
+        self.state_container.set_state(PlayerState::Ready);
+        self.callback_manager.dispatch_event(CallbackEvent::StateChanged {
+            old_state: PlayerState::Loading,
+            new_state: PlayerState::Ready,
+        });
+
+        log::info!("Audio buffer loaded successfully");
+        Ok(())
     }
 
     /// Optimize ring buffer size based on audio duration
@@ -322,10 +667,14 @@ impl AndroidAudioPlayer {
             // Calculate optimal buffer duration
             let buffer_duration_secs = duration_secs
                 .max(MIN_BUFFER_DURATION_SECS)
-                .min(MAX_BUFFER_DURATION_SECS);
+                .min(self.power_mode.lock().max_buffer_secs(MAX_BUFFER_DURATION_SECS));
 
             // Calculate buffer size in samples
-            let optimal_size = (sample_rate as u64 * channels as u64 * buffer_duration_secs) as usize;
+            // The ring holds the stereo output in Fixed mode
+            let optimal_size = match *self.fixed_latency.lock() {
+                Some(fixed) => fixed.ring_samples(2),
+                None => (sample_rate as u64 * channels as u64 * buffer_duration_secs) as usize,
+            };
 
             let current_size = self.ring_buffer.lock().size();
 
@@ -354,11 +703,25 @@ impl AndroidAudioPlayer {
         }
     }
 
+    /// Stop the decoder thread, waiting at most `timeout` for it (it may be stuck in network
+    /// I/O or in an app callback); a thread still running then is left to exit on its own
+    fn stop_decoder_thread_within(&mut self, timeout: std::time::Duration) {
+        self.stop_decoder.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.decoder_thread.take() {
+            if !join_within(handle, timeout) {
+                log::warn!("Decoder thread did not stop within {:?}; abandoning it", timeout);
+            }
+        }
+    }
+
 }
 
 impl AudioPlayer for AndroidAudioPlayer {
     fn load_file(&mut self, path: &str) -> Result<()> {
         log::info!("Loading audio file: {}", path);
+        self.current_source_id = Some(path.to_string());
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
 
         self.state_container.set_state(PlayerState::Loading);
         self.callback_manager.dispatch_event(CallbackEvent::StateChanged {
@@ -376,14 +739,21 @@ impl AudioPlayer for AndroidAudioPlayer {
 
         // Load the audio file
         let decoder = AudioDecoder::from_file(path)?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
         let sample_rate = decoder.format.sample_rate;
         let channels = decoder.format.channels;
 
         // Initialize audio stream with the correct format
+        let stream_started = self.callback_manager.now();
         self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
 
         // Store decoder
         *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
 
         // Optimize buffer size based on audio duration
         self.optimize_buffer_size();
@@ -399,7 +769,21 @@ impl AudioPlayer for AndroidAudioPlayer {
     }
 
     fn load_url(&mut self, url: &str) -> Result<()> {
+        // file: URLs and plain paths (as file pickers hand them out) play from disk
+        if let Some(path) = local_path(url) {
+            return self.load_file(&path);
+        }
+        // Small embedded audio: decode the payload in memory instead of fetching anything
+        if let Some(data_uri) = parse_data_uri(url)? {
+            log::info!("Loading audio from {} data URI", data_uri.mime_type);
+            return self.load_memory(&data_uri.data, data_uri.hint());
+        }
+        // Reject malformed URLs here, before the current source is stopped
+        validate_url(url)?;
         log::info!("Loading audio from URL (streaming): {}", url);
+        self.current_source_id = Some(url.to_string());
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
 
         self.state_container.set_state(PlayerState::Loading);
         self.callback_manager.dispatch_event(CallbackEvent::StateChanged {
@@ -415,14 +799,17 @@ impl AudioPlayer for AndroidAudioPlayer {
         self.ring_buffer.lock().clear();
         *self.sample_count.lock() = 0;
 
-        // Create hint from URL
-        let hint = AudioDecoder::create_hint_from_url(url);
-
         // Use HTTP Range-based source for true streaming without downloading entire file
         // This supports both Fast Start and Non-Fast Start M4A files
         log::info!("Using HTTP Range source (on-demand download)");
-        let source = crate::http_range_source::HttpRangeSource::new(url.to_string())?;
-        let decoder = AudioDecoder::from_streaming_source(Box::new(source), hint)?;
+        let callbacks = &self.callback_manager;
+        let decoder = AudioDecoder::from_url_with_stages(url, |stage| {
+            callbacks.dispatch_event(CallbackEvent::StreamPreparing { stage })
+        })?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
 
         let sample_rate = decoder.format.sample_rate;
         let channels = decoder.format.channels;
@@ -430,10 +817,13 @@ impl AudioPlayer for AndroidAudioPlayer {
         log::info!("Streaming decoder created: {}Hz, {} channels", sample_rate, channels);
 
         // Initialize audio stream with the correct format
+        let stream_started = self.callback_manager.now();
         self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
 
         // Store decoder
         *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
 
         // Optimize buffer size based on audio duration
         self.optimize_buffer_size();
@@ -449,37 +839,22 @@ impl AudioPlayer for AndroidAudioPlayer {
     }
 
     fn load_buffer(&mut self, buffer: &[u8]) -> Result<()> {
-        log::info!("Loading audio from buffer: {} bytes", buffer.len());
-
-        self.state_container.set_state(PlayerState::Loading);
-
-        // Stop any ongoing playback
-        self.is_playing.store(false, Ordering::Relaxed);
-        self.stop_decoder_thread();
-
-        // Clear ring buffer
-        self.ring_buffer.lock().clear();
-        *self.sample_count.lock() = 0;
-
-        // Load the audio buffer
-        let decoder = AudioDecoder::from_buffer(buffer.to_vec())?;
-        let sample_rate = decoder.format.sample_rate;
-        let channels = decoder.format.channels;
-
-        // Initialize audio stream
-        self.initialize_audio_stream(sample_rate, channels)?;
-
-        // Store decoder
-        *self.decoder.lock() = Some(decoder);
-
-        // Optimize buffer size based on audio duration
-        self.optimize_buffer_size();
+        self.load_memory(buffer, Hint::new())
+    }
 
-        self.state_container.set_state(PlayerState::Ready);
-        log::info!("Audio buffer loaded successfully");
+    fn set_start_position(&mut self, position_ms: u64) -> Result<()> {
+        self.start_position_ms = Some(position_ms);
         Ok(())
     }
 
+    fn get_resume_token(&self) -> Option<PlaybackToken> {
+        let source_id = self.current_source_id.clone()?;
+        Some(PlaybackToken {
+            source_id,
+            position_ms: self.get_status().position_ms,
+        })
+    }
+
     fn play(&mut self) -> Result<()> {
         log::info!("Starting playback");
 
@@ -500,7 +875,7 @@ impl AudioPlayer for AndroidAudioPlayer {
 
         // Start decoder thread if not already running
         if self.decoder_thread.is_none() {
-            self.start_decoder_thread();
+            self.start_decoder_thread()?;
         }
 
         self.is_playing.store(true, Ordering::Relaxed);
@@ -556,9 +931,10 @@ impl AudioPlayer for AndroidAudioPlayer {
         self.ring_buffer.lock().clear();
         *self.sample_count.lock() = 0;
 
+        let old_state = self.state_container.get_state();
         self.state_container.set_state(PlayerState::Stopped);
         self.callback_manager.dispatch_event(CallbackEvent::StateChanged {
-            old_state: self.state_container.get_state(),
+            old_state,
             new_state: PlayerState::Stopped,
         });
 
@@ -568,41 +944,182 @@ impl AudioPlayer for AndroidAudioPlayer {
 
     fn seek(&mut self, position_ms: u64) -> Result<()> {
         log::info!("Seeking to {} ms", position_ms);
+        self.seek_decoder(|dec| {
+            dec.seek(position_ms)?;
+            Ok((position_ms * dec.format.sample_rate as u64) / 1000)
+        })
+    }
+
+    fn seek_with_mode(&mut self, position_ms: u64, mode: SeekMode) -> Result<()> {
+        log::info!("Seeking to {} ms ({:?})", position_ms, mode);
+        self.seek_decoder(|dec| {
+            let landed_ms = dec.seek_with_mode(position_ms, mode)?;
+            Ok((landed_ms * dec.format.sample_rate as u64) / 1000)
+        })
+    }
+
+    fn set_looping(&mut self, enabled: bool) -> Result<()> {
+        log::info!("Sample-accurate looping: {}", enabled);
+        let position_ms = self.get_status().position_ms;
+        self.seek_decoder(|dec| {
+            dec.set_sample_loop(enabled, position_ms)?;
+            Ok((position_ms * dec.format.sample_rate as u64) / 1000)
+        })
+    }
+
+    fn seek_to_sample(&mut self, frame_index: u64) -> Result<()> {
+        log::info!("Seeking to frame {}", frame_index);
+        self.seek_decoder(|dec| {
+            dec.seek_to_sample(frame_index)?;
+            Ok(frame_index)
+        })
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        log::info!("Flushing audio pipeline");
 
+        let position_ms = self.get_status().position_ms;
         let was_playing = self.is_playing.load(Ordering::Relaxed);
 
-        // Pause playback
+        self.callback_manager.dispatch_event(CallbackEvent::BufferingChanged {
+            buffering: true,
+        });
+
         if was_playing {
             self.is_playing.store(false, Ordering::Relaxed);
             thread::sleep(std::time::Duration::from_millis(10)); // Wait for audio callback to finish
         }
 
-        // Clear ring buffer
         self.ring_buffer.lock().clear();
 
-        // Seek decoder
-        let mut decoder_lock = self.decoder.lock();
-        if let Some(ref mut dec) = *decoder_lock {
-            dec.seek(position_ms)?;
-
-            // Update sample count
-            let new_sample_count = (position_ms * dec.format.sample_rate as u64) / 1000;
-            *self.sample_count.lock() = new_sample_count;
-        } else {
-            return Err(AudioError::PlaybackError("No decoder available".to_string()));
-        }
-        drop(decoder_lock);
+        // Rewind the decoder to the position that was actually played; the decoder thread refills
+        let result = match self.decoder.lock().as_mut() {
+            Some(dec) => dec.seek(position_ms),
+            None => Err(AudioError::PlaybackError("No decoder available".to_string())),
+        };
 
-        // Resume playback if it was playing
         if was_playing {
             self.is_playing.store(true, Ordering::Relaxed);
         }
 
-        log::info!("Seek completed");
+        self.callback_manager.dispatch_event(CallbackEvent::BufferingChanged {
+            buffering: false,
+        });
+
+        result
+    }
+
+    fn add_pcm_tap(&mut self, tap: PcmTap) -> Result<()> {
+        self.pcm_taps.add(tap)
+    }
+
+    fn add_output_tap(&mut self, tap: OutputTap) -> Result<()> {
+        self.output_taps.add(tap)
+    }
+
+    fn set_limiter_ceiling(&mut self, ceiling_db: Option<f32>) -> Result<()> {
+        self.limiter.lock().set_ceiling_db(ceiling_db)
+    }
+
+    fn duck(&mut self, level: f32, fade_ms: u64) -> Result<()> {
+        let level = clamp_duck_level(level)?;
+        self.duck.lock().fade_to(level, fade_ms);
         Ok(())
     }
 
+    fn unduck(&mut self, fade_ms: u64) -> Result<()> {
+        self.duck.lock().fade_to(1.0, fade_ms);
+        Ok(())
+    }
+
+    fn write_cover_art(&self, path: &str) -> Result<()> {
+        match self.decoder.lock().as_ref() {
+            Some(decoder) => decoder.write_cover_art(path),
+            None => Err(AudioError::InvalidState("No audio loaded".to_string())),
+        }
+    }
+
+    fn set_target_sample_rate(&mut self, sample_rate: Option<u32>) -> Result<()> {
+        if sample_rate == Some(0) {
+            return Err(AudioError::InvalidState("Target sample rate must be positive".to_string()));
+        }
+        self.target_sample_rate = sample_rate;
+        Ok(())
+    }
+
+    fn set_power_mode(&mut self, mode: PowerMode) -> Result<()> {
+        log::info!("Power mode: {:?}", mode);
+        *self.power_mode.lock() = mode;
+        Ok(())
+    }
+
+    fn set_replay_gain(&mut self, settings: ReplayGainSettings) -> Result<()> {
+        settings.validate()?;
+        log::info!("ReplayGain: {:?}", settings);
+        *self.replay_gain.lock() = settings;
+        Ok(())
+    }
+
+    fn set_thread_priority(&mut self, priority: ThreadPriority) -> Result<()> {
+        self.thread_priority = priority;
+        Ok(())
+    }
+
+    fn wait_until_complete(&self, timeout: Option<std::time::Duration>) -> Result<()> {
+        self.state_container.wait_until_complete(timeout)
+    }
+
+    fn add_cue_point(&mut self, id: &str, position_ms: u64) -> Result<()> {
+        self.cue_points.add(id, position_ms, self.get_status().position_ms);
+        Ok(())
+    }
+
+    fn remove_cue_point(&mut self, id: &str) -> Result<()> {
+        self.cue_points.remove(id);
+        Ok(())
+    }
+
+    fn chapters(&self) -> Vec<crate::metadata::Chapter> {
+        self.decoder
+            .lock()
+            .as_ref()
+            .map(|dec| dec.metadata.chapters.clone())
+            .unwrap_or_default()
+    }
+
+    fn set_chapter_cue_points(&mut self, enabled: bool) -> Result<()> {
+        let chapters = self.chapters();
+        self.cue_points.set_chapters_enabled(enabled, &chapters, self.get_status().position_ms);
+        Ok(())
+    }
+
+    fn set_latency_mode(&mut self, mode: LatencyMode) -> Result<()> {
+        if mode == LatencyMode::Fixed(0) {
+            return Err(AudioError::InvalidState("Fixed latency must be positive".to_string()));
+        }
+        log::info!("Latency mode: {:?} (applies from the next load)", mode);
+        self.latency_mode = mode;
+        Ok(())
+    }
+
+    fn output_latency_ms(&self) -> u64 {
+        if let Some(fixed) = *self.fixed_latency.lock() {
+            return fixed.latency_ms();
+        }
+        let buffered = self.ring_buffer.lock().available_read();
+        buffered_ms(buffered, 2, *self.output_sample_rate.lock())
+    }
+
+    fn set_transcript_url(&mut self, url: Option<&str>) -> Result<()> {
+        self.transcript.set_url(url)
+    }
+
+    fn current_transcript_cue(&self, position_ms: u64) -> Option<TranscriptCue> {
+        self.transcript.current_cue(position_ms)
+    }
+
     fn set_volume(&mut self, volume: f32) -> Result<()> {
+        self.require_engine("set volume")?;
         let clamped = volume.clamp(0.0, 1.0);
         *self.volume.lock() = clamped;
 
@@ -615,13 +1132,12 @@ impl AudioPlayer for AndroidAudioPlayer {
     }
 
     fn set_playback_rate(&mut self, rate: f32) -> Result<()> {
-        // TODO: Implement playback rate adjustment
-        // This requires resampling, which is complex
-        *self.playback_rate.lock() = rate;
+        self.require_engine("set playback rate")?;
+        let rate = clamp_playback_rate(rate)?;
+        self.playback_rate.lock().set_target(rate, self.callback_manager.now());
 
-        self.callback_manager.dispatch_event(CallbackEvent::PlaybackRateChanged {
-            rate,
-        });
+        self.callback_manager
+            .dispatch_event(CallbackEvent::PlaybackRateChanged { rate });
 
         log::warn!("Playback rate adjustment not yet implemented");
         Ok(())
@@ -632,46 +1148,93 @@ impl AudioPlayer for AndroidAudioPlayer {
     }
 
     fn get_status(&self) -> PlaybackStatus {
-        let decoder_lock = self.decoder.lock();
-        let duration_ms = if let Some(ref dec) = *decoder_lock {
-            dec.format.duration_ms
-        } else {
-            0
-        };
-        drop(decoder_lock);
+        // One lock, so duration and rate come from the same decoder even across a track change
+        let decoder = self.decoder.lock().as_ref().map(AudioDecoder::status);
+        let (duration_ms, duration_known) = decoder.map_or((0, false), |d| (d.duration_ms, d.duration_known));
 
         let sample_count = *self.sample_count.lock();
-        let decoder_rate = if let Some(ref dec) = *self.decoder.lock() {
-            dec.format.sample_rate
-        } else {
-            48_000 // Default
-        };
+        let decoder_rate = decoder.map_or(48_000, |d| d.sample_rate);
         let output_rate = *self.output_sample_rate.lock();
         let sample_rate = if output_rate > 0 { output_rate } else { decoder_rate };
 
         let position_ms = (sample_count * 1000) / sample_rate as u64;
+        let position_ms = match *self.fixed_latency.lock() {
+            Some(fixed) => fixed.audible_position_ms(position_ms),
+            None => position_ms,
+        };
+        let position_ms = decoder.map_or(position_ms, |d| d.source_position_ms(position_ms));
 
         PlaybackStatus {
             position_ms,
             duration_ms,
             volume: *self.volume.lock(),
-            playback_rate: *self.playback_rate.lock(),
+            playback_rate: self.playback_rate.lock().effective(self.callback_manager.now()),
             buffering: false,
+            duration_known,
+        }
+    }
+
+    fn output_sample_rate(&self) -> u32 {
+        if self.audio_stream.is_some() {
+            *self.output_sample_rate.lock()
+        } else {
+            0
+        }
+    }
+
+    fn output_channels(&self) -> u16 {
+        // The Oboe stream is always opened with a stereo frame type
+        if self.audio_stream.is_some() {
+            2
+        } else {
+            0
         }
     }
 
+    fn get_stats(&self) -> PlaybackStats {
+        let mut stats = self.decoder.lock().as_ref().map(AudioDecoder::stats).unwrap_or_default();
+        stats.phases = self.load_timer.timings();
+        stats.time_to_first_audio_ms = self.load_timer.time_to_first_audio_ms();
+        stats.dropped_tap_chunks = self.pcm_taps.dropped_chunks() + self.output_taps.dropped_chunks();
+        stats
+    }
+
+    fn event_queue_depth(&self) -> usize {
+        self.callback_manager.queue_depth()
+    }
+
     fn set_callback(&mut self, callback: Option<Arc<dyn PlayerCallback>>) {
         self.callback_manager.clear_callbacks();
         if let Some(cb) = callback {
-            self.callback_manager.add_callback(cb, POSITION_UPDATE_INTERVAL_MS);
+            self.callback_manager.add_callback_with_default_interval(cb);
         }
     }
 
+    fn add_callback(
+        &mut self,
+        callback: Arc<dyn PlayerCallback>,
+        position_update_interval_ms: u64,
+    ) -> CallbackHandle {
+        self.callback_manager.add_callback(callback, position_update_interval_ms)
+    }
+
+    fn remove_callback(&mut self, handle: CallbackHandle) -> bool {
+        self.callback_manager.remove_callback(handle)
+    }
+
+    fn set_position_update_interval_ms(&mut self, interval_ms: u64) {
+        self.callback_manager
+            .set_position_update_interval_ms(interval_ms);
+    }
+
     fn release(&mut self) -> Result<()> {
         log::info!("Releasing audio player");
 
-        self.stop()?;
-        self.stop_decoder_thread();
+        // Bounded wait for the decode thread; stop() then delivers the terminal Stopped event
+        // and the teardown below runs even if stopping the stream failed
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread_within(RELEASE_JOIN_TIMEOUT);
+        let stopped = self.stop();
 
         if let Some(stream) = self.audio_stream.take() {
             drop(stream);
@@ -679,14 +1242,22 @@ impl AudioPlayer for AndroidAudioPlayer {
 
         *self.decoder.lock() = None;
         self.state_container.set_state(PlayerState::Idle);
+        // Deliver what is still queued, the final Stopped included, before returning
+        if !self.callback_manager.flush(RELEASE_JOIN_TIMEOUT) {
+            log::warn!("Player callbacks still busy after release");
+        }
 
         log::info!("Audio player released");
-        Ok(())
+        stopped
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 impl AndroidAudioPlayer {
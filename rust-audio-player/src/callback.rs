@@ -1,14 +1,49 @@
 // Thread-safe callback mechanism for player events
-// Addresses the issue of high-frequency JNI callbacks by batching and throttling
+// Addresses the issue of high-frequency JNI callbacks by batching and throttling.
+// Events are queued and delivered on a dedicated thread, so a slow callback (JNI, UI work) never
+// blocks the decode or output threads, and a callback may call back into the player.
 
+use crate::clock::{Clock, SystemClock};
+use crate::diagnostics::LoadPhase;
+use crate::http_range_source::StreamStage;
+use crate::metadata::FormatInfo;
 use crate::player::PlayerState;
+use crate::threads::{catch_panic, join_within, spawn_named, CALLBACK_THREAD, RELEASE_JOIN_TIMEOUT};
+use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
-use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use parking_lot::{Condvar, Mutex};
+use std::thread;
 use std::time::{Duration, Instant};
 
+/// Default interval between position updates (milliseconds)
+pub const DEFAULT_POSITION_UPDATE_INTERVAL_MS: u64 = 100;
+
+/// Events waiting for delivery; when full, the oldest is dropped
+pub const EVENT_QUEUE_CAPACITY: usize = 256;
+
 /// Player event types
 #[derive(Debug, Clone)]
 pub enum CallbackEvent {
+    /// Source opened and its format resolved (fires once per load, before pre-buffering completes)
+    Prepared {
+        format: FormatInfo,
+    },
+
+    /// Pre-buffering after a load or seek reached its target (or the end of a short source);
+    /// playback can start without stalling. Fires after `Prepared`.
+    PrebufferComplete {
+        buffered_ms: u64,
+    },
+
+    /// Total duration became known or changed (file size reported late, end of a VBR/live stream)
+    DurationChanged {
+        duration_ms: u64,
+    },
+
+    /// In-band metadata (tags, stream title) changed; re-read it from the player
+    MetadataChanged,
+
     /// Player state changed
     StateChanged {
         old_state: PlayerState,
@@ -21,6 +56,12 @@ pub enum CallbackEvent {
         duration_ms: u64,
     },
 
+    /// Playback crossed a cue point registered with `add_cue_point` (or a chapter start)
+    CuePointReached {
+        id: String,
+        position_ms: u64,
+    },
+
     /// Playback completed
     PlaybackCompleted,
 
@@ -43,6 +84,17 @@ pub enum CallbackEvent {
     PlaybackRateChanged {
         rate: f32,
     },
+
+    /// A URL load reached the next stage of preparing its stream (before `Prepared`)
+    StreamPreparing {
+        stage: StreamStage,
+    },
+
+    /// A load or seek phase finished, with how long it took (see `PlaybackStats` for the totals)
+    Diagnostics {
+        phase: LoadPhase,
+        duration_ms: u64,
+    },
 }
 
 /// Player callback trait
@@ -53,30 +105,58 @@ pub trait PlayerCallback: Send + Sync {
     fn on_event(&self, event: CallbackEvent);
 }
 
+/// Identifies a callback registered with `add_callback`, for removing it again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackHandle(u64);
+
 /// Throttled callback wrapper
 /// Prevents excessive callback frequency, especially for position updates
 pub struct ThrottledCallback {
     inner: Arc<dyn PlayerCallback>,
     last_position_update: Arc<Mutex<Instant>>,
-    position_update_interval: Duration,
+    position_update_interval_ms: AtomicU64,
+    clock: Arc<dyn Clock>,
 }
 
 impl ThrottledCallback {
     pub fn new(callback: Arc<dyn PlayerCallback>, update_interval_ms: u64) -> Self {
+        Self::with_clock(callback, update_interval_ms, Arc::new(SystemClock))
+    }
+
+    /// Throttle against `clock` instead of the system clock
+    pub fn with_clock(
+        callback: Arc<dyn PlayerCallback>,
+        update_interval_ms: u64,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
         Self {
             inner: callback,
-            last_position_update: Arc::new(Mutex::new(Instant::now())),
-            position_update_interval: Duration::from_millis(update_interval_ms),
+            last_position_update: Arc::new(Mutex::new(clock.now())),
+            position_update_interval_ms: AtomicU64::new(update_interval_ms),
+            clock,
         }
     }
 
+    /// Change the minimum interval between position updates (takes effect on the next dispatch)
+    pub fn set_position_update_interval(&self, update_interval_ms: u64) {
+        self.position_update_interval_ms
+            .store(update_interval_ms, Ordering::Relaxed);
+    }
+
+    pub fn position_update_interval_ms(&self) -> u64 {
+        self.position_update_interval_ms.load(Ordering::Relaxed)
+    }
+
     pub fn dispatch(&self, event: CallbackEvent) {
         match &event {
             CallbackEvent::PositionChanged { .. } => {
                 // Throttle position updates
                 let mut last_update = self.last_position_update.lock();
-                if last_update.elapsed() >= self.position_update_interval {
-                    *last_update = Instant::now();
+                let interval =
+                    Duration::from_millis(self.position_update_interval_ms.load(Ordering::Relaxed));
+                let now = self.clock.now();
+                if now.saturating_duration_since(*last_update) >= interval {
+                    *last_update = now;
                     self.inner.on_event(event);
                 }
             }
@@ -88,32 +168,243 @@ impl ThrottledCallback {
     }
 }
 
+/// Events waiting for the dispatch thread
+#[derive(Default)]
+struct EventQueue {
+    events: VecDeque<CallbackEvent>,
+    /// The dispatch thread is inside a callback
+    delivering: bool,
+    /// Events dropped because the queue was full
+    dropped: u64,
+    shutdown: bool,
+}
+
+struct SharedQueue {
+    queue: Mutex<EventQueue>,
+    /// Signalled when events arrive and when delivery goes idle
+    changed: Condvar,
+}
+
+/// Registered callbacks with their handles
+type Callbacks = Mutex<Vec<(CallbackHandle, Arc<ThrottledCallback>)>>;
+
 /// Callback manager for handling multiple callbacks
 pub struct CallbackManager {
-    callbacks: Arc<Mutex<Vec<Arc<ThrottledCallback>>>>,
+    callbacks: Arc<Callbacks>,
+    /// Interval for callbacks registered without their own (`set_callback`)
+    position_update_interval_ms: AtomicU64,
+    /// Shortest interval any registered callback wants (the default when there are none),
+    /// kept current on every change so the decoder threads can read it without locking
+    effective_interval_ms: AtomicU64,
+    /// Callbacks registered with the default interval; they follow changes to it
+    follows_default: Mutex<HashSet<CallbackHandle>>,
+    next_handle: AtomicU64,
+    clock: Arc<dyn Clock>,
+    shared: Arc<SharedQueue>,
+    /// None when the dispatch thread could not be started (events are then delivered inline)
+    dispatcher: Option<thread::JoinHandle<()>>,
 }
 
 impl CallbackManager {
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Manager whose throttling, and the players' position timing, follow `clock`
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        let callbacks = Arc::new(Mutex::new(Vec::new()));
+        let shared = Arc::new(SharedQueue {
+            queue: Mutex::new(EventQueue::default()),
+            changed: Condvar::new(),
+        });
+        let dispatcher = {
+            let (callbacks, shared) = (Arc::clone(&callbacks), Arc::clone(&shared));
+            spawn_named(CALLBACK_THREAD, move || Self::run_dispatcher(&callbacks, &shared))
+        };
+        let dispatcher = dispatcher
+            .inspect_err(|e| log::warn!("{}; delivering events on the calling thread", e))
+            .ok();
         Self {
-            callbacks: Arc::new(Mutex::new(Vec::new())),
+            callbacks,
+            position_update_interval_ms: AtomicU64::new(DEFAULT_POSITION_UPDATE_INTERVAL_MS),
+            effective_interval_ms: AtomicU64::new(DEFAULT_POSITION_UPDATE_INTERVAL_MS),
+            follows_default: Mutex::new(HashSet::new()),
+            next_handle: AtomicU64::new(1),
+            clock,
+            shared,
+            dispatcher,
+        }
+    }
+
+    /// Deliver queued events until shut down (and drained)
+    fn run_dispatcher(callbacks: &Callbacks, shared: &SharedQueue) {
+        loop {
+            let event = {
+                let mut queue = shared.queue.lock();
+                queue.delivering = false;
+                shared.changed.notify_all();
+                while queue.events.is_empty() && !queue.shutdown {
+                    shared.changed.wait(&mut queue);
+                }
+                let Some(event) = queue.events.pop_front() else {
+                    return;
+                };
+                queue.delivering = true;
+                event
+            };
+            // Snapshot so a callback can add or clear callbacks without deadlocking
+            let targets = callbacks.lock().clone();
+            if let Err(e) = catch_panic(|| Self::deliver(&targets, event)) {
+                log::error!("Player callback failed: {}", e);
+            }
         }
     }
 
-    pub fn add_callback(&self, callback: Arc<dyn PlayerCallback>, throttle_ms: u64) {
-        let throttled = Arc::new(ThrottledCallback::new(callback, throttle_ms));
-        self.callbacks.lock().push(throttled);
+    fn deliver(callbacks: &[(CallbackHandle, Arc<ThrottledCallback>)], event: CallbackEvent) {
+        for (_, callback) in callbacks {
+            callback.dispatch(event.clone());
+        }
+    }
+
+    /// Current interval between position updates (milliseconds): the shortest any registered
+    /// callback wants, or the default when there are none
+    /// Decoder threads read this on every packet so changes apply live
+    pub fn position_update_interval_ms(&self) -> u64 {
+        self.effective_interval_ms.load(Ordering::Relaxed)
+    }
+
+    /// Recompute the cached shortest interval; called with the callback list locked
+    fn update_effective_interval(&self, callbacks: &[(CallbackHandle, Arc<ThrottledCallback>)]) {
+        let interval_ms = callbacks
+            .iter()
+            .map(|(_, callback)| callback.position_update_interval_ms())
+            .min()
+            .unwrap_or_else(|| self.position_update_interval_ms.load(Ordering::Relaxed));
+        self.effective_interval_ms.store(interval_ms, Ordering::Relaxed);
+    }
+
+    /// Change the default interval between `PositionChanged` updates. Callbacks registered with
+    /// the default follow it; those given their own interval in `add_callback` keep theirs.
+    pub fn set_position_update_interval_ms(&self, interval_ms: u64) {
+        self.position_update_interval_ms
+            .store(interval_ms, Ordering::Relaxed);
+        let follows_default = self.follows_default.lock();
+        let callbacks = self.callbacks.lock();
+        for (handle, callback) in callbacks.iter() {
+            if follows_default.contains(handle) {
+                callback.set_position_update_interval(interval_ms);
+            }
+        }
+        self.update_effective_interval(&callbacks);
+    }
+
+    /// Current time on the manager's clock
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    /// Interval given to callbacks registered without their own
+    pub fn default_position_update_interval_ms(&self) -> u64 {
+        self.position_update_interval_ms.load(Ordering::Relaxed)
+    }
+
+    /// Register `callback` next to the existing ones, with its own position update interval
+    pub fn add_callback(&self, callback: Arc<dyn PlayerCallback>, throttle_ms: u64) -> CallbackHandle {
+        let handle = CallbackHandle(self.next_handle.fetch_add(1, Ordering::Relaxed));
+        let clock = Arc::clone(&self.clock);
+        let throttled = Arc::new(ThrottledCallback::with_clock(callback, throttle_ms, clock));
+        let mut callbacks = self.callbacks.lock();
+        callbacks.push((handle, throttled));
+        self.update_effective_interval(&callbacks);
+        handle
+    }
+
+    /// Register `callback` with the default position update interval, following later changes
+    /// to it (`set_position_update_interval_ms`)
+    pub fn add_callback_with_default_interval(&self, callback: Arc<dyn PlayerCallback>) -> CallbackHandle {
+        let mut follows_default = self.follows_default.lock();
+        let handle = self.add_callback(callback, self.default_position_update_interval_ms());
+        follows_default.insert(handle);
+        handle
+    }
+
+    /// Unregister a callback; false if it was not registered (or already removed)
+    /// An event already being delivered may still reach it.
+    pub fn remove_callback(&self, handle: CallbackHandle) -> bool {
+        self.follows_default.lock().remove(&handle);
+        let mut callbacks = self.callbacks.lock();
+        let before = callbacks.len();
+        callbacks.retain(|(registered, _)| *registered != handle);
+        self.update_effective_interval(&callbacks);
+        callbacks.len() != before
     }
 
     pub fn clear_callbacks(&self) {
-        self.callbacks.lock().clear();
+        let mut callbacks = self.callbacks.lock();
+        callbacks.clear();
+        self.update_effective_interval(&callbacks);
+        self.follows_default.lock().clear();
     }
 
+    /// Queue `event` for the dispatch thread; returns without waiting for the callbacks.
+    /// A newer position takes the place of one still queued, so it is never delivered after events
+    /// queued behind the old one; a full queue drops its oldest event.
     pub fn dispatch_event(&self, event: CallbackEvent) {
-        let callbacks = self.callbacks.lock();
-        for callback in callbacks.iter() {
-            callback.dispatch(event.clone());
+        if self.dispatcher.is_none() {
+            let callbacks = self.callbacks.lock().clone();
+            Self::deliver(&callbacks, event);
+            return;
+        }
+
+        let mut queue = self.shared.queue.lock();
+        if matches!(event, CallbackEvent::PositionChanged { .. }) {
+            let queued = queue
+                .events
+                .iter()
+                .position(|queued| matches!(queued, CallbackEvent::PositionChanged { .. }));
+            if let Some(index) = queued {
+                queue.events[index] = event;
+                self.shared.changed.notify_all();
+                return;
+            }
         }
+        if queue.events.len() >= EVENT_QUEUE_CAPACITY {
+            if let Some(dropped) = queue.events.pop_front() {
+                queue.dropped += 1;
+                log::warn!("Callback queue full, dropping {:?}", dropped);
+            }
+        }
+        queue.events.push_back(event);
+        self.shared.changed.notify_all();
+    }
+
+    /// Events waiting for delivery
+    pub fn queue_depth(&self) -> usize {
+        self.shared.queue.lock().events.len()
+    }
+
+    /// Events dropped so far because the queue was full
+    pub fn dropped_events(&self) -> u64 {
+        self.shared.queue.lock().dropped
+    }
+
+    /// Wait until every queued event has been delivered, at most `timeout`.
+    /// Returns false on timeout, and immediately when called from a callback.
+    pub fn flush(&self, timeout: Duration) -> bool {
+        let Some(dispatcher) = &self.dispatcher else {
+            return true;
+        };
+        if thread::current().id() == dispatcher.thread().id() {
+            return false;
+        }
+        let deadline = Instant::now() + timeout;
+        let mut queue = self.shared.queue.lock();
+        while !queue.events.is_empty() || queue.delivering {
+            if self.shared.changed.wait_until(&mut queue, deadline).timed_out() {
+                return false;
+            }
+        }
+        true
     }
 }
 
@@ -123,6 +414,19 @@ impl Default for CallbackManager {
     }
 }
 
+impl Drop for CallbackManager {
+    fn drop(&mut self) {
+        self.shared.queue.lock().shutdown = true;
+        self.shared.changed.notify_all();
+        // The dispatch thread drains what is queued and exits; a stuck callback is abandoned
+        if let Some(dispatcher) = self.dispatcher.take() {
+            if thread::current().id() != dispatcher.thread().id() {
+                join_within(dispatcher, RELEASE_JOIN_TIMEOUT);
+            }
+        }
+    }
+}
+
 /// Simple callback implementation for testing
 #[cfg(test)]
 pub struct TestCallback {
@@ -195,4 +499,288 @@ mod tests {
         let events = test_callback.get_events();
         assert_eq!(events.len(), 2);
     }
+
+    /// Blocks in `on_event` until the gate opens
+    struct SlowCallback {
+        events: TestCallback,
+        gate: Mutex<bool>,
+        opened: parking_lot::Condvar,
+    }
+
+    impl SlowCallback {
+        fn open(&self) {
+            *self.gate.lock() = true;
+            self.opened.notify_all();
+        }
+    }
+
+    impl PlayerCallback for SlowCallback {
+        fn on_event(&self, event: CallbackEvent) {
+            let mut open = self.gate.lock();
+            while !*open {
+                self.opened.wait(&mut open);
+            }
+            self.events.on_event(event);
+        }
+    }
+
+    fn slow_callback() -> Arc<SlowCallback> {
+        Arc::new(SlowCallback {
+            events: TestCallback::new(),
+            gate: Mutex::new(false),
+            opened: Default::default(),
+        })
+    }
+
+    #[test]
+    fn test_slow_callback_does_not_stall_dispatch_and_positions_coalesce() {
+        let callback = slow_callback();
+        let manager = CallbackManager::new();
+        manager.add_callback(callback.clone(), 0);
+
+        // A decode loop reporting positions while the callback is stuck in its first event
+        let start = Instant::now();
+        manager.dispatch_event(CallbackEvent::BufferingChanged { buffering: false });
+        for position_ms in 0..10_000 {
+            manager.dispatch_event(CallbackEvent::PositionChanged { position_ms, duration_ms: 10_000 });
+            if position_ms == 5_000 {
+                manager.dispatch_event(CallbackEvent::PlaybackRateChanged { rate: 1.5 });
+            }
+        }
+        assert!(start.elapsed() < Duration::from_secs(1), "{:?}", start.elapsed());
+        assert!(manager.queue_depth() <= 3, "{}", manager.queue_depth());
+
+        callback.open();
+        assert!(manager.flush(Duration::from_secs(1)));
+        assert_eq!(manager.queue_depth(), 0);
+        assert_eq!(manager.dropped_events(), 0);
+
+        // Only the latest position survives, in the slot of the first one
+        let events = callback.events.get_events();
+        assert!(
+            matches!(
+                events[..],
+                [
+                    CallbackEvent::BufferingChanged { .. },
+                    CallbackEvent::PositionChanged { position_ms: 9_999, .. },
+                    CallbackEvent::PlaybackRateChanged { .. },
+                ]
+            ),
+            "{:?}",
+            events
+        );
+    }
+
+    #[test]
+    fn test_coalesced_position_keeps_its_place_before_completion() {
+        let callback = slow_callback();
+        let manager = CallbackManager::new();
+        manager.add_callback(callback.clone(), 0);
+
+        manager.dispatch_event(CallbackEvent::MetadataChanged);
+        while manager.queue_depth() > 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        manager.dispatch_event(CallbackEvent::PositionChanged { position_ms: 9_900, duration_ms: 10_000 });
+        manager.dispatch_event(CallbackEvent::StateChanged {
+            old_state: PlayerState::Playing,
+            new_state: PlayerState::Stopped,
+        });
+        manager.dispatch_event(CallbackEvent::PlaybackCompleted);
+        // The decode loop's last report races the completion
+        manager.dispatch_event(CallbackEvent::PositionChanged { position_ms: 10_000, duration_ms: 10_000 });
+        assert_eq!(manager.queue_depth(), 3);
+
+        callback.open();
+        assert!(manager.flush(Duration::from_secs(1)));
+        let events = callback.events.get_events();
+        assert!(
+            matches!(
+                events[..],
+                [
+                    CallbackEvent::MetadataChanged,
+                    CallbackEvent::PositionChanged { position_ms: 10_000, .. },
+                    CallbackEvent::StateChanged { .. },
+                    CallbackEvent::PlaybackCompleted,
+                ]
+            ),
+            "{:?}",
+            events
+        );
+    }
+
+    #[test]
+    fn test_full_queue_drops_oldest() {
+        let callback = slow_callback();
+        let manager = CallbackManager::new();
+        manager.add_callback(callback.clone(), 0);
+
+        manager.dispatch_event(CallbackEvent::MetadataChanged);
+        // Wait for the dispatch thread to pick it up and block in the callback
+        while manager.queue_depth() > 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        for rate in 0..EVENT_QUEUE_CAPACITY + 10 {
+            manager.dispatch_event(CallbackEvent::PlaybackRateChanged { rate: rate as f32 });
+        }
+        assert_eq!(manager.queue_depth(), EVENT_QUEUE_CAPACITY);
+        assert_eq!(manager.dropped_events(), 10);
+
+        callback.open();
+        assert!(manager.flush(Duration::from_secs(1)));
+        let events = callback.events.get_events();
+        assert_eq!(events.len(), EVENT_QUEUE_CAPACITY + 1);
+        assert!(matches!(events[1], CallbackEvent::PlaybackRateChanged { rate } if rate == 10.0));
+    }
+
+    #[test]
+    fn test_multiple_callbacks_each_with_own_interval() {
+        let ui = Arc::new(TestCallback::new());
+        let analytics = Arc::new(TestCallback::new());
+        let manager = CallbackManager::new();
+        let ui_handle = manager.add_callback(ui.clone(), 0);
+        let analytics_handle = manager.add_callback(analytics.clone(), 10_000);
+        assert_ne!(ui_handle, analytics_handle);
+        // Positions are produced as often as the most eager callback wants them
+        assert_eq!(manager.position_update_interval_ms(), 0);
+
+        manager.dispatch_event(CallbackEvent::StateChanged {
+            old_state: PlayerState::Ready,
+            new_state: PlayerState::Playing,
+        });
+        for position_ms in [100, 200, 300] {
+            manager.dispatch_event(CallbackEvent::PositionChanged { position_ms, duration_ms: 1000 });
+            assert!(manager.flush(Duration::from_secs(1)));
+        }
+
+        let positions = |callback: &TestCallback| {
+            callback
+                .get_events()
+                .iter()
+                .filter(|event| matches!(event, CallbackEvent::PositionChanged { .. }))
+                .count()
+        };
+        for callback in [&ui, &analytics] {
+            assert!(matches!(callback.get_events()[0], CallbackEvent::StateChanged { .. }));
+        }
+        assert_eq!(positions(&ui), 3);
+        // Throttled to one update per 10s, and the throttle starts at registration
+        assert_eq!(positions(&analytics), 0);
+
+        assert!(manager.remove_callback(ui_handle));
+        assert!(!manager.remove_callback(ui_handle));
+        assert_eq!(manager.position_update_interval_ms(), 10_000);
+        manager.dispatch_event(CallbackEvent::PlaybackCompleted);
+        assert!(manager.flush(Duration::from_secs(1)));
+        assert!(!ui.get_events().iter().any(|e| matches!(e, CallbackEvent::PlaybackCompleted)));
+        assert!(analytics.get_events().iter().any(|e| matches!(e, CallbackEvent::PlaybackCompleted)));
+    }
+
+    #[test]
+    fn test_position_throttle_follows_the_clock() {
+        use crate::clock::TestClock;
+
+        let clock = Arc::new(TestClock::new());
+        let manager = CallbackManager::with_clock(clock.clone());
+        let callback = Arc::new(TestCallback::new());
+        manager.add_callback(callback.clone(), 100);
+
+        // One position every 50ms of clock time; the interval counts from registration
+        for step in 1..=6 {
+            clock.advance(Duration::from_millis(50));
+            manager.dispatch_event(CallbackEvent::PositionChanged {
+                position_ms: step * 50,
+                duration_ms: 1000,
+            });
+            assert!(manager.flush(Duration::from_secs(1)));
+        }
+
+        let delivered: Vec<u64> = callback
+            .get_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CallbackEvent::PositionChanged { position_ms, .. } => Some(position_ms),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(delivered, vec![100, 200, 300]);
+    }
+
+    #[test]
+    fn test_position_interval_update_mid_playback() {
+        let test_callback = Arc::new(TestCallback::new());
+        let manager = CallbackManager::new();
+        manager.set_position_update_interval_ms(200);
+        manager.add_callback_with_default_interval(test_callback.clone());
+
+        let dispatch_for = |duration: Duration| {
+            let start = Instant::now();
+            while start.elapsed() < duration {
+                manager.dispatch_event(CallbackEvent::PositionChanged {
+                    position_ms: 0,
+                    duration_ms: 1000,
+                });
+                thread::sleep(Duration::from_millis(5));
+            }
+        };
+
+        // Slow interval: at most a couple of updates in 300ms
+        dispatch_for(Duration::from_millis(300));
+        assert!(manager.flush(Duration::from_secs(1)));
+        let slow_count = test_callback.get_events().len();
+        assert!(slow_count <= 2);
+
+        // Switch to a scrubbing-friendly interval while "playing"
+        test_callback.clear();
+        manager.set_position_update_interval_ms(20);
+        assert_eq!(manager.position_update_interval_ms(), 20);
+        dispatch_for(Duration::from_millis(300));
+        assert!(manager.flush(Duration::from_secs(1)));
+        let fast_count = test_callback.get_events().len();
+        assert!(fast_count >= 5);
+        assert!(fast_count > slow_count);
+    }
+
+    #[test]
+    fn test_default_interval_change_keeps_explicit_intervals() {
+        let manager = CallbackManager::new();
+        let ui = manager.add_callback_with_default_interval(Arc::new(TestCallback::new()));
+        manager.add_callback(Arc::new(TestCallback::new()), 10_000);
+        let interval_of = |handle| {
+            let callbacks = manager.callbacks.lock();
+            let (_, callback) = callbacks.iter().find(|(registered, _)| *registered == handle).unwrap();
+            callback.position_update_interval_ms()
+        };
+        assert_eq!(interval_of(ui), DEFAULT_POSITION_UPDATE_INTERVAL_MS);
+
+        // The analytics callback asked for 10s and keeps it; the UI one follows the default
+        manager.set_position_update_interval_ms(50_000);
+        assert_eq!(interval_of(ui), 50_000);
+        assert_eq!(manager.position_update_interval_ms(), 10_000);
+    }
+
+    #[test]
+    fn test_cached_interval_follows_registrations() {
+        let manager = CallbackManager::new();
+        assert_eq!(manager.position_update_interval_ms(), DEFAULT_POSITION_UPDATE_INTERVAL_MS);
+
+        // With nothing registered the default applies
+        manager.set_position_update_interval_ms(700);
+        assert_eq!(manager.position_update_interval_ms(), 700);
+
+        let fast = manager.add_callback(Arc::new(TestCallback::new()), 50);
+        manager.add_callback_with_default_interval(Arc::new(TestCallback::new()));
+        assert_eq!(manager.position_update_interval_ms(), 50);
+        manager.set_position_update_interval_ms(20);
+        assert_eq!(manager.position_update_interval_ms(), 20);
+        manager.set_position_update_interval_ms(900);
+        assert_eq!(manager.position_update_interval_ms(), 50);
+
+        manager.remove_callback(fast);
+        assert_eq!(manager.position_update_interval_ms(), 900);
+        manager.add_callback(Arc::new(TestCallback::new()), 300);
+        assert_eq!(manager.position_update_interval_ms(), 300);
+        manager.clear_callbacks();
+        assert_eq!(manager.position_update_interval_ms(), 900);
+    }
 }
@@ -0,0 +1,68 @@
+// Channel-count conversion between decoded audio and the output device
+// Devices that only open with a fixed layout (mono speakers, multichannel interfaces)
+// get the source remixed instead of failing to create a stream.
+
+/// Channel count to open the device with: the source's if supported, else the smallest
+/// layout that holds every source channel, else the largest the device has
+pub fn pick_output_channels(source: u16, supported: &[u16]) -> Option<u16> {
+    if supported.contains(&source) {
+        return Some(source);
+    }
+    supported
+        .iter()
+        .copied()
+        .filter(|&count| count > source)
+        .min()
+        .or_else(|| supported.iter().copied().max())
+}
+
+/// Convert interleaved samples from `from` to `to` channels.
+/// Mono is copied to both front channels, extra output channels are silent,
+/// and surplus source channels are folded into mono or dropped beyond the front pair.
+pub fn remix_channels(samples: &[f32], from: u16, to: u16) -> Vec<f32> {
+    let (from, to) = (from as usize, to as usize);
+    if from == to || from == 0 || to == 0 {
+        return samples.to_vec();
+    }
+
+    let frames = samples.len() / from;
+    let mut out = vec![0.0; frames * to];
+    for (frame, out_frame) in samples.chunks_exact(from).zip(out.chunks_exact_mut(to)) {
+        if to == 1 {
+            out_frame[0] = frame.iter().sum::<f32>() / from as f32;
+        } else if from == 1 {
+            out_frame[0] = frame[0];
+            out_frame[1] = frame[0];
+        } else {
+            let shared = from.min(to);
+            out_frame[..shared].copy_from_slice(&frame[..shared]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_prefers_source_then_smallest_superset() {
+        assert_eq!(pick_output_channels(2, &[1, 2, 6]), Some(2));
+        assert_eq!(pick_output_channels(1, &[2, 6]), Some(2));
+        assert_eq!(pick_output_channels(2, &[8, 6]), Some(6));
+        assert_eq!(pick_output_channels(6, &[1, 2]), Some(2));
+        assert_eq!(pick_output_channels(2, &[]), None);
+    }
+
+    #[test]
+    fn test_remix_between_layouts() {
+        assert_eq!(remix_channels(&[0.5, -0.5], 1, 2), vec![0.5, 0.5, -0.5, -0.5]);
+        assert_eq!(
+            remix_channels(&[0.1, 0.2, 0.3, 0.4], 2, 4),
+            vec![0.1, 0.2, 0.0, 0.0, 0.3, 0.4, 0.0, 0.0]
+        );
+        assert_eq!(remix_channels(&[0.2, 0.4, 1.0, 0.0], 2, 1), vec![0.3, 0.5]);
+        assert_eq!(remix_channels(&[0.1, 0.2, 0.3, 0.4, 0.5, 0.6], 6, 2), vec![0.1, 0.2]);
+        assert_eq!(remix_channels(&[0.1, 0.2], 2, 2), vec![0.1, 0.2]);
+    }
+}
@@ -0,0 +1,70 @@
+// Time source for position throttling and rate ramps
+// Production code reads the monotonic system clock; tests substitute `TestClock` and advance it
+// by hand, so timing behavior can be asserted exactly without real sleeps.
+
+use std::time::Instant;
+
+#[cfg(test)]
+use parking_lot::Mutex;
+#[cfg(test)]
+use std::time::Duration;
+
+/// Source of the current time for time-dependent player logic
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The monotonic system clock
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock that only moves when advanced
+#[cfg(test)]
+pub struct TestClock {
+    now: Mutex<Instant>,
+}
+
+#[cfg(test)]
+impl TestClock {
+    pub fn new() -> Self {
+        Self { now: Mutex::new(Instant::now()) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        *self.now.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playback_rate::{RateRamp, RATE_RAMP};
+
+    #[test]
+    fn test_rate_ramp_follows_the_test_clock() {
+        let clock = TestClock::new();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+
+        let mut ramp = RateRamp::default();
+        ramp.set_target(2.0, clock.now());
+        clock.advance(RATE_RAMP / 2);
+        assert!((ramp.effective(clock.now()) - 1.5).abs() < 1e-6);
+        clock.advance(RATE_RAMP / 2);
+        assert_eq!(ramp.effective(clock.now()), 2.0);
+        assert_eq!(clock.now() - start, RATE_RAMP);
+    }
+}
@@ -0,0 +1,189 @@
+// Cue points: app-defined timestamps reported as playback crosses them
+// Checked from the decode thread against the played position, so an event lines up with
+// what is heard (within one packet). Each point fires once per pass; seeking, stopping
+// and reloading re-arm the points ahead of the new position.
+
+use crate::callback::{CallbackEvent, CallbackManager};
+use crate::metadata::Chapter;
+use parking_lot::Mutex;
+
+/// Id prefix of the cue points registered for chapter starts
+pub const CHAPTER_CUE_PREFIX: &str = "chapter:";
+
+struct CuePoint {
+    id: String,
+    position_ms: u64,
+    /// Not yet reached in the current pass
+    armed: bool,
+}
+
+#[derive(Default)]
+struct CueState {
+    /// Sorted by position
+    points: Vec<CuePoint>,
+    chapters_enabled: bool,
+}
+
+/// Registered cue points of one player
+#[derive(Default)]
+pub struct CuePoints {
+    state: Mutex<CueState>,
+}
+
+impl CuePoints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or move) cue point `id`. It fires once playback reaches `position_ms`;
+    /// a point already behind `current_ms` waits for the next pass.
+    pub fn add(&self, id: &str, position_ms: u64, current_ms: u64) {
+        let mut state = self.state.lock();
+        state.points.retain(|point| point.id != id);
+        Self::insert(&mut state.points, id.to_string(), position_ms, current_ms);
+    }
+
+    /// Returns whether a cue point with this id existed
+    pub fn remove(&self, id: &str) -> bool {
+        let mut state = self.state.lock();
+        let before = state.points.len();
+        state.points.retain(|point| point.id != id);
+        state.points.len() != before
+    }
+
+    /// Start a new pass at `position_ms` (seek, loop, new playback)
+    pub fn rearm(&self, position_ms: u64) {
+        for point in self.state.lock().points.iter_mut() {
+            point.armed = point.position_ms >= position_ms;
+        }
+    }
+
+    /// Turn automatic chapter cue points on or off, registering `chapters` when on
+    pub fn set_chapters_enabled(&self, enabled: bool, chapters: &[Chapter], current_ms: u64) {
+        self.state.lock().chapters_enabled = enabled;
+        self.sync_chapters(chapters, current_ms);
+    }
+
+    /// Replace the chapter cue points with `chapters` (removes them if chapter cues are off).
+    /// Chapter `i` gets the id `chapter:<i>`.
+    pub fn sync_chapters(&self, chapters: &[Chapter], current_ms: u64) {
+        let mut state = self.state.lock();
+        state.points.retain(|point| !point.id.starts_with(CHAPTER_CUE_PREFIX));
+        if state.chapters_enabled {
+            for (index, chapter) in chapters.iter().enumerate() {
+                let id = format!("{}{}", CHAPTER_CUE_PREFIX, index);
+                Self::insert(&mut state.points, id, chapter.start_time_ms, current_ms);
+            }
+        }
+    }
+
+    /// Fire every armed point at or before `position_ms`, in position order
+    pub fn dispatch_reached(&self, position_ms: u64, callback_manager: &CallbackManager) {
+        let reached: Vec<CallbackEvent> = {
+            let mut state = self.state.lock();
+            state
+                .points
+                .iter_mut()
+                .take_while(|point| point.position_ms <= position_ms)
+                .filter(|point| point.armed)
+                .map(|point| {
+                    point.armed = false;
+                    CallbackEvent::CuePointReached {
+                        id: point.id.clone(),
+                        position_ms: point.position_ms,
+                    }
+                })
+                .collect()
+        };
+        // Dispatch outside the lock so callbacks can add or remove cue points
+        for event in reached {
+            callback_manager.dispatch_event(event);
+        }
+    }
+
+    fn insert(points: &mut Vec<CuePoint>, id: String, position_ms: u64, current_ms: u64) {
+        let index = points.partition_point(|point| point.position_ms <= position_ms);
+        points.insert(
+            index,
+            CuePoint {
+                id,
+                position_ms,
+                armed: position_ms >= current_ms,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::TestCallback;
+    use std::sync::Arc;
+
+    fn reached(callback_manager: &CallbackManager, callback: &TestCallback) -> Vec<(String, u64)> {
+        assert!(callback_manager.flush(std::time::Duration::from_secs(1)));
+        callback
+            .get_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CallbackEvent::CuePointReached { id, position_ms } => Some((id, position_ms)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cue_points_fire_once_per_pass() {
+        let callback = Arc::new(TestCallback::new());
+        let callback_manager = CallbackManager::new();
+        callback_manager.add_callback(callback.clone(), 0);
+        let cues = CuePoints::new();
+        cues.add("ad-end", 2_500, 0);
+        cues.add("ad-start", 1_000, 0);
+
+        // Playback advancing in ~23 ms packets
+        for position_ms in (0..3_000).step_by(23) {
+            cues.dispatch_reached(position_ms, &callback_manager);
+        }
+        let expected = vec![("ad-start".to_string(), 1_000), ("ad-end".to_string(), 2_500)];
+        assert_eq!(reached(&callback_manager, &callback), expected);
+
+        // Seeking back re-arms only what lies ahead
+        callback.clear();
+        cues.rearm(2_000);
+        cues.dispatch_reached(2_600, &callback_manager);
+        assert_eq!(reached(&callback_manager, &callback), vec![("ad-end".to_string(), 2_500)]);
+
+        // Removed points stay quiet
+        callback.clear();
+        assert!(cues.remove("ad-end"));
+        cues.rearm(0);
+        cues.dispatch_reached(3_000, &callback_manager);
+        assert_eq!(reached(&callback_manager, &callback), vec![("ad-start".to_string(), 1_000)]);
+    }
+
+    #[test]
+    fn test_chapter_cue_points() {
+        let chapter = |start_time_ms| Chapter {
+            start_time_ms,
+            end_time_ms: start_time_ms + 60_000,
+            title: None,
+            description: None,
+            url: None,
+        };
+        let callback = Arc::new(TestCallback::new());
+        let callback_manager = CallbackManager::new();
+        callback_manager.add_callback(callback.clone(), 0);
+        let cues = CuePoints::new();
+
+        // Off by default
+        cues.sync_chapters(&[chapter(0), chapter(60_000)], 0);
+        cues.dispatch_reached(120_000, &callback_manager);
+        assert!(reached(&callback_manager, &callback).is_empty());
+
+        cues.set_chapters_enabled(true, &[chapter(0), chapter(60_000)], 0);
+        cues.dispatch_reached(61_000, &callback_manager);
+        let expected = vec![("chapter:0".to_string(), 0), ("chapter:1".to_string(), 60_000)];
+        assert_eq!(reached(&callback_manager, &callback), expected);
+    }
+}
@@ -0,0 +1,199 @@
+// data: URIs carrying small embedded audio (e.g. from web content)
+// The payload is decoded in memory and played like `load_buffer`: no network, no temp file.
+
+use crate::error::{AudioError, Result};
+use symphonia::core::probe::Hint;
+
+/// Audio embedded in a `data:` URI
+#[derive(Debug)]
+pub struct DataUri {
+    /// Media type, lowercased and without parameters (e.g. "audio/mpeg")
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl DataUri {
+    /// Decoder hint for the media type
+    pub fn hint(&self) -> Hint {
+        let mut hint = Hint::new();
+        hint.mime_type(&self.mime_type);
+        let extension = match self.mime_type.as_str() {
+            "audio/mpeg" | "audio/mp3" | "audio/mpeg3" | "audio/x-mpeg-3" => Some("mp3"),
+            "audio/aac" | "audio/aacp" | "audio/x-aac" => Some("aac"),
+            "audio/mp4" | "audio/m4a" | "audio/x-m4a" => Some("m4a"),
+            "audio/wav" | "audio/wave" | "audio/x-wav" | "audio/vnd.wave" => Some("wav"),
+            "audio/flac" | "audio/x-flac" => Some("flac"),
+            "audio/ogg" | "audio/opus" | "audio/vorbis" => Some("ogg"),
+            "audio/webm" => Some("webm"),
+            _ => None,
+        };
+        if let Some(extension) = extension {
+            hint.with_extension(extension);
+        }
+        hint
+    }
+}
+
+/// Parse `url` if it is a `data:` URI (`Ok(None)` for any other URL).
+/// Only audio media types are accepted; the payload may be Base64 or percent-encoded.
+pub fn parse_data_uri(url: &str) -> Result<Option<DataUri>> {
+    let trimmed = url.trim();
+    if !trimmed
+        .get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+    {
+        return Ok(None);
+    }
+    let (header, payload) = trimmed[5..]
+        .split_once(',')
+        .ok_or_else(|| AudioError::LoadError("Malformed data URI: missing ','".to_string()))?;
+
+    let mut params = header.split(';').map(str::trim);
+    let mime_type = params.next().unwrap_or_default().to_ascii_lowercase();
+    let base64 = params.any(|param| param.eq_ignore_ascii_case("base64"));
+    if !mime_type.starts_with("audio/") {
+        return Err(AudioError::UnsupportedFormat(format!(
+            "data URI is not audio: {}",
+            if mime_type.is_empty() { "text/plain" } else { &mime_type }
+        )));
+    }
+
+    let data = if base64 {
+        decode_base64(payload)?
+    } else {
+        percent_decode(payload)
+    };
+    if data.is_empty() {
+        return Err(AudioError::LoadError("data URI has no payload".to_string()));
+    }
+    Ok(Some(DataUri { mime_type, data }))
+}
+
+/// Decode standard or URL-safe Base64, ignoring whitespace and optional padding
+fn decode_base64(text: &str) -> Result<Vec<u8>> {
+    let invalid = || AudioError::LoadError("Invalid Base64 in data URI".to_string());
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+    let mut padding = false;
+    for byte in text.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => {
+                padding = true;
+                continue;
+            }
+            _ => return Err(invalid()),
+        };
+        if padding {
+            return Err(invalid());
+        }
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push((bits >> bit_count) as u8);
+        }
+    }
+    // A lone trailing character cannot encode a whole byte
+    if bit_count >= 6 {
+        return Err(invalid());
+    }
+    Ok(out)
+}
+
+/// Decode %XX escapes, leaving malformed ones as they are
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::decoder::tests::test_wav;
+    use crate::decoder::AudioDecoder;
+
+    /// Standard Base64 with padding
+    pub(crate) fn encode_base64(data: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = String::new();
+        for chunk in data.chunks(3) {
+            let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_base64_round_trip() {
+        for len in 0..8 {
+            let data: Vec<u8> = (0..len).map(|i| (i * 37 + 200) as u8).collect();
+            assert_eq!(decode_base64(&encode_base64(&data)).unwrap(), data);
+        }
+        assert_eq!(decode_base64("aGk").unwrap(), b"hi");
+        assert_eq!(decode_base64("-_8=").unwrap(), [0xfb, 0xff]);
+        assert!(decode_base64("a").is_err());
+        assert!(decode_base64("aGk=x").is_err());
+        assert!(decode_base64("a*bc").is_err());
+    }
+
+    #[test]
+    fn test_audio_data_uri_decodes_in_memory() {
+        let wav = test_wav(8000, 2, 1);
+        let uri = format!("data:audio/wav;base64,{}", encode_base64(&wav));
+        let parsed = parse_data_uri(&uri).unwrap().unwrap();
+        assert_eq!(parsed.mime_type, "audio/wav");
+        assert_eq!(parsed.data, wav);
+
+        let hint = parsed.hint();
+        let mut decoder = AudioDecoder::from_buffer_with_hint(parsed.data, hint).unwrap();
+        assert_eq!(decoder.format.sample_rate, 8000);
+        let (_, samples) = decoder.decode_all().unwrap();
+        assert_eq!(samples.len(), 8000 * 2);
+    }
+
+    #[test]
+    fn test_other_data_uris_are_rejected() {
+        assert!(parse_data_uri("https://example.com/a.mp3").unwrap().is_none());
+        assert!(matches!(
+            parse_data_uri("data:text/plain;base64,aGk="),
+            Err(AudioError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(parse_data_uri("data:,hello"), Err(AudioError::UnsupportedFormat(_))));
+        assert!(matches!(parse_data_uri("DATA:audio/mpeg;base64"), Err(AudioError::LoadError(_))));
+        assert_eq!(
+            parse_data_uri("data:Audio/MPEG;codecs=mp3,%FF%FBx").unwrap().unwrap().data,
+            [0xff, 0xfb, b'x']
+        );
+    }
+}
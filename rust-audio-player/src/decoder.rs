@@ -1,18 +1,45 @@
 // Audio decoding using Symphonia
 // Handles various audio formats (MP3, AAC, FLAC, WAV, etc.)
 
+use crate::callback::{CallbackEvent, CallbackManager};
+use crate::channel_remix::remix_channels;
 use crate::error::{AudioError, Result};
-use crate::metadata::{AudioMetadata, AudioTags, Chapter, CoverArt, FormatInfo, QualityParams};
-use symphonia::core::audio::{AudioBufferRef, Signal};
-use symphonia::core::codecs::{Decoder, DecoderOptions};
+use crate::http_range_source::StreamStage;
+use crate::loop_buffer::{LoopBuffer, LOOP_BUFFER_MAX_MS, LOOP_CHUNK_FRAMES};
+use crate::memory::MemoryCharge;
+use crate::output_rate::resample_linear;
+use crate::player::{PlaybackStats, SeekMode};
+use crate::replay_gain::{ReplayGain, ReplayGainSettings};
+use crate::threads::{spawn_named, PCM_STREAM_THREAD};
+use crate::metadata::{parse_lrc, AudioMetadata, AudioTags, Chapter, CoverArt, FormatInfo, LyricLine, QualityParams};
+use symphonia::core::audio::{AudioBufferRef, SampleBuffer};
+use symphonia::core::codecs::{CodecParameters, Decoder, DecoderOptions, CODEC_TYPE_NULL};
 use symphonia::core::errors::Error as SymphoniaError;
-use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode as FormatSeekMode, SeekTo};
 use symphonia::core::io::{MediaSourceStream, MediaSource};
 use symphonia::core::meta::{MetadataOptions, StandardTagKey, Value, Visual};
 use symphonia::core::probe::Hint;
 use std::fs::File;
-use std::io::Cursor;
+use std::io::{Cursor, SeekFrom};
 use std::path::Path;
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::time::Instant;
+
+/// Widest layout decoded as is; wider sources play their front pair
+pub const MAX_DECODED_CHANNELS: u16 = 8;
+
+/// Channel count to decode a source with `channels` to: none is unplayable, and layouts wider
+/// than `MAX_DECODED_CHANNELS` fall back to their first two channels
+fn decoded_channels(channels: u16) -> Result<u16> {
+    match channels {
+        0 => Err(AudioError::UnsupportedFormat("Audio has no channels".to_string())),
+        count if count > MAX_DECODED_CHANNELS => {
+            log::warn!("{} channel layout not supported, playing the first two channels", count);
+            Ok(2)
+        }
+        count => Ok(count),
+    }
+}
 
 /// Audio format information
 #[derive(Debug, Clone)]
@@ -21,8 +48,135 @@ pub struct AudioFormat {
     pub channels: u16,
     pub bits_per_sample: u16,
     pub duration_ms: u64,
+    /// Encoder delay (priming) frames at the start of the stream, from the LAME tag or iTunSMPB
+    pub encoder_delay: u32,
+    /// Encoder padding frames at the end of the stream
+    pub encoder_padding: u32,
+}
+
+/// Encoder delay/padding of a gapless-encoded MP3/AAC stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GaplessInfo {
+    pub delay: u32,
+    pub padding: u32,
+    /// Frames of real audio between delay and padding, when known
+    pub valid_frames: Option<u64>,
+}
+
+impl GaplessInfo {
+    /// Delay/padding parsed by the MP3 reader from a Xing/LAME tag
+    fn from_codec_params(codec_params: &CodecParameters) -> Option<Self> {
+        let delay = codec_params.delay.unwrap_or(0);
+        let padding = codec_params.padding.unwrap_or(0);
+        if delay == 0 && padding == 0 {
+            return None;
+        }
+        let valid_frames = codec_params
+            .n_frames
+            .map(|n| n.saturating_sub(delay as u64 + padding as u64));
+        Some(Self { delay, padding, valid_frames })
+    }
+
+    /// Parse an iTunes `iTunSMPB` value: " 00000000 <delay> <padding> <valid frames> ..." in hex
+    pub fn from_itunsmpb(value: &str) -> Option<Self> {
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.len() < 4 || fields[0] != "00000000" || fields[1].len() != 8 || fields[2].len() != 8 {
+            return None;
+        }
+        let delay = u32::from_str_radix(fields[1], 16).ok()?;
+        let padding = u32::from_str_radix(fields[2], 16).ok()?;
+        let valid_frames = u64::from_str_radix(fields[3], 16).ok().filter(|&n| n > 0);
+        Some(Self { delay, padding, valid_frames })
+    }
+}
+
+/// Iterator over decoded packets, see `AudioDecoder::frames`
+pub struct Frames<'a> {
+    decoder: &'a mut AudioDecoder,
+    done: bool,
+}
+
+impl Iterator for Frames<'_> {
+    type Item = Result<Vec<f32>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.decoder.decode_next() {
+            Ok(Some(samples)) => Some(Ok(samples)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Packets queued by `AudioDecoder::into_pcm_stream` before its worker blocks
+pub const PCM_STREAM_QUEUE_PACKETS: usize = 16;
+
+/// Message of `AudioDecoder::into_pcm_stream`
+#[derive(Debug, Clone)]
+pub enum PcmMessage {
+    /// Sent first: format of every following packet (channels as returned by `decode_next`)
+    Format(AudioFormat),
+    /// Interleaved samples of one decoded packet
+    Samples(Vec<f32>),
+}
+
+/// What `get_status` reads from the decoder, taken under one lock so the fields agree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecoderStatus {
+    pub duration_ms: u64,
+    pub duration_known: bool,
+    pub sample_rate: u32,
+    /// Output loops from memory (see `set_sample_loop`), so positions wrap at `duration_ms`
+    pub looping: bool,
+}
+
+impl DecoderStatus {
+    /// `position_ms` of played output as a position in the source: a loop's position wraps
+    pub fn source_position_ms(&self, position_ms: u64) -> u64 {
+        if self.looping && self.duration_ms > 0 {
+            position_ms % self.duration_ms
+        } else {
+            position_ms
+        }
+    }
+}
+
+/// Description of one selectable audio track
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackDescriptor {
+    pub id: u32,
+    /// Short codec name (e.g. "aac", "flac")
+    pub codec: String,
+    pub language: Option<String>,
+    pub channels: Option<u16>,
+    pub sample_rate: Option<u32>,
 }
 
+/// A streaming source whose container header (e.g. an M4A moov atom at the end of the file)
+/// may only be complete after the decoder was opened
+pub trait LateHeaderSource: Send {
+    /// True once the full header has been fetched
+    fn header_ready(&self) -> bool;
+
+    /// Open a fresh view of the source from the start so the header can be probed again
+    fn reopen(&self) -> Result<Box<dyn MediaSource>>;
+}
+
+/// Packet size assumed when the container does not declare one
+const FALLBACK_MAX_FRAMES_PER_PACKET: u64 = 65536;
+
+/// Larger ID3v2 tags (mostly embedded artwork) are not read for chapters
+const MAX_ID3_TAG_SIZE: usize = 16 * 1024 * 1024;
+
 /// Audio decoder wrapper
 pub struct AudioDecoder {
     format_reader: Box<dyn FormatReader>,
@@ -32,6 +186,53 @@ pub struct AudioDecoder {
     pub metadata: AudioMetadata,
     cover_art: Option<CoverArt>,
     channels_verified: bool,
+    /// Frames to drop from the next decoded packet so playback starts exactly at the seek target
+    pending_skip_frames: u64,
+    /// Source to re-probe once its header lands, with the hint used to open it
+    late_header: Option<(Box<dyn LateHeaderSource>, Hint)>,
+    /// Frame position just past the last decoded packet
+    end_frame: u64,
+    /// Drop encoder delay/padding from decoded output (see `set_gapless_trim`)
+    gapless_trim: bool,
+    /// Raw frame position where the encoder padding starts, when known
+    gapless_end_frame: Option<u64>,
+    /// Duration was refined since the last `take_duration_change`
+    duration_changed: bool,
+    /// `format.duration_ms` comes from a frame count or the end of the stream, not a placeholder
+    duration_known: bool,
+    /// Interleaving buffer reused across packets
+    sample_buffer: Option<SampleBuffer<f32>>,
+    /// Decode throughput counters for `stats`
+    stats: PlaybackStats,
+    /// Loudness normalization applied to decoded output
+    replay_gain: ReplayGainSettings,
+    /// In-band metadata changed since the last `take_metadata_change`
+    metadata_changed: bool,
+    /// Rate and channel count of the last decoded packet, to spot mid-stream format changes
+    packet_spec: Option<(u32, u16)>,
+    /// Whole output decoded into memory and played in a loop (see `set_sample_loop`)
+    loop_buffer: Option<LoopBuffer>,
+}
+
+/// A probed container: everything `AudioDecoder` needs except the codec decoder
+struct ProbedSource {
+    format_reader: Box<dyn FormatReader>,
+    probe_metadata: symphonia::core::probe::ProbedMetadata,
+    track_id: u32,
+    codec_params: CodecParameters,
+    format: AudioFormat,
+    metadata: AudioMetadata,
+    channels_verified: bool,
+    gapless_end_frame: Option<u64>,
+    total_frames: Option<u64>,
+}
+
+/// Read tags, cover art, chapters and format info from a file without creating a codec
+/// decoder or reading any audio packets (for library scanners)
+pub fn read_metadata_only(path: &str) -> Result<AudioMetadata> {
+    let file = File::open(path)
+        .map_err(|e| AudioError::LoadError(format!("Failed to open file: {}", e)))?;
+    AudioDecoder::read_metadata_from_source(Box::new(file), AudioDecoder::create_hint_from_path(path))
 }
 
 impl AudioDecoder {
@@ -48,84 +249,66 @@ impl AudioDecoder {
 
     /// Create decoder from memory buffer
     pub fn from_buffer(buffer: Vec<u8>) -> Result<Self> {
+        Self::from_buffer_with_hint(buffer, Hint::new())
+    }
+
+    /// Create decoder from memory buffer whose format is hinted (e.g. by a MIME type)
+    pub fn from_buffer_with_hint(buffer: Vec<u8>, hint: Hint) -> Result<Self> {
         let cursor = Cursor::new(buffer);
         let media_source = Box::new(cursor);
-        let hint = Hint::new();
 
         Self::from_media_source(media_source, hint)
     }
 
+    /// Create decoder streaming `url` over HTTP range requests
+    /// Bytes are cached with any other open load of the same URL, so an independent decoder can
+    /// preview an episode (e.g. its first 30 s) while a player streams it, without fetching twice
+    /// or touching the player's position.
+    /// When the server has not said how large the file is, the track info is read again once
+    /// it does (see `refresh_if_header_ready`).
+    pub fn from_url(url: &str) -> Result<Self> {
+        Self::from_url_with_stages(url, |_| {})
+    }
+
+    /// `from_url`, reporting each stage of opening the stream to `on_stage` as it starts
+    pub fn from_url_with_stages(url: &str, mut on_stage: impl FnMut(StreamStage)) -> Result<Self> {
+        on_stage(StreamStage::FetchingHeader);
+        let source = crate::http_range_source::HttpRangeSource::new(url.to_string())?;
+        let hint = Self::create_hint_from_url(url);
+        let late_header = source.byte_len().is_none().then(|| source.share());
+        on_stage(StreamStage::FetchingMoov);
+        let mut decoder = Self::from_streaming_source(Box::new(source), hint.clone())?;
+        if let Some(late_header) = late_header {
+            decoder.set_late_header_source(Box::new(late_header), hint);
+        }
+        on_stage(StreamStage::Ready);
+        Ok(decoder)
+    }
+
     /// Create decoder from a streaming media source (for progressive playback)
     pub fn from_streaming_source(media_source: Box<dyn MediaSource>, hint: Hint) -> Result<Self> {
         Self::from_media_source(media_source, hint)
     }
 
     /// Create decoder from media source
-    fn from_media_source(
-        media_source: Box<dyn MediaSource>,
-        hint: Hint,
-    ) -> Result<Self> {
-        let media_source_stream = MediaSourceStream::new(media_source, Default::default());
-
-        // Probe the media source
-        let mut probe_result = symphonia::default::get_probe()
-            .format(&hint, media_source_stream, &FormatOptions::default(), &MetadataOptions::default())
-            .map_err(|e| AudioError::LoadError(format!("Failed to probe media: {}", e)))?;
-
-        let mut format_reader = probe_result.format;
-
-        // Get the default track
-        let track = format_reader
-            .default_track()
-            .ok_or_else(|| AudioError::LoadError("No default track found".to_string()))?;
-
-        let track_id = track.id;
-        let codec_params = track.codec_params.clone();
+    fn from_media_source(media_source: Box<dyn MediaSource>, hint: Hint) -> Result<Self> {
+        let ProbedSource {
+            format_reader,
+            mut probe_metadata,
+            track_id,
+            codec_params,
+            format,
+            metadata,
+            channels_verified,
+            gapless_end_frame,
+            total_frames,
+        } = Self::probe_source(media_source, hint)?;
 
         // Create decoder for the track
-        let decoder = symphonia::default::get_codecs()
-            .make(&codec_params, &DecoderOptions::default())
-            .map_err(|e| AudioError::DecodingError(format!("Failed to create decoder: {}", e)))?;
-
-        // Extract audio format information
-        let sample_rate = codec_params.sample_rate
-            .ok_or_else(|| AudioError::UnsupportedFormat("Sample rate not specified".to_string()))?;
-
-        // Try to get channels from codec params, but if not available, decode the first packet
-        let (channels, channels_verified) = if let Some(ch) = codec_params.channels {
-            (ch.count() as u16, true)
-        } else {
-            log::warn!("Channels not specified in codec params, will decode first packet to determine");
-            // Default to stereo, we'll verify this when decoding the first packet
-            (2, false)
-        };
-
-        // Calculate duration
-        let duration_ms = if let Some(n_frames) = codec_params.n_frames {
-            (n_frames * 1000) / sample_rate as u64
-        } else {
-            0 // Unknown duration (streaming)
-        };
-
-        let format = AudioFormat {
-            sample_rate,
-            channels,
-            bits_per_sample: 16, // Default to 16-bit
-            duration_ms,
-        };
-
-        // Extract comprehensive metadata
-        let metadata = Self::extract_metadata(
-            &mut format_reader,
-            &mut probe_result.metadata,
-            &codec_params,
-            sample_rate,
-            channels,
-            duration_ms,
-        );
+        let decoder = Self::make_codec_decoder(&codec_params)?;
 
         // Extract cover art if available
-        let cover_art = Self::extract_cover_art(&mut probe_result.metadata);
+        let cover_art = Self::extract_cover_art(&mut probe_metadata);
 
         // Log essential information only
         log::info!("Loaded audio: {}Hz, {} ch, {}ms, codec={}",
@@ -256,15 +439,431 @@ impl AudioDecoder {
             metadata,
             cover_art,
             channels_verified,
+            pending_skip_frames: 0,
+            late_header: None,
+            end_frame: 0,
+            gapless_trim: true,
+            gapless_end_frame,
+            duration_changed: false,
+            duration_known: total_frames.is_some_and(|n| n > 0),
+            replay_gain: ReplayGainSettings::default(),
+            sample_buffer: None,
+            stats: PlaybackStats::default(),
+            metadata_changed: false,
+            packet_spec: None,
+            loop_buffer: None,
+        })
+    }
+
+    /// Header metadata plus cover art from a media source; see `read_metadata_only`
+    fn read_metadata_from_source(media_source: Box<dyn MediaSource>, hint: Hint) -> Result<AudioMetadata> {
+        let mut probed = Self::probe_source(media_source, hint)?;
+        probed.metadata.cover_art = Self::extract_cover_art(&mut probed.probe_metadata);
+        Ok(probed.metadata)
+    }
+
+    /// Probe the container and read its header metadata, without creating a codec decoder
+    fn probe_source(mut media_source: Box<dyn MediaSource>, hint: Hint) -> Result<ProbedSource> {
+        // Symphonia skips ID3v2 CHAP frames, so read them before probing
+        let chapters = Self::read_id3_chapters(media_source.as_mut());
+
+        let media_source_stream = MediaSourceStream::new(media_source, Default::default());
+
+        // Probe the media source
+        let probe_result = symphonia::default::get_probe()
+            .format(&hint, media_source_stream, &FormatOptions::default(), &MetadataOptions::default())
+            .map_err(|e| AudioError::LoadError(format!("Failed to probe media: {}", e)))?;
+
+        let mut format_reader = probe_result.format;
+        let mut probe_metadata = probe_result.metadata;
+
+        // Get the default track
+        let track = format_reader
+            .default_track()
+            .ok_or_else(|| AudioError::LoadError("No default track found".to_string()))?;
+
+        let track_id = track.id;
+        let codec_params = track.codec_params.clone();
+
+        // Extract audio format information
+        let sample_rate = codec_params.sample_rate
+            .ok_or_else(|| AudioError::UnsupportedFormat("Sample rate not specified".to_string()))?;
+
+        // Try to get channels from codec params, but if not available, decode the first packet
+        let (channels, channels_verified) = if let Some(ch) = codec_params.channels {
+            (ch.count() as u16, true)
+        } else {
+            log::warn!("Channels not specified in codec params, will decode first packet to determine");
+            // Default to stereo, we'll verify this when decoding the first packet
+            (2, false)
+        };
+
+        // Encoder delay/padding: LAME tag first, then an iTunSMPB tag (iTunes AAC/MP3)
+        let gapless = GaplessInfo::from_codec_params(&codec_params).or_else(|| {
+            let probe_tags = probe_metadata
+                .get()
+                .and_then(|log| log.current().and_then(|rev| Self::find_itunsmpb(rev.tags())));
+            probe_tags.or_else(|| {
+                format_reader
+                    .metadata()
+                    .current()
+                    .and_then(|rev| Self::find_itunsmpb(rev.tags()))
+            })
+        });
+        let gapless_end_frame = gapless.and_then(|g| Some(g.delay as u64 + g.valid_frames?));
+
+        // Calculate duration (real audio only for gapless streams)
+        let total_frames = gapless.and_then(|g| g.valid_frames).or(codec_params.n_frames);
+        let duration_ms = if let Some(n_frames) = total_frames {
+            (n_frames * 1000) / sample_rate as u64
+        } else {
+            0 // Unknown duration (streaming)
+        };
+
+        let format = AudioFormat {
+            sample_rate,
+            channels: decoded_channels(channels)?,
+            bits_per_sample: 16, // Default to 16-bit
+            duration_ms,
+            encoder_delay: gapless.map_or(0, |g| g.delay),
+            encoder_padding: gapless.map_or(0, |g| g.padding),
+        };
+
+        // Extract comprehensive metadata
+        let mut metadata = Self::extract_metadata(
+            &mut format_reader,
+            &mut probe_metadata,
+            &codec_params,
+            sample_rate,
+            channels,
+            duration_ms,
+        );
+        metadata.chapters = chapters;
+
+        Ok(ProbedSource {
+            format_reader,
+            probe_metadata,
+            track_id,
+            codec_params,
+            format,
+            metadata,
+            channels_verified,
+            gapless_end_frame,
+            total_frames,
+        })
+    }
+
+    /// Create the codec decoder for a track's parameters
+    fn make_codec_decoder(codec_params: &CodecParameters) -> Result<Box<dyn Decoder>> {
+        // Matroska PCM tracks carry no frames-per-packet hint, which the PCM decoder requires
+        let mut codec_params = codec_params.clone();
+        if codec_params.max_frames_per_packet.is_none() {
+            codec_params.with_max_frames_per_packet(FALLBACK_MAX_FRAMES_PER_PACKET);
+        }
+
+        symphonia::default::get_codecs()
+            .make(&codec_params, &DecoderOptions::default())
+            .map_err(|e| AudioError::DecodingError(format!("Failed to create decoder: {}", e)))
+    }
+
+    /// List the audio tracks in the container (tracks without a codec are skipped)
+    pub fn list_tracks(&self) -> Vec<TrackDescriptor> {
+        self.format_reader
+            .tracks()
+            .iter()
+            .filter(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .map(|t| TrackDescriptor {
+                id: t.id,
+                codec: symphonia::default::get_codecs()
+                    .get_codec(t.codec_params.codec)
+                    .map(|d| d.short_name.to_string())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                language: t.language.clone(),
+                channels: t.codec_params.channels.map(|c| c.count() as u16),
+                sample_rate: t.codec_params.sample_rate,
+            })
+            .collect()
+    }
+
+    /// Currently selected track id
+    pub fn track_id(&self) -> u32 {
+        self.track_id
+    }
+
+    /// Switch to another track and rebuild the codec decoder for it.
+    /// `format` is updated to the new track; reading continues from the current container position.
+    pub fn select_track(&mut self, track_id: u32) -> Result<()> {
+        let codec_params = self
+            .format_reader
+            .tracks()
+            .iter()
+            .find(|t| t.id == track_id && t.codec_params.codec != CODEC_TYPE_NULL)
+            .map(|t| t.codec_params.clone())
+            .ok_or_else(|| AudioError::InvalidState(format!("No audio track with id {}", track_id)))?;
+
+        let decoder = Self::make_codec_decoder(&codec_params)?;
+        let sample_rate = codec_params
+            .sample_rate
+            .ok_or_else(|| AudioError::UnsupportedFormat("Sample rate not specified".to_string()))?;
+        let channels = codec_params.channels.map(|ch| decoded_channels(ch.count() as u16)).transpose()?;
+
+        self.decoder = decoder;
+        self.track_id = track_id;
+        self.format.sample_rate = sample_rate;
+        self.channels_verified = channels.is_some();
+        if let Some(channels) = channels {
+            self.format.channels = channels;
+        }
+        let gapless = GaplessInfo::from_codec_params(&codec_params);
+        self.format.encoder_delay = gapless.map_or(0, |g| g.delay);
+        self.format.encoder_padding = gapless.map_or(0, |g| g.padding);
+        self.gapless_end_frame = gapless.and_then(|g| Some(g.delay as u64 + g.valid_frames?));
+        if let Some(n_frames) = gapless.and_then(|g| g.valid_frames).or(codec_params.n_frames) {
+            self.format.duration_ms = (n_frames * 1000) / sample_rate as u64;
+            self.duration_known = true;
+        }
+        self.pending_skip_frames = 0;
+        self.packet_spec = None;
+
+        log::info!("Selected track {}: {}Hz, {} ch", track_id, self.format.sample_rate, self.format.channels);
+        Ok(())
+    }
+
+    /// Whether decoded output has the encoder delay/padding removed (on by default).
+    /// Turn off to get the raw stream and trim using `format.encoder_delay`/`encoder_padding`.
+    pub fn set_gapless_trim(&mut self, enabled: bool) {
+        self.gapless_trim = enabled;
+    }
+
+    /// Normalize decoded output with the track's ReplayGain tags (off by default)
+    pub fn set_replay_gain(&mut self, settings: ReplayGainSettings) {
+        self.replay_gain = settings;
+    }
+
+    /// Scale `samples` by the ReplayGain normalization; also for output decoded before
+    /// `set_replay_gain` was called (a background prepare's pre-buffer)
+    pub(crate) fn apply_replay_gain(&self, samples: &mut [f32]) {
+        // Tags can change in-band, so the gain follows the current ones
+        let gain = self.metadata.tags.replay_gain.normalization_gain(self.replay_gain);
+        if gain != 1.0 {
+            for sample in samples.iter_mut() {
+                *sample *= gain;
+            }
+        }
+    }
+
+    /// Encoder delay/padding from an `iTunSMPB` tag: an MP4 freeform tag,
+    /// or an ID3 comment (whose description symphonia drops, so match on the value)
+    fn find_itunsmpb(tags: &[symphonia::core::meta::Tag]) -> Option<GaplessInfo> {
+        tags.iter().find_map(|tag| {
+            let is_smpb_key = tag.key.contains("iTunSMPB");
+            if !is_smpb_key && tag.std_key != Some(StandardTagKey::Comment) {
+                return None;
+            }
+            match &tag.value {
+                Value::String(value) => GaplessInfo::from_itunsmpb(value),
+                _ => None,
+            }
         })
     }
 
+    /// Frames to drop from a decoded packet at `start_frame` so only audio between the
+    /// encoder delay and padding remains: (leading, trailing)
+    fn gapless_trim_frames(&self, start_frame: u64, frames: u64) -> (u64, u64) {
+        if !self.gapless_trim {
+            return (0, 0);
+        }
+        let lead = (self.format.encoder_delay as u64).saturating_sub(start_frame).min(frames);
+        let tail = self
+            .gapless_end_frame
+            .map_or(0, |end| (start_frame + frames).saturating_sub(end))
+            .min(frames - lead);
+        (lead, tail)
+    }
+
+    /// Encoder delay the output timeline is shifted by
+    fn trimmed_delay(&self) -> u64 {
+        if self.gapless_trim {
+            self.format.encoder_delay as u64
+        } else {
+            0
+        }
+    }
+
+    /// Watch `source` and refresh track info once its header becomes available
+    pub fn set_late_header_source(&mut self, source: Box<dyn LateHeaderSource>, hint: Hint) {
+        self.late_header = Some((source, hint));
+    }
+
+    /// Re-read track info if a late header has arrived since the last call.
+    /// Returns the new duration when it changed; the watch is dropped after the first refresh.
+    pub fn refresh_if_header_ready(&mut self) -> Option<u64> {
+        if !self.late_header.as_ref()?.0.header_ready() {
+            return None;
+        }
+        let (source, hint) = self.late_header.take()?;
+
+        let probed = source.reopen().and_then(|media_source| {
+            let stream = MediaSourceStream::new(media_source, Default::default());
+            symphonia::default::get_probe()
+                .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+                .map_err(|e| AudioError::LoadError(format!("Failed to re-probe media: {}", e)))
+        });
+        let probed = match probed {
+            Ok(probed) => probed,
+            Err(e) => {
+                log::warn!("Header refresh failed: {}", e);
+                return None;
+            }
+        };
+
+        let tracks = probed.format.tracks();
+        let track = tracks
+            .iter()
+            .find(|t| t.id == self.track_id)
+            .or_else(|| probed.format.default_track())?;
+        let n_frames = track.codec_params.n_frames?;
+        let sample_rate = track.codec_params.sample_rate.unwrap_or(self.format.sample_rate);
+        let duration_ms = (n_frames * 1000) / sample_rate.max(1) as u64;
+        self.duration_known = true;
+
+        if duration_ms == self.format.duration_ms {
+            return None;
+        }
+        log::info!("Header refreshed: duration {}ms -> {}ms", self.format.duration_ms, duration_ms);
+        self.format.duration_ms = duration_ms;
+        self.metadata.format_info.duration_ms = duration_ms;
+        self.metadata.format_info.total_frames = Some(n_frames);
+        Some(duration_ms)
+    }
+
+    fn time_base(&self) -> Option<symphonia::core::units::TimeBase> {
+        self.format_reader
+            .tracks()
+            .iter()
+            .find(|t| t.id == self.track_id)
+            .and_then(|t| t.codec_params.time_base)
+    }
+
+    /// Convert a packet timestamp (track time base) to a frame position
+    fn ts_to_frames(&self, ts: u64) -> u64 {
+        match self.time_base() {
+            Some(tb) => {
+                (ts as u128 * self.format.sample_rate as u128 * tb.numer as u128 / tb.denom as u128) as u64
+            }
+            None => ts,
+        }
+    }
+
+    /// Convert a frame position to a timestamp in the track time base (rounded down)
+    fn frames_to_ts(&self, frames: u64) -> u64 {
+        match self.time_base() {
+            Some(tb) if self.format.sample_rate > 0 => {
+                let per_tick = self.format.sample_rate as u128 * tb.numer as u128;
+                (frames as u128 * tb.denom as u128 / per_tick) as u64
+            }
+            _ => frames,
+        }
+    }
+
+    /// At end of stream the end of the last decoded packet is the exact duration; VBR and live
+    /// streams often only had an estimate (or nothing) when the decoder was opened
+    fn refine_duration_at_eof(&mut self) {
+        let mut end_frame = self.end_frame;
+        if self.gapless_trim {
+            end_frame = end_frame.min(self.gapless_end_frame.unwrap_or(u64::MAX));
+        }
+        let end_frame = end_frame.saturating_sub(self.trimmed_delay());
+        let end_ms = (end_frame * 1000) / self.format.sample_rate.max(1) as u64;
+        self.duration_known = true;
+
+        if end_ms > 0 && end_ms != self.format.duration_ms {
+            log::info!("Duration refined at end of stream: {}ms -> {}ms", self.format.duration_ms, end_ms);
+            self.format.duration_ms = end_ms;
+            self.metadata.format_info.duration_ms = end_ms;
+            self.duration_changed = true;
+        }
+    }
+
+    /// Pick up metadata the container delivered in-band since the last packet
+    fn check_metadata_revision(&mut self) {
+        let mut log = self.format_reader.metadata();
+        if log.is_latest() {
+            return;
+        }
+        if let Some(revision) = log.skip_to_latest() {
+            let tags = Self::extract_tags(revision.tags());
+            Self::merge_tags(&mut self.metadata.tags, tags);
+            self.metadata_changed = true;
+        }
+    }
+
+    /// Whether `format.duration_ms` is reliable: read from a frame count (container, Xing/LAME
+    /// or a late header) or measured at the end of the stream. False while it is only a placeholder.
+    pub fn duration_known(&self) -> bool {
+        self.duration_known
+    }
+
+    /// Duration and rate for `get_status`
+    pub fn status(&self) -> DecoderStatus {
+        DecoderStatus {
+            duration_ms: self.format.duration_ms,
+            duration_known: self.duration_known,
+            sample_rate: self.format.sample_rate,
+            looping: self.loop_buffer.is_some(),
+        }
+    }
+
+    /// Decode counters since the source was opened
+    pub fn stats(&self) -> PlaybackStats {
+        self.stats
+    }
+
+    /// Duration learned since the last call (end of stream reached with a different length)
+    pub fn take_duration_change(&mut self) -> Option<u64> {
+        std::mem::take(&mut self.duration_changed).then_some(self.format.duration_ms)
+    }
+
+    /// Whether in-band metadata changed since the last call
+    pub fn take_metadata_change(&mut self) -> bool {
+        std::mem::take(&mut self.metadata_changed)
+    }
+
+    /// Dispatch DurationChanged / MetadataChanged for anything the decoder learned since the last call
+    pub(crate) fn dispatch_changes(&mut self, callbacks: &CallbackManager) {
+        let refreshed = self.refresh_if_header_ready();
+        if let Some(duration_ms) = self.take_duration_change().or(refreshed) {
+            callbacks.dispatch_event(CallbackEvent::DurationChanged { duration_ms });
+        }
+        if self.take_metadata_change() {
+            callbacks.dispatch_event(CallbackEvent::MetadataChanged);
+        }
+    }
+
     /// Decode next packet and return audio samples
     pub fn decode_next(&mut self) -> Result<Option<Vec<f32>>> {
+        if let Some(buffer) = &mut self.loop_buffer {
+            let mut samples = buffer.next_chunk(LOOP_CHUNK_FRAMES);
+            self.apply_replay_gain(&mut samples);
+            return Ok(Some(samples));
+        }
+
+        let started = Instant::now();
+        let decoded = self.decode_next_packet();
+        if let Ok(Some(samples)) = &decoded {
+            self.stats.decoded_frames += (samples.len() / self.output_channels().max(1) as usize) as u64;
+            self.stats.decode_time += started.elapsed();
+        }
+        decoded
+    }
+
+    /// Decode up to the next packet with audio for this track (`decode_next` without the stats)
+    fn decode_next_packet(&mut self) -> Result<Option<Vec<f32>>> {
         // Get the next packet
         let packet = match self.format_reader.next_packet() {
             Ok(packet) => packet,
             Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                self.refine_duration_at_eof();
                 return Ok(None); // End of stream
             }
             Err(e) => {
@@ -274,12 +873,24 @@ impl AudioDecoder {
 
         // Skip packets that don't belong to our track
         if packet.track_id() != self.track_id {
-            return self.decode_next();
+            return self.decode_next_packet();
         }
+        self.check_metadata_revision();
 
         // Decode the packet
-        let decoded = self.decoder.decode(&packet)
-            .map_err(|e| AudioError::DecodingError(format!("Failed to decode packet: {}", e)))?;
+        let start_frame = self.ts_to_frames(packet.ts());
+        if let Err(e) = self.decoder.decode(&packet) {
+            // Decoders reject a packet whose format differs from the earlier ones (concatenated
+            // segments); a fresh decoder takes the new format
+            if !matches!(e, SymphoniaError::DecodeError(_)) || self.packet_spec.is_none() {
+                return Err(AudioError::DecodingError(format!("Failed to decode packet: {}", e)));
+            }
+            self.rebuild_codec_decoder()?;
+            self.decoder.decode(&packet)
+                .map_err(|e| AudioError::DecodingError(format!("Failed to decode packet: {}", e)))?;
+        }
+        let decoded = self.decoder.last_decoded();
+        self.end_frame = start_frame + decoded.frames() as u64;
 
         // Verify/update channels on first decode if not already verified
         if !self.channels_verified {
@@ -296,25 +907,143 @@ impl AudioDecoder {
                 AudioBufferRef::F64(buf) => buf.spec().channels.count(),
             } as u16;
 
-            if actual_channels != self.format.channels {
+            let playable_channels = decoded_channels(actual_channels)?;
+            if playable_channels != self.format.channels {
                 log::info!("Updating channels from default {} to actual {}", self.format.channels, actual_channels);
-                self.format.channels = actual_channels;
+                self.format.channels = playable_channels;
                 self.metadata.format_info.channels = actual_channels;
             }
             self.channels_verified = true;
         }
 
-        // Convert audio buffer to f32 samples
-        let mut samples = Self::convert_to_f32(&decoded)?;
+        let packet_spec = (decoded.spec().rate, decoded.spec().channels.count() as u16);
+        if packet_spec.1 == 0 {
+            return Err(AudioError::UnsupportedFormat("Packet has no channels".to_string()));
+        }
+        if self.packet_spec.is_some_and(|previous| previous != packet_spec) {
+            log::info!(
+                "Stream format changed to {}Hz, {} ch; converting to {}Hz, {} ch",
+                packet_spec.0,
+                packet_spec.1,
+                self.format.sample_rate,
+                self.format.channels
+            );
+        }
+        self.packet_spec = Some(packet_spec);
+
+        // Convert audio buffer to f32 samples in the format established at load
+        let samples = Self::convert_to_f32(&mut self.sample_buffer, decoded);
+        let established = (self.format.sample_rate, self.format.channels);
+        let mut samples = Self::conform_to_format(samples, packet_spec, established);
 
         // Convert mono to stereo if needed
         if self.format.channels == 1 {
             samples = Self::mono_to_stereo(samples);
         }
 
+        // Drop encoder delay/padding so consecutive tracks join without a gap
+        let (lead_frames, tail_frames) = self.gapless_trim_frames(start_frame, self.end_frame - start_frame);
+        if lead_frames > 0 || tail_frames > 0 {
+            let channels = self.output_channels().max(1) as usize;
+            samples.truncate(samples.len() - tail_frames as usize * channels);
+            samples.drain(..lead_frames as usize * channels);
+            // The seek skip below counts from the packet start, which the delay trim already covered
+            self.pending_skip_frames = self.pending_skip_frames.saturating_sub(lead_frames);
+            if samples.is_empty() {
+                return self.decode_next_packet();
+            }
+        }
+
+        // Trim audio preceding the seek target (seeks land on packet boundaries)
+        if self.pending_skip_frames > 0 {
+            let channels = self.output_channels().max(1) as u64;
+            let skip = (self.pending_skip_frames * channels).min(samples.len() as u64) as usize;
+            samples.drain(..skip);
+            self.pending_skip_frames -= skip as u64 / channels;
+            if samples.is_empty() {
+                return self.decode_next_packet();
+            }
+        }
+
+        self.apply_replay_gain(&mut samples);
         Ok(Some(samples))
     }
 
+    /// Loop the whole output sample-accurately, or stop looping
+    /// Enabling decodes the source once into memory (it must be at most `LOOP_BUFFER_MAX_MS`
+    /// long) and `decode_next` then cycles through it from `position_ms`, going from the last
+    /// frame straight to the first without seeking. Disabling seeks the source to `position_ms`,
+    /// so playback carries on from there and ends normally.
+    pub fn set_sample_loop(&mut self, enabled: bool, position_ms: u64) -> Result<()> {
+        let sample_rate = self.format.sample_rate.max(1) as u64;
+        if !enabled {
+            if self.loop_buffer.take().is_some() {
+                self.seek_to_sample(position_ms * sample_rate / 1000)?;
+            }
+            return Ok(());
+        }
+        if self.format.duration_ms > LOOP_BUFFER_MAX_MS {
+            return Err(AudioError::UnsupportedFormat(format!(
+                "{} ms is too long to loop from memory (at most {} ms)",
+                self.format.duration_ms, LOOP_BUFFER_MAX_MS
+            )));
+        }
+
+        self.seek_to_sample(0)?;
+        let mut samples = Vec::new();
+        // Without replay gain, which `decode_next` applies to each chunk as it is played
+        while let Some(packet) = self.decode_next_packet()? {
+            samples.extend_from_slice(&packet);
+            if samples.len() as u64 / self.output_channels() as u64 > LOOP_BUFFER_MAX_MS * sample_rate / 1000 {
+                self.seek_to_sample(position_ms * sample_rate / 1000)?;
+                return Err(AudioError::UnsupportedFormat(format!(
+                    "Source is too long to loop from memory (at most {} ms)",
+                    LOOP_BUFFER_MAX_MS
+                )));
+            }
+        }
+        let buffer = LoopBuffer::new(samples, self.output_channels(), position_ms * sample_rate / 1000)?;
+        log::info!("Looping {} frames from memory", buffer.frames());
+        self.loop_buffer = Some(buffer);
+        Ok(())
+    }
+
+    /// Reset the codec's internal state without reopening the source, e.g. after a decode error
+    /// left it inconsistent. Decoding continues with the next packet; the format, metadata,
+    /// position and any pending seek trim are kept.
+    pub fn reset_codec(&mut self) {
+        self.decoder.reset();
+    }
+
+    /// Replace the codec decoder with a fresh one for the current track
+    fn rebuild_codec_decoder(&mut self) -> Result<()> {
+        let codec_params = self
+            .format_reader
+            .tracks()
+            .iter()
+            .find(|t| t.id == self.track_id)
+            .map(|t| t.codec_params.clone())
+            .ok_or_else(|| AudioError::InvalidState(format!("No audio track with id {}", self.track_id)))?;
+        self.decoder = Self::make_codec_decoder(&codec_params)?;
+        Ok(())
+    }
+
+    /// Iterate decoded packets (the output of `decode_next`) until end of stream.
+    /// Iteration stops after the first error.
+    pub fn frames(&mut self) -> Frames<'_> {
+        Frames { decoder: self, done: false }
+    }
+
+    /// Remix and resample a packet's samples from its (rate, channels) to the stream's
+    fn conform_to_format(samples: Vec<f32>, from: (u32, u16), to: (u32, u16)) -> Vec<f32> {
+        let samples = if from.1 != to.1 { remix_channels(&samples, from.1, to.1) } else { samples };
+        if from.0 != to.0 {
+            resample_linear(&samples, from.0, to.0, to.1)
+        } else {
+            samples
+        }
+    }
+
     /// Convert mono samples to stereo by duplicating each sample
     fn mono_to_stereo(mono_samples: Vec<f32>) -> Vec<f32> {
         let mut stereo_samples = Vec::with_capacity(mono_samples.len() * 2);
@@ -325,104 +1054,151 @@ impl AudioDecoder {
         stereo_samples
     }
 
+    /// Decode the remaining stream to EOF without real-time playback
+    /// Returns the output format and all interleaved samples (mono sources are upmixed to stereo,
+    /// matching `decode_next`, and the returned format reflects that).
+    pub fn decode_all(&mut self) -> Result<(AudioFormat, Vec<f32>)> {
+        self.decode_all_with_progress(|_, _| {})
+    }
+
+    /// Same as `decode_all`, calling `progress(decoded_ms, duration_ms)` after every packet
+    /// `duration_ms` is 0 when the stream does not report a duration
+    pub fn decode_all_with_progress<F>(&mut self, mut progress: F) -> Result<(AudioFormat, Vec<f32>)>
+    where
+        F: FnMut(u64, u64),
+    {
+        let expected_samples = (self.format.duration_ms * self.format.sample_rate as u64 / 1000) as usize
+            * self.output_channels() as usize;
+        let mut output = Vec::with_capacity(expected_samples);
+
+        while let Some(samples) = self.decode_next()? {
+            output.extend_from_slice(&samples);
+
+            let frames = output.len() as u64 / self.output_channels().max(1) as u64;
+            let decoded_ms = if self.format.sample_rate > 0 {
+                frames * 1000 / self.format.sample_rate as u64
+            } else {
+                0
+            };
+            progress(decoded_ms, self.format.duration_ms);
+        }
+
+        let mut format = self.format.clone();
+        format.channels = self.output_channels();
+        Ok((format, output))
+    }
+
+    /// Decode on a worker thread, for consumers that want PCM without playback (transcoding).
+    /// The channel yields `PcmMessage::Format` first, then one `Samples` per packet, and closes
+    /// at end of stream or after the first error. At most `PCM_STREAM_QUEUE_PACKETS` packets are
+    /// queued: the worker blocks until the receiver catches up, and exits once it is dropped.
+    pub fn into_pcm_stream(mut self) -> Result<Receiver<Result<PcmMessage>>> {
+        let (sender, receiver) = sync_channel(PCM_STREAM_QUEUE_PACKETS);
+        spawn_named(PCM_STREAM_THREAD, move || {
+            let mut format = self.format.clone();
+            format.channels = self.output_channels();
+            if sender.send(Ok(PcmMessage::Format(format))).is_err() {
+                return;
+            }
+
+            loop {
+                let message = match self.decode_next() {
+                    Ok(Some(samples)) => Ok(PcmMessage::Samples(samples)),
+                    Ok(None) => break,
+                    Err(e) => Err(e),
+                };
+                let failed = message.is_err();
+                // Fails once the receiver has been dropped
+                if sender.send(message).is_err() || failed {
+                    break;
+                }
+            }
+            log::debug!("PCM stream finished");
+        })?;
+        Ok(receiver)
+    }
+
+    /// Channel count of the samples returned by `decode_next` (mono is upmixed to stereo)
+    pub(crate) fn output_channels(&self) -> u16 {
+        if self.format.channels == 1 {
+            2
+        } else {
+            self.format.channels
+        }
+    }
+
     /// Seek to a specific time position
     pub fn seek(&mut self, position_ms: u64) -> Result<()> {
-        let sample_position = (position_ms * self.format.sample_rate as u64) / 1000;
+        self.seek_to_sample((position_ms * self.format.sample_rate as u64) / 1000)
+    }
 
-        self.format_reader
+    /// Seek to an exact frame of the output: the next `decode_next` starts with frame `frame_index`.
+    /// Seeks land on packet boundaries; the frames before the target are decoded and dropped.
+    pub fn seek_to_sample(&mut self, frame_index: u64) -> Result<()> {
+        if let Some(buffer) = &mut self.loop_buffer {
+            buffer.seek_to_frame(frame_index);
+            return Ok(());
+        }
+
+        // Frame 0 of the trimmed output is the first frame after the encoder delay
+        let target_frame = frame_index + self.trimmed_delay();
+
+        let seeked_to = self.format_reader
             .seek(
-                SeekMode::Accurate,
-                SeekTo::TimeStamp { ts: sample_position, track_id: self.track_id }
+                FormatSeekMode::Accurate,
+                SeekTo::TimeStamp { ts: self.frames_to_ts(target_frame), track_id: self.track_id }
             )
             .map_err(|e| AudioError::PlaybackError(format!("Seek failed: {}", e)))?;
 
         // Reset decoder after seek
         self.decoder.reset();
+        self.pending_skip_frames = target_frame.saturating_sub(self.ts_to_frames(seeked_to.actual_ts));
 
         Ok(())
     }
 
-    /// Convert AudioBufferRef to f32 samples (interleaved)
-    fn convert_to_f32(buffer: &AudioBufferRef) -> Result<Vec<f32>> {
-        use symphonia::core::conv::IntoSample;
-
-        let mut samples = Vec::new();
+    /// Seek to `position_ms` with `mode`; returns the position output continues from (ms)
+    /// Coarse seeks start at the packet holding the target without decoding up to it, so they can
+    /// land up to a packet early. Formats that cannot seek coarsely fall back to an accurate seek.
+    pub fn seek_with_mode(&mut self, position_ms: u64, mode: SeekMode) -> Result<u64> {
+        let sample_rate = self.format.sample_rate.max(1) as u64;
+        let frame_index = (position_ms * sample_rate) / 1000;
+        // A seek within the loop buffer is exact and free
+        if mode == SeekMode::Accurate || self.loop_buffer.is_some() {
+            self.seek_to_sample(frame_index)?;
+            return Ok(position_ms);
+        }
 
-        match buffer {
-            AudioBufferRef::F32(buf) => {
-                // Already f32, just interleave channels
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx]);
-                    }
-                }
-            }
-            AudioBufferRef::U8(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx].into_sample());
-                    }
-                }
-            }
-            AudioBufferRef::U16(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx].into_sample());
-                    }
-                }
-            }
-            AudioBufferRef::U24(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx].into_sample());
-                    }
-                }
-            }
-            AudioBufferRef::U32(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx].into_sample());
-                    }
-                }
-            }
-            AudioBufferRef::S8(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx].into_sample());
-                    }
-                }
-            }
-            AudioBufferRef::S16(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx].into_sample());
-                    }
-                }
-            }
-            AudioBufferRef::S24(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx].into_sample());
-                    }
-                }
-            }
-            AudioBufferRef::S32(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx].into_sample());
-                    }
-                }
-            }
-            AudioBufferRef::F64(buf) => {
-                for frame_idx in 0..buf.frames() {
-                    for ch in 0..buf.spec().channels.count() {
-                        samples.push(buf.chan(ch)[frame_idx] as f32);
-                    }
-                }
+        let target_frame = frame_index + self.trimmed_delay();
+        let to = SeekTo::TimeStamp { ts: self.frames_to_ts(target_frame), track_id: self.track_id };
+        let seeked_to = match self.format_reader.seek(FormatSeekMode::Coarse, to) {
+            Ok(seeked_to) => seeked_to,
+            Err(e) => {
+                log::debug!("Coarse seek unavailable ({}), seeking accurately", e);
+                self.seek_to_sample(frame_index)?;
+                return Ok(position_ms);
             }
-        }
+        };
+
+        // Play from wherever the packet starts instead of trimming up to the target
+        self.decoder.reset();
+        self.pending_skip_frames = 0;
+        let landed_frame = self.ts_to_frames(seeked_to.actual_ts).saturating_sub(self.trimmed_delay());
+        Ok(landed_frame * 1000 / sample_rate)
+    }
 
-        Ok(samples)
+    /// Convert AudioBufferRef to f32 samples (interleaved), through a buffer reused across packets
+    fn convert_to_f32(sample_buffer: &mut Option<SampleBuffer<f32>>, buffer: AudioBufferRef) -> Vec<f32> {
+        let needed = buffer.frames() * buffer.spec().channels.count();
+        if sample_buffer.as_ref().is_some_and(|reused| reused.capacity() < needed) {
+            *sample_buffer = None;
+        }
+        // Sized for the codec's largest packet, so this normally allocates once per source
+        let frames = buffer.capacity().max(buffer.frames()) as u64;
+        let spec = *buffer.spec();
+        let reused = sample_buffer.get_or_insert_with(|| SampleBuffer::new(frames, spec));
+        reused.copy_interleaved_ref(buffer);
+        reused.samples().to_vec()
     }
 
     /// Create a hint from file path
@@ -491,6 +1267,7 @@ impl AudioDecoder {
         if let Some(meta_ref) = probe_metadata.get() {
             if let Some(current) = meta_ref.current() {
                 metadata.tags = Self::extract_tags(current.tags());
+                metadata.synced_lyrics = Self::extract_synced_lyrics(current.tags());
             }
         }
 
@@ -499,10 +1276,19 @@ impl AudioDecoder {
             let format_tags = Self::extract_tags(metadata_rev.tags());
             // Merge tags (format_tags take precedence if present)
             Self::merge_tags(&mut metadata.tags, format_tags);
+
+            let synced = Self::extract_synced_lyrics(metadata_rev.tags());
+            if !synced.is_empty() {
+                metadata.synced_lyrics = synced;
+            }
         }
 
-        // Extract chapters if available
-        metadata.chapters = Self::extract_chapters(format_reader);
+        // Fall back to LRC timestamps embedded in unsynced lyrics
+        if metadata.synced_lyrics.is_empty() {
+            if let Some(ref lyrics) = metadata.tags.lyrics {
+                metadata.synced_lyrics = parse_lrc(lyrics);
+            }
+        }
 
         metadata
     }
@@ -539,6 +1325,13 @@ impl AudioDecoder {
                     StandardTagKey::Label => audio_tags.publisher = Some(value_str),
                     StandardTagKey::IdentIsrc => audio_tags.isrc = Some(value_str),
                     StandardTagKey::Language => audio_tags.language = Some(value_str),
+                    StandardTagKey::Mood => audio_tags.mood = Some(value_str),
+                    StandardTagKey::Bpm => match Self::parse_bpm(&value_str) {
+                        Some(bpm) => audio_tags.bpm = Some(bpm),
+                        None => {
+                            audio_tags.custom_tags.insert(tag.key.clone(), value_str);
+                        }
+                    },
                     StandardTagKey::TrackNumber => {
                         if let Ok(num) = value_str.parse::<u32>() {
                             audio_tags.track_number = Some(num);
@@ -554,6 +1347,12 @@ impl AudioDecoder {
                             audio_tags.disc_number = Some(num);
                         }
                     }
+                    StandardTagKey::ReplayGainTrackGain
+                    | StandardTagKey::ReplayGainTrackPeak
+                    | StandardTagKey::ReplayGainAlbumGain
+                    | StandardTagKey::ReplayGainAlbumPeak => {
+                        Self::store_custom_tag(&mut audio_tags, &tag.key, value_str);
+                    }
                     StandardTagKey::DiscTotal => {
                         if let Ok(num) = value_str.parse::<u32>() {
                             audio_tags.disc_total = Some(num);
@@ -565,14 +1364,147 @@ impl AudioDecoder {
                     }
                 }
             } else {
-                // Non-standard tags go into custom_tags
-                audio_tags.custom_tags.insert(tag.key.clone(), value_str);
+                Self::store_custom_tag(&mut audio_tags, &tag.key, value_str);
             }
         }
 
         audio_tags
     }
 
+    /// Store a tag without a standard key: DJ tags (tempo, key, mood, energy) get typed fields,
+    /// anything else or anything unparsable goes into custom_tags.
+    /// Keys are matched case-insensitively, with or without the ID3 "TXXX:" prefix.
+    fn store_custom_tag(audio_tags: &mut AudioTags, key: &str, value: String) {
+        let name = key.strip_prefix("TXXX:").unwrap_or(key).to_ascii_uppercase();
+        let trimmed = value.trim();
+        match name.as_str() {
+            "TBPM" | "BPM" | "TEMPO" => {
+                if let Some(bpm) = Self::parse_bpm(trimmed) {
+                    audio_tags.bpm = Some(bpm);
+                    return;
+                }
+            }
+            "TKEY" | "INITIALKEY" | "INITIAL KEY" | "KEY" if !trimmed.is_empty() => {
+                audio_tags.initial_key = Some(trimmed.to_string());
+                return;
+            }
+            "TMOO" | "MOOD" if !trimmed.is_empty() => {
+                audio_tags.mood = Some(trimmed.to_string());
+                return;
+            }
+            "ENERGY" | "ENERGYLEVEL" | "ENERGY LEVEL" => {
+                if let Ok(energy) = trimmed.parse::<u32>() {
+                    audio_tags.energy = Some(energy);
+                    return;
+                }
+            }
+            _ => {}
+        }
+        // MP4 freeform atoms carry a "----:com.apple.iTunes:" style prefix
+        let replay_gain = &mut audio_tags.replay_gain;
+        let typed = match name.rsplit(':').next().unwrap_or_default() {
+            "REPLAYGAIN_TRACK_GAIN" => Some((&mut replay_gain.track_gain_db, ReplayGain::parse_gain(trimmed))),
+            "REPLAYGAIN_TRACK_PEAK" => Some((&mut replay_gain.track_peak, ReplayGain::parse_peak(trimmed))),
+            "REPLAYGAIN_ALBUM_GAIN" => Some((&mut replay_gain.album_gain_db, ReplayGain::parse_gain(trimmed))),
+            "REPLAYGAIN_ALBUM_PEAK" => Some((&mut replay_gain.album_peak, ReplayGain::parse_peak(trimmed))),
+            _ => None,
+        };
+        if let Some((field, Some(value))) = typed {
+            *field = Some(value);
+            return;
+        }
+        audio_tags.custom_tags.insert(key.to_string(), value);
+    }
+
+    /// Tempo tags are integers in ID3 but often fractional elsewhere ("124.50")
+    fn parse_bpm(value: &str) -> Option<f32> {
+        value.trim().parse::<f32>().ok().filter(|bpm| bpm.is_finite() && *bpm > 0.0)
+    }
+
+    /// Extract synced lyrics from an ID3v2 SYLT frame (millisecond timestamps only)
+    fn extract_synced_lyrics(tags: &[symphonia::core::meta::Tag]) -> Vec<LyricLine> {
+        tags.iter()
+            .filter(|tag| tag.key.eq_ignore_ascii_case("SYLT"))
+            .find_map(|tag| match &tag.value {
+                Value::Binary(data) => Self::parse_sylt(data),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Parse the body of an ID3v2 SYLT frame
+    /// Layout: encoding(1) language(3) timestamp_format(1) content_type(1) descriptor, then (text, u32 BE time)*
+    fn parse_sylt(data: &[u8]) -> Option<Vec<LyricLine>> {
+        if data.len() < 6 {
+            return None;
+        }
+        let encoding = data[0];
+        let timestamp_format = data[4];
+        if timestamp_format != 2 {
+            // Timestamps in MPEG frames cannot be mapped without the frame rate
+            log::debug!("Unsupported SYLT timestamp format {}", timestamp_format);
+            return None;
+        }
+
+        let (_, descriptor_len) = Self::read_id3_text(&data[6..], encoding)?;
+        let mut pos = 6 + descriptor_len;
+        let mut lines = Vec::new();
+
+        while pos < data.len() {
+            let (text, consumed) = Self::read_id3_text(&data[pos..], encoding)?;
+            pos += consumed;
+            let time_bytes = data.get(pos..pos + 4)?;
+            pos += 4;
+
+            let time_ms = u32::from_be_bytes([time_bytes[0], time_bytes[1], time_bytes[2], time_bytes[3]]);
+            lines.push(LyricLine {
+                time_ms: time_ms as u64,
+                text: text.trim_start_matches('\n').to_string(),
+            });
+        }
+
+        lines.sort_by_key(|line| line.time_ms);
+        Some(lines)
+    }
+
+    /// Read a null-terminated ID3v2 string, returning the text and bytes consumed (including terminator)
+    fn read_id3_text(data: &[u8], encoding: u8) -> Option<(String, usize)> {
+        match encoding {
+            // ISO-8859-1 and UTF-8 use a single null byte terminator
+            0 | 3 => {
+                let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+                let text = if encoding == 0 {
+                    data[..end].iter().map(|&b| b as char).collect()
+                } else {
+                    String::from_utf8_lossy(&data[..end]).into_owned()
+                };
+                Some((text, (end + 1).min(data.len())))
+            }
+            // UTF-16 with BOM (1) or big-endian without BOM (2), terminated by 0x0000
+            1 | 2 => {
+                let mut end = 0;
+                while end + 1 < data.len() && (data[end] != 0 || data[end + 1] != 0) {
+                    end += 2;
+                }
+                let mut bytes = &data[..end.min(data.len())];
+                let mut big_endian = encoding == 2;
+                if encoding == 1 && bytes.len() >= 2 {
+                    match (bytes[0], bytes[1]) {
+                        (0xFF, 0xFE) => { big_endian = false; bytes = &bytes[2..]; }
+                        (0xFE, 0xFF) => { big_endian = true; bytes = &bytes[2..]; }
+                        _ => {}
+                    }
+                }
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|c| if big_endian { u16::from_be_bytes([c[0], c[1]]) } else { u16::from_le_bytes([c[0], c[1]]) })
+                    .collect();
+                Some((String::from_utf16_lossy(&units), (end + 2).min(data.len())))
+            }
+            _ => None,
+        }
+    }
+
     /// Merge tags, preferring non-None values from source
     fn merge_tags(dest: &mut AudioTags, source: AudioTags) {
         if source.title.is_some() { dest.title = source.title; }
@@ -593,6 +1525,10 @@ impl AudioDecoder {
         if source.publisher.is_some() { dest.publisher = source.publisher; }
         if source.isrc.is_some() { dest.isrc = source.isrc; }
         if source.language.is_some() { dest.language = source.language; }
+        if source.bpm.is_some() { dest.bpm = source.bpm; }
+        if source.initial_key.is_some() { dest.initial_key = source.initial_key; }
+        if source.mood.is_some() { dest.mood = source.mood; }
+        if source.energy.is_some() { dest.energy = source.energy; }
 
         // Merge custom tags
         for (key, value) in source.custom_tags {
@@ -638,17 +1574,115 @@ impl AudioDecoder {
         }
     }
 
-    /// Extract chapter information from the audio file
-    fn extract_chapters(_format_reader: &Box<dyn FormatReader>) -> Vec<Chapter> {
-        let chapters = Vec::new();
+    /// Chapters from the CHAP frames of an ID3v2 tag at the start of `source`, which is left
+    /// rewound. Unseekable sources and files without a tag have none.
+    fn read_id3_chapters(source: &mut dyn MediaSource) -> Vec<Chapter> {
+        if !source.is_seekable() {
+            return Vec::new();
+        }
+        let mut header = [0u8; 10];
+        let tag = match source.read_exact(&mut header) {
+            Ok(()) if &header[..3] == b"ID3" => {
+                let size = Self::syncsafe(&header[6..10]) as usize;
+                if size > MAX_ID3_TAG_SIZE {
+                    log::warn!("ID3 tag of {} bytes is too large to scan for chapters", size);
+                    None
+                } else {
+                    let mut body = vec![0u8; size];
+                    source.read_exact(&mut body).ok().map(|()| body)
+                }
+            }
+            _ => None,
+        };
+        if let Err(e) = source.seek(SeekFrom::Start(0)) {
+            log::warn!("Could not rewind after reading the ID3 tag: {}", e);
+        }
+
+        match tag {
+            Some(body) => Self::parse_id3_chapters(header[3], header[5], &body),
+            None => Vec::new(),
+        }
+    }
 
-        // Symphonia doesn't have a direct chapter API yet
-        // This is a placeholder for future implementation
-        // Would need format-specific parsing for MP3 CHAP, MP4 chapters, etc.
+    /// Parse the CHAP frames of an ID3v2.3/2.4 tag body, sorted by start time
+    fn parse_id3_chapters(version: u8, flags: u8, body: &[u8]) -> Vec<Chapter> {
+        // Whole-tag unsynchronisation is not undone; such tags are rare and skipped
+        if !(3..=4).contains(&version) || flags & 0x80 != 0 {
+            return Vec::new();
+        }
+        let mut pos = 0;
+        if flags & 0x40 != 0 {
+            // Extended header: v2.4 counts its size field, v2.3 does not
+            let Some(size) = body.get(..4) else { return Vec::new() };
+            pos = match version {
+                4 => Self::syncsafe(size) as usize,
+                _ => 4 + u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize,
+            };
+        }
 
+        let mut chapters: Vec<Chapter> = Self::id3_frames(version, body.get(pos..).unwrap_or_default())
+            .filter(|(id, _)| id == b"CHAP")
+            .filter_map(|(_, data)| Self::parse_chap(version, data))
+            .collect();
+        chapters.sort_by_key(|chapter| chapter.start_time_ms);
         chapters
     }
 
+    /// Layout: element ID (null-terminated), start ms, end ms, start offset, end offset (u32 BE),
+    /// then sub-frames (TIT2 title, TIT3 description, WXXX link)
+    fn parse_chap(version: u8, data: &[u8]) -> Option<Chapter> {
+        let id_end = data.iter().position(|&b| b == 0)?;
+        let times = data.get(id_end + 1..id_end + 17)?;
+        let start_time_ms = u32::from_be_bytes([times[0], times[1], times[2], times[3]]) as u64;
+        let end_time_ms = u32::from_be_bytes([times[4], times[5], times[6], times[7]]) as u64;
+
+        let mut chapter = Chapter {
+            start_time_ms,
+            end_time_ms,
+            title: None,
+            description: None,
+            url: None,
+        };
+        for (id, sub) in Self::id3_frames(version, &data[id_end + 17..]) {
+            let Some((&encoding, text)) = sub.split_first() else { continue };
+            match &id {
+                b"TIT2" => chapter.title = Self::read_id3_text(text, encoding).map(|(t, _)| t),
+                b"TIT3" => chapter.description = Self::read_id3_text(text, encoding).map(|(t, _)| t),
+                b"WXXX" => {
+                    // Description in the frame's encoding, then the link in ISO-8859-1
+                    let (_, consumed) = Self::read_id3_text(text, encoding)?;
+                    let link = text.get(consumed..).unwrap_or_default();
+                    chapter.url = Self::read_id3_text(link, 0).map(|(t, _)| t);
+                }
+                _ => {}
+            }
+        }
+        Some(chapter)
+    }
+
+    /// Frames (ID, body) of an ID3v2 tag body or of a frame's sub-frames, stopping at padding
+    fn id3_frames(version: u8, mut data: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8])> {
+        std::iter::from_fn(move || {
+            let header = data.get(..10)?;
+            if header[0] == 0 {
+                return None;
+            }
+            let id = [header[0], header[1], header[2], header[3]];
+            let size = match version {
+                4 => Self::syncsafe(&header[4..8]),
+                _ => u32::from_be_bytes([header[4], header[5], header[6], header[7]]),
+            } as usize;
+            let body = data.get(10..10 + size)?;
+            data = &data[10 + size..];
+            Some((id, body))
+        })
+    }
+
+    /// 28-bit ID3v2 syncsafe integer (7 bits per byte)
+    fn syncsafe(bytes: &[u8]) -> u32 {
+        bytes.iter().fold(0, |value, &b| (value << 7) | (b & 0x7F) as u32)
+    }
+
     /// Get reference to cover art
     pub fn get_cover_art(&self) -> Option<&CoverArt> {
         self.cover_art.as_ref()
@@ -658,14 +1692,75 @@ impl AudioDecoder {
     pub fn take_cover_art(&mut self) -> Option<CoverArt> {
         self.cover_art.take()
     }
+
+    /// Write the embedded cover art to `path` as-is.
+    /// The extension is replaced by the one matching the image type (jpg/png/webp),
+    /// see `CoverArt::file_extension`.
+    pub fn write_cover_art<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let cover_art = self
+            .cover_art
+            .as_ref()
+            .ok_or_else(|| AudioError::InvalidState("No cover art embedded in this file".to_string()))?;
+        let extension = cover_art.file_extension().ok_or_else(|| {
+            AudioError::UnsupportedFormat(format!("Unknown cover art type: {}", cover_art.mime_type))
+        })?;
+
+        std::fs::write(path.as_ref().with_extension(extension), &cover_art.data)
+            .map_err(|e| AudioError::IoError(format!("Failed to write cover art: {}", e)))
+    }
+}
+
+/// Write interleaved f32 samples to a 32-bit float WAV file
+/// Works for any channel count and sample rate described by `format`
+pub fn write_wav<P: AsRef<Path>>(path: P, format: &AudioFormat, samples: &[f32]) -> Result<()> {
+    use std::io::{BufWriter, Write};
+
+    let channels = format.channels.max(1);
+    let bytes_per_sample = 4u16;
+    let block_align = channels * bytes_per_sample;
+    let data_len = (samples.len() * bytes_per_sample as usize) as u32;
+
+    let file = File::create(path.as_ref())
+        .map_err(|e| AudioError::IoError(format!("Failed to create WAV file: {}", e)))?;
+    let mut writer = BufWriter::new(file);
+
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&(36 + data_len).to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&3u16.to_le_bytes()); // WAVE_FORMAT_IEEE_FLOAT
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&format.sample_rate.to_le_bytes());
+    header.extend_from_slice(&(format.sample_rate * block_align as u32).to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&(bytes_per_sample * 8).to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_len.to_le_bytes());
+
+    let write_err = |e: std::io::Error| AudioError::IoError(format!("Failed to write WAV file: {}", e));
+    writer.write_all(&header).map_err(write_err)?;
+    for sample in samples {
+        writer.write_all(&sample.to_le_bytes()).map_err(write_err)?;
+    }
+    writer.flush().map_err(write_err)?;
+
+    Ok(())
 }
 
+/// Output callbacks' worth of audio the ring buffer must hold: one being read while the
+/// decoder writes the next. A smaller ring can never fill a callback and plays silence.
+pub const MIN_RING_CALLBACKS: usize = 2;
+
 // Sample ring buffer for smooth audio playback
 pub struct AudioRingBuffer {
     buffer: Vec<f32>,
     write_pos: usize,
     read_pos: usize,
     size: usize,
+    /// Charge for `buffer` in the global memory pool
+    memory: MemoryCharge,
 }
 
 impl AudioRingBuffer {
@@ -675,6 +1770,7 @@ impl AudioRingBuffer {
             write_pos: 0,
             read_pos: 0,
             size,
+            memory: MemoryCharge::global(size * std::mem::size_of::<f32>()),
         }
     }
 
@@ -734,6 +1830,14 @@ impl AudioRingBuffer {
         to_read
     }
 
+    /// Read only whole frames of `channels` samples; a partial frame stays for the next read,
+    /// so the channels of later reads stay in place
+    pub fn read_frames(&mut self, output: &mut [f32], channels: usize) -> usize {
+        let channels = channels.max(1);
+        let whole = output.len().min(self.available_read()) / channels * channels;
+        self.read(&mut output[..whole])
+    }
+
     pub fn available_write(&self) -> usize {
         if self.write_pos >= self.read_pos {
             self.size - (self.write_pos - self.read_pos) - 1
@@ -761,6 +1865,7 @@ impl AudioRingBuffer {
         if new_size != self.size {
             self.buffer = vec![0.0; new_size];
             self.size = new_size;
+            self.memory.set(new_size * std::mem::size_of::<f32>());
             self.write_pos = 0;
             self.read_pos = 0;
             log::debug!("Ring buffer resized to {} samples ({:.2} MB)",
@@ -773,9 +1878,938 @@ impl AudioRingBuffer {
         self.size
     }
 
-    /// Get buffer fullness as a percentage (0.0 to 1.0)
+    /// Samples the buffer can hold: one slot stays free to tell full from empty
+    pub fn capacity(&self) -> usize {
+        self.size.saturating_sub(1)
+    }
+
+    /// Smallest size holding `MIN_RING_CALLBACKS` callbacks of `callback_samples`
+    pub fn min_size_for_callback(callback_samples: usize) -> usize {
+        callback_samples * MIN_RING_CALLBACKS + 1
+    }
+
+    /// Grow the buffer if it cannot hold `MIN_RING_CALLBACKS` callbacks of `callback_samples`.
+    /// Growing clears it, like `resize`. Returns whether it grew.
+    pub fn fit_callback(&mut self, callback_samples: usize) -> bool {
+        let min_size = Self::min_size_for_callback(callback_samples);
+        if self.size >= min_size {
+            return false;
+        }
+        log::warn!(
+            "Output callback of {} samples does not fit the {}-sample ring buffer; growing it to {}",
+            callback_samples,
+            self.size,
+            min_size
+        );
+        self.resize(min_size);
+        true
+    }
+
+    /// Get buffer fullness as a percentage of usable capacity (0.0 to 1.0)
     pub fn fullness(&self) -> f32 {
         let used = self.available_read();
-        used as f32 / self.size as f32
+        used as f32 / self.capacity().max(1) as f32
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{Read, Seek, SeekFrom};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    /// Build a 16-bit PCM WAV file in memory (sawtooth test signal)
+    pub(crate) fn test_wav(sample_rate: u32, channels: u16, seconds: u32) -> Vec<u8> {
+        let frames = sample_rate * seconds;
+        let data_len = frames * channels as u32 * 2;
+        let mut wav = Vec::with_capacity(44 + data_len as usize);
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&channels.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * channels as u32 * 2).to_le_bytes());
+        wav.extend_from_slice(&(channels * 2).to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for i in 0..frames {
+            let sample = ((i % 100) as i16 - 50) * 100;
+            for _ in 0..channels {
+                wav.extend_from_slice(&sample.to_le_bytes());
+            }
+        }
+        wav
+    }
+
+    #[test]
+    fn test_unusual_channel_counts_do_not_panic() {
+        // Ten channels: the front pair is played
+        let mut decoder = AudioDecoder::from_buffer(test_wav(8000, 10, 1)).unwrap();
+        assert_eq!(decoder.format.channels, 2);
+        assert_eq!(decoder.metadata.format_info.channels, 10);
+        let (format, samples) = decoder.decode_all().unwrap();
+        assert_eq!(format.channels, 2);
+        assert_eq!(samples.len(), 8000 * 2);
+        assert_eq!(samples[0], (-50i16 * 100) as f32 / 32768.0);
+
+        // No channels at all is rejected, whether caught by the reader or by the decoder
+        assert!(AudioDecoder::from_buffer(test_wav(8000, 0, 1)).is_err());
+        assert!(matches!(decoded_channels(0), Err(AudioError::UnsupportedFormat(_))));
+        assert_eq!(decoded_channels(MAX_DECODED_CHANNELS).unwrap(), MAX_DECODED_CHANNELS);
+        assert_eq!(decoded_channels(1).unwrap(), 1);
+    }
+
+    /// Serves a WAV whose sizes are still placeholders until the header is "downloaded"
+    struct DelayedHeader {
+        complete: Vec<u8>,
+        ready: Arc<AtomicBool>,
+    }
+
+    impl LateHeaderSource for DelayedHeader {
+        fn header_ready(&self) -> bool {
+            self.ready.load(Ordering::SeqCst)
+        }
+
+        fn reopen(&self) -> Result<Box<dyn MediaSource>> {
+            Ok(Box::new(Cursor::new(self.complete.clone())))
+        }
+    }
+
+    /// Counts the bytes the decoder has pulled from it
+    struct CountingSource {
+        inner: Cursor<Vec<u8>>,
+        read: Arc<AtomicUsize>,
+    }
+
+    impl Read for CountingSource {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.read.fetch_add(n, Ordering::SeqCst);
+            Ok(n)
+        }
+    }
+
+    impl Seek for CountingSource {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    impl MediaSource for CountingSource {
+        fn is_seekable(&self) -> bool {
+            true
+        }
+
+        fn byte_len(&self) -> Option<u64> {
+            Some(self.inner.get_ref().len() as u64)
+        }
+    }
+
+    #[test]
+    fn test_pcm_stream_delivers_whole_file() {
+        let decoder = AudioDecoder::from_buffer(test_wav(44100, 1, 2)).unwrap();
+        let stream = decoder.into_pcm_stream().unwrap();
+
+        let Ok(PcmMessage::Format(format)) = stream.recv().unwrap() else {
+            panic!("first message is not the format");
+        };
+        assert_eq!((format.sample_rate, format.channels), (44100, 2));
+
+        let mut samples = 0;
+        for message in stream {
+            match message.unwrap() {
+                PcmMessage::Samples(packet) => samples += packet.len(),
+                PcmMessage::Format(_) => panic!("format sent twice"),
+            }
+        }
+        // Mono upmixed to stereo
+        assert_eq!(samples, 44100 * 2 * 2);
+    }
+
+    #[test]
+    fn test_pcm_stream_applies_backpressure_and_stops_when_dropped() {
+        let wav = test_wav(44100, 2, 60);
+        let total = wav.len();
+        let read = Arc::new(AtomicUsize::new(0));
+        let source = CountingSource { inner: Cursor::new(wav), read: read.clone() };
+        let mut hint = Hint::new();
+        hint.with_extension("wav");
+        let decoder = AudioDecoder::from_streaming_source(Box::new(source), hint).unwrap();
+        let stream = decoder.into_pcm_stream().unwrap();
+        stream.recv().unwrap().unwrap();
+
+        // Nobody is receiving: the worker stops after filling the queue
+        thread::sleep(Duration::from_millis(200));
+        let blocked_at = read.load(Ordering::SeqCst);
+        assert!(blocked_at < total / 4, "read {} of {} bytes", blocked_at, total);
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(read.load(Ordering::SeqCst), blocked_at);
+
+        drop(stream);
+        thread::sleep(Duration::from_millis(200));
+        let stopped_at = read.load(Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(read.load(Ordering::SeqCst), stopped_at);
+        assert!(stopped_at < total / 4);
+    }
+
+    #[test]
+    fn test_full_ring_buffer_reads_as_full() {
+        let mut ring = AudioRingBuffer::new(8);
+        assert_eq!(ring.write(&[0.5; 16]), ring.capacity());
+        assert!(ring.fullness() >= 0.99, "{}", ring.fullness());
+    }
+
+    #[test]
+    fn test_partial_frames_stay_in_the_ring() {
+        let mut ring = AudioRingBuffer::new(16);
+        // L/R pairs, then a stray left sample whose right arrives later
+        ring.write(&[1.0, -1.0, 2.0, -2.0, 3.0]);
+        let mut out = [0.0; 8];
+        assert_eq!(ring.read_frames(&mut out, 2), 4);
+        assert_eq!(out[..4], [1.0, -1.0, 2.0, -2.0]);
+
+        ring.write(&[-3.0, 4.0, -4.0]);
+        assert_eq!(ring.read_frames(&mut out, 2), 4);
+        assert_eq!(out[..4], [3.0, -3.0, 4.0, -4.0]);
+        assert_eq!(ring.available_read(), 0);
+    }
+
+    #[test]
+    fn test_late_header_updates_duration() {
+        let complete = test_wav(8000, 1, 2);
+        let mut partial = complete.clone();
+        partial[4..8].copy_from_slice(&36u32.to_le_bytes());
+        partial[40..44].copy_from_slice(&0u32.to_le_bytes());
+
+        let mut hint = Hint::new();
+        hint.with_extension("wav");
+        let mut decoder =
+            AudioDecoder::from_streaming_source(Box::new(Cursor::new(partial)), hint.clone()).unwrap();
+        assert_eq!(decoder.format.duration_ms, 0);
+
+        let ready = Arc::new(AtomicBool::new(false));
+        decoder.set_late_header_source(
+            Box::new(DelayedHeader { complete, ready: ready.clone() }),
+            hint,
+        );
+        assert_eq!(decoder.refresh_if_header_ready(), None);
+        assert_eq!(decoder.format.duration_ms, 0);
+
+        ready.store(true, Ordering::SeqCst);
+        assert_eq!(decoder.refresh_if_header_ready(), Some(2000));
+        assert_eq!(decoder.format.duration_ms, 2000);
+        assert_eq!(decoder.metadata.format_info.duration_ms, 2000);
+
+        // One-shot: nothing more to refresh
+        assert_eq!(decoder.refresh_if_header_ready(), None);
+    }
+
+    #[test]
+    fn test_duration_known_once_header_arrives() {
+        let complete = test_wav(8000, 1, 2);
+        let mut partial = complete.clone();
+        partial[4..8].copy_from_slice(&36u32.to_le_bytes());
+        partial[40..44].copy_from_slice(&0u32.to_le_bytes());
+
+        let mut hint = Hint::new();
+        hint.with_extension("wav");
+        let mut decoder =
+            AudioDecoder::from_streaming_source(Box::new(Cursor::new(partial)), hint.clone()).unwrap();
+        assert!(!decoder.duration_known());
+
+        let ready = Arc::new(AtomicBool::new(false));
+        decoder.set_late_header_source(
+            Box::new(DelayedHeader { complete: complete.clone(), ready: ready.clone() }),
+            hint,
+        );
+        decoder.refresh_if_header_ready();
+        assert!(!decoder.duration_known());
+        ready.store(true, Ordering::SeqCst);
+        decoder.refresh_if_header_ready();
+        assert!(decoder.duration_known());
+
+        // A complete file states its length up front
+        assert!(AudioDecoder::from_buffer(complete).unwrap().duration_known());
+    }
+
+    #[test]
+    fn test_duration_refined_at_end_of_stream() {
+        use crate::callback::TestCallback;
+
+        // Header written before the stream was cut short: it claims 3s, only 2s arrive
+        let mut wav = test_wav(8000, 1, 2);
+        wav[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        wav[40..44].copy_from_slice(&(8000u32 * 2 * 3).to_le_bytes());
+        let mut decoder = AudioDecoder::from_buffer(wav).unwrap();
+        let estimated = decoder.format.duration_ms;
+        assert_ne!(estimated, 2000);
+
+        let callbacks = CallbackManager::new();
+        let callback = Arc::new(TestCallback::new());
+        callbacks.add_callback(callback.clone(), 0);
+
+        while decoder.decode_next().unwrap().is_some() {
+            decoder.dispatch_changes(&callbacks);
+        }
+        decoder.dispatch_changes(&callbacks);
+        // Already reported; reaching the end again does not repeat it
+        assert_eq!(decoder.decode_next().unwrap(), None);
+        decoder.dispatch_changes(&callbacks);
+        assert!(callbacks.flush(std::time::Duration::from_secs(1)));
+
+        let changes: Vec<u64> = callback
+            .get_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CallbackEvent::DurationChanged { duration_ms } => Some(duration_ms),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(changes, [2000]);
+        assert_eq!(decoder.format.duration_ms, 2000);
+        assert_eq!(decoder.metadata.format_info.duration_ms, 2000);
+    }
+
+    #[test]
+    fn test_decode_all_matches_duration() {
+        for (sample_rate, channels) in [(44100u32, 2u16), (22050, 6)] {
+            let mut decoder = AudioDecoder::from_buffer(test_wav(sample_rate, channels, 1)).unwrap();
+            let duration_ms = decoder.format.duration_ms;
+
+            let mut last_progress = 0;
+            let (format, samples) = decoder
+                .decode_all_with_progress(|decoded_ms, _| last_progress = decoded_ms)
+                .unwrap();
+
+            assert_eq!(format.sample_rate, sample_rate);
+            assert_eq!(format.channels, channels);
+            assert_eq!(
+                samples.len() as u64,
+                duration_ms * sample_rate as u64 / 1000 * channels as u64
+            );
+            assert_eq!(last_progress, duration_ms);
+        }
+    }
+
+    #[test]
+    fn test_write_wav_round_trip() {
+        let mut decoder = AudioDecoder::from_buffer(test_wav(48000, 2, 1)).unwrap();
+        let (format, samples) = decoder.decode_all().unwrap();
+
+        let path = std::env::temp_dir().join(format!("rust_audio_player_wav_{}.wav", std::process::id()));
+        write_wav(&path, &format, &samples).unwrap();
+
+        let mut reread = AudioDecoder::from_file(path.to_str().unwrap()).unwrap();
+        let (reread_format, reread_samples) = reread.decode_all().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(reread_format.sample_rate, 48000);
+        assert_eq!(reread_format.channels, 2);
+        assert_eq!(reread_samples, samples);
+    }
+
+    /// Prefix `audio` with an ID3v2.3 tag holding a single front-cover APIC frame
+    fn with_cover_art(audio: Vec<u8>, mime_type: &str, image: &[u8]) -> Vec<u8> {
+        with_id3_frames(audio, &[(b"APIC", apic(mime_type, image))])
+    }
+
+    /// APIC frame body holding a front cover
+    fn apic(mime_type: &str, image: &[u8]) -> Vec<u8> {
+        let mut apic = vec![0u8]; // ISO-8859-1 description
+        apic.extend_from_slice(mime_type.as_bytes());
+        apic.push(0);
+        apic.push(3); // front cover
+        apic.extend_from_slice(b"Cover\0");
+        apic.extend_from_slice(image);
+        apic
+    }
+
+    /// Prefix `audio` with an ID3v2.3 tag holding the given (frame ID, body) frames
+    fn with_id3_frames(audio: Vec<u8>, frames: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (id, data) in frames {
+            body.extend_from_slice(*id);
+            body.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            body.extend_from_slice(&[0, 0]);
+            body.extend_from_slice(data);
+        }
+
+        // Tag size is syncsafe: 7 bits per byte
+        let size = body.len() as u32;
+        let mut tag = b"ID3\x03\x00\x00".to_vec();
+        tag.extend((0..4).rev().map(|i| ((size >> (7 * i)) & 0x7F) as u8));
+        tag.extend_from_slice(&body);
+        tag.extend_from_slice(&audio);
+        tag
+    }
+
+    /// One-second-per-chapter WAV (`seconds` long) with an ID3v2.3 CHAP frame per
+    /// (start ms, end ms, title), each carrying a TIT2 sub-frame
+    pub(crate) fn chaptered_wav(seconds: u32, chapters: &[(u32, u32, &str)]) -> Vec<u8> {
+        let frames: Vec<(&[u8; 4], Vec<u8>)> = chapters
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, end, title))| {
+                let mut chap = format!("ch{}\0", i).into_bytes();
+                for value in [start, end, u32::MAX, u32::MAX] {
+                    chap.extend_from_slice(&value.to_be_bytes());
+                }
+                let tit2 = id3_text(None, title);
+                chap.extend_from_slice(b"TIT2");
+                chap.extend_from_slice(&(tit2.len() as u32).to_be_bytes());
+                chap.extend_from_slice(&[0, 0]);
+                chap.extend_from_slice(&tit2);
+                (b"CHAP", chap)
+            })
+            .collect();
+        with_id3_frames(test_wav(8000, 1, seconds), &frames)
+    }
+
+    #[test]
+    fn test_id3_chapters_are_parsed_in_order() {
+        let tagged = chaptered_wav(3, &[(2000, 3000, "Outro"), (0, 1000, "Intro"), (1000, 2000, "Main")]);
+        let decoder = AudioDecoder::from_buffer(tagged).unwrap();
+
+        let chapters: Vec<_> = decoder
+            .metadata
+            .chapters
+            .iter()
+            .map(|c| (c.start_time_ms, c.end_time_ms, c.title.as_deref().unwrap_or_default()))
+            .collect();
+        assert_eq!(chapters, [(0, 1000, "Intro"), (1000, 2000, "Main"), (2000, 3000, "Outro")]);
+        // Reading the tag must not disturb probing
+        assert_eq!(decoder.format.duration_ms, 3000);
+    }
+
+    /// ISO-8859-1 text frame body (TXXX when `description` is given)
+    fn id3_text(description: Option<&str>, text: &str) -> Vec<u8> {
+        let mut data = vec![0u8];
+        if let Some(description) = description {
+            data.extend_from_slice(description.as_bytes());
+            data.push(0);
+        }
+        data.extend_from_slice(text.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_dj_tags_are_typed() {
+        let tagged = with_id3_frames(
+            test_wav(8000, 1, 1),
+            &[
+                (b"TIT2", id3_text(None, "Warm Up")),
+                (b"TBPM", id3_text(None, "124.5")),
+                (b"TKEY", id3_text(None, "Am")),
+                (b"TMOO", id3_text(None, "Uplifting")),
+                (b"TXXX", id3_text(Some("EnergyLevel"), "7")),
+                (b"TXXX", id3_text(Some("DJ Notes"), "peak time")),
+            ],
+        );
+        let decoder = AudioDecoder::from_buffer(tagged).unwrap();
+        let tags = &decoder.metadata.tags;
+
+        assert_eq!(tags.title.as_deref(), Some("Warm Up"));
+        assert_eq!(tags.bpm, Some(124.5));
+        assert_eq!(tags.initial_key.as_deref(), Some("Am"));
+        assert_eq!(tags.mood.as_deref(), Some("Uplifting"));
+        assert_eq!(tags.energy, Some(7));
+        // Unknown tags are kept as they were
+        assert_eq!(tags.custom_tags.get("TXXX:DJ Notes").map(String::as_str), Some("peak time"));
+        assert!(!tags.custom_tags.keys().any(|key| key.contains("TKEY") || key.contains("Energy")));
+    }
+
+    #[test]
+    fn test_replay_gain_tags_limit_the_applied_gain() {
+        use crate::replay_gain::{ReplayGainMode, CLIP_CEILING};
+
+        // A quiet track whose loudest sample sits at half scale, tagged +12 dB
+        let mut wav = test_wav(8000, 1, 1);
+        let peak_sample = (i16::MAX / 2).to_le_bytes();
+        wav[44..46].copy_from_slice(&peak_sample);
+        let tagged = with_id3_frames(
+            wav,
+            &[
+                (b"TXXX", id3_text(Some("REPLAYGAIN_TRACK_GAIN"), "+12.00 dB")),
+                (b"TXXX", id3_text(Some("REPLAYGAIN_TRACK_PEAK"), "0.500000")),
+            ],
+        );
+        let mut decoder = AudioDecoder::from_buffer(tagged).unwrap();
+        let replay_gain = decoder.metadata.tags.replay_gain;
+        assert_eq!(replay_gain.track_gain_db, Some(12.0));
+        assert_eq!(replay_gain.track_peak, Some(0.5));
+        assert!(decoder.metadata.tags.custom_tags.is_empty());
+
+        // +12 dB is about 4x; the peak allows only 2x
+        decoder.set_replay_gain(ReplayGainSettings { mode: ReplayGainMode::Track, preamp_db: 0.0 });
+        let samples = decoder.decode_next().unwrap().unwrap();
+        let loudest = samples.iter().fold(0f32, |max, s| max.max(s.abs()));
+        assert!(loudest <= CLIP_CEILING, "{}", loudest);
+        assert!(loudest > 0.99, "{}", loudest);
+    }
+
+    #[test]
+    fn test_decode_throughput_is_measured() {
+        let mut decoder = AudioDecoder::from_buffer(test_wav(44100, 2, 2)).unwrap();
+        assert_eq!(decoder.stats().decode_throughput_frames_per_sec(), 0.0);
+
+        // Warm up: a few packets, all frames of which are counted
+        let mut frames = 0;
+        for _ in 0..8 {
+            frames += decoder.decode_next().unwrap().unwrap().len() as u64 / 2;
+        }
+        let stats = decoder.stats();
+        assert_eq!(stats.decoded_frames, frames);
+        assert!(stats.decode_time > Duration::ZERO);
+        assert!(stats.decode_throughput_frames_per_sec() > 0.0);
+    }
+
+    #[test]
+    fn test_vorbis_style_dj_tags() {
+        let mut tags = AudioTags::new();
+        AudioDecoder::store_custom_tag(&mut tags, "INITIALKEY", "8A".to_string());
+        AudioDecoder::store_custom_tag(&mut tags, "bpm", " 128 ".to_string());
+        AudioDecoder::store_custom_tag(&mut tags, "ENERGY", "high".to_string());
+        assert_eq!(tags.initial_key.as_deref(), Some("8A"));
+        assert_eq!(tags.bpm, Some(128.0));
+        // Unparsable values stay available as text
+        assert_eq!(tags.energy, None);
+        assert_eq!(tags.custom_tags.get("ENERGY").map(String::as_str), Some("high"));
+    }
+
+    #[test]
+    fn test_write_cover_art_matches_embedded_data() {
+        let mut image = b"\x89PNG\r\n\x1a\n".to_vec();
+        image.extend((0..=255u8).cycle().take(2000));
+        let tagged = with_cover_art(test_wav(8000, 1, 1), "image/png", &image);
+        let decoder = AudioDecoder::from_buffer(tagged).unwrap();
+        assert_eq!(decoder.get_cover_art().unwrap().data, image);
+
+        // The requested extension is replaced by the image's own
+        let dir = std::env::temp_dir();
+        let requested = dir.join(format!("rust_audio_player_cover_{}.jpg", std::process::id()));
+        decoder.write_cover_art(&requested).unwrap();
+        let written = requested.with_extension("png");
+        let bytes = std::fs::read(&written).unwrap();
+        let _ = std::fs::remove_file(&written);
+        assert_eq!(bytes, image);
+
+        // No artwork: a clean error and no file
+        let bare = AudioDecoder::from_buffer(test_wav(8000, 1, 1)).unwrap();
+        let missing = dir.join(format!("rust_audio_player_no_cover_{}", std::process::id()));
+        assert!(matches!(bare.write_cover_art(&missing), Err(AudioError::InvalidState(_))));
+        assert!(!missing.with_extension("png").exists());
+    }
+
+    #[test]
+    fn test_read_metadata_only_matches_full_load_without_reading_audio() {
+        let image = b"\xFF\xD8\xFF\xE0cover".to_vec();
+        let tagged = with_id3_frames(
+            test_wav(44100, 2, 10),
+            &[
+                (b"TIT2", id3_text(None, "Episode 12")),
+                (b"TPE1", id3_text(None, "Host")),
+                (b"APIC", apic("image/jpeg", &image)),
+            ],
+        );
+        let path = std::env::temp_dir().join(format!("rust_audio_player_scan_{}.wav", std::process::id()));
+        std::fs::write(&path, &tagged).unwrap();
+        let scanned = read_metadata_only(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        let scanned = scanned.unwrap();
+
+        let loaded = AudioDecoder::from_buffer(tagged.clone()).unwrap();
+        assert_eq!(scanned.tags.title.as_deref(), Some("Episode 12"));
+        assert_eq!(scanned.tags.title, loaded.metadata.tags.title);
+        assert_eq!(scanned.tags.artist, loaded.metadata.tags.artist);
+        assert_eq!(scanned.format_info.duration_ms, 10_000);
+        assert_eq!(scanned.format_info.channels, loaded.metadata.format_info.channels);
+        assert_eq!(scanned.cover_art.map(|art| art.data), Some(image));
+
+        // Only the headers are read, not the 1.7 MB of samples
+        let read = Arc::new(AtomicUsize::new(0));
+        let source = CountingSource { inner: Cursor::new(tagged.clone()), read: read.clone() };
+        AudioDecoder::read_metadata_from_source(Box::new(source), Hint::new()).unwrap();
+        assert!(read.load(Ordering::SeqCst) < tagged.len() / 4);
+    }
+
+    #[test]
+    fn test_seek_to_sample_is_exact() {
+        // Every frame unique, odd rate so no frame index is a whole millisecond multiple by chance
+        let format = AudioFormat {
+            sample_rate: 44_101,
+            channels: 2,
+            bits_per_sample: 32,
+            duration_ms: 1000,
+            encoder_delay: 0,
+            encoder_padding: 0,
+        };
+        let reference: Vec<f32> = (0..44_101u32)
+            .flat_map(|i| {
+                let v = i as f32 / 44_101.0;
+                [v, -v]
+            })
+            .collect();
+        let path = std::env::temp_dir().join(format!("rust_audio_player_seek_{}.wav", std::process::id()));
+        write_wav(&path, &format, &reference).unwrap();
+        let mut decoder = AudioDecoder::from_file(path.to_str().unwrap()).unwrap();
+
+        // Forward, backward, mid-packet and the last frame
+        for frame in [12_345u64, 1, 1_153, 30_001, 0, 44_100] {
+            decoder.seek_to_sample(frame).unwrap();
+            let first = decoder.decode_next().unwrap().unwrap();
+            let expected = &reference[frame as usize * 2..frame as usize * 2 + 2];
+            assert_eq!(&first[..2], expected, "frame {}", frame);
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// MPEG-1 layer III, 128 kbps, 44.1 kHz, mono: 417-byte frames of 1152 samples
+    const MP3_FRAME_HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0xC0];
+    const MP3_FRAME_LEN: usize = 417;
+
+    /// Silent MP3 with an Info/LAME frame declaring `frames` audio frames and the given
+    /// raw LAME delay/padding fields (the reader adds the 529-sample decoder delay)
+    fn gapless_mp3(frames: u32, lame_delay: u32, lame_padding: u32) -> Vec<u8> {
+        let mut info = vec![0u8; MP3_FRAME_LEN];
+        info[..4].copy_from_slice(&MP3_FRAME_HEADER);
+        // The tag follows the (zeroed) 17-byte mono side info
+        let mut tag = Vec::new();
+        tag.extend_from_slice(b"Info");
+        tag.extend_from_slice(&1u32.to_be_bytes()); // flags: frame count present
+        tag.extend_from_slice(&frames.to_be_bytes());
+        tag.extend_from_slice(b"Lavf58.76"); // encoder; no tag CRC for non-LAME encoders
+        tag.extend_from_slice(&[0; 2 + 4 + 2 + 2 + 1 + 1]); // revision .. ABR
+        let trim = (lame_delay << 12) | lame_padding;
+        tag.extend_from_slice(&trim.to_be_bytes()[1..]);
+        info[21..21 + tag.len()].copy_from_slice(&tag);
+
+        let mut mp3 = info;
+        for _ in 0..frames {
+            let mut frame = vec![0u8; MP3_FRAME_LEN];
+            frame[..4].copy_from_slice(&MP3_FRAME_HEADER);
+            mp3.extend_from_slice(&frame);
+        }
+        mp3
+    }
+
+    #[test]
+    fn test_gapless_delay_and_padding_are_trimmed() {
+        // Encoder delay 576 + 529 decoder delay, padding 1000 - 529
+        let mp3 = gapless_mp3(10, 576, 1000);
+        let mut hint = Hint::new();
+        hint.with_extension("mp3");
+
+        let mut decoder =
+            AudioDecoder::from_streaming_source(Box::new(Cursor::new(mp3.clone())), hint.clone()).unwrap();
+        assert_eq!(decoder.format.encoder_delay, 1105);
+        assert_eq!(decoder.format.encoder_padding, 471);
+        let valid_frames = 10 * 1152 - 1105 - 471;
+        assert_eq!(decoder.format.duration_ms, valid_frames * 1000 / 44100);
+        let (format, trimmed) = decoder.decode_all().unwrap();
+
+        // Apps doing their own trimming get the raw stream
+        let mut raw =
+            AudioDecoder::from_streaming_source(Box::new(Cursor::new(mp3.clone())), hint.clone()).unwrap();
+        raw.set_gapless_trim(false);
+        let (_, untrimmed) = raw.decode_all().unwrap();
+
+        let channels = format.channels as u64;
+        assert_eq!(untrimmed.len() as u64, 10 * 1152 * channels);
+        assert_eq!(trimmed.len() as u64, valid_frames * channels);
+
+        // Sample-exact seeks land mid-packet, counted from the end of the encoder delay
+        let mut seeking = AudioDecoder::from_streaming_source(Box::new(Cursor::new(mp3)), hint).unwrap();
+        seeking.seek_to_sample(5_000).unwrap();
+        let (_, rest) = seeking.decode_all().unwrap();
+        assert_eq!(rest.len() as u64, (valid_frames - 5_000) * channels);
+    }
+
+    #[test]
+    fn test_coarse_seek_reads_less_and_lands_near_target() {
+        let mp3 = gapless_mp3(1000, 0, 0);
+        let open = || {
+            let read = Arc::new(AtomicUsize::new(0));
+            let source = CountingSource { inner: Cursor::new(mp3.clone()), read: read.clone() };
+            let mut hint = Hint::new();
+            hint.with_extension("mp3");
+            let decoder = AudioDecoder::from_streaming_source(Box::new(source), hint).unwrap();
+            read.store(0, Ordering::SeqCst);
+            (decoder, read)
+        };
+        let target_ms = 23_456;
+        let packet_ms = 1152 * 1000 / 44100 + 1;
+
+        let (mut accurate, accurate_read) = open();
+        assert_eq!(accurate.seek_with_mode(target_ms, SeekMode::Accurate).unwrap(), target_ms);
+        let accurate_first = accurate.decode_next().unwrap().unwrap();
+
+        let (mut coarse, coarse_read) = open();
+        let landed_ms = coarse.seek_with_mode(target_ms, SeekMode::Coarse).unwrap();
+        let coarse_first = coarse.decode_next().unwrap().unwrap();
+
+        // Accurate parses every frame up to the target; coarse jumps near it by byte offset
+        let accurate_read = accurate_read.load(Ordering::SeqCst);
+        let coarse_read = coarse_read.load(Ordering::SeqCst);
+        assert!(coarse_read * 4 < accurate_read, "coarse {} vs accurate {} bytes", coarse_read, accurate_read);
+        // Within a packet of the target, starting on a whole packet with nothing trimmed
+        assert!(landed_ms.abs_diff(target_ms) <= packet_ms, "landed at {} ms", landed_ms);
+        assert_eq!(coarse_first.len(), 1152 * 2);
+        assert!(accurate_first.len() < 1152 * 2);
+    }
+
+    /// `frames` silent MP3 frames of each (header, frame length) in turn
+    pub(crate) fn concatenated_mp3(segments: &[([u8; 4], usize, usize)]) -> Vec<u8> {
+        let mut mp3 = Vec::new();
+        for &(header, frame_len, frames) in segments {
+            for _ in 0..frames {
+                let mut frame = vec![0u8; frame_len];
+                frame[..4].copy_from_slice(&header);
+                mp3.extend_from_slice(&frame);
+            }
+        }
+        mp3
+    }
+
+    #[test]
+    fn test_format_change_mid_stream_is_converted() {
+        // MPEG-2 mono 22050 Hz (576 frames per packet), then MPEG-1 stereo 44100 Hz (1152)
+        let mono_22050 = ([0xFF, 0xF3, 0x80, 0xC0], 208, 20);
+        let stereo_44100 = ([0xFF, 0xFB, 0x90, 0x00], 417, 20);
+        let mut hint = Hint::new();
+        hint.with_extension("mp3");
+        let mp3 = concatenated_mp3(&[mono_22050, stereo_44100]);
+        let mut decoder = AudioDecoder::from_streaming_source(Box::new(Cursor::new(mp3)), hint).unwrap();
+        assert_eq!((decoder.format.sample_rate, decoder.format.channels), (22050, 1));
+
+        // Both segments play through at the established rate: 20 * 576 + 20 * 1152 / 2 frames
+        decoder.set_gapless_trim(false);
+        let (format, samples) = decoder.decode_all().unwrap();
+        assert_eq!((format.sample_rate, format.channels), (22050, 2));
+        assert_eq!(samples.len(), (20 * 576 + 20 * 576) * 2);
+    }
+
+    #[test]
+    fn test_conformed_packets_continue_the_signal() {
+        // A 440 Hz tone that switches from mono 22050 Hz to stereo 44100 Hz half way
+        let tone = |rate: u32, channels: usize, from_s: f64, to_s: f64| -> Vec<f32> {
+            let frames = ((from_s * rate as f64).round() as usize)..((to_s * rate as f64).round() as usize);
+            frames
+                .flat_map(|n| {
+                    let s = (2.0 * std::f64::consts::PI * 440.0 * n as f64 / rate as f64).sin() as f32;
+                    std::iter::repeat_n(s, channels)
+                })
+                .collect()
+        };
+        let mut output = Vec::new();
+        for packet in tone(22050, 1, 0.0, 0.5).chunks(576) {
+            output.extend(AudioDecoder::conform_to_format(packet.to_vec(), (22050, 1), (22050, 1)));
+        }
+        for packet in tone(44100, 2, 0.5, 1.0).chunks(1152 * 2) {
+            output.extend(AudioDecoder::conform_to_format(packet.to_vec(), (44100, 2), (22050, 1)));
+        }
+
+        assert_eq!(output.len(), 22050);
+        let expected = tone(22050, 1, 0.0, 1.0);
+        for (n, (out, exp)) in output.iter().zip(&expected).enumerate() {
+            assert!((out - exp).abs() < 1e-3, "frame {}: {} vs {}", n, out, exp);
+        }
+    }
+
+    #[test]
+    fn test_parse_itunsmpb() {
+        let value = " 00000000 00000840 000001CA 00000000000E2A76 00000000 00000000 00000000 00000000";
+        assert_eq!(
+            GaplessInfo::from_itunsmpb(value),
+            Some(GaplessInfo { delay: 2112, padding: 458, valid_frames: Some(0xE2A76) })
+        );
+        // An ordinary comment is not mistaken for one
+        assert_eq!(GaplessInfo::from_itunsmpb("great album"), None);
+    }
+
+    #[test]
+    fn test_parse_sylt_frame() {
+        // UTF-8, "eng", millisecond timestamps, lyrics content, empty descriptor
+        let mut frame = vec![3u8, b'e', b'n', b'g', 2, 1, 0];
+        for (text, time_ms) in [("Hello", 1_000u32), ("World", 2_500u32)] {
+            frame.extend_from_slice(text.as_bytes());
+            frame.push(0);
+            frame.extend_from_slice(&time_ms.to_be_bytes());
+        }
+
+        let lines = AudioDecoder::parse_sylt(&frame).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], LyricLine { time_ms: 1_000, text: "Hello".to_string() });
+        assert_eq!(lines[1], LyricLine { time_ms: 2_500, text: "World".to_string() });
+    }
+
+    /// Codec wrapper that fails every packet once `corrupt` is set, until it is reset
+    struct CorruptibleCodec {
+        inner: Box<dyn Decoder>,
+        corrupt: Arc<AtomicBool>,
+    }
+
+    impl Decoder for CorruptibleCodec {
+        fn try_new(_: &CodecParameters, _: &DecoderOptions) -> symphonia::core::errors::Result<Self> {
+            unreachable!()
+        }
+
+        fn supported_codecs() -> &'static [symphonia::core::codecs::CodecDescriptor] {
+            &[]
+        }
+
+        fn reset(&mut self) {
+            self.corrupt.store(false, Ordering::SeqCst);
+            self.inner.reset();
+        }
+
+        fn codec_params(&self) -> &CodecParameters {
+            self.inner.codec_params()
+        }
+
+        fn decode(
+            &mut self,
+            packet: &symphonia::core::formats::Packet,
+        ) -> symphonia::core::errors::Result<AudioBufferRef<'_>> {
+            if self.corrupt.load(Ordering::SeqCst) {
+                return Err(SymphoniaError::ResetRequired);
+            }
+            self.inner.decode(packet)
+        }
+
+        fn finalize(&mut self) -> symphonia::core::codecs::FinalizeResult {
+            self.inner.finalize()
+        }
+
+        fn last_decoded(&self) -> AudioBufferRef<'_> {
+            self.inner.last_decoded()
+        }
+    }
+
+    #[test]
+    fn test_reset_codec_recovers_from_corrupt_state() {
+        let wav = test_wav(44100, 2, 1);
+        let mut reference = AudioDecoder::from_buffer(wav.clone()).unwrap();
+        let mut expected = Vec::new();
+        while let Some(packet) = reference.decode_next().unwrap() {
+            expected.push(packet);
+        }
+        assert!(expected.len() > 3);
+
+        let mut decoder = AudioDecoder::from_buffer(wav).unwrap();
+        let corrupt = Arc::new(AtomicBool::new(false));
+        let inner = AudioDecoder::make_codec_decoder(&decoder.decoder.codec_params().clone()).unwrap();
+        decoder.decoder = Box::new(CorruptibleCodec { inner, corrupt: corrupt.clone() });
+        let duration_ms = decoder.format.duration_ms;
+
+        assert_eq!(decoder.decode_next().unwrap().unwrap(), expected[0]);
+        corrupt.store(true, Ordering::SeqCst);
+        // Stays broken until reset: the codec state does not heal by itself
+        assert!(decoder.decode_next().is_err());
+        assert!(decoder.decode_next().is_err());
+
+        decoder.reset_codec();
+        let mut recovered = Vec::new();
+        while let Some(packet) = decoder.decode_next().unwrap() {
+            recovered.push(packet);
+        }
+        // The two failed packets are lost; everything after them decodes as in a clean run
+        assert_eq!(recovered[..], expected[3..]);
+        assert_eq!((decoder.format.sample_rate, decoder.format.channels), (44100, 2));
+        assert_eq!(decoder.format.duration_ms, duration_ms);
+        assert_eq!(decoder.metadata.format_info.sample_rate, 44100);
+    }
+
+    #[test]
+    fn test_status_is_consistent_while_decoders_change() {
+        use parking_lot::Mutex;
+
+        let tracks = [(44100, 2), (48000, 1)];
+        let slot = Arc::new(Mutex::new(None::<AudioDecoder>));
+        let stop = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let (slot, stop) = (slot.clone(), stop.clone());
+            thread::spawn(move || {
+                let mut next = 0;
+                while !stop.load(Ordering::SeqCst) {
+                    // Load the other track, then decode from it like the decode thread does
+                    let (rate, seconds) = tracks[next % 2];
+                    *slot.lock() = Some(AudioDecoder::from_buffer(test_wav(rate, 2, seconds)).unwrap());
+                    for _ in 0..4 {
+                        if let Some(decoder) = slot.lock().as_mut() {
+                            decoder.decode_next().unwrap();
+                        }
+                    }
+                    next += 1;
+                }
+            })
+        };
+
+        let mut seen = std::collections::HashSet::new();
+        let deadline = std::time::Instant::now() + Duration::from_millis(300);
+        while std::time::Instant::now() < deadline {
+            if let Some(status) = slot.lock().as_ref().map(AudioDecoder::status) {
+                let pair = (status.sample_rate, status.duration_ms);
+                assert!(pair == (44100, 2000) || pair == (48000, 1000), "{:?}", status);
+                seen.insert(pair);
+            }
+        }
+        stop.store(true, Ordering::SeqCst);
+        writer.join().unwrap();
+        assert_eq!(seen.len(), 2);
+    }
+
+    #[test]
+    fn test_sample_loop_is_seamless_across_many_iterations() {
+        // 100 ms of 400 Hz is exactly 40 periods, so a seamless loop is one continuous sine
+        let tone = crate::test_tone::TestTone::new(400.0, 0.8, 100, 8000, 2).unwrap();
+        let (_, source) = AudioDecoder::from_buffer(tone.to_wav()).unwrap().decode_all().unwrap();
+        assert_eq!(source.len(), 800 * 2);
+
+        let mut decoder = AudioDecoder::from_buffer(tone.to_wav()).unwrap();
+        decoder.decode_next().unwrap();
+        decoder.set_sample_loop(true, 0).unwrap();
+        assert!(decoder.status().looping);
+
+        // Chunks don't line up with the loop, so boundaries fall mid-chunk at varying offsets
+        let mut looped = Vec::new();
+        while looped.len() < source.len() * 50 {
+            looped.extend(decoder.decode_next().unwrap().unwrap());
+        }
+        // Every iteration is the source, frame for frame: nothing dropped, repeated or inserted
+        for (i, &sample) in looped.iter().enumerate() {
+            assert_eq!(sample, source[i % source.len()], "sample {} (iteration {})", i, i / source.len());
+        }
+        // And the join is no bigger a step than any within the tone
+        let largest_step = |samples: &[f32]| {
+            let frames = samples.chunks(2);
+            frames.clone().zip(frames.skip(1)).map(|(a, b)| (b[0] - a[0]).abs()).fold(0.0, f32::max)
+        };
+        let boundary = [&source[source.len() - 2..], &source[..2]].concat();
+        assert!(largest_step(&boundary) <= largest_step(&source));
+        assert!(largest_step(&looped) <= largest_step(&source));
+
+        // Seeks stay inside the loop; disabling carries on to the end of the source
+        decoder.seek(150).unwrap();
+        assert_eq!(decoder.decode_next().unwrap().unwrap()[..4], source[400 * 2..400 * 2 + 4]);
+        decoder.set_sample_loop(false, 50).unwrap();
+        let (_, rest) = decoder.decode_all().unwrap();
+        assert_eq!(rest, source[400 * 2..]);
+        assert!(!decoder.status().looping);
+    }
+
+    #[test]
+    fn test_long_sources_are_not_looped_from_memory() {
+        let mut decoder = AudioDecoder::from_buffer(test_wav(8000, 1, 31)).unwrap();
+        assert!(matches!(decoder.set_sample_loop(true, 1000), Err(AudioError::UnsupportedFormat(_))));
+        assert!(!decoder.status().looping);
+        // Still playable from where it was
+        assert!(decoder.decode_next().unwrap().is_some());
     }
 }
@@ -1,17 +1,53 @@
 // Desktop audio player implementation using cpal
 // Supports Windows, macOS, and Linux
 
-use crate::callback::{CallbackEvent, CallbackManager, PlayerCallback};
-use crate::decoder::{AudioDecoder, AudioRingBuffer};
+mod output;
+
+use crate::callback::{CallbackEvent, CallbackHandle, CallbackManager, PlayerCallback};
+use crate::diagnostics::{LoadPhase, PhaseTimer};
+use crate::channel_remix::{pick_output_channels, remix_channels};
+use crate::decoder::{AudioDecoder, AudioRingBuffer, DecoderStatus};
+use crate::data_uri::parse_data_uri;
+use crate::file_url::local_path;
 use crate::error::{AudioError, Result};
-use crate::output_rate::effective_output_rate;
-use crate::player::{AudioPlayer, PlaybackStatus, PlayerState, PlayerStateContainer};
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, Host, SampleRate, Stream, StreamConfig};
+use crate::http_transport::validate_url;
+use crate::output_rate::{effective_output_rate, requested_stream_rate};
+use crate::cue_points::CuePoints;
+use crate::ducking::{clamp_duck_level, DuckGain};
+use crate::latency::{FixedLatency, LatencyMode};
+use crate::equalizer::{EqBand, Equalizer};
+use crate::handoff::Handoff;
+use crate::level_meter::{OutputLevels, SharedLevelMeter};
+use crate::limiter::{ceiling_from_db, db_to_linear, Limiter, DEFAULT_CEILING_DB};
+use crate::pcm_tap::{OutputTap, OutputTaps, PcmTap, PcmTaps};
+use crate::power_mode::{buffered_ms, PowerMode};
+use crate::replay_gain::ReplayGainSettings;
+use crate::transcript::{TranscriptCue, TranscriptSlot};
+use crate::playback_rate::{clamp_playback_rate, RateRamp};
+use crate::player::{
+    AudioPlayer, AudioSource, OutputProcessing, PlaybackStats, PlaybackStatus, PlaybackToken, PlayerState,
+    PlayerStateContainer, SeekMode, TrackSelection,
+};
+use crate::prepare::{decode_snippet, open_decoder_at, PrepareTask, PreparedSource};
+use crate::scrub::ScrubSnippet;
+use crate::spsc_ring::SpscRing;
+use crate::stream_owner::StreamOwner;
+use output::OutputDevice;
+use crate::sample_format::{f32_to_i16, f32_to_i24, f32_to_i32, OutputBitDepth, TpdfDither};
+use crate::threads::{
+    join_within, run_isolated, set_current_thread_priority, spawn_named, ThreadPriority, DECODE_THREAD,
+    RELEASE_JOIN_TIMEOUT,
+};
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::{
+    Device, Host, SampleFormat, SampleRate, SizedSample, Stream, StreamConfig, SupportedBufferSize,
+    SupportedStreamConfigRange, I24,
+};
 use parking_lot::Mutex;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
+use symphonia::core::probe::Hint;
 
 /// Default ring buffer size (in samples) - used at initialization
 /// Will be optimized based on audio duration when loading
@@ -23,31 +59,235 @@ const MIN_BUFFER_DURATION_SECS: u64 = 2;
 /// Maximum buffer duration in seconds (to limit memory usage)
 const MAX_BUFFER_DURATION_SECS: u64 = 8;
 
-/// Position update interval (milliseconds)
-const POSITION_UPDATE_INTERVAL_MS: u64 = 100;
-
 /// Pre-buffer target in milliseconds (amount to decode before playback starts)
 const PRE_BUFFER_MS: u64 = 100;
 
+/// `ChainControls::limiter_ceiling` value that turns the limiter off
+const LIMITER_OFF: u32 = u32::MAX;
+
+/// Output chain settings the player changes, read by the stream callback without taking a lock
+struct ChainControls {
+    /// New scrub preview, or None to cancel the one playing
+    scrub: Handoff<Option<ScrubSnippet>>,
+    /// Whether a scrub preview is still being mixed
+    scrub_active: AtomicBool,
+    /// Equalizer with new bands, swapped in whole
+    equalizer: Handoff<Equalizer>,
+    /// Linear limiter ceiling as `f32` bits, or `LIMITER_OFF`
+    limiter_ceiling: AtomicU32,
+    /// Latest duck target and fade in milliseconds
+    duck: Handoff<(f32, u64)>,
+}
+
+impl ChainControls {
+    fn new() -> Self {
+        Self {
+            scrub: Handoff::new(),
+            scrub_active: AtomicBool::new(false),
+            equalizer: Handoff::new(),
+            limiter_ceiling: AtomicU32::new(db_to_linear(DEFAULT_CEILING_DB).to_bits()),
+            duck: Handoff::new(),
+        }
+    }
+
+    fn set_limiter_ceiling(&self, ceiling: Option<f32>) {
+        let bits = ceiling.map_or(LIMITER_OFF, f32::to_bits);
+        self.limiter_ceiling.store(bits, Ordering::Relaxed);
+    }
+}
+
+/// Output stages of the stream callback, owned by it; settings arrive through `ChainControls`
+struct OutputChain {
+    controls: Arc<ChainControls>,
+    /// Scrub preview being mixed over the main playback; kept once finished so the callback
+    /// never frees it
+    scrub: Option<ScrubSnippet>,
+    /// Per-channel EQ of the main playback, after volume and ducking
+    equalizer: Equalizer,
+    /// Final stage, after volume and the scrub mix
+    limiter: Limiter,
+    /// `controls.limiter_ceiling` as last applied to `limiter`
+    limiter_ceiling: u32,
+    /// Per-channel levels of what the device is handed
+    meter: Arc<SharedLevelMeter>,
+    /// Ducks the main playback (not the scrub preview) while the app plays a clip over it
+    duck: DuckGain,
+}
+
+impl OutputChain {
+    /// Chain for a new stream, starting from the player's current settings
+    fn new(controls: Arc<ChainControls>, equalizer: Equalizer, duck_level: f32, meter: Arc<SharedLevelMeter>) -> Self {
+        let mut duck = DuckGain::default();
+        duck.fade_to(duck_level, 0);
+        let mut chain = Self {
+            controls,
+            scrub: None,
+            equalizer,
+            limiter: Limiter::new(),
+            limiter_ceiling: db_to_linear(DEFAULT_CEILING_DB).to_bits(),
+            meter,
+            duck,
+        };
+        chain.apply_settings();
+        chain
+    }
+
+    /// Pick up settings the player published since the last callback
+    fn apply_settings(&mut self) {
+        let controls = &self.controls;
+        if let Some(mut scrub) = controls.scrub.take() {
+            std::mem::swap(&mut *scrub, &mut self.scrub);
+            controls.scrub_active.store(self.scrub.is_some(), Ordering::Relaxed);
+            controls.scrub.retire(scrub);
+        }
+        if let Some(mut equalizer) = controls.equalizer.take() {
+            std::mem::swap(&mut *equalizer, &mut self.equalizer);
+            controls.equalizer.retire(equalizer);
+        }
+        if let Some(duck) = controls.duck.take() {
+            let (level, fade_ms) = *duck;
+            self.duck.fade_to(level, fade_ms);
+            controls.duck.retire(duck);
+        }
+        let ceiling = controls.limiter_ceiling.load(Ordering::Relaxed);
+        if ceiling != self.limiter_ceiling {
+            self.limiter.set_ceiling((ceiling != LIMITER_OFF).then(|| f32::from_bits(ceiling)));
+            self.limiter_ceiling = ceiling;
+        }
+    }
+
+    /// Run a callback's samples through the chain; the first `main` samples are the main playback
+    fn process(&mut self, samples: &mut [f32], main: usize, volume: f32, channels: u16, sample_rate: u32) {
+        self.apply_settings();
+        self.duck.process(&mut samples[..main], channels, sample_rate);
+        self.equalizer.process(&mut samples[..main], channels, sample_rate);
+
+        // Mix an active scrub preview over the (ducked) main playback
+        if let Some(snippet) = self.scrub.as_mut() {
+            if !snippet.is_finished() && snippet.mix_into(samples, volume) {
+                self.controls.scrub_active.store(false, Ordering::Relaxed);
+            }
+        }
+
+        self.limiter.process(samples, channels, sample_rate);
+        self.meter.process(samples);
+    }
+}
+
+/// What the output stream callback works with: state shared with the player, and the output
+/// chain it owns. Nothing here is behind a lock, so a callback never waits on the player.
+struct StreamShared {
+    ring_buffer: Arc<SpscRing>,
+    is_playing: Arc<AtomicBool>,
+    sample_count: Arc<AtomicU64>,
+    /// Volume as `f32` bits
+    volume: Arc<AtomicU32>,
+    /// App taps fed with exactly what each callback hands the device
+    output_taps: Arc<OutputTaps>,
+    output_chain: OutputChain,
+    /// Largest callback seen, in samples, so the decode thread can keep the ring big enough
+    callback_samples: Arc<AtomicUsize>,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl StreamShared {
+    /// Fill one callback's worth of interleaved f32 output
+    fn render(&mut self, samples: &mut [f32]) {
+        let volume = f32::from_bits(self.volume.load(Ordering::Relaxed));
+        let mut played_from = None;
+        let mut read = 0;
+        if self.is_playing.load(Ordering::Relaxed) {
+            self.callback_samples.fetch_max(samples.len(), Ordering::Relaxed);
+            read = self.ring_buffer.read_frames(samples, self.channels as usize);
+
+            // Apply volume (skip if volume is 1.0 to avoid unnecessary multiplication)
+            if (volume - 1.0).abs() > 0.001 {
+                for sample in samples[..read].iter_mut() {
+                    *sample *= volume;
+                }
+            }
+
+            let frames = (read / self.channels as usize) as u64;
+            played_from = Some(self.sample_count.fetch_add(frames, Ordering::Relaxed));
+        }
+        // Fill remaining with silence
+        samples[read..].fill(0.0);
+
+        self.output_chain
+            .process(samples, read, volume, self.channels, self.sample_rate);
+
+        if let Some(position_frames) = played_from {
+            self.output_taps
+                .publish(position_frames, self.sample_rate, self.channels, samples);
+        }
+    }
+}
+
 /// Desktop audio player
 pub struct DesktopAudioPlayer {
     state_container: PlayerStateContainer,
     callback_manager: Arc<CallbackManager>,
-    // Wrapped in Arc<Mutex> because cpal::Stream is not Send+Sync on all platforms
-    audio_stream: Arc<Mutex<Option<Stream>>>,
-    ring_buffer: Arc<Mutex<AudioRingBuffer>>,
+    /// Durations of the current load's phases and the last seek
+    load_timer: PhaseTimer,
+    /// Output stream, kept on its own thread because cpal::Stream is not Send on all platforms
+    audio_stream: Arc<Mutex<Option<StreamOwner>>>,
+    ring_buffer: Arc<SpscRing>,
     is_playing: Arc<AtomicBool>,
-    sample_count: Arc<Mutex<u64>>,
+    sample_count: Arc<AtomicU64>,
     decoder_thread: Option<thread::JoinHandle<()>>,
     stop_decoder: Arc<AtomicBool>,
     decoder: Arc<Mutex<Option<AudioDecoder>>>,
-    volume: Arc<Mutex<f32>>,
-    playback_rate: Arc<Mutex<f32>>,
+    /// Loaded source, used for resume tokens and scrub previews
+    current_source: Option<AudioSource>,
+    /// Where the next load starts playing from (see `set_start_position`)
+    start_position_ms: Option<u64>,
+    /// Volume as `f32` bits, read by the output callback
+    volume: Arc<AtomicU32>,
+    /// Requested rate and its ramp; status reports the rate currently in effect
+    playback_rate: Arc<Mutex<RateRamp>>,
     /// Actual output sample rate selected for the audio device. May differ from the decoder's rate
     /// when the hardware does not support it. We resample to this rate to keep playback speed natural.
     output_sample_rate: Arc<Mutex<u32>>,
+    /// Channel count the output stream was opened with (0 until a stream exists)
+    output_channels: Arc<Mutex<u16>>,
     host: Host,
-    device: Option<Device>,
+    device: Option<OutputDevice>,
+    /// Source being opened in the background by `prepare`
+    prepared: Option<PrepareTask>,
+    /// Next source warmed up by `prepare_next` while the current one plays
+    prepared_next: Option<PrepareTask>,
+    /// Requested output sample format
+    output_bit_depth: OutputBitDepth,
+    /// App taps fed with every decoded chunk
+    pcm_taps: Arc<PcmTaps>,
+    /// App taps fed from the output callback, in step with playback
+    output_taps: Arc<OutputTaps>,
+    /// Scrub preview, EQ, limiter and duck settings, published to the stream callback
+    chain_controls: Arc<ChainControls>,
+    /// EQ bands and duck level in effect, so the next stream's chain starts from them
+    eq_bands: Vec<EqBand>,
+    duck_level: f32,
+    /// Levels measured by the current stream's callback
+    output_meter: Arc<SharedLevelMeter>,
+    /// Applied by the decoder thread when it starts
+    thread_priority: ThreadPriority,
+    /// Checked by the decoder thread against the played position
+    cue_points: Arc<CuePoints>,
+    /// Fixed output stream rate; None follows each source
+    target_sample_rate: Option<u32>,
+    /// Read by the decoder thread; the ring buffer length follows on the next load
+    power_mode: Arc<Mutex<PowerMode>>,
+    /// Loudness normalization, handed to the decoder before every packet
+    replay_gain: Arc<Mutex<ReplayGainSettings>>,
+    /// Fetched when the app sets the episode's transcript URL
+    transcript: Arc<TranscriptSlot>,
+    /// Applied when the next stream is opened
+    latency_mode: LatencyMode,
+    /// Buffer sizes in effect for a Fixed latency mode; None in Auto
+    fixed_latency: Arc<Mutex<Option<FixedLatency>>>,
+    /// Largest output callback of the current stream, in samples (0 until known)
+    callback_samples: Arc<AtomicUsize>,
 }
 
 impl DesktopAudioPlayer {
@@ -65,22 +305,48 @@ impl DesktopAudioPlayer {
             device.name().unwrap_or_else(|_| "Unknown".to_string())
         );
 
-        Ok(Self {
+        Ok(Self::with_device(host, OutputDevice::Cpal(device)))
+    }
+
+    fn with_device(host: Host, device: OutputDevice) -> Self {
+        Self {
             state_container: PlayerStateContainer::new(),
             callback_manager: Arc::new(CallbackManager::new()),
+            load_timer: PhaseTimer::default(),
             audio_stream: Arc::new(Mutex::new(None)),
-            ring_buffer: Arc::new(Mutex::new(AudioRingBuffer::new(RING_BUFFER_SIZE))),
+            ring_buffer: Arc::new(SpscRing::new(RING_BUFFER_SIZE)),
             is_playing: Arc::new(AtomicBool::new(false)),
-            sample_count: Arc::new(Mutex::new(0)),
+            sample_count: Arc::new(AtomicU64::new(0)),
             decoder_thread: None,
             stop_decoder: Arc::new(AtomicBool::new(false)),
             decoder: Arc::new(Mutex::new(None)),
-            volume: Arc::new(Mutex::new(1.0)),
-            playback_rate: Arc::new(Mutex::new(1.0)),
+            current_source: None,
+            start_position_ms: None,
+            volume: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            playback_rate: Arc::new(Mutex::new(RateRamp::default())),
             output_sample_rate: Arc::new(Mutex::new(0)),
+            output_channels: Arc::new(Mutex::new(0)),
             host,
             device: Some(device),
-        })
+            prepared: None,
+            prepared_next: None,
+            output_bit_depth: OutputBitDepth::default(),
+            pcm_taps: Arc::new(PcmTaps::new()),
+            output_taps: Arc::new(OutputTaps::new()),
+            chain_controls: Arc::new(ChainControls::new()),
+            eq_bands: Vec::new(),
+            duck_level: 1.0,
+            output_meter: Arc::new(SharedLevelMeter::default()),
+            thread_priority: ThreadPriority::default(),
+            cue_points: Arc::new(CuePoints::new()),
+            target_sample_rate: None,
+            power_mode: Arc::new(Mutex::new(PowerMode::default())),
+            replay_gain: Arc::new(Mutex::new(ReplayGainSettings::default())),
+            transcript: Arc::new(TranscriptSlot::new()),
+            latency_mode: LatencyMode::default(),
+            fixed_latency: Arc::new(Mutex::new(None)),
+            callback_samples: Arc::new(AtomicUsize::new(0)),
+        }
     }
 
     fn initialize_audio_stream(&mut self, sample_rate: u32, channels: u16) -> Result<()> {
@@ -90,8 +356,13 @@ impl DesktopAudioPlayer {
             channels
         );
 
+        let sample_rate = requested_stream_rate(sample_rate, self.target_sample_rate);
+
         // Drop existing stream
         *self.audio_stream.lock() = None;
+        // The new stream's chain starts from the current settings, with no preview playing
+        drop(self.chain_controls.scrub.take());
+        self.chain_controls.scrub_active.store(false, Ordering::Relaxed);
 
         let device = self
             .device
@@ -99,68 +370,226 @@ impl DesktopAudioPlayer {
             .ok_or_else(|| AudioError::DeviceError("No audio device".to_string()))?;
 
         // Configure stream with a supported rate (clamp to device capabilities if needed)
-        let config = self.pick_stream_config(device, sample_rate, channels);
+        let mut config = self.pick_stream_config(device, sample_rate, channels);
+
+        // A fixed latency pins the device buffer; the ring buffer gets the rest
+        let fixed_latency = match self.latency_mode {
+            LatencyMode::Auto => None,
+            LatencyMode::Fixed(total_ms) => {
+                let range = Self::device_buffer_range(device, &config);
+                let plan = FixedLatency::plan(total_ms, config.sample_rate.0, range);
+                if range.is_some() {
+                    config.buffer_size = cpal::BufferSize::Fixed(plan.device_frames);
+                }
+                log::info!(
+                    "Fixed latency {} ms: device buffer {} frames, ring buffer {} frames",
+                    plan.latency_ms(),
+                    plan.device_frames,
+                    plan.ring_frames
+                );
+                Some(plan)
+            }
+        };
+        *self.fixed_latency.lock() = fixed_latency;
+
+        // A fixed device buffer is known now; otherwise the first callback reports its size
+        let callback_samples = match config.buffer_size {
+            cpal::BufferSize::Fixed(frames) => frames as usize * config.channels as usize,
+            cpal::BufferSize::Default => 0,
+        };
+        self.callback_samples.store(callback_samples, Ordering::Relaxed);
 
         log::debug!("Stream config: {:?}", config);
 
         // Create stream
-        let ring_buffer = self.ring_buffer.clone();
-        let is_playing = self.is_playing.clone();
-        let sample_count = self.sample_count.clone();
-        let volume = self.volume.clone();
+        let mut equalizer = Equalizer::new();
+        equalizer.set_bands(&self.eq_bands)?;
+        self.output_meter = Arc::new(SharedLevelMeter::new(config.channels));
+        let output_chain = OutputChain::new(
+            self.chain_controls.clone(),
+            equalizer,
+            self.duck_level,
+            self.output_meter.clone(),
+        );
+        let shared = StreamShared {
+            ring_buffer: self.ring_buffer.clone(),
+            is_playing: self.is_playing.clone(),
+            sample_count: self.sample_count.clone(),
+            volume: self.volume.clone(),
+            output_taps: self.output_taps.clone(),
+            output_chain,
+            callback_samples: self.callback_samples.clone(),
+            sample_rate: config.sample_rate.0,
+            channels: config.channels,
+        };
+
+        let sample_format = self.pick_sample_format(device, &config);
+        log::debug!("Stream sample format: {:?}", sample_format);
+
+        // Built on its owner thread, where it stays until dropped
+        let output_bit_depth = self.output_bit_depth;
+        let stream_config = config.clone();
+        let stream_device = match device {
+            OutputDevice::Cpal(device) => device.clone(),
+            #[cfg(test)]
+            OutputDevice::Headless(_) => {
+                let stream = StreamOwner::spawn(move || Ok(output::HeadlessStream::new(&stream_config, shared)))?;
+                return self.stream_opened(stream, &config);
+            }
+        };
+        let stream = StreamOwner::spawn(move || {
+            let (device, config) = (&stream_device, &stream_config);
+            let mut dither = TpdfDither::default();
+            match (sample_format, output_bit_depth) {
+                (SampleFormat::I16, _) => {
+                    Self::build_stream(device, config, shared, move |s| f32_to_i16(s, dither.next_offset()))
+                }
+                (SampleFormat::I24, _) => Self::build_stream(device, config, shared, move |s| {
+                    I24::new_unchecked(f32_to_i24(s, dither.next_offset()))
+                }),
+                // 24-bit audio in a 32-bit container: dither at the 24-bit LSB
+                (SampleFormat::I32, OutputBitDepth::Int24) => {
+                    Self::build_stream(device, config, shared, move |s| {
+                        f32_to_i24(s, dither.next_offset()) << 8
+                    })
+                }
+                (SampleFormat::I32, _) => {
+                    Self::build_stream(device, config, shared, move |s| f32_to_i32(s, dither.next_offset()))
+                }
+                _ => Self::build_stream(device, config, shared, |s| s),
+            }
+        })?;
+        self.stream_opened(stream, &config)
+    }
+
+    fn stream_opened(&mut self, stream: StreamOwner, config: &StreamConfig) -> Result<()> {
+        *self.audio_stream.lock() = Some(stream);
+        *self.output_sample_rate.lock() = config.sample_rate.0;
+        *self.output_channels.lock() = config.channels;
+
+        log::info!("Audio stream initialized successfully");
+        Ok(())
+    }
 
+    /// Build the output stream, converting the f32 ring buffer contents to the device sample type
+    fn build_stream<T, F>(
+        device: &Device,
+        config: &StreamConfig,
+        mut shared: StreamShared,
+        mut convert: F,
+    ) -> Result<Stream>
+    where
+        T: SizedSample + Send + 'static,
+        F: FnMut(f32) -> T + Send + 'static,
+    {
         let err_fn = |err| {
             log::error!("Audio stream error: {}", err);
         };
 
-        let stream = device
-            .build_output_stream(
-                &config,
-                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                    if !is_playing.load(Ordering::Relaxed) {
-                        // Fill with silence
-                        data.fill(0.0);
-                        return;
-                    }
-
-                    let vol = *volume.lock();
-                    let mut buffer = ring_buffer.lock();
-                    let read = buffer.read(data);
+        // Reused between callbacks; only grows if the device asks for a larger burst
+        let mut scratch: Vec<f32> = Vec::new();
 
-                    // Apply volume (skip if volume is 1.0 to avoid unnecessary multiplication)
-                    if (vol - 1.0).abs() > 0.001 {
-                        for sample in data[..read].iter_mut() {
-                            *sample *= vol;
-                        }
+        device
+            .build_output_stream(
+                config,
+                move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                    if scratch.len() < data.len() {
+                        scratch.resize(data.len(), 0.0);
                     }
+                    let samples = &mut scratch[..data.len()];
+                    shared.render(samples);
 
-                    // Fill remaining with silence
-                    if read < data.len() {
-                        data[read..].fill(0.0);
+                    for (out, &sample) in data.iter_mut().zip(samples.iter()) {
+                        *out = convert(sample);
                     }
-
-                    // Update sample count
-                    let mut count = sample_count.lock();
-                    *count += (read / channels as usize) as u64;
                 },
                 err_fn,
                 None,
             )
             .map_err(|e| {
                 AudioError::InitializationError(format!("Failed to build output stream: {}", e))
-            })?;
+            })
+    }
 
-        *self.audio_stream.lock() = Some(stream);
-        *self.output_sample_rate.lock() = config.sample_rate.0;
+    /// Pick the device sample format for the requested output bit depth.
+    /// Float unless an integer depth was requested and the device supports it for this config.
+    fn pick_sample_format(&self, device: &OutputDevice, config: &StreamConfig) -> SampleFormat {
+        let preferred: &[SampleFormat] = match self.output_bit_depth {
+            OutputBitDepth::Float => return SampleFormat::F32,
+            OutputBitDepth::Int16 => &[SampleFormat::I16],
+            OutputBitDepth::Int24 => &[SampleFormat::I24, SampleFormat::I32],
+            OutputBitDepth::Int32 => &[SampleFormat::I32],
+        };
 
-        log::info!("Audio stream initialized successfully");
-        Ok(())
+        let supported: Vec<SampleFormat> = match device.supported_output_configs() {
+            Ok(configs) => configs
+                .into_iter()
+                .filter(|range| {
+                    range.channels() == config.channels
+                        && range.min_sample_rate() <= config.sample_rate
+                        && config.sample_rate <= range.max_sample_rate()
+                })
+                .map(|range| range.sample_format())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        match preferred.iter().find(|format| supported.contains(format)) {
+            Some(format) => *format,
+            None => {
+                log::warn!(
+                    "{}-bit output not supported by device; using f32",
+                    self.output_bit_depth.bits()
+                );
+                SampleFormat::F32
+            }
+        }
+    }
+
+    /// Device buffer sizes (in frames) supported for `config`, if the device reports them
+    fn device_buffer_range(device: &OutputDevice, config: &StreamConfig) -> Option<(u32, u32)> {
+        device
+            .supported_output_configs()
+            .ok()?
+            .into_iter()
+            .filter(|range| range.channels() == config.channels)
+            .filter(|range| {
+                range.min_sample_rate() <= config.sample_rate && config.sample_rate <= range.max_sample_rate()
+            })
+            .find_map(|range| match *range.buffer_size() {
+                SupportedBufferSize::Range { min, max } => Some((min, max)),
+                SupportedBufferSize::Unknown => None,
+            })
     }
 
     /// Pick a stream config that best matches the decoder output while being supported by the device.
     fn pick_stream_config(
         &self,
-        device: &Device,
+        device: &OutputDevice,
+        decoder_sample_rate: u32,
+        channels: u16,
+    ) -> StreamConfig {
+        match device.supported_output_configs() {
+            Ok(ranges) => Self::choose_stream_config(&ranges, decoder_sample_rate, channels),
+            Err(err) => {
+                log::warn!(
+                    "Failed to query supported output configs ({}); using decoder sample rate {}Hz",
+                    err,
+                    decoder_sample_rate
+                );
+                StreamConfig {
+                    channels,
+                    sample_rate: SampleRate(decoder_sample_rate),
+                    buffer_size: cpal::BufferSize::Default,
+                }
+            }
+        }
+    }
+
+    /// Choose among the device's supported ranges: the decoder's channel count when available
+    /// (otherwise the closest layout, remixed on the way in), at the closest supported rate.
+    fn choose_stream_config(
+        ranges: &[SupportedStreamConfigRange],
         decoder_sample_rate: u32,
         channels: u16,
     ) -> StreamConfig {
@@ -171,198 +600,261 @@ impl DesktopAudioPlayer {
             buffer_size: cpal::BufferSize::Default,
         };
 
-        // Try to find a supported range that matches the channel count
-        match device.supported_output_configs() {
-            Ok(mut configs) => {
-                let mut chosen: Option<StreamConfig> = None;
-                while let Some(cfg_range) = configs.next() {
-                    if cfg_range.channels() != channels {
-                        continue;
-                    }
+        let supported: Vec<u16> = ranges.iter().map(|range| range.channels()).collect();
+        let Some(output_channels) = pick_output_channels(channels, &supported) else {
+            log::warn!(
+                "No supported config found for {} channels; using decoder sample rate {}Hz",
+                channels,
+                decoder_sample_rate
+            );
+            return default_config;
+        };
+        if output_channels != channels {
+            log::warn!(
+                "Device does not support {} channels; remixing to {}",
+                channels,
+                output_channels
+            );
+        }
 
-                    let min = cfg_range.min_sample_rate().0;
-                    let max = cfg_range.max_sample_rate().0;
-                    let target = decoder_sample_rate.clamp(min, max);
+        let mut chosen: Option<StreamConfig> = None;
+        for cfg_range in ranges.iter().filter(|range| range.channels() == output_channels) {
+            let min = cfg_range.min_sample_rate().0;
+            let max = cfg_range.max_sample_rate().0;
+            let target = decoder_sample_rate.clamp(min, max);
 
-                    let stream_cfg = cfg_range.with_sample_rate(SampleRate(target)).config();
+            chosen = Some((*cfg_range).with_sample_rate(SampleRate(target)).config());
 
-                    chosen = Some(stream_cfg);
+            // Prefer exact match
+            if target == decoder_sample_rate {
+                break;
+            }
+        }
 
-                    // Prefer exact match
-                    if target == decoder_sample_rate {
-                        break;
-                    }
-                }
+        let cfg = chosen.unwrap_or(default_config);
+        if cfg.sample_rate.0 != decoder_sample_rate {
+            log::warn!(
+                "Decoder sample rate {}Hz not supported; using closest supported {}Hz",
+                decoder_sample_rate,
+                cfg.sample_rate.0
+            );
+        }
+        cfg
+    }
 
-                if let Some(cfg) = chosen {
-                    if cfg.sample_rate.0 != decoder_sample_rate {
-                        log::warn!(
-                            "Decoder sample rate {}Hz not supported; using closest supported {}Hz",
-                            decoder_sample_rate,
-                            cfg.sample_rate.0
-                        );
-                    }
-                    cfg
-                } else {
-                    log::warn!(
-                        "No supported config found for {} channels; using decoder sample rate {}Hz",
-                        channels,
-                        decoder_sample_rate
-                    );
-                    default_config
-                }
-            }
-            Err(err) => {
-                log::warn!(
-                    "Failed to query supported output configs ({}); using decoder sample rate {}Hz",
-                    err,
-                    decoder_sample_rate
-                );
-                default_config
-            }
+    /// Remix decoded samples to the output stream's channel count (0 = no stream yet)
+    fn remix_to_output(samples: Vec<f32>, channels: u16, output_channels: u16) -> Vec<f32> {
+        if output_channels == 0 || output_channels == channels {
+            samples
+        } else {
+            remix_channels(&samples, channels, output_channels)
         }
     }
 
-    fn start_decoder_thread(&mut self) {
+    fn start_decoder_thread(&mut self) -> Result<()> {
         // Stop any existing decoder thread
         self.stop_decoder_thread();
 
         let decoder = self.decoder.clone();
         let ring_buffer = self.ring_buffer.clone();
         let is_playing = self.is_playing.clone();
+        // A fresh flag per pass, so a thread abandoned by `release` is never revived
+        self.stop_decoder = Arc::new(AtomicBool::new(false));
         let stop_decoder = self.stop_decoder.clone();
         let sample_count = self.sample_count.clone();
         let callback_manager = self.callback_manager.clone();
         let state_container = self.state_container.clone();
         let output_sample_rate = self.output_sample_rate.clone();
-
-        stop_decoder.store(false, Ordering::Relaxed);
-
-        let handle = thread::spawn(move || {
-            log::info!("Decoder thread started");
-
-            let mut last_position_update = std::time::Instant::now();
-
-            loop {
-                if stop_decoder.load(Ordering::Relaxed) {
-                    log::info!("Decoder thread stopping");
-                    break;
+        let output_channels = self.output_channels.clone();
+        let pcm_taps = self.pcm_taps.clone();
+        let thread_priority = self.thread_priority;
+        let cue_points = self.cue_points.clone();
+        let power_mode = self.power_mode.clone();
+        let replay_gain = self.replay_gain.clone();
+        let fixed_latency = self.fixed_latency.clone();
+        let callback_samples = self.callback_samples.clone();
+
+        // A new pass starts from the current position, with this track's chapters
+        let start_ms = self.get_status().position_ms;
+        let chapters = self
+            .decoder
+            .lock()
+            .as_ref()
+            .map(|dec| dec.metadata.chapters.clone())
+            .unwrap_or_default();
+        cue_points.sync_chapters(&chapters, start_ms);
+        cue_points.rearm(start_ms);
+
+        let panic_playing = self.is_playing.clone();
+        let panic_callbacks = self.callback_manager.clone();
+        let panic_state = self.state_container.clone();
+
+        let handle = spawn_named(DECODE_THREAD, move || {
+            let _ = run_isolated(&panic_playing, &panic_callbacks, &panic_state, move || {
+                log::info!("Decoder thread started");
+                if let Err(e) = set_current_thread_priority(thread_priority) {
+                    log::warn!("{}", e);
                 }
 
-                if !is_playing.load(Ordering::Relaxed) {
-                    thread::sleep(std::time::Duration::from_millis(10));
-                    continue;
-                }
+                let mut last_position_update = callback_manager.now();
+                // Played position: fires reached cue points and the periodic position callback
+                let mut report_position = |status: DecoderStatus| {
+                    let count = sample_count.load(Ordering::Relaxed);
+                    let effective_rate = effective_output_rate(
+                        *output_sample_rate.lock(),
+                        Some(status.sample_rate),
+                        status.sample_rate,
+                    ) as u64;
+                    let position_ms = (count * 1000).checked_div(effective_rate).unwrap_or(0);
+                    let position_ms = match *fixed_latency.lock() {
+                        Some(fixed) => fixed.audible_position_ms(position_ms),
+                        None => position_ms,
+                    };
+                    let position_ms = status.source_position_ms(position_ms);
+                    cue_points.dispatch_reached(position_ms, &callback_manager);
+
+                    // Update position periodically
+                    let interval_ms = power_mode
+                        .lock()
+                        .position_interval_ms(callback_manager.position_update_interval_ms());
+                    let now = callback_manager.now();
+                    let since_update = now.saturating_duration_since(last_position_update);
+                    if since_update.as_millis() >= interval_ms as u128 {
+                        callback_manager.dispatch_event(CallbackEvent::PositionChanged {
+                            position_ms,
+                            duration_ms: status.duration_ms,
+                        });
+                        last_position_update = now;
+                    }
+                };
 
-                // Decode next packet and get format info
-                let decode_result = {
-                    let mut decoder_lock = decoder.lock();
-                    if let Some(ref mut dec) = *decoder_lock {
-                        let sample_rate = dec.format.sample_rate;
-                        let duration_ms = dec.format.duration_ms;
-                        let channels = dec.format.channels;
-                        match dec.decode_next() {
-                            Ok(Some(samples)) => {
-                                Some((samples, sample_rate, duration_ms, channels))
-                            }
-                            Ok(None) => None,
-                            Err(e) => {
-                                log::error!("Decoding error: {}", e);
-                                callback_manager.dispatch_event(CallbackEvent::Error {
-                                    message: e.to_string(),
-                                });
-                                is_playing.store(false, Ordering::Relaxed);
-                                state_container.set_state(PlayerState::Error);
-                                return; // Exit decode_result block with implicit None
-                            }
-                        }
-                    } else {
-                        return; // No decoder, exit decode_result block
+                loop {
+                    if stop_decoder.load(Ordering::Relaxed) {
+                        log::info!("Decoder thread stopping");
+                        break;
                     }
-                }; // decoder_lock is released here
-
-                match decode_result {
-                    Some((samples, sample_rate, duration_ms, channels)) => {
-                        // Resample if device sample rate differs from decoded audio
-                        let target_rate = effective_output_rate(
-                            *output_sample_rate.lock(),
-                            Some(sample_rate),
-                            sample_rate,
-                        );
-                        let processed = if sample_rate != target_rate {
-                            log::debug!(
-                                "Resampling from {}Hz to {}Hz to match device",
-                                sample_rate,
-                                target_rate
-                            );
-                            Self::resample_linear(&samples, sample_rate, target_rate, channels)
-                        } else {
-                            samples
-                        };
 
-                        // Write to ring buffer (decoder lock already released)
-                        let mut buffer = ring_buffer.lock();
-                        let mut written = 0;
-                        while written < processed.len() {
-                            let w = buffer.write(&processed[written..]);
-                            if w == 0 {
-                                // Buffer is full - sleep based on fullness
-                                let fullness = buffer.fullness();
-                                drop(buffer);
-
-                                // Smart sleep: longer sleep when buffer is fuller
-                                let sleep_ms = if fullness > 0.9 {
-                                    15 // Buffer >90% full: long sleep
-                                } else if fullness > 0.7 {
-                                    10 // Buffer >70% full: medium sleep
-                                } else {
-                                    5 // Buffer <70% full: short sleep
-                                };
-                                thread::sleep(std::time::Duration::from_millis(sleep_ms));
-                                buffer = ring_buffer.lock();
-                            } else {
-                                written += w;
+                    if !is_playing.load(Ordering::Relaxed) {
+                        thread::sleep(power_mode.lock().idle_sleep());
+                        continue;
+                    }
+
+                    // Decode next packet and get format info
+                    let decode_result = {
+                        let mut decoder_lock = decoder.lock();
+                        if let Some(ref mut dec) = *decoder_lock {
+                            dec.set_replay_gain(*replay_gain.lock());
+                            let decoded = match dec.decode_next() {
+                                // One bad packet can leave the codec inconsistent: reset it and
+                                // carry on from the next packet; a second failure is reported
+                                Err(e @ AudioError::DecodingError(_)) => {
+                                    log::warn!("{}; resetting codec", e);
+                                    dec.reset_codec();
+                                    dec.decode_next()
+                                }
+                                decoded => decoded,
+                            };
+                            dec.dispatch_changes(&callback_manager);
+                            let sample_rate = dec.format.sample_rate;
+                            let status = dec.status();
+                            let channels = dec.format.channels;
+                            match decoded {
+                                Ok(Some(samples)) => (Some((samples, sample_rate, channels)), status),
+                                Ok(None) => (None, status),
+                                Err(e) => {
+                                    log::error!("Decoding error: {}", e);
+                                    callback_manager.dispatch_event(CallbackEvent::Error {
+                                        message: e.to_string(),
+                                    });
+                                    is_playing.store(false, Ordering::Relaxed);
+                                    state_container.set_state(PlayerState::Error);
+                                    return; // Exit decode_result block with implicit None
+                                }
                             }
+                        } else {
+                            return; // No decoder, exit decode_result block
                         }
-                        drop(buffer);
-
-                        // Update position periodically
-                        if last_position_update.elapsed().as_millis()
-                            >= POSITION_UPDATE_INTERVAL_MS as u128
-                        {
-                            let count = *sample_count.lock();
-                            let effective_rate = effective_output_rate(
+                    }; // decoder_lock is released here
+
+                    match decode_result {
+                        (Some((samples, sample_rate, channels)), status) => {
+                            pcm_taps.publish(&samples);
+
+                            // Resample if device sample rate differs from decoded audio
+                            let target_rate = effective_output_rate(
                                 *output_sample_rate.lock(),
                                 Some(sample_rate),
                                 sample_rate,
-                            ) as u64;
-                            let position_ms = if effective_rate > 0 {
-                                (count * 1000) / effective_rate
+                            );
+                            let processed = if sample_rate != target_rate {
+                                log::debug!(
+                                    "Resampling from {}Hz to {}Hz to match device",
+                                    sample_rate,
+                                    target_rate
+                                );
+                                Self::resample_linear(&samples, sample_rate, target_rate, channels)
                             } else {
-                                0
+                                samples
                             };
-                            callback_manager.dispatch_event(CallbackEvent::PositionChanged {
-                                position_ms,
-                                duration_ms,
-                            });
-                            last_position_update = std::time::Instant::now();
+                            let processed =
+                                Self::remix_to_output(processed, channels, *output_channels.lock());
+
+                            // Write to ring buffer (decoder lock already released)
+                            // A callback larger than the ring could never be filled: grow it
+                            ring_buffer.fit_callback(callback_samples.load(Ordering::Relaxed));
+                            let mut written = 0;
+                            while written < processed.len() {
+                                let w = ring_buffer.write(&processed[written..]);
+                                if w == 0 {
+                                    // Nothing drains a full ring once playback stops: give up the packet
+                                    if stop_decoder.load(Ordering::Relaxed) {
+                                        break;
+                                    }
+                                    // Buffer is full - sleep based on fullness and power mode
+                                    let fullness = ring_buffer.fullness();
+                                    let frame_channels = match *output_channels.lock() {
+                                        0 => channels,
+                                        n => n,
+                                    };
+                                    let buffered =
+                                        buffered_ms(ring_buffer.available_read(), frame_channels, target_rate);
+
+                                    let sleep = match *fixed_latency.lock() {
+                                        Some(fixed) => fixed.refill_sleep(),
+                                        None => power_mode.lock().full_buffer_sleep(fullness, buffered),
+                                    };
+                                    thread::sleep(sleep);
+                                } else {
+                                    written += w;
+                                }
+                            }
+                            report_position(status);
+                        }
+                        (None, status) => {
+                            // Decoding is done, but the ring still holds the tail: keep the
+                            // pass alive (a seek can still land before the end) until it plays out
+                            if ring_buffer.available_read() > 0 {
+                                report_position(status);
+                                thread::sleep(power_mode.lock().idle_sleep());
+                                continue;
+                            }
+
+                            // Playback completed
+                            log::info!("Playback completed");
+                            is_playing.store(false, Ordering::Relaxed);
+                            callback_manager.dispatch_event(CallbackEvent::PlaybackCompleted);
+                            state_container.set_state(PlayerState::Stopped);
+                            break;
                         }
-                    }
-                    None => {
-                        // Playback completed
-                        log::info!("Playback completed");
-                        is_playing.store(false, Ordering::Relaxed);
-                        callback_manager.dispatch_event(CallbackEvent::PlaybackCompleted);
-                        state_container.set_state(PlayerState::Stopped);
-                        break;
                     }
                 }
-            }
 
-            log::info!("Decoder thread exited");
-        });
+                log::info!("Decoder thread exited");
+            });
+        })?;
 
         self.decoder_thread = Some(handle);
+        Ok(())
     }
 
     fn stop_decoder_thread(&mut self) {
@@ -374,6 +866,69 @@ impl DesktopAudioPlayer {
         }
     }
 
+    /// Stop the decoder thread, waiting at most `timeout` for it (it may be stuck in network
+    /// I/O or in an app callback); a thread still running then is left to exit on its own
+    fn stop_decoder_thread_within(&mut self, timeout: std::time::Duration) {
+        self.stop_decoder.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.decoder_thread.take() {
+            if !join_within(handle, timeout) {
+                log::warn!("Decoder thread did not stop within {:?}; abandoning it", timeout);
+            }
+        }
+    }
+
+    /// Load encoded audio held in memory (`load_buffer`, data: URIs)
+    fn load_memory(&mut self, buffer: &[u8], hint: Hint) -> Result<()> {
+        log::info!("Loading audio from buffer: {} bytes", buffer.len());
+        self.current_source = Some(AudioSource::Buffer(buffer.to_vec()));
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
+
+        self.state_container.set_state(PlayerState::Loading);
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state: PlayerState::Idle,
+                new_state: PlayerState::Loading,
+            });
+
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread();
+        self.cancel_prepare();
+        self.ring_buffer.clear();
+        self.sample_count.store(0, Ordering::Relaxed);
+
+        let decoder = AudioDecoder::from_buffer_with_hint(buffer.to_vec(), hint)?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
+        let sample_rate = decoder.format.sample_rate;
+        let channels = decoder.format.channels;
+
+        let stream_started = self.callback_manager.now();
+        self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
+        *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
+
+        // Optimize buffer size based on audio duration
+        self.optimize_buffer_size();
+
+        // Pre-buffer audio to reduce playback latency
+        self.prebuffer()?;
+
+        self.state_container.set_state(PlayerState::Ready);
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state: PlayerState::Loading,
+                new_state: PlayerState::Ready,
+            });
+
+        log::info!("Audio buffer loaded successfully");
+        Ok(())
+    }
+
     /// Optimize ring buffer size based on audio duration
     /// Adjusts buffer to use between MIN_BUFFER_DURATION_SECS and MAX_BUFFER_DURATION_SECS
     fn optimize_buffer_size(&mut self) {
@@ -384,20 +939,28 @@ impl DesktopAudioPlayer {
                 Some(decoder.format.sample_rate),
                 decoder.format.sample_rate,
             );
-            let channels = decoder.format.channels;
+            // The ring holds audio already remixed to the stream's layout
+            let channels = match *self.output_channels.lock() {
+                0 => decoder.format.channels,
+                output_channels => output_channels,
+            };
             let duration_ms = decoder.format.duration_ms;
             let duration_secs = duration_ms / 1000;
 
             // Calculate optimal buffer duration
             let buffer_duration_secs = duration_secs
                 .max(MIN_BUFFER_DURATION_SECS)
-                .min(MAX_BUFFER_DURATION_SECS);
+                .min(self.power_mode.lock().max_buffer_secs(MAX_BUFFER_DURATION_SECS));
 
             // Calculate buffer size in samples
-            let optimal_size =
-                (sample_rate as u64 * channels as u64 * buffer_duration_secs) as usize;
+            let optimal_size = match *self.fixed_latency.lock() {
+                Some(fixed) => fixed.ring_samples(channels),
+                None => (sample_rate as u64 * channels as u64 * buffer_duration_secs) as usize,
+            };
+            let callback_samples = self.callback_samples.load(Ordering::Relaxed);
+            let optimal_size = optimal_size.max(AudioRingBuffer::min_size_for_callback(callback_samples));
 
-            let current_size = self.ring_buffer.lock().size();
+            let current_size = self.ring_buffer.size();
 
             if optimal_size != current_size {
                 log::info!(
@@ -409,51 +972,181 @@ impl DesktopAudioPlayer {
                 );
 
                 drop(decoder_lock);
-                self.ring_buffer.lock().resize(optimal_size);
+                self.ring_buffer.resize(optimal_size);
             } else {
                 drop(decoder_lock);
             }
         }
     }
 
-    /// Pre-buffer audio data to reduce initial playback latency
-    fn prebuffer(&mut self) -> Result<()> {
-        let mut decoder_lock = self.decoder.lock();
-        if let Some(ref mut decoder) = *decoder_lock {
-            let sample_rate = decoder.format.sample_rate;
-            let channels = decoder.format.channels;
-            let target_rate = effective_output_rate(
-                *self.output_sample_rate.lock(),
-                Some(sample_rate),
-                sample_rate,
-            );
+    /// Install a decoder opened by `prepare` and write its pre-decoded samples to the ring buffer
+    fn install_prepared(&mut self, mut prepared: PreparedSource) -> Result<()> {
+        let sample_rate = prepared.decoder.format.sample_rate;
+        let channels = prepared.decoder.format.channels;
+        // The pre-buffer was decoded before normalization was known
+        prepared.decoder.set_replay_gain(*self.replay_gain.lock());
+        prepared.decoder.apply_replay_gain(&mut prepared.prebuffer);
 
-            // Calculate target samples for pre-buffering at the output rate
-            let target_samples =
-                ((PRE_BUFFER_MS * target_rate as u64) / 1000) as usize * channels as usize;
-            let mut total_buffered = 0;
+        self.initialize_audio_stream(sample_rate, channels)?;
+        *self.decoder.lock() = Some(prepared.decoder);
+        self.optimize_buffer_size();
 
-            log::debug!(
-                "Pre-buffering {}ms ({} samples) at {}Hz target...",
-                PRE_BUFFER_MS,
-                target_samples,
-                target_rate
-            );
+        let target_rate = effective_output_rate(
+            *self.output_sample_rate.lock(),
+            Some(sample_rate),
+            sample_rate,
+        );
+        self.pcm_taps.publish(&prepared.prebuffer);
+        let processed = if sample_rate != target_rate {
+            Self::resample_linear(&prepared.prebuffer, sample_rate, target_rate, channels)
+        } else {
+            prepared.prebuffer
+        };
+        let processed = Self::remix_to_output(processed, channels, *self.output_channels.lock());
+        let written = self.ring_buffer.write(&processed);
 
-            // Decode and buffer data
-            while total_buffered < target_samples {
-                match decoder.decode_next() {
-                    Ok(Some(samples)) => {
-                        let processed = if sample_rate != target_rate {
-                            Self::resample_linear(&samples, sample_rate, target_rate, channels)
-                        } else {
+        log::debug!(
+            "Installed prepared source: {} of {} pre-buffered samples",
+            written,
+            processed.len()
+        );
+        Ok(())
+    }
+
+    /// Reposition the decoder with `seek`, which returns the new output sample count
+    /// given the effective output rate
+    fn seek_decoder(&mut self, seek: impl FnOnce(&mut AudioDecoder, u64) -> Result<u64>) -> Result<()> {
+        let seek_started = self.callback_manager.now();
+        let was_playing = self.is_playing.load(Ordering::Relaxed);
+
+        if was_playing {
+            self.is_playing.store(false, Ordering::Relaxed);
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        self.ring_buffer.clear();
+
+        let mut decoder_lock = self.decoder.lock();
+        if let Some(ref mut dec) = *decoder_lock {
+            let effective_rate = effective_output_rate(
+                *self.output_sample_rate.lock(),
+                Some(dec.format.sample_rate),
+                dec.format.sample_rate,
+            ) as u64;
+            let new_sample_count = match seek(dec, effective_rate) {
+                Ok(count) => count,
+                Err(e) => {
+                    // A refused seek leaves the decoder where it was: carry on playing
+                    if was_playing {
+                        self.is_playing.store(true, Ordering::Relaxed);
+                    }
+                    return Err(e);
+                }
+            };
+            self.sample_count.store(new_sample_count, Ordering::Relaxed);
+            self.cue_points.rearm(new_sample_count * 1000 / effective_rate.max(1));
+        } else {
+            return Err(AudioError::PlaybackError(
+                "No decoder available".to_string(),
+            ));
+        }
+        drop(decoder_lock);
+
+        if was_playing {
+            self.is_playing.store(true, Ordering::Relaxed);
+        }
+
+        self.load_timer.finish(LoadPhase::Seek, seek_started, &self.callback_manager);
+        log::info!("Seek completed");
+        Ok(())
+    }
+
+    /// Discard any background preparation for the current source
+    fn cancel_prepare(&mut self) {
+        if let Some(task) = self.prepared.take() {
+            task.cancel();
+        }
+    }
+
+    /// Fail on an idle player with no output engine (never loaded, or released), where a
+    /// setting would be silently dropped
+    fn require_engine(&self, operation: &str) -> Result<()> {
+        if self.state_container.get_state() == PlayerState::Idle && self.audio_stream.lock().is_none() {
+            return Err(AudioError::InvalidState(format!(
+                "Cannot {} on an idle player; load a source first",
+                operation
+            )));
+        }
+        Ok(())
+    }
+
+    /// Seek a freshly loaded decoder to the requested start, before anything is pre-buffered
+    fn seek_to_start(&mut self, start_position_ms: Option<u64>) -> Result<()> {
+        let Some(position_ms) = start_position_ms.filter(|&ms| ms > 0) else {
+            return Ok(());
+        };
+        log::info!("Starting at {} ms", position_ms);
+        self.seek_decoder(|dec, effective_rate| {
+            let duration_ms = dec.format.duration_ms;
+            let start_ms = if duration_ms > 0 { position_ms.min(duration_ms) } else { position_ms };
+            dec.seek(start_ms)?;
+            Ok((start_ms * effective_rate) / 1000)
+        })
+    }
+
+    /// Pre-buffer audio data to reduce initial playback latency
+    fn prebuffer(&mut self) -> Result<()> {
+        let prebuffer_started = self.callback_manager.now();
+        let mut decoder_lock = self.decoder.lock();
+        if let Some(ref mut decoder) = *decoder_lock {
+            decoder.set_replay_gain(*self.replay_gain.lock());
+            let sample_rate = decoder.format.sample_rate;
+            let channels = decoder.format.channels;
+            let target_rate = effective_output_rate(
+                *self.output_sample_rate.lock(),
+                Some(sample_rate),
+                sample_rate,
+            );
+
+            let output_channels = match *self.output_channels.lock() {
+                0 => channels,
+                n => n,
+            } as usize;
+
+            // Calculate target samples for pre-buffering in the output format; a fixed latency
+            // starts with the ring at its full depth
+            let target_samples = match *self.fixed_latency.lock() {
+                Some(fixed) => fixed.ring_samples(output_channels as u16),
+                None => ((PRE_BUFFER_MS * target_rate as u64) / 1000) as usize * output_channels,
+            };
+            let mut total_buffered = 0;
+
+            log::debug!(
+                "Pre-buffering {}ms ({} samples) at {}Hz target...",
+                PRE_BUFFER_MS,
+                target_samples,
+                target_rate
+            );
+
+            // Decode and buffer data
+            while total_buffered < target_samples {
+                match decoder.decode_next() {
+                    Ok(Some(samples)) => {
+                        if total_buffered == 0 {
+                            let callbacks = &self.callback_manager;
+                            self.load_timer.finish(LoadPhase::FirstPacket, prebuffer_started, callbacks);
+                        }
+                        self.pcm_taps.publish(&samples);
+                        let processed = if sample_rate != target_rate {
+                            Self::resample_linear(&samples, sample_rate, target_rate, channels)
+                        } else {
                             samples
                         };
+                        let processed =
+                            Self::remix_to_output(processed, channels, *self.output_channels.lock());
 
-                        let mut buffer = self.ring_buffer.lock();
-                        let written = buffer.write(&processed);
+                        let written = self.ring_buffer.write(&processed);
                         total_buffered += written;
-                        drop(buffer);
 
                         if written < processed.len() {
                             // Ring buffer full, we have enough
@@ -475,31 +1168,23 @@ impl DesktopAudioPlayer {
                 }
             }
 
-            log::debug!(
-                "Pre-buffered {} samples ({}ms)",
-                total_buffered,
-                (total_buffered / channels as usize) * 1000 / sample_rate as usize
-            );
+            let buffered_ms = ((total_buffered / output_channels) as u64 * 1000) / target_rate as u64;
+            log::debug!("Pre-buffered {} samples ({}ms)", total_buffered, buffered_ms);
+            self.load_timer.finish(LoadPhase::Prebuffer, prebuffer_started, &self.callback_manager);
+            self.callback_manager
+                .dispatch_event(CallbackEvent::PrebufferComplete { buffered_ms });
         }
         drop(decoder_lock);
         Ok(())
     }
 }
 
-// SAFETY: DesktopAudioPlayer is safe to send between threads because:
-// 1. The audio_stream (cpal::Stream) is only accessed from the thread that created it
-// 2. All other fields are already Send+Sync (Arc, Mutex, AtomicBool, etc.)
-// 3. The AudioPlayer trait methods are always called from the same thread
-// 4. We use proper synchronization (Arc<Mutex>) for shared state
-//
-// Note: cpal::Stream is intentionally !Send+!Sync on Windows due to COM threading
-// requirements, but in our usage pattern (single-threaded access), this is safe.
-unsafe impl Send for DesktopAudioPlayer {}
-unsafe impl Sync for DesktopAudioPlayer {}
-
 impl AudioPlayer for DesktopAudioPlayer {
     fn load_file(&mut self, path: &str) -> Result<()> {
         log::info!("Loading audio file: {}", path);
+        self.current_source = Some(AudioSource::File(path.to_string()));
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
 
         self.state_container.set_state(PlayerState::Loading);
         self.callback_manager
@@ -510,15 +1195,24 @@ impl AudioPlayer for DesktopAudioPlayer {
 
         self.is_playing.store(false, Ordering::Relaxed);
         self.stop_decoder_thread();
-        self.ring_buffer.lock().clear();
-        *self.sample_count.lock() = 0;
+        self.cancel_prepare();
+        self.ring_buffer.clear();
+        self.sample_count.store(0, Ordering::Relaxed);
 
         let decoder = AudioDecoder::from_file(path)?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
         let sample_rate = decoder.format.sample_rate;
         let channels = decoder.format.channels;
 
+        let stream_started = self.callback_manager.now();
         self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
         *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
 
         // Optimize buffer size based on audio duration
         self.optimize_buffer_size();
@@ -538,7 +1232,21 @@ impl AudioPlayer for DesktopAudioPlayer {
     }
 
     fn load_url(&mut self, url: &str) -> Result<()> {
+        // file: URLs and plain paths (as file pickers hand them out) play from disk
+        if let Some(path) = local_path(url) {
+            return self.load_file(&path);
+        }
+        // Small embedded audio: decode the payload in memory instead of fetching anything
+        if let Some(data_uri) = parse_data_uri(url)? {
+            log::info!("Loading audio from {} data URI", data_uri.mime_type);
+            return self.load_memory(&data_uri.data, data_uri.hint());
+        }
+        // Reject malformed URLs here, before the current source is stopped
+        validate_url(url)?;
         log::info!("Loading audio from URL (streaming): {}", url);
+        self.current_source = Some(AudioSource::Url(url.to_string()));
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
 
         self.state_container.set_state(PlayerState::Loading);
         self.callback_manager
@@ -549,17 +1257,21 @@ impl AudioPlayer for DesktopAudioPlayer {
 
         self.is_playing.store(false, Ordering::Relaxed);
         self.stop_decoder_thread();
-        self.ring_buffer.lock().clear();
-        *self.sample_count.lock() = 0;
-
-        // Create hint from URL
-        let hint = AudioDecoder::create_hint_from_url(url);
+        self.cancel_prepare();
+        self.ring_buffer.clear();
+        self.sample_count.store(0, Ordering::Relaxed);
 
         // Use HTTP Range-based source for true streaming without downloading entire file
         // This supports both Fast Start and Non-Fast Start M4A files
         log::info!("Using HTTP Range source (on-demand download)");
-        let source = crate::http_range_source::HttpRangeSource::new(url.to_string())?;
-        let decoder = AudioDecoder::from_streaming_source(Box::new(source), hint)?;
+        let callbacks = &self.callback_manager;
+        let decoder = AudioDecoder::from_url_with_stages(url, |stage| {
+            callbacks.dispatch_event(CallbackEvent::StreamPreparing { stage })
+        })?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
 
         let sample_rate = decoder.format.sample_rate;
         let channels = decoder.format.channels;
@@ -570,8 +1282,11 @@ impl AudioPlayer for DesktopAudioPlayer {
             channels
         );
 
+        let stream_started = self.callback_manager.now();
         self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
         *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
 
         // Optimize buffer size based on audio duration
         self.optimize_buffer_size();
@@ -591,36 +1306,154 @@ impl AudioPlayer for DesktopAudioPlayer {
     }
 
     fn load_buffer(&mut self, buffer: &[u8]) -> Result<()> {
-        log::info!("Loading audio from buffer: {} bytes", buffer.len());
+        self.load_memory(buffer, Hint::new())
+    }
 
+    fn set_start_position(&mut self, position_ms: u64) -> Result<()> {
+        self.start_position_ms = Some(position_ms);
+        Ok(())
+    }
+
+    fn prepare(&mut self, source: AudioSource) -> Result<()> {
+        log::info!("Preparing audio source in background");
+
+        let old_state = self.state_container.get_state();
         self.state_container.set_state(PlayerState::Loading);
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state,
+                new_state: PlayerState::Loading,
+            });
 
         self.is_playing.store(false, Ordering::Relaxed);
         self.stop_decoder_thread();
-        self.ring_buffer.lock().clear();
-        *self.sample_count.lock() = 0;
+        self.cancel_prepare();
+        self.ring_buffer.clear();
+        self.sample_count.store(0, Ordering::Relaxed);
+        *self.decoder.lock() = None;
+
+        self.current_source = Some(source.clone());
+
+        // Reuse the warmed-up decoder if this is the source passed to prepare_next
+        let task = match self.prepared_next.take() {
+            Some(next) if next.source() == &source => next,
+            Some(next) => {
+                next.cancel();
+                PrepareTask::spawn(source, PRE_BUFFER_MS)?
+            }
+            None => PrepareTask::spawn(source, PRE_BUFFER_MS)?,
+        };
+
+        let state_container = self.state_container.clone();
+        let callback_manager = self.callback_manager.clone();
+        task.set_on_ready(move |outcome| match outcome {
+            Ok(info) => {
+                callback_manager.dispatch_event(CallbackEvent::Prepared { format: info.format });
+                callback_manager.dispatch_event(CallbackEvent::PrebufferComplete {
+                    buffered_ms: info.buffered_ms,
+                });
+                state_container.set_state(PlayerState::Ready);
+                callback_manager.dispatch_event(CallbackEvent::StateChanged {
+                    old_state: PlayerState::Loading,
+                    new_state: PlayerState::Ready,
+                });
+                log::info!("Audio source prepared");
+            }
+            Err(e) => {
+                log::error!("Failed to prepare audio source: {}", e);
+                state_container.set_state(PlayerState::Error);
+                callback_manager.dispatch_event(CallbackEvent::Error {
+                    message: e.to_string(),
+                });
+            }
+        });
+
+        self.prepared = Some(task);
+        Ok(())
+    }
+
+    fn load_with_resume(&mut self, source: AudioSource, position_ms: u64) -> Result<()> {
+        log::info!("Loading audio source, resuming at {} ms", position_ms);
+
+        let old_state = self.state_container.get_state();
+        self.state_container.set_state(PlayerState::Loading);
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state,
+                new_state: PlayerState::Loading,
+            });
 
-        let decoder = AudioDecoder::from_buffer(buffer.to_vec())?;
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread();
+        self.cancel_prepare();
+        self.ring_buffer.clear();
+        self.current_source = Some(source.clone());
+        self.start_position_ms = None;
+
+        // Seek before pre-buffering so nothing is primed from the start of the file
+        let (decoder, start_ms) = open_decoder_at(&source, position_ms)?;
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
         let sample_rate = decoder.format.sample_rate;
         let channels = decoder.format.channels;
 
         self.initialize_audio_stream(sample_rate, channels)?;
         *self.decoder.lock() = Some(decoder);
 
-        // Optimize buffer size based on audio duration
-        self.optimize_buffer_size();
+        let effective_rate = effective_output_rate(
+            *self.output_sample_rate.lock(),
+            Some(sample_rate),
+            sample_rate,
+        ) as u64;
+        self.sample_count.store((start_ms * effective_rate) / 1000, Ordering::Relaxed);
 
-        // Pre-buffer audio to reduce playback latency
+        self.optimize_buffer_size();
         self.prebuffer()?;
 
         self.state_container.set_state(PlayerState::Ready);
-        log::info!("Audio buffer loaded successfully");
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state: PlayerState::Loading,
+                new_state: PlayerState::Ready,
+            });
+
+        log::info!("Audio source loaded at {} ms", start_ms);
+        Ok(())
+    }
+
+    fn get_resume_token(&self) -> Option<PlaybackToken> {
+        let source_id = self.current_source.as_ref()?.id();
+        Some(PlaybackToken {
+            source_id,
+            position_ms: self.get_status().position_ms,
+        })
+    }
+
+    fn prepare_next(&mut self, source: AudioSource) -> Result<()> {
+        if let Some(next) = &self.prepared_next {
+            if next.source() == &source {
+                return Ok(());
+            }
+        }
+
+        log::info!("Warming up next audio source");
+        self.prepared_next = Some(PrepareTask::spawn(source, PRE_BUFFER_MS)?);
         Ok(())
     }
 
     fn play(&mut self) -> Result<()> {
         log::info!("Starting playback");
 
+        // Finish a pending prepare (only blocks if the background work is still running)
+        if let Some(task) = self.prepared.take() {
+            if !task.is_finished() {
+                log::debug!("Waiting for background prepare to finish");
+            }
+            let prepared = task.wait()?;
+            self.install_prepared(prepared)?;
+        }
+
         let current_state = self.state_container.get_state();
         if current_state != PlayerState::Ready && current_state != PlayerState::Paused {
             return Err(AudioError::InvalidState(format!(
@@ -631,7 +1464,7 @@ impl AudioPlayer for DesktopAudioPlayer {
 
         // Start decoder thread first (if not already running)
         if self.decoder_thread.is_none() {
-            self.start_decoder_thread();
+            self.start_decoder_thread()?;
         }
 
         // Enable playback flag before starting stream
@@ -641,9 +1474,7 @@ impl AudioPlayer for DesktopAudioPlayer {
         // Start audio stream
         let stream_guard = self.audio_stream.lock();
         if let Some(ref stream) = *stream_guard {
-            stream
-                .play()
-                .map_err(|e| AudioError::PlaybackError(format!("Failed to start stream: {}", e)))?;
+            stream.play()?;
         } else {
             return Err(AudioError::PlaybackError(
                 "No audio stream available".to_string(),
@@ -677,9 +1508,7 @@ impl AudioPlayer for DesktopAudioPlayer {
 
         let stream_guard = self.audio_stream.lock();
         if let Some(ref stream) = *stream_guard {
-            stream
-                .pause()
-                .map_err(|e| AudioError::PlaybackError(format!("Failed to pause stream: {}", e)))?;
+            stream.pause()?;
         }
         drop(stream_guard);
 
@@ -702,19 +1531,19 @@ impl AudioPlayer for DesktopAudioPlayer {
 
         let stream_guard = self.audio_stream.lock();
         if let Some(ref stream) = *stream_guard {
-            stream
-                .pause()
-                .map_err(|e| AudioError::PlaybackError(format!("Failed to stop stream: {}", e)))?;
+            stream.pause()?;
         }
         drop(stream_guard);
 
-        self.ring_buffer.lock().clear();
-        *self.sample_count.lock() = 0;
+        self.ring_buffer.clear();
+        self.sample_count.store(0, Ordering::Relaxed);
+        self.chain_controls.scrub.put(None);
 
+        let old_state = self.state_container.get_state();
         self.state_container.set_state(PlayerState::Stopped);
         self.callback_manager
             .dispatch_event(CallbackEvent::StateChanged {
-                old_state: self.state_container.get_state(),
+                old_state,
                 new_state: PlayerState::Stopped,
             });
 
@@ -724,44 +1553,187 @@ impl AudioPlayer for DesktopAudioPlayer {
 
     fn seek(&mut self, position_ms: u64) -> Result<()> {
         log::info!("Seeking to {} ms", position_ms);
+        self.seek_decoder(|dec, effective_rate| {
+            dec.seek(position_ms)?;
+            Ok((position_ms * effective_rate) / 1000)
+        })
+    }
+
+    fn seek_with_mode(&mut self, position_ms: u64, mode: SeekMode) -> Result<()> {
+        log::info!("Seeking to {} ms ({:?})", position_ms, mode);
+        self.seek_decoder(|dec, effective_rate| {
+            let landed_ms = dec.seek_with_mode(position_ms, mode)?;
+            Ok((landed_ms * effective_rate) / 1000)
+        })
+    }
+
+    fn set_looping(&mut self, enabled: bool) -> Result<()> {
+        log::info!("Sample-accurate looping: {}", enabled);
+        let position_ms = self.get_status().position_ms;
+        self.seek_decoder(|dec, effective_rate| {
+            dec.set_sample_loop(enabled, position_ms)?;
+            Ok((position_ms * effective_rate) / 1000)
+        })
+    }
 
+    fn seek_to_sample(&mut self, frame_index: u64) -> Result<()> {
+        log::info!("Seeking to frame {}", frame_index);
+        self.seek_decoder(|dec, effective_rate| {
+            dec.seek_to_sample(frame_index)?;
+            Ok((frame_index * effective_rate) / dec.format.sample_rate.max(1) as u64)
+        })
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        log::info!("Flushing audio pipeline");
+
+        let position_ms = self.get_status().position_ms;
         let was_playing = self.is_playing.load(Ordering::Relaxed);
 
+        self.callback_manager
+            .dispatch_event(CallbackEvent::BufferingChanged { buffering: true });
+
         if was_playing {
             self.is_playing.store(false, Ordering::Relaxed);
             thread::sleep(std::time::Duration::from_millis(10));
         }
 
-        self.ring_buffer.lock().clear();
+        self.ring_buffer.clear();
 
-        let mut decoder_lock = self.decoder.lock();
-        if let Some(ref mut dec) = *decoder_lock {
-            dec.seek(position_ms)?;
-            let effective_rate = effective_output_rate(
-                *self.output_sample_rate.lock(),
-                Some(dec.format.sample_rate),
-                dec.format.sample_rate,
-            ) as u64;
-            let new_sample_count = (position_ms * effective_rate) / 1000;
-            *self.sample_count.lock() = new_sample_count;
-        } else {
-            return Err(AudioError::PlaybackError(
+        // The decoder has run ahead of the output; rewind it to what was actually heard
+        let result = match self.decoder.lock().as_mut() {
+            Some(dec) => dec.seek(position_ms),
+            None => Err(AudioError::PlaybackError(
                 "No decoder available".to_string(),
-            ));
-        }
-        drop(decoder_lock);
+            )),
+        };
+        let result = result.and_then(|_| self.prebuffer());
 
         if was_playing {
             self.is_playing.store(true, Ordering::Relaxed);
         }
 
-        log::info!("Seek completed");
+        self.callback_manager
+            .dispatch_event(CallbackEvent::BufferingChanged { buffering: false });
+
+        result
+    }
+
+    fn add_pcm_tap(&mut self, tap: PcmTap) -> Result<()> {
+        self.pcm_taps.add(tap)
+    }
+
+    fn add_output_tap(&mut self, tap: OutputTap) -> Result<()> {
+        self.output_taps.add(tap)
+    }
+
+    fn set_limiter_ceiling(&mut self, ceiling_db: Option<f32>) -> Result<()> {
+        self.chain_controls.set_limiter_ceiling(ceiling_from_db(ceiling_db)?);
+        Ok(())
+    }
+
+    fn duck(&mut self, level: f32, fade_ms: u64) -> Result<()> {
+        self.duck_level = clamp_duck_level(level)?;
+        self.chain_controls.duck.put((self.duck_level, fade_ms));
+        Ok(())
+    }
+
+    fn unduck(&mut self, fade_ms: u64) -> Result<()> {
+        self.duck_level = 1.0;
+        self.chain_controls.duck.put((1.0, fade_ms));
+        Ok(())
+    }
+
+    fn write_cover_art(&self, path: &str) -> Result<()> {
+        match self.decoder.lock().as_ref() {
+            Some(decoder) => decoder.write_cover_art(path),
+            None => Err(AudioError::InvalidState("No audio loaded".to_string())),
+        }
+    }
+
+    fn set_target_sample_rate(&mut self, sample_rate: Option<u32>) -> Result<()> {
+        if sample_rate == Some(0) {
+            return Err(AudioError::InvalidState("Target sample rate must be positive".to_string()));
+        }
+        self.target_sample_rate = sample_rate;
+        Ok(())
+    }
+
+    fn set_power_mode(&mut self, mode: PowerMode) -> Result<()> {
+        log::info!("Power mode: {:?}", mode);
+        *self.power_mode.lock() = mode;
+        Ok(())
+    }
+
+    fn set_replay_gain(&mut self, settings: ReplayGainSettings) -> Result<()> {
+        settings.validate()?;
+        log::info!("ReplayGain: {:?}", settings);
+        *self.replay_gain.lock() = settings;
+        Ok(())
+    }
+
+    fn set_thread_priority(&mut self, priority: ThreadPriority) -> Result<()> {
+        self.thread_priority = priority;
+        Ok(())
+    }
+
+    fn wait_until_complete(&self, timeout: Option<std::time::Duration>) -> Result<()> {
+        self.state_container.wait_until_complete(timeout)
+    }
+
+    fn add_cue_point(&mut self, id: &str, position_ms: u64) -> Result<()> {
+        self.cue_points.add(id, position_ms, self.get_status().position_ms);
+        Ok(())
+    }
+
+    fn remove_cue_point(&mut self, id: &str) -> Result<()> {
+        self.cue_points.remove(id);
+        Ok(())
+    }
+
+    fn chapters(&self) -> Vec<crate::metadata::Chapter> {
+        self.decoder
+            .lock()
+            .as_ref()
+            .map(|dec| dec.metadata.chapters.clone())
+            .unwrap_or_default()
+    }
+
+    fn set_chapter_cue_points(&mut self, enabled: bool) -> Result<()> {
+        let chapters = self.chapters();
+        self.cue_points.set_chapters_enabled(enabled, &chapters, self.get_status().position_ms);
         Ok(())
     }
 
+    fn set_latency_mode(&mut self, mode: LatencyMode) -> Result<()> {
+        if mode == LatencyMode::Fixed(0) {
+            return Err(AudioError::InvalidState("Fixed latency must be positive".to_string()));
+        }
+        log::info!("Latency mode: {:?} (applies from the next load)", mode);
+        self.latency_mode = mode;
+        Ok(())
+    }
+
+    fn output_latency_ms(&self) -> u64 {
+        if let Some(fixed) = *self.fixed_latency.lock() {
+            return fixed.latency_ms();
+        }
+        let buffered = self.ring_buffer.available_read();
+        buffered_ms(buffered, *self.output_channels.lock(), *self.output_sample_rate.lock())
+    }
+
+    fn set_transcript_url(&mut self, url: Option<&str>) -> Result<()> {
+        self.transcript.set_url(url)
+    }
+
+    fn current_transcript_cue(&self, position_ms: u64) -> Option<TranscriptCue> {
+        self.transcript.current_cue(position_ms)
+    }
+
     fn set_volume(&mut self, volume: f32) -> Result<()> {
+        self.require_engine("set volume")?;
         let clamped = volume.clamp(0.0, 1.0);
-        *self.volume.lock() = clamped;
+        self.volume.store(clamped.to_bits(), Ordering::Relaxed);
 
         self.callback_manager
             .dispatch_event(CallbackEvent::VolumeChanged { volume: clamped });
@@ -771,7 +1743,9 @@ impl AudioPlayer for DesktopAudioPlayer {
     }
 
     fn set_playback_rate(&mut self, rate: f32) -> Result<()> {
-        *self.playback_rate.lock() = rate;
+        self.require_engine("set playback rate")?;
+        let rate = clamp_playback_rate(rate)?;
+        self.playback_rate.lock().set_target(rate, self.callback_manager.now());
 
         self.callback_manager
             .dispatch_event(CallbackEvent::PlaybackRateChanged { rate });
@@ -785,62 +1759,195 @@ impl AudioPlayer for DesktopAudioPlayer {
     }
 
     fn get_status(&self) -> PlaybackStatus {
-        let decoder_lock = self.decoder.lock();
-        let duration_ms = if let Some(ref dec) = *decoder_lock {
-            dec.format.duration_ms
-        } else {
-            0
-        };
-        drop(decoder_lock);
+        // One lock, so duration and rate come from the same decoder even across a track change
+        let decoder = self.decoder.lock().as_ref().map(AudioDecoder::status);
+        let (duration_ms, duration_known) = decoder.map_or((0, false), |d| (d.duration_ms, d.duration_known));
 
-        let sample_count = *self.sample_count.lock();
+        let sample_count = self.sample_count.load(Ordering::Relaxed);
         let selected_rate = *self.output_sample_rate.lock();
-        let decoder_rate = self
-            .decoder
-            .lock()
-            .as_ref()
-            .map(|dec| dec.format.sample_rate);
+        let decoder_rate = decoder.map(|d| d.sample_rate);
         let sample_rate = effective_output_rate(selected_rate, decoder_rate, 48000) as u64;
 
-        let position_ms = if sample_rate > 0 {
-            (sample_count * 1000) / sample_rate
-        } else {
-            0
+        let position_ms = (sample_count * 1000).checked_div(sample_rate).unwrap_or(0);
+        let position_ms = match *self.fixed_latency.lock() {
+            Some(fixed) => fixed.audible_position_ms(position_ms),
+            None => position_ms,
         };
+        let position_ms = decoder.map_or(position_ms, |d| d.source_position_ms(position_ms));
 
         PlaybackStatus {
             position_ms,
             duration_ms,
-            volume: *self.volume.lock(),
-            playback_rate: *self.playback_rate.lock(),
+            volume: f32::from_bits(self.volume.load(Ordering::Relaxed)),
+            playback_rate: self.playback_rate.lock().effective(self.callback_manager.now()),
             buffering: false,
+            duration_known,
         }
     }
 
+    fn output_sample_rate(&self) -> u32 {
+        *self.output_sample_rate.lock()
+    }
+
+    fn output_channels(&self) -> u16 {
+        *self.output_channels.lock()
+    }
+
+    fn get_stats(&self) -> PlaybackStats {
+        let mut stats = self.decoder.lock().as_ref().map(AudioDecoder::stats).unwrap_or_default();
+        stats.phases = self.load_timer.timings();
+        stats.time_to_first_audio_ms = self.load_timer.time_to_first_audio_ms();
+        stats.dropped_tap_chunks = self.pcm_taps.dropped_chunks() + self.output_taps.dropped_chunks();
+        stats
+    }
+
+    fn event_queue_depth(&self) -> usize {
+        self.callback_manager.queue_depth()
+    }
+
     fn set_callback(&mut self, callback: Option<Arc<dyn PlayerCallback>>) {
         self.callback_manager.clear_callbacks();
         if let Some(cb) = callback {
-            self.callback_manager
-                .add_callback(cb, POSITION_UPDATE_INTERVAL_MS);
+            self.callback_manager.add_callback_with_default_interval(cb);
         }
     }
 
+    fn add_callback(
+        &mut self,
+        callback: Arc<dyn PlayerCallback>,
+        position_update_interval_ms: u64,
+    ) -> CallbackHandle {
+        self.callback_manager.add_callback(callback, position_update_interval_ms)
+    }
+
+    fn remove_callback(&mut self, handle: CallbackHandle) -> bool {
+        self.callback_manager.remove_callback(handle)
+    }
+
+    fn set_position_update_interval_ms(&mut self, interval_ms: u64) {
+        self.callback_manager
+            .set_position_update_interval_ms(interval_ms);
+    }
+
     fn release(&mut self) -> Result<()> {
         log::info!("Releasing audio player");
 
-        self.stop()?;
-        self.stop_decoder_thread();
+        // Bounded wait for the decode thread; stop() then delivers the terminal Stopped event
+        // and the teardown below runs even if stopping the stream failed
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread_within(RELEASE_JOIN_TIMEOUT);
+        let stopped = self.stop();
+        self.cancel_prepare();
+        self.prepared_next = None;
         *self.audio_stream.lock() = None;
+        *self.output_sample_rate.lock() = 0;
+        *self.output_channels.lock() = 0;
         *self.decoder.lock() = None;
         self.state_container.set_state(PlayerState::Idle);
+        // Deliver what is still queued, the final Stopped included, before returning
+        if !self.callback_manager.flush(RELEASE_JOIN_TIMEOUT) {
+            log::warn!("Player callbacks still busy after release");
+        }
 
         log::info!("Audio player released");
-        Ok(())
+        stopped
+    }
+
+    fn output_processing(&mut self) -> Option<&mut dyn OutputProcessing> {
+        Some(self)
+    }
+
+    fn track_selection(&mut self) -> Option<&mut dyn TrackSelection> {
+        Some(self)
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl OutputProcessing for DesktopAudioPlayer {
+    fn set_output_bit_depth(&mut self, depth: OutputBitDepth) -> Result<()> {
+        log::info!("Output bit depth set to {:?}", depth);
+        self.output_bit_depth = depth;
+        Ok(())
+    }
+
+    fn set_eq_bands(&mut self, bands: &[EqBand]) -> Result<()> {
+        log::info!("EQ bands: {:?}", bands);
+        let mut equalizer = Equalizer::new();
+        equalizer.set_bands(bands)?;
+        self.eq_bands = bands.to_vec();
+        self.chain_controls.equalizer.put(equalizer);
+        Ok(())
+    }
+
+    fn output_levels(&self) -> Result<OutputLevels> {
+        Ok(self.output_meter.take_levels())
+    }
+
+    fn scrub_preview(&mut self, position_ms: u64, duration_ms: u64) -> Result<()> {
+        log::debug!("Scrub preview at {} ms ({} ms)", position_ms, duration_ms);
+
+        let source = self
+            .current_source
+            .as_ref()
+            .ok_or_else(|| AudioError::InvalidState("No audio loaded".to_string()))?;
+
+        // Decoded with its own decoder, so the main decoder and sample_count are left alone
+        let (snippet, sample_rate, channels) = decode_snippet(source, position_ms, duration_ms)?;
+        let target_rate = effective_output_rate(
+            *self.output_sample_rate.lock(),
+            Some(sample_rate),
+            sample_rate,
+        );
+        let snippet = if sample_rate != target_rate {
+            Self::resample_linear(&snippet, sample_rate, target_rate, channels)
+        } else {
+            snippet
+        };
+        let snippet = Self::remix_to_output(snippet, channels, *self.output_channels.lock());
+
+        self.chain_controls.scrub.put(Some(ScrubSnippet::new(snippet)));
+        self.chain_controls.scrub_active.store(true, Ordering::Relaxed);
+
+        // A paused stream has to run for the preview to be heard; it renders silence afterwards
+        if !self.is_playing.load(Ordering::Relaxed) {
+            if let Some(ref stream) = *self.audio_stream.lock() {
+                stream.play()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TrackSelection for DesktopAudioPlayer {
+    fn set_audio_track(&mut self, track_id: u32) -> Result<()> {
+        log::info!("Switching to audio track {}", track_id);
+
+        let position_ms = self.get_status().position_ms;
+        let (previous, current) = {
+            let mut decoder_lock = self.decoder.lock();
+            let dec = decoder_lock.as_mut().ok_or_else(|| {
+                AudioError::PlaybackError("No decoder available".to_string())
+            })?;
+            let previous = (dec.format.sample_rate, dec.format.channels);
+            dec.select_track(track_id)?;
+            (previous, (dec.format.sample_rate, dec.format.channels))
+        };
+
+        // A track with a different layout or rate needs its own output stream
+        if current != previous {
+            self.initialize_audio_stream(current.0, current.1)?;
+        }
+
+        // Drop audio buffered from the old track and resume the new one where we were
+        self.seek(position_ms)
+    }
 }
 
 impl DesktopAudioPlayer {
@@ -890,3 +1997,744 @@ impl Drop for DesktopAudioPlayer {
         let _ = self.release();
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Player on a headless output device, rendering in real time without a sound card
+    pub(crate) fn headless_player() -> DesktopAudioPlayer {
+        DesktopAudioPlayer::with_device(cpal::default_host(), OutputDevice::headless())
+    }
+
+    /// Samples waiting in the player's ring buffer
+    pub(crate) fn buffered_samples(player: &DesktopAudioPlayer) -> usize {
+        player.ring_buffer.available_read()
+    }
+
+    /// Playing stream state over `ring`, with the limiter switched off so the render path
+    /// passes samples through as-is
+    fn shared_with(ring: SpscRing, sample_rate: u32, channels: u16) -> StreamShared {
+        let controls = Arc::new(ChainControls::new());
+        controls.set_limiter_ceiling(None);
+        let meter = Arc::new(SharedLevelMeter::new(channels));
+        let output_chain = OutputChain::new(controls, Equalizer::new(), 1.0, meter);
+        StreamShared {
+            ring_buffer: Arc::new(ring),
+            is_playing: Arc::new(AtomicBool::new(true)),
+            sample_count: Arc::new(AtomicU64::new(0)),
+            volume: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            output_taps: Arc::new(OutputTaps::new()),
+            output_chain,
+            callback_samples: Arc::new(AtomicUsize::new(0)),
+            sample_rate,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_output_tap_follows_playback_position() {
+        const WINDOW_FRAMES: usize = 256;
+        // Each stereo frame carries its own index, so tapped samples reveal where they were played
+        let ring = SpscRing::new(48000 * 2);
+        let ramp: Vec<f32> = (0..8000).flat_map(|frame| [frame as f32; 2]).collect();
+        ring.write(&ramp);
+
+        let mut shared = shared_with(ring, 48000, 2);
+        let (chunks, received) = std::sync::mpsc::channel();
+        shared
+            .output_taps
+            .add(Box::new(move |chunk| chunks.send(chunk.clone()).unwrap()))
+            .unwrap();
+
+        let mut samples = vec![0.0; WINDOW_FRAMES * 2];
+        for _ in 0..10 {
+            shared.render(&mut samples);
+            let chunk = received.recv_timeout(std::time::Duration::from_secs(1)).unwrap();
+            let playing_at = shared.sample_count.load(Ordering::Relaxed);
+
+            assert_eq!(chunk.samples[..], samples[..]);
+            assert_eq!(chunk.samples[0], chunk.position_frames as f32);
+            assert!(playing_at - chunk.position_frames <= WINDOW_FRAMES as u64);
+        }
+        assert_eq!(shared.output_taps.dropped_chunks(), 0);
+
+        // Paused output is silence at a standing position: nothing to visualize
+        shared.is_playing.store(false, Ordering::SeqCst);
+        shared.render(&mut samples);
+        assert!(received.recv_timeout(std::time::Duration::from_millis(100)).is_err());
+    }
+
+    #[test]
+    fn test_test_tone_renders_at_its_frequency() {
+        use crate::test_tone::{tests::dominant_frequency, TestTone};
+
+        // The tone goes through the same decode path as load_test_tone, then the output callback
+        let tone = TestTone::new(440.0, 0.5, 500, 48000, 2).unwrap();
+        let (_, decoded) = AudioDecoder::from_buffer(tone.to_wav()).unwrap().decode_all().unwrap();
+        let ring = SpscRing::new(decoded.len() * 2);
+        assert_eq!(ring.write(&decoded), decoded.len());
+
+        let mut shared = shared_with(ring, 48000, 2);
+        let mut rendered = Vec::new();
+        let mut window = vec![0.0; 512 * 2];
+        for _ in 0..(tone.frames() as usize / 512) {
+            shared.render(&mut window);
+            rendered.extend_from_slice(&window);
+        }
+        assert_eq!(dominant_frequency(&rendered, 2, 48000), 440.0);
+        let peak = rendered.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.01, "peak {}", peak);
+    }
+
+    #[test]
+    fn test_duck_attenuates_until_unduck() {
+        let ring = SpscRing::new(48000 * 2);
+        ring.write(&vec![0.5; 48000 * 2]);
+        let mut shared = shared_with(ring, 48000, 2);
+        shared.volume.store(0.8f32.to_bits(), Ordering::Relaxed);
+        let mut callback = vec![0.0; 480 * 2];
+
+        // 10 ms fade: the first callback glides down, the next sits at volume x duck level
+        shared.output_chain.controls.duck.put((0.25, 10));
+        shared.render(&mut callback);
+        assert!(callback[0] > 0.39 && callback[callback.len() - 1] < 0.11);
+        shared.render(&mut callback);
+        assert!(callback.iter().all(|&s| (s - 0.1).abs() < 1e-6));
+
+        // Unducking restores the volume, not full scale
+        shared.output_chain.controls.duck.put((1.0, 10));
+        shared.render(&mut callback);
+        shared.render(&mut callback);
+        assert!(callback.iter().all(|&s| (s - 0.4).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_render_is_unaffected_by_concurrent_player_changes() {
+        const CALLBACKS: usize = 2000;
+        let ring = SpscRing::new(CALLBACKS * 480 * 2 + 1);
+        ring.write(&vec![0.5; CALLBACKS * 480 * 2]);
+        let mut shared = shared_with(ring, 48000, 2);
+        let controls = shared.output_chain.controls.clone();
+        let meter = shared.output_chain.meter.clone();
+        controls.duck.put((0.5, 0));
+
+        // The player re-publishes settings, reads levels and holds the ring's writer side nonstop
+        let done = Arc::new(AtomicBool::new(false));
+        let player = {
+            let (done, ring) = (done.clone(), shared.ring_buffer.clone());
+            thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    controls.duck.put((0.5, 0));
+                    controls.equalizer.put(Equalizer::new());
+                    controls.set_limiter_ceiling(None);
+                    meter.take_levels();
+                    ring.fit_callback(0);
+                }
+            })
+        };
+
+        // Every callback is read in full and ducked: nothing is skipped or underrun
+        let mut callback = vec![0.0; 480 * 2];
+        for _ in 0..CALLBACKS {
+            shared.render(&mut callback);
+            assert!(callback.iter().all(|&s| s == 0.25));
+        }
+        assert_eq!(shared.sample_count.load(Ordering::Relaxed), (CALLBACKS * 480) as u64);
+        done.store(true, Ordering::Relaxed);
+        player.join().unwrap();
+    }
+
+    #[test]
+    fn test_ring_grows_to_fit_large_callbacks() {
+        use crate::decoder::MIN_RING_CALLBACKS;
+
+        const CALLBACK_SAMPLES: usize = 4096 * 2;
+        let mut shared = shared_with(SpscRing::new(1024), 48000, 2);
+        let packet = vec![0.25; 1152 * 2];
+        // One decode thread pass: fit the ring to the callback seen so far, then top it up
+        let decode_pass = |shared: &StreamShared| {
+            let ring = &shared.ring_buffer;
+            ring.fit_callback(shared.callback_samples.load(Ordering::Relaxed));
+            while ring.write(&packet) > 0 {}
+        };
+
+        // The first callback outgrows the ring and is only partly filled
+        decode_pass(&shared);
+        let mut callback = vec![0.0; CALLBACK_SAMPLES];
+        shared.render(&mut callback);
+        assert!(callback.contains(&0.0));
+        assert_eq!(shared.callback_samples.load(Ordering::Relaxed), CALLBACK_SAMPLES);
+
+        // After the decode thread grows the ring, every callback is filled in full
+        for _ in 0..10 {
+            decode_pass(&shared);
+            assert!(shared.ring_buffer.capacity() >= CALLBACK_SAMPLES * MIN_RING_CALLBACKS);
+            shared.render(&mut callback);
+            assert!(callback.iter().all(|&s| s == 0.25));
+        }
+    }
+
+    #[test]
+    fn test_fixed_channel_device_gets_remixed_audio() {
+        let device = |channels| {
+            [SupportedStreamConfigRange::new(
+                channels,
+                SampleRate(8000),
+                SampleRate(96000),
+                cpal::SupportedBufferSize::Unknown,
+                SampleFormat::F32,
+            )]
+        };
+
+        // Stereo source on a 6-channel interface: front pair carries the audio
+        let config = DesktopAudioPlayer::choose_stream_config(&device(6), 44100, 2);
+        assert_eq!((config.channels, config.sample_rate.0), (6, 44100));
+        let remixed = DesktopAudioPlayer::remix_to_output(vec![0.1, 0.2, 0.3, 0.4], 2, config.channels);
+        assert_eq!(remixed, vec![0.1, 0.2, 0.0, 0.0, 0.0, 0.0, 0.3, 0.4, 0.0, 0.0, 0.0, 0.0]);
+
+        // The callback plays it back as two 6-channel frames
+        let ring = SpscRing::new(64);
+        ring.write(&remixed);
+        let mut shared = shared_with(ring, config.sample_rate.0, config.channels);
+        let mut samples = [0.0; 12];
+        shared.render(&mut samples);
+        assert_eq!(samples[..], remixed[..]);
+        assert_eq!(shared.sample_count.load(Ordering::Relaxed), 2);
+
+        // Mono source on a stereo-only device
+        let config = DesktopAudioPlayer::choose_stream_config(&device(2), 22050, 1);
+        assert_eq!(config.channels, 2);
+        assert_eq!(DesktopAudioPlayer::remix_to_output(vec![0.5], 1, 2), vec![0.5, 0.5]);
+
+        // Stereo source on a mono-only device
+        let config = DesktopAudioPlayer::choose_stream_config(&device(1), 48000, 2);
+        assert_eq!(config.channels, 1);
+        assert_eq!(DesktopAudioPlayer::remix_to_output(vec![0.25, 0.75], 2, 1), vec![0.5]);
+    }
+
+    #[test]
+    fn test_output_config_matches_stream_config() {
+        let mut player = headless_player();
+        assert_eq!(player.output_sample_rate(), 0);
+        assert_eq!(player.output_channels(), 0);
+
+        let config = player.pick_stream_config(player.device.as_ref().unwrap(), 44100, 2);
+        player.initialize_audio_stream(44100, 2).unwrap();
+
+        assert_eq!(player.output_sample_rate(), config.sample_rate.0);
+        assert_eq!(player.output_channels(), config.channels);
+
+        player.release().unwrap();
+        assert_eq!(player.output_sample_rate(), 0);
+    }
+
+    #[test]
+    fn test_target_sample_rate_fixes_output_rate() {
+        use crate::decoder::tests::test_wav;
+
+        let device = [SupportedStreamConfigRange::new(
+            2,
+            SampleRate(8000),
+            SampleRate(96000),
+            cpal::SupportedBufferSize::Unknown,
+            SampleFormat::F32,
+        )];
+        for source_rate in [44100, 48000] {
+            let mut decoder = AudioDecoder::from_buffer(test_wav(source_rate, 2, 1)).unwrap();
+            let requested = requested_stream_rate(decoder.format.sample_rate, Some(48000));
+            let config = DesktopAudioPlayer::choose_stream_config(&device, requested, 2);
+            assert_eq!(config.sample_rate.0, 48000);
+
+            // What the decode loop writes to the ring buffer: one second at the stream rate
+            let mut frames = 0;
+            while let Some(samples) = decoder.decode_next().unwrap() {
+                let output = if source_rate != config.sample_rate.0 {
+                    DesktopAudioPlayer::resample_linear(&samples, source_rate, config.sample_rate.0, 2)
+                } else {
+                    samples
+                };
+                frames += output.len() / 2;
+            }
+            // Per-packet rounding loses at most a frame per packet
+            assert!(frames.abs_diff(48000) <= 48, "{} Hz source gave {} frames", source_rate, frames);
+        }
+        assert_eq!(requested_stream_rate(44100, None), 44100);
+    }
+
+    #[test]
+    fn test_target_sample_rate_fixes_device_output_rate() {
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        assert!(player.set_target_sample_rate(Some(0)).is_err());
+        player.set_target_sample_rate(Some(48000)).unwrap();
+        // A device that cannot run at 48 kHz opens at its closest rate
+        let expected = player.pick_stream_config(player.device.as_ref().unwrap(), 48000, 2).sample_rate.0;
+        for source_rate in [44100, 48000] {
+            player.load_buffer(&test_wav(source_rate, 2, 1)).unwrap();
+            assert_eq!(player.output_sample_rate(), expected);
+        }
+    }
+
+    #[test]
+    fn test_fixed_latency_is_stable() {
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        assert!(player.set_latency_mode(LatencyMode::Fixed(0)).is_err());
+        player.set_latency_mode(LatencyMode::Fixed(200)).unwrap();
+        player.load_buffer(&test_wav(48000, 2, 5)).unwrap();
+        let fixed = (*player.fixed_latency.lock()).unwrap();
+        let channels = player.output_channels();
+        assert_eq!(player.ring_buffer.size(), fixed.ring_samples(channels));
+        // Prebuffering fills the ring to its full depth, less the slot a full ring keeps free
+        assert_eq!(player.ring_buffer.available_read(), fixed.ring_samples(channels) - 1);
+
+        let configured = player.output_latency_ms();
+        assert!(configured.abs_diff(200) <= 1, "{} ms", configured);
+        player.play().unwrap();
+        for _ in 0..10 {
+            thread::sleep(std::time::Duration::from_millis(30));
+            assert_eq!(player.output_latency_ms(), configured);
+        }
+        player.stop().unwrap();
+    }
+
+    #[test]
+    fn test_flush_refills_from_same_position() {
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        player.load_buffer(&test_wav(44100, 2, 2)).unwrap();
+        player.seek(500).unwrap();
+        player.prebuffer().unwrap();
+
+        let buffered = player.ring_buffer.available_read();
+        assert!(buffered > 0);
+        let mut before = vec![0.0; buffered];
+        player.ring_buffer.read(&mut before);
+        player.ring_buffer.write(&before);
+
+        player.flush().unwrap();
+
+        let refilled = player.ring_buffer.available_read();
+        assert!(refilled > 0);
+        let mut after = vec![0.0; refilled];
+        player.ring_buffer.read(&mut after);
+
+        let compared = before.len().min(after.len());
+        assert_eq!(&before[..compared], &after[..compared]);
+        assert_eq!(player.get_status().position_ms, 500);
+        assert_eq!(player.get_state(), PlayerState::Ready);
+    }
+
+    #[test]
+    fn test_start_position_is_the_first_reported_position() {
+        use crate::callback::TestCallback;
+        use crate::decoder::tests::test_wav;
+        use std::time::Duration;
+
+        let mut player = headless_player();
+        let callback = Arc::new(TestCallback::new());
+        player.set_callback(Some(callback.clone()));
+        player.set_start_position(1500).unwrap();
+        player.load_buffer(&test_wav(44100, 2, 3)).unwrap();
+        assert_eq!(player.get_status().position_ms, 1500);
+        player.play().unwrap();
+        std::thread::sleep(Duration::from_millis(300));
+        player.stop().unwrap();
+        assert!(player.callback_manager.flush(Duration::from_secs(1)));
+
+        let positions: Vec<u64> = callback
+            .get_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CallbackEvent::PositionChanged { position_ms, .. } => Some(position_ms),
+                _ => None,
+            })
+            .collect();
+        assert!(positions.first().is_some_and(|&first| first >= 1500), "{:?}", positions);
+
+        // The start applies to one load only
+        player.load_buffer(&test_wav(44100, 2, 3)).unwrap();
+        assert_eq!(player.get_status().position_ms, 0);
+    }
+
+    #[test]
+    fn test_load_phases_are_timed_in_order() {
+        use crate::callback::TestCallback;
+        use crate::decoder::tests::test_wav;
+        use crate::diagnostics::LoadPhase;
+        use std::time::Duration;
+
+        let mut player = headless_player();
+        let path = std::env::temp_dir().join(format!("podium-phases-{}.wav", std::process::id()));
+        std::fs::write(&path, test_wav(44100, 2, 2)).unwrap();
+        let callback = Arc::new(TestCallback::new());
+        player.set_callback(Some(callback.clone()));
+        let loaded = player.load_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        loaded.unwrap();
+        assert!(player.callback_manager.flush(Duration::from_secs(1)));
+
+        let phases: Vec<(LoadPhase, u64)> = callback
+            .get_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CallbackEvent::Diagnostics { phase, duration_ms } => Some((phase, duration_ms)),
+                _ => None,
+            })
+            .collect();
+        let order: Vec<LoadPhase> = phases.iter().map(|&(phase, _)| phase).collect();
+        assert_eq!(
+            order,
+            [LoadPhase::Probe, LoadPhase::StreamBuild, LoadPhase::FirstPacket, LoadPhase::Prebuffer]
+        );
+        // A local WAV loads in well under the time anyone would notice
+        assert!(phases.iter().all(|&(_, duration_ms)| duration_ms < 5_000), "{:?}", phases);
+
+        let stats = player.get_stats();
+        assert_eq!(stats.phases.first_packet_ms, Some(phases[2].1));
+        assert!(stats.phases.first_packet_ms <= stats.phases.prebuffer_ms);
+        let first_audio = stats.time_to_first_audio_ms.unwrap();
+        assert!(first_audio >= phases[0].1 + phases[1].1 && first_audio < 5_000, "{:?}", stats);
+
+        player.seek(500).unwrap();
+        assert!(player.get_stats().phases.last_seek_ms.is_some());
+    }
+
+    #[test]
+    fn test_volume_and_rate_need_a_loaded_source() {
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        assert!(matches!(player.set_volume(0.5), Err(AudioError::InvalidState(_))));
+        assert!(matches!(player.set_playback_rate(1.5), Err(AudioError::InvalidState(_))));
+        player.load_buffer(&test_wav(44100, 2, 1)).unwrap();
+        player.set_volume(0.5).unwrap();
+        player.set_playback_rate(1.5).unwrap();
+
+        player.release().unwrap();
+        assert!(matches!(player.set_volume(0.5), Err(AudioError::InvalidState(_))));
+        assert!(matches!(player.set_playback_rate(1.0), Err(AudioError::InvalidState(_))));
+    }
+
+    #[test]
+    fn test_chapter_navigation_lands_on_chapter_starts() {
+        use crate::decoder::tests::chaptered_wav;
+
+        let mut player = headless_player();
+        assert!(player.next_chapter().is_err());
+        let chaptered = chaptered_wav(10, &[(0, 3000, "One"), (3000, 7000, "Two"), (7000, 10_000, "Three")]);
+        player.load_buffer(&chaptered).unwrap();
+        assert_eq!(player.chapters().len(), 3);
+
+        player.seek_to_chapter(1).unwrap();
+        assert_eq!(player.get_status().position_ms, 3000);
+        assert_eq!(player.next_chapter().unwrap(), 2);
+        assert_eq!(player.get_status().position_ms, 7000);
+        assert!(player.next_chapter().is_err());
+
+        // Well into a chapter: back to its start; right at the start: to the chapter before
+        player.seek(6500).unwrap();
+        assert_eq!(player.previous_chapter().unwrap(), 1);
+        assert_eq!(player.get_status().position_ms, 3000);
+        assert_eq!(player.previous_chapter().unwrap(), 0);
+        assert_eq!(player.get_status().position_ms, 0);
+        player.seek(8500).unwrap();
+        assert_eq!(player.previous_chapter().unwrap(), 1);
+        player.seek(1000).unwrap();
+        assert_eq!(player.previous_chapter().unwrap(), 0);
+        assert_eq!(player.get_status().position_ms, 0);
+
+        assert!(player.seek_to_chapter(3).is_err());
+    }
+
+    #[test]
+    fn test_load_emits_prepared_once() {
+        use crate::callback::TestCallback;
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        let callback = Arc::new(TestCallback::new());
+        player.set_callback(Some(callback.clone()));
+        player.load_buffer(&test_wav(22050, 1, 1)).unwrap();
+        assert!(player.callback_manager.flush(std::time::Duration::from_secs(1)));
+
+        let formats: Vec<crate::metadata::FormatInfo> = callback
+            .get_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CallbackEvent::Prepared { format } => Some(format),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(formats.len(), 1);
+        assert_eq!(formats[0].sample_rate, 22050);
+        assert_eq!(formats[0].channels, 1);
+    }
+
+    #[test]
+    fn test_prebuffer_complete_follows_prepared() {
+        use crate::callback::TestCallback;
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        let callback = Arc::new(TestCallback::new());
+        player.set_callback(Some(callback.clone()));
+        player.load_buffer(&test_wav(44100, 2, 2)).unwrap();
+        assert!(player.callback_manager.flush(std::time::Duration::from_secs(1)));
+
+        let events = callback.get_events();
+        let prepared = events.iter().position(|e| matches!(e, CallbackEvent::Prepared { .. }));
+        let complete = events.iter().position(|e| matches!(e, CallbackEvent::PrebufferComplete { .. }));
+        assert!(prepared.unwrap() < complete.unwrap());
+        let CallbackEvent::PrebufferComplete { buffered_ms } = events[complete.unwrap()] else {
+            unreachable!()
+        };
+        assert!(buffered_ms >= PRE_BUFFER_MS, "{} ms", buffered_ms);
+        assert!(player.ring_buffer.available_read() > 0);
+    }
+
+    #[test]
+    fn test_stats_report_decode_throughput_after_prebuffer() {
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        assert_eq!(player.get_stats(), PlaybackStats::default());
+        player.load_buffer(&test_wav(44100, 2, 2)).unwrap();
+        let stats = player.get_stats();
+        assert!(stats.decoded_frames > 0);
+        assert!(stats.decode_throughput_frames_per_sec() > 0.0);
+    }
+
+    #[test]
+    fn test_release_mid_playback_delivers_stopped() {
+        use crate::callback::TestCallback;
+        use crate::decoder::tests::test_wav;
+        use std::time::{Duration, Instant};
+
+        let mut player = headless_player();
+        let callback = Arc::new(TestCallback::new());
+        player.set_callback(Some(callback.clone()));
+        player.load_buffer(&test_wav(44100, 2, 5)).unwrap();
+        player.play().unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+
+        let start = Instant::now();
+        let _ = player.release();
+        assert!(start.elapsed() < RELEASE_JOIN_TIMEOUT + Duration::from_secs(1));
+
+        // The last event is the transition out of Playing; nothing arrives after release
+        let events = callback.get_events();
+        assert!(matches!(
+            events.last(),
+            Some(CallbackEvent::StateChanged {
+                old_state: PlayerState::Playing,
+                new_state: PlayerState::Stopped
+            })
+        ));
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(callback.get_events().len(), events.len());
+    }
+
+    #[test]
+    fn test_player_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<DesktopAudioPlayer>();
+    }
+
+    #[test]
+    fn test_transport_commands_from_several_threads() {
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        player.load_buffer(&test_wav(44100, 2, 5)).unwrap();
+        let player = Arc::new(Mutex::new(player));
+
+        // Each thread drives the stream; commands that do not fit the state are refused, not fatal
+        let workers: Vec<_> = (0..3)
+            .map(|i| {
+                let player = player.clone();
+                thread::spawn(move || {
+                    for _ in 0..5 {
+                        let mut player = player.lock();
+                        let _ = match i {
+                            0 => player.play(),
+                            1 => player.pause(),
+                            _ => player.stop(),
+                        };
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        // The stream still answers from yet another thread (reloading first if a stop came last)
+        thread::spawn(move || {
+            let mut player = player.lock();
+            if player.get_state() == PlayerState::Stopped {
+                player.load_buffer(&test_wav(44100, 2, 5)).unwrap();
+            }
+            player.play().unwrap();
+            assert_eq!(player.get_state(), PlayerState::Playing);
+            player.pause().unwrap();
+            player.stop().unwrap();
+            player.release().unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_wait_until_complete_returns_after_short_file() {
+        use crate::decoder::tests::test_wav;
+        use std::time::{Duration, Instant};
+
+        let mut player = headless_player();
+        let callback = Arc::new(crate::callback::TestCallback::new());
+        player.set_callback(Some(callback.clone()));
+        // A fifth of a second of audio
+        let mut wav = test_wav(8000, 1, 1);
+        wav.truncate(44 + 1600 * 2);
+        wav[4..8].copy_from_slice(&(36 + 3200u32).to_le_bytes());
+        wav[40..44].copy_from_slice(&3200u32.to_le_bytes());
+        player.load_buffer(&wav).unwrap();
+
+        let start = Instant::now();
+        player.play().unwrap();
+        player.wait_until_complete(Some(Duration::from_secs(5))).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(player.get_state(), PlayerState::Stopped);
+        // Registered callbacks still saw the completion
+        assert!(player.callback_manager.flush(Duration::from_secs(1)));
+        assert!(callback.get_events().iter().any(|e| matches!(e, CallbackEvent::PlaybackCompleted)));
+    }
+
+    #[test]
+    fn test_data_uri_plays_from_memory() {
+        use crate::data_uri::tests::encode_base64;
+        use crate::decoder::tests::test_wav;
+
+        let mut player = headless_player();
+        let uri = format!("data:audio/wav;base64,{}", encode_base64(&test_wav(22050, 2, 1)));
+        player.load_url(&uri).unwrap();
+        assert!(matches!(player.current_source, Some(AudioSource::Buffer(_))));
+        assert_eq!(player.get_status().duration_ms, 1000);
+
+        player.play().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        assert_eq!(player.get_state(), PlayerState::Playing);
+        assert!(player.get_status().position_ms > 0);
+        player.stop().unwrap();
+    }
+
+    #[test]
+    fn test_size_reported_after_load_refreshes_duration() {
+        use crate::callback::TestCallback;
+        use crate::decoder::tests::concatenated_mp3;
+        use crate::http_transport::tests::{install, MockTransport};
+
+        // 320 kbps MPEG-1 mono at 32 kHz with no Xing header: the length is estimated from the size
+        let mp3 = concatenated_mp3(&[([0xFF, 0xFB, 0xE8, 0xC0], 1440, 556)]);
+        let mut hint = Hint::new();
+        hint.with_extension("mp3");
+        let expected_ms = AudioDecoder::from_buffer_with_hint(mp3.clone(), hint).unwrap().format.duration_ms;
+        assert!(expected_ms > 19_000, "{} ms", expected_ms);
+
+        // The server does not know the size yet when the episode is loaded
+        let transport = MockTransport::new(mp3, true);
+        transport.hide_size(true);
+        let _installed = install(Some(transport.clone()));
+        let mut player = headless_player();
+        let callback = Arc::new(TestCallback::new());
+        player.set_callback(Some(callback.clone()));
+        player.load_url("http://example.com/late-size.mp3").unwrap();
+        assert_eq!(player.get_status().duration_ms, 0);
+
+        // Later replies carry it; the seek fetches past the first chunk
+        transport.hide_size(false);
+        player.seek(15_000).unwrap();
+        player.play().unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let changes = loop {
+            let changes: Vec<u64> = callback
+                .get_events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    CallbackEvent::DurationChanged { duration_ms } => Some(duration_ms),
+                    _ => None,
+                })
+                .collect();
+            if !changes.is_empty() || std::time::Instant::now() > deadline {
+                break changes;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+        player.stop().unwrap();
+
+        assert_eq!(changes, [expected_ms]);
+        assert_eq!(player.get_status().duration_ms, expected_ms);
+    }
+
+    #[test]
+    fn test_url_load_reports_stream_stages_in_order() {
+        use crate::callback::TestCallback;
+        use crate::decoder::tests::test_wav;
+        use crate::http_range_source::StreamStage;
+        use crate::http_transport::tests::{install, MockTransport};
+
+        let _installed = install(Some(MockTransport::new(test_wav(8000, 1, 1), true)));
+        let mut player = headless_player();
+        let callback = Arc::new(TestCallback::new());
+        player.set_callback(Some(callback.clone()));
+        player.load_url("http://example.com/stages.wav").unwrap();
+        assert!(player.callback_manager.flush(std::time::Duration::from_secs(1)));
+
+        // Each stage once, all before Prepared (None here)
+        let stages: Vec<Option<StreamStage>> = callback
+            .get_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CallbackEvent::StreamPreparing { stage } => Some(Some(stage)),
+                CallbackEvent::Prepared { .. } => Some(None),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            stages,
+            [
+                Some(StreamStage::FetchingHeader),
+                Some(StreamStage::FetchingMoov),
+                Some(StreamStage::Ready),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scrub_preview_keeps_main_position() {
+        use crate::decoder::tests::test_wav;
+        use std::time::{Duration, Instant};
+
+        let mut player = headless_player();
+        player.load_buffer(&test_wav(44100, 2, 2)).unwrap();
+        player.seek(500).unwrap();
+        player.prebuffer().unwrap();
+        let buffered = player.ring_buffer.available_read();
+
+        player.scrub_preview(1500, 50).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while player.chain_controls.scrub_active.load(Ordering::Relaxed) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(!player.chain_controls.scrub_active.load(Ordering::Relaxed));
+
+        assert_eq!(player.get_status().position_ms, 500);
+        assert_eq!(player.ring_buffer.available_read(), buffered);
+        assert_eq!(player.get_state(), PlayerState::Ready);
+    }
+}
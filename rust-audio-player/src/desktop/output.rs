@@ -0,0 +1,135 @@
+// Output device the desktop player opens its streams on
+// The player needs only two things from a device: the stream configs it supports and a stream
+// that pulls audio through `StreamShared::render`. Keeping both behind `OutputDevice` lets the
+// load, seek and transport logic run in tests on a headless device, without a sound card.
+
+use cpal::traits::DeviceTrait;
+use cpal::{Device, SupportedStreamConfigRange, SupportedStreamConfigsError};
+
+/// Device the player's output streams are opened on
+pub(super) enum OutputDevice {
+    Cpal(Device),
+    /// No hardware: reports the given configs and renders on a timer (see `HeadlessStream`)
+    #[cfg(test)]
+    Headless(Vec<SupportedStreamConfigRange>),
+}
+
+impl OutputDevice {
+    /// Stream configs the device can open
+    pub(super) fn supported_output_configs(
+        &self,
+    ) -> std::result::Result<Vec<SupportedStreamConfigRange>, SupportedStreamConfigsError> {
+        match self {
+            OutputDevice::Cpal(device) => Ok(device.supported_output_configs()?.collect()),
+            #[cfg(test)]
+            OutputDevice::Headless(configs) => Ok(configs.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(super) use headless::HeadlessStream;
+
+#[cfg(test)]
+mod headless {
+    use super::*;
+    use crate::desktop::StreamShared;
+    use cpal::traits::StreamTrait;
+    use cpal::{PauseStreamError, PlayStreamError, SampleFormat, SampleRate, StreamConfig, SupportedBufferSize};
+    use parking_lot::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// Frames per callback when the stream config leaves the buffer size to the device
+    const DEFAULT_CALLBACK_FRAMES: u32 = 512;
+
+    impl OutputDevice {
+        /// Mono and stereo f32 at any common rate, with a settable buffer size
+        pub(in crate::desktop) fn headless() -> Self {
+            let range = |channels| {
+                SupportedStreamConfigRange::new(
+                    channels,
+                    SampleRate(8000),
+                    SampleRate(96000),
+                    SupportedBufferSize::Range { min: 64, max: 8192 },
+                    SampleFormat::F32,
+                )
+            };
+            OutputDevice::Headless(vec![range(1), range(2)])
+        }
+    }
+
+    /// Stands in for a device stream: while playing, a thread renders one callback per period
+    /// in real time and discards the audio
+    pub(in crate::desktop) struct HeadlessStream {
+        callback_frames: u32,
+        channels: u16,
+        period: Duration,
+        playing: Arc<AtomicBool>,
+        /// The renderer while paused; the pump thread owns it while playing and hands it back
+        idle: Mutex<Option<StreamShared>>,
+        pump: Mutex<Option<thread::JoinHandle<StreamShared>>>,
+    }
+
+    impl HeadlessStream {
+        pub(in crate::desktop) fn new(config: &StreamConfig, shared: StreamShared) -> Self {
+            let callback_frames = match config.buffer_size {
+                cpal::BufferSize::Fixed(frames) => frames,
+                cpal::BufferSize::Default => DEFAULT_CALLBACK_FRAMES,
+            };
+            Self {
+                callback_frames,
+                channels: shared.channels,
+                period: Duration::from_secs_f64(callback_frames as f64 / shared.sample_rate as f64),
+                playing: Arc::new(AtomicBool::new(false)),
+                idle: Mutex::new(Some(shared)),
+                pump: Mutex::new(None),
+            }
+        }
+    }
+
+    impl StreamTrait for HeadlessStream {
+        fn play(&self) -> std::result::Result<(), PlayStreamError> {
+            let mut pump = self.pump.lock();
+            if pump.is_some() {
+                return Ok(());
+            }
+            let Some(mut shared) = self.idle.lock().take() else {
+                return Ok(());
+            };
+            self.playing.store(true, Ordering::Relaxed);
+
+            let playing = self.playing.clone();
+            let period = self.period;
+            let mut samples = vec![0.0; self.callback_frames as usize * self.channels as usize];
+            *pump = Some(thread::spawn(move || {
+                let mut next = Instant::now();
+                while playing.load(Ordering::Relaxed) {
+                    shared.render(&mut samples);
+                    next += period;
+                    thread::sleep(next.saturating_duration_since(Instant::now()));
+                }
+                shared
+            }));
+            Ok(())
+        }
+
+        fn pause(&self) -> std::result::Result<(), PauseStreamError> {
+            self.playing.store(false, Ordering::Relaxed);
+            if let Some(pump) = self.pump.lock().take() {
+                if let Ok(shared) = pump.join() {
+                    *self.idle.lock() = Some(shared);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for HeadlessStream {
+        fn drop(&mut self) {
+            let _ = self.pause();
+        }
+    }
+}
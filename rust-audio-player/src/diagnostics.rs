@@ -0,0 +1,152 @@
+// Load and seek phase timings
+// Each phase of a load (probe, stream build, first packet, pre-buffer) and each seek is timed
+// against the player's clock, reported as `CallbackEvent::Diagnostics` and kept for
+// `PlaybackStats`, so a slow start in the field shows which step took the time.
+
+use crate::callback::{CallbackEvent, CallbackManager};
+use std::time::Instant;
+
+/// A timed step of loading or seeking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadPhase {
+    /// Opening the source and probing its container and codec, timed from the load call (so
+    /// stopping the previous source counts too)
+    Probe,
+    /// Building (or reusing) the output stream for the source's format
+    StreamBuild,
+    /// Decoding the first packet of audio
+    FirstPacket,
+    /// Filling the ring buffer before playback can start (includes the first packet)
+    Prebuffer,
+    /// Repositioning the decoder
+    Seek,
+}
+
+impl LoadPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoadPhase::Probe => "probe",
+            LoadPhase::StreamBuild => "stream-build",
+            LoadPhase::FirstPacket => "first-packet",
+            LoadPhase::Prebuffer => "prebuffer",
+            LoadPhase::Seek => "seek",
+        }
+    }
+}
+
+/// Durations of the current source's load phases and of the last seek (None until they ran)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PhaseTimings {
+    pub probe_ms: Option<u64>,
+    pub stream_build_ms: Option<u64>,
+    pub first_packet_ms: Option<u64>,
+    pub prebuffer_ms: Option<u64>,
+    pub last_seek_ms: Option<u64>,
+}
+
+/// Records phase durations for one player
+#[derive(Debug, Default)]
+pub struct PhaseTimer {
+    timings: PhaseTimings,
+    load_started: Option<Instant>,
+    time_to_first_audio_ms: Option<u64>,
+}
+
+impl PhaseTimer {
+    /// Forget the previous source's timings; returns the load's start time
+    pub fn begin_load(&mut self, callbacks: &CallbackManager) -> Instant {
+        let now = callbacks.now();
+        *self = Self { load_started: Some(now), ..Self::default() };
+        now
+    }
+
+    /// Record `phase` as having run from `started` until now, and report it
+    pub fn finish(&mut self, phase: LoadPhase, started: Instant, callbacks: &CallbackManager) {
+        let now = callbacks.now();
+        let duration_ms = now.saturating_duration_since(started).as_millis() as u64;
+        log::debug!("{} took {} ms", phase.as_str(), duration_ms);
+
+        let slot = match phase {
+            LoadPhase::Probe => &mut self.timings.probe_ms,
+            LoadPhase::StreamBuild => &mut self.timings.stream_build_ms,
+            LoadPhase::FirstPacket => &mut self.timings.first_packet_ms,
+            LoadPhase::Prebuffer => &mut self.timings.prebuffer_ms,
+            LoadPhase::Seek => &mut self.timings.last_seek_ms,
+        };
+        *slot = Some(duration_ms);
+        if phase == LoadPhase::FirstPacket && self.time_to_first_audio_ms.is_none() {
+            self.time_to_first_audio_ms = self
+                .load_started
+                .map(|load_started| now.saturating_duration_since(load_started).as_millis() as u64);
+        }
+
+        callbacks.dispatch_event(CallbackEvent::Diagnostics { phase, duration_ms });
+    }
+
+    pub fn timings(&self) -> PhaseTimings {
+        self.timings
+    }
+
+    /// From the start of the load to the end of its first packet
+    pub fn time_to_first_audio_ms(&self) -> Option<u64> {
+        self.time_to_first_audio_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::TestCallback;
+    use crate::clock::TestClock;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_phases_are_reported_in_order_with_their_durations() {
+        let clock = Arc::new(TestClock::new());
+        let callbacks = CallbackManager::with_clock(clock.clone());
+        let events = Arc::new(TestCallback::new());
+        callbacks.add_callback(events.clone(), 0);
+        let mut timer = PhaseTimer::default();
+
+        let load_started = timer.begin_load(&callbacks);
+        clock.advance(Duration::from_millis(40));
+        timer.finish(LoadPhase::Probe, load_started, &callbacks);
+        let started = callbacks.now();
+        clock.advance(Duration::from_millis(15));
+        timer.finish(LoadPhase::StreamBuild, started, &callbacks);
+        let prebuffer_started = callbacks.now();
+        clock.advance(Duration::from_millis(5));
+        timer.finish(LoadPhase::FirstPacket, prebuffer_started, &callbacks);
+        clock.advance(Duration::from_millis(30));
+        timer.finish(LoadPhase::Prebuffer, prebuffer_started, &callbacks);
+
+        callbacks.flush(Duration::from_secs(1));
+        let reported: Vec<_> = events
+            .get_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CallbackEvent::Diagnostics { phase, duration_ms } => Some((phase, duration_ms)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                (LoadPhase::Probe, 40),
+                (LoadPhase::StreamBuild, 15),
+                (LoadPhase::FirstPacket, 5),
+                (LoadPhase::Prebuffer, 35),
+            ]
+        );
+        assert_eq!(timer.time_to_first_audio_ms(), Some(60));
+        assert_eq!(timer.timings().prebuffer_ms, Some(35));
+
+        // A seek is kept alongside; the next load starts from scratch
+        timer.finish(LoadPhase::Seek, callbacks.now(), &callbacks);
+        assert_eq!(timer.timings().last_seek_ms, Some(0));
+        timer.begin_load(&callbacks);
+        assert_eq!(timer.timings(), PhaseTimings::default());
+        assert_eq!(timer.time_to_first_audio_ms(), None);
+    }
+}
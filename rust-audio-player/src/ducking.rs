@@ -0,0 +1,97 @@
+// Ducking: temporarily lowering the main output while the app plays a short clip over it
+// (a notification, TTS). The duck gain is its own stage after volume, so the two compose and
+// unducking restores whatever volume is set; level changes fade linearly frame by frame.
+
+use crate::error::{AudioError, Result};
+
+/// Check a duck level: a gain in 0.0..=1.0 (clamped); NaN and infinities are rejected
+pub fn clamp_duck_level(level: f32) -> Result<f32> {
+    if !level.is_finite() {
+        return Err(AudioError::InvalidState(format!("Invalid duck level: {}", level)));
+    }
+    Ok(level.clamp(0.0, 1.0))
+}
+
+/// Gain the output callback applies on top of volume (1.0 when not ducked)
+#[derive(Debug, Clone, Copy)]
+pub struct DuckGain {
+    gain: f32,
+    target: f32,
+    /// Fade for the latest target; turned into a per-frame step once the output rate is known
+    fade_ms: Option<u64>,
+    step: f32,
+}
+
+impl Default for DuckGain {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            target: 1.0,
+            fade_ms: None,
+            step: 0.0,
+        }
+    }
+}
+
+impl DuckGain {
+    /// Fade from the gain currently applied to `level` over `fade_ms`
+    pub fn fade_to(&mut self, level: f32, fade_ms: u64) {
+        self.target = level;
+        self.fade_ms = Some(fade_ms);
+    }
+
+    /// Scale interleaved `samples`, advancing the fade by one step per frame
+    pub fn process(&mut self, samples: &mut [f32], channels: u16, sample_rate: u32) {
+        if let Some(fade_ms) = self.fade_ms.take() {
+            let frames = fade_ms * sample_rate as u64 / 1000;
+            self.step = if frames == 0 {
+                f32::INFINITY
+            } else {
+                (self.target - self.gain).abs() / frames as f32
+            };
+        }
+        if self.gain == 1.0 && self.target == 1.0 {
+            return;
+        }
+
+        for frame in samples.chunks_mut(channels.max(1) as usize) {
+            if self.gain != self.target {
+                self.gain = if self.gain < self.target {
+                    (self.gain + self.step).min(self.target)
+                } else {
+                    (self.gain - self.step).max(self.target)
+                };
+            }
+            for sample in frame {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duck_fades_down_and_back() {
+        let mut duck = DuckGain::default();
+        duck.fade_to(0.25, 10);
+
+        // 10 ms at 8 kHz is 80 frames: a monotonic glide, then the duck level
+        let mut samples = vec![1.0f32; 100 * 2];
+        duck.process(&mut samples, 2, 8000);
+        assert!(samples.chunks(2).all(|frame| frame[0] == frame[1]));
+        assert!(samples.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert!(samples[0] > 0.98);
+        assert!(samples[80 * 2..].iter().all(|&s| s == 0.25));
+
+        duck.fade_to(1.0, 0);
+        let mut restored = vec![0.5f32; 8];
+        duck.process(&mut restored, 2, 8000);
+        assert!(restored.iter().all(|&s| s == 0.5));
+
+        assert!(clamp_duck_level(f32::NAN).is_err());
+        assert_eq!(clamp_duck_level(1.5).unwrap(), 1.0);
+    }
+}
@@ -0,0 +1,223 @@
+// Output equalizer: peaking bands applied to every output channel separately
+// Each channel keeps its own filter state, so the bands are correct for any layout the stream
+// carries, stereo or 5.1 passed through without a downmix.
+
+use crate::error::{AudioError, Result};
+use std::f32::consts::PI;
+
+/// Most bands the equalizer takes
+pub const MAX_EQ_BANDS: usize = 10;
+
+/// Largest boost or cut of one band
+pub const MAX_EQ_GAIN_DB: f32 = 24.0;
+
+/// One peaking band
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EqBand {
+    /// Centre frequency
+    pub freq_hz: f32,
+    /// Boost (positive) or cut (negative) at the centre frequency
+    pub gain_db: f32,
+    /// Bandwidth: higher is narrower (0.707 is about two octaves)
+    pub q: f32,
+}
+
+impl EqBand {
+    fn validate(&self) -> Result<()> {
+        let valid = self.freq_hz.is_finite()
+            && self.freq_hz > 0.0
+            && self.gain_db.is_finite()
+            && self.gain_db.abs() <= MAX_EQ_GAIN_DB
+            && self.q.is_finite()
+            && self.q > 0.0;
+        if valid {
+            Ok(())
+        } else {
+            Err(AudioError::InvalidState(format!("Invalid EQ band: {:?}", self)))
+        }
+    }
+}
+
+/// Normalized biquad coefficients (RBJ peaking filter)
+#[derive(Debug, Clone, Copy)]
+struct Coefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Coefficients {
+    fn peaking(band: &EqBand, sample_rate: u32) -> Self {
+        // A band at or above Nyquist cannot be represented: leave the signal alone
+        if band.freq_hz >= sample_rate as f32 / 2.0 {
+            return Self { b0: 1.0, b1: 0.0, b2: 0.0, a1: 0.0, a2: 0.0 };
+        }
+        let a = 10f32.powf(band.gain_db / 40.0);
+        let w0 = 2.0 * PI * band.freq_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * band.q);
+        let cos_w0 = w0.cos();
+        let a0 = 1.0 + alpha / a;
+        Self {
+            b0: (1.0 + alpha * a) / a0,
+            b1: -2.0 * cos_w0 / a0,
+            b2: (1.0 - alpha * a) / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha / a) / a0,
+        }
+    }
+}
+
+/// Peaking equalizer for interleaved f32 audio with any channel count
+pub struct Equalizer {
+    bands: Vec<EqBand>,
+    coefficients: Vec<Coefficients>,
+    sample_rate: u32,
+    channels: usize,
+    /// Transposed direct form II state, `bands` entries per channel
+    state: Vec<[f32; 2]>,
+}
+
+impl Equalizer {
+    /// Flat (no bands)
+    pub fn new() -> Self {
+        Self { bands: Vec::new(), coefficients: Vec::new(), sample_rate: 0, channels: 0, state: Vec::new() }
+    }
+
+    /// Replace the bands; an empty list turns the equalizer off
+    pub fn set_bands(&mut self, bands: &[EqBand]) -> Result<()> {
+        if bands.len() > MAX_EQ_BANDS {
+            return Err(AudioError::InvalidState(format!(
+                "At most {} EQ bands, got {}",
+                MAX_EQ_BANDS,
+                bands.len()
+            )));
+        }
+        bands.iter().try_for_each(EqBand::validate)?;
+        self.bands = bands.to_vec();
+        // Coefficients follow on the next block
+        self.sample_rate = 0;
+        Ok(())
+    }
+
+    /// Forget the filter state (new stream)
+    #[cfg(any(target_os = "ios", target_os = "macos", test))]
+    pub fn reset(&mut self) {
+        self.sample_rate = 0;
+    }
+
+    /// Equalize `samples` in place, each of the `channels` through its own filters
+    pub fn process(&mut self, samples: &mut [f32], channels: u16, sample_rate: u32) {
+        if self.bands.is_empty() || channels == 0 || sample_rate == 0 {
+            return;
+        }
+        if sample_rate != self.sample_rate || channels as usize != self.channels {
+            self.configure(channels as usize, sample_rate);
+        }
+
+        let bands = self.coefficients.len();
+        for frame in samples.chunks_exact_mut(self.channels) {
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let state = &mut self.state[channel * bands..(channel + 1) * bands];
+                for (c, z) in self.coefficients.iter().zip(state) {
+                    let x = *sample;
+                    let y = c.b0 * x + z[0];
+                    z[0] = c.b1 * x - c.a1 * y + z[1];
+                    z[1] = c.b2 * x - c.a2 * y;
+                    *sample = y;
+                }
+            }
+        }
+    }
+
+    fn configure(&mut self, channels: usize, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+        self.coefficients = self.bands.iter().map(|band| Coefficients::peaking(band, sample_rate)).collect();
+        self.state = vec![[0.0; 2]; channels * self.bands.len()];
+    }
+}
+
+impl Default for Equalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level_meter::LevelMeter;
+
+    const RATE: u32 = 48000;
+
+    /// One second of 6-channel audio: a sine at `freqs[ch]` Hz and `levels[ch]` peak per channel
+    fn surround(freqs: [f32; 6], levels: [f32; 6]) -> Vec<f32> {
+        (0..RATE as usize)
+            .flat_map(|frame| {
+                let t = frame as f32 / RATE as f32;
+                (0..6).map(move |ch| levels[ch] * (2.0 * PI * freqs[ch] * t).sin())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_six_channels_are_metered_and_equalized_independently() {
+        let freqs = [1000.0, 1000.0, 100.0, 60.0, 1000.0, 5000.0];
+        let levels = [0.25, 0.5, 0.5, 0.0, 0.1, 0.2];
+        let mut samples = surround(freqs, levels);
+
+        let mut meter = LevelMeter::new();
+        meter.process(&samples, 6);
+        let before = meter.take_levels();
+        assert_eq!(before.peak.len(), 6);
+        for (ch, level) in levels.iter().enumerate() {
+            assert!((before.peak[ch] - level).abs() < 0.01, "{:?}", before.peak);
+            assert!((before.rms[ch] - level / 2f32.sqrt()).abs() < 0.01, "{:?}", before.rms);
+        }
+
+        // +12 dB at 1 kHz: only the 1 kHz channels rise; silence stays silent
+        let mut equalizer = Equalizer::new();
+        equalizer.set_bands(&[EqBand { freq_hz: 1000.0, gain_db: 12.0, q: 2.0 }]).unwrap();
+        equalizer.process(&mut samples, 6, RATE);
+        // Skip the filters' settling time
+        meter.process(&samples[RATE as usize / 2 * 6..], 6);
+        let after = meter.take_levels();
+        let boost = 10f32.powf(12.0 / 20.0);
+        for ch in [0, 1, 4] {
+            assert!((after.rms[ch] / before.rms[ch] - boost).abs() < 0.1, "channel {}: {:?}", ch, after.rms);
+        }
+        for ch in [2, 5] {
+            assert!((after.rms[ch] / before.rms[ch] - 1.0).abs() < 0.05, "channel {}: {:?}", ch, after.rms);
+        }
+        assert_eq!(after.peak[3], 0.0);
+
+        // An impulse on one channel rings only through that channel's filter
+        let mut impulse = vec![0.0; 512 * 6];
+        impulse[3] = 1.0;
+        equalizer.reset();
+        equalizer.process(&mut impulse, 6, RATE);
+        for (i, &sample) in impulse.iter().enumerate() {
+            if i % 6 != 3 {
+                assert_eq!(sample, 0.0, "leak into channel {}", i % 6);
+            }
+        }
+        assert!(impulse.iter().skip(6 + 3).step_by(6).any(|&s| s != 0.0));
+    }
+
+    #[test]
+    fn test_bands_are_validated() {
+        let mut equalizer = Equalizer::new();
+        let band = EqBand { freq_hz: 1000.0, gain_db: 6.0, q: 1.0 };
+        assert!(equalizer.set_bands(&[EqBand { q: 0.0, ..band }]).is_err());
+        assert!(equalizer.set_bands(&[EqBand { gain_db: 40.0, ..band }]).is_err());
+        assert!(equalizer.set_bands(&[band; MAX_EQ_BANDS + 1]).is_err());
+
+        // Above Nyquist the band is a pass-through
+        equalizer.set_bands(&[EqBand { freq_hz: 30_000.0, ..band }]).unwrap();
+        let mut samples = vec![0.5, -0.25, 0.125];
+        equalizer.process(&mut samples, 1, RATE);
+        assert_eq!(samples, [0.5, -0.25, 0.125]);
+    }
+}
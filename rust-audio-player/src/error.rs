@@ -39,6 +39,9 @@ pub enum AudioError {
     /// Network error (download/streaming)
     NetworkError(String),
 
+    /// Feature not available on this platform
+    Unsupported(String),
+
     /// Generic error
     Other(String),
 }
@@ -58,6 +61,7 @@ impl fmt::Display for AudioError {
             AudioError::IoError(msg) => write!(f, "IO error: {}", msg),
             AudioError::DecodingError(msg) => write!(f, "Decoding error: {}", msg),
             AudioError::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            AudioError::Unsupported(msg) => write!(f, "Not supported: {}", msg),
             AudioError::Other(msg) => write!(f, "Error: {}", msg),
         }
     }
@@ -65,6 +69,31 @@ impl fmt::Display for AudioError {
 
 impl std::error::Error for AudioError {}
 
+impl AudioError {
+    /// Return code for the C ABI and JNI, so hosts can tell failures apart
+    /// -1 other (and the generic failure), -2 initialization, -3 load, -4 playback, -5 invalid state,
+    /// -6 unsupported format, -7 device, -8 thread, -9 JNI, -10 IO, -11 decoding, -12 network,
+    /// -13 not supported on this platform
+    pub fn code(&self) -> i32 {
+        match self {
+            AudioError::Other(_) => -1,
+            AudioError::InitializationError(_) => -2,
+            AudioError::LoadError(_) => -3,
+            AudioError::PlaybackError(_) => -4,
+            AudioError::InvalidState(_) => -5,
+            AudioError::UnsupportedFormat(_) => -6,
+            AudioError::DeviceError(_) => -7,
+            AudioError::ThreadError(_) => -8,
+            #[cfg(target_os = "android")]
+            AudioError::JniError(_) => -9,
+            AudioError::IoError(_) => -10,
+            AudioError::DecodingError(_) => -11,
+            AudioError::NetworkError(_) => -12,
+            AudioError::Unsupported(_) => -13,
+        }
+    }
+}
+
 /// Result type alias for audio operations
 pub type Result<T> = std::result::Result<T, AudioError>;
 
@@ -0,0 +1,143 @@
+// Media source over a raw file descriptor
+// Android hands out Storage Access Framework `content://` documents and APK assets as file
+// descriptors rather than paths; an asset fd is the whole APK, with the asset at an offset.
+// The descriptor is dup'd so the app may close its own copy, and reads use pread so a shared file
+// offset (dup'd descriptors share one) is never disturbed.
+
+use crate::error::{AudioError, Result};
+use crate::stream_seek::resolve_seek;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::{FromRawFd, RawFd};
+use symphonia::core::io::MediaSource;
+
+/// Read-only window `start..start + len` of a file descriptor; the dup'd descriptor is closed on drop
+pub struct FdSource {
+    file: File,
+    start: u64,
+    len: u64,
+    position: u64,
+}
+
+impl FdSource {
+    /// Wrap a duplicate of `fd`, exposing `length` bytes from `start_offset`, or everything after
+    /// it when `length` is None (AssetFileDescriptor's UNKNOWN_LENGTH)
+    pub fn new(fd: RawFd, start_offset: u64, length: Option<u64>) -> Result<Self> {
+        // SAFETY: dup only reads the descriptor table; an invalid fd is reported as an error
+        let owned = unsafe { libc::dup(fd) };
+        if owned < 0 {
+            return Err(AudioError::LoadError(format!(
+                "Invalid file descriptor {}: {}",
+                fd,
+                std::io::Error::last_os_error()
+            )));
+        }
+        // SAFETY: `owned` is a fresh descriptor that nothing else refers to
+        let file = unsafe { File::from_raw_fd(owned) };
+
+        let size = file
+            .metadata()
+            .map_err(|e| AudioError::LoadError(format!("Failed to stat file descriptor: {}", e)))?
+            .len();
+        let available = size.checked_sub(start_offset).ok_or_else(|| {
+            AudioError::LoadError(format!("Offset {} is past the end of a {} byte file", start_offset, size))
+        })?;
+        let len = length.map_or(available, |length| length.min(available));
+
+        Ok(Self { file, start: start_offset, len, position: 0 })
+    }
+}
+
+impl Read for FdSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.position);
+        let want = (buf.len() as u64).min(remaining) as usize;
+        if want == 0 {
+            return Ok(0);
+        }
+        let read = self.file.read_at(&mut buf[..want], self.start + self.position)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for FdSource {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = resolve_seek(pos, self.position, Some(self.len))?;
+        Ok(self.position)
+    }
+}
+
+impl MediaSource for FdSource {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::tests::test_wav;
+    use crate::decoder::AudioDecoder;
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+    use symphonia::core::probe::Hint;
+
+    #[test]
+    fn test_decodes_a_window_of_a_descriptor() {
+        // A WAV embedded between unrelated bytes, like an asset inside an APK
+        let wav = test_wav(8000, 1, 1);
+        let path = std::env::temp_dir().join(format!("podium-fd-{}.bin", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(&[0xAB; 4096]).unwrap();
+        file.write_all(&wav).unwrap();
+        file.write_all(&[0xCD; 1000]).unwrap();
+        drop(file);
+
+        let file = File::open(&path).unwrap();
+        let source = FdSource::new(file.as_raw_fd(), 4096, Some(wav.len() as u64)).unwrap();
+        // The app can close its descriptor right away
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(source.byte_len(), Some(wav.len() as u64));
+        let owned = source.file.as_raw_fd();
+
+        let mut decoder = AudioDecoder::from_streaming_source(Box::new(source), Hint::new()).unwrap();
+        assert_eq!(decoder.format.sample_rate, 8000);
+        assert_eq!(decoder.format.duration_ms, 1000);
+        let (_, samples) = decoder.decode_all().unwrap();
+        // Mono upmixed to stereo; nothing read past the window
+        assert_eq!(samples.len(), 8000 * 2);
+
+        // Dropping the decoder closes the duplicate
+        drop(decoder);
+        // SAFETY: F_GETFD only queries the descriptor
+        assert_eq!(unsafe { libc::fcntl(owned, libc::F_GETFD) }, -1);
+    }
+
+    #[test]
+    fn test_window_is_clamped_and_bad_descriptors_fail() {
+        let path = std::env::temp_dir().join(format!("podium-fd-small-{}.bin", std::process::id()));
+        std::fs::write(&path, b"0123456789").unwrap();
+        let file = File::open(&path).unwrap();
+
+        let mut source = FdSource::new(file.as_raw_fd(), 4, None).unwrap();
+        let mut rest = String::new();
+        source.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "456789");
+        assert_eq!(source.seek(SeekFrom::End(-2)).unwrap(), 4);
+        source.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "45678989");
+
+        // A length past the end is cut to what the file has
+        assert_eq!(FdSource::new(file.as_raw_fd(), 8, Some(100)).unwrap().byte_len(), Some(2));
+        assert!(FdSource::new(file.as_raw_fd(), 11, None).is_err());
+        assert!(FdSource::new(-1, 0, None).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
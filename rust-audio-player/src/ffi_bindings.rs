@@ -1,9 +1,10 @@
 // C FFI bindings for iOS/macOS
 // Provides C-compatible interface to the audio player
 
+use crate::error::AudioError;
 use crate::player::AudioPlayer;
 use std::collections::HashMap;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 use std::sync::Mutex;
 
@@ -111,6 +112,36 @@ pub extern "C" fn rust_audio_player_load_url(player_id: i64, url: *const c_char)
     }
 }
 
+/// Load a generated sine tone (diagnostics: checks the output path without a media file)
+/// `amplitude` is the peak level, 0.0 to 1.0
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_load_test_tone(
+    player_id: i64,
+    freq_hz: f32,
+    amplitude: f32,
+    duration_ms: u32,
+    sample_rate: u32,
+    channels: u16,
+) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => {
+            match player.load_test_tone(freq_hz, amplitude, duration_ms as u64, sample_rate, channels) {
+                Ok(_) => 0,
+                Err(e) => {
+                    log::error!("Failed to load test tone: {}", e);
+                    -1
+                }
+            }
+        }
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
 /// Start or resume playback
 /// Returns: 0 on success, -1 on error
 #[no_mangle]
@@ -204,6 +235,541 @@ pub extern "C" fn rust_audio_player_seek(player_id: i64, position_ms: i64) -> i3
     }
 }
 
+/// Seek with a precision mode: 0 = accurate (as `rust_audio_player_seek`), 1 = coarse, starting at
+/// the nearest packet/keyframe for fast scrubbing
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_seek_with_mode(player_id: i64, position_ms: i64, mode: i32) -> i32 {
+    if position_ms < 0 {
+        log::error!("Invalid position: {}", position_ms);
+        return -1;
+    }
+    let mode = match mode {
+        0 => crate::SeekMode::Accurate,
+        1 => crate::SeekMode::Coarse,
+        _ => {
+            log::error!("Invalid seek mode: {}", mode);
+            return -1;
+        }
+    };
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.seek_with_mode(position_ms as u64, mode) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to seek: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Loop the loaded source sample-accurately from memory (`enabled` nonzero) or stop looping
+/// Returns: 0 on success, -1 on error (nothing loaded, source longer than 30 s)
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_looping(player_id: i64, enabled: i32) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.set_looping(enabled != 0) {
+            Ok(()) => 0,
+            Err(e) => {
+                log::error!("Failed to set looping: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Seek to the start of chapter `index`
+/// Returns: 0 on success, -1 on error (no chapters, index out of range)
+#[no_mangle]
+pub extern "C" fn rust_audio_player_seek_to_chapter(player_id: i64, index: i32) -> i32 {
+    if index < 0 {
+        log::error!("Invalid chapter index: {}", index);
+        return -1;
+    }
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.seek_to_chapter(index as usize) {
+            Ok(()) => 0,
+            Err(e) => {
+                log::error!("Failed to seek to chapter: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Skip to the next chapter (`forward` != 0) or back as `previous_chapter` does
+/// Returns: the chapter index now playing, or -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_skip_chapter(player_id: i64, forward: i32) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => {
+            let result = if forward != 0 { player.next_chapter() } else { player.previous_chapter() };
+            match result {
+                Ok(index) => index as i32,
+                Err(e) => {
+                    log::error!("Failed to skip chapter: {}", e);
+                    -1
+                }
+            }
+        }
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Set the User-Agent for every HTTP request made from now on; null or empty restores the default
+/// Returns: 0 on success, -1 on error
+///
+/// # Safety
+/// `user_agent` must be null or a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rust_audio_player_set_user_agent(user_agent: *const c_char) -> i32 {
+    if user_agent.is_null() {
+        crate::http_utils::set_user_agent(None);
+        return 0;
+    }
+
+    match CStr::from_ptr(user_agent).to_str() {
+        Ok(ua) => {
+            crate::http_utils::set_user_agent(Some(ua));
+            0
+        }
+        Err(e) => {
+            log::error!("Invalid UTF-8 in User-Agent: {}", e);
+            -1
+        }
+    }
+}
+
+/// Set the download chunk size for HTTP sources created from now on; 0 restores the defaults.
+/// `adaptive` != 0 lets the chunk grow on fast links and shrink after stalls.
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_http_chunk_size(chunk_size: usize, adaptive: i32) {
+    crate::http_transport::set_http_config(crate::HttpConfig {
+        chunk_size: (chunk_size > 0).then_some(chunk_size),
+        adaptive_chunks: adaptive != 0,
+        ..crate::http_transport::http_config()
+    });
+}
+
+/// Set the connect and read timeouts of HTTP sources created from now on, in milliseconds;
+/// 0 keeps the default for that timeout (30 s to connect, 60 s per read)
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_http_timeouts(connect_timeout_ms: u64, read_timeout_ms: u64) {
+    let timeout = |ms: u64| (ms > 0).then(|| std::time::Duration::from_millis(ms));
+    crate::http_transport::set_http_config(crate::HttpConfig {
+        connect_timeout: timeout(connect_timeout_ms),
+        read_timeout: timeout(read_timeout_ms),
+        ..crate::http_transport::http_config()
+    });
+}
+
+/// Bytes held by ring buffers, streaming buffers and caches of all players
+#[no_mangle]
+pub extern "C" fn rust_audio_player_memory_usage_bytes() -> u64 {
+    crate::memory_usage_bytes() as u64
+}
+
+/// Cap buffer memory for all players; 0 removes the cap
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_memory_budget(bytes: u64) {
+    crate::set_memory_budget((bytes > 0).then_some(bytes as usize));
+}
+
+/// Set the output bit depth: 0=float (default), 16, 24 or 32. Applies from the next load.
+/// Returns: 0 on success, -1 on error (invalid depth or unsupported on this platform)
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_output_bit_depth(player_id: i64, bits: i32) -> i32 {
+    let Some(depth) = crate::sample_format::OutputBitDepth::from_bits(bits) else {
+        log::error!("Invalid output bit depth: {}", bits);
+        return -1;
+    };
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => {
+            let result = match player.output_processing() {
+                Some(output) => output.set_output_bit_depth(depth),
+                None => Err(AudioError::Unsupported("Output bit depth".to_string())),
+            };
+            match result {
+                Ok(_) => 0,
+                Err(e) => {
+                    log::error!("Failed to set output bit depth: {}", e);
+                    -1
+                }
+            }
+        }
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Resample every source to a fixed output rate; 0 follows each source (default). Applies from the next load.
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_target_sample_rate(player_id: i64, sample_rate: u32) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.set_target_sample_rate((sample_rate > 0).then_some(sample_rate)) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to set target sample rate: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Set the power mode: 0=normal (default), 1=low power for background playback
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_power_mode(player_id: i64, mode: i32) -> i32 {
+    let mode = match mode {
+        0 => crate::PowerMode::Normal,
+        1 => crate::PowerMode::LowPower,
+        _ => {
+            log::error!("Invalid power mode: {}", mode);
+            return -1;
+        }
+    };
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.set_power_mode(mode) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to set power mode: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Set volume (0.0 - 1.0)
+/// Returns: 0 on success, otherwise the error's code (-5 invalid state when nothing is loaded or the
+/// player was released)
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_volume(player_id: i64, volume: f32) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    let result = match registry.get_mut(&player_id) {
+        Some(player) => player.set_volume(volume),
+        None => Err(released_player(player_id)),
+    };
+    match result {
+        Ok(_) => 0,
+        Err(e) => {
+            log::error!("Failed to set volume: {}", e);
+            e.code()
+        }
+    }
+}
+
+/// Set playback rate (1.0 = normal speed)
+/// Returns: 0 on success, otherwise the error's code (-5 invalid state when nothing is loaded or the
+/// player was released)
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_playback_rate(player_id: i64, rate: f32) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    let result = match registry.get_mut(&player_id) {
+        Some(player) => player.set_playback_rate(rate),
+        None => Err(released_player(player_id)),
+    };
+    match result {
+        Ok(_) => 0,
+        Err(e) => {
+            log::error!("Failed to set playback rate: {}", e);
+            e.code()
+        }
+    }
+}
+
+/// Error for an ID that is not (or no longer) in the registry
+fn released_player(player_id: i64) -> AudioError {
+    AudioError::InvalidState(format!("Player {} does not exist or was released", player_id))
+}
+
+/// Start the next load at `position_ms` instead of 0; the decoder seeks before pre-buffering,
+/// so no audio or position is reported from the beginning. Applies to one load only.
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_start_position(player_id: i64, position_ms: u64) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.set_start_position(position_ms) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to set start position: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Set ReplayGain normalization: mode 0=off (default), 1=track, 2=album, plus a preamp in dB.
+/// The gain is lowered where a track's peak tag says it would clip.
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_replay_gain(player_id: i64, mode: i32, preamp_db: f32) -> i32 {
+    let mode = match mode {
+        0 => crate::ReplayGainMode::Off,
+        1 => crate::ReplayGainMode::Track,
+        2 => crate::ReplayGainMode::Album,
+        _ => {
+            log::error!("Invalid ReplayGain mode: {}", mode);
+            return -1;
+        }
+    };
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.set_replay_gain(crate::ReplayGainSettings { mode, preamp_db }) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to set ReplayGain: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Set the latency mode: 0 = auto (default), otherwise a fixed output latency in milliseconds.
+/// Applies from the next load.
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_latency_mode(player_id: i64, fixed_ms: u32) -> i32 {
+    let mode = match fixed_ms {
+        0 => crate::LatencyMode::Auto,
+        ms => crate::LatencyMode::Fixed(ms),
+    };
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.set_latency_mode(mode) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to set latency mode: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Get the delay between decoding and hearing audio (constant in fixed latency mode)
+/// Returns: latency in milliseconds, or -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_output_latency_ms(player_id: i64) -> i64 {
+    let registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get(&player_id) {
+        Some(player) => player.output_latency_ms() as i64,
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Fetch the episode transcript (WebVTT or SRT) from `url` in the background; null clears it
+/// Returns: 0 on success, -1 on error
+///
+/// # Safety
+/// `url` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rust_audio_player_set_transcript_url(player_id: i64, url: *const c_char) -> i32 {
+    let url_str = if url.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(url).to_str() {
+            Ok(s) => Some(s),
+            Err(e) => {
+                log::error!("Invalid UTF-8 in transcript URL: {}", e);
+                return -1;
+            }
+        }
+    };
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.set_transcript_url(url_str) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to set transcript URL: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Get the transcript cue at `position_ms` as a JSON object: `{start_ms, end_ms, text, speaker}`
+/// Returns: string to free with `rust_audio_player_free_string`, or null if there is no cue
+#[no_mangle]
+pub extern "C" fn rust_audio_player_current_transcript_cue_json(
+    player_id: i64,
+    position_ms: i64,
+) -> *mut c_char {
+    let registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get(&player_id) {
+        Some(player) => player
+            .current_transcript_cue(position_ms.max(0) as u64)
+            .and_then(|cue| CString::new(cue.to_json()).ok())
+            .map(CString::into_raw)
+            .unwrap_or(std::ptr::null_mut()),
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Switch to another audio track of the loaded source
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_set_audio_track(player_id: i64, track_id: i32) -> i32 {
+    if track_id < 0 {
+        log::error!("Invalid track id: {}", track_id);
+        return -1;
+    }
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => {
+            let result = match player.track_selection() {
+                Some(tracks) => tracks.set_audio_track(track_id as u32),
+                None => Err(AudioError::Unsupported("Audio track selection".to_string())),
+            };
+            match result {
+                Ok(_) => 0,
+                Err(e) => {
+                    log::error!("Failed to set audio track: {}", e);
+                    -1
+                }
+            }
+        }
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Briefly play `duration_ms` of audio from `position_ms` over the ducked main playback
+/// (scrub bar previews); the playback position is not changed
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_scrub_preview(player_id: i64, position_ms: i64, duration_ms: i64) -> i32 {
+    if position_ms < 0 || duration_ms <= 0 {
+        log::error!("Invalid scrub preview window: {} ms + {} ms", position_ms, duration_ms);
+        return -1;
+    }
+
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => {
+            let result = match player.output_processing() {
+                Some(output) => output.scrub_preview(position_ms as u64, duration_ms as u64),
+                None => Err(AudioError::Unsupported("Scrub preview".to_string())),
+            };
+            match result {
+                Ok(_) => 0,
+                Err(e) => {
+                    log::error!("Failed to play scrub preview: {}", e);
+                    -1
+                }
+            }
+        }
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Lower the main output to `level` (0.0 - 1.0) over `fade_ms` while the app plays a short clip
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_duck(player_id: i64, level: f32, fade_ms: u64) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.duck(level, fade_ms) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to duck: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Fade the main output back up from a duck over `fade_ms`
+/// Returns: 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_unduck(player_id: i64, fade_ms: u64) -> i32 {
+    let mut registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get_mut(&player_id) {
+        Some(player) => match player.unduck(fade_ms) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to unduck: {}", e);
+                -1
+            }
+        },
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
 /// Get current playback position in milliseconds
 /// Returns: position in ms, or -1 on error
 #[no_mangle]
@@ -238,6 +804,104 @@ pub extern "C" fn rust_audio_player_get_duration(player_id: i64) -> i64 {
     }
 }
 
+/// Get playback status as a JSON object:
+/// `{position_ms, duration_ms, duration_known, volume, playback_rate, buffering}`
+/// Returns: string to free with `rust_audio_player_free_string`, or null on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_status_json(player_id: i64) -> *mut c_char {
+    let registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get(&player_id) {
+        Some(player) => CString::new(player.get_status().to_json())
+            .map(CString::into_raw)
+            .unwrap_or(std::ptr::null_mut()),
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Free a string returned by this library
+///
+/// # Safety
+/// `ptr` must be null or a pointer previously returned by this library, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rust_audio_player_free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+/// Get the sample rate the output stream was opened at
+/// Returns: rate in Hz, 0 if no stream has been initialized yet, or -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_output_sample_rate(player_id: i64) -> i32 {
+    let registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get(&player_id) {
+        Some(player) => player.output_sample_rate() as i32,
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Get the channel count the output stream was opened with
+/// Returns: channels, 0 if no stream has been initialized yet, or -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_output_channels(player_id: i64) -> i32 {
+    let registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get(&player_id) {
+        Some(player) => player.output_channels() as i32,
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Get the decode throughput of the current source in frames per second of decode work
+/// Returns: throughput, 0 before anything was decoded, or -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_decode_throughput(player_id: i64) -> f64 {
+    let registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get(&player_id) {
+        Some(player) => player.get_stats().decode_throughput_frames_per_sec(),
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1.0
+        }
+    }
+}
+
+/// Get the number of chunks the PCM and output taps dropped because a tap fell behind
+/// Returns: dropped chunks, or -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_dropped_tap_chunks(player_id: i64) -> i64 {
+    let registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get(&player_id) {
+        Some(player) => player.get_stats().dropped_tap_chunks as i64,
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
+/// Get the number of player events waiting for delivery to the callback
+/// Returns: queue depth, or -1 on error
+#[no_mangle]
+pub extern "C" fn rust_audio_player_get_event_queue_depth(player_id: i64) -> i32 {
+    let registry = PLAYER_REGISTRY.lock().unwrap();
+    match registry.get(&player_id) {
+        Some(player) => player.event_queue_depth() as i32,
+        None => {
+            log::error!("Invalid player ID: {}", player_id);
+            -1
+        }
+    }
+}
+
 /// Get player state
 /// Returns: 0=Idle, 1=Loading, 2=Ready, 3=Playing, 4=Paused, 5=Stopped, 6=Error, -1=Invalid player ID
 #[no_mangle]
@@ -279,3 +943,21 @@ pub extern "C" fn rust_audio_player_release(player_id: i64) -> i32 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_volume_on_released_player_returns_invalid_state() {
+        let invalid_state = AudioError::InvalidState(String::new()).code();
+        let player_id = rust_audio_player_create();
+        // Without an output device creation fails, but a stale ID must still be rejected
+        if player_id > 0 {
+            assert_eq!(rust_audio_player_set_volume(player_id, 0.5), invalid_state);
+            assert_eq!(rust_audio_player_release(player_id), 0);
+        }
+        assert_eq!(rust_audio_player_set_volume(player_id, 0.5), invalid_state);
+        assert_eq!(rust_audio_player_set_playback_rate(player_id, 1.5), invalid_state);
+    }
+}
@@ -0,0 +1,110 @@
+// file: URLs and plain local paths handed to `load_url` (common from file pickers)
+// They are played from disk through `load_file` instead of going to the HTTP source.
+
+/// The local path behind a `file:` URL or a plain absolute path, None for anything else.
+/// `file:` URLs are percent-decoded; `file:///C:/x` becomes `C:/x` and `file://host/x`
+/// becomes the UNC path `//host/x`. Plain paths are returned as given.
+pub(crate) fn local_path(location: &str) -> Option<String> {
+    let Some(rest) = strip_prefix_ignore_case(location, "file:") else {
+        return is_absolute_path(location).then(|| location.to_string());
+    };
+
+    let path = match rest.strip_prefix("//") {
+        Some(authority_and_path) => {
+            let (host, path) = match authority_and_path.find('/') {
+                Some(slash) => authority_and_path.split_at(slash),
+                None => (authority_and_path, ""),
+            };
+            if host.is_empty() || host.eq_ignore_ascii_case("localhost") {
+                path.to_string()
+            } else if is_drive(host) {
+                // Non-standard but common: file://C:/Music/a.mp3
+                format!("{}{}", host, path)
+            } else {
+                format!("//{}{}", host, path)
+            }
+        }
+        None => rest.to_string(),
+    };
+
+    let path = percent_decode(&path);
+    // /C:/Music -> C:/Music
+    match path.strip_prefix('/') {
+        Some(rest) if is_drive(rest.get(..2).unwrap_or("")) => Some(rest.to_string()),
+        _ => Some(path),
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = value.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &value[prefix.len()..])
+}
+
+/// Absolute on either Unix or Windows, whatever platform we run on
+fn is_absolute_path(location: &str) -> bool {
+    let bytes = location.as_bytes();
+    match bytes.first() {
+        Some(b'/') | Some(b'\\') => true,
+        _ => is_drive(location.get(..2).unwrap_or("")) && matches!(bytes.get(2), Some(b'/' | b'\\')),
+    }
+}
+
+/// `C:` style Windows drive
+fn is_drive(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// Decode `%XX` escapes; malformed escapes are kept as written
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_path_from_file_urls() {
+        let path = local_path;
+        assert_eq!(path("file:///tmp/a.mp3").as_deref(), Some("/tmp/a.mp3"));
+        assert_eq!(path("FILE://localhost/tmp/a.mp3").as_deref(), Some("/tmp/a.mp3"));
+        assert_eq!(path("file:/tmp/a.mp3").as_deref(), Some("/tmp/a.mp3"));
+        assert_eq!(
+            path("file:///home/me/My%20Music/%C3%A9t%C3%A9.flac").as_deref(),
+            Some("/home/me/My Music/été.flac")
+        );
+        assert_eq!(path("file:///tmp/100%.mp3").as_deref(), Some("/tmp/100%.mp3"));
+
+        // Windows forms
+        assert_eq!(path("file:///C:/Music/a%20b.mp3").as_deref(), Some("C:/Music/a b.mp3"));
+        assert_eq!(path("file://C:/Music/a.mp3").as_deref(), Some("C:/Music/a.mp3"));
+        assert_eq!(path("file://server/share/a.mp3").as_deref(), Some("//server/share/a.mp3"));
+
+        // Plain paths pass through untouched; other schemes are not local
+        assert_eq!(path("/tmp/a%20b.mp3").as_deref(), Some("/tmp/a%20b.mp3"));
+        assert_eq!(path(r"C:\Music\a.mp3").as_deref(), Some(r"C:\Music\a.mp3"));
+        assert_eq!(path("https://example.com/a.mp3"), None);
+        assert_eq!(path("content://media/external/audio/1"), None);
+        assert_eq!(path("music/a.mp3"), None);
+    }
+}
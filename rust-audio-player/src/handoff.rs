@@ -0,0 +1,103 @@
+// Lock-free handoff of a value from the player to the output callback
+// The player puts a new value (EQ settings, a scrub preview); the callback takes it with one
+// atomic swap and hands back the value it replaced, so the free happens on the player's next
+// put instead of on the audio thread.
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Single-slot mailbox: the latest value put is the one taken
+pub struct Handoff<T: Send> {
+    pending: AtomicPtr<T>,
+    retired: AtomicPtr<T>,
+}
+
+impl<T: Send> Handoff<T> {
+    pub fn new() -> Self {
+        Self {
+            pending: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Offer `value`, replacing one not yet taken; also frees whatever the taker handed back
+    pub fn put(&self, value: T) {
+        free(self.retired.swap(ptr::null_mut(), Ordering::AcqRel));
+        free(self.pending.swap(Box::into_raw(Box::new(value)), Ordering::AcqRel));
+    }
+
+    /// Take the latest value put, if any
+    pub fn take(&self) -> Option<Box<T>> {
+        let value = self.pending.swap(ptr::null_mut(), Ordering::AcqRel);
+        // SAFETY: non-null pointers in `pending` come from `Box::into_raw` in `put`, and the
+        // swap gives us the only copy
+        (!value.is_null()).then(|| unsafe { Box::from_raw(value) })
+    }
+
+    /// Hand back a taken box (now holding the replaced value) to be freed by the next `put`
+    pub fn retire(&self, value: Box<T>) {
+        free(self.retired.swap(Box::into_raw(value), Ordering::AcqRel));
+    }
+}
+
+impl<T: Send> Default for Handoff<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send> Drop for Handoff<T> {
+    fn drop(&mut self) {
+        free(*self.pending.get_mut());
+        free(*self.retired.get_mut());
+    }
+}
+
+fn free<T>(value: *mut T) {
+    if !value.is_null() {
+        // SAFETY: every pointer stored in a `Handoff` comes from `Box::into_raw` and is
+        // swapped out exactly once before being freed here
+        drop(unsafe { Box::from_raw(value) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+
+    /// Counts its drops
+    struct Tracked(Arc<AtomicUsize>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_latest_value_wins_and_replaced_values_are_freed_by_the_putter() {
+        let handoff = Handoff::new();
+        assert!(handoff.take().is_none());
+        handoff.put(1);
+        handoff.put(2);
+        assert_eq!(*handoff.take().unwrap(), 2);
+        assert!(handoff.take().is_none());
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let handoff = Handoff::new();
+        handoff.put(Tracked(drops.clone()));
+        let mut current = Tracked(drops.clone());
+        let mut taken = handoff.take().unwrap();
+        std::mem::swap(&mut *taken, &mut current);
+        handoff.retire(taken);
+        // The replaced value waits for the next put
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        handoff.put(Tracked(drops.clone()));
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+        drop(handoff);
+        assert_eq!(drops.load(Ordering::SeqCst), 2);
+        drop(current);
+    }
+}
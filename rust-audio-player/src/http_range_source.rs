@@ -1,48 +1,86 @@
 // HTTP Range-based media source
 // Downloads data on-demand using HTTP Range requests
 // Perfect for M4A files where moov atom might be anywhere
+// Sources open at the same time for the same URL share one cache, so a preview decode running
+// next to the player's load of an episode is served the bytes either of them already fetched.
 
+use crate::decoder::LateHeaderSource;
 use crate::error::{AudioError, Result};
+use crate::http_transport::{http_config, http_transport, ChunkSizer, HttpConfig, HttpTransport};
+use crate::memory::{MemoryCharge, MemoryPool};
+use crate::stream_seek::{discover_total_size, parse_total_from_content_range, range_total_size, resolve_seek};
 use parking_lot::Mutex;
 use std::io::{Read, Seek, SeekFrom};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Weak};
+use std::time::Instant;
 use symphonia::core::io::MediaSource;
 
-/// Chunk size for Range requests (256KB)
+/// Chunk size for Range requests unless `HttpConfig::chunk_size` says otherwise (256KB)
 const CHUNK_SIZE: usize = 256 * 1024;
 
+/// Range requests per chunk: a reply that fails or ends early is fetched again from where it
+/// stopped, up to this many times in all
+const FETCH_ATTEMPTS: usize = 3;
+
+/// Bytes of fetched chunks kept before the least recently used one is dropped (8MB)
+const MAX_CACHE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Stage of opening a URL, reported while a load is preparing the stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamStage {
+    /// Asking the server for the file size
+    FetchingHeader,
+    /// Reading the container header to find the format (for M4A, the moov atom, wherever it sits)
+    FetchingMoov,
+    /// Format known; the load carries on to prebuffering
+    Ready,
+}
+
 /// Cache entry
-#[derive(Clone)]
 struct CacheEntry {
     offset: u64,
     data: Vec<u8>,
+    /// `HttpRangeState::use_tick` at the last read, for LRU eviction
+    last_used: u64,
+    _memory: MemoryCharge,
 }
 
+/// Live shared states by URL
+type OpenStates = Vec<(String, Weak<Mutex<HttpRangeState>>)>;
+
+static OPEN_STATES: Mutex<OpenStates> = Mutex::new(Vec::new());
+
 /// HTTP Range source state
 struct HttpRangeState {
     url: String,
     total_size: Option<u64>,
     /// Cache of downloaded chunks
     cache: Vec<CacheEntry>,
-    /// Agent for HTTP requests
-    agent: ureq::Agent,
+    /// Transport for HTTP requests
+    transport: Arc<dyn HttpTransport>,
+    /// Pool the cache is charged to
+    memory: &'static MemoryPool,
+    /// Size of the next Range request
+    chunk: ChunkSizer,
+    use_tick: u64,
 }
 
 impl HttpRangeState {
-    fn new(url: String) -> Self {
-        let agent = ureq::AgentBuilder::new()
-            .timeout_connect(Duration::from_secs(30))
-            .timeout_read(Duration::from_secs(60))
-            .user_agent("Mozilla/5.0 (compatible; RustAudioPlayer/1.0)")
-            .redirects(10)
-            .build();
-
+    fn new(
+        url: String,
+        transport: Arc<dyn HttpTransport>,
+        memory: &'static MemoryPool,
+        config: HttpConfig,
+    ) -> Self {
+        let base = config.chunk_size.unwrap_or(CHUNK_SIZE).max(1);
         Self {
             url,
             total_size: None,
             cache: Vec::new(),
-            agent,
+            transport,
+            memory,
+            chunk: ChunkSizer::new(base, (base / 4).max(1), base * 4, config.adaptive_chunks),
+            use_tick: 0,
         }
     }
 
@@ -53,15 +91,16 @@ impl HttpRangeState {
         }
 
         // Try HEAD first
-        match self.agent.head(&self.url).call() {
+        match self.transport.head(&self.url) {
             Ok(response) => {
-                self.total_size = response
-                    .header("Content-Length")
-                    .and_then(|s| s.parse::<u64>().ok());
+                self.total_size = response.content_length();
+                if self.total_size.is_none() {
+                    self.total_size = range_total_size(self.transport.as_ref(), &self.url).unwrap_or(None);
+                }
             }
             Err(head_err) => {
                 log::warn!("HEAD request failed ({}), falling back to Range GET", head_err);
-                self.total_size = self.try_get_size_with_range_request()?;
+                self.total_size = range_total_size(self.transport.as_ref(), &self.url)?;
                 if self.total_size.is_none() {
                     return Err(AudioError::NetworkError(format!(
                         "Failed to determine content length: HEAD error={}, Range GET returned no size",
@@ -84,48 +123,12 @@ impl HttpRangeState {
         Ok(())
     }
 
-    /// Fallback: fetch a 0-0 range to derive total size when HEAD fails or lacks Content-Length.
-    fn try_get_size_with_range_request(&self) -> Result<Option<u64>> {
-        let mut response = self
-            .agent
-            .get(&self.url)
-            .set("Range", "bytes=0-0")
-            .call()
-            .map_err(|e| AudioError::NetworkError(format!("Range request (fallback) failed: {}", e)))?;
-
-        // Capture headers before consuming the body
-        let content_range_header = response
-            .header("Content-Range")
-            .map(|s| s.to_string());
-        let content_length_header = response
-            .header("Content-Length")
-            .and_then(|s| s.parse::<u64>().ok());
-
-        // Ensure body is consumed so connection can be reused
-        let mut sink = Vec::new();
-        if let Err(e) = response.into_reader().read_to_end(&mut sink) {
-            log::warn!("Failed to read fallback range body: {}", e);
-        }
-
-        // Try Content-Range first, then Content-Length
-        if let Some(range) = content_range_header.as_deref() {
-            if let Some(total) = Self::parse_total_from_content_range(range) {
-                return Ok(Some(total));
-            }
-        }
-
-        Ok(content_length_header)
-    }
-
-    /// Parse Content-Range header to extract total size, e.g., "bytes 0-0/12345"
-    fn parse_total_from_content_range(header: &str) -> Option<u64> {
-        header.split('/').last()?.parse::<u64>().ok()
-    }
-
     /// Check if data is in cache
-    fn get_from_cache(&self, offset: u64, size: usize) -> Option<Vec<u8>> {
-        for entry in &self.cache {
+    fn get_from_cache(&mut self, offset: u64, size: usize) -> Option<Vec<u8>> {
+        self.use_tick += 1;
+        for entry in &mut self.cache {
             if offset >= entry.offset && offset + size as u64 <= entry.offset + entry.data.len() as u64 {
+                entry.last_used = self.use_tick;
                 let start = (offset - entry.offset) as usize;
                 let end = start + size;
                 return Some(entry.data[start..end].to_vec());
@@ -134,6 +137,26 @@ impl HttpRangeState {
         None
     }
 
+    fn cached_bytes(&self) -> usize {
+        self.cache.iter().map(|entry| entry.data.len()).sum()
+    }
+
+    /// Store a fetched chunk, evicting the least recently used others while over
+    /// `MAX_CACHE_BYTES` or the memory budget; the chunk just fetched always stays
+    fn insert_chunk(&mut self, offset: u64, data: Vec<u8>) {
+        self.cache.push(CacheEntry {
+            offset,
+            _memory: MemoryCharge::new(self.memory, data.len()),
+            data,
+            last_used: self.use_tick,
+        });
+        while self.cache.len() > 1 && (self.cached_bytes() > MAX_CACHE_BYTES || self.memory.over_budget()) {
+            let last = self.cache.len() - 1;
+            let victim = (0..last).min_by_key(|&i| self.cache[i].last_used).unwrap_or(0);
+            self.cache.remove(victim);
+        }
+    }
+
     /// Fetch data from URL using Range request
     fn fetch_range(&mut self, offset: u64, size: usize) -> Result<Vec<u8>> {
         // Check cache first
@@ -141,8 +164,8 @@ impl HttpRangeState {
             return Ok(data);
         }
 
-        // Fetch a chunk (at least CHUNK_SIZE or the requested size, whichever is larger)
-        let chunk_size = size.max(CHUNK_SIZE);
+        // Fetch a chunk (at least the current chunk size or the requested size, whichever is larger)
+        let chunk_size = size.max(self.chunk.current());
         let end = if let Some(total) = self.total_size {
             (offset + chunk_size as u64).min(total)
         } else {
@@ -156,34 +179,66 @@ impl HttpRangeState {
             end - offset
         );
 
-        let range_header = format!("bytes={}-{}", offset, end - 1);
-        let response = self
-            .agent
-            .get(&self.url)
-            .set("Range", &range_header)
-            .call()
-            .map_err(|e| AudioError::NetworkError(format!("Range request failed: {}", e)))?;
-
-        let mut data = Vec::new();
-        response
-            .into_reader()
-            .read_to_end(&mut data)
-            .map_err(|e| AudioError::IoError(format!("Failed to read response: {}", e)))?;
-
-        // Add to cache
-        self.cache.push(CacheEntry {
-            offset,
-            data: data.clone(),
-        });
-
-        // Limit cache size (keep last 20 chunks = ~5MB)
-        if self.cache.len() > 20 {
-            self.cache.remove(0);
+        let started = Instant::now();
+        let size_known = self.total_size.is_some();
+        let mut data = Vec::with_capacity((end - offset) as usize);
+        for attempt in 1..=FETCH_ATTEMPTS {
+            let from = offset + data.len() as u64;
+            let result = self.read_range(from, end - 1, &mut data);
+            // Without a known size, a reply that ends early is the end of the file
+            let complete = offset + data.len() as u64 >= end || (result.is_ok() && !size_known);
+            if complete {
+                break;
+            }
+            let reason = result.err().map_or_else(|| "reply ended early".to_string(), |e| e.to_string());
+            if attempt == FETCH_ATTEMPTS {
+                if data.is_empty() {
+                    return Err(AudioError::NetworkError(format!(
+                        "Range {}-{} failed after {} attempts: {}",
+                        offset,
+                        end - 1,
+                        FETCH_ATTEMPTS,
+                        reason
+                    )));
+                }
+                // Serve what arrived; the next read asks for the rest again
+                log::warn!("Range {}-{} incomplete after {} attempts: {}", offset, end - 1, attempt, reason);
+                break;
+            }
+            log::warn!(
+                "Range {}-{} stopped after {} bytes ({}), fetching the rest",
+                offset,
+                end - 1,
+                data.len(),
+                reason
+            );
         }
+        self.chunk.record(data.len(), started.elapsed());
 
         // Return requested slice
-        let requested_size = size.min(data.len());
-        Ok(data[..requested_size].to_vec())
+        let requested = data[..size.min(data.len())].to_vec();
+        self.insert_chunk(offset, data);
+        Ok(requested)
+    }
+
+    /// Append bytes `start..=end` to `data`; on failure `data` keeps whatever arrived
+    fn read_range(&mut self, start: u64, end: u64, data: &mut Vec<u8>) -> Result<()> {
+        let mut response = self
+            .transport
+            .get_range(&self.url, start, Some(end))
+            .map_err(|e| AudioError::NetworkError(format!("Range request failed: {}", e)))?;
+        // Some servers only state the size once they know it (e.g. a file still being written)
+        if self.total_size.is_none() {
+            self.total_size = response.header("Content-Range").and_then(parse_total_from_content_range);
+            if let Some(size) = self.total_size {
+                log::info!("HTTP Range source size became known: {} bytes", size);
+            }
+        }
+        response
+            .body
+            .read_to_end(data)
+            .map_err(|e| AudioError::IoError(format!("Failed to read response: {}", e)))?;
+        Ok(())
     }
 
     /// Read data at offset
@@ -214,8 +269,37 @@ pub struct HttpRangeSource {
 
 impl HttpRangeSource {
     /// Create a new HTTP Range source
+    /// Shares the cache of any other open source for `url`, reading from its own position.
     pub fn new(url: String) -> Result<Self> {
-        let mut state = HttpRangeState::new(url);
+        let mut open = OPEN_STATES.lock();
+        open.retain(|(_, state)| state.strong_count() > 0);
+        let live = open
+            .iter()
+            .find(|(open_url, _)| *open_url == url)
+            .and_then(|(_, state)| state.upgrade());
+        if let Some(state) = live {
+            log::info!("HTTP Range source joins the open cache for {}", url);
+            return Ok(Self { state, position: 0 });
+        }
+
+        // Not held across the HEAD request: a slow server must not hold up other URLs
+        drop(open);
+
+        let state = HttpRangeState::new(url.clone(), http_transport(), MemoryPool::global(), http_config());
+        let source = Self::open(state)?;
+        OPEN_STATES.lock().push((url, Arc::downgrade(&source.state)));
+        Ok(source)
+    }
+
+    /// Another reader of the same cache, starting at 0
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            position: 0,
+        }
+    }
+
+    fn open(mut state: HttpRangeState) -> Result<Self> {
         state.initialize()?;
 
         Ok(Self {
@@ -223,11 +307,17 @@ impl HttpRangeSource {
             position: 0,
         })
     }
+}
 
-    /// Get total size if known
-    pub fn total_size(&self) -> Option<u64> {
-        let state = self.state.lock();
-        state.total_size
+/// The size is what the probe was missing: without it, formats like MP3 without a Xing header
+/// cannot estimate their length. Reopening shares the cache, so the re-probe fetches nothing new.
+impl LateHeaderSource for HttpRangeSource {
+    fn header_ready(&self) -> bool {
+        self.state.lock().total_size.is_some()
+    }
+
+    fn reopen(&self) -> Result<Box<dyn MediaSource>> {
+        Ok(Box::new(self.share()))
     }
 }
 
@@ -253,33 +343,15 @@ impl Read for HttpRangeSource {
 
 impl Seek for HttpRangeSource {
     fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
-        let state = self.state.lock();
+        let mut state = self.state.lock();
+        // Ask again: the size may have been unavailable when the source was opened
+        if state.total_size.is_none() && matches!(pos, SeekFrom::End(_)) {
+            state.total_size = discover_total_size(state.transport.as_ref(), &state.url);
+        }
         let total_size = state.total_size;
         drop(state);
 
-        let new_pos = match pos {
-            SeekFrom::Start(offset) => offset as i64,
-            SeekFrom::Current(offset) => self.position as i64 + offset,
-            SeekFrom::End(offset) => {
-                if let Some(size) = total_size {
-                    size as i64 + offset
-                } else {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::Unsupported,
-                        "Cannot seek from end: total size unknown",
-                    ));
-                }
-            }
-        };
-
-        if new_pos < 0 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Cannot seek to negative position",
-            ));
-        }
-
-        self.position = new_pos as u64;
+        self.position = resolve_seek(pos, self.position, total_size)?;
         Ok(self.position)
     }
 }
@@ -294,3 +366,382 @@ impl MediaSource for HttpRangeSource {
         state.total_size
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_transport::tests::{install, MockTransport};
+    use crate::http_transport::UreqTransport;
+    use crate::http_utils::{set_user_agent, DEFAULT_USER_AGENT};
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    /// Open `url` through `transport`, outside the shared cache
+    fn open_with(url: &str, transport: Arc<MockTransport>) -> HttpRangeSource {
+        let state = HttpRangeState::new(url.to_string(), transport, MemoryPool::global(), http_config());
+        HttpRangeSource::open(state).unwrap()
+    }
+
+    #[test]
+    fn test_configured_user_agent_is_sent_on_head_and_get() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (requests, received) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 1024];
+                let n = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..n]).to_string();
+                let response: &[u8] = if request.starts_with("HEAD") {
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n"
+                } else {
+                    b"HTTP/1.1 206 Partial Content\r\nContent-Length: 4\r\nContent-Range: bytes 0-3/4\r\n\r\ndata"
+                };
+                let _ = requests.send(request);
+                let _ = stream.write_all(response);
+            }
+        });
+
+        let _installed = install(None);
+        set_user_agent(Some("PodiumTest/1.0 (+https://example.com)"));
+        let source = HttpRangeSource::new(format!("http://{}/a.m4a", addr));
+        set_user_agent(None);
+        assert_eq!(crate::http_utils::user_agent(), DEFAULT_USER_AGENT);
+
+        let mut source = source.unwrap();
+        let mut buf = [0u8; 4];
+        source.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"data");
+
+        for method in ["HEAD", "GET"] {
+            let request = received.recv().unwrap();
+            assert!(request.starts_with(method), "{}", request);
+            assert!(
+                request
+                    .to_ascii_lowercase()
+                    .contains("\r\nuser-agent: podiumtest/1.0 (+https://example.com)\r\n"),
+                "{}",
+                request
+            );
+        }
+    }
+
+    #[test]
+    fn test_cache_stays_within_memory_budget() {
+        let body: Vec<u8> = (0..4 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let total = body.len();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 1024];
+                let n = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..n]).to_ascii_lowercase();
+                let range = request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .and_then(|range| range.split_once('-'))
+                    .map(|(start, end)| (start.parse::<usize>().unwrap(), end.parse::<usize>().unwrap()));
+                let head = match range {
+                    Some((start, end)) => format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\n\
+                         Content-Range: bytes {}-{}/{}\r\n",
+                        end + 1 - start, start, end, total
+                    ),
+                    None => format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n", total),
+                };
+                let _ = stream.write_all(format!("{}Connection: close\r\n\r\n", head).as_bytes());
+                if let Some((start, end)) = range {
+                    let _ = stream.write_all(&body[start..=end]);
+                }
+            }
+        });
+
+        let pool = crate::memory::tests::test_pool();
+        let budget = 4 * CHUNK_SIZE;
+        pool.set_budget(Some(budget));
+        let url = format!("http://{}/a.mp3", addr);
+        let state = HttpRangeState::new(url, Arc::new(UreqTransport::new()), pool, http_config());
+        let mut source = HttpRangeSource::open(state).unwrap();
+        assert_eq!(pool.used(), 0);
+
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut read = 0;
+        while read < total {
+            source.read_exact(&mut buf).unwrap();
+            assert!(buf.iter().enumerate().all(|(i, b)| *b == ((read + i) % 251) as u8));
+            read += buf.len();
+            assert!(pool.used() > 0);
+            assert!(pool.used() <= budget, "{} cached over a {} budget", pool.used(), budget);
+        }
+
+        // Evicted chunks are fetched again
+        source.seek(SeekFrom::Start(0)).unwrap();
+        source.read_exact(&mut buf).unwrap();
+        assert_eq!(buf[..4], [0, 1, 2, 3]);
+        drop(source);
+        assert_eq!(pool.used(), 0);
+    }
+
+    #[test]
+    fn test_range_requests_reuse_one_connection() {
+        use std::io::{BufRead, BufReader};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let body: Arc<Vec<u8>> = Arc::new((0..CHUNK_SIZE * 8).map(|i| (i % 251) as u8).collect());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(AtomicUsize::new(0));
+        let (accepted, served) = (Arc::clone(&connections), Arc::clone(&requests));
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                let (body, served) = (Arc::clone(&body), Arc::clone(&served));
+                // Keep-alive: answer requests on this connection until the client hangs up
+                std::thread::spawn(move || {
+                    let mut writer = stream.try_clone().unwrap();
+                    let mut reader = BufReader::new(stream);
+                    loop {
+                        let mut head = Vec::new();
+                        let mut line = String::new();
+                        while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                            head.push(line.to_ascii_lowercase());
+                            line.clear();
+                        }
+                        let Some(request) = head.first() else { return };
+                        served.fetch_add(1, Ordering::SeqCst);
+                        let (start, end) = head
+                            .iter()
+                            .find_map(|line| line.trim().strip_prefix("range: bytes="))
+                            .and_then(|range| range.split_once('-'))
+                            .map(|(start, end)| (start.parse().unwrap(), end.parse::<usize>().unwrap()))
+                            .unwrap_or((0, body.len() - 1));
+                        let response = format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\n\
+                             Content-Range: bytes {}-{}/{}\r\n\r\n",
+                            end + 1 - start, start, end, body.len()
+                        );
+                        let _ = writer.write_all(response.as_bytes());
+                        if !request.starts_with("head") {
+                            let _ = writer.write_all(&body[start..=end]);
+                        }
+                    }
+                });
+            }
+        });
+
+        let _installed = install(None);
+        let mut source = HttpRangeSource::new(format!("http://{}/a.mp3", addr)).unwrap();
+        let mut buf = vec![0u8; 1024];
+        for chunk in [0, 5, 2, 7, 1, 6, 3] {
+            let offset = (chunk * CHUNK_SIZE) as u64;
+            source.seek(SeekFrom::Start(offset)).unwrap();
+            source.read_exact(&mut buf).unwrap();
+            assert_eq!(buf[0], (offset % 251) as u8);
+        }
+
+        // HEAD plus one GET per chunk, all over a single TCP (and TLS) connection
+        assert_eq!(requests.load(Ordering::SeqCst), 8);
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_preview_and_full_load_of_one_url_share_fetches() {
+        use crate::decoder::{tests::test_wav, AudioDecoder};
+        use symphonia::core::probe::Hint;
+
+        // 30 s of 16-bit stereo at 8 kHz: several range chunks
+        let wav = test_wav(8000, 2, 30);
+        let url = "mock://shared-episode.wav";
+        let open = |url: &str| {
+            let source = HttpRangeSource::new(url.to_string()).unwrap();
+            AudioDecoder::from_streaming_source(Box::new(source), Hint::new()).unwrap()
+        };
+        let play_through = |decoder: &mut AudioDecoder| {
+            let mut decoded = 0;
+            while let Some(samples) = decoder.decode_next().unwrap() {
+                decoded += samples.len();
+            }
+            decoded
+        };
+
+        // What a full load fetches on its own
+        let alone = MockTransport::new(wav.clone(), true);
+        let installed = install(Some(alone.clone()));
+        let total = play_through(&mut open(url));
+        drop(installed);
+        let alone = alone.requests.lock().clone();
+        assert!(alone.len() > 2, "{:?}", alone);
+
+        let transport = MockTransport::new(wav, true);
+        let _installed = install(Some(transport.clone()));
+        let mut full = open(url);
+        let mut played = 0;
+        for _ in 0..4 {
+            played += full.decode_next().unwrap().unwrap().len();
+        }
+
+        // The preview starts over at 0 on its own decoder, reading what the full load fetched
+        let mut preview = open(url);
+        let mut previewed = 0;
+        while previewed < 10 * 8000 * 2 {
+            previewed += preview.decode_next().unwrap().unwrap().len();
+        }
+        drop(preview);
+
+        // The full load carries on from where it was, and nothing was fetched twice
+        assert_eq!(played + play_through(&mut full), total);
+        assert_eq!(*transport.requests.lock(), alone);
+
+        // Another URL is another cache
+        let other = "mock://other-episode.wav";
+        drop(open(other));
+        let heads = transport.requests.lock().iter().filter(|r| **r == format!("HEAD {}", other)).count();
+        assert_eq!(heads, 1);
+    }
+
+    #[test]
+    fn test_far_seek_fetches_from_the_target() {
+        let body: Vec<u8> = (0..8 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let transport = MockTransport::new(body.clone(), true);
+        let url = "mock://far-seek.mp3".to_string();
+        let mut source = open_with(&url, transport.clone());
+        let mut buf = vec![0u8; 4096];
+        source.read_exact(&mut buf).unwrap();
+
+        // One ranged GET at the target; the gap is never downloaded
+        let target = 6 * 1024 * 1024;
+        source.seek(SeekFrom::Start(target as u64)).unwrap();
+        source.read_exact(&mut buf).unwrap();
+        assert!(buf == body[target..target + buf.len()]);
+        assert_eq!(
+            *transport.requests.lock(),
+            [format!("HEAD {}", url), format!("GET {} from 0", url), format!("GET {} from {}", url, target)]
+        );
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used_chunks_past_its_bound() {
+        let body: Vec<u8> = (0..64 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let transport = MockTransport::new(body.clone(), true);
+        let url = "mock://audiobook.m4a".to_string();
+        let mut source = open_with(&url, transport.clone());
+        let mut buf = vec![0u8; 4096];
+
+        // Seek around the whole file, going back to the header between reads like a demuxer
+        for step in 0..64u64 {
+            for offset in [0, (step * 7 % 64) * 1024 * 1024 + 12345] {
+                source.seek(SeekFrom::Start(offset)).unwrap();
+                source.read_exact(&mut buf).unwrap();
+                assert!(buf == body[offset as usize..offset as usize + buf.len()]);
+            }
+            let cached = source.state.lock().cached_bytes();
+            assert!(cached <= MAX_CACHE_BYTES, "{} cached bytes exceeds {}", cached, MAX_CACHE_BYTES);
+        }
+
+        // The header was read most recently each time, so it was never the one dropped
+        let header_fetches = transport
+            .requests
+            .lock()
+            .iter()
+            .filter(|request| **request == format!("GET {} from 0", url))
+            .count();
+        assert_eq!(header_fetches, 1);
+
+        // A far chunk read early on went out of the cache and is fetched again
+        let before = transport.requests.lock().len();
+        source.seek(SeekFrom::Start(7 * 1024 * 1024 + 12345)).unwrap();
+        source.read_exact(&mut buf).unwrap();
+        assert_eq!(transport.requests.lock().len(), before + 1);
+    }
+
+    #[test]
+    fn test_range_reply_that_fails_or_ends_early_is_fetched_again() {
+        use crate::http_transport::tests::Fault;
+
+        let body: Vec<u8> = (0..CHUNK_SIZE * 8).map(|i| (i % 251) as u8).collect();
+        let transport = MockTransport::new(body.clone(), true);
+        let url = "mock://flaky.mp3".to_string();
+        let mut source = open_with(&url, transport.clone());
+        let mut buf = vec![0u8; 4096];
+
+        // The connection resets 1000 bytes in: the rest of the chunk is asked for from there
+        transport.break_next_range(Fault::Reset(1000));
+        source.read_exact(&mut buf).unwrap();
+        assert!(buf == body[..buf.len()]);
+
+        // A reply that ends before the first byte is not the end of the file
+        let target = CHUNK_SIZE * 4;
+        transport.break_next_range(Fault::Cut(0));
+        source.seek(SeekFrom::Start(target as u64)).unwrap();
+        source.read_exact(&mut buf).unwrap();
+        assert!(buf == body[target..target + buf.len()]);
+
+        assert_eq!(
+            *transport.requests.lock(),
+            [
+                format!("HEAD {}", url),
+                format!("GET {} from 0", url),
+                format!("GET {} from 1000", url),
+                format!("GET {} from {}", url, target),
+                format!("GET {} from {}", url, target),
+            ]
+        );
+
+        // A range that keeps failing is an error rather than an early end of stream
+        for _ in 0..FETCH_ATTEMPTS {
+            transport.break_next_range(Fault::Cut(0));
+        }
+        source.seek(SeekFrom::Start(CHUNK_SIZE as u64 * 6)).unwrap();
+        assert!(source.read(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_range_requests_follow_the_configured_chunk_size() {
+        let body: Vec<u8> = (0..4 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        // Where each Range request of a 1 MB sequential read starts, in KB
+        let request_starts = |config: HttpConfig| {
+            let transport = MockTransport::new(body.clone(), true);
+            let url = "mock://chunks.mp3".to_string();
+            let state = HttpRangeState::new(url, transport.clone(), MemoryPool::global(), config);
+            let mut source = HttpRangeSource::open(state).unwrap();
+            let mut buf = vec![0u8; 4096];
+            for _ in 0..256 {
+                source.read_exact(&mut buf).unwrap();
+            }
+            let requests = transport.requests.lock();
+            requests
+                .iter()
+                .filter_map(|request| request.rsplit_once(" from "))
+                .map(|(_, start)| start.parse::<u64>().unwrap() / 1024)
+                .collect::<Vec<_>>()
+        };
+
+        let fixed = HttpConfig { chunk_size: Some(64 * 1024), ..HttpConfig::default() };
+        assert_eq!(request_starts(fixed), (0..16).map(|i| i * 64).collect::<Vec<_>>());
+
+        // Replies come back at once: each request doubles, up to four times the configured size
+        let adaptive = HttpConfig { adaptive_chunks: true, ..fixed };
+        assert_eq!(request_starts(adaptive), [0, 64, 192, 448, 704, 960]);
+    }
+
+    #[test]
+    fn test_seek_from_end_with_known_and_unknown_size() {
+        let body: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+        let len = body.len() as u64;
+        let transports = [MockTransport::new(body.clone(), true), MockTransport::without_length(body.clone())];
+        for transport in transports {
+            let url = "mock://episode.m4a".to_string();
+            let mut source = open_with(&url, transport);
+
+            assert_eq!(source.seek(SeekFrom::End(0)).unwrap(), len);
+            assert_eq!(source.seek(SeekFrom::End(-1024)).unwrap(), len - 1024);
+            let mut tail = vec![0u8; 1024];
+            source.read_exact(&mut tail).unwrap();
+            assert!(tail == body[body.len() - 1024..]);
+            // Past the start clamps
+            assert_eq!(source.seek(SeekFrom::End(-(len as i64) - 10)).unwrap(), 0);
+        }
+    }
+}
@@ -0,0 +1,466 @@
+// Pluggable HTTP transport for the streaming sources
+// The sources only need HEAD, GET and ranged GET. Routing them through `HttpTransport` lets an
+// app send requests through its own networking stack (corporate proxy, platform HTTP), and lets
+// tests serve bytes from memory. `UreqTransport` is used unless another one is installed.
+
+use crate::error::{AudioError, Result};
+use parking_lot::RwLock;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An adaptive step finishing faster than this grows the chunk
+const FAST_STEP: Duration = Duration::from_millis(200);
+
+/// An adaptive step taking longer than this shrinks the chunk
+const STALL_STEP: Duration = Duration::from_secs(1);
+
+/// Time allowed to connect unless `HttpConfig::connect_timeout` says otherwise
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time a read may wait for data unless `HttpConfig::read_timeout` says otherwise
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time a request may take to send
+const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Keep-alive connections kept per host; ranged requests reuse these instead of reconnecting
+const IDLE_CONNECTIONS_PER_HOST: usize = 4;
+
+/// Response to an `HttpTransport` request
+pub struct HttpResponse {
+    pub status: u16,
+    /// Header names and values as received
+    pub headers: Vec<(String, String)>,
+    /// Body, read as it arrives (empty for HEAD)
+    pub body: Box<dyn Read + Send>,
+}
+
+impl HttpResponse {
+    /// Value of header `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.header("Content-Length")?.trim().parse().ok()
+    }
+
+    /// Whether a ranged GET got only the requested range
+    pub fn is_partial(&self) -> bool {
+        self.status == 206
+    }
+
+    /// Read the whole body
+    pub fn into_bytes(mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.body
+            .read_to_end(&mut data)
+            .map_err(|e| AudioError::NetworkError(format!("Failed to read response: {}", e)))?;
+        Ok(data)
+    }
+}
+
+/// HTTP client used by the streaming sources.
+/// Redirects are followed; error statuses (4xx/5xx) are returned as `Err`, like failed
+/// connections.
+pub trait HttpTransport: Send + Sync {
+    fn head(&self, url: &str) -> Result<HttpResponse>;
+
+    fn get(&self, url: &str) -> Result<HttpResponse>;
+
+    /// GET bytes `start..=end` of `url`, or `start..` to the end when `end` is None.
+    /// A server that ignores the range answers 200 with the whole body (see `is_partial`).
+    fn get_range(&self, url: &str, start: u64, end: Option<u64>) -> Result<HttpResponse>;
+}
+
+/// Default transport: ureq with the configured User-Agent.
+/// Each source holds one for its lifetime, so its requests share the agent's connection pool.
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl UreqTransport {
+    /// Transport sending the User-Agent currently set through `set_user_agent`, with the
+    /// timeouts currently set through `set_http_config`
+    pub fn new() -> Self {
+        Self::with_config(http_config())
+    }
+
+    fn with_config(config: HttpConfig) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(config.connect_timeout.unwrap_or(CONNECT_TIMEOUT))
+            .timeout_read(config.read_timeout.unwrap_or(READ_TIMEOUT))
+            .timeout_write(WRITE_TIMEOUT)
+            .user_agent(&crate::http_utils::user_agent())
+            .redirects(10)
+            // A source's download worker and its seeks may each hold a connection
+            .max_idle_connections_per_host(IDLE_CONNECTIONS_PER_HOST)
+            .build();
+        Self { agent }
+    }
+
+    fn call(request: ureq::Request) -> Result<HttpResponse> {
+        let response = request.call().map_err(|e| AudioError::NetworkError(e.to_string()))?;
+        let headers = response
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = response.header(&name)?.to_string();
+                Some((name, value))
+            })
+            .collect();
+        Ok(HttpResponse {
+            status: response.status(),
+            headers,
+            body: Box::new(response.into_reader()),
+        })
+    }
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpTransport for UreqTransport {
+    fn head(&self, url: &str) -> Result<HttpResponse> {
+        Self::call(self.agent.head(url))
+    }
+
+    fn get(&self, url: &str) -> Result<HttpResponse> {
+        Self::call(self.agent.get(url))
+    }
+
+    fn get_range(&self, url: &str, start: u64, end: Option<u64>) -> Result<HttpResponse> {
+        let range = match end {
+            Some(end) => format!("bytes={}-{}", start, end),
+            None => format!("bytes={}-", start),
+        };
+        Self::call(self.agent.get(url).set("Range", &range))
+    }
+}
+
+/// Check that `url` is a well-formed http(s) URL, without touching the network
+pub(crate) fn validate_url(url: &str) -> Result<()> {
+    let lower = url.to_ascii_lowercase();
+    let rest = lower
+        .strip_prefix("http://")
+        .or_else(|| lower.strip_prefix("https://"))
+        .ok_or_else(|| AudioError::NetworkError(format!("Unsupported URL scheme: {}", url)))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if host.is_empty() || host.starts_with(':') || url.chars().any(char::is_whitespace) {
+        return Err(AudioError::NetworkError(format!("Malformed URL: {}", url)));
+    }
+    Ok(())
+}
+
+/// App-installed transport for sources created afterwards (None = ureq)
+static TRANSPORT: RwLock<Option<Arc<dyn HttpTransport>>> = RwLock::new(None);
+
+/// Route the HTTP requests of sources created from now on through `transport`; None restores
+/// the built-in ureq transport
+pub fn set_http_transport(transport: Option<Arc<dyn HttpTransport>>) {
+    *TRANSPORT.write() = transport;
+}
+
+/// Transport for a new source: the installed one, or ureq with the current User-Agent
+pub fn http_transport() -> Arc<dyn HttpTransport> {
+    match TRANSPORT.read().as_ref() {
+        Some(transport) => Arc::clone(transport),
+        None => Arc::new(UreqTransport::new()),
+    }
+}
+
+/// Download tuning for the HTTP range source and the timeouts of the built-in transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HttpConfig {
+    /// Bytes per Range request (None keeps the default, 256 KB)
+    pub chunk_size: Option<usize>,
+    /// Grow the request while requests complete quickly and shrink it after a stall
+    pub adaptive_chunks: bool,
+    /// Time allowed to establish a connection (None keeps the default, 30 s)
+    pub connect_timeout: Option<Duration>,
+    /// Time a single read may wait for data before the request fails (None keeps the default, 60 s)
+    pub read_timeout: Option<Duration>,
+}
+
+static CONFIG: RwLock<HttpConfig> = RwLock::new(HttpConfig {
+    chunk_size: None,
+    adaptive_chunks: false,
+    connect_timeout: None,
+    read_timeout: None,
+});
+
+/// Download tuning for sources created from now on
+pub fn set_http_config(config: HttpConfig) {
+    log::info!("HTTP config -> {:?}", config);
+    *CONFIG.write() = config;
+}
+
+pub fn http_config() -> HttpConfig {
+    *CONFIG.read()
+}
+
+/// Size of the next download step, adapted to how long the previous steps took
+#[derive(Debug, Clone)]
+pub(crate) struct ChunkSizer {
+    size: usize,
+    min: usize,
+    max: usize,
+    adaptive: bool,
+}
+
+impl ChunkSizer {
+    /// Start at `size`; adaptive sizing (if enabled) stays within `min..=max`
+    pub(crate) fn new(size: usize, min: usize, max: usize, adaptive: bool) -> Self {
+        Self { size: size.clamp(min, max), min, max, adaptive }
+    }
+
+    pub(crate) fn current(&self) -> usize {
+        self.size
+    }
+
+    /// Record a step that moved `bytes` in `elapsed`: a full step that came back quickly doubles
+    /// the size, a stalled one halves it
+    pub(crate) fn record(&mut self, bytes: usize, elapsed: Duration) {
+        if !self.adaptive {
+            return;
+        }
+        let size = if bytes >= self.size && elapsed < FAST_STEP {
+            self.size.saturating_mul(2).min(self.max)
+        } else if elapsed > STALL_STEP {
+            (self.size / 2).max(self.min)
+        } else {
+            self.size
+        };
+        if size != self.size {
+            log::debug!(
+                "Download chunk {} -> {} bytes after {} bytes in {:?}",
+                self.size,
+                size,
+                bytes,
+                elapsed
+            );
+            self.size = size;
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::collections::VecDeque;
+    use std::io::Cursor;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// How `MockTransport` breaks off the body of an upcoming ranged GET
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) enum Fault {
+        /// The body ends cleanly after this many bytes
+        Cut(usize),
+        /// The connection resets after this many bytes
+        Reset(usize),
+    }
+
+    /// Body that fails with a connection reset once its bytes are read
+    struct ResetAfter(Cursor<Vec<u8>>);
+
+    impl Read for ResetAfter {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.read(buf)? {
+                0 if !buf.is_empty() => Err(std::io::ErrorKind::ConnectionReset.into()),
+                n => Ok(n),
+            }
+        }
+    }
+
+    /// Serves `body` from memory, honouring ranges when `ranges` is set, and records requests
+    pub(crate) struct MockTransport {
+        body: Vec<u8>,
+        ranges: bool,
+        /// Send Content-Length on whole-body responses (HEAD and GET)
+        lengths: bool,
+        /// Leave the size out of every response, Content-Range included (see `hide_size`)
+        size_hidden: AtomicBool,
+        /// Applied to the next ranged GETs, one each
+        faults: Mutex<VecDeque<Fault>>,
+        pub requests: Mutex<Vec<String>>,
+    }
+
+    impl MockTransport {
+        pub(crate) fn new(body: Vec<u8>, ranges: bool) -> Arc<Self> {
+            Arc::new(Self {
+                body,
+                ranges,
+                lengths: true,
+                size_hidden: AtomicBool::new(false),
+                faults: Mutex::new(VecDeque::new()),
+                requests: Mutex::new(Vec::new()),
+            })
+        }
+
+        /// A range-capable server that never states the full length up front (chunked replies)
+        pub(crate) fn without_length(body: Vec<u8>) -> Arc<Self> {
+            Arc::new(Self {
+                body,
+                ranges: true,
+                lengths: false,
+                size_hidden: AtomicBool::new(false),
+                faults: Mutex::new(VecDeque::new()),
+                requests: Mutex::new(Vec::new()),
+            })
+        }
+
+        /// Break off the body of the next ranged GET not already given a fault
+        pub(crate) fn break_next_range(&self, fault: Fault) {
+            self.faults.lock().push_back(fault);
+        }
+
+        /// Act like a server that does not know the size yet (`bytes 0-0/*`) until called with false
+        pub(crate) fn hide_size(&self, hidden: bool) {
+            self.size_hidden.store(hidden, Ordering::SeqCst);
+        }
+
+        fn respond(&self, status: u16, length: usize, body: Vec<u8>) -> HttpResponse {
+            let mut headers = Vec::new();
+            if (self.lengths && !self.size_hidden.load(Ordering::SeqCst)) || status == 206 {
+                headers.push(("Content-Length".to_string(), length.to_string()));
+            }
+            if self.ranges {
+                headers.push(("Accept-Ranges".to_string(), "bytes".to_string()));
+            }
+            HttpResponse { status, headers, body: Box::new(Cursor::new(body)) }
+        }
+    }
+
+    impl HttpTransport for MockTransport {
+        fn head(&self, url: &str) -> Result<HttpResponse> {
+            self.requests.lock().push(format!("HEAD {}", url));
+            Ok(self.respond(200, self.body.len(), Vec::new()))
+        }
+
+        fn get(&self, url: &str) -> Result<HttpResponse> {
+            self.requests.lock().push(format!("GET {}", url));
+            Ok(self.respond(200, self.body.len(), self.body.clone()))
+        }
+
+        fn get_range(&self, url: &str, start: u64, end: Option<u64>) -> Result<HttpResponse> {
+            if !self.ranges {
+                return self.get(url);
+            }
+            self.requests.lock().push(format!("GET {} from {}", url, start));
+            let last = self.body.len() - 1;
+            let end = end.map_or(last, |end| (end as usize).min(last));
+            let body = self.body[start as usize..=end].to_vec();
+            let mut response = self.respond(206, body.len(), body.clone());
+            let total = if self.size_hidden.load(Ordering::SeqCst) {
+                "*".to_string()
+            } else {
+                self.body.len().to_string()
+            };
+            let content_range = format!("bytes {}-{}/{}", start, end, total);
+            response.headers.push(("Content-Range".to_string(), content_range));
+            match self.faults.lock().pop_front() {
+                Some(Fault::Cut(bytes)) => {
+                    response.body = Box::new(Cursor::new(body[..bytes.min(body.len())].to_vec()));
+                }
+                Some(Fault::Reset(bytes)) => {
+                    let sent = body[..bytes.min(body.len())].to_vec();
+                    response.body = Box::new(ResetAfter(Cursor::new(sent)));
+                }
+                None => {}
+            }
+            Ok(response)
+        }
+    }
+
+    /// Held while a test has a transport installed, so tests relying on the installed one run
+    /// one at a time
+    static INSTALLED: Mutex<()> = Mutex::new(());
+
+    /// Keeps a transport installed; the built-in one is restored on drop
+    pub(crate) struct Installed {
+        _lock: parking_lot::MutexGuard<'static, ()>,
+    }
+
+    impl Drop for Installed {
+        fn drop(&mut self) {
+            set_http_transport(None);
+        }
+    }
+
+    /// Install `transport` (None = ureq) for sources the calling test creates
+    pub(crate) fn install(transport: Option<Arc<dyn HttpTransport>>) -> Installed {
+        let lock = INSTALLED.lock();
+        set_http_transport(transport);
+        Installed { _lock: lock }
+    }
+
+    #[test]
+    fn test_validate_url_accepts_only_well_formed_http_urls() {
+        for url in ["http://example.com/a.mp3", "HTTPS://example.com:8443/feed?id=1", "http://127.0.0.1:9/"] {
+            assert!(validate_url(url).is_ok(), "{}", url);
+        }
+        for url in ["not a url", "ftp://example.com/a.mp3", "http://", "https://:80/a.mp3", "http://exa mple.com/"] {
+            assert!(matches!(validate_url(url), Err(AudioError::NetworkError(_))), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_configured_timeouts_fail_an_unresponsive_server() {
+        use std::net::TcpListener;
+        use std::time::Instant;
+
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let _held: Vec<_> = listener.incoming().take(2).collect();
+            std::thread::sleep(Duration::from_secs(5));
+        });
+
+        let timeout = Duration::from_millis(300);
+        let transport = UreqTransport::with_config(HttpConfig {
+            connect_timeout: Some(timeout),
+            read_timeout: Some(timeout),
+            ..HttpConfig::default()
+        });
+        let started = Instant::now();
+        let result = transport.head(&format!("http://{}/audio.mp3", addr));
+        assert!(matches!(result, Err(AudioError::NetworkError(_))), "{:?}", result.err());
+        assert!(started.elapsed() < timeout * 2 + Duration::from_millis(500), "{:?}", started.elapsed());
+    }
+
+    #[test]
+    fn test_chunk_sizer_grows_when_fast_and_shrinks_after_stalls() {
+        let fast = Duration::from_millis(20);
+        let stall = Duration::from_secs(3);
+        let mut chunk = ChunkSizer::new(64 * 1024, 16 * 1024, 256 * 1024, true);
+
+        // Short reads are not a signal, however quick
+        chunk.record(1024, fast);
+        assert_eq!(chunk.current(), 64 * 1024);
+
+        for _ in 0..5 {
+            let size = chunk.current();
+            chunk.record(size, fast);
+        }
+        assert_eq!(chunk.current(), 256 * 1024);
+
+        for _ in 0..5 {
+            chunk.record(1024, stall);
+        }
+        assert_eq!(chunk.current(), 16 * 1024);
+
+        let mut fixed = ChunkSizer::new(64 * 1024, 16 * 1024, 256 * 1024, false);
+        fixed.record(64 * 1024, fast);
+        fixed.record(1024, stall);
+        assert_eq!(fixed.current(), 64 * 1024);
+    }
+}
@@ -1,17 +1,48 @@
 // iOS audio player implementation using cpal
 // cpal 0.15+ supports iOS via CoreAudio backend
 
-use crate::callback::{CallbackEvent, CallbackManager, PlayerCallback};
+use crate::callback::{CallbackEvent, CallbackHandle, CallbackManager, PlayerCallback};
+use crate::diagnostics::{LoadPhase, PhaseTimer};
+use crate::channel_remix::{pick_output_channels, remix_channels};
 use crate::decoder::{AudioDecoder, AudioRingBuffer};
+use crate::data_uri::parse_data_uri;
+use crate::file_url::local_path;
 use crate::error::{AudioError, Result};
-use crate::output_rate::effective_output_rate;
-use crate::player::{AudioPlayer, PlaybackStatus, PlayerState, PlayerStateContainer};
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, Host, SampleRate, Stream, StreamConfig};
+use crate::http_transport::validate_url;
+use crate::output_rate::{effective_output_rate, requested_stream_rate};
+use crate::cue_points::CuePoints;
+use crate::ducking::{clamp_duck_level, DuckGain};
+use crate::latency::{FixedLatency, LatencyMode};
+use crate::equalizer::{EqBand, Equalizer};
+use crate::level_meter::{LevelMeter, OutputLevels};
+use crate::limiter::Limiter;
+use crate::pcm_tap::{OutputTap, OutputTaps, PcmTap, PcmTaps};
+use crate::power_mode::{buffered_ms, PowerMode};
+use crate::replay_gain::ReplayGainSettings;
+use crate::transcript::{TranscriptCue, TranscriptSlot};
+use crate::playback_rate::{clamp_playback_rate, RateRamp};
+use crate::player::{
+    AudioPlayer, AudioSource, OutputProcessing, PlaybackStats, PlaybackStatus, PlaybackToken, PlayerState,
+    PlayerStateContainer, SeekMode, TrackSelection,
+};
+use crate::prepare::{decode_snippet, open_decoder_at, PrepareTask, PreparedSource};
+use crate::scrub::ScrubSnippet;
+use crate::stream_owner::StreamOwner;
+use crate::sample_format::{f32_to_i16, f32_to_i24, f32_to_i32, OutputBitDepth, TpdfDither};
+use crate::threads::{
+    join_within, run_isolated, set_current_thread_priority, spawn_named, ThreadPriority, DECODE_THREAD,
+    RELEASE_JOIN_TIMEOUT,
+};
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::{
+    Device, Host, SampleFormat, SampleRate, SizedSample, Stream, StreamConfig, SupportedBufferSize,
+    SupportedStreamConfigRange, I24,
+};
 use parking_lot::Mutex;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
+use symphonia::core::probe::Hint;
 
 /// Default ring buffer size (in samples) - used at initialization
 /// Will be optimized based on audio duration when loading
@@ -23,31 +54,158 @@ const MIN_BUFFER_DURATION_SECS: u64 = 2;
 /// Maximum buffer duration in seconds (to limit memory usage)
 const MAX_BUFFER_DURATION_SECS: u64 = 8;
 
-/// Position update interval (milliseconds)
-const POSITION_UPDATE_INTERVAL_MS: u64 = 100;
-
 /// Pre-buffer target in milliseconds (amount to decode before playback starts)
 const PRE_BUFFER_MS: u64 = 100;
 
+/// State shared between the player and the output stream callback
+#[derive(Clone)]
+struct StreamShared {
+    ring_buffer: Arc<Mutex<AudioRingBuffer>>,
+    is_playing: Arc<AtomicBool>,
+    sample_count: Arc<Mutex<u64>>,
+    volume: Arc<Mutex<f32>>,
+    /// Scrub preview being mixed over the main playback
+    scrub: Arc<Mutex<Option<ScrubSnippet>>>,
+    /// App taps fed with exactly what each callback hands the device
+    output_taps: Arc<OutputTaps>,
+    /// Per-channel EQ of the main playback, after volume and ducking
+    equalizer: Arc<Mutex<Equalizer>>,
+    /// Final stage, after volume and the scrub mix
+    limiter: Arc<Mutex<Limiter>>,
+    /// Per-channel levels of what the device is handed
+    meter: Arc<Mutex<LevelMeter>>,
+    /// Ducks the main playback (not the scrub preview) while the app plays a clip over it
+    duck: Arc<Mutex<DuckGain>>,
+    /// Largest callback seen, in samples, so the decode thread can keep the ring big enough
+    callback_samples: Arc<AtomicUsize>,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl StreamShared {
+    /// Fill one callback's worth of interleaved f32 output
+    fn render(&self, samples: &mut [f32]) {
+        let mut played_from = None;
+        if !self.is_playing.load(Ordering::Relaxed) {
+            // Fill with silence
+            samples.fill(0.0);
+        } else {
+            let vol = *self.volume.lock();
+            let mut buffer = self.ring_buffer.lock();
+            self.callback_samples.fetch_max(samples.len(), Ordering::Relaxed);
+            let read = buffer.read(samples);
+            drop(buffer);
+
+            // Apply volume (skip if volume is 1.0 to avoid unnecessary multiplication)
+            if (vol - 1.0).abs() > 0.001 {
+                for sample in samples[..read].iter_mut() {
+                    *sample *= vol;
+                }
+            }
+
+            self.duck
+                .lock()
+                .process(&mut samples[..read], self.channels, self.sample_rate);
+            self.equalizer
+                .lock()
+                .process(&mut samples[..read], self.channels, self.sample_rate);
+
+            // Fill remaining with silence
+            if read < samples.len() {
+                samples[read..].fill(0.0);
+            }
+
+            // Update sample count
+            let mut count = self.sample_count.lock();
+            played_from = Some(*count);
+            *count += (read / self.channels as usize) as u64;
+        }
+
+        // Mix an active scrub preview over the (ducked) main playback
+        let mut scrub = self.scrub.lock();
+        if let Some(snippet) = scrub.as_mut() {
+            if snippet.mix_into(samples, *self.volume.lock()) {
+                *scrub = None;
+            }
+        }
+        drop(scrub);
+
+        self.limiter.lock().process(samples, self.channels, self.sample_rate);
+        self.meter.lock().process(samples, self.channels);
+
+        if let Some(position_frames) = played_from {
+            self.output_taps
+                .publish(position_frames, self.sample_rate, self.channels, samples);
+        }
+    }
+}
+
 /// iOS audio player using cpal
 pub struct IOSAudioPlayer {
     state_container: PlayerStateContainer,
     callback_manager: Arc<CallbackManager>,
-    // Wrapped in Arc<Mutex> because cpal::Stream is not Send+Sync on all platforms
-    audio_stream: Arc<Mutex<Option<Stream>>>,
+    /// Durations of the current load's phases and the last seek
+    load_timer: PhaseTimer,
+    /// Output stream, kept on its own thread because cpal::Stream is not Send on all platforms
+    audio_stream: Arc<Mutex<Option<StreamOwner>>>,
     ring_buffer: Arc<Mutex<AudioRingBuffer>>,
     is_playing: Arc<AtomicBool>,
     sample_count: Arc<Mutex<u64>>,
     decoder_thread: Option<thread::JoinHandle<()>>,
     stop_decoder: Arc<AtomicBool>,
     decoder: Arc<Mutex<Option<AudioDecoder>>>,
+    /// Loaded source, used for resume tokens and scrub previews
+    current_source: Option<AudioSource>,
+    /// Where the next load starts playing from (see `set_start_position`)
+    start_position_ms: Option<u64>,
+    /// Scrub preview being mixed over the main playback
+    scrub: Arc<Mutex<Option<ScrubSnippet>>>,
     volume: Arc<Mutex<f32>>,
-    playback_rate: Arc<Mutex<f32>>,
+    /// Requested rate and its ramp; status reports the rate currently in effect
+    playback_rate: Arc<Mutex<RateRamp>>,
     /// Actual output sample rate selected for the audio device. This may differ from the decoder's sample rate
     /// if the device does not support it, in which case we resample to this rate to avoid speed/pitch issues.
     output_sample_rate: Arc<Mutex<u32>>,
+    /// Channel count the output stream was opened with (0 until a stream exists)
+    output_channels: Arc<Mutex<u16>>,
     host: Host,
     device: Option<Device>,
+    /// Source being opened in the background by `prepare`
+    prepared: Option<PrepareTask>,
+    /// Next source warmed up by `prepare_next` while the current one plays
+    prepared_next: Option<PrepareTask>,
+    /// Requested output sample format
+    output_bit_depth: OutputBitDepth,
+    /// App taps fed with every decoded chunk
+    pcm_taps: Arc<PcmTaps>,
+    /// App taps fed from the output callback, in step with playback
+    output_taps: Arc<OutputTaps>,
+    /// Output equalizer, kept across streams so its bands survive reloads
+    equalizer: Arc<Mutex<Equalizer>>,
+    /// Output limiter, kept across streams so its ceiling survives reloads
+    limiter: Arc<Mutex<Limiter>>,
+    /// Output level meter, read (and restarted) by `output_levels`
+    meter: Arc<Mutex<LevelMeter>>,
+    /// Duck gain, applied after volume in the output callback
+    duck: Arc<Mutex<DuckGain>>,
+    /// Applied by the decoder thread when it starts
+    thread_priority: ThreadPriority,
+    /// Checked by the decoder thread against the played position
+    cue_points: Arc<CuePoints>,
+    /// Fixed output stream rate; None follows each source
+    target_sample_rate: Option<u32>,
+    /// Read by the decoder thread; the ring buffer length follows on the next load
+    power_mode: Arc<Mutex<PowerMode>>,
+    /// Loudness normalization, handed to the decoder before every packet
+    replay_gain: Arc<Mutex<ReplayGainSettings>>,
+    /// Fetched when the app sets the episode's transcript URL
+    transcript: Arc<TranscriptSlot>,
+    /// Applied when the next stream is opened
+    latency_mode: LatencyMode,
+    /// Buffer sizes in effect for a Fixed latency mode; None in Auto
+    fixed_latency: Arc<Mutex<Option<FixedLatency>>>,
+    /// Largest output callback of the current stream, in samples (0 until known)
+    callback_samples: Arc<AtomicUsize>,
 }
 
 impl IOSAudioPlayer {
@@ -68,6 +226,7 @@ impl IOSAudioPlayer {
         Ok(Self {
             state_container: PlayerStateContainer::new(),
             callback_manager: Arc::new(CallbackManager::new()),
+            load_timer: PhaseTimer::default(),
             audio_stream: Arc::new(Mutex::new(None)),
             ring_buffer: Arc::new(Mutex::new(AudioRingBuffer::new(RING_BUFFER_SIZE))),
             is_playing: Arc::new(AtomicBool::new(false)),
@@ -75,11 +234,33 @@ impl IOSAudioPlayer {
             decoder_thread: None,
             stop_decoder: Arc::new(AtomicBool::new(false)),
             decoder: Arc::new(Mutex::new(None)),
+            current_source: None,
+            start_position_ms: None,
+            scrub: Arc::new(Mutex::new(None)),
             volume: Arc::new(Mutex::new(1.0)),
-            playback_rate: Arc::new(Mutex::new(1.0)),
+            playback_rate: Arc::new(Mutex::new(RateRamp::default())),
             output_sample_rate: Arc::new(Mutex::new(0)),
+            output_channels: Arc::new(Mutex::new(0)),
             host,
             device: Some(device),
+            prepared: None,
+            prepared_next: None,
+            output_bit_depth: OutputBitDepth::default(),
+            pcm_taps: Arc::new(PcmTaps::new()),
+            output_taps: Arc::new(OutputTaps::new()),
+            equalizer: Arc::new(Mutex::new(Equalizer::new())),
+            limiter: Arc::new(Mutex::new(Limiter::new())),
+            meter: Arc::new(Mutex::new(LevelMeter::new())),
+            duck: Arc::new(Mutex::new(DuckGain::default())),
+            thread_priority: ThreadPriority::default(),
+            cue_points: Arc::new(CuePoints::new()),
+            target_sample_rate: None,
+            power_mode: Arc::new(Mutex::new(PowerMode::default())),
+            replay_gain: Arc::new(Mutex::new(ReplayGainSettings::default())),
+            transcript: Arc::new(TranscriptSlot::new()),
+            latency_mode: LatencyMode::default(),
+            fixed_latency: Arc::new(Mutex::new(None)),
+            callback_samples: Arc::new(AtomicUsize::new(0)),
         })
     }
 
@@ -90,8 +271,13 @@ impl IOSAudioPlayer {
             channels
         );
 
+        let sample_rate = requested_stream_rate(sample_rate, self.target_sample_rate);
+
         // Drop existing stream
         *self.audio_stream.lock() = None;
+        *self.scrub.lock() = None;
+        self.equalizer.lock().reset();
+        self.limiter.lock().reset();
 
         let device = self
             .device
@@ -99,63 +285,179 @@ impl IOSAudioPlayer {
             .ok_or_else(|| AudioError::DeviceError("No audio device".to_string()))?;
 
         // Configure stream with a sample rate supported by the device (clamp if necessary)
-        let config = self.pick_stream_config(device, sample_rate, channels);
+        let mut config = self.pick_stream_config(device, sample_rate, channels);
+
+        // A fixed latency pins the device buffer; the ring buffer gets the rest
+        let fixed_latency = match self.latency_mode {
+            LatencyMode::Auto => None,
+            LatencyMode::Fixed(total_ms) => {
+                let range = Self::device_buffer_range(device, &config);
+                let plan = FixedLatency::plan(total_ms, config.sample_rate.0, range);
+                if range.is_some() {
+                    config.buffer_size = cpal::BufferSize::Fixed(plan.device_frames);
+                }
+                log::info!(
+                    "Fixed latency {} ms: device buffer {} frames, ring buffer {} frames",
+                    plan.latency_ms(),
+                    plan.device_frames,
+                    plan.ring_frames
+                );
+                Some(plan)
+            }
+        };
+        *self.fixed_latency.lock() = fixed_latency;
+
+        // A fixed device buffer is known now; otherwise the first callback reports its size
+        let callback_samples = match config.buffer_size {
+            cpal::BufferSize::Fixed(frames) => frames as usize * config.channels as usize,
+            cpal::BufferSize::Default => 0,
+        };
+        self.callback_samples.store(callback_samples, Ordering::Relaxed);
 
         log::debug!("Stream config: {:?}", config);
 
         // Create stream
-        let ring_buffer = self.ring_buffer.clone();
-        let is_playing = self.is_playing.clone();
-        let sample_count = self.sample_count.clone();
-        let volume = self.volume.clone();
-        let output_sample_rate = self.output_sample_rate.clone();
+        let shared = StreamShared {
+            ring_buffer: self.ring_buffer.clone(),
+            is_playing: self.is_playing.clone(),
+            sample_count: self.sample_count.clone(),
+            volume: self.volume.clone(),
+            scrub: self.scrub.clone(),
+            output_taps: self.output_taps.clone(),
+            equalizer: self.equalizer.clone(),
+            limiter: self.limiter.clone(),
+            meter: self.meter.clone(),
+            duck: self.duck.clone(),
+            callback_samples: self.callback_samples.clone(),
+            sample_rate: config.sample_rate.0,
+            channels: config.channels,
+        };
+
+        let sample_format = self.pick_sample_format(device, &config);
+        log::debug!("Stream sample format: {:?}", sample_format);
+
+        // Built on its owner thread, where it stays until dropped
+        let output_bit_depth = self.output_bit_depth;
+        let stream_device = device.clone();
+        let stream_config = config.clone();
+        let stream = StreamOwner::spawn(move || {
+            let (device, config) = (&stream_device, &stream_config);
+            let mut dither = TpdfDither::default();
+            match (sample_format, output_bit_depth) {
+                (SampleFormat::I16, _) => {
+                    Self::build_stream(device, config, shared, move |s| f32_to_i16(s, dither.next_offset()))
+                }
+                (SampleFormat::I24, _) => Self::build_stream(device, config, shared, move |s| {
+                    I24::new_unchecked(f32_to_i24(s, dither.next_offset()))
+                }),
+                // 24-bit audio in a 32-bit container: dither at the 24-bit LSB
+                (SampleFormat::I32, OutputBitDepth::Int24) => {
+                    Self::build_stream(device, config, shared, move |s| {
+                        f32_to_i24(s, dither.next_offset()) << 8
+                    })
+                }
+                (SampleFormat::I32, _) => {
+                    Self::build_stream(device, config, shared, move |s| f32_to_i32(s, dither.next_offset()))
+                }
+                _ => Self::build_stream(device, config, shared, |s| s),
+            }
+        })?;
+
+        *self.audio_stream.lock() = Some(stream);
+        *self.output_sample_rate.lock() = config.sample_rate.0;
+        *self.output_channels.lock() = config.channels;
+
+        log::info!("Audio stream initialized successfully");
+        Ok(())
+    }
 
+    /// Build the output stream, converting the f32 ring buffer contents to the device sample type
+    fn build_stream<T, F>(
+        device: &Device,
+        config: &StreamConfig,
+        shared: StreamShared,
+        mut convert: F,
+    ) -> Result<Stream>
+    where
+        T: SizedSample + Send + 'static,
+        F: FnMut(f32) -> T + Send + 'static,
+    {
         let err_fn = |err| {
             log::error!("Audio stream error: {}", err);
         };
 
-        let stream = device
-            .build_output_stream(
-                &config,
-                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                    if !is_playing.load(Ordering::Relaxed) {
-                        // Fill with silence
-                        data.fill(0.0);
-                        return;
-                    }
-
-                    let vol = *volume.lock();
-                    let mut buffer = ring_buffer.lock();
-                    let read = buffer.read(data);
+        // Reused between callbacks; only grows if the device asks for a larger burst
+        let mut scratch: Vec<f32> = Vec::new();
 
-                    // Apply volume (skip if volume is 1.0 to avoid unnecessary multiplication)
-                    if (vol - 1.0).abs() > 0.001 {
-                        for sample in data[..read].iter_mut() {
-                            *sample *= vol;
-                        }
+        device
+            .build_output_stream(
+                config,
+                move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                    if scratch.len() < data.len() {
+                        scratch.resize(data.len(), 0.0);
                     }
+                    let samples = &mut scratch[..data.len()];
+                    shared.render(samples);
 
-                    // Fill remaining with silence
-                    if read < data.len() {
-                        data[read..].fill(0.0);
+                    for (out, &sample) in data.iter_mut().zip(samples.iter()) {
+                        *out = convert(sample);
                     }
-
-                    // Update sample count
-                    let mut count = sample_count.lock();
-                    *count += (read as u64 / channels as u64);
                 },
                 err_fn,
                 None,
             )
             .map_err(|e| {
                 AudioError::InitializationError(format!("Failed to build output stream: {}", e))
-            })?;
+            })
+    }
 
-        *self.audio_stream.lock() = Some(stream);
-        *self.output_sample_rate.lock() = config.sample_rate.0;
+    /// Pick the device sample format for the requested output bit depth.
+    /// Float unless an integer depth was requested and the device supports it for this config.
+    fn pick_sample_format(&self, device: &Device, config: &StreamConfig) -> SampleFormat {
+        let preferred: &[SampleFormat] = match self.output_bit_depth {
+            OutputBitDepth::Float => return SampleFormat::F32,
+            OutputBitDepth::Int16 => &[SampleFormat::I16],
+            OutputBitDepth::Int24 => &[SampleFormat::I24, SampleFormat::I32],
+            OutputBitDepth::Int32 => &[SampleFormat::I32],
+        };
 
-        log::info!("Audio stream initialized successfully");
-        Ok(())
+        let supported: Vec<SampleFormat> = match device.supported_output_configs() {
+            Ok(configs) => configs
+                .filter(|range| {
+                    range.channels() == config.channels
+                        && range.min_sample_rate() <= config.sample_rate
+                        && config.sample_rate <= range.max_sample_rate()
+                })
+                .map(|range| range.sample_format())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        match preferred.iter().find(|format| supported.contains(format)) {
+            Some(format) => *format,
+            None => {
+                log::warn!(
+                    "{}-bit output not supported by device; using f32",
+                    self.output_bit_depth.bits()
+                );
+                SampleFormat::F32
+            }
+        }
+    }
+
+    /// Device buffer sizes (in frames) supported for `config`, if the device reports them
+    fn device_buffer_range(device: &Device, config: &StreamConfig) -> Option<(u32, u32)> {
+        device
+            .supported_output_configs()
+            .ok()?
+            .filter(|range| range.channels() == config.channels)
+            .filter(|range| {
+                range.min_sample_rate() <= config.sample_rate && config.sample_rate <= range.max_sample_rate()
+            })
+            .find_map(|range| match *range.buffer_size() {
+                SupportedBufferSize::Range { min, max } => Some((min, max)),
+                SupportedBufferSize::Unknown => None,
+            })
     }
 
     /// Pick a stream config that best matches the decoder output while being supported by the device.
@@ -164,6 +466,33 @@ impl IOSAudioPlayer {
         device: &Device,
         decoder_sample_rate: u32,
         channels: u16,
+    ) -> StreamConfig {
+        match device.supported_output_configs() {
+            Ok(configs) => {
+                let ranges: Vec<SupportedStreamConfigRange> = configs.collect();
+                Self::choose_stream_config(&ranges, decoder_sample_rate, channels)
+            }
+            Err(err) => {
+                log::warn!(
+                    "Failed to query supported output configs ({}); using decoder sample rate {}Hz",
+                    err,
+                    decoder_sample_rate
+                );
+                StreamConfig {
+                    channels,
+                    sample_rate: SampleRate(decoder_sample_rate),
+                    buffer_size: cpal::BufferSize::Default,
+                }
+            }
+        }
+    }
+
+    /// Choose among the device's supported ranges: the decoder's channel count when available
+    /// (otherwise the closest layout, remixed on the way in), at the closest supported rate.
+    fn choose_stream_config(
+        ranges: &[SupportedStreamConfigRange],
+        decoder_sample_rate: u32,
+        channels: u16,
     ) -> StreamConfig {
         // Default to decoder sample rate
         let default_config = StreamConfig {
@@ -172,198 +501,256 @@ impl IOSAudioPlayer {
             buffer_size: cpal::BufferSize::Default,
         };
 
-        // Try to find a supported range that matches the channel count
-        match device.supported_output_configs() {
-            Ok(mut configs) => {
-                let mut chosen: Option<StreamConfig> = None;
-                while let Some(cfg_range) = configs.next() {
-                    if cfg_range.channels() != channels {
-                        continue;
-                    }
+        let supported: Vec<u16> = ranges.iter().map(|range| range.channels()).collect();
+        let Some(output_channels) = pick_output_channels(channels, &supported) else {
+            log::warn!(
+                "No supported config found for {} channels; using decoder sample rate {}Hz",
+                channels,
+                decoder_sample_rate
+            );
+            return default_config;
+        };
+        if output_channels != channels {
+            log::warn!(
+                "Device does not support {} channels; remixing to {}",
+                channels,
+                output_channels
+            );
+        }
 
-                    let min = cfg_range.min_sample_rate().0;
-                    let max = cfg_range.max_sample_rate().0;
-                    let target = decoder_sample_rate.clamp(min, max);
+        let mut chosen: Option<StreamConfig> = None;
+        for cfg_range in ranges.iter().filter(|range| range.channels() == output_channels) {
+            let min = cfg_range.min_sample_rate().0;
+            let max = cfg_range.max_sample_rate().0;
+            let target = decoder_sample_rate.clamp(min, max);
 
-                    let stream_cfg = cfg_range.with_sample_rate(SampleRate(target)).config();
+            chosen = Some((*cfg_range).with_sample_rate(SampleRate(target)).config());
 
-                    chosen = Some(stream_cfg);
+            // Prefer exact match
+            if target == decoder_sample_rate {
+                break;
+            }
+        }
 
-                    // Prefer exact match
-                    if target == decoder_sample_rate {
-                        break;
-                    }
-                }
+        let cfg = chosen.unwrap_or(default_config);
+        if cfg.sample_rate.0 != decoder_sample_rate {
+            log::warn!(
+                "Decoder sample rate {}Hz not supported; using closest supported {}Hz",
+                decoder_sample_rate,
+                cfg.sample_rate.0
+            );
+        }
+        cfg
+    }
 
-                if let Some(cfg) = chosen {
-                    if cfg.sample_rate.0 != decoder_sample_rate {
-                        log::warn!(
-                            "Decoder sample rate {}Hz not supported; using closest supported {}Hz",
-                            decoder_sample_rate,
-                            cfg.sample_rate.0
-                        );
-                    }
-                    cfg
-                } else {
-                    log::warn!(
-                        "No supported config found for {} channels; using decoder sample rate {}Hz",
-                        channels,
-                        decoder_sample_rate
-                    );
-                    default_config
-                }
-            }
-            Err(err) => {
-                log::warn!(
-                    "Failed to query supported output configs ({}); using decoder sample rate {}Hz",
-                    err,
-                    decoder_sample_rate
-                );
-                default_config
-            }
+    /// Remix decoded samples to the output stream's channel count (0 = no stream yet)
+    fn remix_to_output(samples: Vec<f32>, channels: u16, output_channels: u16) -> Vec<f32> {
+        if output_channels == 0 || output_channels == channels {
+            samples
+        } else {
+            remix_channels(&samples, channels, output_channels)
         }
     }
 
-    fn start_decoder_thread(&mut self) {
+    fn start_decoder_thread(&mut self) -> Result<()> {
         // Stop any existing decoder thread
         self.stop_decoder_thread();
 
         let decoder = self.decoder.clone();
         let ring_buffer = self.ring_buffer.clone();
         let is_playing = self.is_playing.clone();
+        // A fresh flag per pass, so a thread abandoned by `release` is never revived
+        self.stop_decoder = Arc::new(AtomicBool::new(false));
         let stop_decoder = self.stop_decoder.clone();
         let sample_count = self.sample_count.clone();
         let callback_manager = self.callback_manager.clone();
         let state_container = self.state_container.clone();
         let output_sample_rate = self.output_sample_rate.clone();
+        let output_channels = self.output_channels.clone();
+        let pcm_taps = self.pcm_taps.clone();
+        let thread_priority = self.thread_priority;
+        let cue_points = self.cue_points.clone();
+        let power_mode = self.power_mode.clone();
+        let replay_gain = self.replay_gain.clone();
+        let fixed_latency = self.fixed_latency.clone();
+        let callback_samples = self.callback_samples.clone();
+
+        // A new pass starts from the current position, with this track's chapters
+        let start_ms = self.get_status().position_ms;
+        let chapters = self
+            .decoder
+            .lock()
+            .as_ref()
+            .map(|dec| dec.metadata.chapters.clone())
+            .unwrap_or_default();
+        cue_points.sync_chapters(&chapters, start_ms);
+        cue_points.rearm(start_ms);
+
+        let panic_playing = self.is_playing.clone();
+        let panic_callbacks = self.callback_manager.clone();
+        let panic_state = self.state_container.clone();
+
+        let handle = spawn_named(DECODE_THREAD, move || {
+            let _ = run_isolated(&panic_playing, &panic_callbacks, &panic_state, move || {
+                log::info!("Decoder thread started");
+                if let Err(e) = set_current_thread_priority(thread_priority) {
+                    log::warn!("{}", e);
+                }
 
-        stop_decoder.store(false, Ordering::Relaxed);
-
-        let handle = thread::spawn(move || {
-            log::info!("Decoder thread started");
-
-            let mut last_position_update = std::time::Instant::now();
+                let mut last_position_update = callback_manager.now();
 
-            loop {
-                if stop_decoder.load(Ordering::Relaxed) {
-                    log::info!("Decoder thread stopping");
-                    break;
-                }
+                loop {
+                    if stop_decoder.load(Ordering::Relaxed) {
+                        log::info!("Decoder thread stopping");
+                        break;
+                    }
 
-                if !is_playing.load(Ordering::Relaxed) {
-                    thread::sleep(std::time::Duration::from_millis(10));
-                    continue;
-                }
+                    if !is_playing.load(Ordering::Relaxed) {
+                        thread::sleep(power_mode.lock().idle_sleep());
+                        continue;
+                    }
 
-                // Decode next packet and get format info
-                let decode_result = {
-                    let mut decoder_lock = decoder.lock();
-                    if let Some(ref mut dec) = *decoder_lock {
-                        let sample_rate = dec.format.sample_rate;
-                        let duration_ms = dec.format.duration_ms;
-                        let channels = dec.format.channels;
-                        match dec.decode_next() {
-                            Ok(Some(samples)) => {
-                                Some((samples, sample_rate, duration_ms, channels))
-                            }
-                            Ok(None) => None,
-                            Err(e) => {
-                                log::error!("Decoding error: {}", e);
-                                callback_manager.dispatch_event(CallbackEvent::Error {
-                                    message: e.to_string(),
-                                });
-                                is_playing.store(false, Ordering::Relaxed);
-                                state_container.set_state(PlayerState::Error);
-                                return; // Exit decode_result block with implicit None
+                    // Decode next packet and get format info
+                    let decode_result = {
+                        let mut decoder_lock = decoder.lock();
+                        if let Some(ref mut dec) = *decoder_lock {
+                            dec.set_replay_gain(*replay_gain.lock());
+                            let decoded = match dec.decode_next() {
+                                // One bad packet can leave the codec inconsistent: reset it and
+                                // carry on from the next packet; a second failure is reported
+                                Err(e @ AudioError::DecodingError(_)) => {
+                                    log::warn!("{}; resetting codec", e);
+                                    dec.reset_codec();
+                                    dec.decode_next()
+                                }
+                                decoded => decoded,
+                            };
+                            dec.dispatch_changes(&callback_manager);
+                            let sample_rate = dec.format.sample_rate;
+                            let status = dec.status();
+                            let channels = dec.format.channels;
+                            match decoded {
+                                Ok(Some(samples)) => {
+                                    Some((samples, sample_rate, status, channels))
+                                }
+                                Ok(None) => None,
+                                Err(e) => {
+                                    log::error!("Decoding error: {}", e);
+                                    callback_manager.dispatch_event(CallbackEvent::Error {
+                                        message: e.to_string(),
+                                    });
+                                    is_playing.store(false, Ordering::Relaxed);
+                                    state_container.set_state(PlayerState::Error);
+                                    return; // Exit decode_result block with implicit None
+                                }
                             }
+                        } else {
+                            return; // No decoder, exit decode_result block
                         }
-                    } else {
-                        return; // No decoder, exit decode_result block
-                    }
-                }; // decoder_lock is released here
-
-                match decode_result {
-                    Some((samples, sample_rate, duration_ms, channels)) => {
-                        // Resample if device sample rate differs from decoded audio
-                        let target_rate = effective_output_rate(
-                            *output_sample_rate.lock(),
-                            Some(sample_rate),
-                            sample_rate,
-                        );
-                        let processed = if sample_rate != target_rate {
-                            log::debug!(
-                                "Resampling from {}Hz to {}Hz to match device",
+                    }; // decoder_lock is released here
+
+                    match decode_result {
+                        Some((samples, sample_rate, status, channels)) => {
+                            pcm_taps.publish(&samples);
+
+                            // Resample if device sample rate differs from decoded audio
+                            let target_rate = effective_output_rate(
+                                *output_sample_rate.lock(),
+                                Some(sample_rate),
                                 sample_rate,
-                                target_rate
                             );
-                            Self::resample_linear(&samples, sample_rate, target_rate, channels)
-                        } else {
-                            samples
-                        };
-
-                        // Write to ring buffer (decoder lock already released)
-                        let mut buffer = ring_buffer.lock();
-                        let mut written = 0;
-                        while written < processed.len() {
-                            let w = buffer.write(&processed[written..]);
-                            if w == 0 {
-                                // Buffer is full - sleep based on fullness
-                                let fullness = buffer.fullness();
-                                drop(buffer);
-
-                                // Smart sleep: longer sleep when buffer is fuller
-                                let sleep_ms = if fullness > 0.9 {
-                                    15 // Buffer >90% full: long sleep
-                                } else if fullness > 0.7 {
-                                    10 // Buffer >70% full: medium sleep
-                                } else {
-                                    5 // Buffer <70% full: short sleep
-                                };
-                                thread::sleep(std::time::Duration::from_millis(sleep_ms));
-                                buffer = ring_buffer.lock();
+                            let processed = if sample_rate != target_rate {
+                                log::debug!(
+                                    "Resampling from {}Hz to {}Hz to match device",
+                                    sample_rate,
+                                    target_rate
+                                );
+                                Self::resample_linear(&samples, sample_rate, target_rate, channels)
                             } else {
-                                written += w;
+                                samples
+                            };
+                            let processed =
+                                Self::remix_to_output(processed, channels, *output_channels.lock());
+
+                            // Write to ring buffer (decoder lock already released)
+                            let mut buffer = ring_buffer.lock();
+                            // A callback larger than the ring could never be filled: grow it
+                            buffer.fit_callback(callback_samples.load(Ordering::Relaxed));
+                            let mut written = 0;
+                            while written < processed.len() {
+                                let w = buffer.write(&processed[written..]);
+                                if w == 0 {
+                                    // Nothing drains a full ring once playback stops: give up the packet
+                                    if stop_decoder.load(Ordering::Relaxed) {
+                                        break;
+                                    }
+                                    // Buffer is full - sleep based on fullness and power mode
+                                    let fullness = buffer.fullness();
+                                    let frame_channels = match *output_channels.lock() {
+                                        0 => channels,
+                                        n => n,
+                                    };
+                                    let buffered =
+                                        buffered_ms(buffer.available_read(), frame_channels, target_rate);
+                                    drop(buffer);
+
+                                    let sleep = match *fixed_latency.lock() {
+                                        Some(fixed) => fixed.refill_sleep(),
+                                        None => power_mode.lock().full_buffer_sleep(fullness, buffered),
+                                    };
+                                    thread::sleep(sleep);
+                                    buffer = ring_buffer.lock();
+                                } else {
+                                    written += w;
+                                }
                             }
-                        }
-                        drop(buffer);
+                            drop(buffer);
 
-                        // Update position periodically
-                        if last_position_update.elapsed().as_millis()
-                            >= POSITION_UPDATE_INTERVAL_MS as u128
-                        {
                             let count = *sample_count.lock();
                             let effective_rate = effective_output_rate(
                                 *output_sample_rate.lock(),
                                 Some(sample_rate),
                                 sample_rate,
                             ) as u64;
-                            let position_ms = if effective_rate > 0 {
-                                (count * 1000) / effective_rate
-                            } else {
-                                0
+                            let position_ms = (count * 1000).checked_div(effective_rate).unwrap_or(0);
+                            let position_ms = match *fixed_latency.lock() {
+                                Some(fixed) => fixed.audible_position_ms(position_ms),
+                                None => position_ms,
                             };
-                            callback_manager.dispatch_event(CallbackEvent::PositionChanged {
-                                position_ms,
-                                duration_ms,
-                            });
-                            last_position_update = std::time::Instant::now();
+                            let position_ms = status.source_position_ms(position_ms);
+                            cue_points.dispatch_reached(position_ms, &callback_manager);
+
+                            // Update position periodically
+                            let interval_ms = power_mode
+                                .lock()
+                                .position_interval_ms(callback_manager.position_update_interval_ms());
+                            let now = callback_manager.now();
+                            let since_update = now.saturating_duration_since(last_position_update);
+                            if since_update.as_millis() >= interval_ms as u128 {
+                                callback_manager.dispatch_event(CallbackEvent::PositionChanged {
+                                    position_ms,
+                                    duration_ms: status.duration_ms,
+                                });
+                                last_position_update = now;
+                            }
+                        }
+                        None => {
+                            // Playback completed
+                            log::info!("Playback completed");
+                            is_playing.store(false, Ordering::Relaxed);
+                            callback_manager.dispatch_event(CallbackEvent::PlaybackCompleted);
+                            state_container.set_state(PlayerState::Stopped);
+                            break;
                         }
-                    }
-                    None => {
-                        // Playback completed
-                        log::info!("Playback completed");
-                        is_playing.store(false, Ordering::Relaxed);
-                        callback_manager.dispatch_event(CallbackEvent::PlaybackCompleted);
-                        state_container.set_state(PlayerState::Stopped);
-                        break;
                     }
                 }
-            }
 
-            log::info!("Decoder thread exited");
-        });
+                log::info!("Decoder thread exited");
+            });
+        })?;
 
         self.decoder_thread = Some(handle);
+        Ok(())
     }
 
     fn stop_decoder_thread(&mut self) {
@@ -375,6 +762,69 @@ impl IOSAudioPlayer {
         }
     }
 
+    /// Stop the decoder thread, waiting at most `timeout` for it (it may be stuck in network
+    /// I/O or in an app callback); a thread still running then is left to exit on its own
+    fn stop_decoder_thread_within(&mut self, timeout: std::time::Duration) {
+        self.stop_decoder.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.decoder_thread.take() {
+            if !join_within(handle, timeout) {
+                log::warn!("Decoder thread did not stop within {:?}; abandoning it", timeout);
+            }
+        }
+    }
+
+    /// Load encoded audio held in memory (`load_buffer`, data: URIs)
+    fn load_memory(&mut self, buffer: &[u8], hint: Hint) -> Result<()> {
+        log::info!("Loading audio from buffer: {} bytes", buffer.len());
+        self.current_source = Some(AudioSource::Buffer(buffer.to_vec()));
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
+
+        self.state_container.set_state(PlayerState::Loading);
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state: PlayerState::Idle,
+                new_state: PlayerState::Loading,
+            });
+
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread();
+        self.cancel_prepare();
+        self.ring_buffer.lock().clear();
+        *self.sample_count.lock() = 0;
+
+        let decoder = AudioDecoder::from_buffer_with_hint(buffer.to_vec(), hint)?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
+        let sample_rate = decoder.format.sample_rate;
+        let channels = decoder.format.channels;
+
+        let stream_started = self.callback_manager.now();
+        self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
+        *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
+
+        // Optimize buffer size based on audio duration
+        self.optimize_buffer_size();
+
+        // Pre-buffer audio to reduce playback latency
+        self.prebuffer()?;
+
+        self.state_container.set_state(PlayerState::Ready);
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state: PlayerState::Loading,
+                new_state: PlayerState::Ready,
+            });
+
+        log::info!("Audio buffer loaded successfully");
+        Ok(())
+    }
+
     /// Optimize ring buffer size based on audio duration
     /// Adjusts buffer to use between MIN_BUFFER_DURATION_SECS and MAX_BUFFER_DURATION_SECS
     fn optimize_buffer_size(&mut self) {
@@ -385,18 +835,26 @@ impl IOSAudioPlayer {
                 Some(decoder.format.sample_rate),
                 decoder.format.sample_rate,
             );
-            let channels = decoder.format.channels;
+            // The ring holds audio already remixed to the stream's layout
+            let channels = match *self.output_channels.lock() {
+                0 => decoder.format.channels,
+                output_channels => output_channels,
+            };
             let duration_ms = decoder.format.duration_ms;
             let duration_secs = duration_ms / 1000;
 
             // Calculate optimal buffer duration
             let buffer_duration_secs = duration_secs
                 .max(MIN_BUFFER_DURATION_SECS)
-                .min(MAX_BUFFER_DURATION_SECS);
+                .min(self.power_mode.lock().max_buffer_secs(MAX_BUFFER_DURATION_SECS));
 
             // Calculate buffer size in samples
-            let optimal_size =
-                (target_rate as u64 * channels as u64 * buffer_duration_secs) as usize;
+            let optimal_size = match *self.fixed_latency.lock() {
+                Some(fixed) => fixed.ring_samples(channels),
+                None => (target_rate as u64 * channels as u64 * buffer_duration_secs) as usize,
+            };
+            let callback_samples = self.callback_samples.load(Ordering::Relaxed);
+            let optimal_size = optimal_size.max(AudioRingBuffer::min_size_for_callback(callback_samples));
 
             let current_size = self.ring_buffer.lock().size();
 
@@ -457,10 +915,127 @@ impl IOSAudioPlayer {
         output
     }
 
+    /// Install a decoder opened by `prepare` and write its pre-decoded samples to the ring buffer
+    fn install_prepared(&mut self, mut prepared: PreparedSource) -> Result<()> {
+        let sample_rate = prepared.decoder.format.sample_rate;
+        let channels = prepared.decoder.format.channels;
+        // The pre-buffer was decoded before normalization was known
+        prepared.decoder.set_replay_gain(*self.replay_gain.lock());
+        prepared.decoder.apply_replay_gain(&mut prepared.prebuffer);
+
+        self.initialize_audio_stream(sample_rate, channels)?;
+        *self.decoder.lock() = Some(prepared.decoder);
+        self.optimize_buffer_size();
+
+        let target_rate = effective_output_rate(
+            *self.output_sample_rate.lock(),
+            Some(sample_rate),
+            sample_rate,
+        );
+        self.pcm_taps.publish(&prepared.prebuffer);
+        let processed = if sample_rate != target_rate {
+            Self::resample_linear(&prepared.prebuffer, sample_rate, target_rate, channels)
+        } else {
+            prepared.prebuffer
+        };
+        let processed = Self::remix_to_output(processed, channels, *self.output_channels.lock());
+        let written = self.ring_buffer.lock().write(&processed);
+
+        log::debug!(
+            "Installed prepared source: {} of {} pre-buffered samples",
+            written,
+            processed.len()
+        );
+        Ok(())
+    }
+
+    /// Reposition the decoder with `seek`, which returns the new output sample count
+    /// given the effective output rate
+    fn seek_decoder(&mut self, seek: impl FnOnce(&mut AudioDecoder, u64) -> Result<u64>) -> Result<()> {
+        let seek_started = self.callback_manager.now();
+        let was_playing = self.is_playing.load(Ordering::Relaxed);
+
+        if was_playing {
+            self.is_playing.store(false, Ordering::Relaxed);
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        self.ring_buffer.lock().clear();
+
+        let mut decoder_lock = self.decoder.lock();
+        if let Some(ref mut dec) = *decoder_lock {
+            let effective_rate = effective_output_rate(
+                *self.output_sample_rate.lock(),
+                Some(dec.format.sample_rate),
+                dec.format.sample_rate,
+            ) as u64;
+            let new_sample_count = match seek(dec, effective_rate) {
+                Ok(count) => count,
+                Err(e) => {
+                    // A refused seek leaves the decoder where it was: carry on playing
+                    if was_playing {
+                        self.is_playing.store(true, Ordering::Relaxed);
+                    }
+                    return Err(e);
+                }
+            };
+            *self.sample_count.lock() = new_sample_count;
+            self.cue_points.rearm(new_sample_count * 1000 / effective_rate.max(1));
+        } else {
+            return Err(AudioError::PlaybackError(
+                "No decoder available".to_string(),
+            ));
+        }
+        drop(decoder_lock);
+
+        if was_playing {
+            self.is_playing.store(true, Ordering::Relaxed);
+        }
+
+        self.load_timer.finish(LoadPhase::Seek, seek_started, &self.callback_manager);
+        log::info!("Seek completed");
+        Ok(())
+    }
+
+    /// Discard any background preparation for the current source
+    fn cancel_prepare(&mut self) {
+        if let Some(task) = self.prepared.take() {
+            task.cancel();
+        }
+    }
+
+    /// Fail on an idle player with no output engine (never loaded, or released), where a
+    /// setting would be silently dropped
+    fn require_engine(&self, operation: &str) -> Result<()> {
+        if self.state_container.get_state() == PlayerState::Idle && self.audio_stream.lock().is_none() {
+            return Err(AudioError::InvalidState(format!(
+                "Cannot {} on an idle player; load a source first",
+                operation
+            )));
+        }
+        Ok(())
+    }
+
+    /// Seek a freshly loaded decoder to the requested start, before anything is pre-buffered
+    fn seek_to_start(&mut self, start_position_ms: Option<u64>) -> Result<()> {
+        let Some(position_ms) = start_position_ms.filter(|&ms| ms > 0) else {
+            return Ok(());
+        };
+        log::info!("Starting at {} ms", position_ms);
+        self.seek_decoder(|dec, effective_rate| {
+            let duration_ms = dec.format.duration_ms;
+            let start_ms = if duration_ms > 0 { position_ms.min(duration_ms) } else { position_ms };
+            dec.seek(start_ms)?;
+            Ok((start_ms * effective_rate) / 1000)
+        })
+    }
+
     /// Pre-buffer audio data to reduce initial playback latency
     fn prebuffer(&mut self) -> Result<()> {
+        let prebuffer_started = self.callback_manager.now();
         let mut decoder_lock = self.decoder.lock();
         if let Some(ref mut decoder) = *decoder_lock {
+            decoder.set_replay_gain(*self.replay_gain.lock());
             let sample_rate = decoder.format.sample_rate;
             let channels = decoder.format.channels;
             let target_rate = effective_output_rate(
@@ -469,9 +1044,17 @@ impl IOSAudioPlayer {
                 sample_rate,
             );
 
-            // Calculate target samples for pre-buffering at the output rate
-            let target_samples =
-                ((PRE_BUFFER_MS * target_rate as u64) / 1000) as usize * channels as usize;
+            let output_channels = match *self.output_channels.lock() {
+                0 => channels,
+                n => n,
+            } as usize;
+
+            // Calculate target samples for pre-buffering in the output format; a fixed latency
+            // starts with the ring at its full depth
+            let target_samples = match *self.fixed_latency.lock() {
+                Some(fixed) => fixed.ring_samples(output_channels as u16),
+                None => ((PRE_BUFFER_MS * target_rate as u64) / 1000) as usize * output_channels,
+            };
             let mut total_buffered = 0;
 
             log::debug!(
@@ -484,11 +1067,18 @@ impl IOSAudioPlayer {
             while total_buffered < target_samples {
                 match decoder.decode_next() {
                     Ok(Some(samples)) => {
+                        if total_buffered == 0 {
+                            let callbacks = &self.callback_manager;
+                            self.load_timer.finish(LoadPhase::FirstPacket, prebuffer_started, callbacks);
+                        }
+                        self.pcm_taps.publish(&samples);
                         let processed = if sample_rate != target_rate {
                             Self::resample_linear(&samples, sample_rate, target_rate, channels)
                         } else {
                             samples
                         };
+                        let processed =
+                            Self::remix_to_output(processed, channels, *self.output_channels.lock());
 
                         let mut buffer = self.ring_buffer.lock();
                         let written = buffer.write(&processed);
@@ -515,28 +1105,23 @@ impl IOSAudioPlayer {
                 }
             }
 
-            log::debug!(
-                "Pre-buffered {} samples ({}ms)",
-                total_buffered,
-                (total_buffered / channels as usize) * 1000 / target_rate as usize
-            );
+            let buffered_ms = ((total_buffered / output_channels) as u64 * 1000) / target_rate as u64;
+            log::debug!("Pre-buffered {} samples ({}ms)", total_buffered, buffered_ms);
+            self.load_timer.finish(LoadPhase::Prebuffer, prebuffer_started, &self.callback_manager);
+            self.callback_manager
+                .dispatch_event(CallbackEvent::PrebufferComplete { buffered_ms });
         }
         drop(decoder_lock);
         Ok(())
     }
 }
 
-// SAFETY: IOSAudioPlayer is safe to send between threads because:
-// 1. The audio_stream (cpal::Stream) is only accessed from the thread that created it
-// 2. All other fields are already Send+Sync (Arc, Mutex, AtomicBool, etc.)
-// 3. The AudioPlayer trait methods are always called from the same thread
-// 4. We use proper synchronization (Arc<Mutex>) for shared state
-unsafe impl Send for IOSAudioPlayer {}
-unsafe impl Sync for IOSAudioPlayer {}
-
 impl AudioPlayer for IOSAudioPlayer {
     fn load_file(&mut self, path: &str) -> Result<()> {
         log::info!("Loading audio file: {}", path);
+        self.current_source = Some(AudioSource::File(path.to_string()));
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
 
         self.state_container.set_state(PlayerState::Loading);
         self.callback_manager
@@ -547,15 +1132,24 @@ impl AudioPlayer for IOSAudioPlayer {
 
         self.is_playing.store(false, Ordering::Relaxed);
         self.stop_decoder_thread();
+        self.cancel_prepare();
         self.ring_buffer.lock().clear();
         *self.sample_count.lock() = 0;
 
         let decoder = AudioDecoder::from_file(path)?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
         let sample_rate = decoder.format.sample_rate;
         let channels = decoder.format.channels;
 
+        let stream_started = self.callback_manager.now();
         self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
         *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
 
         // Optimize buffer size based on audio duration
         self.optimize_buffer_size();
@@ -575,7 +1169,21 @@ impl AudioPlayer for IOSAudioPlayer {
     }
 
     fn load_url(&mut self, url: &str) -> Result<()> {
+        // file: URLs and plain paths (as file pickers hand them out) play from disk
+        if let Some(path) = local_path(url) {
+            return self.load_file(&path);
+        }
+        // Small embedded audio: decode the payload in memory instead of fetching anything
+        if let Some(data_uri) = parse_data_uri(url)? {
+            log::info!("Loading audio from {} data URI", data_uri.mime_type);
+            return self.load_memory(&data_uri.data, data_uri.hint());
+        }
+        // Reject malformed URLs here, before the current source is stopped
+        validate_url(url)?;
         log::info!("Loading audio from URL (streaming): {}", url);
+        self.current_source = Some(AudioSource::Url(url.to_string()));
+        let start_position_ms = self.start_position_ms.take();
+        let load_started = self.load_timer.begin_load(&self.callback_manager);
 
         self.state_container.set_state(PlayerState::Loading);
         self.callback_manager
@@ -586,17 +1194,21 @@ impl AudioPlayer for IOSAudioPlayer {
 
         self.is_playing.store(false, Ordering::Relaxed);
         self.stop_decoder_thread();
+        self.cancel_prepare();
         self.ring_buffer.lock().clear();
         *self.sample_count.lock() = 0;
 
-        // Create hint from URL
-        let hint = AudioDecoder::create_hint_from_url(url);
-
         // Use HTTP Range-based source for true streaming without downloading entire file
         // This supports both Fast Start and Non-Fast Start M4A files
         log::info!("Using HTTP Range source (on-demand download)");
-        let source = crate::http_range_source::HttpRangeSource::new(url.to_string())?;
-        let decoder = AudioDecoder::from_streaming_source(Box::new(source), hint)?;
+        let callbacks = &self.callback_manager;
+        let decoder = AudioDecoder::from_url_with_stages(url, |stage| {
+            callbacks.dispatch_event(CallbackEvent::StreamPreparing { stage })
+        })?;
+        self.load_timer.finish(LoadPhase::Probe, load_started, &self.callback_manager);
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
 
         let sample_rate = decoder.format.sample_rate;
         let channels = decoder.format.channels;
@@ -607,8 +1219,11 @@ impl AudioPlayer for IOSAudioPlayer {
             channels
         );
 
+        let stream_started = self.callback_manager.now();
         self.initialize_audio_stream(sample_rate, channels)?;
+        self.load_timer.finish(LoadPhase::StreamBuild, stream_started, &self.callback_manager);
         *self.decoder.lock() = Some(decoder);
+        self.seek_to_start(start_position_ms)?;
 
         // Optimize buffer size based on audio duration
         self.optimize_buffer_size();
@@ -628,36 +1243,154 @@ impl AudioPlayer for IOSAudioPlayer {
     }
 
     fn load_buffer(&mut self, buffer: &[u8]) -> Result<()> {
-        log::info!("Loading audio from buffer: {} bytes", buffer.len());
+        self.load_memory(buffer, Hint::new())
+    }
+
+    fn set_start_position(&mut self, position_ms: u64) -> Result<()> {
+        self.start_position_ms = Some(position_ms);
+        Ok(())
+    }
+
+    fn prepare(&mut self, source: AudioSource) -> Result<()> {
+        log::info!("Preparing audio source in background");
 
+        let old_state = self.state_container.get_state();
         self.state_container.set_state(PlayerState::Loading);
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state,
+                new_state: PlayerState::Loading,
+            });
 
         self.is_playing.store(false, Ordering::Relaxed);
         self.stop_decoder_thread();
+        self.cancel_prepare();
         self.ring_buffer.lock().clear();
         *self.sample_count.lock() = 0;
+        *self.decoder.lock() = None;
+
+        self.current_source = Some(source.clone());
+
+        // Reuse the warmed-up decoder if this is the source passed to prepare_next
+        let task = match self.prepared_next.take() {
+            Some(next) if next.source() == &source => next,
+            Some(next) => {
+                next.cancel();
+                PrepareTask::spawn(source, PRE_BUFFER_MS)?
+            }
+            None => PrepareTask::spawn(source, PRE_BUFFER_MS)?,
+        };
+
+        let state_container = self.state_container.clone();
+        let callback_manager = self.callback_manager.clone();
+        task.set_on_ready(move |outcome| match outcome {
+            Ok(info) => {
+                callback_manager.dispatch_event(CallbackEvent::Prepared { format: info.format });
+                callback_manager.dispatch_event(CallbackEvent::PrebufferComplete {
+                    buffered_ms: info.buffered_ms,
+                });
+                state_container.set_state(PlayerState::Ready);
+                callback_manager.dispatch_event(CallbackEvent::StateChanged {
+                    old_state: PlayerState::Loading,
+                    new_state: PlayerState::Ready,
+                });
+                log::info!("Audio source prepared");
+            }
+            Err(e) => {
+                log::error!("Failed to prepare audio source: {}", e);
+                state_container.set_state(PlayerState::Error);
+                callback_manager.dispatch_event(CallbackEvent::Error {
+                    message: e.to_string(),
+                });
+            }
+        });
+
+        self.prepared = Some(task);
+        Ok(())
+    }
+
+    fn load_with_resume(&mut self, source: AudioSource, position_ms: u64) -> Result<()> {
+        log::info!("Loading audio source, resuming at {} ms", position_ms);
+
+        let old_state = self.state_container.get_state();
+        self.state_container.set_state(PlayerState::Loading);
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state,
+                new_state: PlayerState::Loading,
+            });
+
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread();
+        self.cancel_prepare();
+        self.ring_buffer.lock().clear();
+        self.current_source = Some(source.clone());
+        self.start_position_ms = None;
 
-        let decoder = AudioDecoder::from_buffer(buffer.to_vec())?;
+        // Seek before pre-buffering so nothing is primed from the start of the file
+        let (decoder, start_ms) = open_decoder_at(&source, position_ms)?;
+        self.callback_manager.dispatch_event(CallbackEvent::Prepared {
+            format: decoder.metadata.format_info.clone(),
+        });
         let sample_rate = decoder.format.sample_rate;
         let channels = decoder.format.channels;
 
         self.initialize_audio_stream(sample_rate, channels)?;
         *self.decoder.lock() = Some(decoder);
 
-        // Optimize buffer size based on audio duration
-        self.optimize_buffer_size();
+        let effective_rate = effective_output_rate(
+            *self.output_sample_rate.lock(),
+            Some(sample_rate),
+            sample_rate,
+        ) as u64;
+        *self.sample_count.lock() = (start_ms * effective_rate) / 1000;
 
-        // Pre-buffer audio to reduce playback latency
+        self.optimize_buffer_size();
         self.prebuffer()?;
 
         self.state_container.set_state(PlayerState::Ready);
-        log::info!("Audio buffer loaded successfully");
+        self.callback_manager
+            .dispatch_event(CallbackEvent::StateChanged {
+                old_state: PlayerState::Loading,
+                new_state: PlayerState::Ready,
+            });
+
+        log::info!("Audio source loaded at {} ms", start_ms);
+        Ok(())
+    }
+
+    fn get_resume_token(&self) -> Option<PlaybackToken> {
+        let source_id = self.current_source.as_ref()?.id();
+        Some(PlaybackToken {
+            source_id,
+            position_ms: self.get_status().position_ms,
+        })
+    }
+
+    fn prepare_next(&mut self, source: AudioSource) -> Result<()> {
+        if let Some(next) = &self.prepared_next {
+            if next.source() == &source {
+                return Ok(());
+            }
+        }
+
+        log::info!("Warming up next audio source");
+        self.prepared_next = Some(PrepareTask::spawn(source, PRE_BUFFER_MS)?);
         Ok(())
     }
 
     fn play(&mut self) -> Result<()> {
         log::info!("Starting playback");
 
+        // Finish a pending prepare (only blocks if the background work is still running)
+        if let Some(task) = self.prepared.take() {
+            if !task.is_finished() {
+                log::debug!("Waiting for background prepare to finish");
+            }
+            let prepared = task.wait()?;
+            self.install_prepared(prepared)?;
+        }
+
         let current_state = self.state_container.get_state();
         if current_state != PlayerState::Ready && current_state != PlayerState::Paused {
             return Err(AudioError::InvalidState(format!(
@@ -668,7 +1401,7 @@ impl AudioPlayer for IOSAudioPlayer {
 
         // Start decoder thread first (if not already running)
         if self.decoder_thread.is_none() {
-            self.start_decoder_thread();
+            self.start_decoder_thread()?;
         }
 
         // Enable playback flag before starting stream
@@ -678,9 +1411,7 @@ impl AudioPlayer for IOSAudioPlayer {
         // Start audio stream
         let stream_guard = self.audio_stream.lock();
         if let Some(ref stream) = *stream_guard {
-            stream
-                .play()
-                .map_err(|e| AudioError::PlaybackError(format!("Failed to start stream: {}", e)))?;
+            stream.play()?;
         } else {
             return Err(AudioError::PlaybackError(
                 "No audio stream available".to_string(),
@@ -714,9 +1445,7 @@ impl AudioPlayer for IOSAudioPlayer {
 
         let stream_guard = self.audio_stream.lock();
         if let Some(ref stream) = *stream_guard {
-            stream
-                .pause()
-                .map_err(|e| AudioError::PlaybackError(format!("Failed to pause stream: {}", e)))?;
+            stream.pause()?;
         }
         drop(stream_guard);
 
@@ -739,19 +1468,19 @@ impl AudioPlayer for IOSAudioPlayer {
 
         let stream_guard = self.audio_stream.lock();
         if let Some(ref stream) = *stream_guard {
-            stream
-                .pause()
-                .map_err(|e| AudioError::PlaybackError(format!("Failed to stop stream: {}", e)))?;
+            stream.pause()?;
         }
         drop(stream_guard);
 
         self.ring_buffer.lock().clear();
         *self.sample_count.lock() = 0;
+        *self.scrub.lock() = None;
 
+        let old_state = self.state_container.get_state();
         self.state_container.set_state(PlayerState::Stopped);
         self.callback_manager
             .dispatch_event(CallbackEvent::StateChanged {
-                old_state: self.state_container.get_state(),
+                old_state,
                 new_state: PlayerState::Stopped,
             });
 
@@ -761,9 +1490,46 @@ impl AudioPlayer for IOSAudioPlayer {
 
     fn seek(&mut self, position_ms: u64) -> Result<()> {
         log::info!("Seeking to {} ms", position_ms);
+        self.seek_decoder(|dec, effective_rate| {
+            dec.seek(position_ms)?;
+            Ok((position_ms * effective_rate) / 1000)
+        })
+    }
+
+    fn seek_with_mode(&mut self, position_ms: u64, mode: SeekMode) -> Result<()> {
+        log::info!("Seeking to {} ms ({:?})", position_ms, mode);
+        self.seek_decoder(|dec, effective_rate| {
+            let landed_ms = dec.seek_with_mode(position_ms, mode)?;
+            Ok((landed_ms * effective_rate) / 1000)
+        })
+    }
+
+    fn set_looping(&mut self, enabled: bool) -> Result<()> {
+        log::info!("Sample-accurate looping: {}", enabled);
+        let position_ms = self.get_status().position_ms;
+        self.seek_decoder(|dec, effective_rate| {
+            dec.set_sample_loop(enabled, position_ms)?;
+            Ok((position_ms * effective_rate) / 1000)
+        })
+    }
 
+    fn seek_to_sample(&mut self, frame_index: u64) -> Result<()> {
+        log::info!("Seeking to frame {}", frame_index);
+        self.seek_decoder(|dec, effective_rate| {
+            dec.seek_to_sample(frame_index)?;
+            Ok((frame_index * effective_rate) / dec.format.sample_rate.max(1) as u64)
+        })
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        log::info!("Flushing audio pipeline");
+
+        let position_ms = self.get_status().position_ms;
         let was_playing = self.is_playing.load(Ordering::Relaxed);
 
+        self.callback_manager
+            .dispatch_event(CallbackEvent::BufferingChanged { buffering: true });
+
         if was_playing {
             self.is_playing.store(false, Ordering::Relaxed);
             thread::sleep(std::time::Duration::from_millis(10));
@@ -771,32 +1537,136 @@ impl AudioPlayer for IOSAudioPlayer {
 
         self.ring_buffer.lock().clear();
 
-        let mut decoder_lock = self.decoder.lock();
-        if let Some(ref mut dec) = *decoder_lock {
-            dec.seek(position_ms)?;
-            let effective_rate = effective_output_rate(
-                *self.output_sample_rate.lock(),
-                Some(dec.format.sample_rate),
-                dec.format.sample_rate,
-            ) as u64;
-            let new_sample_count = (position_ms * effective_rate) / 1000;
-            *self.sample_count.lock() = new_sample_count;
-        } else {
-            return Err(AudioError::PlaybackError(
+        // The decoder has run ahead of the output; rewind it to what was actually heard
+        let result = match self.decoder.lock().as_mut() {
+            Some(dec) => dec.seek(position_ms),
+            None => Err(AudioError::PlaybackError(
                 "No decoder available".to_string(),
-            ));
-        }
-        drop(decoder_lock);
+            )),
+        };
+        let result = result.and_then(|_| self.prebuffer());
 
         if was_playing {
             self.is_playing.store(true, Ordering::Relaxed);
         }
 
-        log::info!("Seek completed");
+        self.callback_manager
+            .dispatch_event(CallbackEvent::BufferingChanged { buffering: false });
+
+        result
+    }
+
+    fn add_pcm_tap(&mut self, tap: PcmTap) -> Result<()> {
+        self.pcm_taps.add(tap)
+    }
+
+    fn add_output_tap(&mut self, tap: OutputTap) -> Result<()> {
+        self.output_taps.add(tap)
+    }
+
+    fn set_limiter_ceiling(&mut self, ceiling_db: Option<f32>) -> Result<()> {
+        self.limiter.lock().set_ceiling_db(ceiling_db)
+    }
+
+    fn duck(&mut self, level: f32, fade_ms: u64) -> Result<()> {
+        let level = clamp_duck_level(level)?;
+        self.duck.lock().fade_to(level, fade_ms);
+        Ok(())
+    }
+
+    fn unduck(&mut self, fade_ms: u64) -> Result<()> {
+        self.duck.lock().fade_to(1.0, fade_ms);
         Ok(())
     }
 
+    fn write_cover_art(&self, path: &str) -> Result<()> {
+        match self.decoder.lock().as_ref() {
+            Some(decoder) => decoder.write_cover_art(path),
+            None => Err(AudioError::InvalidState("No audio loaded".to_string())),
+        }
+    }
+
+    fn set_target_sample_rate(&mut self, sample_rate: Option<u32>) -> Result<()> {
+        if sample_rate == Some(0) {
+            return Err(AudioError::InvalidState("Target sample rate must be positive".to_string()));
+        }
+        self.target_sample_rate = sample_rate;
+        Ok(())
+    }
+
+    fn set_power_mode(&mut self, mode: PowerMode) -> Result<()> {
+        log::info!("Power mode: {:?}", mode);
+        *self.power_mode.lock() = mode;
+        Ok(())
+    }
+
+    fn set_replay_gain(&mut self, settings: ReplayGainSettings) -> Result<()> {
+        settings.validate()?;
+        log::info!("ReplayGain: {:?}", settings);
+        *self.replay_gain.lock() = settings;
+        Ok(())
+    }
+
+    fn set_thread_priority(&mut self, priority: ThreadPriority) -> Result<()> {
+        self.thread_priority = priority;
+        Ok(())
+    }
+
+    fn wait_until_complete(&self, timeout: Option<std::time::Duration>) -> Result<()> {
+        self.state_container.wait_until_complete(timeout)
+    }
+
+    fn add_cue_point(&mut self, id: &str, position_ms: u64) -> Result<()> {
+        self.cue_points.add(id, position_ms, self.get_status().position_ms);
+        Ok(())
+    }
+
+    fn remove_cue_point(&mut self, id: &str) -> Result<()> {
+        self.cue_points.remove(id);
+        Ok(())
+    }
+
+    fn chapters(&self) -> Vec<crate::metadata::Chapter> {
+        self.decoder
+            .lock()
+            .as_ref()
+            .map(|dec| dec.metadata.chapters.clone())
+            .unwrap_or_default()
+    }
+
+    fn set_chapter_cue_points(&mut self, enabled: bool) -> Result<()> {
+        let chapters = self.chapters();
+        self.cue_points.set_chapters_enabled(enabled, &chapters, self.get_status().position_ms);
+        Ok(())
+    }
+
+    fn set_latency_mode(&mut self, mode: LatencyMode) -> Result<()> {
+        if mode == LatencyMode::Fixed(0) {
+            return Err(AudioError::InvalidState("Fixed latency must be positive".to_string()));
+        }
+        log::info!("Latency mode: {:?} (applies from the next load)", mode);
+        self.latency_mode = mode;
+        Ok(())
+    }
+
+    fn output_latency_ms(&self) -> u64 {
+        if let Some(fixed) = *self.fixed_latency.lock() {
+            return fixed.latency_ms();
+        }
+        let buffered = self.ring_buffer.lock().available_read();
+        buffered_ms(buffered, *self.output_channels.lock(), *self.output_sample_rate.lock())
+    }
+
+    fn set_transcript_url(&mut self, url: Option<&str>) -> Result<()> {
+        self.transcript.set_url(url)
+    }
+
+    fn current_transcript_cue(&self, position_ms: u64) -> Option<TranscriptCue> {
+        self.transcript.current_cue(position_ms)
+    }
+
     fn set_volume(&mut self, volume: f32) -> Result<()> {
+        self.require_engine("set volume")?;
         let clamped = volume.clamp(0.0, 1.0);
         *self.volume.lock() = clamped;
 
@@ -808,7 +1678,9 @@ impl AudioPlayer for IOSAudioPlayer {
     }
 
     fn set_playback_rate(&mut self, rate: f32) -> Result<()> {
-        *self.playback_rate.lock() = rate;
+        self.require_engine("set playback rate")?;
+        let rate = clamp_playback_rate(rate)?;
+        self.playback_rate.lock().set_target(rate, self.callback_manager.now());
 
         self.callback_manager
             .dispatch_event(CallbackEvent::PlaybackRateChanged { rate });
@@ -822,62 +1694,190 @@ impl AudioPlayer for IOSAudioPlayer {
     }
 
     fn get_status(&self) -> PlaybackStatus {
-        let decoder_lock = self.decoder.lock();
-        let duration_ms = if let Some(ref dec) = *decoder_lock {
-            dec.format.duration_ms
-        } else {
-            0
-        };
-        drop(decoder_lock);
+        // One lock, so duration and rate come from the same decoder even across a track change
+        let decoder = self.decoder.lock().as_ref().map(AudioDecoder::status);
+        let (duration_ms, duration_known) = decoder.map_or((0, false), |d| (d.duration_ms, d.duration_known));
 
         let sample_count = *self.sample_count.lock();
         let selected_rate = *self.output_sample_rate.lock();
-        let decoder_rate = self
-            .decoder
-            .lock()
-            .as_ref()
-            .map(|dec| dec.format.sample_rate);
+        let decoder_rate = decoder.map(|d| d.sample_rate);
         let sample_rate = effective_output_rate(selected_rate, decoder_rate, 48000) as u64;
 
-        let position_ms = if sample_rate > 0 {
-            (sample_count * 1000) / sample_rate
-        } else {
-            0
+        let position_ms = (sample_count * 1000).checked_div(sample_rate).unwrap_or(0);
+        let position_ms = match *self.fixed_latency.lock() {
+            Some(fixed) => fixed.audible_position_ms(position_ms),
+            None => position_ms,
         };
+        let position_ms = decoder.map_or(position_ms, |d| d.source_position_ms(position_ms));
 
         PlaybackStatus {
             position_ms,
             duration_ms,
             volume: *self.volume.lock(),
-            playback_rate: *self.playback_rate.lock(),
+            playback_rate: self.playback_rate.lock().effective(self.callback_manager.now()),
             buffering: false,
+            duration_known,
         }
     }
 
+    fn output_sample_rate(&self) -> u32 {
+        *self.output_sample_rate.lock()
+    }
+
+    fn output_channels(&self) -> u16 {
+        *self.output_channels.lock()
+    }
+
+    fn get_stats(&self) -> PlaybackStats {
+        let mut stats = self.decoder.lock().as_ref().map(AudioDecoder::stats).unwrap_or_default();
+        stats.phases = self.load_timer.timings();
+        stats.time_to_first_audio_ms = self.load_timer.time_to_first_audio_ms();
+        stats.dropped_tap_chunks = self.pcm_taps.dropped_chunks() + self.output_taps.dropped_chunks();
+        stats
+    }
+
+    fn event_queue_depth(&self) -> usize {
+        self.callback_manager.queue_depth()
+    }
+
     fn set_callback(&mut self, callback: Option<Arc<dyn PlayerCallback>>) {
         self.callback_manager.clear_callbacks();
         if let Some(cb) = callback {
-            self.callback_manager
-                .add_callback(cb, POSITION_UPDATE_INTERVAL_MS);
+            self.callback_manager.add_callback_with_default_interval(cb);
         }
     }
 
+    fn add_callback(
+        &mut self,
+        callback: Arc<dyn PlayerCallback>,
+        position_update_interval_ms: u64,
+    ) -> CallbackHandle {
+        self.callback_manager.add_callback(callback, position_update_interval_ms)
+    }
+
+    fn remove_callback(&mut self, handle: CallbackHandle) -> bool {
+        self.callback_manager.remove_callback(handle)
+    }
+
+    fn set_position_update_interval_ms(&mut self, interval_ms: u64) {
+        self.callback_manager
+            .set_position_update_interval_ms(interval_ms);
+    }
+
     fn release(&mut self) -> Result<()> {
         log::info!("Releasing audio player");
 
-        self.stop()?;
-        self.stop_decoder_thread();
+        // Bounded wait for the decode thread; stop() then delivers the terminal Stopped event
+        // and the teardown below runs even if stopping the stream failed
+        self.is_playing.store(false, Ordering::Relaxed);
+        self.stop_decoder_thread_within(RELEASE_JOIN_TIMEOUT);
+        let stopped = self.stop();
+        self.cancel_prepare();
+        self.prepared_next = None;
         *self.audio_stream.lock() = None;
+        *self.output_sample_rate.lock() = 0;
+        *self.output_channels.lock() = 0;
         *self.decoder.lock() = None;
         self.state_container.set_state(PlayerState::Idle);
+        // Deliver what is still queued, the final Stopped included, before returning
+        if !self.callback_manager.flush(RELEASE_JOIN_TIMEOUT) {
+            log::warn!("Player callbacks still busy after release");
+        }
 
         log::info!("Audio player released");
-        Ok(())
+        stopped
+    }
+
+    fn output_processing(&mut self) -> Option<&mut dyn OutputProcessing> {
+        Some(self)
+    }
+
+    fn track_selection(&mut self) -> Option<&mut dyn TrackSelection> {
+        Some(self)
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl OutputProcessing for IOSAudioPlayer {
+    fn set_output_bit_depth(&mut self, depth: OutputBitDepth) -> Result<()> {
+        log::info!("Output bit depth set to {:?}", depth);
+        self.output_bit_depth = depth;
+        Ok(())
+    }
+
+    fn set_eq_bands(&mut self, bands: &[EqBand]) -> Result<()> {
+        log::info!("EQ bands: {:?}", bands);
+        self.equalizer.lock().set_bands(bands)
+    }
+
+    fn output_levels(&self) -> Result<OutputLevels> {
+        Ok(self.meter.lock().take_levels())
+    }
+
+    fn scrub_preview(&mut self, position_ms: u64, duration_ms: u64) -> Result<()> {
+        log::debug!("Scrub preview at {} ms ({} ms)", position_ms, duration_ms);
+
+        let source = self
+            .current_source
+            .as_ref()
+            .ok_or_else(|| AudioError::InvalidState("No audio loaded".to_string()))?;
+
+        // Decoded with its own decoder, so the main decoder and sample_count are left alone
+        let (snippet, sample_rate, channels) = decode_snippet(source, position_ms, duration_ms)?;
+        let target_rate = effective_output_rate(
+            *self.output_sample_rate.lock(),
+            Some(sample_rate),
+            sample_rate,
+        );
+        let snippet = if sample_rate != target_rate {
+            Self::resample_linear(&snippet, sample_rate, target_rate, channels)
+        } else {
+            snippet
+        };
+        let snippet = Self::remix_to_output(snippet, channels, *self.output_channels.lock());
+
+        *self.scrub.lock() = Some(ScrubSnippet::new(snippet));
+
+        // A paused stream has to run for the preview to be heard; it renders silence afterwards
+        if !self.is_playing.load(Ordering::Relaxed) {
+            if let Some(ref stream) = *self.audio_stream.lock() {
+                stream.play()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TrackSelection for IOSAudioPlayer {
+    fn set_audio_track(&mut self, track_id: u32) -> Result<()> {
+        log::info!("Switching to audio track {}", track_id);
+
+        let position_ms = self.get_status().position_ms;
+        let (previous, current) = {
+            let mut decoder_lock = self.decoder.lock();
+            let dec = decoder_lock.as_mut().ok_or_else(|| {
+                AudioError::PlaybackError("No decoder available".to_string())
+            })?;
+            let previous = (dec.format.sample_rate, dec.format.channels);
+            dec.select_track(track_id)?;
+            (previous, (dec.format.sample_rate, dec.format.channels))
+        };
+
+        // A track with a different layout or rate needs its own output stream
+        if current != previous {
+            self.initialize_audio_stream(current.0, current.1)?;
+        }
+
+        // Drop audio buffered from the old track and resume the new one where we were
+        self.seek(position_ms)
+    }
 }
 
 impl Drop for IOSAudioPlayer {
@@ -16,6 +16,8 @@ use once_cell::sync::Lazy;
 #[cfg(target_os = "android")]
 use std::collections::HashMap;
 
+#[cfg(target_os = "android")]
+use crate::error::AudioError;
 #[cfg(target_os = "android")]
 use crate::player::{AudioPlayer, PlayerState};
 #[cfg(target_os = "android")]
@@ -186,6 +188,47 @@ pub extern "C" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeLoadBuffer
     }
 }
 
+/// Load audio from a file descriptor window (content:// documents, APK assets)
+/// A negative `length` means the rest of the file after `start_offset`.
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "C" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeLoadFd(
+    _env: JNIEnv,
+    _class: JClass,
+    player_id: jlong,
+    fd: jint,
+    start_offset: jlong,
+    length: jlong,
+) -> jint {
+    if start_offset < 0 {
+        log::error!("Invalid start offset: {}", start_offset);
+        return -1;
+    }
+    let length = if length < 0 { None } else { Some(length as u64) };
+
+    use crate::android::AndroidAudioPlayer;
+
+    let mut registry = PLAYER_REGISTRY.lock();
+    let player = registry
+        .get_mut(&player_id)
+        .and_then(|player| player.as_any_mut().downcast_mut::<AndroidAudioPlayer>());
+    if let Some(player) = player {
+        match player.load_fd(fd, start_offset as u64, length) {
+            Ok(_) => {
+                log::info!("File descriptor loaded successfully");
+                0
+            }
+            Err(e) => {
+                log::error!("Failed to load file descriptor: {}", e);
+                -1
+            }
+        }
+    } else {
+        log::error!("Invalid player ID: {}", player_id);
+        -1
+    }
+}
+
 /// Load audio from URL (streaming)
 #[cfg(target_os = "android")]
 #[no_mangle]
@@ -324,6 +367,35 @@ pub extern "C" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeSeek(
     }
 }
 
+/// Start the next load at a position (milliseconds) instead of 0
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "C" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeSetStartPosition(
+    _env: JNIEnv,
+    _class: JClass,
+    player_id: jlong,
+    position_ms: jlong,
+) -> jint {
+    if position_ms < 0 {
+        log::error!("Invalid start position: {}", position_ms);
+        return -1;
+    }
+
+    let mut registry = PLAYER_REGISTRY.lock();
+    if let Some(player) = registry.get_mut(&player_id) {
+        match player.set_start_position(position_ms as u64) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Failed to set start position: {}", e);
+                -1
+            }
+        }
+    } else {
+        log::error!("Invalid player ID: {}", player_id);
+        -1
+    }
+}
+
 /// Set volume (0.0 - 1.0)
 #[cfg(target_os = "android")]
 #[no_mangle]
@@ -341,12 +413,12 @@ pub extern "C" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeSetVolume(
             Ok(_) => 0,
             Err(e) => {
                 log::error!("Failed to set volume: {}", e);
-                -1
+                e.code()
             }
         }
     } else {
         log::error!("Invalid player ID: {}", player_id);
-        -1
+        AudioError::InvalidState(format!("Player {} was released", player_id)).code()
     }
 }
 
@@ -500,7 +572,11 @@ pub extern "C" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeGetMetadat
                             "encoder": {},
                             "publisher": {},
                             "isrc": {},
-                            "language": {}
+                            "language": {},
+                            "bpm": {},
+                            "initialKey": {},
+                            "mood": {},
+                            "energy": {}
                         }},
                         "hasCoverArt": {}
                     }}"#,
@@ -532,6 +608,10 @@ pub extern "C" fn Java_com_opoojkk_podium_audio_RustAudioPlayer_nativeGetMetadat
                     json_option_string(&metadata.tags.publisher),
                     json_option_string(&metadata.tags.isrc),
                     json_option_string(&metadata.tags.language),
+                    metadata.tags.bpm.map(|b| format!("{}", b)).unwrap_or("null".to_string()),
+                    json_option_string(&metadata.tags.initial_key),
+                    json_option_string(&metadata.tags.mood),
+                    metadata.tags.energy.map(|e| format!("{}", e)).unwrap_or("null".to_string()),
                     decoder.get_cover_art().is_some()
                 );
 
@@ -146,12 +146,10 @@ pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayerJvm_nativeSe
 pub extern "system" fn Java_com_opoojkk_podium_audio_RustAudioPlayerJvm_nativeSetVolume(
     _env: JNIEnv,
     _class: JClass,
-    _player_id: jlong,
-    _volume: jni::sys::jfloat,
+    player_id: jlong,
+    volume: jni::sys::jfloat,
 ) -> jint {
-    // TODO: Implement volume control if needed
-    log::warn!("nativeSetVolume not yet implemented");
-    0 // Return success for now
+    rust_audio_player_set_volume(player_id, volume)
 }
 
 /// Get current playback position in milliseconds
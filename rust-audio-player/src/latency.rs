@@ -0,0 +1,139 @@
+// Latency modes: how deep the output pipeline runs
+// Auto sizes the ring buffer from the track length and power mode and lets the platform pick
+// the device buffer, so the delay between decoding a sample and hearing it varies. Fixed pins
+// both: the device buffer is requested at a short fixed period, the ring buffer holds the rest
+// of the configured latency and is kept topped up, so an app syncing video can offset it by a
+// constant `output_latency_ms`.
+
+use crate::decoder::MIN_RING_CALLBACKS;
+use std::time::Duration;
+
+/// Device buffer requested in Fixed mode
+pub const FIXED_DEVICE_BUFFER_MS: u32 = 10;
+
+/// Shortest ring buffer depth in Fixed mode (below this the decoder cannot keep up reliably)
+pub const MIN_FIXED_RING_MS: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LatencyMode {
+    /// Buffer depth follows track length and power mode (default)
+    #[default]
+    Auto,
+    /// Constant output latency in milliseconds (device buffer + ring buffer)
+    Fixed(u32),
+}
+
+/// Buffer sizes that realize a Fixed latency at one stream rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedLatency {
+    pub sample_rate: u32,
+    /// Frames per device buffer
+    pub device_frames: u32,
+    /// Frames kept in the ring buffer
+    pub ring_frames: u32,
+}
+
+impl FixedLatency {
+    /// Split `total_ms` between the device buffer, clamped to the frame counts the device
+    /// supports (`device_range`, when known), and the ring buffer
+    pub fn plan(total_ms: u32, sample_rate: u32, device_range: Option<(u32, u32)>) -> Self {
+        let frames = |ms: u32| (ms as u64 * sample_rate as u64 / 1000) as u32;
+        let mut device_frames = frames(FIXED_DEVICE_BUFFER_MS);
+        if let Some((min, max)) = device_range {
+            device_frames = device_frames.clamp(min, max.max(min));
+        }
+        let ring_frames = frames(total_ms)
+            .saturating_sub(device_frames)
+            .max(frames(MIN_FIXED_RING_MS))
+            .max(device_frames * MIN_RING_CALLBACKS as u32);
+        Self {
+            sample_rate,
+            device_frames,
+            ring_frames,
+        }
+    }
+
+    pub fn device_ms(&self) -> u64 {
+        self.device_frames as u64 * 1000 / self.sample_rate.max(1) as u64
+    }
+
+    /// Delay between a sample entering the ring buffer and leaving the speaker
+    pub fn latency_ms(&self) -> u64 {
+        (self.device_frames as u64 + self.ring_frames as u64) * 1000 / self.sample_rate.max(1) as u64
+    }
+
+    /// Ring buffer capacity in interleaved samples
+    pub fn ring_samples(&self, channels: u16) -> usize {
+        self.ring_frames as usize * channels as usize
+    }
+
+    /// How long the decode thread sleeps once the ring is full: short enough that the ring
+    /// never drains by more than a device period before it is topped up again
+    pub fn refill_sleep(&self) -> Duration {
+        Duration::from_millis((self.device_ms() / 2).clamp(1, 5))
+    }
+
+    /// Position being heard, given the position handed to the device
+    pub fn audible_position_ms(&self, played_ms: u64) -> u64 {
+        played_ms.saturating_sub(self.device_ms())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::AudioRingBuffer;
+
+    #[test]
+    fn test_plan_matches_requested_latency() {
+        for (total_ms, rate, range) in [
+            (200, 48_000, None),
+            (200, 44_100, Some((64, 4096))),
+            (120, 48_000, Some((1024, 4096))),
+            (80, 96_000, Some((16, 256))),
+        ] {
+            let plan = FixedLatency::plan(total_ms, rate, range);
+            assert!(
+                plan.latency_ms().abs_diff(total_ms as u64) <= 1,
+                "{:?} gives {} ms for {} ms",
+                plan,
+                plan.latency_ms(),
+                total_ms
+            );
+            if let Some((min, max)) = range {
+                assert!((min..=max).contains(&plan.device_frames));
+            }
+        }
+
+        // Too short to split: the ring keeps its minimum depth
+        let plan = FixedLatency::plan(5, 48_000, None);
+        assert_eq!(plan.latency_ms(), (FIXED_DEVICE_BUFFER_MS + MIN_FIXED_RING_MS) as u64);
+        assert_eq!(plan.audible_position_ms(1_000), 1_000 - FIXED_DEVICE_BUFFER_MS as u64);
+    }
+
+    /// Ring depth plus device buffer stays at the configured latency while the device drains
+    /// the ring one period at a time and the decoder refills it after each `refill_sleep`
+    #[test]
+    fn test_fixed_depth_is_stable_while_playing() {
+        const CHANNELS: u16 = 2;
+        let plan = FixedLatency::plan(200, 48_000, None);
+        let mut ring = AudioRingBuffer::new(plan.ring_samples(CHANNELS));
+        let packet = vec![0.0; 1152 * CHANNELS as usize];
+        while ring.write(&packet) > 0 {}
+
+        let sleep_ms = plan.refill_sleep().as_millis() as u64;
+        let mut period = vec![0.0; plan.device_frames as usize * CHANNELS as usize];
+        for tick in 0..2_000u64 {
+            // Device pulls a period every device_ms; the decoder wakes every sleep_ms
+            if tick % plan.device_ms() == 0 {
+                ring.read(&mut period);
+            }
+            if tick % sleep_ms == 0 {
+                while ring.write(&packet) > 0 {}
+            }
+            let depth_ms = (ring.available_read() / CHANNELS as usize) as u64 * 1000 / 48_000;
+            let latency = depth_ms + plan.device_ms();
+            assert!(latency.abs_diff(200) <= 20, "{} ms at tick {}", latency, tick);
+        }
+    }
+}
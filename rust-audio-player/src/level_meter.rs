@@ -0,0 +1,118 @@
+// Output level meter: peak and RMS of each output channel
+// Measured on what is handed to the device, one value per channel of the stream's layout, so
+// surround output is metered channel by channel rather than as a stereo pair.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// Levels of each output channel since the previous reading, linear (1.0 = full scale)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OutputLevels {
+    pub peak: Vec<f32>,
+    pub rms: Vec<f32>,
+}
+
+/// Accumulates per-channel peak and RMS over the blocks it is fed
+#[cfg(any(target_os = "ios", target_os = "macos", test))]
+#[derive(Debug, Default)]
+pub struct LevelMeter {
+    peak: Vec<f32>,
+    sum_squares: Vec<f64>,
+    frames: u64,
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos", test))]
+impl LevelMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the interleaved `samples` of `channels` channels; a new channel count starts over
+    pub fn process(&mut self, samples: &[f32], channels: u16) {
+        let channels = channels as usize;
+        if channels == 0 {
+            return;
+        }
+        if channels != self.peak.len() {
+            self.peak = vec![0.0; channels];
+            self.sum_squares = vec![0.0; channels];
+            self.frames = 0;
+        }
+
+        for frame in samples.chunks_exact(channels) {
+            for (channel, &sample) in frame.iter().enumerate() {
+                self.peak[channel] = self.peak[channel].max(sample.abs());
+                self.sum_squares[channel] += (sample as f64) * (sample as f64);
+            }
+        }
+        self.frames += (samples.len() / channels) as u64;
+    }
+
+    /// Levels since the last call, then start a new measurement
+    pub fn take_levels(&mut self) -> OutputLevels {
+        let frames = self.frames.max(1) as f64;
+        let levels = OutputLevels {
+            peak: self.peak.clone(),
+            rms: self.sum_squares.iter().map(|sum| (sum / frames).sqrt() as f32).collect(),
+        };
+        self.peak.fill(0.0);
+        self.sum_squares.fill(0.0);
+        self.frames = 0;
+        levels
+    }
+}
+
+/// `LevelMeter` fed by a stream callback that must not wait on its reader: each block is folded
+/// into per-channel atomics, and a reading swaps them back to zero
+#[derive(Debug, Default)]
+pub struct SharedLevelMeter {
+    /// Peak per channel as `f32` bits; non-negative floats order like their bits
+    peak: Box<[AtomicU32]>,
+    /// Sum of squares per channel as `f64` bits
+    sum_squares: Box<[AtomicU64]>,
+    frames: AtomicU64,
+}
+
+impl SharedLevelMeter {
+    /// Meter for a stream of `channels` channels
+    pub fn new(channels: u16) -> Self {
+        Self {
+            peak: (0..channels).map(|_| AtomicU32::new(0)).collect(),
+            sum_squares: (0..channels).map(|_| AtomicU64::new(0)).collect(),
+            frames: AtomicU64::new(0),
+        }
+    }
+
+    /// Add the interleaved `samples`; a trailing partial frame is ignored
+    pub fn process(&self, samples: &[f32]) {
+        let channels = self.peak.len();
+        if channels == 0 {
+            return;
+        }
+        let samples = &samples[..samples.len() / channels * channels];
+        for channel in 0..channels {
+            let (peak, sum_squares) = samples
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .fold((0.0f32, 0.0f64), |(peak, sum), &s| (peak.max(s.abs()), sum + (s as f64) * (s as f64)));
+            self.peak[channel].fetch_max(peak.to_bits(), Ordering::Relaxed);
+            let _ = self.sum_squares[channel].fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((f64::from_bits(bits) + sum_squares).to_bits())
+            });
+        }
+        self.frames.fetch_add((samples.len() / channels) as u64, Ordering::Relaxed);
+    }
+
+    /// Levels since the last call, then start a new measurement
+    pub fn take_levels(&self) -> OutputLevels {
+        let frames = self.frames.swap(0, Ordering::Relaxed).max(1) as f64;
+        OutputLevels {
+            peak: self.peak.iter().map(|peak| f32::from_bits(peak.swap(0, Ordering::Relaxed))).collect(),
+            rms: self
+                .sum_squares
+                .iter()
+                .map(|sum| (f64::from_bits(sum.swap(0, Ordering::Relaxed)) / frames).sqrt() as f32)
+                .collect(),
+        }
+    }
+}
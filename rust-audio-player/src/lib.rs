@@ -1,6 +1,10 @@
 // Cross-platform audio player library
 // Supports Android (x86, ARM, ARMv8), iOS, Windows, and macOS
 
+// Worker isolation (`threads::catch_panic`) needs panics to unwind
+#[cfg(panic = "abort")]
+compile_error!("build with panic = \"unwind\": panic = \"abort\" defeats threads::catch_panic");
+
 // Platform-specific modules
 #[cfg(target_os = "android")]
 pub mod android;
@@ -15,21 +19,74 @@ pub mod player;
 pub mod decoder;
 pub mod error;
 pub mod callback;
+mod clock;
+mod diagnostics;
 pub mod metadata;
+pub mod sample_format;
+mod data_uri;
+mod file_url;
 mod http_utils;
-mod streaming_source;
-mod m4a_streaming;
-mod streaming_http_source;
-mod m4a_smart_source;
-mod m4a_virtual_faststart;
+mod http_transport;
 mod http_range_source;
+mod stream_seek;
 mod output_rate;
+mod threads;
+mod pcm_tap;
+mod limiter;
+mod equalizer;
+mod level_meter;
+mod cue_points;
+mod ducking;
+mod replay_gain;
+mod memory;
+mod power_mode;
+mod transcript;
+mod latency;
+mod loop_buffer;
+mod test_tone;
+mod playback_rate;
+mod channel_remix;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos", target_os = "ios"))]
+mod prepare;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos", target_os = "ios"))]
+mod scrub;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos", target_os = "ios"))]
+mod stream_owner;
+#[cfg(any(target_os = "windows", target_os = "linux", all(target_os = "macos", not(target_os = "ios"))))]
+mod spsc_ring;
+#[cfg(any(target_os = "windows", target_os = "linux", all(target_os = "macos", not(target_os = "ios"))))]
+mod handoff;
+#[cfg(any(target_os = "android", all(unix, test)))]
+mod fd_source;
+#[cfg(any(target_os = "android", test))]
+mod scratch_buffer;
+#[cfg(any(target_os = "android", test))]
+mod stream_fallback;
 
 // Re-exports
-pub use player::{AudioPlayer, PlayerState, PlaybackStatus};
+pub use player::{
+    AudioPlayer, AudioSource, PlaybackStats, PlaybackToken, PlayerState, PlaybackStatus, SeekMode,
+};
 pub use error::{AudioError, Result};
-pub use callback::{PlayerCallback, CallbackEvent};
-pub use metadata::{AudioMetadata, AudioTags, FormatInfo, QualityParams, CoverArt, Chapter};
+pub use callback::{CallbackHandle, PlayerCallback, CallbackEvent};
+pub use diagnostics::{LoadPhase, PhaseTimings};
+pub use sample_format::OutputBitDepth;
+pub use pcm_tap::{OutputChunk, OutputTap, PcmTap};
+pub use decoder::{read_metadata_only, PcmMessage, TrackDescriptor};
+pub use threads::ThreadPriority;
+pub use power_mode::PowerMode;
+pub use replay_gain::{ReplayGain, ReplayGainMode, ReplayGainSettings};
+pub use transcript::{Transcript, TranscriptCue};
+pub use latency::LatencyMode;
+pub use equalizer::{EqBand, MAX_EQ_BANDS};
+pub use level_meter::OutputLevels;
+pub use test_tone::{TestTone, Waveform};
+pub use http_range_source::StreamStage;
+pub use http_transport::{
+    set_http_config, set_http_transport, HttpConfig, HttpResponse, HttpTransport, UreqTransport,
+};
+pub use memory::{memory_usage_bytes, set_memory_budget};
+pub use metadata::{AudioMetadata, AudioTags, FormatInfo, QualityParams, CoverArt, Chapter, LyricLine};
 
 // JNI bindings for Android
 #[cfg(target_os = "android")]
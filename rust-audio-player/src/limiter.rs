@@ -0,0 +1,211 @@
+// Output limiter: the last stage before the device
+// Mixing (scrub preview over playback) and hot-mastered or resampled audio can exceed full
+// scale; a short lookahead lets the gain ramp down ahead of a peak instead of clipping it.
+
+use crate::error::{AudioError, Result};
+
+/// Default ceiling in dBFS
+pub const DEFAULT_CEILING_DB: f32 = -0.3;
+
+/// How far ahead peaks are seen; also the latency the limiter adds
+const LOOKAHEAD_MS: u32 = 2;
+
+/// Time constant for the gain to recover once peaks have passed
+const RELEASE_MS: f32 = 80.0;
+
+/// Lookahead brick-wall limiter for interleaved f32 audio.
+/// The gain needed by each frame is held over the lookahead window and then averaged over it,
+/// so reduction ramps in linearly over the window and every frame ends up at or under the ceiling.
+pub struct Limiter {
+    /// Linear ceiling; None bypasses the limiter
+    ceiling: Option<f32>,
+    sample_rate: u32,
+    channels: usize,
+    /// Delayed input, one lookahead window of frames
+    delay: Vec<f32>,
+    /// Gain each frame in the window needs to stay under the ceiling
+    required: Vec<f32>,
+    /// Windowed minimum of `required`, averaged into the applied gain
+    held: Vec<f32>,
+    held_sum: f64,
+    cursor: usize,
+    gain: f32,
+    release: f32,
+}
+
+impl Limiter {
+    pub fn new() -> Self {
+        Self {
+            ceiling: Some(db_to_linear(DEFAULT_CEILING_DB)),
+            sample_rate: 0,
+            channels: 0,
+            delay: Vec::new(),
+            required: Vec::new(),
+            held: Vec::new(),
+            held_sum: 0.0,
+            cursor: 0,
+            gain: 1.0,
+            release: 0.0,
+        }
+    }
+
+    /// Set the ceiling in dBFS (at most 0), or None to turn the limiter off
+    #[cfg(any(target_os = "android", target_os = "ios", target_os = "macos", test))]
+    pub fn set_ceiling_db(&mut self, ceiling_db: Option<f32>) -> Result<()> {
+        self.set_ceiling(ceiling_from_db(ceiling_db)?);
+        Ok(())
+    }
+
+    /// Set a linear ceiling checked by `ceiling_from_db`, or None to turn the limiter off
+    pub fn set_ceiling(&mut self, ceiling: Option<f32>) {
+        self.ceiling = ceiling;
+    }
+
+    /// Forget the delayed audio and gain state (new stream)
+    #[cfg(any(target_os = "android", target_os = "ios", target_os = "macos"))]
+    pub fn reset(&mut self) {
+        self.sample_rate = 0;
+    }
+
+    /// Limit `samples` in place. Output lags input by the lookahead window.
+    pub fn process(&mut self, samples: &mut [f32], channels: u16, sample_rate: u32) {
+        let Some(ceiling) = self.ceiling else {
+            return;
+        };
+        if channels == 0 || sample_rate == 0 {
+            return;
+        }
+        if sample_rate != self.sample_rate || channels as usize != self.channels {
+            self.configure(channels as usize, sample_rate);
+        }
+
+        let window = self.required.len();
+        let channels = self.channels;
+        for frame in samples.chunks_exact_mut(channels) {
+            let peak = frame.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+            self.required[self.cursor] = if peak > ceiling { ceiling / peak } else { 1.0 };
+            let held = self.required.iter().copied().fold(1.0f32, f32::min);
+            self.held_sum += (held - self.held[self.cursor]) as f64;
+            self.held[self.cursor] = held;
+            let target = (self.held_sum / window as f64) as f32;
+            self.gain = if target < self.gain {
+                target
+            } else {
+                self.gain + (target - self.gain) * self.release
+            };
+
+            // Swap the new frame into the delay line and emit the oldest one
+            let slot = self.cursor * channels;
+            self.delay[slot..slot + channels].swap_with_slice(frame);
+            self.cursor = (self.cursor + 1) % window;
+            let oldest = self.cursor * channels;
+            frame.copy_from_slice(&self.delay[oldest..oldest + channels]);
+            for sample in frame.iter_mut() {
+                // The clamp only absorbs rounding in the averaged gain
+                *sample = (*sample * self.gain).clamp(-ceiling, ceiling);
+            }
+        }
+    }
+
+    fn configure(&mut self, channels: usize, sample_rate: u32) {
+        let window = (sample_rate * LOOKAHEAD_MS / 1000).max(1) as usize;
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+        self.delay = vec![0.0; window * channels];
+        self.required = vec![1.0; window];
+        self.held = vec![1.0; window];
+        self.held_sum = window as f64;
+        self.cursor = 0;
+        self.gain = 1.0;
+        self.release = 1.0 - (-1000.0 / (RELEASE_MS * sample_rate as f32)).exp();
+    }
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Linear ceiling for a ceiling in dBFS (at most 0); None stays None (limiter off)
+pub fn ceiling_from_db(ceiling_db: Option<f32>) -> Result<Option<f32>> {
+    match ceiling_db {
+        Some(db) if !db.is_finite() || db > 0.0 => Err(AudioError::InvalidState(format!(
+            "Limiter ceiling must be at most 0 dBFS: {}",
+            db
+        ))),
+        _ => Ok(ceiling_db.map(db_to_linear)),
+    }
+}
+
+pub fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frames: usize, amplitude: f32) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| {
+                let s = amplitude * (i as f32 * 2.0 * std::f32::consts::PI * 440.0 / 48000.0).sin();
+                [s, s]
+            })
+            .collect()
+    }
+
+    fn max_step(samples: &[f32]) -> f32 {
+        samples
+            .chunks_exact(2)
+            .zip(samples.chunks_exact(2).skip(1))
+            .map(|(a, b)| (a[0] - b[0]).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_boosted_signal_stays_under_ceiling_without_clicks() {
+        let ceiling = db_to_linear(DEFAULT_CEILING_DB);
+        // Quiet passage, then the same tone boosted to +6 dBFS, then quiet again
+        let mut input = sine(4800, 0.5);
+        input.extend(sine(9600, 2.0));
+        input.extend(sine(48000, 0.5));
+        let mut output = input.clone();
+
+        let mut limiter = Limiter::new();
+        // Odd callback sizes: state must carry across calls
+        for chunk in output.chunks_mut(2 * 333) {
+            limiter.process(chunk, 2, 48000);
+        }
+
+        let peak = output.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak <= ceiling, "peak {} over ceiling {}", peak, ceiling);
+        // Gain moves no faster than the tone itself: no step larger than the input's own
+        assert!(max_step(&output) <= max_step(&input), "{} > {}", max_step(&output), max_step(&input));
+
+        // Once released, quiet audio passes through unchanged (just delayed)
+        let delay = 2 * (48000 * LOOKAHEAD_MS / 1000) as usize - 2;
+        let tail = output.len() - 4800;
+        for (out, inp) in output[tail..].iter().zip(&input[tail - delay..]) {
+            assert!((out - inp).abs() < 1e-4, "{} vs {}", out, inp);
+        }
+    }
+
+    #[test]
+    fn test_ceiling_is_configurable() {
+        let mut limiter = Limiter::new();
+        assert!(limiter.set_ceiling_db(Some(3.0)).is_err());
+        limiter.set_ceiling_db(Some(-6.0)).unwrap();
+
+        let mut samples = sine(4800, 1.0);
+        limiter.process(&mut samples, 2, 48000);
+        assert!(samples.iter().all(|s| s.abs() <= db_to_linear(-6.0)));
+
+        // Off: samples pass through untouched
+        limiter.set_ceiling_db(None).unwrap();
+        let mut loud = sine(480, 2.0);
+        let expected = loud.clone();
+        limiter.process(&mut loud, 2, 48000);
+        assert_eq!(loud, expected);
+    }
+}
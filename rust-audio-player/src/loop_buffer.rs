@@ -0,0 +1,81 @@
+// Pre-decoded PCM for sample-accurate looping
+// A short sound effect is decoded once into memory and the decoder threads fill the ring buffer
+// from it, wrapping the read index at the end: the loop point is exact to the frame and no seek
+// (with its packet pre-roll and I/O) happens at the boundary.
+
+use crate::error::{AudioError, Result};
+
+/// Longest source that can be looped from memory (30 s of 48kHz stereo f32 is ~11 MB)
+pub const LOOP_BUFFER_MAX_MS: u64 = 30_000;
+
+/// Frames handed out per `next_chunk` when the decoder thread asks for the next packet
+pub const LOOP_CHUNK_FRAMES: usize = 1024;
+
+/// Interleaved PCM played round and round
+#[derive(Debug, Clone)]
+pub struct LoopBuffer {
+    samples: Vec<f32>,
+    channels: usize,
+    /// Index of the next sample to hand out; always at a frame boundary
+    read: usize,
+}
+
+impl LoopBuffer {
+    /// Loop `samples` (interleaved, `channels` per frame), starting from frame `start_frame`
+    pub fn new(samples: Vec<f32>, channels: u16, start_frame: u64) -> Result<Self> {
+        let channels = channels.max(1) as usize;
+        if samples.len() < channels {
+            return Err(AudioError::InvalidState("Nothing decoded to loop".to_string()));
+        }
+        // A stray partial frame at the end would shift every later loop by a channel
+        let mut samples = samples;
+        samples.truncate(samples.len() / channels * channels);
+
+        let mut buffer = Self { samples, channels, read: 0 };
+        buffer.seek_to_frame(start_frame);
+        Ok(buffer)
+    }
+
+    /// Length of one loop in frames
+    pub fn frames(&self) -> u64 {
+        (self.samples.len() / self.channels) as u64
+    }
+
+    /// Continue from `frame`, taken modulo the loop length
+    pub fn seek_to_frame(&mut self, frame: u64) {
+        self.read = (frame % self.frames()) as usize * self.channels;
+    }
+
+    /// The next `frames` frames, wrapping from the last frame straight to the first
+    pub fn next_chunk(&mut self, frames: usize) -> Vec<f32> {
+        let wanted = frames * self.channels;
+        let mut chunk = Vec::with_capacity(wanted);
+        while chunk.len() < wanted {
+            let take = (wanted - chunk.len()).min(self.samples.len() - self.read);
+            chunk.extend_from_slice(&self.samples[self.read..self.read + take]);
+            self.read = (self.read + take) % self.samples.len();
+        }
+        chunk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_wrap_at_any_size() {
+        let mut buffer = LoopBuffer::new(vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5], 2, 1).unwrap();
+        assert_eq!(buffer.frames(), 3);
+        assert_eq!(buffer.next_chunk(4), vec![1.0, 1.5, 2.0, 2.5, 0.0, 0.5, 1.0, 1.5]);
+        // Longer than the loop itself
+        assert_eq!(buffer.next_chunk(7)[..4], [2.0, 2.5, 0.0, 0.5]);
+        assert_eq!(buffer.next_chunk(1), vec![0.0, 0.5]);
+
+        buffer.seek_to_frame(7);
+        assert_eq!(buffer.next_chunk(1), vec![1.0, 1.5]);
+        // The partial trailing frame is dropped; an empty loop is refused
+        assert_eq!(LoopBuffer::new(vec![0.0, 1.0, 2.0], 2, 0).unwrap().frames(), 1);
+        assert!(LoopBuffer::new(vec![0.0], 2, 0).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+// Buffer memory accounting
+// Ring buffers and streaming caches charge their allocations to a pool so integrators can
+// see what the crate holds (`memory_usage_bytes`) and bound it (`set_memory_budget`).
+// Caches that can re-fetch data (HTTP range chunks, M4A chunks) evict early while the pool
+// is over budget; buffers that cannot (ring buffers, full downloads) are only counted.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Process-wide pool used by every player
+static GLOBAL_POOL: MemoryPool = MemoryPool::new();
+
+/// Bytes in use plus an optional budget
+pub struct MemoryPool {
+    used: AtomicUsize,
+    /// 0 = no budget
+    budget: AtomicUsize,
+}
+
+impl MemoryPool {
+    pub const fn new() -> Self {
+        Self {
+            used: AtomicUsize::new(0),
+            budget: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn global() -> &'static MemoryPool {
+        &GLOBAL_POOL
+    }
+
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    pub fn budget(&self) -> Option<usize> {
+        match self.budget.load(Ordering::Relaxed) {
+            0 => None,
+            bytes => Some(bytes),
+        }
+    }
+
+    pub fn set_budget(&self, bytes: Option<usize>) {
+        self.budget.store(bytes.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Whether evictable caches should release memory
+    pub fn over_budget(&self) -> bool {
+        self.budget().is_some_and(|budget| self.used() > budget)
+    }
+}
+
+impl Default for MemoryPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bytes charged to a pool for as long as the charge lives
+pub struct MemoryCharge {
+    pool: &'static MemoryPool,
+    bytes: usize,
+}
+
+impl MemoryCharge {
+    pub fn new(pool: &'static MemoryPool, bytes: usize) -> Self {
+        pool.used.fetch_add(bytes, Ordering::Relaxed);
+        Self { pool, bytes }
+    }
+
+    /// Charge to the global pool
+    pub fn global(bytes: usize) -> Self {
+        Self::new(MemoryPool::global(), bytes)
+    }
+
+    /// Update the charge after the owning buffer grew or shrank
+    pub fn set(&mut self, bytes: usize) {
+        if bytes > self.bytes {
+            self.pool.used.fetch_add(bytes - self.bytes, Ordering::Relaxed);
+        } else {
+            self.pool.used.fetch_sub(self.bytes - bytes, Ordering::Relaxed);
+        }
+        self.bytes = bytes;
+    }
+}
+
+impl Drop for MemoryCharge {
+    fn drop(&mut self) {
+        self.pool.used.fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+/// Bytes currently held by ring buffers, streaming buffers and caches of all players
+pub fn memory_usage_bytes() -> usize {
+    MemoryPool::global().used()
+}
+
+/// Bound buffer memory (None = unbounded). Range and chunk caches evict down to the budget;
+/// ring buffers and full-file downloads are counted but cannot shrink below what they hold.
+pub fn set_memory_budget(bytes: Option<usize>) {
+    MemoryPool::global().set_budget(bytes);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A pool of its own, so parallel tests don't see each other's buffers
+    pub(crate) fn test_pool() -> &'static MemoryPool {
+        Box::leak(Box::new(MemoryPool::new()))
+    }
+
+    #[test]
+    fn test_charges_follow_buffers() {
+        let pool = test_pool();
+        let mut charge = MemoryCharge::new(pool, 1000);
+        let other = MemoryCharge::new(pool, 24);
+        assert_eq!(pool.used(), 1024);
+
+        charge.set(4000);
+        assert_eq!(pool.used(), 4024);
+        pool.set_budget(Some(2048));
+        assert!(pool.over_budget());
+
+        charge.set(1000);
+        assert!(!pool.over_budget());
+        drop(charge);
+        drop(other);
+        assert_eq!(pool.used(), 0);
+    }
+}
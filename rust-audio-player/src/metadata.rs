@@ -1,6 +1,7 @@
 // Audio metadata extraction and management
 // Supports ID3, Vorbis Comments, and other formats
 
+use crate::replay_gain::ReplayGain;
 use std::collections::HashMap;
 
 /// Comprehensive audio metadata
@@ -17,6 +18,13 @@ pub struct AudioMetadata {
 
     // Chapter information (for podcasts)
     pub chapters: Vec<Chapter>,
+
+    // Time-synced lyrics (ID3 SYLT or LRC-formatted lyrics), sorted by time; empty when absent
+    pub synced_lyrics: Vec<LyricLine>,
+
+    // Embedded artwork; only filled by `read_metadata_only` (a loaded decoder keeps it apart,
+    // see `AudioDecoder::get_cover_art`)
+    pub cover_art: Option<CoverArt>,
 }
 
 /// Basic audio format information
@@ -114,6 +122,21 @@ pub struct AudioTags {
     /// Language
     pub language: Option<String>,
 
+    /// Tempo in beats per minute (TBPM, BPM)
+    pub bpm: Option<f32>,
+
+    /// Musical key as tagged, e.g. "Am" or Camelot "8A" (TKEY, INITIALKEY)
+    pub initial_key: Option<String>,
+
+    /// Mood (TMOO, MOOD)
+    pub mood: Option<String>,
+
+    /// Energy level, usually 1-10 (ENERGY, ENERGYLEVEL)
+    pub energy: Option<u32>,
+
+    /// Loudness normalization gains and peaks (REPLAYGAIN_*)
+    pub replay_gain: ReplayGain,
+
     /// Additional custom tags
     pub custom_tags: HashMap<String, String>,
 }
@@ -134,6 +157,18 @@ pub struct CoverArt {
     pub picture_type: u8,
 }
 
+impl CoverArt {
+    /// File extension matching the image's MIME type, if it's a known image format
+    pub fn file_extension(&self) -> Option<&'static str> {
+        match self.mime_type.to_ascii_lowercase().as_str() {
+            "image/jpeg" | "image/jpg" => Some("jpg"),
+            "image/png" => Some("png"),
+            "image/webp" => Some("webp"),
+            _ => None,
+        }
+    }
+}
+
 /// Chapter marker (important for podcasts)
 #[derive(Debug, Clone)]
 pub struct Chapter {
@@ -153,12 +188,43 @@ pub struct Chapter {
     pub url: Option<String>,
 }
 
+/// Index of the chapter playing at `position_ms` in start-ordered `chapters`
+/// Returns None before the first chapter starts
+pub fn chapter_index_at(chapters: &[Chapter], position_ms: u64) -> Option<usize> {
+    chapters
+        .partition_point(|chapter| chapter.start_time_ms <= position_ms)
+        .checked_sub(1)
+}
+
+/// A single line of time-synced lyrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyricLine {
+    /// Time at which the line starts, in milliseconds
+    pub time_ms: u64,
+
+    /// Lyric text (may be empty for instrumental gaps)
+    pub text: String,
+}
+
 impl AudioMetadata {
     /// Create a new empty metadata instance
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Get the lyric line that should be displayed at `position_ms`
+    /// Returns None before the first line or when no synced lyrics are available
+    pub fn current_lyric_line(&self, position_ms: u64) -> Option<&LyricLine> {
+        let idx = self
+            .synced_lyrics
+            .partition_point(|line| line.time_ms <= position_ms);
+        if idx == 0 {
+            None
+        } else {
+            self.synced_lyrics.get(idx - 1)
+        }
+    }
+
     /// Check if metadata has any tag information
     pub fn has_tags(&self) -> bool {
         self.tags.title.is_some()
@@ -228,6 +294,73 @@ impl AudioTags {
     }
 }
 
+/// Parse LRC-formatted lyrics (`[mm:ss.xx]text`) into time-sorted lines
+/// Lines may carry several timestamps; ID tags such as `[ar:...]` are ignored and `[offset:±ms]` is applied.
+/// Returns an empty Vec if the text contains no timestamps (plain unsynced lyrics).
+pub fn parse_lrc(text: &str) -> Vec<LyricLine> {
+    let mut offset_ms: i64 = 0;
+    let mut lines = Vec::new();
+
+    for raw_line in text.lines() {
+        let mut rest = raw_line.trim();
+        let mut timestamps = Vec::new();
+
+        while let Some(stripped) = rest.strip_prefix('[') {
+            let Some(end) = stripped.find(']') else {
+                break;
+            };
+            let tag = &stripped[..end];
+            rest = &stripped[end + 1..];
+
+            if let Some(time_ms) = parse_lrc_timestamp(tag) {
+                timestamps.push(time_ms);
+            } else if let Some(value) = tag.strip_prefix("offset:") {
+                offset_ms = value.trim().parse().unwrap_or(0);
+            }
+        }
+
+        let text = rest.trim();
+        for time_ms in timestamps {
+            lines.push((time_ms, text.to_string()));
+        }
+    }
+
+    // A positive offset makes lyrics appear sooner
+    let mut lyrics: Vec<LyricLine> = lines
+        .into_iter()
+        .map(|(time_ms, text)| LyricLine {
+            time_ms: (time_ms as i64 - offset_ms).max(0) as u64,
+            text,
+        })
+        .collect();
+    lyrics.sort_by_key(|line| line.time_ms);
+    lyrics
+}
+
+/// Parse an LRC timestamp body (`mm:ss`, `mm:ss.xx` or `mm:ss.xxx`) into milliseconds
+fn parse_lrc_timestamp(tag: &str) -> Option<u64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u64 = minutes.trim().parse().ok()?;
+    let (secs, fraction) = match seconds.split_once('.') {
+        Some((secs, fraction)) => (secs, fraction),
+        None => (seconds, ""),
+    };
+    let secs: u64 = secs.trim().parse().ok()?;
+    if secs >= 60 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // Scale hundredths/thousandths to milliseconds
+    let fraction_ms = match fraction.len() {
+        0 => 0,
+        1 => fraction.parse::<u64>().ok()? * 100,
+        2 => fraction.parse::<u64>().ok()? * 10,
+        _ => fraction[..3].parse::<u64>().ok()?,
+    };
+
+    Some(minutes * 60_000 + secs * 1000 + fraction_ms)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,4 +388,37 @@ mod tests {
         assert_eq!(tags.get_tag("artist"), Some("My Artist"));
         assert_eq!(tags.get_tag("unknown"), None);
     }
+
+    #[test]
+    fn test_parse_lrc_lyrics() {
+        let lrc = "[ar:Someone]\n[ti:Song]\n[00:12.34]First line\n[00:15.5]Second line\n[01:02.345][01:30.00]Chorus\nplain text";
+        let lines = parse_lrc(lrc);
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], LyricLine { time_ms: 12_340, text: "First line".to_string() });
+        assert_eq!(lines[1].time_ms, 15_500);
+        assert_eq!(lines[2], LyricLine { time_ms: 62_345, text: "Chorus".to_string() });
+        assert_eq!(lines[3].time_ms, 90_000);
+
+        // Unsynced lyrics yield nothing
+        assert!(parse_lrc("just some words\nno timestamps").is_empty());
+    }
+
+    #[test]
+    fn test_lrc_offset() {
+        let lines = parse_lrc("[offset:+500]\n[00:01.00]Hello");
+        assert_eq!(lines[0].time_ms, 500);
+    }
+
+    #[test]
+    fn test_current_lyric_line() {
+        let mut metadata = AudioMetadata::new();
+        metadata.synced_lyrics = parse_lrc("[00:10.00]One\n[00:20.00]Two\n[00:30.00]Three");
+
+        assert_eq!(metadata.current_lyric_line(5_000), None);
+        assert_eq!(metadata.current_lyric_line(10_000).unwrap().text, "One");
+        assert_eq!(metadata.current_lyric_line(19_999).unwrap().text, "One");
+        assert_eq!(metadata.current_lyric_line(25_000).unwrap().text, "Two");
+        assert_eq!(metadata.current_lyric_line(999_000).unwrap().text, "Three");
+    }
 }
@@ -13,3 +13,38 @@ pub fn effective_output_rate(
         default_rate
     }
 }
+
+/// Rate to open the output stream at: the fixed target rate when one is set, otherwise the
+/// decoder's rate. Decoded audio is resampled to whatever rate the stream ends up running at.
+pub fn requested_stream_rate(decoder_rate: u32, target_rate: Option<u32>) -> u32 {
+    target_rate.unwrap_or(decoder_rate)
+}
+
+/// Linear resampler for interleaved samples, used where a packet's rate differs from the stream's
+pub fn resample_linear(samples: &[f32], input_rate: u32, output_rate: u32, channels: u16) -> Vec<f32> {
+    if input_rate == 0 || output_rate == 0 || input_rate == output_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let channels = channels.max(1) as usize;
+    let input_frames = samples.len() / channels;
+    let output_frames = ((input_frames as u64 * output_rate as u64) / input_rate as u64) as usize;
+    if input_frames == 0 || output_frames == 0 {
+        return Vec::new();
+    }
+
+    let ratio = input_rate as f64 / output_rate as f64;
+    let mut output = Vec::with_capacity(output_frames * channels);
+    for out_index in 0..output_frames {
+        let input_pos = out_index as f64 * ratio;
+        let base_idx = input_pos.floor() as usize;
+        let frac = (input_pos - base_idx as f64) as f32;
+        let next_idx = (base_idx + 1).min(input_frames - 1);
+        for ch in 0..channels {
+            let s0 = samples[base_idx * channels + ch];
+            let s1 = samples[next_idx * channels + ch];
+            output.push(s0 + (s1 - s0) * frac);
+        }
+    }
+    output
+}
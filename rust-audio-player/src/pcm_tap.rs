@@ -0,0 +1,196 @@
+// Decoded and output PCM taps for custom pipelines (DSP, recording, visualizers)
+// Each tap runs on its own thread behind a bounded queue, so a slow tap loses chunks
+// instead of stalling the decode or audio thread.
+
+use crate::error::Result;
+use crate::threads::{catch_panic, spawn_named, PCM_TAP_THREAD};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::sync::Arc;
+
+/// Chunks queued per tap before new ones are dropped
+const TAP_QUEUE_CHUNKS: usize = 64;
+
+/// Receives each decoded chunk: interleaved f32 in the decoder's format, before resampling
+pub type PcmTap = Box<dyn FnMut(&[f32]) + Send>;
+
+/// Receives each chunk as it is handed to the output device, in step with what is heard
+pub type OutputTap = Box<dyn FnMut(&OutputChunk) + Send>;
+
+/// Samples written by one output callback (after volume and scrub mixing)
+#[derive(Debug, Clone)]
+pub struct OutputChunk {
+    /// Output frames played before this chunk; `position_ms` at the output rate
+    pub position_frames: u64,
+    /// Output sample rate
+    pub sample_rate: u32,
+    /// Interleaved channel count
+    pub channels: u16,
+    /// Interleaved f32 samples
+    pub samples: Arc<[f32]>,
+}
+
+impl OutputChunk {
+    /// Playback position of the first frame in this chunk
+    pub fn position_ms(&self) -> u64 {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        self.position_frames * 1000 / self.sample_rate as u64
+    }
+}
+
+/// Registered taps, each on its own thread behind a bounded queue
+pub struct Taps<C> {
+    senders: Mutex<Vec<SyncSender<C>>>,
+    dropped_chunks: AtomicU64,
+}
+
+/// Taps fed from the decode thread
+pub type PcmTaps = Taps<Arc<[f32]>>;
+
+/// Taps fed from the output callback
+pub type OutputTaps = Taps<OutputChunk>;
+
+impl<C> Default for Taps<C> {
+    fn default() -> Self {
+        Self {
+            senders: Mutex::new(Vec::new()),
+            dropped_chunks: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<C: Clone + Send + 'static> Taps<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a tap thread; it ends when the taps are dropped
+    fn spawn(&self, mut handle: impl FnMut(C) + Send + 'static) -> Result<()> {
+        let (sender, receiver) = sync_channel::<C>(TAP_QUEUE_CHUNKS);
+        spawn_named(PCM_TAP_THREAD, move || {
+            let result = catch_panic(move || {
+                for chunk in receiver {
+                    handle(chunk);
+                }
+            });
+            if let Err(e) = result {
+                log::error!("PCM tap failed: {}", e);
+            }
+        })?;
+        self.senders.lock().push(sender);
+        Ok(())
+    }
+
+    /// Queue `chunk` for every tap without blocking
+    fn send(senders: &mut Vec<SyncSender<C>>, dropped_chunks: &AtomicU64, chunk: C) {
+        senders.retain(|sender| match sender.try_send(chunk.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                let dropped = dropped_chunks.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped % 100 == 1 {
+                    log::warn!("PCM tap too slow, dropped {} chunks", dropped);
+                }
+                true
+            }
+            // The tap thread ended (its callback panicked)
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+    }
+
+    /// Chunks dropped because a tap's queue was full
+    pub fn dropped_chunks(&self) -> u64 {
+        self.dropped_chunks.load(Ordering::Relaxed)
+    }
+}
+
+impl PcmTaps {
+    /// Start a tap thread; it ends when the taps are dropped
+    pub fn add(&self, mut tap: PcmTap) -> Result<()> {
+        self.spawn(move |chunk: Arc<[f32]>| tap(&chunk))
+    }
+
+    /// Hand a decoded chunk to every tap without blocking
+    pub fn publish(&self, samples: &[f32]) {
+        let mut senders = self.senders.lock();
+        if senders.is_empty() || samples.is_empty() {
+            return;
+        }
+        Self::send(&mut senders, &self.dropped_chunks, samples.into());
+    }
+}
+
+impl OutputTaps {
+    /// Start a tap thread; it ends when the taps are dropped
+    pub fn add(&self, mut tap: OutputTap) -> Result<()> {
+        self.spawn(move |chunk: OutputChunk| tap(&chunk))
+    }
+
+    /// Hand the samples just written to the device to every tap.
+    /// Called on the audio thread: skips the chunk rather than wait for a tap being added.
+    pub fn publish(&self, position_frames: u64, sample_rate: u32, channels: u16, samples: &[f32]) {
+        let Some(mut senders) = self.senders.try_lock() else {
+            return;
+        };
+        if senders.is_empty() || samples.is_empty() {
+            return;
+        }
+        let chunk = OutputChunk {
+            position_frames,
+            sample_rate,
+            channels,
+            samples: samples.into(),
+        };
+        Self::send(&mut senders, &self.dropped_chunks, chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::tests::test_wav;
+    use crate::decoder::AudioDecoder;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_tap_receives_every_decoded_sample() {
+        let (expected_format, expected) = AudioDecoder::from_buffer(test_wav(8000, 2, 1))
+            .unwrap()
+            .decode_all()
+            .unwrap();
+        assert_eq!(expected_format.channels, 2);
+
+        let taps = PcmTaps::new();
+        let (counts, received) = mpsc::channel();
+        taps.add(Box::new(move |samples| counts.send(samples.len()).unwrap())).unwrap();
+
+        let mut decoder = AudioDecoder::from_buffer(test_wav(8000, 2, 1)).unwrap();
+        for chunk in decoder.frames() {
+            taps.publish(&chunk.unwrap());
+        }
+        assert_eq!(taps.dropped_chunks(), 0);
+
+        // Dropping the taps ends the tap thread once its queue is drained
+        drop(taps);
+        assert_eq!(received.iter().sum::<usize>(), expected.len());
+    }
+
+    #[test]
+    fn test_slow_tap_drops_instead_of_blocking() {
+        let taps = PcmTaps::new();
+        let (release, blocked) = mpsc::channel::<()>();
+        taps.add(Box::new(move |_| {
+            let _ = blocked.recv();
+        }))
+        .unwrap();
+
+        // The tap is stuck on its first chunk: publishing past the queue returns immediately
+        for _ in 0..TAP_QUEUE_CHUNKS + 10 {
+            taps.publish(&[0.0; 4]);
+        }
+        assert!(taps.dropped_chunks() >= 9);
+        drop(release);
+    }
+}
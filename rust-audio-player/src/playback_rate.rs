@@ -0,0 +1,103 @@
+// Playback rate range and ramping shared by every platform player
+// Rate changes glide to the new value instead of jumping, so tempo/pitch never jolts.
+
+use crate::error::{AudioError, Result};
+use std::time::{Duration, Instant};
+
+/// Slowest supported playback rate
+pub const MIN_PLAYBACK_RATE: f32 = 0.25;
+
+/// Fastest supported playback rate
+pub const MAX_PLAYBACK_RATE: f32 = 4.0;
+
+/// Time the effective rate takes to reach a new target
+pub const RATE_RAMP: Duration = Duration::from_millis(250);
+
+/// Clamp a requested rate into the supported range; NaN and infinities are rejected
+pub fn clamp_playback_rate(rate: f32) -> Result<f32> {
+    if !rate.is_finite() {
+        return Err(AudioError::InvalidState(format!(
+            "Invalid playback rate: {}",
+            rate
+        )));
+    }
+    Ok(rate.clamp(MIN_PLAYBACK_RATE, MAX_PLAYBACK_RATE))
+}
+
+/// Linear glide from the rate in effect when the target last changed to the target
+#[derive(Debug, Clone, Copy)]
+pub struct RateRamp {
+    from: f32,
+    target: f32,
+    started: Instant,
+}
+
+impl Default for RateRamp {
+    fn default() -> Self {
+        Self {
+            from: 1.0,
+            target: 1.0,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl RateRamp {
+    /// Start ramping toward `target`, from wherever the current ramp is at `now`
+    pub fn set_target(&mut self, target: f32, now: Instant) {
+        self.from = self.effective(now);
+        self.target = target;
+        self.started = now;
+    }
+
+    /// Rate in effect at `now`
+    pub fn effective(&self, now: Instant) -> f32 {
+        let elapsed = now.saturating_duration_since(self.started);
+        if elapsed >= RATE_RAMP {
+            return self.target;
+        }
+        let progress = elapsed.as_secs_f32() / RATE_RAMP.as_secs_f32();
+        self.from + (self.target - self.from) * progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_out_of_range_rates_are_clamped() {
+        assert_eq!(clamp_playback_rate(1.5).unwrap(), 1.5);
+        assert_eq!(clamp_playback_rate(10.0).unwrap(), MAX_PLAYBACK_RATE);
+        assert_eq!(clamp_playback_rate(0.1).unwrap(), MIN_PLAYBACK_RATE);
+        assert_eq!(clamp_playback_rate(-2.0).unwrap(), MIN_PLAYBACK_RATE);
+        assert!(clamp_playback_rate(f32::NAN).is_err());
+        assert!(clamp_playback_rate(f32::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_effective_rate_converges_smoothly() {
+        let start = Instant::now();
+        let mut ramp = RateRamp::default();
+        ramp.set_target(2.0, start);
+        assert_eq!(ramp.effective(start), 1.0);
+
+        // Every 10 ms step moves a small, monotonic amount toward the target
+        let steps = (RATE_RAMP.as_millis() / 10) as u32;
+        let max_step = 1.0 / steps as f32 + 1e-4;
+        let mut previous = ramp.effective(start);
+        for i in 1..=steps {
+            let rate = ramp.effective(start + Duration::from_millis(10) * i);
+            assert!(rate >= previous && rate - previous <= max_step, "{} -> {}", previous, rate);
+            previous = rate;
+        }
+        assert_eq!(ramp.effective(start + RATE_RAMP), 2.0);
+
+        // A new target ramps from the rate currently in effect
+        let later = start + RATE_RAMP * 3;
+        ramp.set_target(1.0, later);
+        assert_eq!(ramp.effective(later), 2.0);
+        let mid = ramp.effective(later + RATE_RAMP / 2);
+        assert!((mid - 1.5).abs() < 1e-3);
+    }
+}
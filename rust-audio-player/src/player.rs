@@ -1,14 +1,28 @@
 // Core audio player trait and state management
 
 use crate::error::{AudioError, Result};
-use crate::callback::PlayerCallback;
+use crate::callback::{CallbackHandle, PlayerCallback};
+use crate::diagnostics::PhaseTimings;
+use crate::equalizer::EqBand;
+use crate::level_meter::OutputLevels;
+use crate::pcm_tap::{OutputTap, PcmTap};
+use crate::sample_format::OutputBitDepth;
+use crate::power_mode::PowerMode;
+use crate::replay_gain::ReplayGainSettings;
+use crate::threads::ThreadPriority;
+use crate::transcript::TranscriptCue;
+use crate::latency::LatencyMode;
+use crate::test_tone::TestTone;
+use crate::metadata::{chapter_index_at, Chapter};
 use std::sync::Arc;
-use parking_lot::RwLock;
+use std::time::{Duration, Instant};
+use parking_lot::{Condvar, Mutex, RwLock};
 
 /// Player state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerState {
     /// Player is idle (no audio loaded)
+    /// Loads, settings for the next load and release are valid; volume and rate are not.
     Idle,
     /// Audio is loading
     Loading,
@@ -24,6 +38,59 @@ pub enum PlayerState {
     Error,
 }
 
+/// Audio source description (used by `prepare` / `prepare_next`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioSource {
+    /// Local file path
+    File(String),
+
+    /// HTTP(S) URL (streamed with range requests)
+    Url(String),
+
+    /// Encoded audio held in memory
+    Buffer(Vec<u8>),
+}
+
+impl AudioSource {
+    /// Stable identifier for this source (path, URL, or a content hash for in-memory buffers)
+    pub fn id(&self) -> String {
+        match self {
+            AudioSource::File(path) => path.clone(),
+            AudioSource::Url(url) => url.clone(),
+            AudioSource::Buffer(data) => buffer_source_id(data),
+        }
+    }
+}
+
+/// Identifier used for in-memory sources (`AudioSource::Buffer` / `load_buffer`)
+pub(crate) fn buffer_source_id(data: &[u8]) -> String {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    data.hash(&mut hasher);
+    format!("buffer:{}:{:016x}", data.len(), hasher.finish())
+}
+
+/// Seek precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeekMode {
+    /// Output starts exactly at the target; the frames before it in its packet are decoded and dropped
+    #[default]
+    Accurate,
+    /// Output starts at the packet (keyframe) holding the target, found without parsing up to it
+    Coarse,
+}
+
+/// Saved playback position, used to resume a source where the listener left off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaybackToken {
+    /// Identifier of the loaded source (see `AudioSource::id`)
+    pub source_id: String,
+
+    /// Playback position in milliseconds
+    pub position_ms: u64,
+}
+
 /// Playback status information
 #[derive(Debug, Clone)]
 pub struct PlaybackStatus {
@@ -37,6 +104,9 @@ pub struct PlaybackStatus {
     pub playback_rate: f32,
     /// Whether the player is buffering
     pub buffering: bool,
+    /// `duration_ms` is reliable; false while it is unknown (0) or only a placeholder,
+    /// e.g. a stream whose header has not arrived yet
+    pub duration_known: bool,
 }
 
 impl Default for PlaybackStatus {
@@ -47,10 +117,57 @@ impl Default for PlaybackStatus {
             volume: 1.0,
             playback_rate: 1.0,
             buffering: false,
+            duration_known: false,
+        }
+    }
+}
+
+impl PlaybackStatus {
+    /// Serialize as a flat JSON object (for the C ABI)
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"position_ms\":{},\"duration_ms\":{},\"duration_known\":{},\"volume\":{:.3},\"playback_rate\":{:.3},\"buffering\":{}}}",
+            self.position_ms,
+            self.duration_ms,
+            self.duration_known,
+            self.volume,
+            self.playback_rate,
+            self.buffering
+        )
+    }
+}
+
+/// Decoder performance counters for the current source (diagnostics)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PlaybackStats {
+    /// Frames decoded since the source was opened
+    pub decoded_frames: u64,
+    /// Time spent reading, decoding and converting them
+    pub decode_time: std::time::Duration,
+    /// How long each phase of loading this source (and the last seek) took
+    pub phases: PhaseTimings,
+    /// From the load call to the first decoded audio of this source
+    pub time_to_first_audio_ms: Option<u64>,
+    /// Chunks the PCM and output taps lost because a tap fell behind, over the player's lifetime
+    pub dropped_tap_chunks: u64,
+}
+
+impl PlaybackStats {
+    /// Frames decoded per second of decode work; divided by the sample rate this is how many
+    /// times faster than real time the source decodes. 0.0 until a packet has been decoded.
+    pub fn decode_throughput_frames_per_sec(&self) -> f64 {
+        let seconds = self.decode_time.as_secs_f64();
+        if seconds > 0.0 {
+            self.decoded_frames as f64 / seconds
+        } else {
+            0.0
         }
     }
 }
 
+/// How far into a chapter `previous_chapter` still goes back to the chapter before
+pub const PREVIOUS_CHAPTER_WINDOW_MS: u64 = 3000;
+
 /// Core audio player trait
 /// All platform-specific implementations must implement this trait
 pub trait AudioPlayer: Send + Sync {
@@ -63,6 +180,68 @@ pub trait AudioPlayer: Send + Sync {
     /// Load audio from memory buffer
     fn load_buffer(&mut self, buffer: &[u8]) -> Result<()>;
 
+    /// Start the next `load_file`, `load_url` or `load_buffer` at `position_ms` (clamped to the duration)
+    /// The decoder seeks before pre-buffering, so playback and the first reported position begin
+    /// at the start rather than at 0. Applies to one load only; 0 starts from the beginning.
+    fn set_start_position(&mut self, position_ms: u64) -> Result<()>;
+
+    /// Load a generated sine tone, for checking the output path without a media file
+    /// `amplitude` is the peak level (0.0..=1.0 of full scale); every channel plays the same tone.
+    fn load_test_tone(
+        &mut self,
+        freq_hz: f32,
+        amplitude: f32,
+        duration_ms: u64,
+        sample_rate: u32,
+        channels: u16,
+    ) -> Result<()> {
+        let tone = TestTone::new(freq_hz, amplitude, duration_ms, sample_rate, channels)?;
+        self.load_tone(&tone)
+    }
+
+    /// Load a generated tone of any waveform (see `TestTone`)
+    fn load_tone(&mut self, tone: &TestTone) -> Result<()> {
+        self.load_buffer(&tone.to_wav())
+    }
+
+    /// Open a source and pre-buffer it in the background, leaving the player `Ready`
+    /// Playback starts without decode latency if `play` is called after preparation completes.
+    /// The default implementation loads synchronously.
+    fn prepare(&mut self, source: AudioSource) -> Result<()> {
+        match source {
+            AudioSource::File(path) => self.load_file(&path),
+            AudioSource::Url(url) => self.load_url(&url),
+            AudioSource::Buffer(data) => self.load_buffer(&data),
+        }
+    }
+
+    /// Warm up the next source while the current one keeps playing
+    /// A later `prepare` of the same source reuses the warmed decoder. No-op by default.
+    fn prepare_next(&mut self, _source: AudioSource) -> Result<()> {
+        Ok(())
+    }
+
+    /// Load a source and start from `position_ms` (clamped to the duration)
+    /// Implementations should seek before pre-buffering so audio is never primed from 0.
+    /// The default implementation loads and then seeks.
+    fn load_with_resume(&mut self, source: AudioSource, position_ms: u64) -> Result<()> {
+        match source {
+            AudioSource::File(path) => self.load_file(&path)?,
+            AudioSource::Url(url) => self.load_url(&url)?,
+            AudioSource::Buffer(data) => self.load_buffer(&data)?,
+        }
+
+        let duration_ms = self.get_status().duration_ms;
+        let position_ms = if duration_ms > 0 { position_ms.min(duration_ms) } else { position_ms };
+        if position_ms > 0 {
+            self.seek(position_ms)?;
+        }
+        Ok(())
+    }
+
+    /// Token for resuming the current source at the current position (None if nothing is loaded)
+    fn get_resume_token(&self) -> Option<PlaybackToken>;
+
     /// Start or resume playback
     fn play(&mut self) -> Result<()>;
 
@@ -75,10 +254,166 @@ pub trait AudioPlayer: Send + Sync {
     /// Seek to a specific position (in milliseconds)
     fn seek(&mut self, position_ms: u64) -> Result<()>;
 
+    /// Seek with a choice of precision: `Coarse` for scrubbing while dragging, `Accurate` (as
+    /// `seek`) on release. Platforms without a coarse path seek accurately.
+    fn seek_with_mode(&mut self, position_ms: u64, _mode: SeekMode) -> Result<()> {
+        self.seek(position_ms)
+    }
+
+    /// Loop the loaded source sample-accurately (for short sound effects), or stop looping.
+    /// The source is decoded into memory once and played round without seeking, so the loop
+    /// point has no gap; it must be at most `LOOP_BUFFER_MAX_MS` (30 s) long. Looping never
+    /// completes, and positions wrap back to 0. Applies until disabled or the next load.
+    fn set_looping(&mut self, enabled: bool) -> Result<()>;
+
+    /// Block until playback completes or stops (Ok) or fails (Err), or `timeout` elapses (Err).
+    /// Returns immediately if the player is already stopped. Without a timeout, a player that is
+    /// not loading or playing (idle, ready or paused) is an error rather than an endless wait.
+    /// Callbacks are delivered as usual.
+    fn wait_until_complete(&self, timeout: Option<Duration>) -> Result<()>;
+
+    /// Register (or move) a cue point: `CallbackEvent::CuePointReached` fires once when playback
+    /// crosses `position_ms`, and again after a seek or restart moves playback before it
+    fn add_cue_point(&mut self, id: &str, position_ms: u64) -> Result<()>;
+
+    /// Remove a cue point (no-op for unknown ids)
+    fn remove_cue_point(&mut self, id: &str) -> Result<()>;
+
+    /// Register a cue point (id `chapter:<index>`) at the start of each chapter of the current
+    /// and later tracks
+    fn set_chapter_cue_points(&mut self, enabled: bool) -> Result<()>;
+
+    /// Chapters of the loaded source, ordered by start time (empty if it has none)
+    fn chapters(&self) -> Vec<Chapter> {
+        Vec::new()
+    }
+
+    /// Seek to the start of chapter `index`
+    fn seek_to_chapter(&mut self, index: usize) -> Result<()> {
+        let chapters = self.chapters();
+        if chapters.is_empty() {
+            return Err(AudioError::InvalidState("The loaded source has no chapters".to_string()));
+        }
+        let chapter = chapters.get(index).ok_or_else(|| {
+            AudioError::InvalidState(format!("Chapter {} out of range (0..{})", index, chapters.len()))
+        })?;
+        self.seek(chapter.start_time_ms)
+    }
+
+    /// Seek to the start of the chapter after the current one; returns its index
+    fn next_chapter(&mut self) -> Result<usize> {
+        let chapters = self.chapters();
+        if chapters.is_empty() {
+            return Err(AudioError::InvalidState("The loaded source has no chapters".to_string()));
+        }
+        let next = chapter_index_at(&chapters, self.get_status().position_ms).map_or(0, |current| current + 1);
+        if next >= chapters.len() {
+            return Err(AudioError::InvalidState("Already in the last chapter".to_string()));
+        }
+        self.seek_to_chapter(next)?;
+        Ok(next)
+    }
+
+    /// Seek to the start of the current chapter, or of the previous one when less than
+    /// `PREVIOUS_CHAPTER_WINDOW_MS` into the current chapter (like a music player's back button);
+    /// returns the index seeked to
+    fn previous_chapter(&mut self) -> Result<usize> {
+        let chapters = self.chapters();
+        if chapters.is_empty() {
+            return Err(AudioError::InvalidState("The loaded source has no chapters".to_string()));
+        }
+        let position_ms = self.get_status().position_ms;
+        let target = match chapter_index_at(&chapters, position_ms) {
+            Some(current) if position_ms - chapters[current].start_time_ms < PREVIOUS_CHAPTER_WINDOW_MS => {
+                current.saturating_sub(1)
+            }
+            Some(current) => current,
+            None => 0,
+        };
+        self.seek_to_chapter(target)?;
+        Ok(target)
+    }
+
+    /// Pin the output latency for syncing with video (`LatencyMode::Fixed`), or let buffering
+    /// follow the track (`Auto`, default). Applies from the next load.
+    /// In Fixed mode reported positions are those being heard, after the device buffer.
+    fn set_latency_mode(&mut self, mode: LatencyMode) -> Result<()>;
+
+    /// Delay between a sample being decoded and being heard. In Fixed mode this is the
+    /// configured latency and does not change during playback; in Auto it is the audio
+    /// currently buffered ahead of the device (device buffer not included).
+    fn output_latency_ms(&self) -> u64 {
+        0
+    }
+
+    /// Fetch the episode's WebVTT or SRT transcript from `url` in the background, replacing the
+    /// previous one; None clears it. Until the fetch completes (or if it fails, which is logged)
+    /// `current_transcript_cue` returns None.
+    fn set_transcript_url(&mut self, url: Option<&str>) -> Result<()>;
+
+    /// Transcript cue spoken at `position_ms` (usually `get_status().position_ms`)
+    fn current_transcript_cue(&self, _position_ms: u64) -> Option<TranscriptCue> {
+        None
+    }
+
+    /// Seek to an exact frame of the source (sample index per channel, no millisecond rounding)
+    fn seek_to_sample(&mut self, frame_index: u64) -> Result<()>;
+
+    /// Drop all buffered audio and re-prime from the current playback position
+    /// Play/pause state is preserved; a BufferingChanged(true/false) pair is emitted around the refill.
+    fn flush(&mut self) -> Result<()>;
+
+    /// Receive every decoded chunk (interleaved f32 in the source format, before resampling)
+    /// on a dedicated thread, e.g. for custom DSP, recording or a visualizer.
+    /// A tap that falls behind loses chunks; it never blocks decoding.
+    fn add_pcm_tap(&mut self, tap: PcmTap) -> Result<()>;
+
+    /// Receive exactly what is handed to the output device, tagged with its playback position,
+    /// on a dedicated thread. Unlike `add_pcm_tap`, which runs ahead by the buffer depth,
+    /// this stays in step with what is heard, e.g. for spectrum or level meters.
+    fn add_output_tap(&mut self, tap: OutputTap) -> Result<()>;
+
+    /// Set the output limiter's ceiling in dBFS (at most 0, default -0.3), or None to turn it off.
+    /// The limiter is the last stage before the device, so mixed or hot audio cannot clip.
+    fn set_limiter_ceiling(&mut self, ceiling_db: Option<f32>) -> Result<()>;
+
+    /// Save the current track's embedded cover art to `path`, with the extension
+    /// replaced to match the image type (jpg/png/webp)
+    fn write_cover_art(&self, path: &str) -> Result<()>;
+
+    /// Resample every source to a fixed output rate (None = follow the source, the default).
+    /// The output stream is opened at this rate instead of the source's, so downstream mixing
+    /// sees the same rate whatever is loaded. Takes effect when the next output stream is built.
+    fn set_target_sample_rate(&mut self, sample_rate: Option<u32>) -> Result<()>;
+
+    /// Trade latency and memory for fewer wakeups (default `PowerMode::Normal`). LowPower suits
+    /// background playback: see `PowerMode` for what changes. Sleep and callback throttling
+    /// apply right away; the longer ring buffer from the next load.
+    fn set_power_mode(&mut self, mode: PowerMode) -> Result<()>;
+
+    /// Scheduling priority of the decoder thread (default `ThreadPriority::Elevated`).
+    /// Takes effect the next time the decoder thread starts.
+    fn set_thread_priority(&mut self, priority: ThreadPriority) -> Result<()>;
+
+    /// Lower the main output to `level` (0.0 - 1.0) over `fade_ms`, e.g. while the app plays a
+    /// notification or TTS clip over playback. Ducking is applied on top of volume, so
+    /// `set_volume` during a duck is kept and `unduck` returns to it.
+    fn duck(&mut self, level: f32, fade_ms: u64) -> Result<()>;
+
+    /// Fade the main output back up from a `duck` over `fade_ms`
+    fn unduck(&mut self, fade_ms: u64) -> Result<()>;
+
+    /// Normalize loudness with the tracks' ReplayGain tags (default off). The gain is lowered
+    /// where the tagged peak would otherwise clip; untagged tracks play unchanged.
+    fn set_replay_gain(&mut self, settings: ReplayGainSettings) -> Result<()>;
+
     /// Set volume (0.0 - 1.0)
+    /// Valid once a source is loaded (Ready, Playing, Paused, ...); an idle player with no output
+    /// engine, never loaded or released, returns `InvalidState`.
     fn set_volume(&mut self, volume: f32) -> Result<()>;
 
     /// Set playback rate/speed (1.0 = normal speed)
+    /// Like `set_volume`, returns `InvalidState` on an idle player with no output engine.
     fn set_playback_rate(&mut self, rate: f32) -> Result<()>;
 
     /// Get current player state
@@ -87,40 +422,148 @@ pub trait AudioPlayer: Send + Sync {
     /// Get current playback status
     fn get_status(&self) -> PlaybackStatus;
 
-    /// Set a callback for player events
+    /// Sample rate the output stream was actually opened at (0 before a stream is initialized)
+    fn output_sample_rate(&self) -> u32;
+
+    /// Channel count the output stream was actually opened with (0 before a stream is initialized)
+    fn output_channels(&self) -> u16;
+
+    /// Set a callback for player events, replacing every registered callback (None clears them)
     fn set_callback(&mut self, callback: Option<Arc<dyn PlayerCallback>>);
 
+    /// Register another callback next to the existing ones, e.g. analytics beside the UI
+    /// Each gets position updates at most every `position_update_interval_ms`.
+    fn add_callback(
+        &mut self,
+        callback: Arc<dyn PlayerCallback>,
+        position_update_interval_ms: u64,
+    ) -> CallbackHandle;
+
+    /// Unregister a callback added with `add_callback`; false if it was already removed
+    fn remove_callback(&mut self, handle: CallbackHandle) -> bool;
+
+    /// Decode counters for the current source (default when nothing is loaded)
+    fn get_stats(&self) -> PlaybackStats {
+        PlaybackStats::default()
+    }
+
+    /// Events waiting for the callback thread (diagnostics: a growing depth means a slow callback)
+    fn event_queue_depth(&self) -> usize {
+        0
+    }
+
+    /// Set how often position updates are dispatched (milliseconds)
+    /// Applies to the `set_callback` callback and to playback already in progress; callbacks
+    /// added with their own interval (`add_callback`) keep it
+    fn set_position_update_interval_ms(&mut self, interval_ms: u64);
+
     /// Release all resources
     fn release(&mut self) -> Result<()>;
 
+    /// EQ, metering, output bit depth and scrub previews, where the platform's output path has them
+    fn output_processing(&mut self) -> Option<&mut dyn OutputProcessing> {
+        None
+    }
+
+    /// Switching between a file's audio tracks, where the platform supports it
+    fn track_selection(&mut self) -> Option<&mut dyn TrackSelection> {
+        None
+    }
+
     /// Downcast to concrete type (for accessing platform-specific features)
     fn as_any(&self) -> &dyn std::any::Any;
+
+    /// Mutable `as_any`, for platform-specific loads and settings
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
+}
+
+/// Output-path extras of the players that have them (see `AudioPlayer::output_processing`)
+pub trait OutputProcessing {
+    /// Select the output sample format (float by default). Integer depths are used only when the
+    /// device supports them and take effect when the next output stream is built (next load).
+    fn set_output_bit_depth(&mut self, depth: OutputBitDepth) -> Result<()>;
+
+    /// Set the output equalizer's peaking bands (at most `MAX_EQ_BANDS`; empty turns it off).
+    /// Every output channel is filtered on its own, so the bands suit surround layouts as well.
+    fn set_eq_bands(&mut self, bands: &[EqBand]) -> Result<()>;
+
+    /// Peak and RMS of each output channel since the previous call (one entry per channel)
+    fn output_levels(&self) -> Result<OutputLevels>;
+
+    /// Briefly play `duration_ms` of audio from `position_ms` over the (ducked) main playback,
+    /// e.g. while the user drags a scrub bar. The playback position and state are not changed.
+    fn scrub_preview(&mut self, position_ms: u64, duration_ms: u64) -> Result<()>;
+}
+
+/// Audio track switching of the players that have it (see `AudioPlayer::track_selection`)
+pub trait TrackSelection {
+    /// Switch the loaded source to another audio track (see `AudioDecoder::list_tracks`).
+    /// Playback continues from the current position on the new track.
+    fn set_audio_track(&mut self, track_id: u32) -> Result<()>;
 }
 
 /// Thread-safe player state container
 #[derive(Clone)]
 pub struct PlayerStateContainer {
-    state: Arc<RwLock<PlayerState>>,
+    state: Arc<Mutex<PlayerState>>,
+    /// Signalled on every state change (see `wait_until_complete`)
+    state_changed: Arc<Condvar>,
     status: Arc<RwLock<PlaybackStatus>>,
 }
 
 impl PlayerStateContainer {
     pub fn new() -> Self {
         Self {
-            state: Arc::new(RwLock::new(PlayerState::Idle)),
+            state: Arc::new(Mutex::new(PlayerState::Idle)),
+            state_changed: Arc::new(Condvar::new()),
             status: Arc::new(RwLock::new(PlaybackStatus::default())),
         }
     }
 
     pub fn get_state(&self) -> PlayerState {
-        *self.state.read()
+        *self.state.lock()
     }
 
     pub fn set_state(&self, new_state: PlayerState) {
-        *self.state.write() = new_state;
+        *self.state.lock() = new_state;
+        self.state_changed.notify_all();
         log::debug!("Player state changed to: {:?}", new_state);
     }
 
+    /// Block until the state is Stopped (Ok) or Error (Err), or `timeout` elapses (Err).
+    /// With no timeout, Idle, Ready and Paused are refused (Err): nothing would end the wait.
+    pub fn wait_until_complete(&self, timeout: Option<Duration>) -> Result<()> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = self.state.lock();
+        loop {
+            match *state {
+                PlayerState::Stopped => return Ok(()),
+                PlayerState::Error => {
+                    return Err(AudioError::PlaybackError("Playback stopped with an error".to_string()))
+                }
+                PlayerState::Loading | PlayerState::Playing => {}
+                _ if deadline.is_none() => {
+                    return Err(AudioError::InvalidState(format!(
+                        "Nothing is playing to wait for (state {:?})",
+                        *state
+                    )))
+                }
+                _ => {}
+            }
+            match deadline {
+                Some(deadline) => {
+                    if self.state_changed.wait_until(&mut state, deadline).timed_out() {
+                        return Err(AudioError::PlaybackError(format!(
+                            "Timed out waiting for playback to complete (state {:?})",
+                            *state
+                        )));
+                    }
+                }
+                None => self.state_changed.wait(&mut state),
+            }
+        }
+    }
+
     pub fn get_status(&self) -> PlaybackStatus {
         self.status.read().clone()
     }
@@ -191,3 +634,51 @@ impl Default for PlayerStateContainer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_wait_until_complete_wakes_on_completion() {
+        let container = PlayerStateContainer::new();
+        container.set_state(PlayerState::Playing);
+
+        let decoder_side = container.clone();
+        let finisher = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            decoder_side.set_state(PlayerState::Stopped);
+        });
+
+        let start = Instant::now();
+        container.wait_until_complete(Some(Duration::from_secs(5))).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(1));
+        finisher.join().unwrap();
+
+        // Errors and timeouts are reported as such
+        container.set_state(PlayerState::Playing);
+        assert!(container.wait_until_complete(Some(Duration::from_millis(20))).is_err());
+        container.set_state(PlayerState::Error);
+        assert!(container.wait_until_complete(None).is_err());
+    }
+
+    #[test]
+    fn test_wait_without_timeout_refuses_states_that_never_complete() {
+        let container = PlayerStateContainer::new();
+        for state in [PlayerState::Idle, PlayerState::Ready, PlayerState::Paused] {
+            container.set_state(state);
+            assert!(matches!(container.wait_until_complete(None), Err(AudioError::InvalidState(_))));
+        }
+
+        // Pausing mid-wait ends the wait too
+        container.set_state(PlayerState::Playing);
+        let ui_side = container.clone();
+        let pauser = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            ui_side.set_state(PlayerState::Paused);
+        });
+        assert!(matches!(container.wait_until_complete(None), Err(AudioError::InvalidState(_))));
+        pauser.join().unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+// Power mode: how eagerly the decode thread keeps the ring buffer topped up
+// Normal keeps latency and memory low by refilling in small steps every few milliseconds.
+// LowPower is meant for background playback (screen off, podcasts): a much longer ring buffer
+// is refilled in large batches with long sleeps in between, and position callbacks are
+// throttled. The cost is memory (tens of seconds of decoded audio), coarser position updates,
+// and a longer delay before anything applied at decode time (e.g. Android's volume) is heard.
+
+use std::time::Duration;
+
+/// Ring buffer length used in LowPower mode, in seconds
+pub const LOW_POWER_BUFFER_SECS: u64 = 20;
+
+/// Floor on the position callback interval in LowPower mode
+pub const LOW_POWER_POSITION_INTERVAL_MS: u64 = 1000;
+
+/// Bounds on the decode thread's sleep once the ring buffer is full in LowPower mode
+const LOW_POWER_MIN_SLEEP_MS: u64 = 50;
+const LOW_POWER_MAX_SLEEP_MS: u64 = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PowerMode {
+    /// Small buffer, frequent refills (default)
+    #[default]
+    Normal,
+    /// Large buffer, refilled in batches; fewer wakeups at the cost of memory and latency
+    LowPower,
+}
+
+impl PowerMode {
+    /// Upper bound on the ring buffer length, given the platform's normal bound
+    pub fn max_buffer_secs(self, normal_max_secs: u64) -> u64 {
+        match self {
+            PowerMode::Normal => normal_max_secs,
+            PowerMode::LowPower => normal_max_secs.max(LOW_POWER_BUFFER_SECS),
+        }
+    }
+
+    /// How long the decode thread sleeps when the ring buffer is full.
+    /// LowPower waits for about half of the buffered audio to play, so each wakeup refills a
+    /// large batch instead of a few milliseconds' worth.
+    pub fn full_buffer_sleep(self, fullness: f32, buffered_ms: u64) -> Duration {
+        let sleep_ms = match self {
+            PowerMode::Normal if fullness > 0.9 => 15,
+            PowerMode::Normal if fullness > 0.7 => 10,
+            PowerMode::Normal => 5,
+            PowerMode::LowPower => (buffered_ms / 2).clamp(LOW_POWER_MIN_SLEEP_MS, LOW_POWER_MAX_SLEEP_MS),
+        };
+        Duration::from_millis(sleep_ms)
+    }
+
+    /// How long the decode thread sleeps between checks while paused
+    pub fn idle_sleep(self) -> Duration {
+        match self {
+            PowerMode::Normal => Duration::from_millis(10),
+            PowerMode::LowPower => Duration::from_millis(LOW_POWER_MIN_SLEEP_MS),
+        }
+    }
+
+    /// Position callback interval actually used for a requested one
+    pub fn position_interval_ms(self, requested_ms: u64) -> u64 {
+        match self {
+            PowerMode::Normal => requested_ms,
+            PowerMode::LowPower => requested_ms.max(LOW_POWER_POSITION_INTERVAL_MS),
+        }
+    }
+}
+
+/// Duration of `samples` interleaved samples
+pub fn buffered_ms(samples: usize, channels: u16, sample_rate: u32) -> u64 {
+    if channels == 0 || sample_rate == 0 {
+        return 0;
+    }
+    (samples / channels as usize) as u64 * 1000 / sample_rate as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::AudioRingBuffer;
+
+    /// Times the decode loop wakes up while `seconds` of 48 kHz stereo play: it writes packets
+    /// until the ring is full, then sleeps while the device drains it in real time
+    fn decode_loop_wakeups(mode: PowerMode, seconds: u64) -> u64 {
+        const RATE: u32 = 48000;
+        let mut ring = AudioRingBuffer::new((RATE as u64 * 2 * mode.max_buffer_secs(8)) as usize);
+        let packet = vec![0.0; 1152 * 2];
+        let (mut now_ms, mut wakeups) = (0, 0);
+        while now_ms < seconds * 1000 {
+            if ring.write(&packet) > 0 {
+                continue;
+            }
+            let buffered = buffered_ms(ring.available_read(), 2, RATE);
+            let sleep = mode.full_buffer_sleep(ring.fullness(), buffered).as_millis() as u64;
+            let mut played = vec![0.0; (sleep * RATE as u64 / 1000 * 2) as usize];
+            ring.read(&mut played);
+            now_ms += sleep;
+            wakeups += 1;
+        }
+        wakeups
+    }
+
+    #[test]
+    fn test_low_power_wakes_the_decoder_less_often() {
+        let normal = decode_loop_wakeups(PowerMode::Normal, 60);
+        let low_power = decode_loop_wakeups(PowerMode::LowPower, 60);
+        assert!(normal >= 60 * 50, "{} wakeups in normal mode", normal);
+        assert!(low_power <= 60, "{} wakeups in low power mode", low_power);
+
+        assert_eq!(PowerMode::LowPower.position_interval_ms(100), LOW_POWER_POSITION_INTERVAL_MS);
+        assert_eq!(PowerMode::Normal.position_interval_ms(100), 100);
+        assert!(PowerMode::LowPower.idle_sleep() > PowerMode::Normal.idle_sleep());
+    }
+}
@@ -0,0 +1,316 @@
+// Background source preparation
+// Opens the decoder and decodes the first few hundred milliseconds off the caller's thread,
+// so that play() after a list tap does not pay the full open + decode latency
+
+use crate::decoder::AudioDecoder;
+use crate::error::{AudioError, Result};
+use crate::metadata::FormatInfo;
+use crate::player::AudioSource;
+use crate::threads::{catch_panic, spawn_named, PREPARE_THREAD};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::thread;
+
+/// Callback invoked once when preparation finishes (what was prepared or the error that stopped it)
+type ReadyCallback = Box<dyn FnOnce(Result<PreparedInfo>) + Send>;
+
+/// Summary of a finished preparation, for the ready callback
+#[derive(Debug, Clone)]
+pub struct PreparedInfo {
+    pub format: FormatInfo,
+    /// Audio decoded ahead, at most the requested pre-buffer (less for very short sources)
+    pub buffered_ms: u64,
+}
+
+/// A source that has been opened and pre-decoded
+pub struct PreparedSource {
+    pub decoder: AudioDecoder,
+    /// Interleaved samples at the decoder's native rate, ready to be written to the ring buffer
+    pub prebuffer: Vec<f32>,
+}
+
+/// Completion state shared between the worker thread and the owner
+struct ReadyState {
+    outcome: Option<Result<PreparedInfo>>,
+    on_ready: Option<ReadyCallback>,
+}
+
+/// Handle to a background preparation
+pub struct PrepareTask {
+    source: AudioSource,
+    handle: thread::JoinHandle<Result<PreparedSource>>,
+    ready: Arc<Mutex<ReadyState>>,
+}
+
+/// Open a decoder for any supported source description
+pub fn open_decoder(source: &AudioSource) -> Result<AudioDecoder> {
+    match source {
+        AudioSource::File(path) => AudioDecoder::from_file(path),
+        AudioSource::Url(url) => AudioDecoder::from_url(url),
+        AudioSource::Buffer(data) => AudioDecoder::from_buffer(data.clone()),
+    }
+}
+
+/// Open a decoder positioned at `position_ms` (clamped to the known duration)
+/// Returns the decoder and the position it actually starts from
+pub fn open_decoder_at(source: &AudioSource, position_ms: u64) -> Result<(AudioDecoder, u64)> {
+    let mut decoder = open_decoder(source)?;
+    let duration_ms = decoder.format.duration_ms;
+    let start_ms = if duration_ms > 0 {
+        position_ms.min(duration_ms)
+    } else {
+        position_ms
+    };
+
+    if start_ms > 0 {
+        decoder.seek(start_ms)?;
+    }
+    Ok((decoder, start_ms))
+}
+
+/// Decode `duration_ms` of audio from `position_ms` with a throwaway decoder (scrub previews).
+/// Returns interleaved samples with their sample rate and channel count.
+pub fn decode_snippet(source: &AudioSource, position_ms: u64, duration_ms: u64) -> Result<(Vec<f32>, u32, u16)> {
+    let (mut decoder, _) = open_decoder_at(source, position_ms)?;
+    let sample_rate = decoder.format.sample_rate;
+    let channels = decoder.format.channels.max(1);
+    let target_samples = ((duration_ms * sample_rate as u64) / 1000) as usize * channels as usize;
+
+    let mut snippet = Vec::with_capacity(target_samples);
+    while snippet.len() < target_samples {
+        match decoder.decode_next()? {
+            Some(samples) => snippet.extend_from_slice(&samples),
+            None => break,
+        }
+    }
+    snippet.truncate(target_samples);
+    Ok((snippet, sample_rate, channels))
+}
+
+/// Open `source` and decode roughly `prebuffer_ms` of audio
+pub fn prepare_source(source: &AudioSource, prebuffer_ms: u64) -> Result<PreparedSource> {
+    let mut decoder = open_decoder(source)?;
+    let target_samples = ((prebuffer_ms * decoder.format.sample_rate as u64) / 1000) as usize
+        * decoder.output_channels().max(1) as usize;
+
+    let mut prebuffer = Vec::with_capacity(target_samples);
+    while prebuffer.len() < target_samples {
+        match decoder.decode_next() {
+            Ok(Some(samples)) => prebuffer.extend_from_slice(&samples),
+            Ok(None) => break,
+            Err(e) => {
+                log::warn!("Prepare: pre-buffer decode error: {}", e);
+                break;
+            }
+        }
+    }
+
+    Ok(PreparedSource {
+        decoder,
+        prebuffer,
+    })
+}
+
+impl PreparedSource {
+    /// Duration of the pre-decoded samples
+    pub fn buffered_ms(&self) -> u64 {
+        let frames = (self.prebuffer.len() / self.decoder.output_channels().max(1) as usize) as u64;
+        frames * 1000 / self.decoder.format.sample_rate.max(1) as u64
+    }
+}
+
+impl PrepareTask {
+    /// Start preparing `source` on a background thread
+    pub fn spawn(source: AudioSource, prebuffer_ms: u64) -> Result<Self> {
+        let ready = Arc::new(Mutex::new(ReadyState {
+            outcome: None,
+            on_ready: None,
+        }));
+
+        let worker_source = source.clone();
+        let worker_ready = ready.clone();
+        let handle = spawn_named(PREPARE_THREAD, move || {
+            let result = catch_panic(|| prepare_source(&worker_source, prebuffer_ms)).and_then(|r| r);
+            let outcome = result
+                .as_ref()
+                .map(|prepared| PreparedInfo {
+                    format: prepared.decoder.metadata.format_info.clone(),
+                    buffered_ms: prepared.buffered_ms(),
+                })
+                .map_err(|e| e.clone());
+
+            let callback = {
+                let mut state = worker_ready.lock();
+                state.outcome = Some(outcome.clone());
+                state.on_ready.take()
+            };
+            if let Some(callback) = callback {
+                callback(outcome);
+            }
+            result
+        })?;
+
+        Ok(Self {
+            source,
+            handle,
+            ready,
+        })
+    }
+
+    pub fn source(&self) -> &AudioSource {
+        &self.source
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.ready.lock().outcome.is_some()
+    }
+
+    /// Register a completion callback. Runs immediately if preparation already finished.
+    pub fn set_on_ready(&self, callback: impl FnOnce(Result<PreparedInfo>) + Send + 'static) {
+        let mut state = self.ready.lock();
+        if let Some(outcome) = state.outcome.clone() {
+            drop(state);
+            callback(outcome);
+        } else {
+            state.on_ready = Some(Box::new(callback));
+        }
+    }
+
+    /// Drop any pending completion callback (used when the task is superseded)
+    pub fn cancel(&self) {
+        self.ready.lock().on_ready = None;
+    }
+
+    /// Wait for the prepared source (returns immediately once `is_finished`)
+    pub fn wait(self) -> Result<PreparedSource> {
+        self.handle
+            .join()
+            .map_err(|_| AudioError::ThreadError("Prepare thread panicked".to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::tests::test_wav;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_decode_snippet_is_clipped_to_window() {
+        let source = AudioSource::Buffer(test_wav(44100, 2, 2));
+        let (snippet, sample_rate, channels) = decode_snippet(&source, 1500, 100).unwrap();
+        assert_eq!((sample_rate, channels), (44100, 2));
+        assert_eq!(snippet.len(), 4410 * 2);
+
+        // Near the end only what is left is returned
+        let (tail, _, _) = decode_snippet(&source, 1950, 100).unwrap();
+        assert!(tail.len() <= 2205 * 2);
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "linux", all(target_os = "macos", not(target_os = "ios"))))]
+    #[test]
+    fn test_prepared_source_is_available_instantly() {
+        use crate::desktop::tests::{buffered_samples, headless_player};
+        use crate::player::{AudioPlayer, PlayerState};
+
+        let mut player = headless_player();
+        let (chunks, decoded) = std::sync::mpsc::channel();
+        player.add_pcm_tap(Box::new(move |chunk: &[f32]| chunks.send(chunk.to_vec()).unwrap())).unwrap();
+        player.prepare(AudioSource::Buffer(test_wav(44100, 2, 2))).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while player.get_state() != PlayerState::Ready && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(player.get_state(), PlayerState::Ready);
+        // All the decoding so far happened on the prepare thread
+        assert_eq!(player.get_stats().decoded_frames, 0);
+        assert!(decoded.try_recv().is_err());
+
+        player.play().unwrap();
+        // What reached the ring first is the pre-buffer decoded by `prepare`, handed over whole:
+        // play() did not decode it again, packet by packet
+        let prebuffer = decoded.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(prebuffer.len() >= 4410 * 2, "{} samples", prebuffer.len());
+        let reference = AudioDecoder::from_buffer(test_wav(44100, 2, 2)).unwrap().decode_all().unwrap().1;
+        assert!(prebuffer[..] == reference[..prebuffer.len()]);
+        let played = (player.get_status().position_ms * 44100 / 1000) as usize * 2;
+        assert!(played + buffered_samples(&player) >= prebuffer.len());
+        player.stop().unwrap();
+    }
+
+    #[test]
+    fn test_ready_callback_carries_format() {
+        let task = PrepareTask::spawn(AudioSource::Buffer(test_wav(22050, 1, 1)), 100).unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        task.set_on_ready(move |outcome| {
+            tx.send(outcome).unwrap();
+        });
+
+        let info = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        let format = info.format;
+        assert_eq!(format.sample_rate, 22050);
+        assert_eq!(format.channels, 1);
+        assert_eq!(format.duration_ms, 1000);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_ready_callback_reports_prebuffered_audio() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let task = PrepareTask::spawn(AudioSource::Buffer(test_wav(44100, 2, 2)), 250).unwrap();
+        task.set_on_ready(move |outcome| tx.send(outcome).unwrap());
+        let info = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        assert!(info.buffered_ms >= 250, "{} ms", info.buffered_ms);
+        assert_eq!(task.wait().unwrap().buffered_ms(), info.buffered_ms);
+
+        // A source shorter than the target reports what there is
+        let (tx, rx) = std::sync::mpsc::channel();
+        let short = PrepareTask::spawn(AudioSource::Buffer(test_wav(8000, 1, 1)), 5000).unwrap();
+        short.set_on_ready(move |outcome| tx.send(outcome).unwrap());
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap().buffered_ms, 1000);
+    }
+
+    #[test]
+    fn test_prepare_reports_errors() {
+        let task = PrepareTask::spawn(AudioSource::Buffer(vec![0u8; 16]), 100).unwrap();
+        let result = task.wait();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_resume_starts_at_position() {
+        use crate::decoder::{write_wav, AudioFormat};
+
+        // Ramp signal so every frame is unique
+        let format = AudioFormat {
+            sample_rate: 8000,
+            channels: 2,
+            bits_per_sample: 32,
+            duration_ms: 2000,
+            encoder_delay: 0,
+            encoder_padding: 0,
+        };
+        let samples: Vec<f32> = (0..16000u32)
+            .flat_map(|i| {
+                let v = i as f32 / 16000.0;
+                [v, -v]
+            })
+            .collect();
+        let path = std::env::temp_dir().join(format!("rust_audio_player_resume_{}.wav", std::process::id()));
+        write_wav(&path, &format, &samples).unwrap();
+        let source = AudioSource::File(path.to_string_lossy().to_string());
+
+        let (mut decoder, start_ms) = open_decoder_at(&source, 1250).unwrap();
+        assert_eq!(start_ms, 1250);
+        let first = decoder.decode_next().unwrap().unwrap();
+        let expected_frame = 1250 * 8000 / 1000;
+        assert_eq!(&first[..4], &samples[expected_frame * 2..expected_frame * 2 + 4]);
+
+        // Positions past the end are clamped to the duration
+        let (_, clamped_ms) = open_decoder_at(&source, 60_000).unwrap();
+        assert_eq!(clamped_ms, 2000);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,125 @@
+// ReplayGain normalization
+// Tagged files carry a gain (dB) bringing them to a reference loudness, and the peak sample
+// magnitude (1.0 = full scale). Boosting a quiet but dynamic track by its full gain can push its
+// peaks over 0 dBFS, so the applied gain is capped to keep gain * peak within the ceiling.
+
+use crate::error::{AudioError, Result};
+
+/// Highest level a normalized peak may reach (0 dBFS)
+pub const CLIP_CEILING: f32 = 1.0;
+
+/// ReplayGain tags of a track (REPLAYGAIN_TRACK_GAIN, ..._TRACK_PEAK, ..._ALBUM_GAIN, ..._ALBUM_PEAK)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ReplayGain {
+    pub track_gain_db: Option<f32>,
+    /// Largest sample magnitude in the track, 1.0 = full scale
+    pub track_peak: Option<f32>,
+    pub album_gain_db: Option<f32>,
+    pub album_peak: Option<f32>,
+}
+
+/// Which ReplayGain value to normalize with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayGainMode {
+    #[default]
+    Off,
+    /// Every track at the reference loudness
+    Track,
+    /// Keep the loudness differences within an album (track values when there are no album tags)
+    Album,
+}
+
+/// Normalization applied by the player
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ReplayGainSettings {
+    pub mode: ReplayGainMode,
+    /// Added to the tagged gain, e.g. +6 dB for a louder reference (still peak-limited)
+    pub preamp_db: f32,
+}
+
+impl ReplayGainSettings {
+    /// Check the settings: the preamp must be finite and within ±24 dB
+    pub fn validate(&self) -> Result<()> {
+        if !self.preamp_db.is_finite() || self.preamp_db.abs() > 24.0 {
+            return Err(AudioError::InvalidState(format!("Invalid ReplayGain preamp: {} dB", self.preamp_db)));
+        }
+        Ok(())
+    }
+}
+
+impl ReplayGain {
+    /// Linear gain for `settings`, lowered where needed so the tagged peak stays within
+    /// `CLIP_CEILING`; 1.0 when normalization is off or the track has no gain tag
+    pub fn normalization_gain(&self, settings: ReplayGainSettings) -> f32 {
+        let (gain_db, peak) = match settings.mode {
+            ReplayGainMode::Off => return 1.0,
+            ReplayGainMode::Track => {
+                (self.track_gain_db.or(self.album_gain_db), self.track_peak.or(self.album_peak))
+            }
+            ReplayGainMode::Album => {
+                (self.album_gain_db.or(self.track_gain_db), self.album_peak.or(self.track_peak))
+            }
+        };
+        let Some(gain_db) = gain_db else {
+            return 1.0;
+        };
+        let gain = 10f32.powf((gain_db + settings.preamp_db) / 20.0);
+        match peak {
+            Some(peak) if peak > 0.0 => gain.min(CLIP_CEILING / peak),
+            _ => gain,
+        }
+    }
+
+    /// Parse a gain tag such as "-6.54 dB" or "+1.2dB"
+    pub(crate) fn parse_gain(value: &str) -> Option<f32> {
+        let value = value.trim();
+        let number = value
+            .strip_suffix("dB")
+            .or_else(|| value.strip_suffix("db"))
+            .or_else(|| value.strip_suffix("DB"))
+            .unwrap_or(value);
+        number.trim().parse::<f32>().ok().filter(|gain| gain.is_finite())
+    }
+
+    /// Parse a peak tag such as "0.988553"
+    pub(crate) fn parse_peak(value: &str) -> Option<f32> {
+        value.trim().parse::<f32>().ok().filter(|peak| peak.is_finite() && *peak >= 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gain_is_limited_by_peak() {
+        let tags = ReplayGain {
+            track_gain_db: ReplayGain::parse_gain("+6.02 dB"),
+            track_peak: ReplayGain::parse_peak("0.8"),
+            album_gain_db: ReplayGain::parse_gain("-3 dB"),
+            album_peak: ReplayGain::parse_peak("0.95"),
+        };
+        let track = ReplayGainSettings { mode: ReplayGainMode::Track, preamp_db: 0.0 };
+        let album = ReplayGainSettings { mode: ReplayGainMode::Album, ..track };
+
+        // +6 dB would take the 0.8 peak to 1.6: capped at 1 / 0.8
+        let gain = tags.normalization_gain(track);
+        assert!((gain - 1.25).abs() < 1e-4, "{}", gain);
+        assert!(gain * 0.8 <= CLIP_CEILING + 1e-6);
+
+        // Attenuation never needs limiting
+        let gain = tags.normalization_gain(album);
+        assert!((gain - 0.7079).abs() < 1e-3, "{}", gain);
+
+        // Without a peak tag the gain is applied as is
+        let unpeaked = ReplayGain { track_gain_db: Some(6.0), ..Default::default() };
+        assert!((unpeaked.normalization_gain(track) - 1.9953).abs() < 1e-3);
+        assert_eq!(unpeaked.normalization_gain(ReplayGainSettings::default()), 1.0);
+        assert_eq!(ReplayGain::default().normalization_gain(track), 1.0);
+
+        assert_eq!(ReplayGain::parse_gain("-6.54dB"), Some(-6.54));
+        assert_eq!(ReplayGain::parse_gain("loud"), None);
+        assert_eq!(ReplayGain::parse_peak("-1"), None);
+        assert!(ReplayGainSettings { preamp_db: f32::NAN, ..track }.validate().is_err());
+    }
+}
@@ -0,0 +1,152 @@
+// Output bit depth selection and f32 -> integer sample conversion
+// Decoded audio is carried as f32 internally; when a high-res device accepts 24/32-bit integer
+// samples we convert straight to that depth (with TPDF dither) instead of going through 16-bit.
+
+/// Preferred output sample format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputBitDepth {
+    /// 32-bit float stream (no conversion; the device/OS mixer handles the rest)
+    #[default]
+    Float,
+    /// 16-bit integer output
+    Int16,
+    /// 24-bit integer output (falls back to a 32-bit container if the device has no packed 24-bit format)
+    Int24,
+    /// 32-bit integer output
+    Int32,
+}
+
+impl OutputBitDepth {
+    /// Convert from the integer used by the FFI/JNI layers (0=float, 16, 24, 32)
+    pub fn from_bits(bits: i32) -> Option<Self> {
+        match bits {
+            0 => Some(OutputBitDepth::Float),
+            16 => Some(OutputBitDepth::Int16),
+            24 => Some(OutputBitDepth::Int24),
+            32 => Some(OutputBitDepth::Int32),
+            _ => None,
+        }
+    }
+
+    /// Integer bit depth (0 for float)
+    pub fn bits(&self) -> u16 {
+        match self {
+            OutputBitDepth::Float => 0,
+            OutputBitDepth::Int16 => 16,
+            OutputBitDepth::Int24 => 24,
+            OutputBitDepth::Int32 => 32,
+        }
+    }
+}
+
+/// Triangular (TPDF) dither source producing offsets in [-1, 1) LSB.
+/// Uses a small xorshift generator so it is allocation- and lock-free for the audio callback.
+pub struct TpdfDither {
+    state: u32,
+}
+
+impl TpdfDither {
+    pub fn new(seed: u32) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    fn next_uniform(&mut self) -> f64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state as f64 / u32::MAX as f64
+    }
+
+    /// Next dither offset in LSB units (sum of two uniform values, triangular distribution)
+    pub fn next_offset(&mut self) -> f64 {
+        self.next_uniform() - self.next_uniform()
+    }
+}
+
+impl Default for TpdfDither {
+    fn default() -> Self {
+        Self::new(0x2545_f491)
+    }
+}
+
+/// Scale, dither, round and clamp a float sample to a signed integer of `bits` bits
+fn quantize(sample: f32, bits: u32, dither_lsb: f64) -> i64 {
+    let max = (1i64 << (bits - 1)) as f64;
+    let scaled = (sample.clamp(-1.0, 1.0) as f64) * max + dither_lsb;
+    (scaled.round() as i64).clamp(-(1i64 << (bits - 1)), (1i64 << (bits - 1)) - 1)
+}
+
+/// Convert to 16-bit (`dither_lsb` is added before rounding; pass 0.0 for none)
+pub fn f32_to_i16(sample: f32, dither_lsb: f64) -> i16 {
+    quantize(sample, 16, dither_lsb) as i16
+}
+
+/// Convert to 24-bit, returned in the low bits of an i32 (range -(1 << 23)..(1 << 23))
+pub fn f32_to_i24(sample: f32, dither_lsb: f64) -> i32 {
+    quantize(sample, 24, dither_lsb) as i32
+}
+
+/// Convert to full-scale 32-bit
+pub fn f32_to_i32(sample: f32, dither_lsb: f64) -> i32 {
+    quantize(sample, 32, dither_lsb) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const I24_MAX: i32 = (1 << 23) - 1;
+    const I24_MIN: i32 = -(1 << 23);
+
+    #[test]
+    fn test_f32_to_i24_range_and_rounding() {
+        assert_eq!(f32_to_i24(0.0, 0.0), 0);
+        assert_eq!(f32_to_i24(1.0, 0.0), I24_MAX);
+        assert_eq!(f32_to_i24(-1.0, 0.0), I24_MIN);
+        assert_eq!(f32_to_i24(0.5, 0.0), 1 << 22);
+        assert_eq!(f32_to_i24(-0.5, 0.0), -(1 << 22));
+
+        // Out-of-range input clips instead of wrapping
+        assert_eq!(f32_to_i24(1.5, 0.0), I24_MAX);
+        assert_eq!(f32_to_i24(-1.5, 0.0), I24_MIN);
+
+        // Rounds to nearest LSB
+        let lsb = 1.0 / (1u32 << 23) as f32;
+        assert_eq!(f32_to_i24(lsb * 0.4, 0.0), 0);
+        assert_eq!(f32_to_i24(lsb * 0.6, 0.0), 1);
+        assert_eq!(f32_to_i24(-lsb * 0.6, 0.0), -1);
+    }
+
+    #[test]
+    fn test_f32_to_i32_range_and_rounding() {
+        assert_eq!(f32_to_i32(0.0, 0.0), 0);
+        assert_eq!(f32_to_i32(1.0, 0.0), i32::MAX);
+        assert_eq!(f32_to_i32(-1.0, 0.0), i32::MIN);
+        assert_eq!(f32_to_i32(0.25, 0.0), 1 << 29);
+        assert_eq!(f32_to_i32(2.0, 0.0), i32::MAX);
+        assert_eq!(f32_to_i32(-2.0, 0.0), i32::MIN);
+
+        // 24-bit precision of f32 is preserved exactly
+        let sample = 0x12_3456 as f32 / (1u32 << 23) as f32;
+        assert_eq!(f32_to_i32(sample, 0.0), 0x12_3456 << 8);
+    }
+
+    #[test]
+    fn test_dither_stays_within_one_lsb() {
+        let mut dither = TpdfDither::default();
+        for i in 0..10_000 {
+            let sample = (i as f32 / 10_000.0) * 2.0 - 1.0;
+            let plain = f32_to_i24(sample, 0.0);
+            let dithered = f32_to_i24(sample, dither.next_offset());
+            assert!((dithered - plain).abs() <= 1, "{} vs {}", dithered, plain);
+        }
+    }
+
+    #[test]
+    fn test_bit_depth_from_bits() {
+        assert_eq!(OutputBitDepth::from_bits(24), Some(OutputBitDepth::Int24));
+        assert_eq!(OutputBitDepth::from_bits(0), Some(OutputBitDepth::Float));
+        assert_eq!(OutputBitDepth::from_bits(8), None);
+        assert_eq!(OutputBitDepth::Int32.bits(), 32);
+    }
+}
@@ -0,0 +1,181 @@
+// Reusable scratch storage and frame helpers for real-time audio callbacks
+// The output callback runs on a high-priority audio thread where heap allocation can cause glitches
+
+use crate::decoder::AudioRingBuffer;
+use crate::ducking::DuckGain;
+use crate::limiter::Limiter;
+use parking_lot::Mutex;
+
+/// Initial scratch capacity in samples (covers typical Oboe bursts of up to 2048 stereo frames)
+pub const DEFAULT_SCRATCH_SAMPLES: usize = 2048 * 2;
+
+/// Interleaved f32 buffer that is allocated once and reused across callbacks.
+/// It only grows when a callback asks for more samples than it has ever held before.
+pub struct ScratchBuffer {
+    data: Vec<f32>,
+}
+
+impl ScratchBuffer {
+    pub fn with_capacity(samples: usize) -> Self {
+        Self {
+            data: vec![0.0; samples],
+        }
+    }
+
+    /// Get a slice of exactly `len` samples, growing the backing storage only if needed
+    pub fn get_mut(&mut self, len: usize) -> &mut [f32] {
+        if self.data.len() < len {
+            self.data.resize(len, 0.0);
+        }
+        &mut self.data[..len]
+    }
+
+    /// Number of samples currently allocated
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+}
+
+impl Default for ScratchBuffer {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_SCRATCH_SAMPLES)
+    }
+}
+
+/// Copy interleaved stereo samples into frame format.
+/// Exactly `samples_read / 2` complete frames are emitted and the remaining frames are silenced;
+/// a trailing odd sample (incomplete frame) is discarded rather than paired with stale data.
+/// Returns the number of frames that carried audio.
+pub fn fill_stereo_frames(
+    interleaved: &[f32],
+    samples_read: usize,
+    output: &mut [(f32, f32)],
+) -> usize {
+    let available = samples_read.min(interleaved.len()) / 2;
+    let frames = available.min(output.len());
+
+    for (frame, pair) in output[..frames]
+        .iter_mut()
+        .zip(interleaved.chunks_exact(2))
+    {
+        *frame = (pair[0], pair[1]);
+    }
+    for frame in output[frames..].iter_mut() {
+        *frame = (0.0, 0.0); // Silence
+    }
+
+    frames
+}
+
+/// Body of a stereo output callback: read whole frames from `ring` through `scratch`, silence
+/// what it could not fill, duck and limit, and write the result to `output`.
+/// Returns the interleaved samples handed to the device, for the output taps.
+pub fn render_stereo<'a>(
+    ring: &Mutex<AudioRingBuffer>,
+    scratch: &'a mut ScratchBuffer,
+    duck: &Mutex<DuckGain>,
+    limiter: &Mutex<Limiter>,
+    sample_rate: u32,
+    output: &mut [(f32, f32)],
+) -> &'a [f32] {
+    let interleaved = scratch.get_mut(output.len() * 2);
+
+    // Whole frames only: a stray sample stays in the ring so left and right never swap
+    let samples_read = ring.lock().read_frames(interleaved, 2);
+
+    // Silence what the ring could not fill, then limit what the device gets
+    interleaved[samples_read..].fill(0.0);
+    duck.lock().process(&mut interleaved[..samples_read], 2, sample_rate);
+    limiter.lock().process(interleaved, 2, sample_rate);
+
+    fill_stereo_frames(interleaved, interleaved.len(), output);
+    interleaved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_reallocation_after_first_call() {
+        let mut scratch = ScratchBuffer::with_capacity(0);
+
+        // First (largest) callback sizes the buffer
+        let ptr = scratch.get_mut(960 * 2).as_ptr();
+        let capacity = scratch.capacity();
+
+        for frames in [192usize, 480, 960, 240, 1, 960] {
+            let slice = scratch.get_mut(frames * 2);
+            assert_eq!(slice.len(), frames * 2);
+            assert_eq!(slice.as_ptr(), ptr);
+        }
+        assert_eq!(scratch.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_render_stereo_with_varying_callback_sizes() {
+        let sizes = [192usize, 480, 960, 240, 1, 37, 960];
+        let total: usize = sizes.iter().sum();
+        // Each frame carries its index (negated on the right), so a skip or swap shows;
+        // the ring runs dry partway through the last callback
+        let ring = Mutex::new(AudioRingBuffer::new(total * 2));
+        let available = total - 100;
+        let level = |frame: usize| frame as f32 / total as f32;
+        let ramp: Vec<f32> = (0..available).flat_map(|i| [level(i), -level(i)]).collect();
+        assert_eq!(ring.lock().write(&ramp), ramp.len());
+
+        let mut scratch = ScratchBuffer::default();
+        let duck = Mutex::new(DuckGain::default());
+        let limiter = Mutex::new(Limiter::new());
+        limiter.lock().set_ceiling(None);
+        let storage = scratch.get_mut(0).as_ptr();
+
+        let mut frame = 0;
+        for frames in sizes {
+            let mut output = vec![(9.0, 9.0); frames];
+            let rendered = render_stereo(&ring, &mut scratch, &duck, &limiter, 48000, &mut output);
+            assert_eq!(rendered.len(), frames * 2);
+            for (i, &(left, right)) in output.iter().enumerate() {
+                let expected = if frame + i < available { level(frame + i) } else { 0.0 };
+                assert_eq!((left, right), (expected, -expected), "frame {}", frame + i);
+                assert_eq!(rendered[i * 2..i * 2 + 2], [left, right]);
+            }
+            frame += frames;
+            // The default scratch covers every size here: it is never reallocated
+            assert_eq!(scratch.get_mut(0).as_ptr(), storage);
+        }
+        assert_eq!(ring.lock().available_read(), 0);
+    }
+
+    #[test]
+    fn test_fill_full_read() {
+        let frame_count = 4;
+        let interleaved: Vec<f32> = (1..=(frame_count * 2)).map(|i| i as f32).collect();
+        let mut output = vec![(9.0, 9.0); frame_count];
+
+        let frames = fill_stereo_frames(&interleaved, frame_count * 2, &mut output);
+
+        assert_eq!(frames, frame_count);
+        assert_eq!(output, vec![(1.0, 2.0), (3.0, 4.0), (5.0, 6.0), (7.0, 8.0)]);
+    }
+
+    #[test]
+    fn test_fill_odd_partial_read() {
+        let frame_count = 4;
+        let interleaved: Vec<f32> = (1..=(frame_count * 2)).map(|i| i as f32).collect();
+        let mut output = vec![(9.0, 9.0); frame_count];
+
+        let frames = fill_stereo_frames(&interleaved, frame_count * 2 - 1, &mut output);
+
+        // The incomplete last frame is silenced, not half-filled
+        assert_eq!(frames, frame_count - 1);
+        assert_eq!(output, vec![(1.0, 2.0), (3.0, 4.0), (5.0, 6.0), (0.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_grows_for_larger_burst() {
+        let mut scratch = ScratchBuffer::with_capacity(4);
+        assert_eq!(scratch.get_mut(16).len(), 16);
+        assert!(scratch.capacity() >= 16);
+    }
+}
@@ -0,0 +1,52 @@
+// Scrub previews: short snippets mixed over (ducked) main playback
+
+/// Gain applied to main playback while a scrub preview is audible
+pub const SCRUB_DUCK_GAIN: f32 = 0.25;
+
+/// Decoded preview audio, already in the output stream's rate and channel layout
+pub struct ScrubSnippet {
+    samples: Vec<f32>,
+    pos: usize,
+}
+
+impl ScrubSnippet {
+    pub fn new(samples: Vec<f32>) -> Self {
+        Self { samples, pos: 0 }
+    }
+
+    /// Mix the next part of the snippet into `out`, ducking what is already there.
+    /// Only the overlapped samples are ducked. Returns true once the snippet is exhausted.
+    pub fn mix_into(&mut self, out: &mut [f32], volume: f32) -> bool {
+        let remaining = &self.samples[self.pos..];
+        let count = remaining.len().min(out.len());
+        for (out, &sample) in out[..count].iter_mut().zip(remaining) {
+            *out = *out * SCRUB_DUCK_GAIN + sample * volume;
+        }
+        self.pos += count;
+        self.is_finished()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pos >= self.samples.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_ducks_only_while_audible() {
+        let mut snippet = ScrubSnippet::new(vec![0.5; 6]);
+
+        let mut out = vec![0.8; 4];
+        assert!(!snippet.mix_into(&mut out, 1.0));
+        assert!(out.iter().all(|&s| (s - (0.8 * SCRUB_DUCK_GAIN + 0.5)).abs() < 1e-6));
+
+        // The tail covers half the callback; the rest of the main signal is untouched
+        let mut out = vec![0.8; 4];
+        assert!(snippet.mix_into(&mut out, 0.5));
+        assert!((out[0] - (0.8 * SCRUB_DUCK_GAIN + 0.25)).abs() < 1e-6);
+        assert_eq!(&out[2..], &[0.8, 0.8]);
+    }
+}
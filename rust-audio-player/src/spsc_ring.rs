@@ -0,0 +1,267 @@
+// Lock-free sample ring between the decode thread and the output callback
+// The callback side never blocks: it copies out of atomic slots and claims what it read with a
+// compare-and-swap on the read position. Everything else (writes, clears, resizes) comes from the
+// player's threads, which serialize among themselves on a lock the callback never touches.
+
+use crate::decoder::AudioRingBuffer;
+use crate::memory::MemoryCharge;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU64, Ordering};
+
+/// Sample storage, replaced as a whole on resize
+struct Slots {
+    samples: Box<[AtomicU32]>,
+    /// Charge for `samples` in the global memory pool
+    _memory: MemoryCharge,
+}
+
+impl Slots {
+    fn new(size: usize) -> Box<Self> {
+        let size = size.max(1);
+        Box::new(Self {
+            samples: (0..size).map(|_| AtomicU32::new(0)).collect(),
+            _memory: MemoryCharge::global(size * std::mem::size_of::<f32>()),
+        })
+    }
+
+    fn len(&self) -> u64 {
+        self.samples.len() as u64
+    }
+
+    fn slot(&self, pos: u64) -> &AtomicU32 {
+        &self.samples[(pos % self.len()) as usize]
+    }
+}
+
+/// Single-producer single-consumer ring of f32 samples; same sizing rules as `AudioRingBuffer`
+/// (`size` slots, one kept free), with positions that only ever grow
+pub struct SpscRing {
+    slots: AtomicPtr<Slots>,
+    /// Set by the reader while it holds `slots`, so a resize knows when the old storage is free
+    reading: AtomicBool,
+    write_pos: AtomicU64,
+    read_pos: AtomicU64,
+    /// Serializes the writer side: writes, clears and resizes
+    writer: Mutex<()>,
+}
+
+impl SpscRing {
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: AtomicPtr::new(Box::into_raw(Slots::new(size))),
+            reading: AtomicBool::new(false),
+            write_pos: AtomicU64::new(0),
+            read_pos: AtomicU64::new(0),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Storage for the writer side; only valid while `writer` is held, as resizes take it too
+    fn writer_slots(&self) -> &Slots {
+        // SAFETY: the pointer always comes from `Box::into_raw` and is only freed by `resize`
+        // (holding `writer`, which our caller holds too) or by `drop`
+        unsafe { &*self.slots.load(Ordering::SeqCst) }
+    }
+
+    /// Append as much of `data` as fits; returns the number of samples written
+    pub fn write(&self, data: &[f32]) -> usize {
+        let _writer = self.writer.lock();
+        let slots = self.writer_slots();
+        let write_pos = self.write_pos.load(Ordering::Relaxed);
+        let used = write_pos - self.read_pos.load(Ordering::Acquire);
+        let count = data.len().min((slots.len() - 1).saturating_sub(used) as usize);
+        for (i, &sample) in data[..count].iter().enumerate() {
+            slots.slot(write_pos + i as u64).store(sample.to_bits(), Ordering::Relaxed);
+        }
+        self.write_pos.store(write_pos + count as u64, Ordering::Release);
+        count
+    }
+
+    /// Read only whole frames of `channels` samples. Never blocks: a read that races a clear or
+    /// resize returns 0, as the samples it copied were discarded.
+    pub fn read_frames(&self, output: &mut [f32], channels: usize) -> usize {
+        let channels = channels.max(1);
+        self.reading.store(true, Ordering::SeqCst);
+        // SAFETY: `resize` swaps the pointer before waiting for `reading` to drop, so storage
+        // loaded after raising `reading` stays alive until it is lowered
+        let slots = unsafe { &*self.slots.load(Ordering::SeqCst) };
+        let read_pos = self.read_pos.load(Ordering::SeqCst);
+        let available = self.write_pos.load(Ordering::SeqCst).saturating_sub(read_pos);
+        let count = output.len().min(available as usize) / channels * channels;
+        for (i, out) in output[..count].iter_mut().enumerate() {
+            *out = f32::from_bits(slots.slot(read_pos + i as u64).load(Ordering::Relaxed));
+        }
+        let claimed = self
+            .read_pos
+            .compare_exchange(read_pos, read_pos + count as u64, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok();
+        self.reading.store(false, Ordering::SeqCst);
+        if claimed {
+            count
+        } else {
+            0
+        }
+    }
+
+    /// Read any number of samples
+    #[cfg(test)]
+    pub fn read(&self, output: &mut [f32]) -> usize {
+        self.read_frames(output, 1)
+    }
+
+    pub fn available_read(&self) -> usize {
+        let read_pos = self.read_pos.load(Ordering::SeqCst);
+        self.write_pos.load(Ordering::SeqCst).saturating_sub(read_pos) as usize
+    }
+
+    /// Drop everything buffered
+    pub fn clear(&self) {
+        let _writer = self.writer.lock();
+        self.discard();
+    }
+
+    /// Move the read position up to the write position (writer lock held)
+    fn discard(&self) {
+        let write_pos = self.write_pos.load(Ordering::SeqCst);
+        let mut read_pos = self.read_pos.load(Ordering::SeqCst);
+        while read_pos < write_pos {
+            match self
+                .read_pos
+                .compare_exchange(read_pos, write_pos, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => break,
+                Err(current) => read_pos = current,
+            }
+        }
+    }
+
+    /// Resize the ring to `new_size` slots; clears it, like `AudioRingBuffer::resize`
+    pub fn resize(&self, new_size: usize) {
+        let _writer = self.writer.lock();
+        if new_size.max(1) as u64 == self.writer_slots().len() {
+            return;
+        }
+        self.discard();
+        let old = self.slots.swap(Box::into_raw(Slots::new(new_size)), Ordering::SeqCst);
+        // A read that loaded the old storage is still copying out of it
+        while self.reading.load(Ordering::SeqCst) {
+            std::thread::yield_now();
+        }
+        // SAFETY: swapped out above, and no reader holds it any more
+        drop(unsafe { Box::from_raw(old) });
+        log::debug!(
+            "Ring buffer resized to {} samples ({:.2} MB)",
+            new_size,
+            (new_size * std::mem::size_of::<f32>()) as f32 / (1024.0 * 1024.0)
+        );
+    }
+
+    pub fn size(&self) -> usize {
+        let _writer = self.writer.lock();
+        self.writer_slots().len() as usize
+    }
+
+    /// Samples the ring can hold: one slot stays free, as in `AudioRingBuffer`
+    pub fn capacity(&self) -> usize {
+        self.size().saturating_sub(1)
+    }
+
+    /// Grow the ring if it cannot hold `MIN_RING_CALLBACKS` callbacks of `callback_samples`.
+    /// Growing clears it, like `resize`. Returns whether it grew.
+    pub fn fit_callback(&self, callback_samples: usize) -> bool {
+        let min_size = AudioRingBuffer::min_size_for_callback(callback_samples);
+        let size = self.size();
+        if size >= min_size {
+            return false;
+        }
+        log::warn!(
+            "Output callback of {} samples does not fit the {}-sample ring buffer; growing it to {}",
+            callback_samples,
+            size,
+            min_size
+        );
+        self.resize(min_size);
+        true
+    }
+
+    /// Buffer fullness as a fraction of usable capacity (0.0 to 1.0)
+    pub fn fullness(&self) -> f32 {
+        self.available_read() as f32 / self.capacity().max(1) as f32
+    }
+}
+
+impl Drop for SpscRing {
+    fn drop(&mut self) {
+        // SAFETY: the pointer came from `Box::into_raw`, and `&mut self` rules out readers
+        drop(unsafe { Box::from_raw(*self.slots.get_mut()) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_ring_matches_locked_ring_sizing() {
+        let ring = SpscRing::new(8);
+        assert_eq!(ring.capacity(), 7);
+        assert_eq!(ring.write(&[1.0; 10]), 7);
+        assert_eq!(ring.fullness(), 1.0);
+
+        // Whole frames only: the odd sample stays for the next read
+        let mut out = [0.0; 4];
+        assert_eq!(ring.read_frames(&mut out, 2), 4);
+        assert_eq!(ring.read_frames(&mut out, 2), 2);
+        assert_eq!(ring.available_read(), 1);
+
+        // Positions wrap the storage many times over
+        for round in 0..100 {
+            let data: Vec<f32> = (0..6).map(|i| (round * 6 + i) as f32).collect();
+            ring.clear();
+            assert_eq!(ring.write(&data), 6);
+            let mut out = [0.0; 6];
+            assert_eq!(ring.read(&mut out), 6);
+            assert_eq!(out[..], data[..]);
+        }
+
+        ring.resize(16);
+        assert_eq!((ring.size(), ring.available_read()), (16, 0));
+        assert!(ring.fit_callback(10));
+        assert_eq!(ring.size(), 21);
+        assert!(!ring.fit_callback(10));
+    }
+
+    #[test]
+    fn test_reader_sees_every_sample_in_order_while_the_writer_resizes() {
+        const TOTAL: u32 = 20_000;
+        let ring = Arc::new(SpscRing::new(64));
+        let writer_ring = ring.clone();
+        let writer = thread::spawn(move || {
+            let (mut next, mut passes) = (0u32, 0usize);
+            while next < TOTAL {
+                let chunk: Vec<f32> = (next..(next + 32).min(TOTAL)).map(|i| i as f32).collect();
+                next += writer_ring.write(&chunk) as u32;
+                // Resizes clear, so they only happen once the reader has drained the ring
+                passes += 1;
+                if passes % 50 == 0 && writer_ring.available_read() == 0 {
+                    writer_ring.resize(64 + passes / 50 % 3 * 32);
+                }
+            }
+        });
+
+        let mut expected = 0u32;
+        let mut out = [0.0; 48];
+        while expected < TOTAL {
+            let read = ring.read(&mut out);
+            for &sample in &out[..read] {
+                assert_eq!(sample, expected as f32);
+                expected += 1;
+            }
+            // Between callbacks the reader is idle, which is when a resize frees the old storage
+            thread::yield_now();
+        }
+        writer.join().unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+// Output stream configurations to try, from lowest latency to most widely supported
+// Some Android devices refuse exclusive or low-latency streams; playback should still start there.
+
+use crate::error::{AudioError, Result};
+use std::fmt::Debug;
+
+/// Whether the stream may share the device with other apps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamSharing {
+    Exclusive,
+    Shared,
+}
+
+/// Latency/power trade-off requested from the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamPerformance {
+    LowLatency,
+    None,
+}
+
+/// One stream open attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamAttempt {
+    pub sharing: StreamSharing,
+    pub performance: StreamPerformance,
+}
+
+/// Attempts in order: exclusive low-latency, then shared low-latency, then shared default
+pub const STREAM_ATTEMPTS: [StreamAttempt; 3] = [
+    StreamAttempt { sharing: StreamSharing::Exclusive, performance: StreamPerformance::LowLatency },
+    StreamAttempt { sharing: StreamSharing::Shared, performance: StreamPerformance::LowLatency },
+    StreamAttempt { sharing: StreamSharing::Shared, performance: StreamPerformance::None },
+];
+
+/// Open a stream with the first attempt that succeeds, returning it with the attempt used
+pub fn open_with_fallback<S, E: Debug>(
+    mut open: impl FnMut(StreamAttempt) -> std::result::Result<S, E>,
+) -> Result<(S, StreamAttempt)> {
+    let mut last_error = String::new();
+    for attempt in STREAM_ATTEMPTS {
+        match open(attempt) {
+            Ok(stream) => {
+                if attempt != STREAM_ATTEMPTS[0] {
+                    log::warn!("Audio stream opened with fallback configuration {:?}", attempt);
+                }
+                return Ok((stream, attempt));
+            }
+            Err(e) => {
+                log::warn!("Failed to open audio stream with {:?}: {:?}", attempt, e);
+                last_error = format!("{:?}", e);
+            }
+        }
+    }
+    Err(AudioError::InitializationError(format!(
+        "Failed to open audio stream: {}",
+        last_error
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_falls_back_when_exclusive_open_fails() {
+        let mut tried = Vec::new();
+        let (stream, attempt) = open_with_fallback(|attempt| {
+            tried.push(attempt);
+            if attempt.sharing == StreamSharing::Exclusive {
+                Err("exclusive mode unavailable")
+            } else {
+                Ok("stream")
+            }
+        })
+        .unwrap();
+
+        assert_eq!(stream, "stream");
+        assert_eq!(attempt, STREAM_ATTEMPTS[1]);
+        assert_eq!(tried, STREAM_ATTEMPTS[..2]);
+    }
+
+    #[test]
+    fn test_every_attempt_failing_is_an_initialization_error() {
+        let mut tried = Vec::new();
+        let result = open_with_fallback::<(), _>(|attempt| {
+            tried.push(attempt);
+            Err("no output device")
+        });
+
+        assert!(matches!(result, Err(AudioError::InitializationError(_))));
+        assert_eq!(tried, STREAM_ATTEMPTS);
+    }
+}
@@ -0,0 +1,200 @@
+// Output stream owner thread
+// cpal::Stream is !Send on some hosts (WASAPI keeps COM objects in it), so it cannot live inside a
+// player that apps move between threads. The stream is built, played, paused and dropped on one
+// dedicated thread instead; the player holds a `StreamOwner` that sends it commands and waits for
+// each result, which keeps the player Send + Sync without unsafe impls.
+
+use crate::error::{AudioError, Result};
+use crate::threads::{catch_panic, spawn_named, STREAM_THREAD};
+use cpal::traits::StreamTrait;
+use std::sync::mpsc;
+use std::thread;
+
+enum StreamCommand {
+    Play,
+    Pause,
+}
+
+type Request = (StreamCommand, mpsc::Sender<Result<()>>);
+
+/// Handle to an output stream living on its owner thread. Dropping it drops the stream (on that
+/// thread) and waits for the thread to exit, so a replacement stream never overlaps the old one.
+pub struct StreamOwner {
+    commands: Option<mpsc::Sender<Request>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl StreamOwner {
+    /// Run `build` on a new owner thread and keep the stream it returns there
+    pub fn spawn<S, B>(build: B) -> Result<Self>
+    where
+        S: StreamTrait + 'static,
+        B: FnOnce() -> Result<S> + Send + 'static,
+    {
+        let (built_tx, built_rx) = mpsc::channel();
+        let (commands, requests) = mpsc::channel::<Request>();
+
+        let thread = spawn_named(STREAM_THREAD, move || {
+            let stream = match catch_panic(build).and_then(|built| built) {
+                Ok(stream) => {
+                    let _ = built_tx.send(Ok(()));
+                    stream
+                }
+                Err(e) => {
+                    let _ = built_tx.send(Err(e));
+                    return;
+                }
+            };
+
+            // Serve commands until the handle is dropped, then drop the stream here
+            for (command, reply) in requests {
+                let result = match command {
+                    StreamCommand::Play => stream
+                        .play()
+                        .map_err(|e| AudioError::PlaybackError(format!("Failed to start stream: {}", e))),
+                    StreamCommand::Pause => stream
+                        .pause()
+                        .map_err(|e| AudioError::PlaybackError(format!("Failed to pause stream: {}", e))),
+                };
+                let _ = reply.send(result);
+            }
+        })?;
+
+        match built_rx.recv() {
+            Ok(Ok(())) => Ok(Self { commands: Some(commands), thread: Some(thread) }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => {
+                let _ = thread.join();
+                Err(AudioError::InitializationError("Output stream thread exited".to_string()))
+            }
+        }
+    }
+
+    pub fn play(&self) -> Result<()> {
+        self.request(StreamCommand::Play)
+    }
+
+    pub fn pause(&self) -> Result<()> {
+        self.request(StreamCommand::Pause)
+    }
+
+    fn request(&self, command: StreamCommand) -> Result<()> {
+        let exited = || AudioError::PlaybackError("Output stream thread has exited".to_string());
+        let (reply, result) = mpsc::channel();
+        self.commands
+            .as_ref()
+            .ok_or_else(exited)?
+            .send((command, reply))
+            .map_err(|_| exited())?;
+        result.recv().map_err(|_| exited())?
+    }
+}
+
+impl Drop for StreamOwner {
+    fn drop(&mut self) {
+        // Closing the channel ends the command loop
+        self.commands = None;
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("Output stream thread panicked");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpal::{PauseStreamError, PlayStreamError};
+    use parking_lot::Mutex;
+    use std::marker::PhantomData;
+    use std::sync::Arc;
+    use std::thread::ThreadId;
+
+    type CallLog = Arc<Mutex<Vec<(&'static str, ThreadId)>>>;
+
+    /// Stands in for a cpal stream: !Send like WASAPI's, and records where it is used
+    struct FakeStream {
+        calls: CallLog,
+        _not_send: PhantomData<*const ()>,
+    }
+
+    impl FakeStream {
+        fn record(&self, call: &'static str) {
+            self.calls.lock().push((call, thread::current().id()));
+        }
+    }
+
+    impl StreamTrait for FakeStream {
+        fn play(&self) -> std::result::Result<(), PlayStreamError> {
+            self.record("play");
+            Ok(())
+        }
+
+        fn pause(&self) -> std::result::Result<(), PauseStreamError> {
+            self.record("pause");
+            Ok(())
+        }
+    }
+
+    impl Drop for FakeStream {
+        fn drop(&mut self) {
+            self.record("drop");
+        }
+    }
+
+    #[test]
+    fn test_stream_is_only_touched_on_its_owner_thread() {
+        let calls: CallLog = Arc::new(Mutex::new(Vec::new()));
+        let build_calls = calls.clone();
+        let owner = Arc::new(
+            StreamOwner::spawn(move || {
+                build_calls.lock().push(("build", thread::current().id()));
+                Ok(FakeStream { calls: build_calls, _not_send: PhantomData })
+            })
+            .unwrap(),
+        );
+
+        // Commands arrive from several threads at once
+        let workers: Vec<_> = (0..4)
+            .map(|i| {
+                let owner = owner.clone();
+                thread::spawn(move || {
+                    for _ in 0..10 {
+                        if i % 2 == 0 {
+                            owner.play().unwrap();
+                        } else {
+                            owner.pause().unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        drop(Arc::into_inner(owner).unwrap());
+
+        let calls = calls.lock();
+        assert_eq!(calls.len(), 1 + 40 + 1);
+        assert_eq!(calls.first().unwrap().0, "build");
+        assert_eq!(calls.last().unwrap().0, "drop");
+        let owner_thread = calls[0].1;
+        assert_ne!(owner_thread, thread::current().id());
+        assert!(calls.iter().all(|&(_, id)| id == owner_thread));
+    }
+
+    #[test]
+    fn test_build_failure_is_returned() {
+        let result = StreamOwner::spawn(|| -> Result<FakeStream> {
+            Err(AudioError::InitializationError("no device".to_string()))
+        });
+        assert!(matches!(result, Err(AudioError::InitializationError(_))));
+
+        let result = StreamOwner::spawn(|| -> Result<FakeStream> { panic!("backend bug") });
+        assert!(matches!(result, Err(AudioError::Other(_))));
+    }
+}
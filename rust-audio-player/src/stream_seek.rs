@@ -0,0 +1,91 @@
+// Seek handling shared by the streaming sources
+// Symphonia's probes often seek from the end, so every source resolves `SeekFrom` the same way:
+// `End` works whenever the total size is known (sources ask the server for it first when the
+// download hasn't reported one), and positions before the start clamp to 0.
+
+use crate::error::{AudioError, Result};
+use crate::http_transport::HttpTransport;
+use std::io::{Read, SeekFrom};
+
+/// Resolve `pos` against the reader's `position` and the resource size.
+/// Fails only for `SeekFrom::End` when `total_size` is unknown.
+pub(crate) fn resolve_seek(pos: SeekFrom, position: u64, total_size: Option<u64>) -> std::io::Result<u64> {
+    let (base, offset) = match pos {
+        SeekFrom::Start(offset) => return Ok(offset),
+        SeekFrom::Current(offset) => (position, offset),
+        SeekFrom::End(offset) => {
+            let size = total_size.ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::Unsupported, "Cannot seek from end: total size unknown")
+            })?;
+            (size, offset)
+        }
+    };
+    Ok(base.saturating_add_signed(offset))
+}
+
+/// Size of the resource at `url`: HEAD's Content-Length, else the total from the Content-Range of
+/// a one-byte ranged GET. None when the server reports neither.
+pub(crate) fn discover_total_size(transport: &dyn HttpTransport, url: &str) -> Option<u64> {
+    match transport.head(url) {
+        Ok(response) => {
+            if let Some(size) = response.content_length() {
+                return Some(size);
+            }
+        }
+        Err(e) => log::warn!("HEAD request failed ({}), falling back to Range GET", e),
+    }
+    range_total_size(transport, url).unwrap_or_else(|e| {
+        log::warn!("{}", e);
+        None
+    })
+}
+
+/// Fetch a 0-0 range to derive the total size when HEAD fails or lacks Content-Length
+pub(crate) fn range_total_size(transport: &dyn HttpTransport, url: &str) -> Result<Option<u64>> {
+    let mut response = transport
+        .get_range(url, 0, Some(0))
+        .map_err(|e| AudioError::NetworkError(format!("Range request (fallback) failed: {}", e)))?;
+
+    // Capture headers before consuming the body
+    let content_range_total = response.header("Content-Range").and_then(parse_total_from_content_range);
+    let content_length = response.content_length();
+
+    // Ensure body is consumed so connection can be reused
+    let mut sink = Vec::new();
+    if let Err(e) = response.body.read_to_end(&mut sink) {
+        log::warn!("Failed to read fallback range body: {}", e);
+    }
+
+    // A server ignoring the range answers 200 with the whole body, whose length is the size
+    Ok(content_range_total.or(if response.is_partial() { None } else { content_length }))
+}
+
+/// Parse Content-Range header to extract total size, e.g., "bytes 0-0/12345"
+pub(crate) fn parse_total_from_content_range(header: &str) -> Option<u64> {
+    header.rsplit('/').next()?.trim().parse::<u64>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_transport::tests::MockTransport;
+
+    #[test]
+    fn test_resolve_seek_from_end_and_clamping() {
+        assert_eq!(resolve_seek(SeekFrom::End(0), 10, Some(4096)).unwrap(), 4096);
+        assert_eq!(resolve_seek(SeekFrom::End(-1024), 10, Some(4096)).unwrap(), 3072);
+        // Before the start clamps to 0
+        assert_eq!(resolve_seek(SeekFrom::End(-8192), 10, Some(4096)).unwrap(), 0);
+        assert_eq!(resolve_seek(SeekFrom::Current(-20), 10, None).unwrap(), 0);
+        assert_eq!(resolve_seek(SeekFrom::Current(5), 10, None).unwrap(), 15);
+        let unknown = resolve_seek(SeekFrom::End(0), 10, None).unwrap_err();
+        assert_eq!(unknown.kind(), std::io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn test_size_discovered_from_content_range() {
+        let transport = MockTransport::without_length(vec![7u8; 5000]);
+        assert_eq!(discover_total_size(transport.as_ref(), "mock://file"), Some(5000));
+        assert_eq!(parse_total_from_content_range("bytes 0-0/*"), None);
+    }
+}
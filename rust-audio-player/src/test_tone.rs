@@ -0,0 +1,176 @@
+// Built-in test tone for diagnostics
+// Generates a periodic waveform as an in-memory 16-bit WAV and plays it through `load_buffer`,
+// so the whole output path (decoder, resampler, device) can be checked without a media file.
+
+use crate::error::{AudioError, Result};
+use std::f32::consts::PI;
+
+/// Longest tone accepted, so a typo cannot allocate minutes of audio
+pub const MAX_TEST_TONE_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Waveform {
+    #[default]
+    Sine,
+    Square,
+    Triangle,
+    Sawtooth,
+}
+
+impl Waveform {
+    /// Value at `phase` (in cycles, 0.0..1.0), in -1.0..=1.0
+    fn sample(self, phase: f32) -> f32 {
+        match self {
+            Waveform::Sine => (2.0 * PI * phase).sin(),
+            Waveform::Square if phase < 0.5 => 1.0,
+            Waveform::Square => -1.0,
+            Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            Waveform::Sawtooth => 2.0 * phase - 1.0,
+        }
+    }
+}
+
+/// Parameters of a generated tone; every channel carries the same signal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestTone {
+    pub freq_hz: f32,
+    /// Peak level, 0.0..=1.0 of full scale
+    pub amplitude: f32,
+    pub duration_ms: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub waveform: Waveform,
+}
+
+impl TestTone {
+    /// Sine tone, checked against the limits of the format
+    pub fn new(freq_hz: f32, amplitude: f32, duration_ms: u64, sample_rate: u32, channels: u16) -> Result<Self> {
+        let tone = Self {
+            freq_hz,
+            amplitude,
+            duration_ms,
+            sample_rate,
+            channels,
+            waveform: Waveform::Sine,
+        };
+        tone.validate()?;
+        Ok(tone)
+    }
+
+    pub fn with_waveform(mut self, waveform: Waveform) -> Self {
+        self.waveform = waveform;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        let invalid = |what: String| Err(AudioError::InvalidState(format!("Invalid test tone: {}", what)));
+        if self.sample_rate == 0 || self.channels == 0 {
+            return invalid(format!("{} Hz, {} channels", self.sample_rate, self.channels));
+        }
+        if !(self.freq_hz > 0.0 && self.freq_hz < self.sample_rate as f32 / 2.0) {
+            return invalid(format!("{} Hz is not below Nyquist at {} Hz", self.freq_hz, self.sample_rate));
+        }
+        if !(0.0..=1.0).contains(&self.amplitude) {
+            return invalid(format!("amplitude {} is outside 0.0..=1.0", self.amplitude));
+        }
+        if self.duration_ms == 0 || self.duration_ms > MAX_TEST_TONE_MS {
+            return invalid(format!("duration {} ms", self.duration_ms));
+        }
+        Ok(())
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.duration_ms * self.sample_rate as u64 / 1000
+    }
+
+    /// Interleaved f32 samples
+    pub fn samples(&self) -> Vec<f32> {
+        let step = self.freq_hz as f64 / self.sample_rate as f64;
+        (0..self.frames())
+            .flat_map(|frame| {
+                let value = self.amplitude * self.waveform.sample((frame as f64 * step).fract() as f32);
+                std::iter::repeat_n(value, self.channels as usize)
+            })
+            .collect()
+    }
+
+    /// The tone as a 16-bit PCM WAV file, ready for `load_buffer`
+    pub fn to_wav(&self) -> Vec<u8> {
+        let samples = self.samples();
+        let data_len = (samples.len() * 2) as u32;
+        let block_align = self.channels * 2;
+        let mut wav = Vec::with_capacity(44 + data_len as usize);
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&self.channels.to_le_bytes());
+        wav.extend_from_slice(&self.sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(self.sample_rate * block_align as u32).to_le_bytes());
+        wav.extend_from_slice(&block_align.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for sample in samples {
+            wav.extend_from_slice(&((sample * i16::MAX as f32).round() as i16).to_le_bytes());
+        }
+        wav
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::decoder::AudioDecoder;
+
+    /// Power of `mono` at `freq_hz` (Goertzel)
+    fn power_at(mono: &[f32], sample_rate: u32, freq_hz: f32) -> f32 {
+        let coeff = 2.0 * (2.0 * PI * freq_hz / sample_rate as f32).cos();
+        let (mut s1, mut s2) = (0.0f32, 0.0f32);
+        for &x in mono {
+            let s0 = x + coeff * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        s1 * s1 + s2 * s2 - coeff * s1 * s2
+    }
+
+    /// Strongest frequency in the first channel of `interleaved`, to within 1 Hz: a coarse
+    /// 10 Hz scan up to Nyquist, refined around the peak
+    pub(crate) fn dominant_frequency(interleaved: &[f32], channels: u16, sample_rate: u32) -> f32 {
+        let mono: Vec<f32> = interleaved.iter().step_by(channels as usize).copied().collect();
+        let peak = |freqs: &mut dyn Iterator<Item = f32>| {
+            freqs
+                .map(|freq| (freq, power_at(&mono, sample_rate, freq)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(0.0, |(freq, _)| freq)
+        };
+        let coarse = peak(&mut (1..sample_rate / 20).map(|step| step as f32 * 10.0));
+        peak(&mut (-10..=10).map(|offset| coarse + offset as f32))
+    }
+
+    #[test]
+    fn test_decoded_tone_has_requested_frequency_and_level() {
+        let tone = TestTone::new(440.0, 0.5, 500, 44_100, 2).unwrap();
+        let (format, decoded) = AudioDecoder::from_buffer(tone.to_wav()).unwrap().decode_all().unwrap();
+        assert_eq!((format.sample_rate, format.channels), (44_100, 2));
+        assert_eq!(decoded.len() as u64, tone.frames() * 2);
+        assert_eq!(dominant_frequency(&decoded, 2, 44_100), 440.0);
+        let peak = decoded.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.01, "peak {}", peak);
+
+        let square = tone.with_waveform(Waveform::Square).samples();
+        assert_eq!(dominant_frequency(&square, 2, 44_100), 440.0);
+    }
+
+    #[test]
+    fn test_invalid_tones_are_rejected() {
+        assert!(TestTone::new(0.0, 0.5, 100, 48_000, 2).is_err());
+        assert!(TestTone::new(24_000.0, 0.5, 100, 48_000, 2).is_err());
+        assert!(TestTone::new(440.0, 1.5, 100, 48_000, 2).is_err());
+        assert!(TestTone::new(440.0, 0.5, 0, 48_000, 2).is_err());
+        assert!(TestTone::new(440.0, 0.5, 100, 48_000, 0).is_err());
+        assert!(TestTone::new(f32::NAN, 0.5, 100, 48_000, 2).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+// Named, panic-isolated player threads
+
+use crate::callback::{CallbackEvent, CallbackManager};
+use crate::error::{AudioError, Result};
+use crate::player::{PlayerState, PlayerStateContainer};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Decoder thread feeding the ring buffer
+pub const DECODE_THREAD: &str = "podium-decode";
+
+/// Background `prepare`/`prepare_next` work
+pub const PREPARE_THREAD: &str = "podium-prepare";
+
+/// App PCM tap (one per tap)
+pub const PCM_TAP_THREAD: &str = "podium-pcm-tap";
+
+/// Transcript download
+pub const TRANSCRIPT_THREAD: &str = "podium-transcr";
+
+/// `AudioDecoder::into_pcm_stream` worker
+pub const PCM_STREAM_THREAD: &str = "podium-pcm-strm";
+
+/// Delivery of player events to the app's callbacks
+pub const CALLBACK_THREAD: &str = "podium-callback";
+
+/// Owner of the output stream (cpal streams cannot move between threads on every host)
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos", target_os = "ios"))]
+pub const STREAM_THREAD: &str = "podium-stream";
+
+/// How long `release` waits for the decode thread before abandoning it
+pub const RELEASE_JOIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Scheduling priority for the decode thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThreadPriority {
+    /// Whatever the OS gives a new thread
+    Normal,
+    /// Above normal, but never real-time: keeps the ring buffer full on a loaded system
+    /// without being able to starve the rest of the app
+    #[default]
+    Elevated,
+}
+
+/// Nice value for `ThreadPriority::Elevated` (Android's THREAD_PRIORITY_AUDIO)
+#[cfg(target_os = "android")]
+const ELEVATED_NICE: i32 = -16;
+
+/// Nice value for `ThreadPriority::Elevated`
+#[cfg(target_os = "linux")]
+const ELEVATED_NICE: i32 = -10;
+
+/// Apply `priority` to the calling thread.
+/// Where the OS refuses (e.g. an unprivileged Linux process lowering its nice value) the thread
+/// keeps its current priority; only unexpected failures are reported.
+pub fn set_current_thread_priority(priority: ThreadPriority) -> Result<()> {
+    let result = platform::set_current_thread_priority(priority);
+    match result {
+        Err(ref e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            log::debug!("Thread priority {:?} not permitted, keeping the default: {}", priority, e);
+            Ok(())
+        }
+        _ => result.map_err(|e| {
+            AudioError::ThreadError(format!("Failed to set thread priority {:?}: {}", priority, e))
+        }),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod platform {
+    use super::{ThreadPriority, ELEVATED_NICE};
+
+    /// On Linux, nice values are per thread when addressed by thread ID
+    pub fn set_current_thread_priority(priority: ThreadPriority) -> std::io::Result<()> {
+        let nice = match priority {
+            ThreadPriority::Normal => 0,
+            ThreadPriority::Elevated => ELEVATED_NICE,
+        };
+        let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, nice) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod platform {
+    use super::ThreadPriority;
+
+    /// QoS classes drive the Mach thread policy; user-initiated is the highest non-interactive,
+    /// non-real-time class
+    pub fn set_current_thread_priority(priority: ThreadPriority) -> std::io::Result<()> {
+        let qos_class = match priority {
+            ThreadPriority::Normal => libc::qos_class_t::QOS_CLASS_DEFAULT,
+            ThreadPriority::Elevated => libc::qos_class_t::QOS_CLASS_USER_INITIATED,
+        };
+        match unsafe { libc::pthread_set_qos_class_self_np(qos_class, 0) } {
+            0 => Ok(()),
+            errno => Err(std::io::Error::from_raw_os_error(errno)),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::ThreadPriority;
+
+    const THREAD_PRIORITY_NORMAL: i32 = 0;
+    const THREAD_PRIORITY_ABOVE_NORMAL: i32 = 1;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetCurrentThread() -> isize;
+        fn SetThreadPriority(thread: isize, priority: i32) -> i32;
+    }
+
+    pub fn set_current_thread_priority(priority: ThreadPriority) -> std::io::Result<()> {
+        let level = match priority {
+            ThreadPriority::Normal => THREAD_PRIORITY_NORMAL,
+            ThreadPriority::Elevated => THREAD_PRIORITY_ABOVE_NORMAL,
+        };
+        if unsafe { SetThreadPriority(GetCurrentThread(), level) } != 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    }
+}
+
+/// Spawn a thread with the given name (kept under 16 bytes so Linux/Android don't truncate it)
+pub fn spawn_named<F, T>(name: &str, f: F) -> Result<thread::JoinHandle<T>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    thread::Builder::new()
+        .name(name.to_string())
+        .spawn(f)
+        .map_err(|e| AudioError::ThreadError(format!("Failed to spawn {} thread: {}", name, e)))
+}
+
+/// Join `handle` if the thread finishes within `timeout`; otherwise detach it and return false.
+/// The thread must already have been asked to stop.
+pub fn join_within<T>(handle: thread::JoinHandle<T>, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while !handle.is_finished() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    let _ = handle.join();
+    true
+}
+
+/// Run `f`, converting a panic into `AudioError::Other` instead of unwinding the thread
+pub fn catch_panic<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> T,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|msg| msg.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let thread_name = thread::current().name().unwrap_or("worker").to_string();
+        AudioError::Other(format!("{} thread panicked: {}", thread_name, message))
+    })
+}
+
+/// Run a player worker body. A panic stops playback, dispatches `CallbackEvent::Error`
+/// and moves the player to `PlayerState::Error` so the app can react.
+pub fn run_isolated<F>(
+    is_playing: &AtomicBool,
+    callback_manager: &CallbackManager,
+    state_container: &PlayerStateContainer,
+    f: F,
+) -> Result<()>
+where
+    F: FnOnce(),
+{
+    catch_panic(f).inspect_err(|e| {
+        log::error!("{}", e);
+        is_playing.store(false, Ordering::Relaxed);
+        callback_manager.dispatch_event(CallbackEvent::Error {
+            message: e.to_string(),
+        });
+        state_container.set_state(PlayerState::Error);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::TestCallback;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_spawned_thread_is_named() {
+        let handle = spawn_named(DECODE_THREAD, || {
+            thread::current().name().map(|name| name.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(handle.thread().name(), Some(DECODE_THREAD));
+        assert_eq!(handle.join().unwrap().as_deref(), Some(DECODE_THREAD));
+    }
+
+    /// Stand-in for a decoder with a codec bug
+    struct PanickingDecoder;
+
+    impl PanickingDecoder {
+        fn decode_next(&mut self) -> Option<Vec<f32>> {
+            panic!("corrupt packet");
+        }
+    }
+
+    #[test]
+    fn test_worker_panic_reports_error() {
+        let callback = Arc::new(TestCallback::new());
+        let callback_manager = Arc::new(CallbackManager::new());
+        callback_manager.add_callback(callback.clone(), 0);
+        let state_container = PlayerStateContainer::new();
+        state_container.set_state(PlayerState::Playing);
+        let is_playing = Arc::new(AtomicBool::new(true));
+
+        let worker_callbacks = callback_manager.clone();
+        let worker_state = state_container.clone();
+        let worker_playing = is_playing.clone();
+        let handle = spawn_named(DECODE_THREAD, move || {
+            run_isolated(&worker_playing, &worker_callbacks, &worker_state, || {
+                let mut decoder = PanickingDecoder;
+                while decoder.decode_next().is_some() {}
+            })
+        })
+        .unwrap();
+
+        // The thread itself exits cleanly; the panic is reported as an error
+        let result = handle.join().unwrap();
+        assert!(matches!(result, Err(AudioError::Other(ref msg)) if msg.contains("corrupt packet")));
+        assert_eq!(state_container.get_state(), PlayerState::Error);
+        assert!(!is_playing.load(Ordering::Relaxed));
+        assert!(callback_manager.flush(Duration::from_secs(1)));
+        assert!(callback.get_events().iter().any(|event| matches!(
+            event,
+            CallbackEvent::Error { message } if message.contains(DECODE_THREAD)
+        )));
+    }
+
+    #[test]
+    fn test_thread_priority_can_be_set() {
+        let handle = spawn_named(DECODE_THREAD, || {
+            set_current_thread_priority(ThreadPriority::Elevated)?;
+            set_current_thread_priority(ThreadPriority::Normal)?;
+            #[cfg(target_os = "linux")]
+            {
+                let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t) };
+                assert_eq!(nice, 0);
+            }
+            Ok::<_, AudioError>(())
+        })
+        .unwrap();
+
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_join_within_gives_up_on_a_stuck_thread() {
+        let quick = spawn_named(DECODE_THREAD, || ()).unwrap();
+        assert!(join_within(quick, Duration::from_secs(5)));
+
+        let stuck = spawn_named(DECODE_THREAD, || thread::sleep(Duration::from_secs(2))).unwrap();
+        let started = Instant::now();
+        assert!(!join_within(stuck, Duration::from_millis(50)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_thread_names_fit_os_limit() {
+        let names = [
+            DECODE_THREAD,
+            PREPARE_THREAD,
+            PCM_TAP_THREAD,
+            TRANSCRIPT_THREAD,
+            PCM_STREAM_THREAD,
+            CALLBACK_THREAD,
+            #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos", target_os = "ios"))]
+            STREAM_THREAD,
+        ];
+        for name in names {
+            assert!(name.len() <= 15, "{} would be truncated", name);
+        }
+    }
+}
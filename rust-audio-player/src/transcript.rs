@@ -0,0 +1,368 @@
+// Transcripts: WebVTT and SRT cues looked up by playback position
+// Podcasting 2.0 feeds link a transcript per episode; the app hands its URL to the player,
+// which fetches and parses it in the background. Cue settings, NOTE/STYLE blocks and markup
+// are dropped; a WebVTT voice tag (`<v Name>`) becomes the cue's speaker.
+
+use crate::error::{AudioError, Result};
+use crate::threads::{spawn_named, TRANSCRIPT_THREAD};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// One timed piece of a transcript
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptCue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Plain text, lines joined with `\n`
+    pub text: String,
+    /// From a WebVTT `<v Name>` tag
+    pub speaker: Option<String>,
+}
+
+impl TranscriptCue {
+    /// Serialize as a flat JSON object (for the C ABI)
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"start_ms\":{},\"end_ms\":{},\"text\":{},\"speaker\":{}}}",
+            self.start_ms,
+            self.end_ms,
+            json_string(&self.text),
+            self.speaker.as_deref().map(json_string).unwrap_or_else(|| "null".to_string())
+        )
+    }
+}
+
+/// Parsed transcript, cues sorted by start time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcript {
+    pub cues: Vec<TranscriptCue>,
+}
+
+impl Transcript {
+    /// Parse WebVTT or SRT text. Malformed cues are skipped; text without any cue is an error
+    /// unless it is an (empty) WebVTT file.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let is_vtt = text.starts_with("WEBVTT");
+
+        let mut cues = Vec::new();
+        for block in text.split("\n\n") {
+            let lines: Vec<&str> = block.lines().filter(|line| !line.trim().is_empty()).collect();
+            // The header, NOTE/STYLE/REGION blocks and stray text have no timing line
+            let Some(timing_index) = lines.iter().position(|line| line.contains("-->")) else {
+                continue;
+            };
+            if lines[0].starts_with("NOTE") {
+                continue;
+            }
+            let Some((start_ms, end_ms)) = parse_timing(lines[timing_index]) else {
+                log::debug!("Skipping transcript cue with bad timing: {}", lines[timing_index]);
+                continue;
+            };
+            let (text, speaker) = cue_text(&lines[timing_index + 1..]);
+            cues.push(TranscriptCue { start_ms, end_ms, text, speaker });
+        }
+
+        if cues.is_empty() && !is_vtt {
+            return Err(AudioError::DecodingError("No transcript cues found".to_string()));
+        }
+        cues.sort_by_key(|cue| cue.start_ms);
+        Ok(Self { cues })
+    }
+
+    /// Download and parse the transcript at `url`
+    pub fn fetch(url: &str) -> Result<Self> {
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(30))
+            .timeout_read(Duration::from_secs(60))
+            .user_agent(&crate::http_utils::user_agent())
+            .redirects(10)
+            .build();
+        let text = agent
+            .get(url)
+            .call()
+            .map_err(|e| AudioError::NetworkError(format!("Transcript request failed: {}", e)))?
+            .into_string()
+            .map_err(|e| AudioError::NetworkError(format!("Failed to read transcript: {}", e)))?;
+        Self::parse(&text)
+    }
+
+    /// Cue being spoken at `position_ms`; where cues overlap, the one that started last
+    pub fn cue_at(&self, position_ms: u64) -> Option<&TranscriptCue> {
+        let started = self.cues.partition_point(|cue| cue.start_ms <= position_ms);
+        self.cues[..started].iter().rev().find(|cue| cue.end_ms > position_ms)
+    }
+}
+
+/// `00:01:02.345 --> 00:01:05.000 align:start` (cue settings ignored)
+fn parse_timing(line: &str) -> Option<(u64, u64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    let (start_ms, end_ms) = (parse_timestamp(start)?, parse_timestamp(end)?);
+    (end_ms >= start_ms).then_some((start_ms, end_ms))
+}
+
+/// `[hh:]mm:ss.mmm` (WebVTT) or `hh:mm:ss,mmm` (SRT) into milliseconds
+fn parse_timestamp(text: &str) -> Option<u64> {
+    let (clock, fraction) = text.trim().split_once(['.', ','])?;
+    if fraction.is_empty() || fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let millis = fraction.parse::<u64>().ok()? * 10u64.pow(3 - fraction.len() as u32);
+
+    let fields = clock
+        .split(':')
+        .map(|field| field.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (hours, minutes, seconds) = match fields[..] {
+        [minutes, seconds] => (0, minutes, seconds),
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        _ => return None,
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    Some(((hours * 60 + minutes) * 60 + seconds) * 1000 + millis)
+}
+
+/// Plain text of a cue's payload lines plus the speaker of its first voice tag
+fn cue_text(lines: &[&str]) -> (String, Option<String>) {
+    let mut speaker = None;
+    let mut text_lines = Vec::new();
+
+    for line in lines {
+        let mut plain = String::new();
+        let mut rest = *line;
+        while let Some(open) = rest.find('<') {
+            plain.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('>') else {
+                // Not a tag after all
+                plain.push_str(&rest[open..]);
+                rest = "";
+                break;
+            };
+            let tag = &rest[open + 1..open + close];
+            // `<v Name>` or `<v.class Name>`
+            if speaker.is_none() && (tag.starts_with("v ") || tag.starts_with("v.")) {
+                speaker = tag
+                    .split_once(char::is_whitespace)
+                    .map(|(_, name)| name.trim().to_string())
+                    .filter(|name| !name.is_empty());
+            }
+            rest = &rest[open + close + 1..];
+        }
+        plain.push_str(rest);
+
+        let plain = decode_entities(plain.trim());
+        if !plain.is_empty() {
+            text_lines.push(plain);
+        }
+    }
+
+    (text_lines.join("\n"), speaker)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[derive(Default)]
+struct SlotState {
+    url: Option<String>,
+    transcript: Option<Arc<Transcript>>,
+}
+
+/// Transcript of one player, fetched when the app sets its URL
+#[derive(Default)]
+pub struct TranscriptSlot {
+    state: Mutex<SlotState>,
+}
+
+impl TranscriptSlot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop the current transcript and fetch the one at `url` in the background (None only
+    /// clears). A fetch that completes after the URL changed again is discarded; failures are
+    /// logged and leave the slot empty.
+    pub fn set_url(self: &Arc<Self>, url: Option<&str>) -> Result<()> {
+        let mut state = self.state.lock();
+        state.transcript = None;
+        state.url = url.map(str::to_string);
+        let Some(url) = state.url.clone() else {
+            return Ok(());
+        };
+        drop(state);
+
+        let slot = Arc::downgrade(self);
+        spawn_named(TRANSCRIPT_THREAD, move || {
+            let result = Transcript::fetch(&url);
+            let Some(slot) = slot.upgrade() else {
+                return;
+            };
+            let mut state = slot.state.lock();
+            if state.url.as_deref() != Some(url.as_str()) {
+                return;
+            }
+            match result {
+                Ok(transcript) => {
+                    log::info!("Loaded transcript with {} cues from {}", transcript.cues.len(), url);
+                    state.transcript = Some(Arc::new(transcript));
+                }
+                Err(e) => log::warn!("Failed to load transcript from {}: {}", url, e),
+            }
+        })?;
+        Ok(())
+    }
+
+    /// The loaded transcript (None while fetching, after a failure or without a URL)
+    pub fn transcript(&self) -> Option<Arc<Transcript>> {
+        self.state.lock().transcript.clone()
+    }
+
+    pub fn current_cue(&self, position_ms: u64) -> Option<TranscriptCue> {
+        self.transcript()?.cue_at(position_ms).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    const VTT: &str = "WEBVTT - Episode 12\n\
+        \n\
+        NOTE Generated by a transcription service\n\
+        \n\
+        intro\n\
+        00:00.000 --> 00:04.500 align:start position:10%\n\
+        <v Alice>Welcome to the show &amp; thanks</v>\n\
+        \n\
+        00:00:04.500 --> 00:00:09.000\n\
+        <v.host Bob Smith><i>Glad</i> to be here.\n\
+        Really.\n\
+        \n\
+        00:00:12.000 --> 00:00:15.000\n\
+        No speaker here\n";
+
+    #[test]
+    fn test_parse_vtt_with_speakers_and_settings() {
+        let transcript = Transcript::parse(VTT).unwrap();
+        assert_eq!(
+            transcript.cues,
+            vec![
+                TranscriptCue {
+                    start_ms: 0,
+                    end_ms: 4_500,
+                    text: "Welcome to the show & thanks".to_string(),
+                    speaker: Some("Alice".to_string()),
+                },
+                TranscriptCue {
+                    start_ms: 4_500,
+                    end_ms: 9_000,
+                    text: "Glad to be here.\nReally.".to_string(),
+                    speaker: Some("Bob Smith".to_string()),
+                },
+                TranscriptCue {
+                    start_ms: 12_000,
+                    end_ms: 15_000,
+                    text: "No speaker here".to_string(),
+                    speaker: None,
+                },
+            ]
+        );
+
+        assert_eq!(transcript.cue_at(4_499).unwrap().speaker.as_deref(), Some("Alice"));
+        assert_eq!(transcript.cue_at(4_500).unwrap().speaker.as_deref(), Some("Bob Smith"));
+        assert_eq!(transcript.cue_at(10_000), None);
+        assert_eq!(transcript.cue_at(14_000).unwrap().text, "No speaker here");
+        assert_eq!(transcript.cue_at(60_000), None);
+    }
+
+    #[test]
+    fn test_parse_srt() {
+        let srt = "\u{feff}1\r\n00:00:01,000 --> 00:00:03,250\r\nFirst line\r\nsecond line\r\n\r\n\
+            2\r\n00:01:02,500 --> 00:01:04,000\r\nLater\r\n\r\n\
+            3\r\nbroken --> timing\r\nDropped\r\n";
+        let transcript = Transcript::parse(srt).unwrap();
+        assert_eq!(transcript.cues.len(), 2);
+        assert_eq!(transcript.cues[0].text, "First line\nsecond line");
+        assert_eq!(transcript.cues[1].start_ms, 62_500);
+
+        assert_eq!(transcript.cue_at(500), None);
+        assert_eq!(transcript.cue_at(2_000).unwrap().end_ms, 3_250);
+        assert_eq!(transcript.cue_at(63_000).unwrap().text, "Later");
+
+        assert!(Transcript::parse("just some text").is_err());
+        assert_eq!(Transcript::parse("WEBVTT\n").unwrap().cues, vec![]);
+    }
+
+    #[test]
+    fn test_cue_json_escapes_text() {
+        let cue = TranscriptCue {
+            start_ms: 1,
+            end_ms: 2,
+            text: "Say \"hi\"\nthere".to_string(),
+            speaker: None,
+        };
+        assert_eq!(
+            cue.to_json(),
+            r#"{"start_ms":1,"end_ms":2,"text":"Say \"hi\"\nthere","speaker":null}"#
+        );
+    }
+
+    #[test]
+    fn test_slot_fetches_on_demand() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/vtt\r\nContent-Length: {}\r\n\r\n{}",
+                    VTT.len(),
+                    VTT
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        let slot = Arc::new(TranscriptSlot::new());
+        assert_eq!(slot.current_cue(1_000), None);
+        slot.set_url(Some(&format!("http://{}/episode.vtt", addr))).unwrap();
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while slot.transcript().is_none() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(slot.current_cue(1_000).unwrap().speaker.as_deref(), Some("Alice"));
+
+        slot.set_url(None).unwrap();
+        assert_eq!(slot.current_cue(1_000), None);
+    }
+}
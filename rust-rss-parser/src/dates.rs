@@ -0,0 +1,196 @@
+// Tolerant parsing of feed dates (`pubDate`, `updated`, ...)
+// Plenty of feeds write dates that are close to RFC 822/3339 but not quite: a missing leading
+// zero, a misspelled weekday, a time zone abbreviation or "GMT+0000". These are read as meant;
+// dates that still cannot be understood are reported as unknown rather than guessed.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+
+/// Time zone abbreviations seen in feeds, with their UTC offset in minutes (CST is the US one;
+/// IST and other abbreviations with several common meanings are left out)
+const ZONES: [(&str, i32); 19] = [
+    ("EST", -5 * 60),
+    ("EDT", -4 * 60),
+    ("CST", -6 * 60),
+    ("CDT", -5 * 60),
+    ("MST", -7 * 60),
+    ("MDT", -6 * 60),
+    ("PST", -8 * 60),
+    ("PDT", -7 * 60),
+    ("AKST", -9 * 60),
+    ("AKDT", -8 * 60),
+    ("HST", -10 * 60),
+    ("BST", 60),
+    ("CET", 60),
+    ("CEST", 2 * 60),
+    ("EET", 2 * 60),
+    ("EEST", 3 * 60),
+    ("JST", 9 * 60),
+    ("AEST", 10 * 60),
+    ("AEDT", 11 * 60),
+];
+
+/// Parse a feed date as RFC 3339/ISO 8601 or (lenient) RFC 822; None when it can't be read
+pub fn parse_feed_date(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    parse_iso(text)
+        .or_else(|| parse_rfc822(text))
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// RFC 3339 plus the common ISO shortcuts: no colon in the offset, no offset (UTC), date only
+fn parse_iso(text: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date);
+    }
+    if let Ok(date) = DateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(date);
+    }
+    let utc = FixedOffset::east_opt(0)?;
+    let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S"))
+        .or_else(|_| NaiveDate::parse_from_str(text, "%Y-%m-%d").map(|date| date.and_time(NaiveTime::MIN)))
+        .ok()?;
+    utc.from_local_datetime(&naive).single()
+}
+
+/// "[weekday,] day month year hh:mm[:ss] [zone]", tolerating a wrong or long weekday, long month
+/// names, single-digit fields, two-digit years and any zone `parse_zone` understands
+fn parse_rfc822(text: &str) -> Option<DateTime<FixedOffset>> {
+    let mut tokens: Vec<&str> = text.split([' ', '\t', ',']).filter(|t| !t.is_empty()).collect();
+    if tokens.first().is_some_and(|t| t.chars().all(|c| c.is_ascii_alphabetic())) {
+        tokens.remove(0);
+    }
+    let [day, month, year, time, zone @ ..] = tokens.as_slice() else {
+        return None;
+    };
+
+    let day: u32 = day.parse().ok()?;
+    let month = month.get(..3).map(str::to_ascii_lowercase)?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u32 + 1;
+    let year: i32 = match year.parse().ok()? {
+        year @ 0..=49 => 2000 + year,
+        year @ 50..=99 => 1900 + year,
+        year => year,
+    };
+
+    let mut fields = time.split(':').map(|field| field.parse::<u32>().ok());
+    let hour = fields.next()??;
+    let minute = fields.next()??;
+    let second = fields.next().unwrap_or(Some(0))?;
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+    // "24:00" shows up for the midnight that ends the day
+    let date = if (hour, minute, second) == (24, 0, 0) {
+        date.succ_opt()?.and_time(NaiveTime::MIN)
+    } else {
+        date.and_time(NaiveTime::from_hms_opt(hour, minute, second)?)
+    };
+    let offset = FixedOffset::east_opt(parse_zone(&zone.concat())? * 60)?;
+    offset.from_local_datetime(&date).single()
+}
+
+/// Offset in minutes of "+0100", "-05:00", "GMT+0000", "UTC-5", "Z", "PST" ...; a missing zone
+/// is UTC. Unknown abbreviations are read as UTC too, which keeps the day right to within hours.
+fn parse_zone(zone: &str) -> Option<i32> {
+    let zone = zone.trim_matches(|c| c == '(' || c == ')');
+    let numeric = ["GMT", "UTC", "UT", "Z"]
+        .iter()
+        .find_map(|prefix| {
+            zone.get(..prefix.len())
+                .filter(|p| p.eq_ignore_ascii_case(prefix))
+                .map(|_| &zone[prefix.len()..])
+        })
+        .unwrap_or(zone);
+    if numeric.is_empty() {
+        return Some(0);
+    }
+
+    let sign = match numeric.as_bytes()[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => {
+            let named = ZONES.iter().find(|(name, _)| name.eq_ignore_ascii_case(numeric));
+            return Some(named.map_or(0, |(_, minutes)| *minutes));
+        }
+    };
+    let digits: String = numeric[1..].chars().filter(|c| *c != ':').collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        3 | 4 => {
+            let split = digits.len() - 2;
+            (digits[..split].parse::<i32>().ok()?, digits[split..].parse::<i32>().ok()?)
+        }
+        _ => return None,
+    };
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(text: &str) -> Option<i64> {
+        parse_feed_date(text).map(|date| date.timestamp_millis())
+    }
+
+    #[test]
+    fn test_malformed_dates_are_read_as_meant() {
+        // 2024-01-05 09:30:00 UTC
+        let expected = Some(1_704_447_000_000);
+        for text in [
+            "Fri, 05 Jan 2024 09:30:00 GMT",
+            "Fri, 5 Jan 2024 9:30:00 GMT",
+            "Friday, 05 January 2024 09:30:00 +0000",
+            "Thurs, 05 Jan 2024 09:30:00 GMT", // wrong and long weekday
+            "Fri, 05 Jan 2024 04:30:00 EST",
+            "Fri, 05 Jan 2024 01:30:00 PST",
+            "Fri, 05 Jan 2024 09:30:00 GMT+0000",
+            "Fri, 05 Jan 2024 17:30:00 GMT+08:00",
+            "Fri, 05 Jan 2024 04:30:00 UTC-5",
+            "Fri, 05 Jan 24 09:30 GMT",
+            "05 Jan 2024 09:30:00",
+            "2024-01-05T09:30:00Z",
+            "2024-01-05T10:30:00+0100",
+            "2024-01-05T09:30:00",
+        ] {
+            assert_eq!(millis(text), expected, "{}", text);
+        }
+        assert_eq!(millis("2024-01-05"), Some(1_704_412_800_000));
+    }
+
+    #[test]
+    fn test_hour_24_is_the_next_midnight() {
+        // 2024-01-06 00:00:00 UTC
+        assert_eq!(millis("Fri, 05 Jan 2024 24:00:00 GMT"), Some(1_704_499_200_000));
+        assert_eq!(millis("Sat, 06 Jan 2024 00:00:00 GMT"), Some(1_704_499_200_000));
+        // Across a month and a year
+        assert_eq!(millis("31 Dec 2023 24:00 GMT"), millis("2024-01-01"));
+    }
+
+    #[test]
+    fn test_unreadable_dates_are_unknown() {
+        for text in [
+            "",
+            "   ",
+            "yesterday",
+            "Fri, 32 Jan 2024 09:30:00 GMT",
+            "05 Smarch 2024 09:30",
+            "Fri, 05 Jan 2024",
+            "Fri, 05 Jan 2024 09:30:00 +9999",
+            "Fri, 05 Jan 2024 24:30:00 GMT",
+            "Fri, 05 Jan 2024 25:00:00 GMT",
+        ] {
+            assert_eq!(millis(text), None, "{}", text);
+        }
+    }
+}
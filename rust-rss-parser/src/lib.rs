@@ -1,10 +1,16 @@
 use feed_rs::parser;
+use quick_xml::events::Event;
+use quick_xml::{Reader, XmlVersion};
 use jni::objects::{JClass, JString};
 use jni::sys::jstring;
 use jni::JNIEnv;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+mod dates;
+pub use dates::parse_feed_date;
+
 /// Chapter information for podcast episodes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chapter {
@@ -24,12 +30,70 @@ pub struct RssEpisode {
     pub description: String,
     #[serde(rename = "audioUrl")]
     pub audio_url: String,
+    /// Unix timestamp in milliseconds; None when the item has no date or it can't be read
     #[serde(rename = "publishDate")]
-    pub publish_date: i64, // Unix timestamp in milliseconds
+    pub publish_date: Option<i64>,
     pub duration: Option<i64>,
     #[serde(rename = "imageUrl")]
     pub image_url: Option<String>,
     pub chapters: Vec<Chapter>,
+    /// `<podcast:season>` (or `<itunes:season>`)
+    pub season: Option<u32>,
+    /// Season title from the `name` attribute of `<podcast:season>`
+    #[serde(rename = "seasonName")]
+    pub season_name: Option<String>,
+    /// `<podcast:episode>` (or `<itunes:episode>`); may be fractional, e.g. 1.5 for a bonus
+    #[serde(rename = "episodeNumber")]
+    pub episode_number: Option<f64>,
+    /// Included through `ParseOptions::include_non_audio`: `audio_url` is the item's video or
+    /// other enclosure, or empty for items without one
+    #[serde(rename = "nonAudio", default)]
+    pub non_audio: bool,
+    /// Every audio rendition the item offers (enclosures and `<media:content>`), in document
+    /// order; `audio_url` is the one picked by `ParseOptions::rendition`
+    #[serde(default)]
+    pub alternates: Vec<AudioRendition>,
+}
+
+/// One audio file offered for an episode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioRendition {
+    pub url: String,
+    /// Media type, e.g. "audio/mpeg"
+    pub mime: Option<String>,
+    /// kbit/s: the declared `bitrate`, else estimated from `bytes` and the duration
+    pub bitrate: Option<u32>,
+    /// File size from the enclosure `length` or `fileSize`
+    pub bytes: Option<u64>,
+}
+
+/// How `audio_url` is chosen when an item offers several audio renditions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum RenditionPolicy {
+    /// The first one, normally the publisher's primary enclosure
+    #[default]
+    First,
+    /// Highest known bitrate (renditions without one are only used if none has it)
+    HighestBitrate,
+    /// Lowest known bitrate, e.g. to save mobile data
+    LowestBitrate,
+    /// First rendition of this media type (e.g. "audio/aac"), else the first one
+    PreferredMime(String),
+}
+
+impl RenditionPolicy {
+    fn pick<'a>(&self, renditions: &'a [AudioRendition]) -> Option<&'a AudioRendition> {
+        let with_bitrate = || renditions.iter().filter(|r| r.bitrate.is_some());
+        let picked = match self {
+            RenditionPolicy::First => None,
+            RenditionPolicy::HighestBitrate => with_bitrate().rev().max_by_key(|r| r.bitrate),
+            RenditionPolicy::LowestBitrate => with_bitrate().min_by_key(|r| r.bitrate),
+            RenditionPolicy::PreferredMime(mime) => renditions
+                .iter()
+                .find(|r| r.mime.as_deref().is_some_and(|m| m.eq_ignore_ascii_case(mime))),
+        };
+        picked.or(renditions.first())
+    }
 }
 
 /// Parsed podcast feed data
@@ -45,11 +109,247 @@ pub struct PodcastFeed {
     #[serde(rename = "lastUpdated")]
     pub last_updated: i64, // Unix timestamp in milliseconds
     pub episodes: Vec<RssEpisode>,
+    /// Items that were not turned into episodes, by reason
+    #[serde(default)]
+    pub skipped: SkippedItems,
+}
+
+/// Counts of feed items left out of `PodcastFeed::episodes` (none when
+/// `ParseOptions::include_non_audio` keeps them)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedItems {
+    /// Items whose only enclosure is not audio (video podcasts)
+    #[serde(rename = "nonAudioEnclosure")]
+    pub non_audio_enclosure: usize,
+    /// Items without any enclosure (show notes, announcements)
+    #[serde(rename = "noEnclosure")]
+    pub no_enclosure: usize,
+}
+
+impl SkippedItems {
+    /// All items skipped for having no audio
+    pub fn total(&self) -> usize {
+        self.non_audio_enclosure + self.no_enclosure
+    }
+}
+
+/// Options for `parse_rss_with_options`
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Keep items without audio as episodes marked `non_audio` instead of skipping them
+    pub include_non_audio: bool,
+    /// Which rendition becomes `audio_url` when an item offers several
+    pub rendition: RenditionPolicy,
+}
+
+/// Episodes of one season, as grouped by `PodcastFeed::seasons`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Season {
+    /// None for episodes that belong to no season
+    pub number: Option<u32>,
+    pub title: Option<String>,
+    pub episodes: Vec<RssEpisode>,
+}
+
+impl PodcastFeed {
+    /// Episodes grouped by season, in season order with the ungrouped bucket last.
+    /// Within a season episodes are ordered by episode number (unnumbered last), then publish date.
+    pub fn seasons(&self) -> Vec<Season> {
+        let mut seasons: Vec<Season> = Vec::new();
+        for episode in &self.episodes {
+            let index = match seasons.iter().position(|s| s.number == episode.season) {
+                Some(index) => index,
+                None => {
+                    seasons.push(Season {
+                        number: episode.season,
+                        title: None,
+                        episodes: Vec::new(),
+                    });
+                    seasons.len() - 1
+                }
+            };
+            let season = &mut seasons[index];
+            if season.title.is_none() {
+                season.title = episode.season_name.clone();
+            }
+            season.episodes.push(episode.clone());
+        }
+
+        seasons.sort_by_key(|s| (s.number.is_none(), s.number));
+        for season in &mut seasons {
+            season.episodes.sort_by(|a, b| {
+                let by_number = match (a.episode_number, b.episode_number) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                };
+                by_number.then(a.publish_date.cmp(&b.publish_date))
+            });
+        }
+        seasons
+    }
 }
 
-/// Parse RSS feed from XML content
+/// Season and episode numbering of one `<item>`
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ItemNumbering {
+    season: Option<u32>,
+    season_name: Option<String>,
+    episode: Option<f64>,
+}
+
+/// Read season/episode numbering for each `<item>`, in document order.
+/// feed-rs does not keep the podcast namespace, so this scans the XML separately;
+/// `<podcast:*>` values win over the older `<itunes:*>` ones.
+fn parse_item_numbering(xml_content: &str) -> Vec<ItemNumbering> {
+    let mut reader = Reader::from_str(xml_content);
+    reader.config_mut().trim_text(true);
+
+    let mut items = Vec::new();
+    let mut current: Option<ItemNumbering> = None;
+    // Element whose text is being read: (local name, from the podcast namespace)
+    let mut field: Option<(Vec<u8>, bool)> = None;
+    let mut itunes = ItemNumbering::default();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = e.name();
+                if name.as_ref() == b"item" {
+                    current = Some(ItemNumbering::default());
+                    itunes = ItemNumbering::default();
+                } else if let (Some(item), Some(prefix)) = (current.as_mut(), name.prefix()) {
+                    let podcast = prefix.as_ref() == b"podcast";
+                    if podcast || prefix.as_ref() == b"itunes" {
+                        let local = name.local_name().as_ref().to_vec();
+                        if podcast && local == b"season" {
+                            item.season_name = e
+                                .try_get_attribute("name")
+                                .ok()
+                                .flatten()
+                                .and_then(|a| a.decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder()).ok())
+                                .map(|v| v.trim().to_string())
+                                .filter(|v| !v.is_empty());
+                        }
+                        field = Some((local, podcast));
+                    }
+                }
+            }
+            Ok(Event::Text(t)) => {
+                if let (Some(item), Some((local, podcast))) = (current.as_mut(), field.as_ref()) {
+                    let text = t.decode().unwrap_or_default();
+                    let target = if *podcast { &mut *item } else { &mut itunes };
+                    match local.as_slice() {
+                        b"season" => target.season = text.trim().parse().ok(),
+                        b"episode" => target.episode = text.trim().parse().ok().filter(|n: &f64| n.is_finite()),
+                        _ => {}
+                    }
+                }
+            }
+            Ok(Event::End(e)) => {
+                field = None;
+                if e.name().as_ref() == b"item" {
+                    if let Some(mut item) = current.take() {
+                        item.season = item.season.or(itunes.season);
+                        item.episode = item.episode.or(itunes.episode);
+                        items.push(item);
+                    }
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+    items
+}
+
+/// Bitrates (kbit/s) declared on `<enclosure>` / `<media:content>` elements, keyed by URL.
+/// feed-rs drops the non-standard `bitrate` attribute, so this scans the XML separately.
+fn parse_declared_bitrates(xml_content: &str) -> HashMap<String, u32> {
+    let mut reader = Reader::from_str(xml_content);
+    let mut bitrates = HashMap::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                if !matches!(e.name().as_ref(), b"enclosure" | b"media:content") {
+                    continue;
+                }
+                let attribute = |name: &str| {
+                    e.try_get_attribute(name)
+                        .ok()
+                        .flatten()
+                        .and_then(|a| a.decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder()).ok())
+                        .map(|v| v.trim().to_string())
+                };
+                let bitrate = attribute("bitrate")
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .filter(|kbps| kbps.is_finite() && *kbps > 0.0);
+                if let (Some(url), Some(kbps)) = (attribute("url"), bitrate) {
+                    bitrates.insert(url, kbps.round() as u32);
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+    bitrates
+}
+
+/// Audio renditions of one entry: its audio `<enclosure>` / `<media:content>` elements, or
+/// failing those its Atom enclosure links. Duplicate URLs are listed once.
+fn collect_renditions(
+    entry: &feed_rs::model::Entry,
+    declared_bitrates: &HashMap<String, u32>,
+) -> Vec<AudioRendition> {
+    let mut renditions: Vec<AudioRendition> = Vec::new();
+    for media in &entry.media {
+        for content in &media.content {
+            let (Some(url), Some(mime)) = (content.url.as_ref(), content.content_type.as_ref()) else {
+                continue;
+            };
+            if mime.essence().ty != "audio" || renditions.iter().any(|r| r.url == url.as_str()) {
+                continue;
+            }
+            let duration_secs = content.duration.or(media.duration).map(|d| d.as_secs()).filter(|s| *s > 0);
+            let estimated = content.size
+                .zip(duration_secs)
+                .map(|(bytes, secs)| (bytes * 8 / secs / 1000) as u32);
+            renditions.push(AudioRendition {
+                url: url.to_string(),
+                mime: Some(mime.essence().to_string()),
+                bitrate: declared_bitrates.get(url.as_str()).copied().or(estimated),
+                bytes: content.size,
+            });
+        }
+    }
+    if renditions.is_empty() {
+        renditions = entry.links.iter()
+            .filter(|link| link.rel.as_deref() == Some("enclosure"))
+            .map(|link| AudioRendition {
+                url: link.href.clone(),
+                mime: link.media_type.clone(),
+                bitrate: declared_bitrates.get(&link.href).copied(),
+                bytes: link.length,
+            })
+            .collect();
+    }
+    renditions
+}
+
+/// Parse RSS feed from XML content. Items without audio are skipped and counted in
+/// `PodcastFeed::skipped`, so an empty episode list can be told apart from an empty feed.
 pub fn parse_rss(feed_url: &str, xml_content: &str) -> Result<PodcastFeed, String> {
-    let feed = parser::parse(xml_content.as_bytes())
+    parse_rss_with_options(feed_url, xml_content, &ParseOptions::default())
+}
+
+/// Parse RSS feed from XML content with `options`
+pub fn parse_rss_with_options(
+    feed_url: &str,
+    xml_content: &str,
+    options: &ParseOptions,
+) -> Result<PodcastFeed, String> {
+    let feed = parser::Builder::new()
+        .timestamp_parser(parse_feed_date)
+        .build()
+        .parse(xml_content.as_bytes())
         .map_err(|e| format!("Failed to parse RSS feed: {}", e))?;
 
     // Get current timestamp in milliseconds
@@ -75,9 +375,18 @@ pub fn parse_rss(feed_url: &str, xml_content: &str) -> Result<PodcastFeed, Strin
     // Generate feed ID from URL
     let feed_id = generate_id(feed_url);
 
+    let declared_bitrates = parse_declared_bitrates(xml_content);
+
+    // Numbering comes from a separate scan; only trust it if it lines up item for item
+    let mut numbering = parse_item_numbering(xml_content);
+    if numbering.len() != feed.entries.len() {
+        numbering.clear();
+    }
+
     // Parse episodes
     let mut episodes = Vec::new();
-    for entry in feed.entries {
+    let mut skipped = SkippedItems::default();
+    for (index, entry) in feed.entries.into_iter().enumerate() {
         // Extract episode title
         let episode_title = entry.title.as_ref()
             .map(|t| t.content.to_string())
@@ -91,40 +400,30 @@ pub fn parse_rss(feed_url: &str, xml_content: &str) -> Result<PodcastFeed, Strin
                 .map(|s| s.to_string()))
             .unwrap_or_default();
 
-        // Find audio URL from media content or links
-        let audio_url = entry.media.iter()
-            .filter_map(|m| {
-                m.content.iter()
-                    .filter(|c| c.content_type.as_ref()
-                        .map(|ct| ct.essence().ty == "audio")
-                        .unwrap_or(false))
-                    .filter_map(|c| c.url.as_ref())
-                    .next()
-            })
-            .next()
-            .map(|url| url.to_string())
-            .or_else(|| {
-                // Look for enclosure links with audio mime type
-                entry.links.iter()
-                    .find(|link| {
-                        if let Some(rel) = &link.rel {
-                            rel == "enclosure"
-                        } else {
-                            false
-                        }
-                    })
-                    .map(|link| link.href.clone())
-            });
-
-        // Skip episodes without audio URL
-        if audio_url.is_none() {
+        // Find audio renditions from media content or links, and pick one per the policy
+        let alternates = collect_renditions(&entry, &declared_bitrates);
+        let audio_url = options.rendition.pick(&alternates).map(|r| r.url.clone());
+
+        // Skip (or keep, if asked to) episodes without audio URL
+        let other_media_url = entry.media.iter()
+            .flat_map(|m| m.content.iter())
+            .find_map(|c| c.url.as_ref())
+            .map(|url| url.to_string());
+        let non_audio = audio_url.is_none();
+        if non_audio && !options.include_non_audio {
+            if other_media_url.is_some() {
+                skipped.non_audio_enclosure += 1;
+            } else {
+                skipped.no_enclosure += 1;
+            }
             continue;
         }
+        let audio_url = audio_url.or(other_media_url).unwrap_or_default();
 
-        // Extract publish date
+        // Extract publish date (unknown rather than "now" when missing or unreadable, so the
+        // episode doesn't sort as just published)
         let publish_date = entry.published.or(entry.updated)
-            .map(|dt| dt.timestamp_millis())
-            .unwrap_or(now_ms);
+            .map(|dt| dt.timestamp_millis());
 
         // Extract duration from media content
         let duration = entry.media.iter()
@@ -154,15 +453,21 @@ pub fn parse_rss(feed_url: &str, xml_content: &str) -> Result<PodcastFeed, Strin
         // Generate episode ID
         let episode_id = generate_id(&format!("{}_{}", feed_url, episode_title));
 
+        let item_numbering = numbering.get(index).cloned().unwrap_or_default();
         let episode = RssEpisode {
             id: episode_id,
             title: episode_title,
             description: episode_description,
-            audio_url: audio_url.unwrap(),
+            audio_url,
             publish_date,
             duration,
             image_url,
             chapters: Vec::new(), // Chapters would need custom parsing if available
+            season: item_numbering.season,
+            season_name: item_numbering.season_name,
+            episode_number: item_numbering.episode,
+            non_audio,
+            alternates,
         };
 
         episodes.push(episode);
@@ -176,6 +481,7 @@ pub fn parse_rss(feed_url: &str, xml_content: &str) -> Result<PodcastFeed, Strin
         feed_url: feed_url.to_string(),
         last_updated: now_ms,
         episodes,
+        skipped,
     })
 }
 
@@ -283,6 +589,194 @@ mod tests {
         assert_eq!(feed.episodes[0].title, "Episode 1");
     }
 
+    #[test]
+    fn test_seasons_group_and_order_episodes() {
+        let item = |title: &str, date: &str, numbering: &str| {
+            format!(
+                r#"<item><title>{}</title><enclosure url="https://example.com/{}.mp3" type="audio/mpeg" />
+                <pubDate>{}</pubDate>{}</item>"#,
+                title, title, date, numbering
+            )
+        };
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"
+     xmlns:podcast="https://podcastindex.org/namespace/1.0">
+    <channel>
+        <title>Seasons</title>
+        {}{}{}{}{}{}{}
+    </channel>
+</rss>"#,
+            item("s2e2", "Mon, 08 Jan 2024 00:00:00 GMT", "<podcast:season>2</podcast:season><podcast:episode>2</podcast:episode>"),
+            item("trailer", "Mon, 01 Jan 2024 00:00:00 GMT", ""),
+            item("s2e1", "Mon, 01 Jan 2024 00:00:00 GMT", r#"<podcast:season name="The Sequel">2</podcast:season><podcast:episode>1</podcast:episode>"#),
+            item("s1-bonus", "Mon, 10 Jul 2023 00:00:00 GMT", "<podcast:season>1</podcast:season>"),
+            item("s1e1.5", "Mon, 03 Jul 2023 00:00:00 GMT", "<podcast:season>1</podcast:season><podcast:episode>1.5</podcast:episode>"),
+            // Same number twice: publish date breaks the tie
+            item("s1e1-rerun", "Mon, 12 Jun 2023 00:00:00 GMT", "<itunes:season>1</itunes:season><itunes:episode>1</itunes:episode>"),
+            item("s1e1", "Mon, 05 Jun 2023 00:00:00 GMT", "<podcast:season>1</podcast:season><podcast:episode>1</podcast:episode><itunes:episode>9</itunes:episode>"),
+        );
+
+        let feed = parse_rss("https://example.com/feed.xml", &xml).unwrap();
+        // The flat list keeps feed order
+        let flat: Vec<&str> = feed.episodes.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(flat, ["s2e2", "trailer", "s2e1", "s1-bonus", "s1e1.5", "s1e1-rerun", "s1e1"]);
+
+        let seasons = feed.seasons();
+        let grouped: Vec<(Option<u32>, Option<&str>, Vec<&str>)> = seasons
+            .iter()
+            .map(|s| (s.number, s.title.as_deref(), s.episodes.iter().map(|e| e.title.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            grouped,
+            [
+                (Some(1), None, vec!["s1e1", "s1e1-rerun", "s1e1.5", "s1-bonus"]),
+                (Some(2), Some("The Sequel"), vec!["s2e1", "s2e2"]),
+                (None, None, vec!["trailer"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_pub_dates_are_parsed_or_unknown() {
+        let item = |title: &str, date: &str| {
+            format!(
+                r#"<item><title>{}</title><enclosure url="https://example.com/{}.mp3" type="audio/mpeg" />{}</item>"#,
+                title, title, date
+            )
+        };
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+    <channel>
+        <title>Dates</title>
+        {}{}{}{}{}
+    </channel>
+</rss>"#,
+            item("padded", "<pubDate>Fri, 5 Jan 2024 9:30:00 GMT</pubDate>"),
+            item("pacific", "<pubDate>Fri, 05 Jan 2024 01:30:00 PST</pubDate>"),
+            item("offset", "<pubDate>Fri, 05 Jan 2024 09:30:00 GMT+0000</pubDate>"),
+            item("garbled", "<pubDate>sometime last week</pubDate>"),
+            item("missing", ""),
+        );
+
+        let feed = parse_rss("https://example.com/feed.xml", &xml).unwrap();
+        let dates: Vec<(&str, Option<i64>)> = feed.episodes.iter()
+            .map(|e| (e.title.as_str(), e.publish_date))
+            .collect();
+        let expected = Some(1_704_447_000_000);
+        assert_eq!(
+            dates,
+            [("padded", expected), ("pacific", expected), ("offset", expected), ("garbled", None), ("missing", None)]
+        );
+    }
+
+    #[test]
+    fn test_items_without_audio_are_counted() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+    <channel>
+        <title>Mixed Podcast</title>
+        <item>
+            <title>Audio</title>
+            <enclosure url="https://example.com/ep1.mp3" type="audio/mpeg" />
+        </item>
+        <item>
+            <title>Video</title>
+            <enclosure url="https://example.com/ep2.mp4" type="video/mp4" />
+        </item>
+        <item>
+            <title>Notes only</title>
+            <description>No episode this week</description>
+        </item>
+    </channel>
+</rss>"#;
+
+        let feed = parse_rss("https://example.com/feed.xml", xml).unwrap();
+        assert_eq!(feed.episodes.len(), 1);
+        assert_eq!(feed.episodes[0].title, "Audio");
+        assert!(!feed.episodes[0].non_audio);
+        assert_eq!(feed.skipped, SkippedItems { non_audio_enclosure: 1, no_enclosure: 1 });
+        assert_eq!(feed.skipped.total(), 2);
+
+        let options = ParseOptions { include_non_audio: true, ..Default::default() };
+        let feed = parse_rss_with_options("https://example.com/feed.xml", xml, &options).unwrap();
+        let episodes: Vec<_> = feed.episodes.iter()
+            .map(|e| (e.title.as_str(), e.audio_url.as_str(), e.non_audio))
+            .collect();
+        assert_eq!(
+            episodes,
+            vec![
+                ("Audio", "https://example.com/ep1.mp3", false),
+                ("Video", "https://example.com/ep2.mp4", true),
+                ("Notes only", "", true),
+            ]
+        );
+        // Kept items are marked instead of counted
+        assert_eq!(feed.skipped, SkippedItems::default());
+    }
+
+    #[test]
+    fn test_alternate_enclosures_are_captured_and_picked_by_policy() {
+        let rss = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+    <channel>
+        <title>Test</title>
+        <item>
+            <title>Episode 1</title>
+            <itunes:duration>600</itunes:duration>
+            <enclosure url="https://example.com/ep1.mp3" type="audio/mpeg" length="9600000" />
+            <enclosure url="https://example.com/ep1.m4a" type="audio/aac" length="4800000" bitrate="64" />
+        </item>
+    </channel>
+</rss>"#;
+
+        let feed = parse_rss("https://example.com/feed", rss).unwrap();
+        let episode = &feed.episodes[0];
+        assert_eq!(
+            episode.alternates,
+            vec![
+                AudioRendition {
+                    url: "https://example.com/ep1.mp3".to_string(),
+                    mime: Some("audio/mpeg".to_string()),
+                    bitrate: Some(128),
+                    bytes: Some(9_600_000),
+                },
+                AudioRendition {
+                    url: "https://example.com/ep1.m4a".to_string(),
+                    mime: Some("audio/aac".to_string()),
+                    bitrate: Some(64),
+                    bytes: Some(4_800_000),
+                },
+            ]
+        );
+        assert_eq!(episode.audio_url, "https://example.com/ep1.mp3");
+
+        let picked = |rendition| {
+            let options = ParseOptions { rendition, ..Default::default() };
+            parse_rss_with_options("https://example.com/feed", rss, &options).unwrap().episodes[0]
+                .audio_url
+                .clone()
+        };
+        assert_eq!(picked(RenditionPolicy::HighestBitrate), "https://example.com/ep1.mp3");
+        assert_eq!(picked(RenditionPolicy::LowestBitrate), "https://example.com/ep1.m4a");
+        let prefer = |mime: &str| RenditionPolicy::PreferredMime(mime.to_string());
+        assert_eq!(picked(prefer("audio/aac")), "https://example.com/ep1.m4a");
+        assert_eq!(picked(prefer("audio/ogg")), "https://example.com/ep1.mp3");
+    }
+
+    /// `fixtures/feed.json` is what the Kotlin parsers are tested against; it has to stay what
+    /// `parseRss` hands them for `fixtures/feed.xml`
+    #[test]
+    fn test_json_matches_the_kotlin_fixture() {
+        let feed = parse_rss("https://example.com/feed.xml", include_str!("../fixtures/feed.xml")).unwrap();
+        let mut parsed = serde_json::to_value(&feed).unwrap();
+        let fixture: serde_json::Value = serde_json::from_str(include_str!("../fixtures/feed.json")).unwrap();
+        // Stamped with the time of parsing
+        parsed["lastUpdated"] = fixture["lastUpdated"].clone();
+        assert_eq!(parsed, fixture);
+    }
+
     #[test]
     fn test_generate_id() {
         let id1 = generate_id("test");